@@ -14,6 +14,8 @@
 //! concrete types that work with each other. Failure to do so will result in a
 //! ton of compilation errors, and worse: it will not make sense!
 
+use std::sync::Arc;
+
 use fastcrypto::{
     bls12381, ed25519,
     error::FastCryptoError,
@@ -80,9 +82,40 @@ impl Clone for NetworkKeyPair {
 /// Protocol key is used for signing blocks and verifying block signatures.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct ProtocolPublicKey(ed25519::Ed25519PublicKey);
-pub struct ProtocolKeyPair(ed25519::Ed25519KeyPair);
+pub struct ProtocolKeyPair(ProtocolSigner);
 pub struct ProtocolKeySignature(ed25519::Ed25519Signature);
 
+/// Signs blocks with a protocol key that is held outside of the process, e.g.
+/// by a remote signer.
+pub trait BlockSigner: Send + Sync {
+    /// Signs the block with the given BCS encoding. The signature must be over
+    /// the same message a local protocol key signs, so that it can be verified
+    /// with the protocol public key.
+    fn sign_block(&self, block: &[u8]) -> Result<ed25519::Ed25519Signature, BlockSigningError>;
+}
+
+/// Reasons for a [`BlockSigner`] not to sign a block.
+#[derive(Debug)]
+pub enum BlockSigningError {
+    /// A different block was already signed for the same round, e.g. before
+    /// the node restarted. The signer hands back that block, BCS encoded, and
+    /// its signature, so that it can be proposed instead.
+    AlreadySigned {
+        block: Vec<u8>,
+        signature: ed25519::Ed25519Signature,
+    },
+    /// The signer could not be reached or refused to sign.
+    Failed(String),
+}
+
+enum ProtocolSigner {
+    Local(ed25519::Ed25519KeyPair),
+    External {
+        public: ed25519::Ed25519PublicKey,
+        signer: Arc<dyn BlockSigner>,
+    },
+}
+
 impl ProtocolPublicKey {
     pub fn new(key: ed25519::Ed25519PublicKey) -> Self {
         Self(key)
@@ -103,25 +136,54 @@ impl ProtocolPublicKey {
 
 impl ProtocolKeyPair {
     pub fn new(keypair: ed25519::Ed25519KeyPair) -> Self {
-        Self(keypair)
+        Self(ProtocolSigner::Local(keypair))
+    }
+
+    /// Creates a key pair whose private key is held by `signer`.
+    pub fn new_external(public: ProtocolPublicKey, signer: Arc<dyn BlockSigner>) -> Self {
+        Self(ProtocolSigner::External {
+            public: public.0,
+            signer,
+        })
     }
 
     pub fn generate<R: rand::Rng + fastcrypto::traits::AllowedRng>(rng: &mut R) -> Self {
-        Self(ed25519::Ed25519KeyPair::generate(rng))
+        Self::new(ed25519::Ed25519KeyPair::generate(rng))
     }
 
     pub fn public(&self) -> ProtocolPublicKey {
-        ProtocolPublicKey(self.0.public().clone())
+        match &self.0 {
+            ProtocolSigner::Local(keypair) => ProtocolPublicKey(keypair.public().clone()),
+            ProtocolSigner::External { public, .. } => ProtocolPublicKey(public.clone()),
+        }
     }
 
-    pub fn sign(&self, message: &[u8]) -> ProtocolKeySignature {
-        ProtocolKeySignature(self.0.sign(message))
+    /// Signs `message`, the intent message of the block with the given BCS
+    /// encoding. An external signer is handed the block itself, so that it
+    /// can check the block before signing it.
+    pub fn sign_block(
+        &self,
+        block: &[u8],
+        message: &[u8],
+    ) -> Result<ProtocolKeySignature, BlockSigningError> {
+        match &self.0 {
+            ProtocolSigner::Local(keypair) => Ok(ProtocolKeySignature(keypair.sign(message))),
+            ProtocolSigner::External { signer, .. } => {
+                signer.sign_block(block).map(ProtocolKeySignature)
+            }
+        }
     }
 }
 
 impl Clone for ProtocolKeyPair {
     fn clone(&self) -> Self {
-        Self(self.0.copy())
+        match &self.0 {
+            ProtocolSigner::Local(keypair) => Self::new(keypair.copy()),
+            ProtocolSigner::External { public, signer } => Self(ProtocolSigner::External {
+                public: public.clone(),
+                signer: signer.clone(),
+            }),
+        }
     }
 }
 
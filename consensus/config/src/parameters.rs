@@ -78,6 +78,13 @@ pub struct Parameters {
     /// recovery.
     #[serde(default = "Parameters::default_sync_last_known_own_block_timeout")]
     pub sync_last_known_own_block_timeout: Duration,
+
+    /// Strategy used to calculate the reputation scores of authorities, from
+    /// which the leader schedule is derived. All authorities of the
+    /// committee must use the same strategy, otherwise they elect different
+    /// leaders.
+    #[serde(default)]
+    pub leader_scoring_strategy: LeaderScoringStrategy,
 }
 
 impl Parameters {
@@ -166,10 +173,32 @@ impl Default for Parameters {
             commit_sync_batch_size: Parameters::default_commit_sync_batch_size(),
             commit_sync_batches_ahead: Parameters::default_commit_sync_batches_ahead(),
             tonic: TonicParameters::default(),
+            leader_scoring_strategy: LeaderScoringStrategy::default(),
         }
     }
 }
 
+/// Strategies to score authorities for the leader schedule.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderScoringStrategy {
+    /// One point for each vote for a committed leader.
+    #[default]
+    Vote,
+    /// One point for each vote for a committed leader that is included in
+    /// 2f+1 certificates.
+    CertifiedVoteV1,
+    /// Points equal to the stake of the certificates that include a vote for a
+    /// committed leader.
+    CertifiedVoteV2,
+    /// One point for each certificate for a committed leader.
+    Certificate,
+    /// Points based on how fast the blocks of an authority are received by a
+    /// quorum of the committee, so that authorities whose blocks propagate
+    /// slowly are swapped out of the leader schedule.
+    BlockLatency,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct TonicParameters {
     /// Keepalive interval and timeouts for both client and server.
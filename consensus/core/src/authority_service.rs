@@ -171,6 +171,23 @@ impl<C: CoreThreadDispatcher> NetworkService for AuthorityService<C> {
             sleep(forward_time_drift).await;
         }
 
+        self.context
+            .metrics
+            .node_metrics
+            .block_receive_latency
+            .with_label_values(&[
+                &self
+                    .context
+                    .committee
+                    .authority(verified_block.author())
+                    .hostname,
+                "handle_send_block",
+            ])
+            .observe(
+                Duration::from_millis(now.saturating_sub(verified_block.timestamp_ms()))
+                    .as_secs_f64(),
+            );
+
         // Observe the block for the commit votes. When local commit is lagging too
         // much, commit sync loop will trigger fetching.
         self.commit_vote_monitor.observe_block(&verified_block);
@@ -2,6 +2,11 @@
 // Modifications Copyright (c) 2024 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(test)]
+use std::{
+    collections::BTreeMap,
+    sync::atomic::{AtomicBool, Ordering},
+};
 use std::{
     fmt,
     hash::{Hash, Hasher},
@@ -11,11 +16,15 @@ use std::{
 
 use bytes::Bytes;
 use consensus_config::{
-    AuthorityIndex, DIGEST_LENGTH, DefaultHashFunction, Epoch, ProtocolKeyPair,
+    AuthorityIndex, BlockSigningError, DIGEST_LENGTH, DefaultHashFunction, Epoch, ProtocolKeyPair,
     ProtocolKeySignature, ProtocolPublicKey,
 };
 use enum_dispatch::enum_dispatch;
 use fastcrypto::hash::{Digest, HashFunction};
+#[cfg(test)]
+use fastcrypto::{ed25519::Ed25519Signature, traits::ToFromBytes};
+#[cfg(test)]
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use shared_crypto::intent::{Intent, IntentMessage, IntentScope};
 
@@ -340,6 +349,13 @@ impl SignedBlock {
         })
     }
 
+    /// Rebuilds a block that has been signed before from its BCS encoding and
+    /// signature. The signature still has to be verified.
+    pub(crate) fn from_signed_parts(block: &[u8], signature: Bytes) -> ConsensusResult<Self> {
+        let inner = bcs::from_bytes(block).map_err(ConsensusError::MalformedBlock)?;
+        Ok(Self { inner, signature })
+    }
+
     pub(crate) fn signature(&self) -> &Bytes {
         &self.signature
     }
@@ -382,9 +398,14 @@ struct InnerBlockDigest([u8; consensus_config::DIGEST_LENGTH]);
 
 /// Computes the digest of a Block, only for signing and verifications.
 fn compute_inner_block_digest(block: &Block) -> ConsensusResult<InnerBlockDigest> {
+    let block_bytes = bcs::to_bytes(block).map_err(ConsensusError::SerializationFailure)?;
+    Ok(inner_block_digest(&block_bytes))
+}
+
+fn inner_block_digest(block_bytes: &[u8]) -> InnerBlockDigest {
     let mut hasher = DefaultHashFunction::new();
-    hasher.update(bcs::to_bytes(block).map_err(ConsensusError::SerializationFailure)?);
-    Ok(InnerBlockDigest(hasher.finalize().into()))
+    hasher.update(block_bytes);
+    InnerBlockDigest(hasher.finalize().into())
 }
 
 /// Wrap a InnerBlockDigest in the intent message.
@@ -396,15 +417,30 @@ fn to_consensus_block_intent(digest: InnerBlockDigest) -> IntentMessage<InnerBlo
 /// 1. Compute the digest of `Block`.
 /// 2. Wrap the digest in `IntentMessage`.
 /// 3. Sign the serialized `IntentMessage`, or verify signature against it.
+///
+/// An external block signer is given the serialized `Block` as well, so that
+/// it can run the same process on its own.
 fn compute_block_signature(
     block: &Block,
     protocol_keypair: &ProtocolKeyPair,
 ) -> ConsensusResult<ProtocolKeySignature> {
-    let digest = compute_inner_block_digest(block)?;
+    let block_bytes = bcs::to_bytes(block).map_err(ConsensusError::SerializationFailure)?;
+    let digest = inner_block_digest(&block_bytes);
     let message = bcs::to_bytes(&to_consensus_block_intent(digest))
         .map_err(ConsensusError::SerializationFailure)?;
-    Ok(protocol_keypair.sign(&message))
+    protocol_keypair
+        .sign_block(&block_bytes, &message)
+        .map_err(|e| match e {
+            BlockSigningError::AlreadySigned { block, signature } => {
+                ConsensusError::BlockAlreadySigned {
+                    block: block.into(),
+                    signature: Bytes::copy_from_slice(signature.as_ref()),
+                }
+            }
+            BlockSigningError::Failed(e) => ConsensusError::BlockSigningFailure(e),
+        })
 }
+
 fn verify_block_signature(
     block: &Block,
     signature: &[u8],
@@ -610,6 +646,61 @@ impl TestBlock {
     }
 }
 
+/// Signs blocks the way a remote signer does, from the serialized block only.
+/// Like the remote signer, it hands back the block it already signed for a
+/// round instead of signing a different one, and it can be told to refuse.
+#[cfg(test)]
+pub(crate) struct TestBlockSigner {
+    keypair: ProtocolKeyPair,
+    signed_blocks: Mutex<BTreeMap<Round, Vec<u8>>>,
+    refuse: AtomicBool,
+}
+
+#[cfg(test)]
+impl TestBlockSigner {
+    pub(crate) fn new(keypair: ProtocolKeyPair) -> Self {
+        Self {
+            keypair,
+            signed_blocks: Default::default(),
+            refuse: Default::default(),
+        }
+    }
+
+    pub(crate) fn set_refuse(&self, refuse: bool) {
+        self.refuse.store(refuse, Ordering::Relaxed);
+    }
+
+    fn sign(&self, block: &[u8]) -> Result<Ed25519Signature, BlockSigningError> {
+        let message = bcs::to_bytes(&to_consensus_block_intent(inner_block_digest(block)))
+            .map_err(|e| BlockSigningError::Failed(e.to_string()))?;
+        let signature = self.keypair.sign_block(block, &message)?;
+        Ed25519Signature::from_bytes(signature.to_bytes())
+            .map_err(|e| BlockSigningError::Failed(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+impl consensus_config::BlockSigner for TestBlockSigner {
+    fn sign_block(&self, block: &[u8]) -> Result<Ed25519Signature, BlockSigningError> {
+        if self.refuse.load(Ordering::Relaxed) {
+            return Err(BlockSigningError::Failed("refused".to_string()));
+        }
+        let round = bcs::from_bytes::<Block>(block)
+            .map_err(|e| BlockSigningError::Failed(e.to_string()))?
+            .round();
+        let mut signed_blocks = self.signed_blocks.lock();
+        let signed_block = signed_blocks.entry(round).or_insert_with(|| block.to_vec());
+        let signature = self.sign(signed_block)?;
+        if signed_block != block {
+            return Err(BlockSigningError::AlreadySigned {
+                block: signed_block.clone(),
+                signature,
+            });
+        }
+        Ok(signature)
+    }
+}
+
 /// A block can attach reports of misbehavior by other authorities.
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct MisbehaviorReport {
@@ -631,10 +722,11 @@ pub enum MisbehaviorProof {
 mod tests {
     use std::sync::Arc;
 
+    use consensus_config::ProtocolKeyPair;
     use fastcrypto::error::FastCryptoError;
 
     use crate::{
-        block::{SignedBlock, TestBlock},
+        block::{BlockAPI, SignedBlock, TestBlock, TestBlockSigner},
         context::Context,
         error::ConsensusError,
     };
@@ -669,4 +761,39 @@ mod tests {
             err => panic!("Unexpected error: {err:?}"),
         }
     }
+
+    #[tokio::test]
+    async fn test_sign_with_external_signer() {
+        let (context, key_pairs) = Context::new_for_test(4);
+        let context = Arc::new(context);
+        let author_two_key = &key_pairs[2].1;
+        let signer = Arc::new(TestBlockSigner::new(author_two_key.clone()));
+        let external_key = ProtocolKeyPair::new_external(author_two_key.public(), signer.clone());
+
+        // A block signed externally verifies like one signed locally.
+        let block = TestBlock::new(10, 2).build();
+        let signed_block = SignedBlock::new(block, &external_key).expect("Shouldn't fail signing");
+        assert!(signed_block.verify_signature(&context).is_ok());
+
+        // A different block for the same round is not signed, the signer hands
+        // back the block it signed instead.
+        let block = TestBlock::new(10, 2).set_timestamp_ms(1).build();
+        let Err(ConsensusError::BlockAlreadySigned { block, signature }) =
+            SignedBlock::new(block, &external_key)
+        else {
+            panic!("Block should have been already signed");
+        };
+        let signed_block =
+            SignedBlock::from_signed_parts(&block, signature).expect("Shouldn't fail decoding");
+        assert_eq!(signed_block.timestamp_ms(), 0);
+        assert!(signed_block.verify_signature(&context).is_ok());
+
+        // The external signer may refuse to sign.
+        signer.set_refuse(true);
+        let block = TestBlock::new(11, 2).build();
+        assert!(matches!(
+            SignedBlock::new(block, &external_key),
+            Err(ConsensusError::BlockSigningFailure(_))
+        ));
+    }
 }
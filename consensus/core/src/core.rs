@@ -4,6 +4,7 @@
 
 use std::{collections::BTreeSet, iter, sync::Arc, time::Duration, vec};
 
+use bytes::Bytes;
 use consensus_config::{AuthorityIndex, ProtocolKeyPair};
 #[cfg(test)]
 use consensus_config::{Stake, local_committee_and_keys};
@@ -27,10 +28,10 @@ use crate::{
 use crate::{
     block::{
         Block, BlockAPI, BlockRef, BlockTimestampMs, BlockV1, GENESIS_ROUND, Round, SignedBlock,
-        Slot, VerifiedBlock,
+        Slot, Transaction, VerifiedBlock,
     },
     block_manager::BlockManager,
-    commit::CommittedSubDag,
+    commit::{CommitVote, CommittedSubDag},
     commit_observer::CommitObserver,
     context::Context,
     dag_state::DagState,
@@ -98,6 +99,20 @@ pub(crate) struct Core {
     /// None it means that the last block sync mechanism is enabled, but it
     /// hasn't been initialised yet.
     last_known_proposed_round: Option<Round>,
+    /// The contents of the last proposal that could not be signed. They are
+    /// included in the next proposal instead of being dropped.
+    unsigned_proposal: Option<UnsignedProposal>,
+    /// The last round for which signing the proposal failed. The round is
+    /// retried only on a forced proposal, and is skipped once the threshold
+    /// clock advances.
+    unsigned_round: Option<Round>,
+}
+
+/// Transactions and commit votes taken for a block proposal.
+struct UnsignedProposal {
+    transactions: Vec<Transaction>,
+    ack_transactions: Box<dyn FnOnce(BlockRef) + Send>,
+    commit_votes: Vec<CommitVote>,
 }
 
 impl Core {
@@ -169,6 +184,8 @@ impl Core {
             block_signer,
             dag_state,
             last_known_proposed_round: min_propose_round,
+            unsigned_proposal: None,
+            unsigned_round: None,
         }
         .recover()
     }
@@ -207,8 +224,16 @@ impl Core {
         // Try to commit and propose, since they may not have run after the last storage
         // write.
         self.try_commit().unwrap();
-        if self.try_propose(true).unwrap().is_none() {
-            if self.should_propose() {
+        let proposed = self.try_propose(true).unwrap_or_else(|err| match err {
+            ConsensusError::BlockSigningFailure(err) => {
+                // Proposing is retried on the next leader timeout.
+                warn!("Failed to sign the block proposal during recovery: {err}");
+                None
+            }
+            err => panic!("Failed to propose during recovery: {err}"),
+        });
+        if proposed.is_none() {
+            if self.should_propose() && self.unsigned_round.is_none() {
                 assert!(
                     self.last_proposed_block.round() > GENESIS_ROUND,
                     "At minimum a block of round higher that genesis should have been produced during recovery"
@@ -269,7 +294,7 @@ impl Core {
             self.try_commit()?;
 
             // Try to propose now since there are new blocks accepted.
-            self.try_propose(false)?;
+            self.try_propose_or_skip(false)?;
         }
 
         if !missing_blocks.is_empty() {
@@ -317,7 +342,7 @@ impl Core {
                 .leader_timeout_total
                 .with_label_values(&[&format!("{force}")])
                 .inc();
-            return self.try_propose(force);
+            return self.try_propose_or_skip(force);
         }
         Ok(None)
     }
@@ -343,7 +368,7 @@ impl Core {
         if !self.should_propose() {
             return Ok(None);
         }
-        if let Some(block) = self.try_new_block(force) {
+        if let Some(block) = self.try_new_block(force)? {
             self.signals.new_block(block.clone())?;
 
             fail_point!("consensus-after-propose");
@@ -355,10 +380,25 @@ impl Core {
         Ok(None)
     }
 
+    // Like `try_propose`, but a proposal that could not be signed is not an
+    // error. The round is retried on a forced proposal, or skipped once the
+    // threshold clock advances.
+    fn try_propose_or_skip(&mut self, force: bool) -> ConsensusResult<Option<VerifiedBlock>> {
+        match self.try_propose(force) {
+            Err(ConsensusError::BlockSigningFailure(err)) => {
+                warn!("Failed to sign the block proposal: {err}");
+                Ok(None)
+            }
+            result => result,
+        }
+    }
+
     /// Attempts to propose a new block for the next round. If a block has
     /// already proposed for latest or earlier round, then no block is
-    /// created and None is returned.
-    fn try_new_block(&mut self, force: bool) -> Option<VerifiedBlock> {
+    /// created and None is returned. If the signer refuses to sign the
+    /// block, the error is returned and the transactions and commit votes
+    /// taken for it are kept for the next proposal.
+    fn try_new_block(&mut self, force: bool) -> ConsensusResult<Option<VerifiedBlock>> {
         let _s = self
             .context
             .metrics
@@ -369,7 +409,10 @@ impl Core {
 
         let clock_round = self.threshold_clock.get_round();
         if clock_round <= self.last_proposed_round() {
-            return None;
+            return Ok(None);
+        }
+        if !force && self.unsigned_round == Some(clock_round) {
+            return Ok(None);
         }
 
         // There must be a quorum of blocks from the previous round.
@@ -380,7 +423,7 @@ impl Core {
         // block (leader exists and min delay has passed).
         if !force {
             if !self.leaders_exist(quorum_round) {
-                return None;
+                return Ok(None);
             }
 
             if Duration::from_millis(
@@ -390,7 +433,7 @@ impl Core {
                     .saturating_sub(self.last_proposed_timestamp_ms()),
             ) < self.context.parameters.min_round_delay
             {
-                return None;
+                return Ok(None);
             }
         }
 
@@ -424,7 +467,9 @@ impl Core {
         // supposed to be the leader of the round - so we bring down the missed leaders.
         // Probably proposing for all the intermediate rounds might not make much sense.
 
-        // Determine the ancestors to be included in proposal
+        // Determine the ancestors to be included in proposal. The watermarks are
+        // restored if the block cannot be signed.
+        let last_included_ancestors = self.last_included_ancestors.clone();
         let ancestors = self.ancestors_to_propose(clock_round);
         self.context
             .metrics
@@ -452,21 +497,30 @@ impl Core {
             );
         });
 
-        // Consume the next transactions to be included. Do not drop the guards yet as
+        // Consume the next transactions to be included, unless some are left from a
+        // proposal that could not be signed. Do not drop the guards yet as
         // this would acknowledge the inclusion of transactions. Just let this
         // be done in the end of the method.
-        let (transactions, ack_transactions) = self.transaction_consumer.next();
+        let mut proposal = self.unsigned_proposal.take().unwrap_or_else(|| {
+            let (transactions, ack_transactions) = self.transaction_consumer.next();
+            UnsignedProposal {
+                transactions,
+                ack_transactions,
+                commit_votes: Vec::new(),
+            }
+        });
         self.context
             .metrics
             .node_metrics
             .proposed_block_transactions
-            .observe(transactions.len() as f64);
+            .observe(proposal.transactions.len() as f64);
 
         // Consume the commit votes to be included.
         let commit_votes = self
             .dag_state
             .write()
-            .take_commit_votes(MAX_COMMIT_VOTES_PER_BLOCK);
+            .take_commit_votes(MAX_COMMIT_VOTES_PER_BLOCK - proposal.commit_votes.len());
+        proposal.commit_votes.extend(commit_votes);
 
         // Create the block and insert to storage.
         let block = Block::V1(BlockV1::new(
@@ -475,22 +529,45 @@ impl Core {
             self.context.own_index,
             now,
             ancestors.iter().map(|b| b.reference()).collect(),
-            transactions,
-            commit_votes,
+            proposal.transactions.clone(),
+            proposal.commit_votes.clone(),
             vec![],
         ));
-        let signed_block =
-            SignedBlock::new(block, &self.block_signer).expect("Block signing failed.");
-        let serialized = signed_block
-            .serialize()
-            .expect("Block serialization failed.");
+        let (verified_block, proposal) = match SignedBlock::new(block, &self.block_signer) {
+            Ok(signed_block) => {
+                let serialized = signed_block
+                    .serialize()
+                    .expect("Block serialization failed.");
+                // Unnecessary to verify own blocks.
+                (
+                    VerifiedBlock::new_verified(signed_block, serialized),
+                    Some(proposal),
+                )
+            }
+            Err(err) => {
+                self.last_included_ancestors = last_included_ancestors;
+                self.unsigned_proposal = Some(proposal);
+                self.unsigned_round = Some(clock_round);
+                let (block, signature) = match err {
+                    ConsensusError::BlockAlreadySigned { block, signature } => (block, signature),
+                    err => return Err(err),
+                };
+                // The signer already signed a block for this round, e.g. before a
+                // restart. Propose that block instead, while the transactions and commit
+                // votes taken here are left for the next proposal.
+                let verified_block = self.already_signed_block(clock_round, &block, signature)?;
+                for ancestor in verified_block.ancestors() {
+                    self.last_included_ancestors[ancestor.author] = Some(*ancestor);
+                }
+                info!("Proposing block {verified_block:?} signed before for round {clock_round}");
+                (verified_block, None)
+            }
+        };
         self.context
             .metrics
             .node_metrics
             .proposed_block_size
-            .observe(serialized.len() as f64);
-        // Unnecessary to verify own blocks.
-        let verified_block = VerifiedBlock::new_verified(signed_block, serialized);
+            .observe(verified_block.serialized().len() as f64);
 
         // Accept the block into BlockManager and DagState.
         let (accepted_blocks, missing) = self
@@ -509,7 +586,9 @@ impl Core {
         self.last_proposed_block = verified_block.clone();
 
         // Now acknowledge the transactions for their inclusion to block
-        ack_transactions(verified_block.reference());
+        if let Some(proposal) = proposal {
+            (proposal.ack_transactions)(verified_block.reference());
+        }
 
         debug!("Created block {:?}", verified_block);
 
@@ -520,7 +599,46 @@ impl Core {
             .with_label_values(&[&force.to_string()])
             .inc();
 
-        Some(verified_block)
+        Ok(Some(verified_block))
+    }
+
+    /// Checks that a block returned by the signer as already signed for
+    /// `round` can still be proposed by this authority.
+    fn already_signed_block(
+        &self,
+        round: Round,
+        block: &[u8],
+        signature: Bytes,
+    ) -> ConsensusResult<VerifiedBlock> {
+        let signed_block = SignedBlock::from_signed_parts(block, signature)?;
+        let rejected = |reason: &str| {
+            ConsensusError::BlockSigningFailure(format!(
+                "the block already signed for round {round} {reason}"
+            ))
+        };
+        if signed_block.author() != self.context.own_index
+            || signed_block.epoch() != self.context.committee.epoch()
+            || signed_block.round() != round
+        {
+            return Err(rejected("is for another slot"));
+        }
+        signed_block
+            .verify_signature(&self.context)
+            .map_err(|e| rejected(&format!("has an invalid signature: {e}")))?;
+        if signed_block.timestamp_ms() < self.last_proposed_timestamp_ms() {
+            return Err(rejected("is older than the last proposed block"));
+        }
+        let ancestors_exist = self
+            .dag_state
+            .read()
+            .contains_blocks(signed_block.ancestors().to_vec());
+        if ancestors_exist.contains(&false) {
+            return Err(rejected("has unknown ancestors"));
+        }
+        let serialized = signed_block
+            .serialize()
+            .expect("Block serialization failed.");
+        Ok(VerifiedBlock::new_verified(signed_block, serialized))
     }
 
     /// Runs commit rule to attempt to commit additional blocks from the DAG.
@@ -931,7 +1049,7 @@ mod test {
     use super::*;
     use crate::{
         CommitConsumer, CommitIndex,
-        block::{TestBlock, genesis_blocks},
+        block::{TestBlock, TestBlockSigner, genesis_blocks},
         block_verifier::NoopBlockVerifier,
         commit::CommitAPI as _,
         leader_scoring::ReputationScores,
@@ -1289,6 +1407,168 @@ mod test {
         assert_eq!(dag_state.read().last_commit_index(), 0);
     }
 
+    #[tokio::test]
+    async fn test_core_propose_when_signer_refuses() {
+        telemetry_subscribers::init_for_testing();
+        let (context, mut key_pairs) = Context::new_for_test(4);
+        let context = Arc::new(context);
+        let store = Arc::new(MemStore::new());
+        let dag_state = Arc::new(RwLock::new(DagState::new(context.clone(), store.clone())));
+
+        let block_manager = BlockManager::new(
+            context.clone(),
+            dag_state.clone(),
+            Arc::new(NoopBlockVerifier),
+        );
+        let (transaction_client, tx_receiver) = TransactionClient::new(context.clone());
+        let transaction_consumer = TransactionConsumer::new(tx_receiver, context.clone());
+        let (signals, signal_receivers) = CoreSignals::new(context.clone());
+        // Need at least one subscriber to the block broadcast channel.
+        let mut block_receiver = signal_receivers.block_broadcast_receiver();
+        let leader_schedule = Arc::new(LeaderSchedule::from_store(
+            context.clone(),
+            dag_state.clone(),
+        ));
+
+        let (sender, _receiver) = unbounded_channel("consensus_output");
+        let commit_observer = CommitObserver::new(
+            context.clone(),
+            CommitConsumer::new(sender.clone(), 0),
+            dag_state.clone(),
+            store.clone(),
+            leader_schedule.clone(),
+        );
+
+        // The signer refuses to sign, until told otherwise.
+        let own_key = key_pairs.remove(context.own_index.value()).1;
+        let signer = Arc::new(TestBlockSigner::new(own_key.clone()));
+        signer.set_refuse(true);
+        let block_signer = ProtocolKeyPair::new_external(own_key.public(), signer.clone());
+
+        // Submit a transaction, which is held back while signing fails.
+        let transaction = bcs::to_bytes(&"Transaction".to_string()).expect("Shouldn't fail");
+        let _w = transaction_client
+            .submit_no_wait(vec![transaction.clone()])
+            .await
+            .unwrap();
+
+        // Recovery does not panic when the first block cannot be signed.
+        let mut core = Core::new(
+            context.clone(),
+            leader_schedule,
+            transaction_consumer,
+            block_manager,
+            true,
+            commit_observer,
+            signals,
+            block_signer,
+            dag_state.clone(),
+            false,
+        );
+        assert_eq!(core.last_proposed_round(), GENESIS_ROUND);
+
+        // A forced proposal returns the signing error, while other proposals skip
+        // the round.
+        assert!(matches!(
+            core.try_propose(true),
+            Err(ConsensusError::BlockSigningFailure(_))
+        ));
+        assert!(core.try_propose(false).unwrap().is_none());
+        assert!(core.new_block(1, true).unwrap().is_none());
+        assert_eq!(core.last_proposed_round(), GENESIS_ROUND);
+
+        // Once the signer signs again, the round is retried with the transaction.
+        signer.set_refuse(false);
+        let block = core
+            .try_propose(true)
+            .unwrap()
+            .expect("A block should have been created");
+        assert_eq!(block.round(), 1);
+        assert_eq!(block.ancestors().len(), 4);
+        assert_eq!(block.transactions().len(), 1);
+        assert_eq!(block.transactions()[0].data(), transaction.as_slice());
+
+        // Only the signed block has been broadcast.
+        let broadcast = block_receiver.recv().await.unwrap();
+        assert_eq!(broadcast.reference(), block.reference());
+        assert!(block_receiver.try_recv().is_err());
+    }
+
+    /// A block signed before a restart, but not persisted, is proposed again
+    /// instead of a different block for the same round.
+    #[tokio::test]
+    async fn test_core_propose_block_signed_before_restart() {
+        telemetry_subscribers::init_for_testing();
+        let (context, mut key_pairs) = Context::new_for_test(4);
+        let context = Arc::new(context);
+        let store = Arc::new(MemStore::new());
+        let dag_state = Arc::new(RwLock::new(DagState::new(context.clone(), store.clone())));
+
+        let block_manager = BlockManager::new(
+            context.clone(),
+            dag_state.clone(),
+            Arc::new(NoopBlockVerifier),
+        );
+        let (_transaction_client, tx_receiver) = TransactionClient::new(context.clone());
+        let transaction_consumer = TransactionConsumer::new(tx_receiver, context.clone());
+        let (signals, signal_receivers) = CoreSignals::new(context.clone());
+        // Need at least one subscriber to the block broadcast channel.
+        let mut block_receiver = signal_receivers.block_broadcast_receiver();
+        let leader_schedule = Arc::new(LeaderSchedule::from_store(
+            context.clone(),
+            dag_state.clone(),
+        ));
+
+        let (sender, _receiver) = unbounded_channel("consensus_output");
+        let commit_observer = CommitObserver::new(
+            context.clone(),
+            CommitConsumer::new(sender.clone(), 0),
+            dag_state.clone(),
+            store.clone(),
+            leader_schedule.clone(),
+        );
+
+        // The signer already signed a block for round 1 before the restart.
+        let own_key = key_pairs.remove(context.own_index.value()).1;
+        let signer = Arc::new(TestBlockSigner::new(own_key.clone()));
+        let block_signer = ProtocolKeyPair::new_external(own_key.public(), signer);
+        let genesis = genesis_blocks(context.clone());
+        let signed_block = SignedBlock::new(
+            TestBlock::new(1, context.own_index.value() as u32)
+                .set_ancestors(genesis.iter().map(|b| b.reference()).collect())
+                .build(),
+            &block_signer,
+        )
+        .expect("Shouldn't fail signing");
+        let serialized = signed_block.serialize().unwrap();
+        let signed_block = VerifiedBlock::new_verified(signed_block, serialized);
+
+        // Recovery proposes that block again, as the signer does not sign
+        // another one for round 1.
+        let core = Core::new(
+            context.clone(),
+            leader_schedule,
+            transaction_consumer,
+            block_manager,
+            true,
+            commit_observer,
+            signals,
+            block_signer,
+            dag_state.clone(),
+            false,
+        );
+        let block = block_receiver
+            .recv()
+            .await
+            .expect("A block should have been created");
+        assert_eq!(block.reference(), signed_block.reference());
+        assert_eq!(
+            core.last_proposed_block.reference(),
+            signed_block.reference()
+        );
+        assert!(dag_state.read().contains_block(&signed_block.reference()));
+    }
+
     #[tokio::test]
     async fn test_core_propose_once_receiving_a_quorum() {
         telemetry_subscribers::init_for_testing();
@@ -2,6 +2,7 @@
 // Modifications Copyright (c) 2024 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+use bytes::Bytes;
 use consensus_config::{AuthorityIndex, Epoch, Stake};
 use fastcrypto::error::FastCryptoError;
 use strum_macros::IntoStaticStr;
@@ -95,6 +96,12 @@ pub(crate) enum ConsensusError {
     #[error("Failed to verify the block's signature: {0}")]
     SignatureVerificationFailure(FastCryptoError),
 
+    #[error("Failed to sign block: {0}")]
+    BlockSigningFailure(String),
+
+    #[error("A different block was already signed for this round")]
+    BlockAlreadySigned { block: Bytes, signature: Bytes },
+
     #[error("Synchronizer for fetching blocks directly from {0} is saturated")]
     SynchronizerSaturated(AuthorityIndex),
 
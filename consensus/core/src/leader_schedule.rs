@@ -8,7 +8,7 @@ use std::{
     sync::Arc,
 };
 
-use consensus_config::{AuthorityIndex, Stake};
+use consensus_config::{AuthorityIndex, LeaderScoringStrategy, Stake};
 use parking_lot::RwLock;
 use rand::{SeedableRng, prelude::SliceRandom, rngs::StdRng};
 
@@ -19,8 +19,8 @@ use crate::{
     dag_state::DagState,
     leader_scoring::{ReputationScoreCalculator, ReputationScores},
     leader_scoring_strategy::{
-        CertificateScoringStrategy, CertifiedVoteScoringStrategyV1, CertifiedVoteScoringStrategyV2,
-        ScoringStrategy, VoteScoringStrategy,
+        BlockLatencyScoringStrategy, CertificateScoringStrategy, CertifiedVoteScoringStrategyV1,
+        CertifiedVoteScoringStrategyV2, ScoringStrategy, VoteScoringStrategy,
     },
 };
 
@@ -46,10 +46,10 @@ impl LeaderSchedule {
 
     pub(crate) fn new(context: Arc<Context>, leader_swap_table: LeaderSwapTable) -> Self {
         Self {
+            scoring_strategy: Self::choose_scoring_strategy(&context),
             context,
             num_commits_per_schedule: Self::CONSENSUS_COMMITS_PER_SCHEDULE,
             leader_swap_table: Arc::new(RwLock::new(leader_swap_table)),
-            scoring_strategy: Self::choose_scoring_strategy(),
         }
     }
 
@@ -83,23 +83,26 @@ impl LeaderSchedule {
         Self::new(context, leader_swap_table)
     }
 
-    // TODO: remove this once scoring strategy is finalized
-    fn choose_scoring_strategy() -> Arc<dyn ScoringStrategy> {
-        if let Ok(scoring_strategy) = std::env::var("CONSENSUS_SCORING_STRATEGY") {
-            tracing::info!(
-                "Using scoring strategy {scoring_strategy} for ReputationScoreCalculator"
-            );
-            let scoring_strategy: Arc<dyn ScoringStrategy> = match scoring_strategy.as_str() {
-                "vote" => Arc::new(VoteScoringStrategy {}),
-                "certified_vote_v1" => Arc::new(CertifiedVoteScoringStrategyV1 {}),
-                "certified_vote_v2" => Arc::new(CertifiedVoteScoringStrategyV2 {}),
-                "certificate" => Arc::new(CertificateScoringStrategy {}),
-                _ => Arc::new(VoteScoringStrategy {}),
-            };
-            scoring_strategy
-        } else {
-            tracing::info!("Using scoring strategy vote for ReputationScoreCalculator");
-            Arc::new(VoteScoringStrategy {})
+    // TODO: remove the environment variable once scoring strategy is finalized
+    fn choose_scoring_strategy(context: &Context) -> Arc<dyn ScoringStrategy> {
+        let scoring_strategy = match std::env::var("CONSENSUS_SCORING_STRATEGY") {
+            Ok(scoring_strategy) => match scoring_strategy.as_str() {
+                "vote" => LeaderScoringStrategy::Vote,
+                "certified_vote_v1" => LeaderScoringStrategy::CertifiedVoteV1,
+                "certified_vote_v2" => LeaderScoringStrategy::CertifiedVoteV2,
+                "certificate" => LeaderScoringStrategy::Certificate,
+                "block_latency" => LeaderScoringStrategy::BlockLatency,
+                _ => LeaderScoringStrategy::Vote,
+            },
+            Err(_) => context.parameters.leader_scoring_strategy,
+        };
+        tracing::info!("Using scoring strategy {scoring_strategy:?} for ReputationScoreCalculator");
+        match scoring_strategy {
+            LeaderScoringStrategy::Vote => Arc::new(VoteScoringStrategy {}),
+            LeaderScoringStrategy::CertifiedVoteV1 => Arc::new(CertifiedVoteScoringStrategyV1 {}),
+            LeaderScoringStrategy::CertifiedVoteV2 => Arc::new(CertifiedVoteScoringStrategyV2 {}),
+            LeaderScoringStrategy::Certificate => Arc::new(CertificateScoringStrategy {}),
+            LeaderScoringStrategy::BlockLatency => Arc::new(BlockLatencyScoringStrategy {}),
         }
     }
 
@@ -5,7 +5,7 @@
 use std::{collections::HashMap, ops::Range};
 
 use crate::{
-    block::{BlockAPI, BlockRef, Slot},
+    block::{BlockAPI, BlockRef, Slot, VerifiedBlock},
     commit::DEFAULT_WAVE_LENGTH,
     leader_scoring::UnscoredSubdag,
     stake_aggregator::{QuorumThreshold, StakeAggregator},
@@ -287,6 +287,103 @@ impl ScoringStrategy for CertificateScoringStrategy {
     }
 }
 
+/// This scoring strategy scores every authority at the round of each
+/// committed leader by the latency with which its block was received by a
+/// quorum of the committee. The fastest block of the round gets the most
+/// points and a block not received by a quorum within the wave gets no points,
+/// so authorities whose blocks propagate slowly end up with the lowest scores
+/// and are swapped out of the leader schedule.
+///
+/// An authority links the blocks it received through its subscriber or
+/// synchronizer as ancestors of its next proposal, so the receive latency it
+/// measured for a block is the time between its own proposals at the round of
+/// the block and at the first round that links it. Both timestamps are chosen
+/// by the receiving authority, not by the author of the scored block, and
+/// the scores only depend on the committed DAG, so all authorities derive the
+/// same leader schedule.
+pub(crate) struct BlockLatencyScoringStrategy {}
+
+impl BlockLatencyScoringStrategy {
+    /// Returns the latency at which authorities holding 2f+1 stake received
+    /// `block` within the wave, if they did.
+    fn quorum_receive_latency_ms(subdag: &UnscoredSubdag, block: &VerifiedBlock) -> Option<u64> {
+        let round = block.round();
+        let mut latencies = subdag
+            .get_blocks_at_round(round)
+            .into_iter()
+            .filter_map(|own_block| {
+                let receiver = own_block.author();
+                // The first block of the receiver linking the scored block marks
+                // when the receiver got it.
+                (round + 1..round + DEFAULT_WAVE_LENGTH)
+                    .flat_map(|later_round| {
+                        subdag.get_blocks_at_slot(Slot::new(later_round, receiver))
+                    })
+                    .find(|later_block| later_block.ancestors().contains(&block.reference()))
+                    .map(|later_block| {
+                        (
+                            receiver,
+                            later_block
+                                .timestamp_ms()
+                                .saturating_sub(own_block.timestamp_ms()),
+                        )
+                    })
+            })
+            .collect::<Vec<_>>();
+        latencies.sort_by_key(|(_, latency)| *latency);
+
+        let mut stake_agg = StakeAggregator::<QuorumThreshold>::new();
+        latencies
+            .into_iter()
+            .find(|(receiver, _)| stake_agg.add(*receiver, &subdag.context.committee))
+            .map(|(_, latency)| latency)
+    }
+}
+
+impl ScoringStrategy for BlockLatencyScoringStrategy {
+    fn calculate_scores_for_leader(&self, subdag: &UnscoredSubdag, leader_slot: Slot) -> Vec<u64> {
+        let num_authorities = subdag.context.committee.size();
+        let mut scores_per_authority = vec![0_u64; num_authorities];
+
+        let latencies = subdag
+            .get_blocks_at_round(leader_slot.round)
+            .into_iter()
+            .filter_map(|block| {
+                Self::quorum_receive_latency_ms(subdag, &block)
+                    .map(|latency| (block.author(), latency))
+            })
+            .collect::<Vec<_>>();
+
+        let Some(max_latency) = latencies.iter().map(|(_, latency)| *latency).max() else {
+            tracing::trace!(
+                "[{}] No block of round {} received by a quorum in this set of unscored committed subdags, skip scoring",
+                subdag.context.own_index,
+                leader_slot.round
+            );
+            return scores_per_authority;
+        };
+
+        for (authority, latency) in latencies {
+            let score = max_latency - latency + 1;
+            tracing::trace!(
+                "[{}] scores +{score} reputation for {authority} with block receive latency {latency}ms!",
+                subdag.context.own_index
+            );
+            // Equivocating blocks of an authority do not add up.
+            scores_per_authority[authority] = scores_per_authority[authority].max(score);
+        }
+
+        scores_per_authority
+    }
+
+    fn leader_scoring_round_range(&self, min_round: u32, max_round: u32) -> Range<u32> {
+        // To be able to calculate scores using receive latencies we require up to
+        // +2 rounds for the blocks linking the blocks of the leader round.
+        assert!(min_round < max_round - 1);
+        min_round..max_round.saturating_sub(1)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{cmp::max, sync::Arc};
@@ -343,6 +440,19 @@ mod tests {
         assert_eq!(scores.commit_range, (1..=4).into());
     }
 
+    #[tokio::test]
+    async fn test_block_latency_scoring_strategy() {
+        let (context, unscored_subdags) = basic_setup();
+        let scoring_strategy = BlockLatencyScoringStrategy {};
+        let mut calculator =
+            ReputationScoreCalculator::new(context.clone(), &unscored_subdags, &scoring_strategy);
+        let scores = calculator.calculate();
+        // All authorities link the blocks of rounds 1 and 2 one round later.
+        // Blocks of round 3 are only linked by the leader of round 4.
+        assert_eq!(scores.scores_per_authority, vec![2, 2, 2, 2]);
+        assert_eq!(scores.commit_range, (1..=4).into());
+    }
+
     fn basic_setup() -> (Arc<Context>, Vec<CommittedSubDag>) {
         telemetry_subscribers::init_for_testing();
         let context = Arc::new(Context::new_for_test(4).0);
@@ -657,6 +657,18 @@ impl<C: NetworkClient, V: BlockVerifier, D: CoreThreadDispatcher> Synchronizer<C
                 );
                 continue;
             }
+            context
+                .metrics
+                .node_metrics
+                .block_receive_latency
+                .with_label_values(&[
+                    &context
+                        .committee
+                        .authority(verified_block.author())
+                        .hostname,
+                    "synchronizer",
+                ])
+                .observe(Duration::from_millis(now - verified_block.timestamp_ms()).as_secs_f64());
 
             verified_blocks.push(verified_block);
         }
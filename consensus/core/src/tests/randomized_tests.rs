@@ -2,9 +2,9 @@
 // Modifications Copyright (c) 2024 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{env, sync::Arc};
+use std::{cmp::max, env, sync::Arc};
 
-use consensus_config::AuthorityIndex;
+use consensus_config::{AuthorityIndex, LeaderScoringStrategy};
 use parking_lot::RwLock;
 use rand::{Rng, SeedableRng, prelude::SliceRandom, rngs::StdRng};
 
@@ -18,6 +18,7 @@ use crate::{
     leader_schedule::{LeaderSchedule, LeaderSwapTable},
     storage::mem_store::MemStore,
     test_dag::create_random_dag,
+    test_dag_builder::DagBuilder,
     universal_committer::{
         UniversalCommitter, universal_committer_builder::UniversalCommitterBuilder,
     },
@@ -172,6 +173,123 @@ async fn test_randomized_dag_and_decision_sequence() {
     }
 }
 
+/// Test builds a dag in which the blocks of up to f slow authorities reach the
+/// other authorities too late to be linked by their blocks of the next round,
+/// so they are only linked one round later.
+///
+/// The reputation scores calculated over the committed sub dags with the block
+/// latency scoring strategy should swap a slow authority out of the leader
+/// schedule.
+#[tokio::test]
+async fn test_randomized_dag_with_slow_authorities() {
+    let mut random_test_setup = random_test_setup();
+
+    for _ in 0..NUM_RUNS {
+        // With 5 to 9 authorities of equal stake exactly one authority is swapped
+        // out of the leader schedule.
+        let num_authorities = random_test_setup.seeded_rng.gen_range(5..10);
+        let authority = authority_setup(num_authorities, 0);
+
+        let mut authorities = authority
+            .context
+            .committee
+            .authorities()
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        authorities.shuffle(&mut random_test_setup.seeded_rng);
+        let num_slow_authorities = random_test_setup
+            .seeded_rng
+            .gen_range(1..=(num_authorities - 1) / 3);
+        let slow_authorities = authorities[..num_slow_authorities].to_vec();
+
+        tracing::info!(
+            "Running test with committee size {num_authorities}, slow authorities {slow_authorities:?} & {NUM_ROUNDS} rounds in the DAG..."
+        );
+
+        let mut dag_builder = DagBuilder::new(authority.context.clone());
+        let mut ancestors = dag_builder.genesis_block_refs();
+        let mut late_ancestors = vec![];
+        for round in 1..=NUM_ROUNDS {
+            let fast_ancestors = ancestors
+                .iter()
+                .filter(|ancestor| round == 1 || !slow_authorities.contains(&ancestor.author))
+                .chain(late_ancestors.iter())
+                .cloned()
+                .collect::<Vec<_>>();
+            let connections = authorities
+                .iter()
+                .map(|authority| {
+                    if slow_authorities.contains(authority) {
+                        (*authority, ancestors.clone())
+                    } else {
+                        (*authority, fast_ancestors.clone())
+                    }
+                })
+                .collect();
+            dag_builder.layer_with_connections(connections, round);
+
+            late_ancestors = ancestors
+                .into_iter()
+                .filter(|ancestor| round > 1 && slow_authorities.contains(&ancestor.author))
+                .collect();
+            ancestors = dag_builder.last_ancestors.clone();
+        }
+        dag_builder.persist_all_blocks(authority.dag_state.clone());
+
+        let sequence = authority.committer.try_decide(Slot::new_for_test(0, 0));
+        let mut last_committed_rounds = vec![0; num_authorities];
+        let mut unscored_subdags = vec![];
+        for leader in sequence {
+            let DecidedLeader::Commit(leader_block) = leader else {
+                // Leader slots of slow authorities are skipped.
+                continue;
+            };
+            let (subdag, commit) = dag_builder.get_sub_dag_and_commit(
+                leader_block,
+                last_committed_rounds.clone(),
+                unscored_subdags.len() as u32 + 1,
+            );
+            for block in subdag.blocks.iter() {
+                last_committed_rounds[block.author().value()] =
+                    max(block.round(), last_committed_rounds[block.author().value()]);
+            }
+            authority.dag_state.write().add_commit(commit);
+            unscored_subdags.push(subdag);
+        }
+        authority
+            .dag_state
+            .write()
+            .add_unscored_committed_subdags(unscored_subdags);
+
+        let mut context = (*authority.context).clone();
+        context.parameters.leader_scoring_strategy = LeaderScoringStrategy::BlockLatency;
+        let leader_schedule = LeaderSchedule::new(Arc::new(context), LeaderSwapTable::default());
+        leader_schedule.update_leader_schedule(&authority.dag_state);
+
+        let leader_swap_table = leader_schedule.leader_swap_table.read();
+        let scores = &leader_swap_table.reputation_scores.scores_per_authority;
+        let (slow_scores, fast_scores): (Vec<_>, Vec<_>) = authorities
+            .iter()
+            .partition(|authority| slow_authorities.contains(authority));
+        let max_slow_score = slow_scores
+            .iter()
+            .map(|authority| scores[**authority])
+            .max();
+        let min_fast_score = fast_scores
+            .iter()
+            .map(|authority| scores[**authority])
+            .min();
+        assert!(max_slow_score < min_fast_score);
+        assert_eq!(leader_swap_table.bad_nodes.len(), 1);
+        assert!(
+            leader_swap_table
+                .bad_nodes
+                .keys()
+                .all(|authority| slow_authorities.contains(authority))
+        );
+    }
+}
+
 struct AuthorityTestFixture {
     context: Arc<Context>,
     dag_state: Arc<RwLock<DagState>>,
@@ -66,7 +66,7 @@ impl TransactionConsumer {
     // Note that a TransactionsGuard may be partially consumed and the rest saved
     // for the next pull, in which case its `included_in_block_ack` will not be
     // signalled in the callback.
-    pub(crate) fn next(&mut self) -> (Vec<Transaction>, Box<dyn FnOnce(BlockRef)>) {
+    pub(crate) fn next(&mut self) -> (Vec<Transaction>, Box<dyn FnOnce(BlockRef) + Send>) {
         let mut transactions = Vec::new();
         let mut acks = Vec::new();
         let mut total_size: usize = 0;
@@ -22,7 +22,7 @@ use tokio::sync::{Mutex, mpsc, oneshot};
 use tracing::{error, info};
 
 use crate::{
-    AnalyticsIndexerConfig, EPOCH_DIR_PREFIX, FileMetadata, MaxCheckpointReader, ParquetSchema,
+    AnalyticsIndexerConfig, FileMetadata, MaxCheckpointReader, ParquetSchema, PartitionLayout,
     analytics_metrics::AnalyticsMetrics, handlers::AnalyticsHandler, join_paths,
     writers::AnalyticsWriter,
 };
@@ -40,6 +40,7 @@ pub struct AnalyticsProcessor<S: Serialize + ParquetSchema> {
     state: Mutex<State<S>>,
     metrics: AnalyticsMetrics,
     config: AnalyticsIndexerConfig,
+    layout: PartitionLayout,
     sender: mpsc::Sender<FileMetadata>,
     #[expect(dead_code)]
     kill_sender: oneshot::Sender<()>,
@@ -72,7 +73,13 @@ impl<S: Serialize + ParquetSchema + 'static> Worker for AnalyticsProcessor<S> {
 
         let num_checkpoints_processed =
             state.current_checkpoint_range.end - state.current_checkpoint_range.start;
-        let cut_new_files = (num_checkpoints_processed >= self.config.checkpoint_interval)
+        // Files must not span multiple checkpoint partitions
+        let starts_checkpoint_partition = self
+            .layout
+            .checkpoint_partition(checkpoint_num)
+            .is_some_and(|partition| partition.start == checkpoint_num);
+        let cut_new_files = starts_checkpoint_partition
+            || (num_checkpoints_processed >= self.config.checkpoint_interval)
             || (state.last_commit_instant.elapsed().as_secs() > self.config.time_interval_s)
             || (state.num_checkpoint_iterations % CHECK_FILE_SIZE_ITERATION_CYCLE == 0
                 && state.writer.file_size()?.unwrap_or(0)
@@ -107,6 +114,7 @@ impl<S: Serialize + ParquetSchema + 'static> AnalyticsProcessor<S> {
         metrics: AnalyticsMetrics,
         config: AnalyticsIndexerConfig,
     ) -> Result<Self> {
+        let layout = config.partition_layout()?;
         let local_store_config = ObjectStoreConfig {
             directory: Some(config.checkpoint_dir.clone()),
             object_store: Some(ObjectStoreType::File),
@@ -151,6 +159,7 @@ impl<S: Serialize + ParquetSchema + 'static> AnalyticsProcessor<S> {
             max_checkpoint_sender,
             metrics,
             config,
+            layout,
         })
     }
 
@@ -165,6 +174,7 @@ impl<S: Serialize + ParquetSchema + 'static> AnalyticsProcessor<S> {
             let file_metadata = FileMetadata::new(
                 self.config.file_type,
                 self.config.file_format,
+                self.layout,
                 state.current_epoch,
                 state.current_checkpoint_range.clone(),
             );
@@ -183,7 +193,7 @@ impl<S: Serialize + ParquetSchema + 'static> AnalyticsProcessor<S> {
             self.config.checkpoint_dir.to_path_buf(),
             &self.config.file_type.dir_prefix(),
         )?
-        .join(format!("{}{}", EPOCH_DIR_PREFIX, state.current_epoch));
+        .join(self.layout.partitioning().epoch_dir(state.current_epoch));
         Ok(path)
     }
 
@@ -0,0 +1,70 @@
+// Copyright (c) 2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::num::NonZeroU64;
+
+use anyhow::Result;
+use clap::*;
+use iota_analytics_indexer::{
+    FileFormat, FileType, PartitionLayout, Partitioning, compaction::compact_table,
+};
+use iota_config::object_storage_config::ObjectStoreConfig;
+use iota_types::base_types::EpochId;
+use object_store::path::Path;
+use tracing::info;
+
+/// Merges the small parquet files uploaded by the analytics indexer into
+/// larger files. The latest partition of the table is left untouched, so the
+/// compactor can run while the indexer is uploading files.
+#[derive(Parser, Clone, Debug)]
+#[clap(name = "IOTA Analytics Compactor", rename_all = "kebab-case")]
+struct Args {
+    /// Object store the analytics indexer uploads its tables to.
+    #[command(flatten)]
+    remote_store_config: ObjectStoreConfig,
+    /// Path prefix of the tables in the object store.
+    #[clap(long)]
+    remote_store_path_prefix: Option<Path>,
+    /// Table to compact.
+    #[clap(long, value_enum)]
+    file_type: FileType,
+    /// Directory layout of the epochs of the table.
+    #[clap(long, value_enum, default_value = "legacy")]
+    partitioning: Partitioning,
+    /// Number of checkpoints per checkpoint range partition of the table.
+    #[clap(long)]
+    checkpoint_partition_size: Option<NonZeroU64>,
+    /// Maximum size of the merged files.
+    #[clap(long, default_value = "128")]
+    target_file_size_mb: u64,
+    /// Only compact the files of this epoch.
+    #[clap(long)]
+    epoch: Option<EpochId>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let _guard = telemetry_subscribers::TelemetryConfig::new()
+        .with_env()
+        .init();
+
+    let args = Args::parse();
+    let compacted = compact_table(
+        &args.remote_store_config.make()?,
+        args.remote_store_path_prefix,
+        args.file_type,
+        FileFormat::PARQUET,
+        PartitionLayout::new(args.partitioning, args.checkpoint_partition_size)?,
+        args.target_file_size_mb * 1024 * 1024,
+        args.epoch,
+    )
+    .await?;
+    for file in &compacted {
+        info!(
+            "Merged {} files into checkpoints {:?} of epoch {}",
+            file.num_merged_files, file.checkpoint_range, file.epoch
+        );
+    }
+    info!("Compacted {} files", compacted.len());
+    Ok(())
+}
@@ -0,0 +1,473 @@
+// Copyright (c) 2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{ops::Range, sync::Arc};
+
+use anyhow::{Result, bail};
+use arrow::datatypes::Schema;
+use arrow_array::RecordBatch;
+use bytes::Bytes;
+use iota_storage::object_store::{ObjectStoreDeleteExt, ObjectStoreGetExt, ObjectStorePutExt};
+use iota_types::base_types::EpochId;
+use object_store::{DynObjectStore, path::Path};
+use parquet::{
+    arrow::{ArrowWriter, arrow_reader::ParquetRecordBatchReaderBuilder},
+    basic::Compression,
+    file::{metadata::KeyValue, properties::WriterProperties},
+};
+use tracing::info;
+
+use crate::{
+    FileFormat, FileType, PartitionLayout, find_all_checkpoint_partitions, find_all_epochs,
+    join_paths, schema_registry::SCHEMA_VERSION_METADATA_KEY,
+};
+
+/// A file created by merging the files of consecutive checkpoint ranges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactedFile {
+    pub epoch: EpochId,
+    pub checkpoint_range: Range<u64>,
+    pub num_merged_files: usize,
+}
+
+struct DataFile {
+    path: Path,
+    checkpoint_range: Range<u64>,
+    size: u64,
+    schema_version: Option<String>,
+}
+
+/// Merges the small parquet files of a table in `store` into files of up to
+/// `target_file_size` bytes, for all epochs or only `epoch`.
+///
+/// The analytics indexer uploads files in checkpoint order, so it only ever
+/// adds files to the latest partition of a table. That partition is never
+/// compacted, which allows compacting a table while the indexer is running.
+///
+/// Only files of consecutive checkpoint ranges written with the same schema
+/// version are merged. The merged file is named after the checkpoint range it
+/// covers and is stored before the merged files are deleted, so an
+/// interrupted compaction leaves files whose range is covered by another file
+/// behind, which are deleted by the next compaction.
+pub async fn compact_table(
+    store: &Arc<DynObjectStore>,
+    dir_prefix: Option<Path>,
+    file_type: FileType,
+    file_format: FileFormat,
+    layout: PartitionLayout,
+    target_file_size: u64,
+    epoch: Option<EpochId>,
+) -> Result<Vec<CompactedFile>> {
+    if file_format != FileFormat::PARQUET {
+        bail!("Compaction is only supported for parquet files");
+    }
+    let table_dir = join_paths(dir_prefix, &file_type.dir_prefix());
+    let mut epochs = find_all_epochs(store, &table_dir, layout.partitioning()).await?;
+    epochs.sort();
+
+    let mut partitions = vec![];
+    for dir_epoch in epochs {
+        let epoch_dir = table_dir.child(layout.partitioning().epoch_dir(dir_epoch));
+        if !layout.has_checkpoint_partitions() {
+            partitions.push((dir_epoch, epoch_dir));
+            continue;
+        }
+        let mut checkpoint_partitions =
+            find_all_checkpoint_partitions(store, &epoch_dir, layout).await?;
+        checkpoint_partitions.sort();
+        for checkpoint in checkpoint_partitions {
+            partitions.push((
+                dir_epoch,
+                layout.partition_dir(table_dir.clone(), dir_epoch, checkpoint),
+            ));
+        }
+    }
+    // The indexer might still be uploading files to the latest partition
+    partitions.pop();
+
+    let mut compacted = vec![];
+    for (dir_epoch, partition_dir) in partitions {
+        if epoch.is_some_and(|epoch| epoch != dir_epoch) {
+            continue;
+        }
+        for (checkpoint_range, num_merged_files) in
+            compact_partition(store, &partition_dir, file_format, target_file_size).await?
+        {
+            compacted.push(CompactedFile {
+                epoch: dir_epoch,
+                checkpoint_range,
+                num_merged_files,
+            });
+        }
+    }
+    Ok(compacted)
+}
+
+async fn compact_partition(
+    store: &Arc<DynObjectStore>,
+    partition_dir: &Path,
+    file_format: FileFormat,
+    target_file_size: u64,
+) -> Result<Vec<(Range<u64>, usize)>> {
+    let files = remove_covered_files(
+        store,
+        list_data_files(store, partition_dir, file_format).await?,
+    )
+    .await?;
+
+    // Groups files of consecutive checkpoint ranges with the same schema version
+    let mut groups: Vec<Vec<DataFile>> = vec![];
+    for file in files {
+        if let Some(group) = groups.last_mut() {
+            let last = group.last().expect("groups are never empty");
+            let group_size: u64 = group.iter().map(|file| file.size).sum();
+            if last.checkpoint_range.end == file.checkpoint_range.start
+                && last.schema_version == file.schema_version
+                && group_size + file.size <= target_file_size
+            {
+                group.push(file);
+                continue;
+            }
+        }
+        groups.push(vec![file]);
+    }
+
+    let mut compacted = vec![];
+    for group in groups.into_iter().filter(|group| group.len() > 1) {
+        let checkpoint_range =
+            group[0].checkpoint_range.start..group[group.len() - 1].checkpoint_range.end;
+        let path = partition_dir.child(format!(
+            "{}_{}.{}",
+            checkpoint_range.start,
+            checkpoint_range.end,
+            file_format.file_suffix()
+        ));
+        info!("Merging {} files into {path}", group.len());
+        let bytes = merge_parquet_files(store, &group).await?;
+        store.put_bytes(&path, bytes).await?;
+        for file in &group {
+            store.delete_object(&file.path).await?;
+        }
+        compacted.push((checkpoint_range, group.len()));
+    }
+    Ok(compacted)
+}
+
+/// Lists the data files in the partition directory sorted by checkpoint
+/// range.
+async fn list_data_files(
+    store: &Arc<DynObjectStore>,
+    partition_dir: &Path,
+    file_format: FileFormat,
+) -> Result<Vec<DataFile>> {
+    let mut files = vec![];
+    for object in store
+        .list_with_delimiter(Some(partition_dir))
+        .await?
+        .objects
+    {
+        let Some(checkpoint_range) = object
+            .location
+            .filename()
+            .and_then(|name| name.strip_suffix(file_format.file_suffix()))
+            .and_then(|stem| stem.strip_suffix('.'))
+            .and_then(|stem| stem.split_once('_'))
+            .and_then(|(start, end)| Some(start.parse().ok()?..end.parse().ok()?))
+        else {
+            continue;
+        };
+        let reader =
+            ParquetRecordBatchReaderBuilder::try_new(store.get_bytes(&object.location).await?)?;
+        let schema_version = reader
+            .metadata()
+            .file_metadata()
+            .key_value_metadata()
+            .and_then(|metadata| {
+                metadata
+                    .iter()
+                    .find(|kv| kv.key == SCHEMA_VERSION_METADATA_KEY)
+            })
+            .and_then(|kv| kv.value.clone());
+        files.push(DataFile {
+            path: object.location,
+            checkpoint_range,
+            size: object.size as u64,
+            schema_version,
+        });
+    }
+    files.sort_by_key(|file| {
+        (
+            file.checkpoint_range.start,
+            u64::MAX - file.checkpoint_range.end,
+        )
+    });
+    Ok(files)
+}
+
+/// Deletes files whose checkpoint range is covered by another file, which are
+/// left behind by an interrupted compaction. `files` must be sorted by start
+/// and descending end of the checkpoint range.
+async fn remove_covered_files(
+    store: &Arc<DynObjectStore>,
+    files: Vec<DataFile>,
+) -> Result<Vec<DataFile>> {
+    let mut remaining: Vec<DataFile> = vec![];
+    for file in files {
+        if let Some(last) = remaining.last() {
+            if file.checkpoint_range.end <= last.checkpoint_range.end {
+                info!("Removing {} covered by {}", file.path, last.path);
+                store.delete_object(&file.path).await?;
+                continue;
+            }
+        }
+        remaining.push(file);
+    }
+    Ok(remaining)
+}
+
+async fn merge_parquet_files(store: &Arc<DynObjectStore>, files: &[DataFile]) -> Result<Bytes> {
+    let mut batches = vec![];
+    for file in files {
+        let bytes = store.get_bytes(&file.path).await?;
+        for batch in ParquetRecordBatchReaderBuilder::try_new(bytes)?.build()? {
+            batches.push(batch?);
+        }
+    }
+    // Files of the same schema version only differ in the nullability of
+    // their columns, depending on whether they contain null values
+    let schema = Arc::new(Schema::try_merge(
+        batches.iter().map(|batch| batch.schema().as_ref().clone()),
+    )?);
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .set_key_value_metadata(files[0].schema_version.clone().map(|version| {
+            vec![KeyValue::new(
+                SCHEMA_VERSION_METADATA_KEY.to_string(),
+                version,
+            )]
+        }))
+        .build();
+
+    let mut writer = ArrowWriter::try_new(Vec::new(), schema.clone(), Some(properties))?;
+    for batch in batches {
+        writer.write(&RecordBatch::try_new(
+            schema.clone(),
+            batch.columns().to_vec(),
+        )?)?;
+    }
+    Ok(Bytes::from(writer.into_inner()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, num::NonZeroU64, path::Path as FsPath};
+
+    use iota_config::object_storage_config::{ObjectStoreConfig, ObjectStoreType};
+    use serde::Serialize;
+
+    use super::*;
+    use crate::{
+        ParquetSchema, ParquetValue, Partitioning,
+        writers::{AnalyticsWriter, parquet_writer::ParquetWriter},
+    };
+
+    #[derive(Serialize)]
+    struct Entry {
+        checkpoint: u64,
+        value: Option<String>,
+    }
+
+    impl ParquetSchema for Entry {
+        fn schema() -> Vec<String> {
+            vec!["checkpoint".to_string(), "value".to_string()]
+        }
+
+        fn get_column(&self, idx: usize) -> ParquetValue {
+            match idx {
+                0 => self.checkpoint.into(),
+                1 => self.value.clone().into(),
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    fn hive() -> PartitionLayout {
+        PartitionLayout::new(Partitioning::Hive, None).unwrap()
+    }
+
+    fn write_files(
+        root_dir: &FsPath,
+        layout: PartitionLayout,
+        schema_version: u32,
+        epoch: EpochId,
+        ranges: &[Range<u64>],
+    ) {
+        let mut writer = ParquetWriter::new(
+            root_dir,
+            FileType::Checkpoint,
+            layout,
+            schema_version,
+            ranges[0].start,
+        )
+        .unwrap();
+        for range in ranges {
+            AnalyticsWriter::<Entry>::reset(&mut writer, epoch, range.start).unwrap();
+            let rows = range
+                .clone()
+                .map(|checkpoint| Entry {
+                    checkpoint,
+                    // Only some files contain null values
+                    value: (checkpoint % 3 != 0).then(|| checkpoint.to_string()),
+                })
+                .collect::<Vec<_>>();
+            AnalyticsWriter::<Entry>::write(&mut writer, &rows).unwrap();
+            AnalyticsWriter::<Entry>::flush(&mut writer, range.end).unwrap();
+        }
+    }
+
+    fn local_store(root_dir: &FsPath) -> Arc<DynObjectStore> {
+        ObjectStoreConfig {
+            directory: Some(root_dir.to_path_buf()),
+            object_store: Some(ObjectStoreType::File),
+            ..Default::default()
+        }
+        .make()
+        .unwrap()
+    }
+
+    async fn compact(
+        root_dir: &FsPath,
+        layout: PartitionLayout,
+        epoch: Option<EpochId>,
+    ) -> Vec<CompactedFile> {
+        compact_table(
+            &local_store(root_dir),
+            None,
+            FileType::Checkpoint,
+            FileFormat::PARQUET,
+            layout,
+            u64::MAX,
+            epoch,
+        )
+        .await
+        .unwrap()
+    }
+
+    fn file_names(dir: &FsPath) -> Vec<String> {
+        let mut file_names = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        file_names.sort();
+        file_names
+    }
+
+    fn read_checkpoints(path: &FsPath) -> Vec<u64> {
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        reader
+            .flat_map(|batch| {
+                let batch = batch.unwrap();
+                batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<arrow_array::UInt64Array>()
+                    .unwrap()
+                    .values()
+                    .to_vec()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn merges_consecutive_files_of_same_schema_version() {
+        let root_dir = tempfile::tempdir().unwrap();
+        write_files(root_dir.path(), hive(), 1, 0, &[0..2, 2..3, 3..5]);
+        // A schema change starts a new group
+        write_files(root_dir.path(), hive(), 2, 0, &[5..7, 7..8]);
+        write_files(root_dir.path(), hive(), 2, 1, &[8..9, 9..10]);
+
+        assert_eq!(compact(root_dir.path(), hive(), None).await, vec![
+            CompactedFile {
+                epoch: 0,
+                checkpoint_range: 0..5,
+                num_merged_files: 3,
+            },
+            CompactedFile {
+                epoch: 0,
+                checkpoint_range: 5..8,
+                num_merged_files: 2,
+            },
+        ]);
+
+        let epoch_dir = root_dir.path().join("checkpoints").join("epoch=0");
+        assert_eq!(file_names(&epoch_dir), vec!["0_5.parquet", "5_8.parquet"]);
+        assert_eq!(
+            read_checkpoints(&epoch_dir.join("0_5.parquet")),
+            (0..5).collect::<Vec<_>>()
+        );
+        // The latest epoch might still receive files
+        assert_eq!(
+            file_names(&root_dir.path().join("checkpoints").join("epoch=1")),
+            vec!["8_9.parquet", "9_10.parquet"]
+        );
+    }
+
+    #[tokio::test]
+    async fn compacts_sealed_checkpoint_partitions() {
+        let root_dir = tempfile::tempdir().unwrap();
+        let layout = PartitionLayout::new(Partitioning::Hive, NonZeroU64::new(4)).unwrap();
+        write_files(root_dir.path(), layout, 1, 0, &[0..2, 2..4]);
+        write_files(root_dir.path(), layout, 1, 0, &[4..6, 6..8]);
+        write_files(root_dir.path(), layout, 1, 0, &[8..9, 9..10]);
+
+        assert_eq!(compact(root_dir.path(), layout, None).await, vec![
+            CompactedFile {
+                epoch: 0,
+                checkpoint_range: 0..4,
+                num_merged_files: 2,
+            },
+            CompactedFile {
+                epoch: 0,
+                checkpoint_range: 4..8,
+                num_merged_files: 2,
+            },
+        ]);
+
+        let epoch_dir = root_dir.path().join("checkpoints").join("epoch=0");
+        assert_eq!(file_names(&epoch_dir), vec![
+            "checkpoint_partition=0",
+            "checkpoint_partition=4",
+            "checkpoint_partition=8"
+        ]);
+        assert_eq!(file_names(&epoch_dir.join("checkpoint_partition=4")), vec![
+            "4_8.parquet"
+        ]);
+        assert_eq!(file_names(&epoch_dir.join("checkpoint_partition=8")), vec![
+            "8_9.parquet",
+            "9_10.parquet"
+        ]);
+    }
+
+    #[tokio::test]
+    async fn removes_files_left_by_interrupted_compaction() {
+        let root_dir = tempfile::tempdir().unwrap();
+        write_files(root_dir.path(), hive(), 1, 0, &[0..2, 2..4, 0..4]);
+        write_files(root_dir.path(), hive(), 1, 1, &[4..5]);
+
+        assert!(compact(root_dir.path(), hive(), Some(0)).await.is_empty());
+        let epoch_dir = root_dir.path().join("checkpoints").join("epoch=0");
+        assert_eq!(file_names(&epoch_dir), vec!["0_4.parquet"]);
+        assert_eq!(
+            read_checkpoints(&epoch_dir.join("0_4.parquet")),
+            (0..4).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn checkpoint_partitions_require_hive_partitioning() {
+        assert!(PartitionLayout::new(Partitioning::Legacy, NonZeroU64::new(4)).is_err());
+    }
+}
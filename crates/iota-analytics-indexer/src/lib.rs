@@ -2,24 +2,22 @@
 // Modifications Copyright (c) 2024 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{ops::Range, path::PathBuf};
+use std::{num::NonZeroU64, ops::Range, path::PathBuf};
 
-use anyhow::{Result, anyhow};
+use anyhow::{Result, anyhow, bail};
 use arrow_array::Int32Array;
 use clap::*;
 use gcp_bigquery_client::{Client, model::query_request::QueryRequest};
 use iota_config::object_storage_config::ObjectStoreConfig;
 use iota_data_ingestion_core::Worker;
 use iota_rest_api::CheckpointData;
-use iota_storage::object_store::util::{
-    find_all_dirs_with_epoch_prefix, find_all_files_with_epoch_prefix,
-};
+use iota_storage::object_store::util::find_all_files_with_epoch_prefix;
 use iota_types::{
     base_types::EpochId, dynamic_field::DynamicFieldType,
     messages_checkpoint::CheckpointSequenceNumber,
 };
 use num_enum::{IntoPrimitive, TryFromPrimitive};
-use object_store::path::Path;
+use object_store::{DynObjectStore, path::Path};
 use serde::{Deserialize, Serialize};
 use snowflake_api::{QueryResult, SnowflakeApi};
 use strum::EnumIter;
@@ -36,6 +34,7 @@ use crate::{
         transaction_objects_handler::TransactionObjectsHandler,
         wrapped_object_handler::WrappedObjectHandler,
     },
+    schema_registry::SchemaRegistry,
     tables::{
         CheckpointEntry, DynamicFieldEntry, EventEntry, InputObjectKind, MoveCallEntry,
         MovePackageEntry, ObjectEntry, ObjectStatus, OwnerType, TransactionEntry,
@@ -46,13 +45,17 @@ use crate::{
 
 pub mod analytics_metrics;
 pub mod analytics_processor;
+pub mod compaction;
 pub mod errors;
 mod handlers;
 mod package_store;
+pub mod schema_registry;
 pub mod tables;
 mod writers;
 
 const EPOCH_DIR_PREFIX: &str = "epoch_";
+const HIVE_EPOCH_DIR_PREFIX: &str = "epoch=";
+const HIVE_CHECKPOINT_PARTITION_DIR_PREFIX: &str = "checkpoint_partition=";
 const CHECKPOINT_DIR_PREFIX: &str = "checkpoints";
 const OBJECT_DIR_PREFIX: &str = "objects";
 const TRANSACTION_DIR_PREFIX: &str = "transactions";
@@ -107,6 +110,13 @@ pub struct AnalyticsIndexerConfig {
     // Type of data to write i.e. checkpoint, object, transaction, etc
     #[clap(long, value_enum, long, global = true)]
     pub file_type: FileType,
+    // Directory layout of the epochs of a table i.e. legacy, hive
+    #[clap(long, value_enum, default_value = "legacy", global = true)]
+    pub partitioning: Partitioning,
+    // Number of checkpoints per checkpoint range partition within an epoch,
+    // only supported with hive partitioning
+    #[clap(long, default_value = None, global = true)]
+    pub checkpoint_partition_size: Option<NonZeroU64>,
     #[clap(
         long,
         default_value = "https://checkpoints.mainnet.iota.io",
@@ -156,6 +166,12 @@ pub struct AnalyticsIndexerConfig {
     pub report_sf_max_table_checkpoint: bool,
 }
 
+impl AnalyticsIndexerConfig {
+    pub fn partition_layout(&self) -> Result<PartitionLayout> {
+        PartitionLayout::new(self.partitioning, self.checkpoint_partition_size)
+    }
+}
+
 #[async_trait::async_trait]
 pub trait MaxCheckpointReader: Send + Sync + 'static {
     async fn max_checkpoint(&self) -> Result<i64>;
@@ -299,6 +315,100 @@ impl FileFormat {
     }
 }
 
+/// Directory layout of the epochs of a table.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, ValueEnum)]
+pub enum Partitioning {
+    /// `epoch_{epoch}` directories.
+    #[default]
+    Legacy,
+    /// Hive style `epoch={epoch}` directories, which allows query engines to
+    /// prune files by epoch.
+    Hive,
+}
+
+impl Partitioning {
+    pub fn epoch_dir(&self, epoch_num: EpochId) -> String {
+        format!("{}{}", self.epoch_dir_prefix(), epoch_num)
+    }
+
+    /// Parses the epoch from a directory name created by
+    /// [`Partitioning::epoch_dir`].
+    pub fn parse_epoch_dir(&self, dir_name: &str) -> Option<EpochId> {
+        dir_name.strip_prefix(self.epoch_dir_prefix())?.parse().ok()
+    }
+
+    fn epoch_dir_prefix(&self) -> &'static str {
+        match self {
+            Partitioning::Legacy => EPOCH_DIR_PREFIX,
+            Partitioning::Hive => HIVE_EPOCH_DIR_PREFIX,
+        }
+    }
+}
+
+/// Directory layout of the files of a table: files are partitioned by epoch
+/// and optionally by checkpoint range within an epoch, i.e.
+/// `epoch={epoch}/checkpoint_partition={start}` with hive partitioning, where
+/// `start` is the first checkpoint of the partition.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct PartitionLayout {
+    partitioning: Partitioning,
+    checkpoint_partition_size: Option<NonZeroU64>,
+}
+
+impl PartitionLayout {
+    pub fn new(
+        partitioning: Partitioning,
+        checkpoint_partition_size: Option<NonZeroU64>,
+    ) -> Result<Self> {
+        if checkpoint_partition_size.is_some() && partitioning != Partitioning::Hive {
+            bail!("Checkpoint range partitions are only supported with hive partitioning");
+        }
+        Ok(Self {
+            partitioning,
+            checkpoint_partition_size,
+        })
+    }
+
+    pub fn partitioning(&self) -> Partitioning {
+        self.partitioning
+    }
+
+    pub fn has_checkpoint_partitions(&self) -> bool {
+        self.checkpoint_partition_size.is_some()
+    }
+
+    /// Returns the checkpoint range of the partition containing `checkpoint`.
+    pub fn checkpoint_partition(&self, checkpoint: u64) -> Option<Range<u64>> {
+        let size = self.checkpoint_partition_size?.get();
+        let start = checkpoint - checkpoint % size;
+        Some(start..start.saturating_add(size))
+    }
+
+    /// Returns the directory of the partition of a file of epoch `epoch_num`
+    /// starting at `checkpoint`. Files never span multiple checkpoint
+    /// partitions.
+    pub fn partition_dir(&self, table_dir: Path, epoch_num: EpochId, checkpoint: u64) -> Path {
+        let epoch_dir = table_dir.child(self.partitioning.epoch_dir(epoch_num));
+        match self.checkpoint_partition(checkpoint) {
+            Some(partition) => epoch_dir.child(format!(
+                "{}{}",
+                HIVE_CHECKPOINT_PARTITION_DIR_PREFIX, partition.start
+            )),
+            None => epoch_dir,
+        }
+    }
+
+    /// Parses the first checkpoint of a checkpoint partition from a directory
+    /// name created by [`PartitionLayout::partition_dir`].
+    pub fn parse_checkpoint_partition_dir(&self, dir_name: &str) -> Option<u64> {
+        self.checkpoint_partition_size?;
+        dir_name
+            .strip_prefix(HIVE_CHECKPOINT_PARTITION_DIR_PREFIX)?
+            .parse()
+            .ok()
+    }
+}
+
 #[derive(
     Copy,
     Clone,
@@ -343,11 +453,12 @@ impl FileType {
     pub fn file_path(
         &self,
         file_format: FileFormat,
+        layout: PartitionLayout,
         epoch_num: EpochId,
         checkpoint_range: Range<u64>,
     ) -> Path {
-        self.dir_prefix()
-            .child(format!("{}{}", EPOCH_DIR_PREFIX, epoch_num))
+        layout
+            .partition_dir(self.dir_prefix(), epoch_num, checkpoint_range.start)
             .child(format!(
                 "{}_{}.{}",
                 checkpoint_range.start,
@@ -454,6 +565,7 @@ pub trait ParquetSchema {
 pub struct FileMetadata {
     pub file_type: FileType,
     pub file_format: FileFormat,
+    pub layout: PartitionLayout,
     pub epoch_num: u64,
     pub checkpoint_seq_range: Range<u64>,
 }
@@ -462,12 +574,14 @@ impl FileMetadata {
     fn new(
         file_type: FileType,
         file_format: FileFormat,
+        layout: PartitionLayout,
         epoch_num: u64,
         checkpoint_seq_range: Range<u64>,
     ) -> FileMetadata {
         FileMetadata {
             file_type,
             file_format,
+            layout,
             epoch_num,
             checkpoint_seq_range,
         }
@@ -476,6 +590,7 @@ impl FileMetadata {
     pub fn file_path(&self) -> Path {
         self.file_type.file_path(
             self.file_format,
+            self.layout,
             self.epoch_num,
             self.checkpoint_seq_range.clone(),
         )
@@ -530,6 +645,7 @@ impl Processor {
 pub async fn read_store_for_checkpoint(
     remote_store_config: ObjectStoreConfig,
     file_type: FileType,
+    layout: PartitionLayout,
     dir_prefix: Option<Path>,
 ) -> Result<CheckpointSequenceNumber> {
     let remote_object_store = remote_store_config.make()?;
@@ -542,9 +658,20 @@ pub async fn read_store_for_checkpoint(
     info!("Remote store is empty: {remote_store_is_empty}");
     let file_type_prefix = file_type.dir_prefix();
     let prefix = join_paths(dir_prefix, &file_type_prefix);
-    let epoch_dirs = find_all_dirs_with_epoch_prefix(&remote_object_store, Some(&prefix)).await?;
-    let epoch = epoch_dirs.last_key_value().map(|(k, _v)| *k).unwrap_or(0);
-    let epoch_prefix = prefix.child(format!("epoch_{}", epoch));
+    let epoch = find_all_epochs(&remote_object_store, &prefix, layout.partitioning())
+        .await?
+        .into_iter()
+        .max()
+        .unwrap_or(0);
+    let mut epoch_prefix = prefix.child(layout.partitioning().epoch_dir(epoch));
+    if let Some(partition) =
+        find_all_checkpoint_partitions(&remote_object_store, &epoch_prefix, layout)
+            .await?
+            .into_iter()
+            .max()
+    {
+        epoch_prefix = layout.partition_dir(prefix, epoch, partition);
+    }
     let checkpoints =
         find_all_files_with_epoch_prefix(&remote_object_store, Some(&epoch_prefix)).await?;
     let next_checkpoint_seq_num = checkpoints
@@ -555,6 +682,40 @@ pub async fn read_store_for_checkpoint(
     Ok(next_checkpoint_seq_num)
 }
 
+/// Finds the epochs of all epoch directories in `prefix`.
+pub(crate) async fn find_all_epochs(
+    store: &std::sync::Arc<DynObjectStore>,
+    prefix: &Path,
+    partitioning: Partitioning,
+) -> Result<Vec<EpochId>> {
+    Ok(store
+        .list_with_delimiter(Some(prefix))
+        .await?
+        .common_prefixes
+        .iter()
+        .filter_map(|dir| partitioning.parse_epoch_dir(dir.filename()?))
+        .collect())
+}
+
+/// Finds the first checkpoints of all checkpoint partitions in the epoch
+/// directory `epoch_prefix`.
+pub(crate) async fn find_all_checkpoint_partitions(
+    store: &std::sync::Arc<DynObjectStore>,
+    epoch_prefix: &Path,
+    layout: PartitionLayout,
+) -> Result<Vec<u64>> {
+    if !layout.has_checkpoint_partitions() {
+        return Ok(vec![]);
+    }
+    Ok(store
+        .list_with_delimiter(Some(epoch_prefix))
+        .await?
+        .common_prefixes
+        .iter()
+        .filter_map(|dir| layout.parse_checkpoint_partition_dir(dir.filename()?))
+        .collect())
+}
+
 pub async fn make_max_checkpoint_reader(
     config: &AnalyticsIndexerConfig,
 ) -> Result<Box<dyn MaxCheckpointReader>> {
@@ -640,7 +801,8 @@ pub async fn make_checkpoint_processor(
         config.clone(),
         FileType::Checkpoint,
         starting_checkpoint_seq_num,
-    )?;
+    )
+    .await?;
     let max_checkpoint_reader = make_max_checkpoint_reader(&config).await?;
     Processor::new::<CheckpointEntry>(
         handler,
@@ -664,7 +826,8 @@ pub async fn make_transaction_processor(
         config.clone(),
         FileType::Transaction,
         starting_checkpoint_seq_num,
-    )?;
+    )
+    .await?;
     let max_checkpoint_reader = make_max_checkpoint_reader(&config).await?;
     Processor::new::<TransactionEntry>(
         handler,
@@ -691,7 +854,8 @@ pub async fn make_object_processor(
         config.clone(),
         FileType::Object,
         starting_checkpoint_seq_num,
-    )?;
+    )
+    .await?;
     let max_checkpoint_reader = make_max_checkpoint_reader(&config).await?;
     Processor::new::<ObjectEntry>(
         handler,
@@ -715,7 +879,8 @@ pub async fn make_event_processor(
     let starting_checkpoint_seq_num =
         get_starting_checkpoint_seq_num(config.clone(), FileType::Event).await?;
     let writer =
-        make_writer::<EventEntry>(config.clone(), FileType::Event, starting_checkpoint_seq_num)?;
+        make_writer::<EventEntry>(config.clone(), FileType::Event, starting_checkpoint_seq_num)
+            .await?;
     let max_checkpoint_reader = make_max_checkpoint_reader(&config).await?;
     Processor::new::<EventEntry>(
         handler,
@@ -739,7 +904,8 @@ pub async fn make_transaction_objects_processor(
         config.clone(),
         FileType::TransactionObjects,
         starting_checkpoint_seq_num,
-    )?;
+    )
+    .await?;
     let max_checkpoint_reader = make_max_checkpoint_reader(&config).await?;
     Processor::new::<TransactionObjectEntry>(
         handler,
@@ -763,7 +929,8 @@ pub async fn make_move_package_processor(
         config.clone(),
         FileType::MovePackage,
         starting_checkpoint_seq_num,
-    )?;
+    )
+    .await?;
     let max_checkpoint_reader = make_max_checkpoint_reader(&config).await?;
     Processor::new::<MovePackageEntry>(
         handler,
@@ -787,7 +954,8 @@ pub async fn make_move_call_processor(
         config.clone(),
         FileType::MoveCall,
         starting_checkpoint_seq_num,
-    )?;
+    )
+    .await?;
     let max_checkpoint_reader = make_max_checkpoint_reader(&config).await?;
     Processor::new::<MoveCallEntry>(
         handler,
@@ -814,7 +982,8 @@ pub async fn make_dynamic_field_processor(
         config.clone(),
         FileType::DynamicField,
         starting_checkpoint_seq_num,
-    )?;
+    )
+    .await?;
     let max_checkpoint_reader = make_max_checkpoint_reader(&config).await?;
     Processor::new::<DynamicFieldEntry>(
         handler,
@@ -840,7 +1009,8 @@ pub async fn make_wrapped_object_processor(
         config.clone(),
         FileType::WrappedObject,
         starting_checkpoint_seq_num,
-    )?;
+    )
+    .await?;
     let max_checkpoint_reader = make_max_checkpoint_reader(&config).await?;
     Processor::new::<WrappedObjectEntry>(
         handler,
@@ -853,20 +1023,31 @@ pub async fn make_wrapped_object_processor(
     .await
 }
 
-pub fn make_writer<S: Serialize + ParquetSchema>(
+/// Creates the writer of a table, after registering the current schema of
+/// the table in its [`SchemaRegistry`].
+pub async fn make_writer<S: Serialize + ParquetSchema>(
     config: AnalyticsIndexerConfig,
     file_type: FileType,
     starting_checkpoint_seq_num: u64,
 ) -> Result<Box<dyn AnalyticsWriter<S>>> {
+    let schema_version = SchemaRegistry::register_table_schema::<S>(
+        &config.remote_store_config.make()?,
+        config.remote_store_path_prefix.clone(),
+        file_type,
+    )
+    .await?;
     Ok(match config.file_format {
         FileFormat::CSV => Box::new(CSVWriter::new(
             &config.checkpoint_dir,
             file_type,
+            config.partition_layout()?,
             starting_checkpoint_seq_num,
         )?),
         FileFormat::PARQUET => Box::new(ParquetWriter::new(
             &config.checkpoint_dir,
             file_type,
+            config.partition_layout()?,
+            schema_version,
             starting_checkpoint_seq_num,
         )?),
     })
@@ -882,6 +1063,7 @@ pub async fn get_starting_checkpoint_seq_num(
         read_store_for_checkpoint(
             config.remote_store_config.clone(),
             file_type,
+            config.partition_layout()?,
             config.remote_store_path_prefix,
         )
         .await?
@@ -0,0 +1,154 @@
+// Copyright (c) 2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use anyhow::{Result, bail};
+use bytes::Bytes;
+use iota_storage::object_store::{ObjectStoreGetExt, ObjectStorePutExt};
+use object_store::{DynObjectStore, path::Path};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::{FileType, ParquetSchema, join_paths};
+
+/// Name of the schema registry file in the directory of a table.
+pub const SCHEMA_REGISTRY_FILE: &str = "_schema.json";
+/// Key of the schema version in the key-value metadata of parquet files.
+pub const SCHEMA_VERSION_METADATA_KEY: &str = "iota.schema_version";
+
+/// A version of the schema of a table.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SchemaVersion {
+    pub version: u32,
+    pub columns: Vec<String>,
+}
+
+/// The versions of the schema of a table, stored next to the epoch
+/// directories of the table.
+///
+/// Columns can only be appended to a schema. Files written with an older
+/// version remain readable with the latest schema by treating the appended
+/// columns as null, so existing datasets do not break when a table evolves.
+/// Renaming, removing or reordering columns is rejected.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SchemaRegistry {
+    versions: Vec<SchemaVersion>,
+}
+
+impl SchemaRegistry {
+    pub fn versions(&self) -> &[SchemaVersion] {
+        &self.versions
+    }
+
+    pub fn latest(&self) -> Option<&SchemaVersion> {
+        self.versions.last()
+    }
+
+    pub fn get(&self, version: u32) -> Option<&SchemaVersion> {
+        self.versions
+            .iter()
+            .find(|schema| schema.version == version)
+    }
+
+    /// Returns the version of the schema with the given columns. A new
+    /// version is registered if columns were appended to the latest schema.
+    pub fn register(&mut self, columns: Vec<String>) -> Result<u32> {
+        let Some(latest) = self.latest() else {
+            self.versions.push(SchemaVersion {
+                version: 1,
+                columns,
+            });
+            return Ok(1);
+        };
+        if latest.columns == columns {
+            return Ok(latest.version);
+        }
+        if !columns.starts_with(&latest.columns) {
+            bail!(
+                "Incompatible schema change from version {}: columns can only be appended, \
+                 expected {:?} to start with {:?}",
+                latest.version,
+                columns,
+                latest.columns
+            );
+        }
+        let version = latest.version + 1;
+        self.versions.push(SchemaVersion { version, columns });
+        Ok(version)
+    }
+
+    /// Loads the registry of a table from the store, or an empty registry if
+    /// the table has none yet.
+    pub async fn load(store: &Arc<DynObjectStore>, path: &Path) -> Result<Self> {
+        match store.get_bytes(path).await {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(err) if is_not_found(&err) => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub async fn store(&self, store: &Arc<DynObjectStore>, path: &Path) -> Result<()> {
+        let bytes = serde_json::to_vec_pretty(self)?;
+        store.put_bytes(path, Bytes::from(bytes)).await
+    }
+
+    /// Registers the current schema of a table in its registry in the store
+    /// and returns its version.
+    pub async fn register_table_schema<S: ParquetSchema>(
+        store: &Arc<DynObjectStore>,
+        dir_prefix: Option<Path>,
+        file_type: FileType,
+    ) -> Result<u32> {
+        let path = join_paths(dir_prefix, &file_type.dir_prefix()).child(SCHEMA_REGISTRY_FILE);
+        let mut registry = Self::load(store, &path).await?;
+        let latest_version = registry.latest().map(|schema| schema.version);
+        let version = registry.register(S::schema())?;
+        if latest_version != Some(version) {
+            info!("Registering schema version {version} at {path}");
+            registry.store(store, &path).await?;
+        }
+        Ok(version)
+    }
+}
+
+fn is_not_found(err: &anyhow::Error) -> bool {
+    matches!(
+        err.downcast_ref::<object_store::Error>(),
+        Some(object_store::Error::NotFound { .. })
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn appending_columns_registers_new_version() {
+        let mut registry = SchemaRegistry::default();
+        assert_eq!(registry.register(columns(&["a", "b"])).unwrap(), 1);
+        assert_eq!(registry.register(columns(&["a", "b"])).unwrap(), 1);
+        assert_eq!(registry.register(columns(&["a", "b", "c"])).unwrap(), 2);
+        assert_eq!(registry.get(1).unwrap().columns, columns(&["a", "b"]));
+        assert_eq!(registry.latest().unwrap().version, 2);
+    }
+
+    #[test]
+    fn incompatible_changes_are_rejected() {
+        let mut registry = SchemaRegistry::default();
+        registry.register(columns(&["a", "b"])).unwrap();
+        // Removed column
+        assert!(registry.register(columns(&["a"])).is_err());
+        // Renamed column
+        assert!(registry.register(columns(&["a", "c"])).is_err());
+        // Reordered columns
+        assert!(registry.register(columns(&["b", "a"])).is_err());
+        // Column inserted before an existing one
+        assert!(registry.register(columns(&["a", "c", "b"])).is_err());
+        assert_eq!(registry.versions().len(), 1);
+    }
+}
@@ -17,12 +17,13 @@ use iota_storage::object_store::util::path_to_filesystem;
 use iota_types::base_types::EpochId;
 use serde::Serialize;
 
-use crate::{FileFormat, FileType, ParquetSchema, writers::AnalyticsWriter};
+use crate::{FileFormat, FileType, ParquetSchema, PartitionLayout, writers::AnalyticsWriter};
 
 // Save table entries to csv files.
 pub(crate) struct CSVWriter {
     root_dir_path: PathBuf,
     file_type: FileType,
+    layout: PartitionLayout,
     writer: Writer<File>,
     epoch: EpochId,
     checkpoint_range: Range<u64>,
@@ -32,18 +33,21 @@ impl CSVWriter {
     pub(crate) fn new(
         root_dir_path: &Path,
         file_type: FileType,
+        layout: PartitionLayout,
         start_checkpoint_seq_num: u64,
     ) -> Result<Self> {
         let checkpoint_range = start_checkpoint_seq_num..u64::MAX;
         let writer = Self::make_writer(
             root_dir_path.to_path_buf(),
             file_type,
+            layout,
             0,
             checkpoint_range.clone(),
         )?;
         Ok(CSVWriter {
             root_dir_path: root_dir_path.to_path_buf(),
             file_type,
+            layout,
             writer,
             epoch: 0,
             checkpoint_range,
@@ -53,12 +57,13 @@ impl CSVWriter {
     fn make_writer(
         root_dir_path: PathBuf,
         file_type: FileType,
+        layout: PartitionLayout,
         epoch_num: EpochId,
         checkpoint_range: Range<u64>,
     ) -> Result<Writer<File>> {
         let file_path = path_to_filesystem(
             root_dir_path,
-            &file_type.file_path(FileFormat::CSV, epoch_num, checkpoint_range),
+            &file_type.file_path(FileFormat::CSV, layout, epoch_num, checkpoint_range),
         )?;
         create_dir_all(file_path.parent().ok_or(anyhow!("Bad directory path"))?)?;
         if file_path.exists() {
@@ -74,7 +79,9 @@ impl CSVWriter {
     fn file_path(&self, epoch: EpochId, range: Range<u64>) -> Result<PathBuf> {
         path_to_filesystem(
             self.root_dir_path.clone(),
-            &self.file_type.file_path(FileFormat::CSV, epoch, range),
+            &self
+                .file_type
+                .file_path(FileFormat::CSV, self.layout, epoch, range),
         )
     }
 }
@@ -109,6 +116,7 @@ impl<S: Serialize + ParquetSchema> AnalyticsWriter<S> for CSVWriter {
         self.writer = CSVWriter::make_writer(
             self.root_dir_path.clone(),
             self.file_type,
+            self.layout,
             self.epoch,
             self.checkpoint_range.clone(),
         )?;
@@ -13,15 +13,26 @@ use anyhow::{Result, anyhow};
 use arrow_array::{ArrayRef, BooleanArray, Int64Array, RecordBatch, StringArray, UInt64Array};
 use iota_storage::object_store::util::path_to_filesystem;
 use iota_types::base_types::EpochId;
-use parquet::{arrow::ArrowWriter, basic::Compression, file::properties::WriterProperties};
+use parquet::{
+    arrow::ArrowWriter,
+    basic::Compression,
+    file::{metadata::KeyValue, properties::WriterProperties},
+};
 use serde::Serialize;
 
-use crate::{AnalyticsWriter, FileFormat, FileType, ParquetSchema, ParquetValue};
+use crate::{
+    AnalyticsWriter, FileFormat, FileType, ParquetSchema, ParquetValue, PartitionLayout,
+    schema_registry::SCHEMA_VERSION_METADATA_KEY,
+};
 
 // Save table entries to parquet files.
 pub(crate) struct ParquetWriter {
     root_dir_path: PathBuf,
     file_type: FileType,
+    layout: PartitionLayout,
+    /// Version of the table schema in the schema registry, stored in the
+    /// metadata of every file.
+    schema_version: u32,
     epoch: EpochId,
     checkpoint_range: Range<u64>,
     data: Vec<Vec<ParquetValue>>,
@@ -31,12 +42,16 @@ impl ParquetWriter {
     pub(crate) fn new(
         root_dir_path: &Path,
         file_type: FileType,
+        layout: PartitionLayout,
+        schema_version: u32,
         start_checkpoint_seq_num: u64,
     ) -> Result<Self> {
         let checkpoint_range = start_checkpoint_seq_num..u64::MAX;
         Ok(Self {
             root_dir_path: root_dir_path.to_path_buf(),
             file_type,
+            layout,
+            schema_version,
             epoch: 0,
             checkpoint_range,
             data: vec![],
@@ -48,6 +63,7 @@ impl ParquetWriter {
             self.root_dir_path.clone(),
             &self.file_type.file_path(
                 FileFormat::PARQUET,
+                self.layout,
                 self.epoch,
                 self.checkpoint_range.clone(),
             ),
@@ -113,6 +129,10 @@ impl<S: Serialize + ParquetSchema> AnalyticsWriter<S> for ParquetWriter {
 
         let properties = WriterProperties::builder()
             .set_compression(Compression::SNAPPY)
+            .set_key_value_metadata(Some(vec![KeyValue::new(
+                SCHEMA_VERSION_METADATA_KEY.to_string(),
+                self.schema_version.to_string(),
+            )]))
             .build();
 
         let mut writer = ArrowWriter::try_new(self.file()?, batch.schema(), Some(properties))?;
@@ -5,7 +5,7 @@
 use std::{
     collections::{BTreeMap, BTreeSet},
     net::SocketAddr,
-    num::NonZeroUsize,
+    num::{NonZeroU64, NonZeroUsize},
     path::{Path, PathBuf},
     sync::Arc,
     time::Duration,
@@ -244,6 +244,23 @@ pub struct NodeConfig {
 
     #[serde(default = "bool_true")]
     pub enable_validator_tx_finalizer: bool,
+
+    /// Configuration of a remote signer process that holds the authority and
+    /// protocol keys and signs on behalf of the validator. If set,
+    /// `authority_key_pair` must not be, the node only knows the public key
+    /// from [`RemoteSignerConfig::authority_public_key`]. If not set, the
+    /// validator signs with its local key pairs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_signer: Option<RemoteSignerConfig>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct RemoteSignerConfig {
+    /// Path of the Unix socket the signer process listens on.
+    pub socket_path: PathBuf,
+    /// Public key of the authority key held by the signer process.
+    pub authority_public_key: AuthorityPublicKeyBytes,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, Default)]
@@ -393,7 +410,31 @@ impl NodeConfig {
     }
 
     pub fn authority_public_key(&self) -> AuthorityPublicKeyBytes {
-        self.authority_key_pair().public().into()
+        match &self.remote_signer {
+            Some(remote_signer) => remote_signer.authority_public_key,
+            None => self.authority_key_pair().public().into(),
+        }
+    }
+
+    /// Fails if a remote signer is set, but `authority_key_pair` still holds
+    /// or points to the authority private key.
+    pub fn check_remote_signer(&self) -> Result<()> {
+        let Some(remote_signer) = &self.remote_signer else {
+            return Ok(());
+        };
+        if let Some(path) = self.authority_key_pair.path() {
+            anyhow::bail!(
+                "authority-key-pair must not be set when using a remote signer, but points to {path:?}"
+            );
+        }
+        let authority_public_key: AuthorityPublicKeyBytes =
+            self.authority_key_pair.authority_keypair().public().into();
+        if authority_public_key == remote_signer.authority_public_key {
+            anyhow::bail!(
+                "authority-key-pair must not be set when using a remote signer, but holds the private key"
+            );
+        }
+        Ok(())
     }
 
     pub fn db_path(&self) -> PathBuf {
@@ -404,6 +445,12 @@ impl NodeConfig {
         self.db_path.join("db_checkpoints")
     }
 
+    /// Path of the deny list entries added and removed at runtime through the
+    /// admin server.
+    pub fn transaction_deny_overrides_path(&self) -> PathBuf {
+        self.db_path.join("transaction_deny_overrides.yaml")
+    }
+
     pub fn archive_path(&self) -> PathBuf {
         self.db_path.join("archive")
     }
@@ -794,6 +841,11 @@ pub struct StateSnapshotConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub object_store_config: Option<ObjectStoreConfig>,
     pub concurrency: usize,
+    /// Number of epochs between full state snapshots. The snapshots of the
+    /// epochs in between are written as delta snapshots relative to the last
+    /// full snapshot. If not set, every snapshot is a full snapshot.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub full_snapshot_interval_epochs: Option<NonZeroU64>,
 }
 
 #[derive(Default, Debug, Clone, Deserialize, Serialize)]
@@ -1104,6 +1156,14 @@ impl AuthorityKeyPairWithPath {
         }
     }
 
+    /// Returns the path of the key pair file, if the key pair is read from one.
+    pub fn path(&self) -> Option<&Path> {
+        match &self.location {
+            AuthorityKeyPairLocation::InPlace { .. } => None,
+            AuthorityKeyPairLocation::File { path } => Some(path),
+        }
+    }
+
     pub fn authority_keypair(&self) -> &AuthorityKeyPair {
         self.keypair
             .get_or_init(|| match &self.location {
@@ -1142,7 +1202,7 @@ mod tests {
     };
     use rand::{SeedableRng, rngs::StdRng};
 
-    use super::Genesis;
+    use super::{AuthorityKeyPairWithPath, Genesis, RemoteSignerConfig};
     use crate::NodeConfig;
 
     #[test]
@@ -1162,6 +1222,27 @@ mod tests {
         let _template: NodeConfig = serde_yaml::from_str(TEMPLATE).unwrap();
     }
 
+    #[test]
+    fn remote_signer_without_authority_key_pair() {
+        const TEMPLATE: &str = include_str!("../data/fullnode-template.yaml");
+        let mut config: NodeConfig = serde_yaml::from_str(TEMPLATE).unwrap();
+        let authority_key_pair: AuthorityKeyPair =
+            get_key_pair_from_rng(&mut StdRng::from_seed([0; 32])).1;
+        config.remote_signer = Some(RemoteSignerConfig {
+            socket_path: PathBuf::from("signer.sock"),
+            authority_public_key: authority_key_pair.public().into(),
+        });
+
+        config.check_remote_signer().unwrap();
+        assert_eq!(
+            config.authority_public_key(),
+            authority_key_pair.public().into()
+        );
+
+        config.authority_key_pair = AuthorityKeyPairWithPath::new(authority_key_pair);
+        assert!(config.check_remote_signer().is_err());
+    }
+
     #[test]
     fn load_key_pairs_to_node_config() {
         let authority_key_pair: AuthorityKeyPair =
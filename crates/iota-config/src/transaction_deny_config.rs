@@ -2,12 +2,17 @@
 // Modifications Copyright (c) 2024 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::HashSet;
+use std::{
+    collections::{BTreeSet, HashSet},
+    path::Path,
+};
 
 use iota_types::base_types::{IotaAddress, ObjectID};
 use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
 
+use crate::Config;
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct TransactionDenyConfig {
@@ -22,17 +27,19 @@ pub struct TransactionDenyConfig {
     /// A list of package object IDs that are not allowed to be called into in
     /// transactions, either directly or indirectly through transitive
     /// dependencies. Note that this does not apply to type arguments.
-    /// Also since we only compare the deny list against the upgraded package ID
-    /// of each dependency in the used package, when a package ID is denied,
-    /// newer versions of that package are still allowed. If we want to deny
-    /// the entire upgrade family of a package, we need to explicitly
-    /// specify all the package IDs in the deny list. TODO: We could
-    /// consider making this more flexible, e.g. whether to check in type args,
-    /// whether to block entire upgrade family, whether to allow upgrade and
-    /// etc.
+    /// Also unless `package_deny_upgrade_family` is set, we only compare the
+    /// deny list against the upgraded package ID of each dependency in the used
+    /// package, so when a package ID is denied, newer versions of that package
+    /// are still allowed. TODO: We could consider making this more flexible,
+    /// e.g. whether to check in type args, whether to allow upgrade and etc.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     package_deny_list: Vec<ObjectID>,
 
+    /// Whether denying a package ID denies all the packages of its upgrade
+    /// family, i.e. all the versions sharing its original package ID.
+    #[serde(default)]
+    package_deny_upgrade_family: bool,
+
     /// A list of iota addresses that are not allowed to be used as the sender
     /// or sponsor.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
@@ -66,6 +73,11 @@ pub struct TransactionDenyConfig {
     #[serde(skip)]
     address_deny_set: OnceCell<HashSet<IotaAddress>>,
 
+    /// Original package IDs of the denied packages, resolved by the node
+    /// whenever the package deny list changes.
+    #[serde(skip)]
+    package_deny_family_set: HashSet<ObjectID>,
+
     /// Whether receiving objects transferred to other objects is allowed
     #[serde(default)]
     receiving_objects_disabled: bool,
@@ -98,6 +110,20 @@ impl TransactionDenyConfig {
             .get_or_init(|| self.address_deny_list.iter().cloned().collect())
     }
 
+    pub fn package_deny_upgrade_family(&self) -> bool {
+        self.package_deny_upgrade_family
+    }
+
+    /// Returns the original package IDs of the upgrade families of the denied
+    /// packages, as set by [`Self::set_package_deny_families`].
+    pub fn package_deny_family_set(&self) -> &HashSet<ObjectID> {
+        &self.package_deny_family_set
+    }
+
+    pub fn set_package_deny_families(&mut self, families: HashSet<ObjectID>) {
+        self.package_deny_family_set = families;
+    }
+
     pub fn package_publish_disabled(&self) -> bool {
         self.package_publish_disabled
     }
@@ -125,6 +151,127 @@ impl TransactionDenyConfig {
     pub fn zklogin_disabled_providers(&self) -> &HashSet<String> {
         &self.zklogin_disabled_providers
     }
+
+    /// Returns the entries of the object, package and address deny lists.
+    pub fn deny_list_entries(&self) -> Vec<DenyListEntry> {
+        self.object_deny_list
+            .iter()
+            .copied()
+            .map(DenyListEntry::Object)
+            .chain(
+                self.package_deny_list
+                    .iter()
+                    .copied()
+                    .map(DenyListEntry::Package),
+            )
+            .chain(
+                self.address_deny_list
+                    .iter()
+                    .copied()
+                    .map(DenyListEntry::Address),
+            )
+            .collect()
+    }
+
+    /// Adds an entry to the deny lists. Returns false if it was already denied.
+    pub fn add_deny_list_entry(&mut self, entry: DenyListEntry) -> bool {
+        if self.deny_list_entries().contains(&entry) {
+            return false;
+        }
+        match entry {
+            DenyListEntry::Object(id) => self.object_deny_list.push(id),
+            DenyListEntry::Package(id) => self.package_deny_list.push(id),
+            DenyListEntry::Address(address) => self.address_deny_list.push(address),
+        }
+        self.reset_deny_sets();
+        true
+    }
+
+    /// Removes an entry from the deny lists. Returns false if it was not
+    /// denied.
+    pub fn remove_deny_list_entry(&mut self, entry: &DenyListEntry) -> bool {
+        let removed = match entry {
+            DenyListEntry::Object(id) => remove_from_list(&mut self.object_deny_list, id),
+            DenyListEntry::Package(id) => remove_from_list(&mut self.package_deny_list, id),
+            DenyListEntry::Address(address) => {
+                remove_from_list(&mut self.address_deny_list, address)
+            }
+        };
+        self.reset_deny_sets();
+        removed
+    }
+
+    fn reset_deny_sets(&mut self) {
+        self.object_deny_set = OnceCell::new();
+        self.package_deny_set = OnceCell::new();
+        self.address_deny_set = OnceCell::new();
+    }
+}
+
+fn remove_from_list<T: PartialEq>(list: &mut Vec<T>, item: &T) -> bool {
+    let len = list.len();
+    list.retain(|i| i != item);
+    list.len() != len
+}
+
+/// An entry of the object, package or address deny lists of a
+/// [`TransactionDenyConfig`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DenyListEntry {
+    Object(ObjectID),
+    Package(ObjectID),
+    Address(IotaAddress),
+}
+
+/// Deny list entries added and removed at runtime, e.g. through the admin
+/// server of a node, on top of the deny lists of the node config. They are
+/// persisted so that they survive restarts.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DenyListOverrides {
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    denied: BTreeSet<DenyListEntry>,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    allowed: BTreeSet<DenyListEntry>,
+}
+
+impl Config for DenyListOverrides {}
+
+impl DenyListOverrides {
+    /// Saves the overrides to `path` by writing a temporary file and renaming
+    /// it, so that a failed write never leaves a truncated file behind.
+    pub fn save_atomically(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp_path = path.with_extension("yaml.tmp");
+        let mut file = std::fs::File::create(&tmp_path)?;
+        serde_yaml::to_writer(&mut file, self)?;
+        file.sync_all()?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    pub fn deny(&mut self, entry: DenyListEntry) {
+        self.allowed.remove(&entry);
+        self.denied.insert(entry);
+    }
+
+    pub fn allow(&mut self, entry: DenyListEntry) {
+        self.denied.remove(&entry);
+        self.allowed.insert(entry);
+    }
+
+    /// Applies the overrides to the deny lists of `config`.
+    pub fn apply(&self, config: &mut TransactionDenyConfig) {
+        for entry in &self.allowed {
+            config.remove_deny_list_entry(entry);
+        }
+        for entry in &self.denied {
+            config.add_deny_list_entry(*entry);
+        }
+    }
 }
 
 #[derive(Default)]
@@ -181,6 +328,11 @@ impl TransactionDenyConfigBuilder {
         self
     }
 
+    pub fn deny_package_upgrade_family(mut self) -> Self {
+        self.config.package_deny_upgrade_family = true;
+        self
+    }
+
     pub fn disable_zklogin_sig(mut self) -> Self {
         self.config.zklogin_sig_disabled = true;
         self
@@ -26,12 +26,13 @@ use fastcrypto::{
 };
 use iota_archival::reader::ArchiveReaderBalancer;
 use iota_config::{
-    NodeConfig,
+    Config, NodeConfig,
     genesis::Genesis,
     node::{
         AuthorityOverloadConfig, DBCheckpointConfig, ExpensiveSafetyCheckConfig,
         StateDebugDumpConfig,
     },
+    transaction_deny_config::{DenyListEntry, DenyListOverrides, TransactionDenyConfig},
 };
 use iota_framework::{BuiltInFramework, SystemPackage};
 use iota_json_rpc_types::{
@@ -56,7 +57,9 @@ use iota_types::{
     authenticator_state::get_authenticator_state,
     base_types::*,
     committee::{Committee, EpochId, ProtocolVersion},
-    crypto::{AuthoritySignInfo, AuthoritySignature, RandomnessRound, Signer, default_hash},
+    crypto::{
+        AuthoritySignInfo, AuthoritySignature, AuthoritySigner, RandomnessRound, default_hash,
+    },
     deny_list_v1::check_coin_deny_list_v1_during_signing,
     digests::{ChainIdentifier, TransactionEventsDigest},
     dynamic_field::{DynamicFieldInfo, DynamicFieldName, DynamicFieldType},
@@ -744,13 +747,14 @@ impl AuthorityMetrics {
     }
 }
 
-/// a Trait object for `Signer` that is:
+/// a Trait object for `AuthoritySigner` that is:
 /// - Pin, i.e. confined to one place in memory (we don't want to copy private
 ///   keys).
 /// - Sync, i.e. can be safely shared between threads.
 ///
-/// Typically instantiated with Box::pin(keypair) where keypair is a `KeyPair`
-pub type StableSyncAuthoritySigner = Pin<Arc<dyn Signer<AuthoritySignature> + Send + Sync>>;
+/// Typically instantiated with Box::pin(keypair) where keypair is a `KeyPair`,
+/// or with a remote signer holding the key outside of the node.
+pub type StableSyncAuthoritySigner = Pin<Arc<dyn AuthoritySigner>>;
 
 pub struct AuthorityState {
     // Fixed size, static, identity of the authority
@@ -796,6 +800,14 @@ pub struct AuthorityState {
 
     pub config: NodeConfig,
 
+    /// The transaction deny config of the node config with the deny list
+    /// overrides applied. Swapped whenever the overrides are updated.
+    transaction_deny_config: ArcSwap<TransactionDenyConfig>,
+
+    /// Deny list entries added and removed at runtime, persisted next to the
+    /// node database.
+    deny_list_overrides: Mutex<DenyListOverrides>,
+
     /// Current overload status in this authority. Updated periodically.
     pub overload_info: AuthorityOverloadInfo,
 
@@ -811,6 +823,54 @@ pub struct AuthorityState {
 ///
 /// Repeating valid commands should produce no changes and return no error.
 impl AuthorityState {
+    /// Returns the transaction deny config currently in effect.
+    pub fn transaction_deny_config(&self) -> Arc<TransactionDenyConfig> {
+        self.transaction_deny_config.load_full()
+    }
+
+    /// Denies or allows a deny list entry at runtime. The change is persisted
+    /// so that it survives restarts of the node. Returns false if the entry was
+    /// already in the requested state.
+    pub fn update_transaction_deny_list(
+        &self,
+        entry: DenyListEntry,
+        denied: bool,
+    ) -> anyhow::Result<bool> {
+        let mut overrides = self.deny_list_overrides.lock();
+        let mut updated_overrides = overrides.clone();
+        let mut transaction_deny_config = (*self.transaction_deny_config.load_full()).clone();
+        let changed = if denied {
+            updated_overrides.deny(entry);
+            transaction_deny_config.add_deny_list_entry(entry)
+        } else {
+            updated_overrides.allow(entry);
+            transaction_deny_config.remove_deny_list_entry(&entry)
+        };
+        iota_transaction_checks::deny::resolve_package_deny_families(
+            &mut transaction_deny_config,
+            self.get_backing_package_store().as_ref(),
+        );
+        // Overrides that can't be loaded would be lost for good if overwritten,
+        // so they have to be fixed or removed by hand first.
+        let overrides_path = self.config.transaction_deny_overrides_path();
+        if overrides_path.exists() {
+            DenyListOverrides::load(&overrides_path).map_err(|err| {
+                anyhow!(
+                    "Refusing to update the transaction deny list, the overrides in {} could not be loaded: {err:?}",
+                    overrides_path.display()
+                )
+            })?;
+        }
+        // Only swap the state in memory once the overrides are persisted, so that
+        // a failed update is not silently lost on restart.
+        updated_overrides.save_atomically(&overrides_path)?;
+        *overrides = updated_overrides;
+        self.transaction_deny_config
+            .store(Arc::new(transaction_deny_config));
+        info!(?entry, denied, "Updated transaction deny list");
+        Ok(changed)
+    }
+
     pub fn is_validator(&self, epoch_store: &AuthorityPerEpochStore) -> bool {
         epoch_store.committee().authority_exists(&self.name)
     }
@@ -861,7 +921,7 @@ impl AuthorityState {
             transaction.tx_signatures(),
             &input_object_kinds,
             &receiving_objects_refs,
-            &self.config.transaction_deny_config,
+            &self.transaction_deny_config.load(),
             self.get_backing_package_store().as_ref(),
         )?;
 
@@ -891,12 +951,13 @@ impl AuthorityState {
 
         let owned_objects = checked_input_objects.inner().filter_owned_objects();
 
-        let signed_transaction = VerifiedSignedTransaction::new(
+        let signed_transaction = VerifiedSignedTransaction::try_new(
             epoch_store.epoch(),
             transaction,
             self.name,
             &*self.secret,
-        );
+        )
+        .await?;
 
         // Check and write locks, to signed transaction, into the database
         // The call to self.set_transaction_lock checks the lock is not conflicting,
@@ -920,7 +981,10 @@ impl AuthorityState {
         debug!("handle_transaction");
 
         // Ensure an idempotent answer.
-        if let Some((_, status)) = self.get_transaction_status(&tx_digest, epoch_store)? {
+        if let Some((_, status)) = self
+            .get_transaction_status(&tx_digest, epoch_store)
+            .await?
+        {
             return Ok(HandleTransactionResponse { status });
         }
 
@@ -963,7 +1027,8 @@ impl AuthorityState {
             // In that case, we could still return Ok to avoid showing confusing errors.
             Err(err) => Ok(HandleTransactionResponse {
                 status: self
-                    .get_transaction_status(&tx_digest, epoch_store)?
+                    .get_transaction_status(&tx_digest, epoch_store)
+                    .await?
                     .ok_or(err)?
                     .1,
             }),
@@ -1220,7 +1285,7 @@ impl AuthorityState {
                 &epoch_store,
             )
             .await?;
-        let signed_effects = self.sign_effects(effects, &epoch_store)?;
+        let signed_effects = self.sign_effects(effects, &epoch_store).await?;
         Ok((signed_effects, execution_error_opt))
     }
 
@@ -1713,7 +1778,7 @@ impl AuthorityState {
             &[],
             &input_object_kinds,
             &receiving_object_refs,
-            &self.config.transaction_deny_config,
+            &self.transaction_deny_config.load(),
             self.get_backing_package_store().as_ref(),
         )?;
 
@@ -1927,7 +1992,7 @@ impl AuthorityState {
             &[],
             &input_object_kinds,
             &receiving_object_refs,
-            &self.config.transaction_deny_config,
+            &self.transaction_deny_config.load(),
             self.get_backing_package_store().as_ref(),
         )?;
 
@@ -2479,7 +2544,8 @@ impl AuthorityState {
     ) -> IotaResult<TransactionInfoResponse> {
         let epoch_store = self.load_epoch_store_one_call_per_task();
         let (transaction, status) = self
-            .get_transaction_status(&request.transaction_digest, &epoch_store)?
+            .get_transaction_status(&request.transaction_digest, &epoch_store)
+            .await?
             .ok_or(IotaError::TransactionNotFound {
                 digest: request.transaction_digest,
             })?;
@@ -2662,6 +2728,26 @@ impl AuthorityState {
         );
         let input_loader =
             TransactionInputLoader::new(execution_cache_trait_pointers.object_cache_reader.clone());
+        let deny_list_overrides_path = config.transaction_deny_overrides_path();
+        let deny_list_overrides = if deny_list_overrides_path.exists() {
+            DenyListOverrides::load(&deny_list_overrides_path).unwrap_or_else(|err| {
+                error!(
+                    "Failed to load transaction deny list overrides from {}, ignoring them and refusing deny list updates until the file is fixed or removed: {err:?}",
+                    deny_list_overrides_path.display()
+                );
+                DenyListOverrides::default()
+            })
+        } else {
+            DenyListOverrides::default()
+        };
+        let mut transaction_deny_config = config.transaction_deny_config.clone();
+        deny_list_overrides.apply(&mut transaction_deny_config);
+        iota_transaction_checks::deny::resolve_package_deny_families(
+            &mut transaction_deny_config,
+            execution_cache_trait_pointers
+                .backing_package_store
+                .as_ref(),
+        );
         let epoch = epoch_store.epoch();
         let state = Arc::new(AuthorityState {
             name,
@@ -2682,6 +2768,8 @@ impl AuthorityState {
             _authority_per_epoch_pruner,
             db_checkpoint_config: db_checkpoint_config.clone(),
             config,
+            transaction_deny_config: ArcSwap::from_pointee(transaction_deny_config),
+            deny_list_overrides: Mutex::new(deny_list_overrides),
             overload_info: AuthorityOverloadInfo::default(),
             validator_tx_finalizer,
         });
@@ -3915,14 +4003,15 @@ impl AuthorityState {
 
     /// Make a status response for a transaction
     #[instrument(level = "trace", skip_all)]
-    pub fn get_transaction_status(
+    pub async fn get_transaction_status(
         &self,
         transaction_digest: &TransactionDigest,
         epoch_store: &Arc<AuthorityPerEpochStore>,
     ) -> IotaResult<Option<(SenderSignedData, TransactionStatus)>> {
         // TODO: In the case of read path, we should not have to re-sign the effects.
-        if let Some(effects) =
-            self.get_signed_effects_and_maybe_resign(transaction_digest, epoch_store)?
+        if let Some(effects) = self
+            .get_signed_effects_and_maybe_resign(transaction_digest, epoch_store)
+            .await?
         {
             if let Some(transaction) = self
                 .get_transaction_cache_reader()
@@ -3959,7 +4048,7 @@ impl AuthorityState {
     /// signed in a previous epoch, re-sign it so that the caller is able to
     /// form a cert of the effects in the current epoch.
     #[instrument(level = "trace", skip_all)]
-    pub fn get_signed_effects_and_maybe_resign(
+    pub async fn get_signed_effects_and_maybe_resign(
         &self,
         transaction_digest: &TransactionDigest,
         epoch_store: &Arc<AuthorityPerEpochStore>,
@@ -3968,13 +4057,13 @@ impl AuthorityState {
             .get_transaction_cache_reader()
             .get_executed_effects(transaction_digest)?;
         match effects {
-            Some(effects) => Ok(Some(self.sign_effects(effects, epoch_store)?)),
+            Some(effects) => Ok(Some(self.sign_effects(effects, epoch_store).await?)),
             None => Ok(None),
         }
     }
 
     #[instrument(level = "trace", skip_all)]
-    pub(crate) fn sign_effects(
+    pub(crate) async fn sign_effects(
         &self,
         effects: TransactionEffects,
         epoch_store: &Arc<AuthorityPerEpochStore>,
@@ -4013,13 +4102,14 @@ impl AuthorityState {
                     "Re-signing the effects with the current epoch"
                 );
 
-                let sig = AuthoritySignInfo::new(
+                let sig = AuthoritySignInfo::try_new(
                     epoch_store.epoch(),
                     &effects,
                     Intent::iota_app(IntentScope::TransactionEffects),
                     self.name,
                     &*self.secret,
-                );
+                )
+                .await?;
 
                 let effects = SignedTransactionEffects::new_from_data_and_sig(effects, sig.clone());
 
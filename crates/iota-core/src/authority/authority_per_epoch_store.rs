@@ -144,6 +144,9 @@ type JwkAggregator = GenericMultiStakeAggregator<(JwkId, JWK), true>;
 pub enum CancelConsensusCertificateReason {
     CongestionOnObjects(Vec<ObjectID>),
     DkgFailed,
+    /// The gas price of the transaction doesn't cover the congestion surcharge
+    /// of the congested objects.
+    CongestionOnObjectsWithSurcharge(Vec<ObjectID>, u64),
 }
 
 pub enum ConsensusCertificateResult {
@@ -572,6 +575,10 @@ pub struct AuthorityEpochTables {
     /// Transactions that are being deferred until some future time
     deferred_transactions: DBMap<DeferralKey, Vec<VerifiedSequencedConsensusTransaction>>,
 
+    /// The decayed cost by which shared objects exceeded the per commit limit
+    /// in recent commits. Only used with congestion surcharge pricing.
+    congestion_history: DBMap<ObjectID, u64>,
+
     /// Tables for recording state for RandomnessManager.
 
     /// Records messages processed from other nodes. Updated when receiving a
@@ -1697,6 +1704,26 @@ impl AuthorityPerEpochStore {
             .max_accumulated_txn_cost_per_object_in_mysticeti_commit_as_option()
     }
 
+    // Returns the congestion surcharge rate if congested objects are priced
+    // instead of deferring transactions. Without a rate, transactions are still
+    // deferred even if congestion surcharge pricing is enabled.
+    fn get_congestion_surcharge_bps(&self) -> Option<u64> {
+        let protocol_config = self.protocol_config();
+        if protocol_config.congestion_control_surcharge_pricing() {
+            protocol_config.congestion_surcharge_bps_as_option()
+        } else {
+            None
+        }
+    }
+
+    fn load_congestion_history(&self) -> IotaResult<HashMap<ObjectID, u64>> {
+        Ok(self
+            .tables()?
+            .congestion_history
+            .safe_iter()
+            .collect::<Result<_, _>>()?)
+    }
+
     fn should_defer(
         &self,
         cert: &VerifiedExecutableTransaction,
@@ -1723,6 +1750,29 @@ impl AuthorityPerEpochStore {
         if let Some(max_accumulated_txn_cost_per_object_in_commit) =
             self.get_max_accumulated_txn_cost_per_object_in_commit()
         {
+            if let Some(surcharge_bps) = self.get_congestion_surcharge_bps() {
+                // Price the congested objects instead of deferring the transaction. The
+                // transaction is scheduled if its gas price covers the surcharge, which
+                // it then pays as part of its gas price, and cancelled otherwise.
+                let reference_gas_price = self.reference_gas_price();
+                let (surcharge, congested_objects) = shared_object_congestion_tracker
+                    .compute_congestion_surcharge(
+                        cert,
+                        max_accumulated_txn_cost_per_object_in_commit,
+                        reference_gas_price,
+                        surcharge_bps,
+                    )?;
+                if cert.transaction_data().gas_price()
+                    >= reference_gas_price.saturating_add(surcharge)
+                {
+                    return None;
+                }
+                return Some((
+                    DeferralKey::new_for_consensus_round(commit_round + 1, commit_round),
+                    DeferralReason::SharedObjectCongestionSurcharge(congested_objects, surcharge),
+                ));
+            }
+
             // Defer transaction if it uses shared objects that are congested.
             if let Some((deferral_key, congested_objects)) = shared_object_congestion_tracker
                 .should_defer_due_to_object_congestion(
@@ -2759,7 +2809,8 @@ impl AuthorityPerEpochStore {
         for txn in transactions.iter() {
             match cancelled_txns.get(txn.digest()) {
                 Some(CancelConsensusCertificateReason::CongestionOnObjects(_))
-                | Some(CancelConsensusCertificateReason::DkgFailed) => {
+                | Some(CancelConsensusCertificateReason::DkgFailed)
+                | Some(CancelConsensusCertificateReason::CongestionOnObjectsWithSurcharge(..)) => {
                     let assigned_versions = SharedObjVerManager::assign_versions_for_certificate(
                         txn,
                         &mut shared_input_next_version,
@@ -2945,16 +2996,25 @@ impl AuthorityPerEpochStore {
         let mut cancelled_txns: BTreeMap<TransactionDigest, CancelConsensusCertificateReason> =
             BTreeMap::new();
 
+        // The congestion history is only tracked to price congested objects.
+        let congestion_history = if self.get_congestion_surcharge_bps().is_some() {
+            Some(self.load_congestion_history()?)
+        } else {
+            None
+        };
+
         // We track transaction execution cost separately for regular transactions and
         // transactions using randomness, since they will be in different
         // checkpoints.
         let mut shared_object_congestion_tracker = SharedObjectCongestionTracker::new(
             self.protocol_config().per_object_congestion_control_mode(),
-        );
+        )
+        .with_congestion_history(congestion_history.clone().unwrap_or_default());
         let mut shared_object_using_randomness_congestion_tracker =
             SharedObjectCongestionTracker::new(
                 self.protocol_config().per_object_congestion_control_mode(),
-            );
+            )
+            .with_congestion_history(congestion_history.clone().unwrap_or_default());
 
         fail_point_arg!(
             "initial_congestion_tracker",
@@ -3066,6 +3126,27 @@ impl AuthorityPerEpochStore {
             .with_label_values(&["randomness_commit"])
             .set(shared_object_using_randomness_congestion_tracker.max_cost() as i64);
 
+        if let (Some(congestion_history), Some(max_accumulated_txn_cost_per_object_in_commit)) = (
+            congestion_history,
+            self.get_max_accumulated_txn_cost_per_object_in_commit(),
+        ) {
+            let next_congestion_history = SharedObjectCongestionTracker::next_congestion_history(
+                &congestion_history,
+                &[
+                    &shared_object_congestion_tracker,
+                    &shared_object_using_randomness_congestion_tracker,
+                ],
+                max_accumulated_txn_cost_per_object_in_commit,
+            );
+            output.set_congestion_history(
+                congestion_history
+                    .into_keys()
+                    .filter(|id| !next_congestion_history.contains_key(id))
+                    .collect(),
+                next_congestion_history,
+            );
+        }
+
         if randomness_state_updated {
             if let Some(randomness_manager) = randomness_manager.as_mut() {
                 randomness_manager
@@ -3341,6 +3422,27 @@ impl AuthorityPerEpochStore {
                                 ))
                             }
                         }
+                        DeferralReason::SharedObjectCongestionSurcharge(
+                            congested_objects,
+                            surcharge,
+                        ) => {
+                            authority_metrics
+                                .consensus_handler_congested_transactions
+                                .inc();
+                            debug!(
+                                "Cancelling consensus certificate for transaction {:?} not covering congestion surcharge {} on objects {:?}",
+                                certificate.digest(),
+                                surcharge,
+                                congested_objects
+                            );
+                            ConsensusCertificateResult::Cancelled((
+                                certificate,
+                                CancelConsensusCertificateReason::CongestionOnObjectsWithSurcharge(
+                                    congested_objects,
+                                    surcharge,
+                                ),
+                            ))
+                        }
                     };
                     return Ok(deferral_result);
                 }
@@ -3826,6 +3928,9 @@ pub(crate) struct ConsensusCommitOutput {
     deferred_txns: Vec<(DeferralKey, Vec<VerifiedSequencedConsensusTransaction>)>,
     // deferred txns that have been loaded and can be removed
     deleted_deferred_txns: BTreeSet<DeferralKey>,
+    // congestion history to carry over to the next commit, and objects whose
+    // history decayed away
+    congestion_history: Option<(Vec<ObjectID>, HashMap<ObjectID, u64>)>,
 
     // checkpoint state
     user_signatures_for_checkpoints: Vec<(TransactionDigest, Vec<GenericSignature>)>,
@@ -3903,6 +4008,15 @@ impl ConsensusCommitOutput {
             .extend(deferral_keys.iter().cloned());
     }
 
+    fn set_congestion_history(
+        &mut self,
+        deleted: Vec<ObjectID>,
+        congestion_history: HashMap<ObjectID, u64>,
+    ) {
+        assert!(self.congestion_history.is_none());
+        self.congestion_history = Some((deleted, congestion_history));
+    }
+
     fn insert_pending_checkpoint(&mut self, checkpoint: PendingCheckpoint) {
         self.pending_checkpoints.push(checkpoint);
     }
@@ -3996,6 +4110,11 @@ impl ConsensusCommitOutput {
         batch.delete_batch(&tables.deferred_transactions, self.deleted_deferred_txns)?;
         batch.insert_batch(&tables.deferred_transactions, self.deferred_txns)?;
 
+        if let Some((deleted, congestion_history)) = self.congestion_history {
+            batch.delete_batch(&tables.congestion_history, deleted)?;
+            batch.insert_batch(&tables.congestion_history, congestion_history)?;
+        }
+
         batch.insert_batch(
             &tables.user_signatures_for_checkpoints,
             self.user_signatures_for_checkpoints,
@@ -2,7 +2,7 @@
 // Modifications Copyright (c) 2024 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{iter, mem, ops::Not, sync::Arc, thread};
+use std::{collections::HashMap, iter, mem, ops::Not, sync::Arc, thread};
 
 use either::Either;
 use fastcrypto::hash::{HashFunction, Sha3_256};
@@ -50,7 +50,7 @@ use crate::{
         },
         authority_store_tables::TotalIotaSupplyCheck,
         authority_store_types::{
-            ObjectContentDigest, StoreObject, StoreObjectPair, StoreObjectWrapper,
+            ObjectContentDigest, StoreData, StoreObject, StoreObjectPair, StoreObjectWrapper,
             get_store_object_pair,
         },
         epoch_start_configuration::{EpochFlag, EpochStartConfiguration},
@@ -820,6 +820,41 @@ impl AuthorityStore {
         Ok(())
     }
 
+    /// Removes objects that are no longer live, e.g. when applying a delta
+    /// state snapshot on top of a restored live object set. The reference
+    /// counts of the contents of removed indirect objects are decremented.
+    pub fn bulk_remove_live_objects(
+        perpetual_db: &AuthorityPerpetualTables,
+        object_refs: impl Iterator<Item = ObjectRef>,
+    ) -> IotaResult<()> {
+        let object_refs: Vec<_> = object_refs.collect();
+        let object_keys: Vec<_> = object_refs.iter().copied().map(ObjectKey::from).collect();
+        let mut indirect_objects: HashMap<_, i64> = HashMap::new();
+        for object in perpetual_db
+            .objects
+            .multi_get(&object_keys)?
+            .into_iter()
+            .flatten()
+        {
+            if let StoreObject::Value(object) = object.into_inner() {
+                if let StoreData::IndirectObject(indirect_object) = object.data {
+                    *indirect_objects.entry(indirect_object.digest).or_default() -= 1;
+                }
+            }
+        }
+        let mut batch = perpetual_db.objects.batch();
+        batch.delete_batch(&perpetual_db.objects, object_keys)?;
+        batch.delete_batch(&perpetual_db.live_owned_object_markers, object_refs)?;
+        if !indirect_objects.is_empty() {
+            let ref_count_update = indirect_objects
+                .iter()
+                .map(|(digest, delta)| (digest, delta.to_le_bytes()));
+            batch.partial_merge_batch(&perpetual_db.indirect_move_objects, ref_count_update)?;
+        }
+        batch.write()?;
+        Ok(())
+    }
+
     pub fn set_epoch_start_configuration(
         &self,
         epoch_start_configuration: &EpochStartConfiguration,
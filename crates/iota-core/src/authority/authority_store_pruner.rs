@@ -170,10 +170,15 @@ impl AuthorityStorePruner {
             .num_pruned_tombstones
             .inc_by(object_tombstones_to_prune.len() as u64);
 
+        // The objects to prune are read from a single snapshot, so that the reference
+        // counts and the tombstoned versions are consistent with each other while
+        // transactions keep being executed.
+        let snapshot = perpetual_db.snapshot();
+        let objects = snapshot.map(&perpetual_db.objects)?;
+
         let mut indirect_objects: HashMap<_, i64> = HashMap::new();
         if indirect_objects_threshold > 0 && indirect_objects_threshold < usize::MAX {
-            for object in perpetual_db
-                .objects
+            for object in objects
                 .multi_get(live_object_keys_to_prune.iter())?
                 .into_iter()
                 .flatten()
@@ -215,7 +220,7 @@ impl AuthorityStorePruner {
         if !object_tombstones_to_prune.is_empty() {
             let mut object_keys_to_delete = vec![];
             for ObjectKey(object_id, seq_number) in object_tombstones_to_prune {
-                for result in perpetual_db.objects.safe_iter_with_bounds(
+                for result in objects.safe_iter_with_bounds(
                     Some(ObjectKey(object_id, VersionNumber::MIN)),
                     Some(ObjectKey(object_id, seq_number.next())),
                 ) {
@@ -227,6 +232,8 @@ impl AuthorityStorePruner {
 
             wb.delete_batch(&perpetual_db.objects, object_keys_to_delete)?;
         }
+        drop(objects);
+        drop(snapshot);
 
         if !indirect_objects.is_empty() {
             let ref_count_update = indirect_objects
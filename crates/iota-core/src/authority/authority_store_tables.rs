@@ -15,6 +15,7 @@ use typed_store::{
     rocks::{
         DBBatch, DBMap, DBOptions, MetricConf, ReadWriteOptions, default_db_options,
         read_size_from_env,
+        snapshot::{Snapshot, SnapshotMap},
         util::{empty_compaction_filter, reference_count_merge_operator},
     },
     rocksdb::Options,
@@ -200,14 +201,7 @@ impl AuthorityPerpetualTables {
         object_key: &ObjectKey,
         store_object: StoreObjectValue,
     ) -> Result<Object, IotaError> {
-        let indirect_object = match store_object.data {
-            StoreData::IndirectObject(ref metadata) => self
-                .indirect_move_objects
-                .get(&metadata.digest)?
-                .map(|o| o.migrate().into_inner()),
-            _ => None,
-        };
-        try_construct_object(object_key, store_object, indirect_object)
+        construct_object(&self.indirect_move_objects, object_key, store_object)
     }
 
     // Constructs `iota_types::object::Object` from `StoreObjectWrapper`.
@@ -400,11 +394,29 @@ impl AuthorityPerpetualTables {
     pub fn iter_live_object_set(&self) -> LiveSetIter<'_> {
         LiveSetIter {
             iter: self.objects.unbounded_iter(),
-            tables: self,
+            indirect_move_objects: &self.indirect_move_objects,
             prev: None,
         }
     }
 
+    /// Takes a consistent, read-only snapshot across all the perpetual tables.
+    pub fn snapshot(&self) -> Snapshot<'_> {
+        self.objects.snapshot()
+    }
+
+    /// Opens the tables of the live object set at a [`Self::snapshot`]. The
+    /// live object set iterated from it is not affected by the transactions
+    /// executed meanwhile.
+    pub fn live_object_set_at<'a>(
+        &'a self,
+        snapshot: &'a Snapshot<'a>,
+    ) -> IotaResult<LiveObjectSetSnapshot<'a>> {
+        Ok(LiveObjectSetSnapshot {
+            objects: snapshot.map(&self.objects)?,
+            indirect_move_objects: snapshot.map(&self.indirect_move_objects)?,
+        })
+    }
+
     pub fn range_iter_live_object_set(
         &self,
         lower_bound: Option<ObjectID>,
@@ -415,7 +427,7 @@ impl AuthorityPerpetualTables {
 
         LiveSetIter {
             iter: self.objects.iter_with_bounds(lower_bound, upper_bound),
-            tables: self,
+            indirect_move_objects: &self.indirect_move_objects,
             prev: None,
         }
     }
@@ -511,10 +523,50 @@ impl ObjectStore for AuthorityPerpetualTables {
     }
 }
 
-pub struct LiveSetIter<'a> {
+/// Constructs an object stored in the `objects` table, reading its contents
+/// from `indirect_move_objects` if they are stored indirectly.
+fn construct_object<'a>(
+    indirect_move_objects: &impl Map<
+        'a,
+        ObjectContentDigest,
+        StoreMoveObjectWrapper,
+        Error = TypedStoreError,
+    >,
+    object_key: &ObjectKey,
+    store_object: StoreObjectValue,
+) -> Result<Object, IotaError> {
+    let indirect_object = match store_object.data {
+        StoreData::IndirectObject(ref metadata) => indirect_move_objects
+            .get(&metadata.digest)?
+            .map(|o| o.migrate().into_inner()),
+        _ => None,
+    };
+    try_construct_object(object_key, store_object, indirect_object)
+}
+
+/// The tables of the live object set of [`AuthorityPerpetualTables`] at a
+/// [`Snapshot`].
+pub struct LiveObjectSetSnapshot<'a> {
+    objects: SnapshotMap<'a, ObjectKey, StoreObjectWrapper>,
+    indirect_move_objects: SnapshotMap<'a, ObjectContentDigest, StoreMoveObjectWrapper>,
+}
+
+impl<'a> LiveObjectSetSnapshot<'a> {
+    pub fn iter_live_object_set(
+        &self,
+    ) -> LiveSetIter<'_, SnapshotMap<'a, ObjectContentDigest, StoreMoveObjectWrapper>> {
+        LiveSetIter {
+            iter: self.objects.unbounded_iter(),
+            indirect_move_objects: &self.indirect_move_objects,
+            prev: None,
+        }
+    }
+}
+
+pub struct LiveSetIter<'a, M = DBMap<ObjectContentDigest, StoreMoveObjectWrapper>> {
     iter:
         <DBMap<ObjectKey, StoreObjectWrapper> as Map<'a, ObjectKey, StoreObjectWrapper>>::Iterator,
-    tables: &'a AuthorityPerpetualTables,
+    indirect_move_objects: &'a M,
     prev: Option<(ObjectKey, StoreObjectWrapper)>,
 }
 
@@ -554,7 +606,10 @@ impl LiveObject {
     }
 }
 
-impl LiveSetIter<'_> {
+impl<'a, M> LiveSetIter<'a, M>
+where
+    M: Map<'a, ObjectContentDigest, StoreMoveObjectWrapper, Error = TypedStoreError>,
+{
     fn store_object_wrapper_to_live_object(
         &self,
         object_key: ObjectKey,
@@ -562,9 +617,7 @@ impl LiveSetIter<'_> {
     ) -> Option<LiveObject> {
         match store_object.migrate().into_inner() {
             StoreObject::Value(object) => {
-                let object = self
-                    .tables
-                    .construct_object(&object_key, object)
+                let object = construct_object(self.indirect_move_objects, &object_key, object)
                     .expect("Constructing object from store cannot fail");
                 Some(LiveObject::Normal(object))
             }
@@ -573,7 +626,10 @@ impl LiveSetIter<'_> {
     }
 }
 
-impl Iterator for LiveSetIter<'_> {
+impl<'a, M> Iterator for LiveSetIter<'a, M>
+where
+    M: Map<'a, ObjectContentDigest, StoreMoveObjectWrapper, Error = TypedStoreError>,
+{
     type Item = LiveObject;
 
     fn next(&mut self) -> Option<Self::Item> {
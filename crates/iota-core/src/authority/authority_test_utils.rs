@@ -290,7 +290,7 @@ pub fn init_transfer_transaction(
         .unwrap()
 }
 
-pub fn init_certified_transfer_transaction(
+pub async fn init_certified_transfer_transaction(
     sender: IotaAddress,
     secret: &AccountKeyPair,
     recipient: IotaAddress,
@@ -309,22 +309,24 @@ pub fn init_certified_transfer_transaction(
         rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
         rgp,
     );
-    init_certified_transaction(transfer_transaction.into(), authority_state)
+    init_certified_transaction(transfer_transaction.into(), authority_state).await
 }
 
-pub fn init_certified_transaction(
+pub async fn init_certified_transaction(
     transaction: Transaction,
     authority_state: &AuthorityState,
 ) -> VerifiedCertificate {
     let epoch_store = authority_state.epoch_store_for_testing();
     let transaction = epoch_store.verify_transaction(transaction).unwrap();
 
-    let vote = VerifiedSignedTransaction::new(
+    let vote = VerifiedSignedTransaction::try_new(
         0,
         transaction.clone(),
         authority_state.name,
         &*authority_state.secret,
-    );
+    )
+    .await
+    .unwrap();
     CertifiedTransaction::new(
         transaction.into_message(),
         vec![vote.auth_sig().clone()],
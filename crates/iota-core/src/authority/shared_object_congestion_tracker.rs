@@ -6,7 +6,7 @@ use std::collections::HashMap;
 
 use iota_protocol_config::PerObjectCongestionControlMode;
 use iota_types::{
-    base_types::{CommitRound, ObjectID, TransactionDigest},
+    base_types::{CommitRound, ObjectID, SequenceNumber, TransactionDigest},
     executable_transaction::VerifiedExecutableTransaction,
     transaction::SharedInputObject,
 };
@@ -26,9 +26,16 @@ use crate::authority::transaction_deferral::DeferralKey;
 // The mode field determines how the cost is calculated. The cost can be
 // calculated based on the total gas budget, or total number of transaction
 // count.
+//
+// The congestion history field holds, for each object that was congested in
+// recent commits, the cost by which it exceeded the limit, halved on every
+// commit. It is only used to price congested objects when congestion surcharge
+// pricing is enabled, so that objects that are kept hot across commits stay
+// expensive to access.
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub struct SharedObjectCongestionTracker {
     object_execution_cost: HashMap<ObjectID, u64>,
+    congestion_history: HashMap<ObjectID, u64>,
     mode: PerObjectCongestionControlMode,
 }
 
@@ -36,10 +43,16 @@ impl SharedObjectCongestionTracker {
     pub fn new(mode: PerObjectCongestionControlMode) -> Self {
         Self {
             object_execution_cost: HashMap::new(),
+            congestion_history: HashMap::new(),
             mode,
         }
     }
 
+    pub fn with_congestion_history(mut self, congestion_history: HashMap<ObjectID, u64>) -> Self {
+        self.congestion_history = congestion_history;
+        self
+    }
+
     pub fn new_with_initial_value_for_test(
         init_values: &[(ObjectID, u64)],
         mode: PerObjectCongestionControlMode,
@@ -50,6 +63,7 @@ impl SharedObjectCongestionTracker {
         }
         Self {
             object_execution_cost,
+            congestion_history: HashMap::new(),
             mode,
         }
     }
@@ -139,6 +153,76 @@ impl SharedObjectCongestionTracker {
         Some((deferral_key, congested_objects))
     }
 
+    // Given a transaction, returns the congestion surcharge, on top of the
+    // reference gas price, its gas price must cover to be scheduled, together with
+    // the congested objects, if it touches any.
+    //
+    // Each congested shared object is priced separately: `surcharge_bps` of the
+    // reference gas price for every started multiple of the limit in its excess
+    // cost, which is the cost by which it would exceed the limit in this commit
+    // plus its congestion history. The surcharge is capped to the largest one that
+    // can be encoded in the version of a cancelled object, which no gas price can
+    // cover anyway.
+    pub fn compute_congestion_surcharge(
+        &self,
+        cert: &VerifiedExecutableTransaction,
+        max_accumulated_txn_cost_per_object_in_commit: u64,
+        reference_gas_price: u64,
+        surcharge_bps: u64,
+    ) -> Option<(u64, Vec<ObjectID>)> {
+        let tx_cost = self.get_tx_cost(cert)?;
+
+        let mut surcharge = 0u64;
+        let mut congested_objects = vec![];
+        for obj in cert.shared_input_objects() {
+            let end_cost = self.object_execution_cost.get(&obj.id).unwrap_or(&0) + tx_cost;
+            let excess_cost = end_cost
+                .saturating_sub(max_accumulated_txn_cost_per_object_in_commit)
+                .saturating_add(*self.congestion_history.get(&obj.id).unwrap_or(&0));
+            if excess_cost == 0 {
+                continue;
+            }
+            let multiples =
+                excess_cost.div_ceil(max_accumulated_txn_cost_per_object_in_commit.max(1));
+            let object_surcharge =
+                reference_gas_price as u128 * surcharge_bps as u128 * multiples as u128 / 10_000;
+            surcharge =
+                surcharge.saturating_add(u64::try_from(object_surcharge).unwrap_or(u64::MAX));
+            congested_objects.push(obj.id);
+        }
+
+        (!congested_objects.is_empty()).then_some((
+            surcharge.min(SequenceNumber::MAX_CONGESTION_SURCHARGE),
+            congested_objects,
+        ))
+    }
+
+    // Returns the congestion history to use in the next commit, given the
+    // congestion history of this commit and the trackers of all the transactions
+    // scheduled in it: the history is halved, and the cost by which each object
+    // exceeded the limit in this commit is added to it. Objects without history
+    // are dropped.
+    pub fn next_congestion_history(
+        congestion_history: &HashMap<ObjectID, u64>,
+        trackers: &[&SharedObjectCongestionTracker],
+        max_accumulated_txn_cost_per_object_in_commit: u64,
+    ) -> HashMap<ObjectID, u64> {
+        let mut next_congestion_history: HashMap<_, _> = congestion_history
+            .iter()
+            .map(|(id, excess_cost)| (*id, excess_cost / 2))
+            .collect();
+        for tracker in trackers {
+            for (id, cost) in &tracker.object_execution_cost {
+                let excess_cost =
+                    cost.saturating_sub(max_accumulated_txn_cost_per_object_in_commit);
+                let history = next_congestion_history.entry(*id).or_default();
+                *history = history.saturating_add(excess_cost);
+            }
+        }
+        next_congestion_history.retain(|_, excess_cost| *excess_cost > 0);
+        next_congestion_history
+    }
+
     // Update shared objects' execution cost used in `cert` using `cert`'s execution
     // cost. This is called when `cert` is scheduled for execution.
     pub fn bump_object_execution_cost(&mut self, cert: &VerifiedExecutableTransaction) {
@@ -377,6 +461,123 @@ mod object_cost_tests {
         }
     }
 
+    #[test]
+    fn test_compute_congestion_surcharge() {
+        let shared_obj_0 = ObjectID::random();
+        let shared_obj_1 = ObjectID::random();
+        let shared_obj_2 = ObjectID::random();
+
+        // Construct object execution cost as following
+        //                0     2           5
+        // object 0:            |
+        // object 1:                        |
+        // object 2:      |
+        let shared_object_congestion_tracker =
+            SharedObjectCongestionTracker::new_with_initial_value_for_test(
+                &[(shared_obj_0, 2), (shared_obj_1, 5)],
+                PerObjectCongestionControlMode::TotalTxCount,
+            );
+        let max_accumulated_txn_cost_per_object_in_commit = 2;
+        let reference_gas_price = 1000;
+        let surcharge_bps = 5000;
+        let compute_surcharge = |objects: &[(ObjectID, bool)]| {
+            shared_object_congestion_tracker.compute_congestion_surcharge(
+                &build_transaction(objects, 100),
+                max_accumulated_txn_cost_per_object_in_commit,
+                reference_gas_price,
+                surcharge_bps,
+            )
+        };
+
+        // Object 0 exceeds the limit by 1, i.e. one started multiple of the limit.
+        assert_eq!(
+            compute_surcharge(&[(shared_obj_0, true)]),
+            Some((500, vec![shared_obj_0]))
+        );
+        // Object 1 exceeds the limit by 4, i.e. two multiples of the limit.
+        assert_eq!(
+            compute_surcharge(&[(shared_obj_1, false)]),
+            Some((1000, vec![shared_obj_1]))
+        );
+        // The surcharges of all the congested objects add up.
+        assert_eq!(
+            compute_surcharge(&[
+                (shared_obj_0, true),
+                (shared_obj_1, true),
+                (shared_obj_2, true)
+            ]),
+            Some((1500, vec![shared_obj_0, shared_obj_1]))
+        );
+        // Object 2 is not congested.
+        assert_eq!(compute_surcharge(&[(shared_obj_2, true)]), None);
+
+        // Objects congested in recent commits are priced even if they don't exceed
+        // the limit in this commit.
+        let shared_object_congestion_tracker = shared_object_congestion_tracker
+            .with_congestion_history(HashMap::from([(shared_obj_2, 3)]));
+        assert_eq!(
+            shared_object_congestion_tracker.compute_congestion_surcharge(
+                &build_transaction(&[(shared_obj_2, true)], 100),
+                max_accumulated_txn_cost_per_object_in_commit,
+                reference_gas_price,
+                surcharge_bps,
+            ),
+            Some((1000, vec![shared_obj_2]))
+        );
+        // The history adds up to the excess cost in this commit.
+        assert_eq!(
+            shared_object_congestion_tracker.compute_congestion_surcharge(
+                &build_transaction(&[(shared_obj_2, true)], 100),
+                0,
+                reference_gas_price,
+                surcharge_bps,
+            ),
+            Some((2000, vec![shared_obj_2]))
+        );
+
+        // The surcharge is capped to the largest encodable one.
+        assert_eq!(
+            shared_object_congestion_tracker.compute_congestion_surcharge(
+                &build_transaction(&[(shared_obj_1, true)], 100),
+                max_accumulated_txn_cost_per_object_in_commit,
+                u64::MAX,
+                surcharge_bps,
+            ),
+            Some((SequenceNumber::MAX_CONGESTION_SURCHARGE, vec![shared_obj_1]))
+        );
+    }
+
+    #[test]
+    fn test_next_congestion_history() {
+        let shared_obj_0 = ObjectID::random();
+        let shared_obj_1 = ObjectID::random();
+        let shared_obj_2 = ObjectID::random();
+        let shared_obj_3 = ObjectID::random();
+
+        let congestion_history =
+            HashMap::from([(shared_obj_0, 8), (shared_obj_1, 1), (shared_obj_2, 4)]);
+        let tracker = SharedObjectCongestionTracker::new_with_initial_value_for_test(
+            &[(shared_obj_0, 5), (shared_obj_3, 2)],
+            PerObjectCongestionControlMode::TotalTxCount,
+        );
+        let randomness_tracker = SharedObjectCongestionTracker::new_with_initial_value_for_test(
+            &[(shared_obj_2, 4)],
+            PerObjectCongestionControlMode::TotalTxCount,
+        );
+
+        // Object 0 keeps half its history plus its excess cost of 3, object 1 has
+        // its history decayed to zero, object 2 adds up its excess cost of 2 from the
+        // randomness commit and object 3 doesn't exceed the limit.
+        assert_eq!(
+            SharedObjectCongestionTracker::next_congestion_history(
+                &congestion_history,
+                &[&tracker, &randomness_tracker],
+                2
+            ),
+            HashMap::from([(shared_obj_0, 7), (shared_obj_2, 4)])
+        );
+    }
+
     #[rstest]
     fn test_should_defer_return_correct_deferral_key(
         #[values(
@@ -6,7 +6,7 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 
 use iota_types::{
     IOTA_RANDOMNESS_STATE_OBJECT_ID,
-    base_types::{ObjectID, SequenceNumber, TransactionDigest},
+    base_types::{ObjectID, SequenceNumber, SharedObjectCancellation, TransactionDigest},
     crypto::RandomnessRound,
     effects::{TransactionEffects, TransactionEffectsAPI},
     error::IotaResult,
@@ -137,14 +137,23 @@ impl SharedObjVerManager {
 
         // Check if the transaction is cancelled due to congestion.
         let cancellation_info = cancelled_txns.get(tx_digest);
-        let congested_objects_info: Option<HashSet<_>> =
-            if let Some(CancelConsensusCertificateReason::CongestionOnObjects(congested_objects)) =
-                &cancellation_info
-            {
-                Some(congested_objects.iter().cloned().collect())
-            } else {
-                None
-            };
+        let congested_objects_info: Option<(HashSet<_>, SequenceNumber)> = match &cancellation_info
+        {
+            Some(CancelConsensusCertificateReason::CongestionOnObjects(congested_objects)) => {
+                Some((
+                    congested_objects.iter().cloned().collect(),
+                    SequenceNumber::CONGESTED,
+                ))
+            }
+            Some(CancelConsensusCertificateReason::CongestionOnObjectsWithSurcharge(
+                congested_objects,
+                surcharge,
+            )) => Some((
+                congested_objects.iter().cloned().collect(),
+                SharedObjectCancellation::CongestedWithSurcharge(*surcharge).into(),
+            )),
+            _ => None,
+        };
         let txn_cancelled = cancellation_info.is_some();
 
         // Make an iterator to update the locks of the transaction's shared objects.
@@ -164,16 +173,17 @@ impl SharedObjVerManager {
             // any shared objects.
             for SharedInputObject { id, .. } in shared_input_objects.iter() {
                 let assigned_version = match cancellation_info {
-                    Some(CancelConsensusCertificateReason::CongestionOnObjects(_)) => {
-                        if congested_objects_info
-                            .as_ref()
-                            .is_some_and(|info| info.contains(id))
+                    Some(CancelConsensusCertificateReason::CongestionOnObjects(_))
+                    | Some(CancelConsensusCertificateReason::CongestionOnObjectsWithSurcharge(
+                        ..,
+                    )) => match &congested_objects_info {
+                        Some((congested_objects, congested_version))
+                            if congested_objects.contains(id) =>
                         {
-                            SequenceNumber::CONGESTED
-                        } else {
-                            SequenceNumber::CANCELLED_READ
+                            *congested_version
                         }
-                    }
+                        _ => SequenceNumber::CANCELLED_READ,
+                    },
                     Some(CancelConsensusCertificateReason::DkgFailed) => {
                         if id == &IOTA_RANDOMNESS_STATE_OBJECT_ID {
                             SequenceNumber::RANDOMNESS_UNAVAILABLE
@@ -23,7 +23,7 @@ use iota_storage::IndexStore;
 use iota_swarm_config::{genesis_config::AccountConfig, network_config::NetworkConfig};
 use iota_types::{
     base_types::{AuthorityName, ObjectID},
-    crypto::AuthorityKeyPair,
+    crypto::{AuthorityKeyPair, randomness_key_scalar},
     digests::ChainIdentifier,
     executable_transaction::VerifiedExecutableTransaction,
     iota_system_state::IotaSystemStateTrait,
@@ -335,7 +335,7 @@ impl<'a> TestAuthorityBuilder<'a> {
             Arc::downgrade(&epoch_store),
             consensus_client,
             randomness::Handle::new_stub(),
-            &keypair,
+            randomness_key_scalar(&keypair),
         )
         .await;
         if let Some(randomness_manager) = randomness_manager {
@@ -84,6 +84,12 @@ pub enum DeferralReason {
 
     // The list of objects are congested objects.
     SharedObjectCongestion(Vec<ObjectID>),
+
+    // The list of objects are congested objects, and the gas price of the
+    // transaction doesn't cover the congestion surcharge to access them. Such
+    // transactions are cancelled right away instead of deferred, so that users
+    // learn the surcharge from the effects and can bid again.
+    SharedObjectCongestionSurcharge(Vec<ObjectID>, u64),
 }
 
 pub fn transaction_deferral_within_limit(
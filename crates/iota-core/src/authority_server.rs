@@ -506,7 +506,8 @@ impl ValidatorService {
 
             if let Some(signed_effects) = self
                 .state
-                .get_signed_effects_and_maybe_resign(&tx_digest, epoch_store)?
+                .get_signed_effects_and_maybe_resign(&tx_digest, epoch_store)
+                .await?
             {
                 let events = if include_events {
                     if let Some(digest) = signed_effects.events_digest() {
@@ -643,7 +644,7 @@ impl ValidatorService {
                     .then(|| self.state.get_transaction_output_objects(&effects))
                     .and_then(Result::ok);
 
-                let signed_effects = self.state.sign_effects(effects, epoch_store)?;
+                let signed_effects = self.state.sign_effects(effects, epoch_store).await?;
                 epoch_store.insert_tx_cert_sig(certificate.digest(), certificate.auth_sig())?;
 
                 Ok::<_, IotaError>(HandleCertificateResponseV1 {
@@ -48,7 +48,10 @@ use iota_types::{
 use itertools::izip;
 use tap::{TapFallible, TapOptional};
 use tokio::{
-    sync::broadcast::{self, error::RecvError},
+    sync::{
+        broadcast::{self, error::RecvError},
+        watch,
+    },
     task::JoinHandle,
     time::timeout,
 };
@@ -150,6 +153,9 @@ pub struct CheckpointExecutor {
     accumulator: Arc<StateAccumulator>,
     config: CheckpointExecutorConfig,
     metrics: Arc<CheckpointExecutorMetrics>,
+    /// Notified with the sequence number of every checkpoint once it has been
+    /// executed, e.g. to wake up checkpoint subscriptions.
+    executed_checkpoint_sender: Option<watch::Sender<CheckpointSequenceNumber>>,
 }
 
 impl CheckpointExecutor {
@@ -171,9 +177,18 @@ impl CheckpointExecutor {
             accumulator,
             config,
             metrics,
+            executed_checkpoint_sender: None,
         }
     }
 
+    pub fn with_executed_checkpoint_sender(
+        mut self,
+        sender: watch::Sender<CheckpointSequenceNumber>,
+    ) -> Self {
+        self.executed_checkpoint_sender = Some(sender);
+        self
+    }
+
     pub fn new_for_tests(
         mailbox: broadcast::Receiver<VerifiedCheckpoint>,
         checkpoint_store: Arc<CheckpointStore>,
@@ -410,6 +425,9 @@ impl CheckpointExecutor {
             .update_highest_executed_checkpoint(checkpoint)
             .unwrap();
         self.metrics.last_executed_checkpoint.set(seq as i64);
+        if let Some(sender) = &self.executed_checkpoint_sender {
+            sender.send_replace(seq);
+        }
 
         self.metrics
             .last_executed_checkpoint_timestamp_ms
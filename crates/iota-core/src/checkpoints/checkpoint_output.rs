@@ -100,12 +100,13 @@ impl<T: SubmitToConsensus + ReconfigurationInitiator> CheckpointOutput
                 self.next_reconfiguration_timestamp_ms.saturating_sub(checkpoint_timestamp), self.next_reconfiguration_timestamp_ms
             );
 
-            let summary = SignedCheckpointSummary::new(
+            let summary = SignedCheckpointSummary::try_new(
                 epoch_store.epoch(),
                 summary.clone(),
                 &*self.signer,
                 self.authority,
-            );
+            )
+            .await?;
 
             let message = CheckpointSignatureMessage { summary };
             let transaction = ConsensusTransaction::new_checkpoint_signature_message(message);
@@ -2379,10 +2379,7 @@ impl CheckpointServiceNotify for CheckpointServiceNoop {
 
 #[cfg(test)]
 mod tests {
-    use std::{
-        collections::{BTreeMap, HashMap},
-        ops::Deref,
-    };
+    use std::collections::{BTreeMap, HashMap};
 
     use futures::future::BoxFuture;
     use iota_macros::sim_test;
@@ -2608,8 +2605,12 @@ mod tests {
         assert_eq!(c7s.previous_digest, Some(c6s.digest()));
         assert_eq!(c7s.sequence_number, 6);
 
-        let c1ss = SignedCheckpointSummary::new(c1s.epoch, c1s, state.secret.deref(), state.name);
-        let c2ss = SignedCheckpointSummary::new(c2s.epoch, c2s, state.secret.deref(), state.name);
+        let c1ss = SignedCheckpointSummary::try_new(c1s.epoch, c1s, &*state.secret, state.name)
+            .await
+            .unwrap();
+        let c2ss = SignedCheckpointSummary::try_new(c2s.epoch, c2s, &*state.secret, state.name)
+            .await
+            .unwrap();
 
         checkpoint_service
             .notify_checkpoint_signature(&epoch_store, &CheckpointSignatureMessage {
@@ -10,11 +10,14 @@ use std::{
 
 use arc_swap::ArcSwapOption;
 use async_trait::async_trait;
+use anyhow::bail;
+use consensus_config::{Committee, ProtocolKeyPair, ProtocolPublicKey};
 use enum_dispatch::enum_dispatch;
 use fastcrypto::traits::KeyPair as _;
 use iota_config::{ConsensusConfig, NodeConfig};
 use iota_metrics::RegistryService;
 use iota_protocol_config::ProtocolVersion;
+use iota_remote_signer::{LocalSocketSigner, RemoteBlockSigner, RemoteSigner};
 use iota_types::{committee::EpochId, error::IotaResult, messages_consensus::ConsensusTransaction};
 use prometheus::{IntGauge, Registry, register_int_gauge_with_registry};
 use tokio::{
@@ -62,22 +65,50 @@ enum ProtocolManager {
 }
 
 impl ProtocolManager {
-    /// Creates a new mysticeti manager.
-    pub fn new_mysticeti(
+    /// Creates a new mysticeti manager, failing if the remote signer can't be
+    /// reached or doesn't sign with this validator's protocol key in
+    /// `committee`.
+    pub async fn new_mysticeti(
         config: &NodeConfig,
         consensus_config: &ConsensusConfig,
+        committee: &Committee,
         registry_service: &RegistryService,
         metrics: Arc<ConsensusManagerMetrics>,
         client: Arc<LazyMysticetiClient>,
-    ) -> Self {
-        Self::Mysticeti(MysticetiManager::new(
-            config.protocol_key_pair().copy(),
+    ) -> anyhow::Result<Self> {
+        let protocol_keypair = match &config.remote_signer {
+            Some(remote_signer_config) => {
+                // Consensus gets its own connections, so that block proposals
+                // don't queue behind transaction signatures.
+                let signer = LocalSocketSigner::connect(&remote_signer_config.socket_path).await?;
+                let public_key = ProtocolPublicKey::new(signer.public_keys().await?.protocol);
+                let authority_name = config.authority_public_key();
+                if let Some((_, authority)) = committee
+                    .authorities()
+                    .find(|(_, a)| authority_name.0 == a.authority_key.to_bytes())
+                {
+                    if authority.protocol_key != public_key {
+                        bail!(
+                            "Remote signer signs consensus blocks with {public_key:?} instead of {:?}",
+                            authority.protocol_key
+                        );
+                    }
+                }
+                ProtocolKeyPair::new_external(
+                    public_key,
+                    Arc::new(RemoteBlockSigner::new(Arc::new(signer))?),
+                )
+            }
+            None => ProtocolKeyPair::new(config.protocol_key_pair().copy()),
+        };
+        Ok(Self::Mysticeti(MysticetiManager::new(
+            protocol_keypair,
             config.network_key_pair().copy(),
             consensus_config.db_path().to_path_buf(),
             registry_service.clone(),
             metrics,
             client,
-        ))
+        )))
     }
 }
 
@@ -91,12 +122,15 @@ pub struct ConsensusManager {
 }
 
 impl ConsensusManager {
-    pub fn new(
+    /// Creates the consensus manager. `committee` is the consensus committee
+    /// of the current epoch, against which the remote signer's key is checked.
+    pub async fn new(
         node_config: &NodeConfig,
         consensus_config: &ConsensusConfig,
+        committee: &Committee,
         registry_service: &RegistryService,
         consensus_client: Arc<ConsensusClient>,
-    ) -> Self {
+    ) -> anyhow::Result<Self> {
         let metrics = Arc::new(ConsensusManagerMetrics::new(
             &registry_service.default_registry(),
         ));
@@ -104,17 +138,19 @@ impl ConsensusManager {
         let mysticeti_manager = ProtocolManager::new_mysticeti(
             node_config,
             consensus_config,
+            committee,
             registry_service,
             metrics,
             mysticeti_client.clone(),
-        );
-        Self {
+        )
+        .await?;
+        Ok(Self {
             consensus_config: consensus_config.clone(),
             mysticeti_manager,
             mysticeti_client,
             active: parking_lot::Mutex::new(false),
             consensus_client,
-        }
+        })
     }
 
     pub fn get_storage_base_path(&self) -> PathBuf {
@@ -56,7 +56,7 @@ impl MysticetiManager {
     /// But for security, the protocol keypair must be different from the
     /// network keypair.
     pub fn new(
-        protocol_keypair: ed25519::Ed25519KeyPair,
+        protocol_keypair: ProtocolKeyPair,
         network_keypair: ed25519::Ed25519KeyPair,
         storage_base_path: PathBuf,
         registry_service: RegistryService,
@@ -64,7 +64,7 @@ impl MysticetiManager {
         client: Arc<LazyMysticetiClient>,
     ) -> Self {
         Self {
-            protocol_keypair: ProtocolKeyPair::new(protocol_keypair),
+            protocol_keypair,
             network_keypair: NetworkKeyPair::new(network_keypair),
             storage_base_path,
             running: Mutex::new(Running::False),
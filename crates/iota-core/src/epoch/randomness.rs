@@ -14,7 +14,7 @@ use fastcrypto::{
     error::{FastCryptoError, FastCryptoResult},
     groups::bls12381,
     serde_helpers::ToFromByteArray,
-    traits::{KeyPair, ToFromBytes},
+    traits::ToFromBytes,
 };
 use fastcrypto_tbls::{dkg, dkg::Output, dkg_v1, nodes, nodes::PartyId};
 use futures::{StreamExt, stream::FuturesUnordered};
@@ -23,7 +23,7 @@ use iota_network::randomness;
 use iota_types::{
     base_types::{AuthorityName, CommitRound},
     committee::{Committee, EpochId, StakeUnit},
-    crypto::{AuthorityKeyPair, RandomnessRound},
+    crypto::RandomnessRound,
     error::{IotaError, IotaResult},
     iota_system_state::epoch_start_iota_system_state::EpochStartSystemStateTrait,
     messages_consensus::{ConsensusTransaction, VersionedDkgConfirmation, VersionedDkgMessage},
@@ -175,11 +175,13 @@ pub struct RandomnessManager {
 
 impl RandomnessManager {
     // Returns None in case of invalid input or other failure to initialize DKG.
+    // `randomness_key` is the scalar of the authority key, see
+    // `randomness_key_scalar`.
     pub async fn try_new(
         epoch_store_weak: Weak<AuthorityPerEpochStore>,
         consensus_adapter: Box<dyn SubmitToConsensus>,
         network_handle: randomness::Handle,
-        authority_key_pair: &AuthorityKeyPair,
+        randomness_key: bls12381::Scalar,
     ) -> Option<Self> {
         let epoch_store = match epoch_store_weak.upgrade() {
             Some(epoch_store) => epoch_store,
@@ -201,7 +203,7 @@ impl RandomnessManager {
         };
         let protocol_config = epoch_store.protocol_config();
 
-        let name: AuthorityName = authority_key_pair.public().into();
+        let name = epoch_store.name;
         let committee = epoch_store.committee();
         let info = RandomnessManager::randomness_dkg_info_from_committee(committee);
         if tracing::enabled!(tracing::Level::DEBUG) {
@@ -261,17 +263,8 @@ impl RandomnessManager {
             Hex::encode(epoch_store.get_chain_identifier().as_bytes()),
             committee.epoch()
         );
-        let randomness_private_key = bls12381::Scalar::from_byte_array(
-            authority_key_pair
-                .copy()
-                .private()
-                .as_bytes()
-                .try_into()
-                .expect("key length should match"),
-        )
-        .expect("should work to convert BLS key to Scalar");
         let party = match dkg::Party::<PkG, EncG>::new(
-            fastcrypto_tbls::ecies::PrivateKey::<bls12381::G2Element>::from(randomness_private_key),
+            fastcrypto_tbls::ecies::PrivateKey::<bls12381::G2Element>::from(randomness_key),
             nodes,
             t,
             fastcrypto_tbls::random_oracle::RandomOracle::new(prefix_str.as_str()),
@@ -834,7 +827,7 @@ mod tests {
     use std::num::NonZeroUsize;
 
     use iota_protocol_config::{Chain, ProtocolConfig, ProtocolVersion};
-    use iota_types::messages_consensus::ConsensusTransactionKind;
+    use iota_types::{crypto::randomness_key_scalar, messages_consensus::ConsensusTransactionKind};
     use tokio::sync::mpsc;
 
     use crate::{
@@ -900,7 +893,7 @@ mod tests {
                 Arc::downgrade(&epoch_store),
                 Box::new(consensus_adapter.clone()),
                 iota_network::randomness::Handle::new_stub(),
-                validator.authority_key_pair(),
+                randomness_key_scalar(validator.authority_key_pair()),
             )
             .await
             .unwrap();
@@ -1031,7 +1024,7 @@ mod tests {
                 Arc::downgrade(&epoch_store),
                 Box::new(consensus_adapter.clone()),
                 iota_network::randomness::Handle::new_stub(),
-                validator.authority_key_pair(),
+                randomness_key_scalar(validator.authority_key_pair()),
             )
             .await
             .unwrap();
@@ -294,7 +294,7 @@ mod tests {
             s.with_mutated(&[1, 2, 3]); // begin forming a tx but never execute it
             let outputs = s.take_outputs();
 
-            let tx1 = s.make_signed_transaction(&outputs.transaction);
+            let tx1 = s.make_signed_transaction(&outputs.transaction).await;
 
             s.cache
                 .acquire_transaction_locks(&s.epoch_store, &[new1, new2], tx1)
@@ -305,7 +305,7 @@ mod tests {
             // something to insert into the table.
             s.with_created(&[4, 5]);
             let tx2 = s.take_outputs().transaction.clone();
-            let tx2 = s.make_signed_transaction(&tx2);
+            let tx2 = s.make_signed_transaction(&tx2).await;
 
             // both locks are held by tx1, so this should fail
             s.cache
@@ -346,7 +346,7 @@ mod tests {
             s.with_mutated(&[1, 2]); // begin forming a tx but never execute it
             let outputs = s.take_outputs();
 
-            let tx = s.make_signed_transaction(&outputs.transaction);
+            let tx = s.make_signed_transaction(&outputs.transaction).await;
 
             // fails because we are referring to an old object
             s.cache
@@ -382,7 +382,7 @@ mod tests {
             s.with_mutated(&[1, 2]); // begin forming a tx but never execute it
             let outputs = s.take_outputs();
 
-            let tx = s.make_signed_transaction(&outputs.transaction);
+            let tx = s.make_signed_transaction(&outputs.transaction).await;
 
             // fails because we are referring to an old object
             s.cache
@@ -394,7 +394,7 @@ mod tests {
             // something to insert into the table.
             s.with_created(&[4, 5]);
             let tx2 = s.take_outputs().transaction.clone();
-            let tx2 = s.make_signed_transaction(&tx2);
+            let tx2 = s.make_signed_transaction(&tx2).await;
 
             // succeeds because the above call releases the lock on new1 after failing
             // to get the lock on old2
@@ -421,7 +421,7 @@ mod tests {
             s.with_mutated(&[1, 2]);
             let outputs = s.take_outputs();
 
-            let tx2 = s.make_signed_transaction(&outputs.transaction);
+            let tx2 = s.make_signed_transaction(&outputs.transaction).await;
             // assert that acquire_transaction_locks is sync in non-simtest, which causes
             // the fail_point_async! macros above to be elided
             s.cache
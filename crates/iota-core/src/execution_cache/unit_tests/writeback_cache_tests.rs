@@ -525,13 +525,18 @@ impl Scenario {
         self.object(short_id).compute_object_reference()
     }
 
-    pub fn make_signed_transaction(&self, tx: &VerifiedTransaction) -> VerifiedSignedTransaction {
-        VerifiedSignedTransaction::new(
+    pub async fn make_signed_transaction(
+        &self,
+        tx: &VerifiedTransaction,
+    ) -> VerifiedSignedTransaction {
+        VerifiedSignedTransaction::try_new(
             self.epoch_store.epoch(),
             tx.clone(),
             self.authority.name,
             &*self.authority.secret,
         )
+        .await
+        .unwrap()
     }
 }
 
@@ -1095,8 +1100,8 @@ async fn test_concurrent_lockers() {
         s.with_created(&[d]);
         let tx2 = s.take_outputs();
 
-        let tx1 = s.make_signed_transaction(&tx1.transaction);
-        let tx2 = s.make_signed_transaction(&tx2.transaction);
+        let tx1 = s.make_signed_transaction(&tx1.transaction).await;
+        let tx2 = s.make_signed_transaction(&tx2.transaction).await;
 
         txns.push((tx1, tx2, a_ref, b_ref));
     }
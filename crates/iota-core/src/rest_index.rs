@@ -21,13 +21,18 @@ use iota_types::{
         BackingPackageStore, DynamicFieldIndexInfo, DynamicFieldKey, error::Error as StorageError,
     },
 };
-use move_core_types::language_storage::StructTag;
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{StructTag, TypeTag},
+};
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use serde::{Deserialize, Serialize};
-use tracing::{debug, info};
+use tracing::{debug, error, info};
 use typed_store::{
     DBMapUtils, TypedStoreError,
-    rocks::{DBMap, MetricConf},
+    rocks::{DBMap, DBOptions, MetricConf, default_db_options},
+    rocksdb::{CompactionDecision, MergeOperands},
     traits::{Map, TableSummary, TypedStoreDebug},
 };
 
@@ -39,7 +44,7 @@ use crate::{
     checkpoints::CheckpointStore,
 };
 
-const CURRENT_DB_VERSION: u64 = 0;
+const CURRENT_DB_VERSION: u64 = 1;
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 struct MetadataInfo {
@@ -102,6 +107,71 @@ impl CoinIndexInfo {
     }
 }
 
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct BalanceKey {
+    pub owner: IotaAddress,
+    pub coin_type: StructTag,
+}
+
+impl BalanceKey {
+    fn new(owner: IotaAddress, coin_type: StructTag) -> Self {
+        Self { owner, coin_type }
+    }
+
+    /// A key that sorts before the keys of all coin types owned by `owner`.
+    ///
+    /// Keys are ordered by their serialized form, which starts with the address
+    /// of the coin type. No coin type is defined at address `0x0`.
+    fn lower_bound(owner: IotaAddress) -> Self {
+        Self::new(owner, StructTag {
+            address: AccountAddress::ZERO,
+            module: Identifier::new("coin").unwrap(),
+            name: Identifier::new("Coin").unwrap(),
+            type_params: vec![],
+        })
+    }
+}
+
+/// The balance of an owner for a coin type, stored as deltas which are summed
+/// up by the merge operator of the `balance` table.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Debug, Default)]
+pub struct BalanceIndexInfo {
+    pub balance_delta: i128,
+    pub coin_object_count_delta: i64,
+}
+
+impl BalanceIndexInfo {
+    fn add_coin(&mut self, value: u64) {
+        self.balance_delta += value as i128;
+        self.coin_object_count_delta += 1;
+    }
+
+    fn remove_coin(&mut self, value: u64) {
+        self.balance_delta -= value as i128;
+        self.coin_object_count_delta -= 1;
+    }
+
+    fn merge(self, other: Self) -> Self {
+        Self {
+            balance_delta: self.balance_delta + other.balance_delta,
+            coin_object_count_delta: self.coin_object_count_delta + other.coin_object_count_delta,
+        }
+    }
+
+    pub fn balance(&self) -> u64 {
+        self.balance_delta.clamp(0, u64::MAX as i128) as u64
+    }
+
+    pub fn coin_object_count(&self) -> u64 {
+        self.coin_object_count_delta.max(0) as u64
+    }
+
+    /// Whether the owner no longer holds any coins of this type.
+    pub fn is_empty(&self) -> bool {
+        self.coin_object_count_delta <= 0
+    }
+}
+
 /// RocksDB tables for the RestIndexStore
 ///
 /// Anytime a new table is added, or and existing one has it's schema changed,
@@ -145,6 +215,15 @@ struct IndexStoreTables {
     /// Allows looking up information related to published Coins, like the
     /// ObjectID of its coorisponding CoinMetadata.
     coin: DBMap<CoinIndexKey, CoinIndexInfo>,
+
+    /// An index of the balances of user accounts, per coin type.
+    ///
+    /// Entries are updated with deltas through a merge operator, so that
+    /// checkpoints can be indexed without reading the previous balance. Allows
+    /// an efficient iterator to list the balances of all coin types owned by a
+    /// specific user account.
+    #[default_options_override_fn = "balance_table_options"]
+    balance: DBMap<BalanceKey, BalanceIndexInfo>,
     // NOTE: Authors and Reviewers before adding any new tables ensure that they are either:
     // - bounded in size by the live object set
     // - are prune-able and have corresponding logic in the `prune` function
@@ -294,6 +373,7 @@ impl IndexStoreTables {
             .executor()
             .type_layout_resolver(Box::new(package_store));
         let mut batch = self.owner.batch();
+        let mut balance_index = HashMap::<BalanceKey, BalanceIndexInfo>::new();
         let mut object_scanned: u64 = 0;
         for object in authority_store
             .perpetual_tables
@@ -313,6 +393,14 @@ impl IndexStoreTables {
                     let owner_key = OwnerIndexKey::new(owner, object.id());
                     let owner_info = OwnerIndexInfo::new(&object);
                     batch.insert_batch(&self.owner, [(owner_key, owner_info)])?;
+
+                    // Balance Index
+                    if let Some((coin_type, value)) = get_coin_balance(&object) {
+                        balance_index
+                            .entry(BalanceKey::new(owner, coin_type))
+                            .or_default()
+                            .add_coin(value);
+                    }
                 }
 
                 // Dynamic Field Index
@@ -353,6 +441,9 @@ impl IndexStoreTables {
             }
         }
 
+        // Coins of the same owner may be indexed by other tasks, so merge rather than
+        // insert the balances.
+        batch.merge_batch(&self.balance, balance_index)?;
         batch.write()?;
         Ok(())
     }
@@ -404,6 +495,7 @@ impl IndexStoreTables {
         // object indexes
         {
             let mut coin_index = HashMap::new();
+            let mut balance_index = HashMap::<BalanceKey, BalanceIndexInfo>::new();
 
             for tx in &checkpoint.transactions {
                 // determine changes from removed objects
@@ -412,6 +504,13 @@ impl IndexStoreTables {
                         Owner::AddressOwner(address) => {
                             let owner_key = OwnerIndexKey::new(*address, removed_object.id());
                             batch.delete_batch(&self.owner, [owner_key])?;
+
+                            if let Some((coin_type, value)) = get_coin_balance(removed_object) {
+                                balance_index
+                                    .entry(BalanceKey::new(*address, coin_type))
+                                    .or_default()
+                                    .remove_coin(value);
+                            }
                         }
                         Owner::ObjectOwner(object_id) => {
                             batch.delete_batch(&self.dynamic_field, [DynamicFieldKey::new(
@@ -426,6 +525,17 @@ impl IndexStoreTables {
                 // determine changes from changed objects
                 for (object, old_object) in tx.changed_objects() {
                     if let Some(old_object) = old_object {
+                        // The value of a coin may change without a change of owner, so
+                        // always replace the old balance with the new one.
+                        if let Owner::AddressOwner(address) = old_object.owner() {
+                            if let Some((coin_type, value)) = get_coin_balance(old_object) {
+                                balance_index
+                                    .entry(BalanceKey::new(*address, coin_type))
+                                    .or_default()
+                                    .remove_coin(value);
+                            }
+                        }
+
                         if old_object.owner() != object.owner() {
                             match old_object.owner() {
                                 Owner::AddressOwner(address) => {
@@ -449,6 +559,13 @@ impl IndexStoreTables {
                             let owner_key = OwnerIndexKey::new(*owner, object.id());
                             let owner_info = OwnerIndexInfo::new(object);
                             batch.insert_batch(&self.owner, [(owner_key, owner_info)])?;
+
+                            if let Some((coin_type, value)) = get_coin_balance(object) {
+                                balance_index
+                                    .entry(BalanceKey::new(*owner, coin_type))
+                                    .or_default()
+                                    .add_coin(value);
+                            }
                         }
                         Owner::ObjectOwner(parent) => {
                             if let Some(field_info) =
@@ -487,6 +604,12 @@ impl IndexStoreTables {
             }
 
             batch.insert_batch(&self.coin, coin_index)?;
+            batch.merge_batch(
+                &self.balance,
+                balance_index
+                    .into_iter()
+                    .filter(|(_, info)| *info != BalanceIndexInfo::default()),
+            )?;
         }
 
         batch.write()?;
@@ -551,6 +674,24 @@ impl IndexStoreTables {
         };
         self.coin.get(&key)
     }
+
+    fn balance_iter(
+        &self,
+        owner: IotaAddress,
+        cursor: Option<StructTag>,
+    ) -> Result<impl Iterator<Item = (BalanceKey, BalanceIndexInfo)> + '_, TypedStoreError> {
+        let start = match cursor {
+            Some(coin_type) => BalanceKey::new(owner, coin_type),
+            None => BalanceKey::lower_bound(owner),
+        };
+
+        Ok(self
+            .balance
+            .unbounded_iter()
+            .skip_to(&start)?
+            .take_while(move |(key, _)| key.owner == owner)
+            .filter(|(_, info)| !info.is_empty()))
+    }
 }
 
 pub struct RestIndexStore {
@@ -648,6 +789,69 @@ impl RestIndexStore {
     ) -> Result<Option<CoinIndexInfo>, TypedStoreError> {
         self.tables.get_coin_info(coin_type)
     }
+
+    pub fn balance_iter(
+        &self,
+        owner: IotaAddress,
+        cursor: Option<StructTag>,
+    ) -> Result<impl Iterator<Item = (BalanceKey, BalanceIndexInfo)> + '_, TypedStoreError> {
+        self.tables.balance_iter(owner, cursor)
+    }
+}
+
+fn balance_table_options() -> DBOptions {
+    let mut options = default_db_options();
+    options
+        .options
+        .set_merge_operator_associative("balance_merge_operator", balance_delta_merge_operator);
+    options
+        .options
+        .set_compaction_filter("empty_balance_filter", empty_balance_compaction_filter);
+    options
+}
+
+/// Drops balances that sum up to zero. This is safe as a missing entry is
+/// equivalent to an empty balance when merging later deltas.
+fn empty_balance_compaction_filter(_level: u32, _key: &[u8], value: &[u8]) -> CompactionDecision {
+    match bcs::from_bytes::<BalanceIndexInfo>(value) {
+        Ok(info) if info == BalanceIndexInfo::default() => CompactionDecision::Remove,
+        _ => CompactionDecision::Keep,
+    }
+}
+
+/// Merge operator of the `balance` table, summing up the balance deltas.
+///
+/// Returns `None` if a value cannot be decoded, which fails the merge: reads of
+/// the entry and the compactions covering it return an error, rather than the
+/// node aborting.
+fn balance_delta_merge_operator(
+    key: &[u8],
+    existing_val: Option<&[u8]>,
+    operands: &MergeOperands,
+) -> Option<Vec<u8>> {
+    let decode = |value: &[u8]| {
+        bcs::from_bytes::<BalanceIndexInfo>(value)
+            .inspect_err(|e| error!("Invalid balance index entry for key {key:?}: {e}"))
+            .ok()
+    };
+
+    let mut info = match existing_val {
+        Some(value) => decode(value)?,
+        None => BalanceIndexInfo::default(),
+    };
+    for operand in operands.iter() {
+        info = info.merge(decode(operand)?);
+    }
+
+    bcs::to_bytes(&info).ok()
+}
+
+/// The coin type and value of an object, if it is a `Coin<T>`.
+fn get_coin_balance(object: &Object) -> Option<(StructTag, u64)> {
+    let TypeTag::Struct(coin_type) = object.coin_type_maybe()? else {
+        return None;
+    };
+    Some((*coin_type, object.as_coin_maybe()?.value()))
 }
 
 fn try_create_dynamic_field_info(
@@ -739,3 +943,113 @@ fn try_create_coin_index_info(object: &Object) -> Option<(CoinIndexKey, CoinInde
                 })
         })
 }
+
+#[cfg(test)]
+mod tests {
+    use iota_types::gas_coin::GAS;
+
+    use super::*;
+
+    fn coin(value: u64) -> BalanceIndexInfo {
+        let mut info = BalanceIndexInfo::default();
+        info.add_coin(value);
+        info
+    }
+
+    fn spent_coin(value: u64) -> BalanceIndexInfo {
+        let mut info = BalanceIndexInfo::default();
+        info.remove_coin(value);
+        info
+    }
+
+    fn merge(tables: &IndexStoreTables, key: &BalanceKey, delta: BalanceIndexInfo) {
+        let mut batch = tables.balance.batch();
+        batch
+            .merge_batch(&tables.balance, [(key.clone(), delta)])
+            .unwrap();
+        batch.write().unwrap();
+    }
+
+    #[test]
+    fn test_balance_merge_operator() {
+        let path = tempfile::tempdir().unwrap().into_path();
+        let tables = IndexStoreTables::open(&path);
+        let owner = IotaAddress::random_for_testing_only();
+        let key = BalanceKey::new(owner, GAS::type_());
+
+        // Receive two coins, then spend one of them.
+        merge(&tables, &key, coin(100));
+        merge(&tables, &key, coin(50));
+        merge(&tables, &key, spent_coin(100));
+        let info = tables.balance.get(&key).unwrap().unwrap();
+        assert_eq!(info.balance(), 50);
+        assert_eq!(info.coin_object_count(), 1);
+
+        // Deltas are merged on top of compacted values.
+        tables.balance.rocksdb.flush().unwrap();
+        tables.balance.compact_range_to_bottom(&key, &key).unwrap();
+        merge(&tables, &key, coin(25));
+        let info = tables.balance.get(&key).unwrap().unwrap();
+        assert_eq!(info.balance(), 75);
+        assert_eq!(info.coin_object_count(), 2);
+
+        // Balances of other owners are untouched.
+        let other = BalanceKey::new(IotaAddress::random_for_testing_only(), GAS::type_());
+        assert_eq!(tables.balance.get(&other).unwrap(), None);
+    }
+
+    #[test]
+    fn test_empty_balance_compaction_filter() {
+        let path = tempfile::tempdir().unwrap().into_path();
+        let tables = IndexStoreTables::open(&path);
+        let emptied = BalanceKey::new(IotaAddress::random_for_testing_only(), GAS::type_());
+        let kept = BalanceKey::new(IotaAddress::random_for_testing_only(), GAS::type_());
+
+        merge(&tables, &emptied, coin(100));
+        merge(&tables, &emptied, spent_coin(100));
+        merge(&tables, &kept, coin(100));
+        assert_eq!(
+            tables.balance.get(&emptied).unwrap(),
+            Some(BalanceIndexInfo::default())
+        );
+
+        // The first compaction merges the deltas into a single value, which the
+        // compaction filter sees in the next one.
+        tables.balance.rocksdb.flush().unwrap();
+        for key in [&emptied, &kept] {
+            for _ in 0..2 {
+                tables.balance.compact_range_to_bottom(key, key).unwrap();
+            }
+        }
+        assert_eq!(tables.balance.get(&emptied).unwrap(), None);
+        assert_eq!(tables.balance.get(&kept).unwrap(), Some(coin(100)));
+
+        // A removed balance starts over from zero.
+        merge(&tables, &emptied, coin(10));
+        assert_eq!(tables.balance.get(&emptied).unwrap(), Some(coin(10)));
+    }
+
+    #[test]
+    fn test_balance_bookkeeping() {
+        let mut info = coin(100);
+        info = info.merge(coin(200));
+        assert_eq!((info.balance(), info.coin_object_count()), (300, 2));
+        assert!(!info.is_empty());
+
+        // Splitting a coin mutates it and creates a new one.
+        info = info.merge(spent_coin(200)).merge(coin(150)).merge(coin(50));
+        assert_eq!((info.balance(), info.coin_object_count()), (300, 3));
+
+        // Merging coins mutates the primary one and deletes the others.
+        info = info
+            .merge(spent_coin(100))
+            .merge(spent_coin(50))
+            .merge(coin(150));
+        assert_eq!((info.balance(), info.coin_object_count()), (300, 2));
+
+        // Transferring the coins away empties the balance.
+        info = info.merge(spent_coin(150)).merge(spent_coin(150));
+        assert_eq!(info, BalanceIndexInfo::default());
+        assert!(info.is_empty());
+    }
+}
@@ -16,8 +16,8 @@ use iota_types::{
     },
     object::Object,
     storage::{
-        AccountOwnedObjectInfo, CoinInfo, DynamicFieldIndexInfo, DynamicFieldKey, ObjectKey,
-        ObjectStore, ReadStore, RestStateReader, WriteStore,
+        AccountBalanceInfo, AccountOwnedObjectInfo, CoinInfo, DynamicFieldIndexInfo,
+        DynamicFieldKey, ObjectKey, ObjectStore, ReadStore, RestStateReader, WriteStore,
         error::{Error as StorageError, Result},
     },
     transaction::VerifiedTransaction,
@@ -31,7 +31,7 @@ use crate::{
     checkpoints::CheckpointStore,
     epoch::committee_store::CommitteeStore,
     execution_cache::ExecutionCacheTraitPointers,
-    rest_index::{CoinIndexInfo, OwnerIndexInfo, OwnerIndexKey, RestIndexStore},
+    rest_index::{BalanceKey, CoinIndexInfo, OwnerIndexInfo, OwnerIndexKey, RestIndexStore},
 };
 
 #[derive(Clone)]
@@ -579,4 +579,21 @@ impl RestStateReader for RestReadStore {
             )
             .pipe(Ok)
     }
+
+    fn account_balances_iter(
+        &self,
+        owner: IotaAddress,
+        cursor: Option<StructTag>,
+    ) -> iota_types::storage::error::Result<Box<dyn Iterator<Item = AccountBalanceInfo> + '_>> {
+        let iter = self.index()?.balance_iter(owner, cursor)?.map(
+            |(BalanceKey { owner, coin_type }, info)| AccountBalanceInfo {
+                owner,
+                coin_type,
+                balance: info.balance(),
+                coin_object_count: info.coin_object_count(),
+            },
+        );
+
+        Ok(Box::new(iter) as _)
+    }
 }
@@ -176,6 +176,7 @@ impl LocalAuthorityClient {
         let epoch_store = state.epoch_store_for_testing();
         let signed_effects = match state
             .get_signed_effects_and_maybe_resign(&tx_digest, &epoch_store)
+            .await
         {
             Ok(Some(effects)) => effects,
             _ => {
@@ -186,7 +187,7 @@ impl LocalAuthorityClient {
                 // let certificate = certificate.verify(epoch_store.committee())?;
                 state.enqueue_certificates_for_execution(vec![certificate.clone()], &epoch_store);
                 let effects = state.notify_read_effects(&certificate).await?;
-                state.sign_effects(effects, &epoch_store)?
+                state.sign_effects(effects, &epoch_store).await?
             }
         }
         .into_inner();
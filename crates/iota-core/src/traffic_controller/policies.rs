@@ -15,20 +15,25 @@ use std::{
 
 use count_min_sketch::CountMinSketch32;
 use iota_metrics::spawn_monitored_task;
-use iota_types::traffic_control::{FreqThresholdConfig, PolicyConfig, PolicyType, Weight};
+use iota_types::traffic_control::{
+    FreqThresholdConfig, PolicyConfig, PolicyType, ReputationConfig, TokenBucketConfig, Weight,
+};
 use parking_lot::RwLock;
 use tracing::info;
 
 const HIGHEST_RATES_CAPACITY: usize = 20;
 
+/// Interval at which per-client state that has fully replenished is dropped.
+const CLIENT_LEVELS_PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
 /// The type of request client.
-#[derive(Hash, Eq, PartialEq, Debug)]
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Copy)]
 enum ClientType {
     Direct,
     ThroughFullnode,
 }
 
-#[derive(Hash, Eq, PartialEq, Debug)]
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Copy)]
 struct SketchKey(IpAddr, ClientType);
 
 struct HighestRates {
@@ -228,6 +233,82 @@ impl TrafficSketch {
     }
 }
 
+/// Parameters of a level that is replenished linearly over time.
+#[derive(Clone, Copy, Debug)]
+struct LevelParams {
+    capacity: f64,
+    replenish_per_sec: f64,
+}
+
+/// Tracks a per-client level that is lowered by tallies and replenished
+/// linearly over time, up to a capacity. This is the shared state behind
+/// the token bucket (level = available tokens) and reputation (level =
+/// score) policies. Clients that are not tracked are considered to be at
+/// full capacity, so entries that have fully replenished are periodically
+/// dropped in order to bound memory usage to recently active clients.
+struct ClientLevels {
+    direct: LevelParams,
+    proxied: LevelParams,
+    levels: HashMap<SketchKey, (f64, SystemTime)>,
+    last_prune_time: SystemTime,
+}
+
+impl ClientLevels {
+    fn new(direct: LevelParams, proxied: LevelParams) -> Self {
+        Self {
+            direct,
+            proxied,
+            levels: HashMap::new(),
+            last_prune_time: SystemTime::now(),
+        }
+    }
+
+    fn params(&self, client_type: ClientType) -> LevelParams {
+        match client_type {
+            ClientType::Direct => self.direct,
+            ClientType::ThroughFullnode => self.proxied,
+        }
+    }
+
+    fn replenish(params: LevelParams, level: f64, last_update: SystemTime, now: SystemTime) -> f64 {
+        // tallies may be received slightly out of order, in which case
+        // we simply do not replenish
+        let elapsed = now.duration_since(last_update).unwrap_or_default();
+        (level + elapsed.as_secs_f64() * params.replenish_per_sec).min(params.capacity)
+    }
+
+    /// Returns a mutable reference to the current level of the client, after
+    /// replenishing it for the time elapsed since its last update.
+    fn level_mut(&mut self, key: SketchKey, now: SystemTime) -> &mut f64 {
+        let params = self.params(key.1);
+        let (level, last_update) = self.levels.entry(key).or_insert((params.capacity, now));
+        *level = Self::replenish(params, *level, *last_update, now);
+        *last_update = (*last_update).max(now);
+        level
+    }
+
+    fn maybe_prune(&mut self, now: SystemTime) {
+        if now.duration_since(self.last_prune_time).unwrap_or_default()
+            < CLIENT_LEVELS_PRUNE_INTERVAL
+        {
+            return;
+        }
+        let (direct, proxied) = (self.direct, self.proxied);
+        self.levels.retain(|key, (level, last_update)| {
+            let params = match key.1 {
+                ClientType::Direct => direct,
+                ClientType::ThroughFullnode => proxied,
+            };
+            Self::replenish(params, *level, *last_update, now) < params.capacity
+        });
+        self.last_prune_time = now;
+    }
+
+    fn len(&self) -> usize {
+        self.levels.len()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct TrafficTally {
     pub direct: Option<IpAddr>,
@@ -271,6 +352,8 @@ pub trait Policy {
 // not object safe, so we can't use a trait object instead
 pub enum TrafficControlPolicy {
     FreqThreshold(FreqThresholdPolicy),
+    TokenBucket(TokenBucketPolicy),
+    Reputation(ReputationPolicy),
     NoOp(NoOpPolicy),
     // Test policies below this point
     TestNConnIP(TestNConnIPPolicy),
@@ -282,6 +365,8 @@ impl Policy for TrafficControlPolicy {
         match self {
             TrafficControlPolicy::NoOp(policy) => policy.handle_tally(tally),
             TrafficControlPolicy::FreqThreshold(policy) => policy.handle_tally(tally),
+            TrafficControlPolicy::TokenBucket(policy) => policy.handle_tally(tally),
+            TrafficControlPolicy::Reputation(policy) => policy.handle_tally(tally),
             TrafficControlPolicy::TestNConnIP(policy) => policy.handle_tally(tally),
             TrafficControlPolicy::TestPanicOnInvocation(policy) => policy.handle_tally(tally),
         }
@@ -291,6 +376,8 @@ impl Policy for TrafficControlPolicy {
         match self {
             TrafficControlPolicy::NoOp(policy) => policy.policy_config(),
             TrafficControlPolicy::FreqThreshold(policy) => policy.policy_config(),
+            TrafficControlPolicy::TokenBucket(policy) => policy.policy_config(),
+            TrafficControlPolicy::Reputation(policy) => policy.policy_config(),
             TrafficControlPolicy::TestNConnIP(policy) => policy.policy_config(),
             TrafficControlPolicy::TestPanicOnInvocation(policy) => policy.policy_config(),
         }
@@ -310,6 +397,12 @@ impl TrafficControlPolicy {
             PolicyType::FreqThreshold(freq_threshold_config) => Self::FreqThreshold(
                 FreqThresholdPolicy::new(policy_config, freq_threshold_config),
             ),
+            PolicyType::TokenBucket(token_bucket_config) => {
+                Self::TokenBucket(TokenBucketPolicy::new(policy_config, token_bucket_config))
+            }
+            PolicyType::Reputation(reputation_config) => {
+                Self::Reputation(ReputationPolicy::new(policy_config, reputation_config))
+            }
             PolicyType::TestNConnIP(n) => {
                 Self::TestNConnIP(TestNConnIPPolicy::new(policy_config, n).await)
             }
@@ -400,6 +493,143 @@ impl FreqThresholdPolicy {
     }
 }
 
+pub struct TokenBucketPolicy {
+    config: PolicyConfig,
+    buckets: ClientLevels,
+}
+
+impl TokenBucketPolicy {
+    pub fn new(
+        config: PolicyConfig,
+        TokenBucketConfig {
+            client_burst,
+            client_refill_per_sec,
+            proxied_client_burst,
+            proxied_client_refill_per_sec,
+        }: TokenBucketConfig,
+    ) -> Self {
+        let buckets = ClientLevels::new(
+            LevelParams {
+                capacity: client_burst as f64,
+                replenish_per_sec: client_refill_per_sec,
+            },
+            LevelParams {
+                capacity: proxied_client_burst as f64,
+                replenish_per_sec: proxied_client_refill_per_sec,
+            },
+        );
+        Self { config, buckets }
+    }
+
+    /// Number of clients whose buckets are not currently full.
+    pub fn tracked_clients(&self) -> usize {
+        self.buckets.len()
+    }
+
+    pub fn handle_tally(&mut self, tally: TrafficTally) -> PolicyResponse {
+        let now = tally.timestamp;
+        self.buckets.maybe_prune(now);
+        let block_client = tally
+            .direct
+            .filter(|source| !self.try_consume(SketchKey(*source, ClientType::Direct), now));
+        let block_proxied_client = tally.through_fullnode.filter(|source| {
+            !self.try_consume(SketchKey(*source, ClientType::ThroughFullnode), now)
+        });
+        PolicyResponse {
+            block_client,
+            block_proxied_client,
+        }
+    }
+
+    /// Takes a token from the client's bucket, returning false if
+    /// the bucket is empty.
+    fn try_consume(&mut self, key: SketchKey, now: SystemTime) -> bool {
+        let tokens = self.buckets.level_mut(key, now);
+        if *tokens >= 1.0 {
+            *tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn policy_config(&self) -> &PolicyConfig {
+        &self.config
+    }
+}
+
+pub struct ReputationPolicy {
+    config: PolicyConfig,
+    scores: ClientLevels,
+    client_penalty: f64,
+    proxied_client_penalty: f64,
+    block_threshold: f64,
+}
+
+impl ReputationPolicy {
+    pub fn new(
+        config: PolicyConfig,
+        ReputationConfig {
+            max_score,
+            client_penalty,
+            proxied_client_penalty,
+            recovery_per_sec,
+            block_threshold,
+        }: ReputationConfig,
+    ) -> Self {
+        let params = LevelParams {
+            capacity: max_score,
+            replenish_per_sec: recovery_per_sec,
+        };
+        Self {
+            config,
+            scores: ClientLevels::new(params, params),
+            client_penalty,
+            proxied_client_penalty,
+            block_threshold,
+        }
+    }
+
+    /// Number of clients whose score has not fully recovered.
+    pub fn tracked_clients(&self) -> usize {
+        self.scores.len()
+    }
+
+    pub fn handle_tally(&mut self, tally: TrafficTally) -> PolicyResponse {
+        let now = tally.timestamp;
+        self.scores.maybe_prune(now);
+        let block_client = tally
+            .direct
+            .filter(|source| self.penalize(SketchKey(*source, ClientType::Direct), now));
+        let block_proxied_client = tally
+            .through_fullnode
+            .filter(|source| self.penalize(SketchKey(*source, ClientType::ThroughFullnode), now));
+        PolicyResponse {
+            block_client,
+            block_proxied_client,
+        }
+    }
+
+    /// Lowers the client's score by the penalty for its client type,
+    /// returning true if the client should be blocked.
+    fn penalize(&mut self, key: SketchKey, now: SystemTime) -> bool {
+        let penalty = match key.1 {
+            ClientType::Direct => self.client_penalty,
+            ClientType::ThroughFullnode => self.proxied_client_penalty,
+        };
+        let block_threshold = self.block_threshold;
+        let score = self.scores.level_mut(key, now);
+        // scores are floored at the block threshold so that a client
+        // recovers within a bounded time once its block expires
+        *score = (*score - penalty).max(block_threshold);
+        *score <= block_threshold
+    }
+
+    fn policy_config(&self) -> &PolicyConfig {
+        &self.config
+    }
+}
+
 ////////////// *** Test policies below this point *** //////////////
 
 #[derive(Clone)]
@@ -657,6 +887,113 @@ mod tests {
         assert_eq!(proxied_rate, 1);
     }
 
+    fn tally_at(direct: IpAddr, through_fullnode: IpAddr, timestamp: SystemTime) -> TrafficTally {
+        TrafficTally {
+            direct: Some(direct),
+            through_fullnode: Some(through_fullnode),
+            error_weight: Weight::one(),
+            spam_weight: Weight::one(),
+            timestamp,
+        }
+    }
+
+    #[sim_test]
+    async fn test_token_bucket_policy() {
+        // proxied clients may burst 3 tallies and are refilled with 1 token
+        // per second, direct clients may burst 5 tallies.
+        let mut policy = TokenBucketPolicy::new(PolicyConfig::default(), TokenBucketConfig {
+            client_burst: 5,
+            client_refill_per_sec: 1.0,
+            proxied_client_burst: 3,
+            proxied_client_refill_per_sec: 1.0,
+        });
+        let fullnode = IpAddr::V4(Ipv4Addr::new(8, 7, 6, 5));
+        let alice = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+        let bob = IpAddr::V4(Ipv4Addr::new(4, 3, 2, 1));
+        let start = SystemTime::now();
+
+        // alice exhausts her burst and is blocked on the 4th tally
+        for i in 0..3 {
+            let response = policy.handle_tally(tally_at(fullnode, alice, start));
+            assert_eq!(response.block_client, None, "Blocked at i = {}", i);
+            assert_eq!(response.block_proxied_client, None, "Blocked at i = {}", i);
+        }
+        let response = policy.handle_tally(tally_at(fullnode, alice, start));
+        assert_eq!(response.block_client, None);
+        assert_eq!(response.block_proxied_client, Some(alice));
+
+        // bob has his own bucket, but the fullnode bucket is shared and
+        // now exhausted after the 6th tally
+        let response = policy.handle_tally(tally_at(fullnode, bob, start));
+        assert_eq!(response.block_client, None);
+        assert_eq!(response.block_proxied_client, None);
+        let response = policy.handle_tally(tally_at(fullnode, bob, start));
+        assert_eq!(response.block_client, Some(fullnode));
+        assert_eq!(response.block_proxied_client, None);
+
+        // after 2 seconds, alice has been refilled with 2 tokens
+        let later = start + Duration::from_secs(2);
+        for i in 0..2 {
+            let response = policy.handle_tally(tally_at(fullnode, alice, later));
+            assert_eq!(response.block_proxied_client, None, "Blocked at i = {}", i);
+        }
+        let response = policy.handle_tally(tally_at(fullnode, alice, later));
+        assert_eq!(response.block_proxied_client, Some(alice));
+        assert_eq!(policy.tracked_clients(), 3);
+
+        // once buckets are full again, client state is pruned
+        let much_later = later + CLIENT_LEVELS_PRUNE_INTERVAL;
+        let response = policy.handle_tally(tally_at(fullnode, bob, much_later));
+        assert_eq!(response.block_client, None);
+        assert_eq!(response.block_proxied_client, None);
+        assert_eq!(policy.tracked_clients(), 2);
+    }
+
+    #[sim_test]
+    async fn test_reputation_policy() {
+        let mut policy = ReputationPolicy::new(PolicyConfig::default(), ReputationConfig {
+            max_score: 100.0,
+            client_penalty: 1.0,
+            proxied_client_penalty: 25.0,
+            recovery_per_sec: 5.0,
+            block_threshold: 10.0,
+        });
+        let fullnode = IpAddr::V4(Ipv4Addr::new(8, 7, 6, 5));
+        let alice = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+        let start = SystemTime::now();
+
+        // scores: 75, 50, 25 -> not blocked, then floored at 10 -> blocked
+        for i in 0..3 {
+            let response = policy.handle_tally(tally_at(fullnode, alice, start));
+            assert_eq!(response.block_proxied_client, None, "Blocked at i = {}", i);
+        }
+        let response = policy.handle_tally(tally_at(fullnode, alice, start));
+        assert_eq!(response.block_client, None);
+        assert_eq!(response.block_proxied_client, Some(alice));
+
+        // score recovers to 10 + 6 * 5 = 40, so alice survives a single
+        // error, but not a second one
+        let later = start + Duration::from_secs(6);
+        let response = policy.handle_tally(tally_at(fullnode, alice, later));
+        assert_eq!(response.block_proxied_client, None);
+        let response = policy.handle_tally(tally_at(fullnode, alice, later));
+        assert_eq!(response.block_proxied_client, Some(alice));
+
+        // recovery is capped at max score, so after a long break alice
+        // is back to the initial 3 errors of slack
+        let much_later = later + CLIENT_LEVELS_PRUNE_INTERVAL;
+        for i in 0..3 {
+            let response = policy.handle_tally(tally_at(fullnode, alice, much_later));
+            assert_eq!(response.block_proxied_client, None, "Blocked at i = {}", i);
+        }
+        let response = policy.handle_tally(tally_at(fullnode, alice, much_later));
+        assert_eq!(response.block_proxied_client, Some(alice));
+
+        // fully recovered scores were pruned, and both the fullnode and
+        // alice have been penalized since
+        assert_eq!(policy.tracked_clients(), 2);
+    }
+
     #[sim_test]
     async fn test_traffic_sketch_mem_estimate() {
         // Test for getting a rough estimate of memory usage for the traffic sketch
@@ -2338,7 +2338,8 @@ async fn test_handle_confirmation_transaction_receiver_equal_sender() {
         object.compute_object_reference(),
         gas_object.compute_object_reference(),
         &authority_state,
-    );
+    )
+    .await;
     let effects = authority_state
         .execute_certificate(
             &certified_transfer_transaction,
@@ -2378,7 +2379,8 @@ async fn test_handle_confirmation_transaction_ok() {
         object.compute_object_reference(),
         gas_object.compute_object_reference(),
         &authority_state,
-    );
+    )
+    .await;
 
     let old_account = authority_state
         .get_object(&object_id)
@@ -2452,7 +2454,8 @@ async fn test_handle_confirmation_transaction_idempotent() {
         object.compute_object_reference(),
         gas_object.compute_object_reference(),
         &authority_state,
-    );
+    )
+    .await;
 
     let effects = authority_state
         .execute_certificate(
@@ -2607,7 +2610,8 @@ async fn test_move_call_insufficient_gas() {
             .unwrap()
             .compute_object_reference(),
         &authority_state,
-    );
+    )
+    .await;
     let effects = authority_state
         .execute_certificate(
             &certified_transfer_transaction,
@@ -2972,7 +2976,8 @@ async fn test_idempotent_reversed_confirmation() {
         object_ref,
         gas_object_ref,
         &authority_state,
-    );
+    )
+    .await;
     let result1 = authority_state
         .execute_certificate(
             &certified_transfer_transaction,
@@ -3288,7 +3293,7 @@ async fn test_transfer_iota_no_amount() {
         .await
         .unwrap();
 
-    let certificate = init_certified_transaction(transaction.into(), &authority_state);
+    let certificate = init_certified_transaction(transaction.into(), &authority_state).await;
     let effects = authority_state
         .execute_certificate(&certificate, &authority_state.epoch_store_for_testing())
         .await
@@ -3334,7 +3339,7 @@ async fn test_transfer_iota_with_amount() {
         rgp,
     );
     let transaction = to_sender_signed_transaction(tx_data, &sender_key);
-    let certificate = init_certified_transaction(transaction, &authority_state);
+    let certificate = init_certified_transaction(transaction, &authority_state).await;
     let effects = authority_state
         .execute_certificate(&certificate, &authority_state.epoch_store_for_testing())
         .await
@@ -3388,7 +3393,7 @@ async fn test_store_revert_transfer_iota() {
     );
 
     let transaction = to_sender_signed_transaction(tx_data, &sender_key);
-    let certificate = init_certified_transaction(transaction, &authority_state);
+    let certificate = init_certified_transaction(transaction, &authority_state).await;
     let tx_digest = *certificate.digest();
     authority_state
         .execute_certificate(&certificate, &authority_state.epoch_store_for_testing())
@@ -3453,7 +3458,7 @@ async fn test_store_revert_wrap_move_call() {
         &sender_key,
     );
 
-    let wrap_cert = init_certified_transaction(wrap_txn, &authority_state);
+    let wrap_cert = init_certified_transaction(wrap_txn, &authority_state).await;
     let wrap_digest = *wrap_cert.digest();
 
     let wrap_effects = authority_state
@@ -3540,7 +3545,7 @@ async fn test_store_revert_unwrap_move_call() {
         &sender_key,
     );
 
-    let unwrap_cert = init_certified_transaction(unwrap_txn, &authority_state);
+    let unwrap_cert = init_certified_transaction(unwrap_txn, &authority_state).await;
     let unwrap_digest = *unwrap_cert.digest();
 
     let unwrap_effects = authority_state
@@ -3645,7 +3650,7 @@ async fn create_and_retrieve_df_info(function: &IdentStr) -> (IotaAddress, Vec<D
         &sender_key,
     );
 
-    let add_cert = init_certified_transaction(add_txn, &authority_state);
+    let add_cert = init_certified_transaction(add_txn, &authority_state).await;
 
     let add_effects = authority_state
         .try_execute_for_test(&add_cert)
@@ -3794,7 +3799,7 @@ async fn test_store_revert_add_ofield() {
         &sender_key,
     );
 
-    let add_cert = init_certified_transaction(add_txn, &authority_state);
+    let add_cert = init_certified_transaction(add_txn, &authority_state).await;
     let add_digest = *add_cert.digest();
 
     let add_effects = authority_state
@@ -3906,7 +3911,7 @@ async fn test_store_revert_remove_ofield() {
         &sender_key,
     );
 
-    let remove_ofield_cert = init_certified_transaction(remove_ofield_txn, &authority_state);
+    let remove_ofield_cert = init_certified_transaction(remove_ofield_txn, &authority_state).await;
     let remove_ofield_digest = *remove_ofield_cert.digest();
 
     let remove_effects = authority_state
@@ -3972,7 +3977,8 @@ async fn test_iter_live_object_set() {
         obj.compute_object_reference(),
         gas_obj.compute_object_reference(),
         &authority,
-    );
+    )
+    .await;
     authority
         .execute_certificate(
             &certified_transfer_transaction,
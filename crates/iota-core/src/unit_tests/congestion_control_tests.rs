@@ -10,7 +10,7 @@ use iota_protocol_config::{
     Chain, PerObjectCongestionControlMode, ProtocolConfig, ProtocolVersion,
 };
 use iota_types::{
-    base_types::{IotaAddress, ObjectID, ObjectRef, SequenceNumber},
+    base_types::{IotaAddress, ObjectID, ObjectRef, SequenceNumber, SharedObjectCancellation},
     crypto::{AccountKeyPair, get_key_pair},
     digests::TransactionDigest,
     effects::{InputSharedObject, TransactionEffects, TransactionEffectsAPI},
@@ -40,7 +40,8 @@ pub const TEST_ONLY_GAS_PRICE: u64 = 1000;
 pub const TEST_ONLY_GAS_UNIT: u64 = 10_000;
 
 // Note that TestSetup is currently purposely created for
-// test_congestion_control_execution_cancellation.
+// test_congestion_control_execution_cancellation and
+// test_congestion_control_surcharge_cancellation.
 struct TestSetup {
     setup_authority_state: Arc<AuthorityState>,
     protocol_config: ProtocolConfig,
@@ -378,3 +379,183 @@ async fn test_congestion_control_execution_cancellation() {
     );
     assert_eq!(&effects, effects_2.data())
 }
+
+// Tests that with congestion surcharge pricing, a transaction whose gas price
+// doesn't cover the surcharge of its congested objects is cancelled right away,
+// with the surcharge surfaced in its effects.
+#[sim_test]
+async fn test_congestion_control_surcharge_cancellation() {
+    telemetry_subscribers::init_for_testing();
+
+    let test_setup = TestSetup::new().await;
+    let shared_object_1 = test_setup.create_shared_object().await;
+    let shared_object_2 = test_setup.create_shared_object().await;
+    let owned_object = test_setup.create_owned_object().await;
+    let genesis_objects = test_setup
+        .create_genesis_objects_for_new_authority_state(&[
+            shared_object_1.0,
+            shared_object_2.0,
+            owned_object.0,
+        ])
+        .await;
+
+    // Price the congested objects instead of deferring transactions, with a
+    // surcharge of the reference gas price for every multiple of the limit.
+    let mut protocol_config = test_setup.protocol_config.clone();
+    protocol_config.set_congestion_control_surcharge_pricing_for_testing(true);
+    protocol_config.set_congestion_surcharge_bps_for_testing(10_000);
+    // Allow deferral, so that the transaction could only be cancelled because of
+    // the surcharge.
+    protocol_config.set_max_deferral_rounds_for_congestion_control_for_testing(10);
+
+    let authority_state = TestAuthorityBuilder::new()
+        .with_reference_gas_price(TEST_ONLY_GAS_PRICE)
+        .with_protocol_config(protocol_config.clone())
+        .build()
+        .await;
+    authority_state
+        .insert_genesis_objects(&genesis_objects)
+        .await;
+    let authority_state_2 = TestAuthorityBuilder::new()
+        .with_reference_gas_price(TEST_ONLY_GAS_PRICE)
+        .with_protocol_config(protocol_config)
+        .build()
+        .await;
+    authority_state_2
+        .insert_genesis_objects(&genesis_objects)
+        .await;
+
+    // Initialize shared object queue so that any transaction touches
+    // shared_object_1 exceeds the limit by less than one multiple of it.
+    register_fail_point_arg("initial_congestion_tracker", move || {
+        Some(
+            SharedObjectCongestionTracker::new_with_initial_value_for_test(
+                &[(shared_object_1.0, 10)],
+                PerObjectCongestionControlMode::TotalGasBudget,
+            ),
+        )
+    });
+
+    // The transaction pays the reference gas price, which doesn't cover the
+    // surcharge.
+    let (congested_tx, effects) = update_objects(
+        &authority_state,
+        &test_setup.package,
+        &test_setup.sender,
+        &test_setup.sender_key,
+        &test_setup.gas_object_id,
+        &(shared_object_1.0, shared_object_1.1),
+        &(shared_object_2.0, shared_object_2.1),
+        &authority_state
+            .get_object(&owned_object.0)
+            .await
+            .unwrap()
+            .unwrap()
+            .compute_object_reference(),
+    )
+    .await;
+
+    let expected_error =
+        ExecutionFailureStatus::ExecutionCancelledDueToSharedObjectCongestionWithSurcharge {
+            congested_objects: CongestedObjects(vec![shared_object_1.0]),
+            surcharge: TEST_ONLY_GAS_PRICE,
+        };
+    assert_eq!(effects.status(), &ExecutionStatus::Failure {
+        error: expected_error.clone(),
+        command: None
+    });
+    assert_eq!(effects.input_shared_objects(), vec![
+        InputSharedObject::Cancelled(
+            shared_object_1.0,
+            SharedObjectCancellation::CongestedWithSurcharge(TEST_ONLY_GAS_PRICE).into()
+        ),
+        InputSharedObject::Cancelled(shared_object_2.0, SequenceNumber::CANCELLED_READ)
+    ]);
+
+    // Executing the transaction using the above effects should result in the same
+    // cancellation.
+    let cert = certify_shared_obj_transaction_no_execution(&authority_state_2, congested_tx)
+        .await
+        .unwrap();
+    authority_state_2
+        .epoch_store_for_testing()
+        .acquire_shared_locks_from_effects(
+            &VerifiedExecutableTransaction::new_from_certificate(cert.clone()),
+            &effects,
+            authority_state_2.get_object_cache_reader().as_ref(),
+        )
+        .await
+        .unwrap();
+    let (effects_2, execution_error) = authority_state_2.try_execute_for_test(&cert).await.unwrap();
+    assert_eq!(
+        execution_error.unwrap().to_execution_status().0,
+        expected_error
+    );
+    assert_eq!(&effects, effects_2.data())
+}
+
+// Tests that with congestion surcharge pricing enabled but no surcharge rate
+// configured, transactions touching congested objects are still deferred, and
+// therefore cancelled, as without surcharge pricing.
+#[sim_test]
+async fn test_congestion_control_surcharge_pricing_without_rate() {
+    telemetry_subscribers::init_for_testing();
+
+    let test_setup = TestSetup::new().await;
+    let shared_object_1 = test_setup.create_shared_object().await;
+    let shared_object_2 = test_setup.create_shared_object().await;
+    let owned_object = test_setup.create_owned_object().await;
+    let genesis_objects = test_setup
+        .create_genesis_objects_for_new_authority_state(&[
+            shared_object_1.0,
+            shared_object_2.0,
+            owned_object.0,
+        ])
+        .await;
+
+    let mut protocol_config = test_setup.protocol_config.clone();
+    protocol_config.set_congestion_control_surcharge_pricing_for_testing(true);
+    assert_eq!(protocol_config.congestion_surcharge_bps_as_option(), None);
+
+    let authority_state = TestAuthorityBuilder::new()
+        .with_reference_gas_price(TEST_ONLY_GAS_PRICE)
+        .with_protocol_config(protocol_config)
+        .build()
+        .await;
+    authority_state
+        .insert_genesis_objects(&genesis_objects)
+        .await;
+
+    register_fail_point_arg("initial_congestion_tracker", move || {
+        Some(
+            SharedObjectCongestionTracker::new_with_initial_value_for_test(
+                &[(shared_object_1.0, 10)],
+                PerObjectCongestionControlMode::TotalGasBudget,
+            ),
+        )
+    });
+
+    let (_, effects) = update_objects(
+        &authority_state,
+        &test_setup.package,
+        &test_setup.sender,
+        &test_setup.sender_key,
+        &test_setup.gas_object_id,
+        &(shared_object_1.0, shared_object_1.1),
+        &(shared_object_2.0, shared_object_2.1),
+        &authority_state
+            .get_object(&owned_object.0)
+            .await
+            .unwrap()
+            .unwrap()
+            .compute_object_reference(),
+    )
+    .await;
+
+    assert_eq!(effects.status(), &ExecutionStatus::Failure {
+        error: ExecutionFailureStatus::ExecutionCancelledDueToSharedObjectCongestion {
+            congested_objects: CongestedObjects(vec![shared_object_1.0]),
+        },
+        command: None
+    });
+}
@@ -13,6 +13,7 @@ use iota_types::{
     error::{ExecutionErrorKind, IotaError},
     execution_status::{CommandArgumentError, ExecutionFailureStatus, ExecutionStatus},
     move_package::UpgradeCap,
+    object::Object,
     programmable_transaction_builder::ProgrammableTransactionBuilder,
     utils::to_sender_signed_transaction,
 };
@@ -24,9 +25,12 @@ use move_core_types::{
 };
 
 use super::*;
-use crate::authority::authority_tests::{
-    TestCallArg, call_move, call_move_, execute_programmable_transaction, init_state_with_ids,
-    send_and_confirm_transaction,
+use crate::authority::{
+    authority_tests::{
+        TestCallArg, call_move, call_move_, execute_programmable_transaction, init_state_with_ids,
+        send_and_confirm_transaction,
+    },
+    test_authority_builder::TestAuthorityBuilder,
 };
 
 #[tokio::test]
@@ -2989,6 +2993,60 @@ pub fn build_multi_upgrade_txns(
     }
 }
 
+#[tokio::test]
+#[cfg_attr(msim, ignore)]
+async fn test_secp256k1_schnorr_verify_not_supported() {
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let gas = ObjectID::random();
+    let mut protocol_config = ProtocolConfig::get_for_max_version_UNSAFE();
+    protocol_config.set_enable_secp256k1_schnorr_for_testing(false);
+    let authority = TestAuthorityBuilder::new()
+        .with_protocol_config(protocol_config)
+        .build()
+        .await;
+    authority
+        .insert_genesis_object(Object::with_id_owner_for_testing(gas, sender))
+        .await;
+    let rgp = authority.reference_gas_price_for_testing().unwrap();
+
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let signature = builder.pure(vec![0u8; 64]).unwrap();
+        let public_key = builder.pure(vec![0u8; 32]).unwrap();
+        let msg = builder.pure(vec![0u8; 32]).unwrap();
+        builder.programmable_move_call(
+            IOTA_FRAMEWORK_PACKAGE_ID,
+            Identifier::new("ecdsa_k1").unwrap(),
+            Identifier::new("secp256k1_schnorr_verify").unwrap(),
+            vec![],
+            vec![signature, public_key, msg],
+        );
+        builder.finish()
+    };
+    let effects = execute_programmable_transaction(
+        &authority,
+        &gas,
+        &sender,
+        &sender_key,
+        pt,
+        rgp * TEST_ONLY_GAS_UNIT_FOR_GENERIC,
+    )
+    .await
+    .unwrap();
+
+    // The native aborts with `ENotSupported` in `iota::ecdsa_k1` while the
+    // feature flag is off.
+    let ExecutionStatus::Failure {
+        error: ExecutionFailureStatus::MoveAbort(location, code),
+        ..
+    } = effects.status()
+    else {
+        panic!("unexpected status: {:?}", effects.status());
+    };
+    assert_eq!(location.module.name().as_str(), "ecdsa_k1");
+    assert_eq!(*code, 6);
+}
+
 async fn check_latest_object_ref(
     authority: &AuthorityState,
     object_ref: &ObjectRef,
@@ -4,6 +4,7 @@
 
 use std::{sync::Arc, time::Duration};
 
+use consensus_config::ProtocolKeyPair;
 use fastcrypto::traits::KeyPair;
 use iota_metrics::RegistryService;
 use iota_swarm_config::network_config_builder::ConfigBuilder;
@@ -72,7 +73,7 @@ async fn test_mysticeti_manager() {
     let client = Arc::new(LazyMysticetiClient::default());
 
     let manager = MysticetiManager::new(
-        config.protocol_key_pair().copy(),
+        ProtocolKeyPair::new(config.protocol_key_pair().copy()),
         config.network_key_pair().copy(),
         consensus_config.db_path().to_path_buf(),
         registry_service,
@@ -7,7 +7,7 @@ use std::{path::PathBuf, sync::Arc};
 use fastcrypto::{ed25519::Ed25519KeyPair, traits::KeyPair};
 use iota_config::{
     certificate_deny_config::CertificateDenyConfigBuilder,
-    transaction_deny_config::{TransactionDenyConfig, TransactionDenyConfigBuilder},
+    transaction_deny_config::{DenyListEntry, TransactionDenyConfig, TransactionDenyConfigBuilder},
 };
 use iota_swarm_config::{
     genesis_config::{AccountConfig, DEFAULT_GAS_AMOUNT},
@@ -447,6 +447,137 @@ async fn test_package_denied() {
     assert!(result.is_ok());
 }
 
+#[tokio::test]
+async fn test_package_upgrade_family_denied() {
+    let (network_config, state) = setup_test(TransactionDenyConfigBuilder::new().build()).await;
+    let accounts = get_accounts_and_coins(&network_config, &state);
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    // Publish c and b, where b depends on c. Then upgrade c to c', and upgrade b
+    // to b' which uses c' instead of c as dependency.
+    let (package_c, cap_c) = publish_package_on_single_authority(
+        &path.join("src/unit_tests/data/package_deny/c"),
+        accounts[0].0,
+        &accounts[0].1,
+        accounts[0].2[0],
+        [("c", ObjectID::ZERO)],
+        vec![],
+        &state,
+    )
+    .await
+    .unwrap();
+    let (package_b, cap_b) = publish_package_on_single_authority(
+        &path.join("src/unit_tests/data/package_deny/b"),
+        accounts[0].0,
+        &accounts[0].1,
+        accounts[0].2[1],
+        [("b", ObjectID::ZERO), ("c", package_c)],
+        vec![package_c],
+        &state,
+    )
+    .await
+    .unwrap();
+    let package_c_prime = upgrade_package_on_single_authority(
+        &path.join("src/unit_tests/data/package_deny/c"),
+        accounts[0].0,
+        &accounts[0].1,
+        accounts[0].2[2],
+        package_c,
+        cap_c,
+        [("c", ObjectID::ZERO)],
+        vec![],
+        &state,
+    )
+    .await
+    .unwrap();
+    let package_b_prime = upgrade_package_on_single_authority(
+        &path.join("src/unit_tests/data/package_deny/b"),
+        accounts[0].0,
+        &accounts[0].1,
+        accounts[0].2[3],
+        package_b,
+        cap_b,
+        [("b", ObjectID::ZERO), ("c", package_c)],
+        [("C", package_c_prime)],
+        &state,
+    )
+    .await
+    .unwrap();
+
+    // Re-create the state such that we could deny the upgrade family of c.
+    let state = reload_state_with_new_deny_config(
+        &network_config,
+        state,
+        TransactionDenyConfigBuilder::new()
+            .add_denied_package(package_c)
+            .deny_package_upgrade_family()
+            .build(),
+    )
+    .await;
+
+    // Calling modules in c' should fail as it belongs to the same upgrade family.
+    let result =
+        handle_move_call_transaction(&state, package_c_prime, "c", "c", vec![], &accounts[0], 4)
+            .await;
+    assert_denied(&result);
+
+    // Calling modules in b' should fail too as it depends on c'.
+    let result =
+        handle_move_call_transaction(&state, package_b_prime, "b", "b", vec![], &accounts[0], 5)
+            .await;
+    assert_denied(&result);
+}
+
+#[tokio::test]
+async fn test_deny_list_updated_at_runtime() {
+    let (network_config, state) = setup_test(TransactionDenyConfigBuilder::new().build()).await;
+    let accounts = get_accounts_and_coins(&network_config, &state);
+    let entry = DenyListEntry::Address(accounts[0].0);
+
+    assert!(state.update_transaction_deny_list(entry, true).unwrap());
+    assert!(!state.update_transaction_deny_list(entry, true).unwrap());
+    assert_eq!(state.transaction_deny_config().deny_list_entries(), vec![
+        entry
+    ]);
+    assert_denied(&transfer_with_account(&accounts[0], &accounts[0], &state).await);
+
+    assert!(state.update_transaction_deny_list(entry, false).unwrap());
+    assert!(!state.update_transaction_deny_list(entry, false).unwrap());
+    assert!(
+        state
+            .transaction_deny_config()
+            .deny_list_entries()
+            .is_empty()
+    );
+    assert!(
+        transfer_with_account(&accounts[0], &accounts[0], &state)
+            .await
+            .is_ok()
+    );
+}
+
+#[tokio::test]
+async fn test_deny_list_update_keeps_corrupt_overrides() {
+    let (network_config, state) = setup_test(TransactionDenyConfigBuilder::new().build()).await;
+    let accounts = get_accounts_and_coins(&network_config, &state);
+    let entry = DenyListEntry::Address(accounts[0].0);
+    let path = state.config.transaction_deny_overrides_path();
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, "denied: [").unwrap();
+
+    // The corrupt overrides are neither overwritten nor replaced in memory.
+    assert!(state.update_transaction_deny_list(entry, true).is_err());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "denied: [");
+    assert!(
+        state
+            .transaction_deny_config()
+            .deny_list_entries()
+            .is_empty()
+    );
+
+    std::fs::remove_file(&path).unwrap();
+    assert!(state.update_transaction_deny_list(entry, true).unwrap());
+}
+
 #[tokio::test]
 async fn test_certificate_deny() {
     let (network_config, state) = setup_test(TransactionDenyConfig::default()).await;
@@ -414,12 +414,14 @@ async fn do_transaction_test_impl(
     if check_forged_cert {
         let epoch_store = authority_state.epoch_store_for_testing();
         for transaction in transactions {
-            let signed_transaction = VerifiedSignedTransaction::new(
+            let signed_transaction = VerifiedSignedTransaction::try_new(
                 epoch_store.epoch(),
                 VerifiedTransaction::new_unchecked(transaction),
                 authority_state.name,
                 &*authority_state.secret,
-            );
+            )
+            .await
+            .unwrap();
             let mut agg = StakeAggregator::new(epoch_store.committee().clone());
 
             let InsertResult::QuorumReached(cert_sig) =
@@ -1506,12 +1508,14 @@ async fn test_handle_certificate_errors() {
     // Test handle certificate from the wrong epoch
     let epoch_store = authority_state.epoch_store_for_testing();
     let next_epoch = epoch_store.epoch() + 1;
-    let signed_transaction = VerifiedSignedTransaction::new(
+    let signed_transaction = VerifiedSignedTransaction::try_new(
         next_epoch,
         VerifiedTransaction::new_unchecked(transfer_transaction.clone()),
         authority_state.name,
         &*authority_state.secret,
-    );
+    )
+    .await
+    .unwrap();
 
     let mut committee_1 = epoch_store.committee().deref().clone();
     committee_1.epoch = next_epoch;
@@ -1536,12 +1540,14 @@ async fn test_handle_certificate_errors() {
     });
 
     // Test handle certificate with invalid user input
-    let signed_transaction = VerifiedSignedTransaction::new(
+    let signed_transaction = VerifiedSignedTransaction::try_new(
         epoch_store.epoch(),
         VerifiedTransaction::new_unchecked(transfer_transaction.clone().clone()),
         authority_state.name,
         &*authority_state.secret,
-    );
+    )
+    .await
+    .unwrap();
 
     let committee = epoch_store.committee().deref().clone();
 
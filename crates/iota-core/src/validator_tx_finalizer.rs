@@ -358,7 +358,8 @@ mod tests {
             };
             let signed_effects = self
                 .authority
-                .sign_effects(effects, &epoch_store)?
+                .sign_effects(effects, &epoch_store)
+                .await?
                 .into_inner();
             Ok(HandleCertificateResponseV1 {
                 signed_effects,
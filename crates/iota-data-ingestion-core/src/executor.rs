@@ -81,9 +81,11 @@ impl<P: ProgressStore> IndexerExecutor<P> {
         for pool in std::mem::take(&mut self.pools) {
             spawn_monitored_task!(pool);
         }
+        let mut ownership_lost = self.progress_store.ownership_lost();
         loop {
             tokio::select! {
                 _ = &mut exit_receiver => break,
+                err = &mut ownership_lost => return Err(err),
                 Some((task_name, sequence_number)) = self.pool_progress_receiver.recv() => {
                     self.progress_store.save(task_name.clone(), sequence_number).await?;
                     let seq_number = self.progress_store.min_watermark()?;
@@ -18,6 +18,8 @@ use iota_types::{
     full_checkpoint_content::CheckpointData, messages_checkpoint::CheckpointSequenceNumber,
 };
 pub use metrics::DataIngestionMetrics;
+#[cfg(feature = "postgres")]
+pub use progress_store::PgProgressStore;
 pub use progress_store::{FileProgressStore, ProgressStore, ShimProgressStore};
 pub use reader::ReaderOptions;
 pub use util::create_remote_store_client;
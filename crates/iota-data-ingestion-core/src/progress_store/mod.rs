@@ -6,9 +6,14 @@ use std::collections::HashMap;
 
 use anyhow::Result;
 use async_trait::async_trait;
+use futures::future::{self, BoxFuture};
 use iota_types::messages_checkpoint::CheckpointSequenceNumber;
 mod file;
+#[cfg(feature = "postgres")]
+mod postgres;
 pub use file::FileProgressStore;
+#[cfg(feature = "postgres")]
+pub use postgres::PgProgressStore;
 
 pub type ExecutorProgress = HashMap<String, CheckpointSequenceNumber>;
 
@@ -20,6 +25,13 @@ pub trait ProgressStore: Send {
         task_name: String,
         checkpoint_number: CheckpointSequenceNumber,
     ) -> Result<()>;
+
+    /// Resolves once this instance may no longer make progress on the tasks
+    /// it loaded, e.g. because another instance took over their lease. The
+    /// executor stops with the returned error. Never resolves by default.
+    fn ownership_lost(&self) -> BoxFuture<'static, anyhow::Error> {
+        Box::pin(future::pending())
+    }
 }
 
 pub struct ProgressStoreWrapper<P> {
@@ -46,6 +58,10 @@ impl<P: ProgressStore> ProgressStore for ProgressStoreWrapper<P> {
         self.pending_state.insert(task_name, checkpoint_number);
         Ok(())
     }
+
+    fn ownership_lost(&self) -> BoxFuture<'static, anyhow::Error> {
+        self.progress_store.ownership_lost()
+    }
 }
 
 impl<P: ProgressStore> ProgressStoreWrapper<P> {
@@ -4,8 +4,9 @@
 
 use std::{path::PathBuf, time::Duration};
 
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use async_trait::async_trait;
+use futures::future::BoxFuture;
 use iota_protocol_config::ProtocolConfig;
 use iota_storage::blob::{Blob, BlobEncoding};
 use iota_types::{
@@ -24,8 +25,8 @@ use tempfile::NamedTempFile;
 use tokio::sync::oneshot;
 
 use crate::{
-    DataIngestionMetrics, FileProgressStore, IndexerExecutor, ReaderOptions, Worker, WorkerPool,
-    progress_store::ExecutorProgress,
+    DataIngestionMetrics, FileProgressStore, IndexerExecutor, ProgressStore, ReaderOptions, Worker,
+    WorkerPool, progress_store::ExecutorProgress,
 };
 
 async fn add_worker_pool<W: Worker + 'static>(
@@ -109,6 +110,45 @@ async fn basic_flow() {
     assert_eq!(result.unwrap().get("test"), Some(&20));
 }
 
+/// A progress store that immediately loses ownership of its tasks.
+struct LostOwnershipProgressStore;
+
+#[async_trait]
+impl ProgressStore for LostOwnershipProgressStore {
+    async fn load(&mut self, _: String) -> Result<CheckpointSequenceNumber> {
+        Ok(0)
+    }
+
+    async fn save(&mut self, _: String, _: CheckpointSequenceNumber) -> Result<()> {
+        Ok(())
+    }
+
+    fn ownership_lost(&self) -> BoxFuture<'static, anyhow::Error> {
+        Box::pin(async { anyhow!("lease lost") })
+    }
+}
+
+#[tokio::test]
+async fn stops_when_ownership_is_lost() {
+    let mut executor = IndexerExecutor::new(
+        LostOwnershipProgressStore,
+        1,
+        DataIngestionMetrics::new(&Registry::new()),
+    );
+    executor
+        .register(WorkerPool::new(TestWorker, "test".to_string(), 1))
+        .await
+        .unwrap();
+    let (_sender, recv) = oneshot::channel();
+    let result = tokio::time::timeout(
+        Duration::from_secs(5),
+        executor.run(temp_dir(), None, vec![], ReaderOptions::default(), recv),
+    )
+    .await
+    .unwrap();
+    assert!(result.unwrap_err().to_string().contains("lease lost"));
+}
+
 fn temp_dir() -> std::path::PathBuf {
     tempfile::tempdir()
         .expect("Failed to open temporary directory")
@@ -2,14 +2,17 @@
 // Modifications Copyright (c) 2024 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{env, path::PathBuf};
+use std::{env, path::PathBuf, time::Duration};
 
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use iota_data_ingestion::{
     ArchivalConfig, ArchivalWorker, BlobTaskConfig, BlobWorker, DynamoDBProgressStore,
     KVStoreTaskConfig, KVStoreWorker,
 };
-use iota_data_ingestion_core::{DataIngestionMetrics, IndexerExecutor, ReaderOptions, WorkerPool};
+use iota_data_ingestion_core::{
+    DataIngestionMetrics, IndexerExecutor, PgProgressStore, ProgressStore, ReaderOptions,
+    WorkerPool,
+};
 use prometheus::Registry;
 use serde::{Deserialize, Serialize};
 use tokio::{signal, sync::oneshot};
@@ -31,12 +34,23 @@ struct TaskConfig {
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "lowercase")]
-struct ProgressStoreConfig {
-    pub aws_access_key_id: String,
-    pub aws_secret_access_key: String,
-    pub aws_region: String,
-    pub table_name: String,
+#[serde(untagged)]
+enum ProgressStoreConfig {
+    DynamoDB {
+        aws_access_key_id: String,
+        aws_secret_access_key: String,
+        aws_region: String,
+        table_name: String,
+    },
+    Postgres {
+        database_url: String,
+        /// Unique identity of this instance. Defaults to the `HOSTNAME`
+        /// environment variable, which is the pod name on Kubernetes.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        lease_holder: Option<String>,
+        #[serde(default = "default_lease_duration_secs")]
+        lease_duration_secs: u64,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -68,6 +82,10 @@ fn default_remote_read_batch_size() -> usize {
     100
 }
 
+fn default_lease_duration_secs() -> u64 {
+    30
+}
+
 fn setup_env(exit_sender: oneshot::Sender<()>) {
     let default_hook = std::panic::take_hook();
 
@@ -106,13 +124,48 @@ async fn main() -> Result<()> {
     iota_metrics::init_metrics(&registry);
     let metrics = DataIngestionMetrics::new(&registry);
 
-    let progress_store = DynamoDBProgressStore::new(
-        &config.progress_store.aws_access_key_id,
-        &config.progress_store.aws_secret_access_key,
-        config.progress_store.aws_region,
-        config.progress_store.table_name,
-    )
-    .await;
+    match config.progress_store.clone() {
+        ProgressStoreConfig::DynamoDB {
+            aws_access_key_id,
+            aws_secret_access_key,
+            aws_region,
+            table_name,
+        } => {
+            let progress_store = DynamoDBProgressStore::new(
+                &aws_access_key_id,
+                &aws_secret_access_key,
+                aws_region,
+                table_name,
+            )
+            .await;
+            run_executor(progress_store, config, metrics, exit_receiver).await
+        }
+        ProgressStoreConfig::Postgres {
+            database_url,
+            lease_holder,
+            lease_duration_secs,
+        } => {
+            let lease_holder = lease_holder
+                .or_else(|| env::var("HOSTNAME").ok())
+                .ok_or_else(|| {
+                    anyhow!("lease_holder is required for the postgres progress store")
+                })?;
+            let progress_store = PgProgressStore::new(
+                &database_url,
+                lease_holder,
+                Duration::from_secs(lease_duration_secs),
+            )?;
+            run_executor(progress_store, config, metrics, exit_receiver).await
+        }
+    }
+}
+
+async fn run_executor<P: ProgressStore>(
+    progress_store: P,
+    config: IndexerConfig,
+    metrics: DataIngestionMetrics,
+    exit_receiver: oneshot::Receiver<()>,
+) -> Result<()> {
     let mut executor = IndexerExecutor::new(progress_store, config.tasks.len(), metrics);
     for task_config in config.tasks {
         match task_config.task {
@@ -62,6 +62,7 @@ async fn advance_epoch_tx_test() {
                         effects.transaction_digest(),
                         &state.epoch_store_for_testing()
                     )
+                    .await
                     .unwrap()
                     .is_none()
             );
@@ -2,13 +2,23 @@
 // Modifications Copyright (c) 2024 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+use std::time::Duration;
+
+use futures::StreamExt;
+use iota_json_rpc_types::IotaTransactionBlockEffectsAPI;
 use iota_macros::sim_test;
-use iota_rest_api::{Client, ExecuteTransactionQueryParameters, client::BalanceChange};
+use iota_rest_api::{
+    Client, ExecuteTransactionQueryParameters, ListAccountBalancesQueryParameters,
+    client::BalanceChange,
+};
 use iota_test_transaction_builder::make_transfer_iota_transaction;
 use iota_types::{
-    base_types::IotaAddress, effects::TransactionEffectsAPI, transaction::TransactionDataAPI,
+    base_types::IotaAddress,
+    effects::TransactionEffectsAPI,
+    programmable_transaction_builder::ProgrammableTransactionBuilder,
+    transaction::{Command, ObjectArg, TransactionDataAPI},
 };
-use test_cluster::TestClusterBuilder;
+use test_cluster::{TestCluster, TestClusterBuilder};
 
 #[sim_test]
 async fn execute_transaction_transfer() {
@@ -52,3 +62,141 @@ async fn execute_transaction_transfer() {
 
     assert_eq!(actual, expected);
 }
+
+#[sim_test]
+async fn subscribe_checkpoints() {
+    let test_cluster = TestClusterBuilder::new().build().await;
+
+    let client = iota_rest_api::client::sdk::Client::new(test_cluster.rpc_url()).unwrap();
+    let start = client
+        .get_latest_checkpoint()
+        .await
+        .unwrap()
+        .into_inner()
+        .checkpoint
+        .sequence_number;
+
+    let checkpoints = client
+        .subscribe_checkpoints(Some(start))
+        .await
+        .unwrap()
+        .into_inner();
+    futures::pin_mut!(checkpoints);
+
+    // Executing a transaction makes sure new checkpoints get created
+    let txn = make_transfer_iota_transaction(&test_cluster.wallet, None, None).await;
+    test_cluster.execute_transaction(txn).await;
+
+    for expected in start..start + 3 {
+        let checkpoint =
+            tokio::time::timeout(std::time::Duration::from_secs(60), checkpoints.next())
+                .await
+                .expect("timed out waiting for the next checkpoint")
+                .unwrap()
+                .unwrap();
+        assert_eq!(
+            checkpoint.checkpoint_summary.checkpoint.sequence_number,
+            expected
+        );
+    }
+}
+
+/// Waits until the balance index of the fullnode agrees with the JSON-RPC
+/// balance of `address`, which is updated as soon as the transaction is
+/// executed locally.
+async fn wait_for_account_balance(test_cluster: &TestCluster, address: IotaAddress) {
+    let expected = test_cluster
+        .wallet
+        .get_client()
+        .await
+        .unwrap()
+        .coin_read_api()
+        .get_balance(address, None)
+        .await
+        .unwrap();
+    let client = iota_rest_api::client::sdk::Client::new(test_cluster.rpc_url()).unwrap();
+    let parameters = ListAccountBalancesQueryParameters {
+        limit: None,
+        start: None,
+    };
+
+    tokio::time::timeout(Duration::from_secs(60), async {
+        loop {
+            let balances = client
+                .list_account_balances(address.into(), &parameters)
+                .await
+                .unwrap()
+                .into_inner();
+            if let [balance] = balances.as_slice() {
+                if balance.balance as u128 == expected.total_balance
+                    && balance.coin_object_count as usize == expected.coin_object_count
+                {
+                    return;
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .expect("timed out waiting for the balance to be indexed");
+}
+
+#[sim_test]
+async fn list_account_balances() {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let recipient = IotaAddress::random_for_testing_only();
+
+    // Split two coins off the gas coin.
+    let builder = test_cluster.test_transaction_builder().await;
+    let sender = builder.sender();
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    ptb.pay_iota(vec![sender, sender], vec![1_000, 2_000])
+        .unwrap();
+    let response = test_cluster
+        .sign_and_execute_transaction(&builder.programmable(ptb.finish()).build())
+        .await;
+    let effects = response.effects.unwrap();
+    let gas = effects.gas_object().reference.to_object_ref();
+    let [primary, other] = effects.created() else {
+        panic!("expected two coins to be created");
+    };
+    let (primary, other) = (
+        primary.reference.to_object_ref(),
+        other.reference.to_object_ref(),
+    );
+    wait_for_account_balance(&test_cluster, sender).await;
+
+    // Merge them back into a single coin.
+    let builder = test_cluster
+        .test_transaction_builder_with_gas_object(sender, gas)
+        .await;
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    let primary_arg = ptb.obj(ObjectArg::ImmOrOwnedObject(primary)).unwrap();
+    let other_arg = ptb.obj(ObjectArg::ImmOrOwnedObject(other)).unwrap();
+    ptb.command(Command::MergeCoins(primary_arg, vec![other_arg]));
+    let response = test_cluster
+        .sign_and_execute_transaction(&builder.programmable(ptb.finish()).build())
+        .await;
+    let effects = response.effects.unwrap();
+    let gas = effects.gas_object().reference.to_object_ref();
+    let merged = effects
+        .mutated()
+        .iter()
+        .find(|object| object.object_id() == primary.0)
+        .unwrap()
+        .reference
+        .to_object_ref();
+    wait_for_account_balance(&test_cluster, sender).await;
+
+    // Transfer the merged coin away.
+    let transaction = test_cluster
+        .test_transaction_builder_with_gas_object(sender, gas)
+        .await
+        .transfer(merged, recipient)
+        .build();
+    test_cluster
+        .sign_and_execute_transaction(&transaction)
+        .await;
+    wait_for_account_balance(&test_cluster, sender).await;
+    wait_for_account_balance(&test_cluster, recipient).await;
+}
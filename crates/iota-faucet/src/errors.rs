@@ -35,6 +35,12 @@ pub enum FaucetError {
     #[error("Too many coins in the batch queue. Please try again later.")]
     BatchSendQueueFull,
 
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
+    #[error("Daily request quota exceeded for {0}. Please try again tomorrow.")]
+    QuotaExceeded(String),
+
     #[error("Request consumer queue closed.")]
     ChannelClosed,
 
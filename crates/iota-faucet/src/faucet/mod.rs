@@ -2,6 +2,7 @@
 // Modifications Copyright (c) 2024 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+mod quota;
 mod simple_faucet;
 mod write_ahead_log;
 
@@ -13,7 +14,10 @@ use iota_types::base_types::{IotaAddress, ObjectID, TransactionDigest};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-pub use self::simple_faucet::SimpleFaucet;
+pub use self::{
+    quota::{QuotaLimits, QuotaStatus},
+    simple_faucet::SimpleFaucet,
+};
 use crate::FaucetError;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -130,6 +134,33 @@ pub struct FaucetConfig {
 
     #[clap(long, action = clap::ArgAction::Set, default_value_t = false)]
     pub batch_enabled: bool,
+
+    /// Maximum number of requests per recipient address per day. Unlimited
+    /// if not set.
+    #[clap(long)]
+    pub max_requests_per_address_per_day: Option<u64>,
+
+    /// Maximum number of requests per client IP per day. Unlimited if not
+    /// set.
+    #[clap(long)]
+    pub max_requests_per_ip_per_day: Option<u64>,
+
+    /// Header holding the client IP, e.g. `x-forwarded-for` when running
+    /// behind a reverse proxy. The last address in the header is used, since
+    /// that is the one appended by the proxy, while earlier ones are supplied
+    /// by the client. The IP of the peer connecting to the faucet is used if
+    /// the header is not set or not present in a request.
+    #[clap(long)]
+    pub client_ip_header: Option<String>,
+}
+
+impl FaucetConfig {
+    pub fn quota_limits(&self) -> QuotaLimits {
+        QuotaLimits {
+            per_address: self.max_requests_per_address_per_day,
+            per_ip: self.max_requests_per_ip_per_day,
+        }
+    }
 }
 
 impl Default for FaucetConfig {
@@ -148,6 +179,9 @@ impl Default for FaucetConfig {
             batch_request_size: 500,
             ttl_expiration: 300,
             batch_enabled: false,
+            max_requests_per_address_per_day: None,
+            max_requests_per_ip_per_day: None,
+            client_ip_header: None,
         }
     }
 }
@@ -0,0 +1,262 @@
+// Copyright (c) 2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{net::IpAddr, path::Path};
+
+use iota_types::base_types::IotaAddress;
+use serde::{Deserialize, Serialize};
+use typed_store::{
+    DBMapUtils, Map, TypedStoreError,
+    rocks::DBMap,
+    traits::{TableSummary, TypedStoreDebug},
+};
+
+use crate::FaucetError;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Persistent daily request counters of the faucet, per recipient address and
+/// per client IP. Counters are reset lazily once the day they were recorded in
+/// has passed, so entries of inactive clients are simply overwritten the next
+/// time they make a request.
+#[derive(DBMapUtils, Clone)]
+pub struct QuotaStore {
+    pub address_usage: DBMap<IotaAddress, QuotaUsage>,
+    pub ip_usage: DBMap<IpAddr, QuotaUsage>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Default)]
+pub struct QuotaUsage {
+    /// Days since the unix epoch the requests were counted in.
+    pub day: u64,
+    pub requests: u64,
+}
+
+impl QuotaUsage {
+    fn requests_on(&self, day: u64) -> u64 {
+        if self.day == day { self.requests } else { 0 }
+    }
+}
+
+/// Remaining daily quota of a recipient address.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaStatus {
+    pub address: IotaAddress,
+    /// `None` if the requests per address are not limited.
+    pub limit: Option<u64>,
+    pub used: u64,
+    /// `None` if the requests per address are not limited.
+    pub remaining: Option<u64>,
+    /// Unix timestamp in seconds at which the quota is reset.
+    pub resets_at: u64,
+}
+
+/// Maximum number of requests per day, `None` meaning unlimited.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QuotaLimits {
+    pub per_address: Option<u64>,
+    pub per_ip: Option<u64>,
+}
+
+impl QuotaStore {
+    pub(crate) fn open(path: &Path) -> Self {
+        Self::open_tables_read_write(
+            path.to_path_buf(),
+            typed_store::rocks::MetricConf::new("faucet_quota_store"),
+            None,
+            None,
+        )
+    }
+
+    /// Counts a request of `recipient` from `ip` against the daily quotas.
+    /// Fails with [`FaucetError::QuotaExceeded`] without counting anything if
+    /// either quota is used up.
+    pub(crate) fn try_consume(
+        &self,
+        limits: QuotaLimits,
+        recipient: IotaAddress,
+        ip: IpAddr,
+        now_secs: u64,
+    ) -> Result<(), FaucetError> {
+        let day = now_secs / SECONDS_PER_DAY;
+        let address_requests = self.address_requests(&recipient, day)?;
+        if limits
+            .per_address
+            .is_some_and(|limit| address_requests >= limit)
+        {
+            return Err(FaucetError::QuotaExceeded(format!("address {recipient}")));
+        }
+        let ip_requests = self.ip_requests(&ip, day)?;
+        if limits.per_ip.is_some_and(|limit| ip_requests >= limit) {
+            return Err(FaucetError::QuotaExceeded(format!("IP {ip}")));
+        }
+
+        let mut batch = self.address_usage.batch();
+        batch
+            .insert_batch(&self.address_usage, [(recipient, QuotaUsage {
+                day,
+                requests: address_requests + 1,
+            })])
+            .map_err(FaucetError::internal)?;
+        batch
+            .insert_batch(&self.ip_usage, [(ip, QuotaUsage {
+                day,
+                requests: ip_requests + 1,
+            })])
+            .map_err(FaucetError::internal)?;
+        batch.write().map_err(FaucetError::internal)
+    }
+
+    /// Gives back a request counted by [`QuotaStore::try_consume`], for
+    /// requests that the faucet failed to serve.
+    pub(crate) fn refund(
+        &self,
+        recipient: IotaAddress,
+        ip: IpAddr,
+        now_secs: u64,
+    ) -> Result<(), TypedStoreError> {
+        let day = now_secs / SECONDS_PER_DAY;
+        if let Some(usage) = self.address_usage.get(&recipient)? {
+            if usage.day == day && usage.requests > 0 {
+                self.address_usage.insert(&recipient, &QuotaUsage {
+                    day,
+                    requests: usage.requests - 1,
+                })?;
+            }
+        }
+        if let Some(usage) = self.ip_usage.get(&ip)? {
+            if usage.day == day && usage.requests > 0 {
+                self.ip_usage.insert(&ip, &QuotaUsage {
+                    day,
+                    requests: usage.requests - 1,
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn status(
+        &self,
+        limits: QuotaLimits,
+        address: IotaAddress,
+        now_secs: u64,
+    ) -> Result<QuotaStatus, FaucetError> {
+        let day = now_secs / SECONDS_PER_DAY;
+        let used = self.address_requests(&address, day)?;
+        Ok(QuotaStatus {
+            address,
+            limit: limits.per_address,
+            used,
+            remaining: limits.per_address.map(|limit| limit.saturating_sub(used)),
+            resets_at: (day + 1) * SECONDS_PER_DAY,
+        })
+    }
+
+    fn address_requests(&self, address: &IotaAddress, day: u64) -> Result<u64, FaucetError> {
+        Ok(self
+            .address_usage
+            .get(address)
+            .map_err(FaucetError::internal)?
+            .map_or(0, |usage| usage.requests_on(day)))
+    }
+
+    fn ip_requests(&self, ip: &IpAddr, day: u64) -> Result<u64, FaucetError> {
+        Ok(self
+            .ip_usage
+            .get(ip)
+            .map_err(FaucetError::internal)?
+            .map_or(0, |usage| usage.requests_on(day)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000;
+    const IP: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+    #[tokio::test]
+    async fn address_quota_is_enforced_and_reset_daily() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = QuotaStore::open(&tmp.path().join("quota"));
+        let limits = QuotaLimits {
+            per_address: Some(2),
+            per_ip: None,
+        };
+        let recipient = IotaAddress::random_for_testing_only();
+
+        store.try_consume(limits, recipient, IP, NOW).unwrap();
+        store.try_consume(limits, recipient, IP, NOW).unwrap();
+        assert!(matches!(
+            store.try_consume(limits, recipient, IP, NOW),
+            Err(FaucetError::QuotaExceeded(_))
+        ));
+
+        let status = store.status(limits, recipient, NOW).unwrap();
+        assert_eq!(status.used, 2);
+        assert_eq!(status.remaining, Some(0));
+        assert!(status.resets_at > NOW);
+
+        // Other addresses are not affected
+        let other = IotaAddress::random_for_testing_only();
+        store.try_consume(limits, other, IP, NOW).unwrap();
+
+        // The quota is reset on the next day
+        store
+            .try_consume(limits, recipient, IP, status.resets_at)
+            .unwrap();
+        let status = store.status(limits, recipient, status.resets_at).unwrap();
+        assert_eq!(status.used, 1);
+        assert_eq!(status.remaining, Some(1));
+    }
+
+    #[tokio::test]
+    async fn ip_quota_is_shared_between_addresses() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = QuotaStore::open(&tmp.path().join("quota"));
+        let limits = QuotaLimits {
+            per_address: Some(10),
+            per_ip: Some(1),
+        };
+        let ip = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let recipient = IotaAddress::random_for_testing_only();
+
+        store.try_consume(limits, recipient, ip, NOW).unwrap();
+        let other = IotaAddress::random_for_testing_only();
+        assert!(matches!(
+            store.try_consume(limits, other, ip, NOW),
+            Err(FaucetError::QuotaExceeded(_))
+        ));
+        // A rejected request is not counted against the address
+        assert_eq!(store.status(limits, other, NOW).unwrap().used, 0);
+
+        // Refunding the first request frees the IP quota again
+        store.refund(recipient, ip, NOW).unwrap();
+        store.try_consume(limits, other, ip, NOW).unwrap();
+        assert_eq!(store.status(limits, recipient, NOW).unwrap().used, 0);
+    }
+
+    #[tokio::test]
+    async fn quota_is_persisted() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("quota");
+        let limits = QuotaLimits {
+            per_address: Some(1),
+            per_ip: None,
+        };
+        let recipient = IotaAddress::random_for_testing_only();
+        {
+            let store = QuotaStore::open(&path);
+            store.try_consume(limits, recipient, IP, NOW).unwrap();
+        }
+        let store = QuotaStore::open(&path);
+        assert!(matches!(
+            store.try_consume(limits, recipient, IP, NOW),
+            Err(FaucetError::QuotaExceeded(_))
+        ));
+    }
+}
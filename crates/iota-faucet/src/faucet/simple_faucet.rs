@@ -7,8 +7,10 @@ use std::collections::HashSet;
 use std::{
     collections::HashMap,
     fmt,
+    net::IpAddr,
     path::Path,
     sync::{Arc, Weak},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 use async_recursion::async_recursion;
@@ -44,10 +46,11 @@ use ttl_cache::TtlCache;
 use typed_store::Map;
 use uuid::Uuid;
 
-use super::write_ahead_log::WriteAheadLog;
+use super::{quota::QuotaStore, write_ahead_log::WriteAheadLog};
 use crate::{
     BatchFaucetReceipt, BatchSendStatus, BatchSendStatusType, CoinInfo, Faucet, FaucetConfig,
-    FaucetError, FaucetReceipt, faucet::write_ahead_log, metrics::FaucetMetrics,
+    FaucetError, FaucetReceipt, QuotaLimits, QuotaStatus, faucet::write_ahead_log,
+    metrics::FaucetMetrics,
 };
 
 pub struct SimpleFaucet {
@@ -59,6 +62,9 @@ pub struct SimpleFaucet {
     batch_consumer: Mutex<Receiver<ObjectID>>,
     pub metrics: FaucetMetrics,
     pub wal: Mutex<WriteAheadLog>,
+    /// Daily request counters, stored next to the WAL.
+    quota: Mutex<QuotaStore>,
+    quota_limits: QuotaLimits,
     request_producer: Sender<(Uuid, IotaAddress, Vec<u64>)>,
     batch_request_size: u64,
     task_id_cache: Mutex<TtlCache<Uuid, BatchSendStatus>>,
@@ -80,6 +86,7 @@ impl fmt::Debug for SimpleFaucet {
             .field("batch_request_size", &self.batch_request_size)
             .field("ttl_expiration", &self.ttl_expiration)
             .field("coin_amount", &self.coin_amount)
+            .field("quota_limits", &self.quota_limits)
             .finish()
     }
 }
@@ -127,6 +134,7 @@ impl SimpleFaucet {
         let metrics = FaucetMetrics::new(prometheus_registry);
 
         let wal = WriteAheadLog::open(wal_path);
+        let quota = QuotaStore::open(&wal_path.with_extension("quota"));
         let mut pending = vec![];
 
         let (producer, consumer) = mpsc::channel(coins.len());
@@ -195,6 +203,8 @@ impl SimpleFaucet {
             batch_consumer: Mutex::new(batch_consumer),
             metrics,
             wal: Mutex::new(wal),
+            quota: Mutex::new(quota),
+            quota_limits: config.quota_limits(),
             request_producer: sender,
             batch_request_size: config.batch_request_size,
             // Max faucet requests times 10 minutes worth of requests to hold onto at max.
@@ -848,6 +858,45 @@ impl SimpleFaucet {
         Ok(())
     }
 
+    /// Counts a request of `recipient` from `ip` against the daily quotas,
+    /// failing with [`FaucetError::QuotaExceeded`] if either is used up.
+    pub async fn consume_quota(
+        &self,
+        recipient: IotaAddress,
+        ip: IpAddr,
+    ) -> Result<(), FaucetError> {
+        let result = self.quota.lock().await.try_consume(
+            self.quota_limits,
+            recipient,
+            ip,
+            unix_timestamp_secs(),
+        );
+        if matches!(result, Err(FaucetError::QuotaExceeded(_))) {
+            self.metrics.total_quota_exceeded_requests.inc();
+        }
+        result
+    }
+
+    /// Gives back the quota consumed by a request that could not be served.
+    pub async fn refund_quota(&self, recipient: IotaAddress, ip: IpAddr) {
+        if let Err(err) = self
+            .quota
+            .lock()
+            .await
+            .refund(recipient, ip, unix_timestamp_secs())
+        {
+            warn!(?recipient, "Failed to refund faucet quota: {err:?}");
+        }
+    }
+
+    /// Returns the remaining daily quota of `address`.
+    pub async fn quota_status(&self, address: IotaAddress) -> Result<QuotaStatus, FaucetError> {
+        self.quota
+            .lock()
+            .await
+            .status(self.quota_limits, address, unix_timestamp_secs())
+    }
+
     #[cfg(test)]
     pub(crate) fn shutdown_batch_send_task(&self) {
         self.batch_transfer_shutdown
@@ -869,7 +918,7 @@ impl SimpleFaucet {
     }
 
     #[cfg(test)]
-    async fn drain_gas_queue(&mut self, expected_gas_count: usize) -> HashSet<ObjectID> {
+    pub(crate) async fn drain_gas_queue(&mut self, expected_gas_count: usize) -> HashSet<ObjectID> {
         use tokio::sync::mpsc::error::TryRecvError;
         let mut consumer = self.consumer.lock().await;
         let mut candidates = HashSet::new();
@@ -889,6 +938,13 @@ impl SimpleFaucet {
     }
 }
 
+fn unix_timestamp_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
 #[async_trait]
 impl Faucet for SimpleFaucet {
     async fn send(
@@ -29,6 +29,7 @@ pub struct FaucetMetrics {
     pub(crate) total_available_coins: IntGauge,
     pub(crate) total_discarded_coins: IntGauge,
     pub(crate) total_coin_requests_succeeded: IntGauge,
+    pub(crate) total_quota_exceeded_requests: IntCounter,
 }
 
 const LATENCY_SEC_BUCKETS: &[f64] = &[
@@ -113,6 +114,12 @@ impl FaucetMetrics {
                 registry,
             )
             .unwrap(),
+            total_quota_exceeded_requests: register_int_counter_with_registry!(
+                "total_quota_exceeded_requests",
+                "Total number of requests rejected because of an exhausted daily quota",
+                registry,
+            )
+            .unwrap(),
         }
     }
 }
@@ -92,3 +92,28 @@ impl From<BatchSendStatus> for BatchStatusFaucetResponse {
         }
     }
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaFaucetResponse {
+    pub quota: Option<QuotaStatus>,
+    pub error: Option<String>,
+}
+
+impl From<FaucetError> for QuotaFaucetResponse {
+    fn from(e: FaucetError) -> Self {
+        Self {
+            error: Some(e.to_string()),
+            quota: None,
+        }
+    }
+}
+
+impl From<QuotaStatus> for QuotaFaucetResponse {
+    fn from(v: QuotaStatus) -> Self {
+        Self {
+            quota: Some(v),
+            error: None,
+        }
+    }
+}
@@ -6,6 +6,7 @@ use std::{
     borrow::Cow,
     net::{IpAddr, SocketAddr},
     path::PathBuf,
+    str::FromStr,
     sync::Arc,
     time::Duration,
 };
@@ -13,15 +14,16 @@ use std::{
 use axum::{
     BoxError, Extension, Json, Router,
     error_handling::HandleErrorLayer,
-    extract::Path,
+    extract::{ConnectInfo, Path},
     http::StatusCode,
     response::IntoResponse,
     routing::{get, post},
 };
-use http::Method;
+use http::{HeaderMap, Method};
 use iota_config::IOTA_CLIENT_CONFIG;
 use iota_metrics::spawn_monitored_task;
 use iota_sdk::wallet_context::WalletContext;
+use iota_types::base_types::IotaAddress;
 use prometheus::Registry;
 use tower::{ServiceBuilder, limit::RateLimitLayer};
 use tower_http::cors::{Any, CorsLayer};
@@ -30,7 +32,7 @@ use uuid::Uuid;
 
 use crate::{
     AppState, BatchFaucetResponse, BatchStatusFaucetResponse, FaucetConfig, FaucetError,
-    FaucetRequest, FaucetResponse, RequestMetricsLayer, faucet::Faucet,
+    FaucetRequest, FaucetResponse, QuotaFaucetResponse, RequestMetricsLayer, faucet::Faucet,
 };
 
 pub async fn start_faucet(
@@ -58,6 +60,7 @@ pub async fn start_faucet(
         .route("/gas", post(request_gas))
         .route("/v1/gas", post(batch_request_gas))
         .route("/v1/status/:task_id", get(request_status))
+        .route("/v1/quota/:address", get(request_quota))
         .layer(
             ServiceBuilder::new()
                 .layer(HandleErrorLayer::new(handle_error))
@@ -88,7 +91,11 @@ pub async fn start_faucet(
     info!("listening on {}", addr);
 
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
     Ok(())
 }
 
@@ -100,6 +107,8 @@ async fn health() -> &'static str {
 /// handler for batch_request_gas requests
 async fn batch_request_gas(
     Extension(state): Extension<Arc<AppState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(payload): Json<FaucetRequest>,
 ) -> impl IntoResponse {
     let id = Uuid::new_v4();
@@ -115,13 +124,24 @@ async fn batch_request_gas(
         );
     };
 
+    let client_ip = client_ip(&state.config, &headers, peer);
+    if let Err(e) = state
+        .faucet
+        .consume_quota(request.recipient, client_ip)
+        .await
+    {
+        warn!(uuid =?id, "Rejected gas request: {:?}", e);
+        return (error_status(&e), Json(BatchFaucetResponse::from(e)));
+    }
+
     if state.config.batch_enabled {
+        let task_state = state.clone();
         let result = spawn_monitored_task!(async move {
-            state
+            task_state
                 .faucet
                 .batch_send(id, request.recipient, &vec![
-                    state.config.amount;
-                    state.config.num_coins
+                    task_state.config.amount;
+                    task_state.config.num_coins
                 ])
                 .await
         })
@@ -135,6 +155,10 @@ async fn batch_request_gas(
             }
             Err(v) => {
                 warn!(uuid =?id, "Failed to request gas: {:?}", v);
+                state
+                    .faucet
+                    .refund_quota(request.recipient, client_ip)
+                    .await;
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     Json(BatchFaucetResponse::from(v)),
@@ -145,12 +169,13 @@ async fn batch_request_gas(
         // TODO (jian): remove this feature gate when batch has proven to be baked long
         // enough
         info!(uuid = ?id, "Falling back to v1 implementation");
+        let task_state = state.clone();
         let result = spawn_monitored_task!(async move {
-            state
+            task_state
                 .faucet
                 .send(id, request.recipient, &vec![
-                    state.config.amount;
-                    state.config.num_coins
+                    task_state.config.amount;
+                    task_state.config.num_coins
                 ])
                 .await
         })
@@ -164,6 +189,10 @@ async fn batch_request_gas(
             }
             Err(v) => {
                 warn!(uuid =?id, "Failed to request gas: {:?}", v);
+                state
+                    .faucet
+                    .refund_quota(request.recipient, client_ip)
+                    .await;
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     Json(BatchFaucetResponse::from(v)),
@@ -204,26 +233,36 @@ async fn request_status(
 /// handler for all the request_gas requests
 async fn request_gas(
     Extension(state): Extension<Arc<AppState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(payload): Json<FaucetRequest>,
 ) -> impl IntoResponse {
     // ID for traceability
     let id = Uuid::new_v4();
     info!(uuid = ?id, "Got new gas request.");
-    let result = match payload {
+    let client_ip = client_ip(&state.config, &headers, peer);
+    let (result, recipient) = match payload {
         FaucetRequest::FixedAmountRequest(requests) => {
+            let recipient = requests.recipient;
+            if let Err(e) = state.faucet.consume_quota(recipient, client_ip).await {
+                warn!(uuid =?id, "Rejected gas request: {:?}", e);
+                return (error_status(&e), Json(FaucetResponse::from(e)));
+            }
+            let task_state = state.clone();
             // We spawn a tokio task for this such that connection drop will not interrupt
             // it and impact the recycling of coins
-            spawn_monitored_task!(async move {
-                state
+            let result = spawn_monitored_task!(async move {
+                task_state
                     .faucet
-                    .send(id, requests.recipient, &vec![
-                        state.config.amount;
-                        state.config.num_coins
+                    .send(id, recipient, &vec![
+                        task_state.config.amount;
+                        task_state.config.num_coins
                     ])
                     .await
             })
             .await
-            .unwrap()
+            .unwrap();
+            (result, recipient)
         }
         _ => {
             return (
@@ -241,6 +280,7 @@ async fn request_gas(
         }
         Err(v) => {
             warn!(uuid =?id, "Failed to request gas: {:?}", v);
+            state.faucet.refund_quota(recipient, client_ip).await;
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(FaucetResponse::from(v)),
@@ -249,6 +289,55 @@ async fn request_gas(
     }
 }
 
+/// handler for quota requests
+async fn request_quota(
+    Extension(state): Extension<Arc<AppState>>,
+    Path(address): Path<String>,
+) -> impl IntoResponse {
+    let address = match IotaAddress::from_str(&address) {
+        Ok(address) => address,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(QuotaFaucetResponse::from(FaucetError::InvalidRequest(
+                    e.to_string(),
+                ))),
+            );
+        }
+    };
+    match state.faucet.quota_status(address).await {
+        Ok(v) => (StatusCode::OK, Json(QuotaFaucetResponse::from(v))),
+        Err(v) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(QuotaFaucetResponse::from(v)),
+        ),
+    }
+}
+
+/// Returns the IP of the client. If the faucet runs behind a reverse proxy,
+/// it is the last address of the configured header, which is the one
+/// appended by the proxy. Earlier addresses are not used as they can be set
+/// by the client. Requests that did not pass through the proxy are counted
+/// against the IP of the connection.
+fn client_ip(config: &FaucetConfig, headers: &HeaderMap, peer: SocketAddr) -> IpAddr {
+    config
+        .client_ip_header
+        .as_ref()
+        .and_then(|header| headers.get_all(header).iter().last())
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.rsplit(',').next())
+        .and_then(|ip| ip.trim().parse().ok())
+        .unwrap_or_else(|| peer.ip())
+}
+
+fn error_status(error: &FaucetError) -> StatusCode {
+    match error {
+        FaucetError::QuotaExceeded(_) => StatusCode::TOO_MANY_REQUESTS,
+        FaucetError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
 pub fn create_wallet_context(
     timeout_secs: u64,
     config_dir: PathBuf,
@@ -275,3 +364,153 @@ async fn handle_error(error: BoxError) -> impl IntoResponse {
         Cow::from(format!("Unhandled internal error: {}", error)),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use axum::response::Response;
+    use prometheus::Registry;
+    use test_cluster::TestClusterBuilder;
+
+    use super::*;
+    use crate::SimpleFaucet;
+
+    const PEER: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 12345);
+
+    async fn app_state(config: FaucetConfig, drain_coins: bool) -> Arc<AppState> {
+        let test_cluster = TestClusterBuilder::new().build().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let faucet = SimpleFaucet::new(
+            test_cluster.wallet,
+            &Registry::new(),
+            &tmp.into_path().join("faucet.wal"),
+            config.clone(),
+        )
+        .await
+        .unwrap();
+        let mut faucet = Arc::try_unwrap(faucet).unwrap();
+        if drain_coins {
+            let available = faucet.metrics.total_available_coins.get() as usize;
+            faucet.drain_gas_queue(available).await;
+        }
+        Arc::new(AppState::new(Arc::new(faucet), config))
+    }
+
+    async fn request(
+        state: &Arc<AppState>,
+        recipient: IotaAddress,
+        headers: HeaderMap,
+    ) -> Response {
+        request_gas(
+            Extension(state.clone()),
+            ConnectInfo(PEER),
+            headers,
+            Json(FaucetRequest::new_fixed_amount_request(recipient)),
+        )
+        .await
+        .into_response()
+    }
+
+    #[tokio::test]
+    async fn address_quota_is_enforced() {
+        let state = app_state(
+            FaucetConfig {
+                max_requests_per_address_per_day: Some(1),
+                ..Default::default()
+            },
+            false,
+        )
+        .await;
+        let recipient = IotaAddress::random_for_testing_only();
+
+        let response = request(&state, recipient, HeaderMap::new()).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let response = request(&state, recipient, HeaderMap::new()).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        let status = state.faucet.quota_status(recipient).await.unwrap();
+        assert_eq!(status.used, 1);
+        assert_eq!(status.remaining, Some(0));
+    }
+
+    #[tokio::test]
+    async fn quota_is_refunded_if_request_fails() {
+        let state = app_state(
+            FaucetConfig {
+                max_requests_per_address_per_day: Some(1),
+                ..Default::default()
+            },
+            true,
+        )
+        .await;
+        let recipient = IotaAddress::random_for_testing_only();
+
+        // No gas coins are left, so the request cannot be served
+        let response = request(&state, recipient, HeaderMap::new()).await;
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let status = state.faucet.quota_status(recipient).await.unwrap();
+        assert_eq!(status.used, 0);
+        assert_eq!(status.remaining, Some(1));
+    }
+
+    #[tokio::test]
+    async fn ip_quota_applies_without_client_ip_header() {
+        let state = app_state(
+            FaucetConfig {
+                max_requests_per_ip_per_day: Some(1),
+                client_ip_header: Some("x-forwarded-for".to_string()),
+                ..Default::default()
+            },
+            false,
+        )
+        .await;
+
+        let response = request(
+            &state,
+            IotaAddress::random_for_testing_only(),
+            HeaderMap::new(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        // Requests without the header are counted against the peer IP
+        let response = request(
+            &state,
+            IotaAddress::random_for_testing_only(),
+            HeaderMap::new(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn invalid_quota_address_is_a_client_error() {
+        let state = app_state(FaucetConfig::default(), false).await;
+        let response = request_quota(Extension(state), Path("not an address".to_string()))
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn client_ip_is_taken_from_the_proxy() {
+        let config = FaucetConfig {
+            client_ip_header: Some("x-forwarded-for".to_string()),
+            ..Default::default()
+        };
+        let mut headers = HeaderMap::new();
+        // The first address is set by the client, the last one by the proxy
+        headers.insert("x-forwarded-for", "1.1.1.1, 2.2.2.2".parse().unwrap());
+        assert_eq!(
+            client_ip(&config, &headers, PEER),
+            "2.2.2.2".parse::<IpAddr>().unwrap()
+        );
+
+        assert_eq!(client_ip(&config, &HeaderMap::new(), PEER), PEER.ip());
+        assert_eq!(
+            client_ip(&FaucetConfig::default(), &headers, PEER),
+            PEER.ip()
+        );
+    }
+}
@@ -5,6 +5,10 @@
 /// Module which defines hash functions. Note that Sha-256 and Sha3-256 is available in the std::hash module in the
 /// standard library.
 module iota::hash {
+    #[allow(unused_const)]
+    /// Error if the 512-bit hash functions are not enabled by the current protocol version.
+    const ENotSupported: u64 = 0;
+
     /// @param data: Arbitrary binary data to hash
     /// Hash the input bytes using Blake2b-256 and returns 32 bytes.
     native public fun blake2b256(data: &vector<u8>): vector<u8>;
@@ -12,4 +16,22 @@ module iota::hash {
     /// @param data: Arbitrary binary data to hash
     /// Hash the input bytes using keccak256 and returns 32 bytes.
     native public fun keccak256(data: &vector<u8>): vector<u8>;
+
+    /// @param data: Arbitrary binary data to hash
+    /// Hash the input bytes using Blake2b-512 and returns 64 bytes.
+    /// Aborts with `ENotSupported` if the 512-bit hash functions are not enabled
+    /// by the current protocol version.
+    native public fun blake2b512(data: &vector<u8>): vector<u8>;
+
+    /// @param data: Arbitrary binary data to hash
+    /// Hash the input bytes using SHA-512 and returns 64 bytes.
+    /// Aborts with `ENotSupported` if the 512-bit hash functions are not enabled
+    /// by the current protocol version.
+    native public fun sha512(data: &vector<u8>): vector<u8>;
+
+    /// @param data: Arbitrary binary data to hash
+    /// Hash the input bytes using keccak512 and returns 64 bytes.
+    /// Aborts with `ENotSupported` if the 512-bit hash functions are not enabled
+    /// by the current protocol version.
+    native public fun keccak512(data: &vector<u8>): vector<u8>;
 }
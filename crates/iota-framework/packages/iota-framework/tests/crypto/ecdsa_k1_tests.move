@@ -215,4 +215,48 @@ module iota::ecdsa_k1_tests {
         ecdsa_k1::secp256k1_keypair_from_seed(&seed);
     }
 
+    // Test vectors from https://github.com/bitcoin/bips/blob/master/bip-0340/test-vectors.csv
+    #[test]
+    fun test_secp256k1_schnorr_verify() {
+        let pk = x"f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9";
+        let msg = x"0000000000000000000000000000000000000000000000000000000000000000";
+        let sig = x"e907831f80848d1069a5371b402410364bdf1c5f8307b0084c55f1ce2dca821525f66a4a85ea8b71e482a74f382d2ce5ebeee8fdb2172f477df4900d310536c0";
+        assert!(ecdsa_k1::secp256k1_schnorr_verify(&sig, &pk, &msg));
+
+        let pk = x"dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659";
+        let msg = x"243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89";
+        let sig = x"6896bd60eeae296db48a229ff71dfe071bde413e6d43f917dc8dcf8c78de33418906d11ac976abccb20b091292bff4ea897efcb639ea871cfa95f6de339e4b0a";
+        assert!(ecdsa_k1::secp256k1_schnorr_verify(&sig, &pk, &msg));
+    }
+
+    #[test]
+    fun test_secp256k1_schnorr_invalid() {
+        let pk = x"dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659";
+        let msg = x"243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89";
+        let sig = x"6896bd60eeae296db48a229ff71dfe071bde413e6d43f917dc8dcf8c78de33418906d11ac976abccb20b091292bff4ea897efcb639ea871cfa95f6de339e4b0a";
+
+        // Wrong message
+        let other_msg = x"243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c88";
+        assert!(!ecdsa_k1::secp256k1_schnorr_verify(&sig, &pk, &other_msg));
+
+        // Wrong public key
+        let other_pk = x"f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9";
+        assert!(!ecdsa_k1::secp256k1_schnorr_verify(&sig, &other_pk, &msg));
+
+        // Public key not on the curve
+        let invalid_pk = x"eefdea4cdb677750a420fee807eacf21eb9898ae79b9768766e4faa04a2d4a34";
+        assert!(!ecdsa_k1::secp256k1_schnorr_verify(&sig, &invalid_pk, &msg));
+
+        // Public key of invalid length
+        let compressed_pk = x"02dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659";
+        assert!(!ecdsa_k1::secp256k1_schnorr_verify(&sig, &compressed_pk, &msg));
+
+        // Signature of invalid length
+        let short_sig = x"6896bd60eeae296db48a229ff71dfe071bde413e6d43f917dc8dcf8c78de33418906d11ac976abccb20b091292bff4ea897efcb639ea871cfa95f6de339e4b";
+        assert!(!ecdsa_k1::secp256k1_schnorr_verify(&short_sig, &pk, &msg));
+
+        // Message that is not 32 bytes long
+        let long_msg = b"Hello, world! This message is longer than 32 bytes.";
+        assert!(!ecdsa_k1::secp256k1_schnorr_verify(&sig, &pk, &long_msg));
+    }
 }
@@ -32,4 +32,42 @@ module iota::hash_tests {
         let _ = hash::blake2b256(&long_msg);
     }
 
+    #[test]
+    fun test_blake2b512_hash() {
+        let msg = b"hello world!";
+        let hashed_msg_bytes = x"fa02d55d26bc5cda1e2d67fb7424f6132c58fed81a52816342795de54d3b2d8b91749f267d2491ed05ca0cbbd0e641cc1758b92e99eb1d8771060ebacbc83c25";
+        let hashed_msg = hash::blake2b512(&msg);
+        assert!(hashed_msg == hashed_msg_bytes);
+
+        let empty_msg = b"";
+        let _ = hash::blake2b512(&empty_msg);
+        let long_msg = b"57caa176af1ac0433c5df30e8dabcd2ec1af1e92a26eced5f719b88458777cd657caa176af1ac0433c5df30e8dabcd2ec1af1e92a26eced5f719b88458777cd657caa176af1ac0433c5df30e8dabcd2ec1af1e92a26eced5f719b88458777cd657caa176af1ac0433c5df30e8dabcd2ec1af1e92a26eced5f719b88458777cd657caa176af1ac0433c5df30e8dabcd2ec1af1e92a26eced5f719b88458777cd657caa176af1ac0433c5df30e8dabcd2ec1af1e92a26eced5f719b88458777cd657caa176af1ac0433c5df30e8dabcd2ec1af1e92a26eced5f719b88458777cd657caa176af1ac0433c5df30e8dabcd2ec1af1e92a26eced5f719b88458777cd6";
+        let _ = hash::blake2b512(&long_msg);
+    }
+
+    #[test]
+    fun test_sha512_hash() {
+        let msg = b"hello world!";
+        let hashed_msg_bytes = x"db9b1cd3262dee37756a09b9064973589847caa8e53d31a9d142ea2701b1b28abd97838bb9a27068ba305dc8d04a45a1fcf079de54d607666996b3cc54f6b67c";
+        let hashed_msg = hash::sha512(&msg);
+        assert!(hashed_msg == hashed_msg_bytes);
+
+        let empty_msg = b"";
+        let _ = hash::sha512(&empty_msg);
+        let long_msg = b"57caa176af1ac0433c5df30e8dabcd2ec1af1e92a26eced5f719b88458777cd657caa176af1ac0433c5df30e8dabcd2ec1af1e92a26eced5f719b88458777cd657caa176af1ac0433c5df30e8dabcd2ec1af1e92a26eced5f719b88458777cd657caa176af1ac0433c5df30e8dabcd2ec1af1e92a26eced5f719b88458777cd657caa176af1ac0433c5df30e8dabcd2ec1af1e92a26eced5f719b88458777cd657caa176af1ac0433c5df30e8dabcd2ec1af1e92a26eced5f719b88458777cd657caa176af1ac0433c5df30e8dabcd2ec1af1e92a26eced5f719b88458777cd657caa176af1ac0433c5df30e8dabcd2ec1af1e92a26eced5f719b88458777cd6";
+        let _ = hash::sha512(&long_msg);
+    }
+
+    #[test]
+    fun test_keccak512_hash() {
+        let msg = b"hello world!";
+        let hashed_msg_bytes = x"d50b81a30b0ee3b355ae0b5af973fa28518377822165c76b735404470aaf91553ef4a5cf43a3511f2fc0decfd14da1048e244ec1a94aac2fbde2ae87c2d95455";
+        let hashed_msg = hash::keccak512(&msg);
+        assert!(hashed_msg == hashed_msg_bytes);
+
+        let empty_msg = b"";
+        let _ = hash::keccak512(&empty_msg);
+        let long_msg = b"57caa176af1ac0433c5df30e8dabcd2ec1af1e92a26eced5f719b88458777cd657caa176af1ac0433c5df30e8dabcd2ec1af1e92a26eced5f719b88458777cd657caa176af1ac0433c5df30e8dabcd2ec1af1e92a26eced5f719b88458777cd657caa176af1ac0433c5df30e8dabcd2ec1af1e92a26eced5f719b88458777cd657caa176af1ac0433c5df30e8dabcd2ec1af1e92a26eced5f719b88458777cd657caa176af1ac0433c5df30e8dabcd2ec1af1e92a26eced5f719b88458777cd657caa176af1ac0433c5df30e8dabcd2ec1af1e92a26eced5f719b88458777cd657caa176af1ac0433c5df30e8dabcd2ec1af1e92a26eced5f719b88458777cd6";
+        let _ = hash::keccak512(&long_msg);
+    }
 }
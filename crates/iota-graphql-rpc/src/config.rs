@@ -103,6 +103,11 @@ pub struct Limits {
     pub max_transaction_ids: u32,
     /// Maximum number of candidates to scan when gathering a page of results.
     pub max_scan_limit: u32,
+    /// Maximum number of WebSocket connections serving subscriptions at once.
+    pub max_subscription_connections: u32,
+    /// Maximum number of subscriptions active at once on a single WebSocket
+    /// connection.
+    pub max_subscriptions_per_connection: u32,
 }
 
 #[GraphQLConfig]
@@ -314,6 +319,17 @@ impl ServiceConfig {
     async fn max_scan_limit(&self) -> u32 {
         self.limits.max_scan_limit
     }
+
+    /// Maximum number of WebSocket connections serving subscriptions at once.
+    async fn max_subscription_connections(&self) -> u32 {
+        self.limits.max_subscription_connections
+    }
+
+    /// Maximum number of subscriptions active at once on a single WebSocket
+    /// connection.
+    async fn max_subscriptions_per_connection(&self) -> u32 {
+        self.limits.max_subscriptions_per_connection
+    }
 }
 
 impl TxExecFullNodeConfig {
@@ -488,6 +504,9 @@ impl Default for Limits {
             // for the `TransactionBlockFilter`.
             max_transaction_ids: 1000,
             max_scan_limit: 100_000_000,
+            // Every subscription polls the database once per checkpoint.
+            max_subscription_connections: 1000,
+            max_subscriptions_per_connection: 10,
         }
     }
 }
@@ -552,6 +571,8 @@ mod tests {
                 max-move-value-depth = 256
                 max-transaction-ids = 11
                 max-scan-limit = 50
+                max-subscription-connections = 100
+                max-subscriptions-per-connection = 5
             "#,
         )
         .unwrap();
@@ -573,6 +594,8 @@ mod tests {
                 max_move_value_depth: 256,
                 max_transaction_ids: 11,
                 max_scan_limit: 50,
+                max_subscription_connections: 100,
+                max_subscriptions_per_connection: 5,
             },
             ..Default::default()
         };
@@ -637,6 +660,8 @@ mod tests {
                 max-move-value-depth = 256
                 max-transaction-ids = 42
                 max-scan-limit = 420
+                max-subscription-connections = 42
+                max-subscriptions-per-connection = 4
 
                 [experiments]
                 test-flag = true
@@ -661,6 +686,8 @@ mod tests {
                 max_move_value_depth: 256,
                 max_transaction_ids: 42,
                 max_scan_limit: 420,
+                max_subscription_connections: 42,
+                max_subscriptions_per_connection: 4,
             },
             disabled_features: BTreeSet::from([FunctionalGroup::Analytics]),
             experiments: Experiments { test_flag: true },
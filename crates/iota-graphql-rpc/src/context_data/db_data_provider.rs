@@ -8,9 +8,13 @@ use diesel::PgConnection;
 use iota_indexer::{
     apis::GovernanceReadApi, db::ConnectionPoolConfig, indexer_reader::IndexerReader,
 };
-use iota_json_rpc_types::Stake as RpcStakedIota;
+use iota_json_rpc_api::QUERY_MAX_TRANSACTION_DEPENDENCY_EDGES;
+use iota_json_rpc_types::{
+    CheckpointId, Stake as RpcStakedIota,
+    TransactionDependencyGraph as RpcTransactionDependencyGraph,
+};
 use iota_types::{
-    governance::StakedIota as NativeStakedIota,
+    digests::TransactionDigest, governance::StakedIota as NativeStakedIota,
     iota_system_state::iota_system_state_summary::IotaSystemStateSummary as NativeIotaSystemStateSummary,
 };
 
@@ -66,6 +70,31 @@ impl PgManager {
         }
     }
 
+    /// Fetch the ancestors and descendants of a transaction up to `depth`
+    /// levels away from the indexed transaction dependencies, leaving out the
+    /// transactions after `checkpoint_viewed_at`.
+    pub(crate) async fn fetch_transaction_dependency_graph(
+        &self,
+        digest: TransactionDigest,
+        depth: usize,
+        checkpoint_viewed_at: u64,
+    ) -> Result<RpcTransactionDependencyGraph, Error> {
+        Ok(self
+            .inner
+            .spawn_blocking(move |this| {
+                let tx_hi = this
+                    .get_checkpoint_from_db(CheckpointId::SequenceNumber(checkpoint_viewed_at))?
+                    .map_or(0, |checkpoint| checkpoint.network_total_transactions as u64);
+                this.get_transaction_dependency_graph(
+                    digest,
+                    depth,
+                    QUERY_MAX_TRANSACTION_DEPENDENCY_EDGES,
+                    Some(tx_hi),
+                )
+            })
+            .await?)
+    }
+
     /// Make a request to the RPC for its representations of the staked iota we
     /// parsed out of the object.  Used to implement fields that are
     /// implemented in JSON-RPC but not GraphQL (yet).
@@ -11,14 +11,14 @@ use std::{
 };
 
 use async_graphql::{
-    EmptySubscription, Schema, SchemaBuilder,
+    Data, Schema, SchemaBuilder,
     extensions::{ApolloTracing, ExtensionFactory, Tracing},
 };
-use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use async_graphql_axum::{GraphQLProtocol, GraphQLRequest, GraphQLResponse, GraphQLWebSocket};
 use axum::{
     Extension, Router,
     body::Body,
-    extract::{ConnectInfo, FromRef, Query as AxumQuery, State},
+    extract::{ConnectInfo, FromRef, Query as AxumQuery, State, WebSocketUpgrade},
     http::{HeaderMap, StatusCode},
     middleware::{self},
     response::IntoResponse,
@@ -31,7 +31,11 @@ use iota_metrics::spawn_monitored_task;
 use iota_network_stack::callback::{CallbackLayer, MakeCallbackHandler, ResponseHandler};
 use iota_package_resolver::{PackageStoreWithLruCache, Resolver};
 use iota_sdk::IotaClientBuilder;
-use tokio::{join, net::TcpListener, sync::OnceCell};
+use tokio::{
+    join,
+    net::TcpListener,
+    sync::{OnceCell, watch},
+};
 use tokio_util::sync::CancellationToken;
 use tower::{Layer, Service};
 use tower_http::cors::{AllowOrigin, CorsLayer};
@@ -65,6 +69,7 @@ use crate::{
         version::{check_version_middleware, set_version_middleware},
         watermark_task::{Watermark, WatermarkLock, WatermarkTask},
     },
+    subscription::{ConnectionPermits, Subscription, SubscriptionPermits},
     types::{
         datatype::IMoveDatatype,
         move_object::IMoveObject,
@@ -163,7 +168,7 @@ impl Server {
 
 pub(crate) struct ServerBuilder {
     state: AppState,
-    schema: SchemaBuilder<Query, Mutation, EmptySubscription>,
+    schema: SchemaBuilder<Query, Mutation, Subscription>,
     router: Option<Router>,
     db_reader: Option<Db>,
     resolver: Option<PackageResolver>,
@@ -175,6 +180,7 @@ pub(crate) struct AppState {
     service: ServiceConfig,
     metrics: Metrics,
     cancellation_token: CancellationToken,
+    subscription_connections: ConnectionPermits,
     pub version: Version,
 }
 
@@ -186,11 +192,13 @@ impl AppState {
         cancellation_token: CancellationToken,
         version: Version,
     ) -> Self {
+        let subscription_connections = ConnectionPermits::new(&service.limits);
         Self {
             connection,
             service,
             metrics,
             cancellation_token,
+            subscription_connections,
             version,
         }
     }
@@ -236,7 +244,7 @@ impl ServerBuilder {
         self
     }
 
-    fn build_schema(self) -> Schema<Query, Mutation, EmptySubscription> {
+    fn build_schema(self) -> Schema<Query, Mutation, Subscription> {
         self.schema.finish()
     }
 
@@ -246,7 +254,7 @@ impl ServerBuilder {
         self,
     ) -> (
         String,
-        Schema<Query, Mutation, EmptySubscription>,
+        Schema<Query, Mutation, Subscription>,
         Db,
         PackageResolver,
         Router,
@@ -275,6 +283,9 @@ impl ServerBuilder {
                 .route("/:version", post(graphql_handler))
                 .route("/graphql", post(graphql_handler))
                 .route("/graphql/:version", post(graphql_handler))
+                .route("/subscriptions", get(subscription_handler))
+                .route("/graphql/subscriptions", get(subscription_handler))
+                .route("/graphql/:version/subscriptions", get(subscription_handler))
                 .route("/health", get(health_check))
                 .route("/graphql/health", get(health_check))
                 .route("/graphql/:version/health", get(health_check))
@@ -324,8 +335,8 @@ impl ServerBuilder {
         info!("Access control allow origin set to: {acl:?}");
 
         let cors = CorsLayer::new()
-            // Allow `POST` when accessing the resource
-            .allow_methods([Method::POST])
+            // Allow `POST` when accessing the resource, and `GET` to open subscriptions
+            .allow_methods([Method::POST, Method::GET])
             // Allow requests from any origin
             .allow_origin(acl)
             .allow_headers([hyper::header::CONTENT_TYPE, LIMITS_HEADER.clone()]);
@@ -368,6 +379,9 @@ impl ServerBuilder {
             ))
             .layer(axum::extract::Extension(schema))
             .layer(axum::extract::Extension(watermark_task.lock()))
+            .layer(axum::extract::Extension(
+                watermark_task.checkpoint_receiver(),
+            ))
             .layer(Self::cors()?);
 
         Ok(Server {
@@ -521,8 +535,8 @@ impl ServerBuilder {
     }
 }
 
-fn schema_builder() -> SchemaBuilder<Query, Mutation, EmptySubscription> {
-    async_graphql::Schema::build(Query, Mutation, EmptySubscription)
+fn schema_builder() -> SchemaBuilder<Query, Mutation, Subscription> {
+    async_graphql::Schema::build(Query, Mutation, Subscription)
         .register_output_type::<IMoveObject>()
         .register_output_type::<IObject>()
         .register_output_type::<IOwner>()
@@ -567,6 +581,48 @@ async fn graphql_handler(
     (extensions, result.into())
 }
 
+/// Entry point for graphql subscriptions over WebSocket. Each connection is
+/// stamped with a unique ID and the watermark at the time it was opened, like
+/// requests, and receives new checkpoints from the background task to drive its
+/// subscriptions. Connections beyond the service limit are rejected.
+async fn subscription_handler(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    State(state): State<AppState>,
+    Extension(schema): Extension<IotaGraphQLSchema>,
+    Extension(watermark_lock): Extension<WatermarkLock>,
+    Extension(checkpoint_receiver): Extension<watch::Receiver<Option<u64>>>,
+    protocol: GraphQLProtocol,
+    upgrade: WebSocketUpgrade,
+) -> axum::response::Response {
+    let Some(connection_permit) = state.subscription_connections.try_acquire() else {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!(
+                "Too many subscription connections. Limit: {}",
+                state.service.limits.max_subscription_connections
+            ),
+        )
+            .into_response();
+    };
+
+    let mut data = Data::default();
+    data.insert(Uuid::new_v4());
+    data.insert(addr);
+    data.insert(Watermark::new(watermark_lock).await);
+    data.insert(checkpoint_receiver);
+    data.insert(SubscriptionPermits::new(&state.service.limits));
+
+    upgrade
+        .protocols(async_graphql::http::ALL_WEBSOCKET_PROTOCOLS)
+        .on_upgrade(move |stream| async move {
+            GraphQLWebSocket::new(stream, schema, protocol)
+                .with_data(data)
+                .serve()
+                .await;
+            drop(connection_permit);
+        })
+}
+
 #[derive(Clone)]
 struct MetricsMakeCallbackHandler {
     metrics: Metrics,
@@ -694,7 +750,9 @@ pub mod tests {
     use async_graphql::{
         Response,
         extensions::{Extension, ExtensionContext, NextExecute},
+        value,
     };
+    use futures::StreamExt;
     use iota_sdk::{IotaClient, wallet_context::WalletContext};
     use iota_types::transaction::TransactionData;
     use uuid::Uuid;
@@ -1106,4 +1164,42 @@ pub mod tests {
         let resp = reqwest::get(&url_with_param).await.unwrap();
         assert_eq!(resp.status(), reqwest::StatusCode::GATEWAY_TIMEOUT);
     }
+
+    /// Expects the last indexed checkpoint to contain a single transaction,
+    /// with the given digest.
+    pub async fn test_subscription_new_transactions_impl(
+        connection_config: ConnectionConfig,
+        last_checkpoint: u64,
+        digest: &str,
+    ) {
+        let service_config = ServiceConfig::default();
+        let (sender, receiver) = watch::channel(Some(last_checkpoint - 1));
+        let schema = prep_schema(Some(connection_config), Some(service_config.clone()))
+            .context_data(receiver)
+            .context_data(SubscriptionPermits::new(&service_config.limits))
+            .build_schema();
+        let mut stream = schema.execute_stream("subscription { transactions { digest } }");
+
+        // Nothing is streamed until a new checkpoint is indexed.
+        assert!(
+            tokio::time::timeout(Duration::from_millis(500), stream.next())
+                .await
+                .is_err()
+        );
+
+        sender.send(Some(last_checkpoint)).unwrap();
+        let resp = tokio::time::timeout(Duration::from_secs(10), stream.next())
+            .await
+            .expect("Timed out waiting for the new transaction")
+            .unwrap();
+        assert!(resp.errors.is_empty(), "{:?}", resp.errors);
+        assert_eq!(resp.data, value!({ "transactions": { "digest": digest } }));
+
+        // Transactions of the checkpoints known when subscribing are not streamed.
+        assert!(
+            tokio::time::timeout(Duration::from_millis(500), stream.next())
+                .await
+                .is_err()
+        );
+    }
 }
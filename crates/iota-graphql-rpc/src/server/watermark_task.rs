@@ -28,6 +28,9 @@ pub(crate) struct WatermarkTask {
     cancel: CancellationToken,
     sender: watch::Sender<u64>,
     receiver: watch::Receiver<u64>,
+    /// The latest checkpoint, `None` until the watermark is first read.
+    checkpoint_sender: watch::Sender<Option<u64>>,
+    checkpoint_receiver: watch::Receiver<Option<u64>>,
 }
 
 pub(crate) type WatermarkLock = Arc<RwLock<Watermark>>;
@@ -54,6 +57,7 @@ impl WatermarkTask {
         cancel: CancellationToken,
     ) -> Self {
         let (sender, receiver) = watch::channel(0);
+        let (checkpoint_sender, checkpoint_receiver) = watch::channel(None);
 
         Self {
             watermark: Default::default(),
@@ -63,6 +67,8 @@ impl WatermarkTask {
             cancel,
             sender,
             receiver,
+            checkpoint_sender,
+            checkpoint_receiver,
         }
     }
 
@@ -92,6 +98,14 @@ impl WatermarkTask {
                         mem::replace(&mut w.epoch, epoch)
                     };
 
+                    self.checkpoint_sender.send_if_modified(|latest| {
+                        let modified = latest.is_none_or(|latest| checkpoint > latest);
+                        if modified {
+                            *latest = Some(checkpoint);
+                        }
+                        modified
+                    });
+
                     if epoch > prev_epoch {
                         self.sender.send(epoch).unwrap();
                     }
@@ -108,6 +122,11 @@ impl WatermarkTask {
     pub(crate) fn epoch_receiver(&self) -> watch::Receiver<u64> {
         self.receiver.clone()
     }
+
+    /// Receiver for subscribing to new checkpoints being indexed.
+    pub(crate) fn checkpoint_receiver(&self) -> watch::Receiver<Option<u64>> {
+        self.checkpoint_receiver.clone()
+    }
 }
 
 impl Watermark {
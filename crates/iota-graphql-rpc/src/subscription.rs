@@ -0,0 +1,493 @@
+// Copyright (c) 2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use async_graphql::{connection::CursorType, *};
+use futures::{Stream, StreamExt, stream};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore, watch};
+
+use crate::{
+    config::{Limits, ServiceConfig},
+    data::Db,
+    error::Error,
+    functional_group::FunctionalGroup,
+    types::{
+        cursor::Page,
+        event::{self, Event, EventFilter, EventKey},
+        transaction_block::{self, TransactionBlock, TransactionBlockFilter},
+        uint53::UInt53,
+    },
+};
+
+pub struct Subscription;
+
+/// Permits for the WebSocket connections serving subscriptions, shared by the
+/// whole service.
+#[derive(Clone)]
+pub(crate) struct ConnectionPermits(Arc<Semaphore>);
+
+/// Permits for the subscriptions of a single WebSocket connection.
+#[derive(Clone)]
+pub(crate) struct SubscriptionPermits(Arc<Semaphore>);
+
+/// Progress of a subscription through the checkpoints being indexed. Items are
+/// fetched one page at a time, as the subscriber consumes them.
+struct Progress<C> {
+    checkpoints: watch::Receiver<Option<u64>>,
+    /// The checkpoint the items are currently paged at, `None` while waiting
+    /// for a new checkpoint.
+    checkpoint_viewed_at: Option<u64>,
+    /// Where to fetch the next page from.
+    cursor: C,
+    /// Whether fetching the last page failed, in which case it is retried once
+    /// a new checkpoint is indexed.
+    failed: bool,
+    /// Released when the subscription ends.
+    _permit: OwnedSemaphorePermit,
+}
+
+/// Subscriptions stream data from the Iota network as it gets indexed. Each
+/// subscription starts at the latest checkpoint known to the service and
+/// yields every matching item from the checkpoints that follow, in order.
+///
+/// Subscriptions are served over WebSocket, and are subject to the same query
+/// limits as queries.
+#[Subscription]
+impl Subscription {
+    /// Stream the transaction blocks that match the given filter, as the
+    /// checkpoints they belong to get indexed.
+    ///
+    /// Filters that require a `scanLimit` in `Query.transactionBlocks` are
+    /// scanned with the maximum scan limit of the service, per page.
+    async fn transactions<'ctx>(
+        &self,
+        ctx: &'ctx Context<'_>,
+        filter: Option<TransactionBlockFilter>,
+    ) -> Result<impl Stream<Item = Result<TransactionBlock>> + 'ctx> {
+        let cfg: &ServiceConfig = ctx.data_unchecked();
+        check_enabled(cfg)?;
+        let filter = filter.unwrap_or_default();
+        let scan_limit = filter
+            .requires_scan_limit()
+            .then_some(cfg.limits.max_scan_limit as u64);
+
+        let (progress, start) = Progress::start(ctx, cfg)?;
+        let progress = progress.with_cursor((start, None));
+
+        Ok(stream::unfold(progress, move |mut progress| {
+            let filter = filter.clone();
+            async move {
+                let checkpoint_viewed_at = progress.next_checkpoint().await?;
+                let (after_checkpoint, after) = progress.cursor.clone();
+                let result = transaction_blocks_page(
+                    ctx,
+                    filter,
+                    after_checkpoint,
+                    after,
+                    checkpoint_viewed_at,
+                    scan_limit,
+                )
+                .await;
+
+                match &result {
+                    Ok((_, Some(next))) => progress.cursor.1 = Some(next.clone()),
+                    Ok((_, None)) => {
+                        progress.cursor = (checkpoint_viewed_at, None);
+                        progress.checkpoint_viewed_at = None;
+                    }
+                    Err(_) => progress.failed = true,
+                }
+                Some((result.map(|(transactions, _)| transactions), progress))
+            }
+        })
+        .flat_map(flatten))
+    }
+
+    /// Stream the events that match the given filter, as the checkpoints they
+    /// were emitted in get indexed.
+    async fn events<'ctx>(
+        &self,
+        ctx: &'ctx Context<'_>,
+        filter: Option<EventFilter>,
+    ) -> Result<impl Stream<Item = Result<Event>> + 'ctx> {
+        let cfg: &ServiceConfig = ctx.data_unchecked();
+        check_enabled(cfg)?;
+        let filter = filter.unwrap_or_default();
+
+        let (progress, start) = Progress::start(ctx, cfg)?;
+        let db: &Db = ctx.data_unchecked();
+
+        // Events can only be bounded by cursor, so resume after the latest event
+        // matching the filter at the time of subscribing.
+        let page = Page::from_params(cfg, None, None, Some(1), None)?;
+        let latest = Event::paginate(db, page, filter.clone(), start)
+            .await
+            .extend()?;
+        let after = latest
+            .edges
+            .last()
+            .map(|edge| decode_event_key(&edge.cursor))
+            .transpose()
+            .extend()?;
+        let progress = progress.with_cursor(after);
+
+        Ok(stream::unfold(progress, move |mut progress| {
+            let filter = filter.clone();
+            async move {
+                let checkpoint_viewed_at = progress.next_checkpoint().await?;
+                let result =
+                    events_page(ctx, filter, progress.cursor.clone(), checkpoint_viewed_at).await;
+
+                match &result {
+                    Ok((_, last, has_next_page)) => {
+                        if last.is_some() {
+                            progress.cursor = last.clone();
+                        }
+                        if !has_next_page {
+                            progress.checkpoint_viewed_at = None;
+                        }
+                    }
+                    Err(_) => progress.failed = true,
+                }
+                Some((result.map(|(events, _, _)| events), progress))
+            }
+        })
+        .flat_map(flatten))
+    }
+}
+
+impl ConnectionPermits {
+    pub(crate) fn new(limits: &Limits) -> Self {
+        Self(Arc::new(Semaphore::new(
+            limits.max_subscription_connections as usize,
+        )))
+    }
+
+    /// Reserves a connection, until the permit is dropped. Returns `None` if
+    /// the service serves the maximum number of connections already.
+    pub(crate) fn try_acquire(&self) -> Option<OwnedSemaphorePermit> {
+        self.0.clone().try_acquire_owned().ok()
+    }
+}
+
+impl SubscriptionPermits {
+    pub(crate) fn new(limits: &Limits) -> Self {
+        Self(Arc::new(Semaphore::new(
+            limits.max_subscriptions_per_connection as usize,
+        )))
+    }
+}
+
+impl Progress<()> {
+    /// Reserves a subscription on the connection and returns its progress,
+    /// along with the latest checkpoint it starts after. Fails if the
+    /// connection has the maximum number of subscriptions already, or if the
+    /// service has not read the latest checkpoint yet.
+    fn start(ctx: &Context<'_>, cfg: &ServiceConfig) -> Result<(Self, u64)> {
+        let permits: &SubscriptionPermits = ctx
+            .data()
+            .map_err(|_| Error::Internal("Unable to fetch subscription permits".to_string()))
+            .extend()?;
+        let permit = permits
+            .0
+            .clone()
+            .try_acquire_owned()
+            .map_err(|_| {
+                Error::Client(format!(
+                    "Too many subscriptions on this connection. Limit: {}",
+                    cfg.limits.max_subscriptions_per_connection
+                ))
+            })
+            .extend()?;
+
+        let mut checkpoints = ctx
+            .data::<watch::Receiver<Option<u64>>>()
+            .cloned()
+            .map_err(|_| Error::Internal("Unable to fetch checkpoint receiver".to_string()))
+            .extend()?;
+        let Some(start) = *checkpoints.borrow_and_update() else {
+            return Err(Error::Internal(
+                "The latest checkpoint is not known yet, try again later".to_string(),
+            ))
+            .extend();
+        };
+
+        let progress = Self {
+            checkpoints,
+            checkpoint_viewed_at: None,
+            cursor: (),
+            failed: false,
+            _permit: permit,
+        };
+        Ok((progress, start))
+    }
+
+    fn with_cursor<C>(self, cursor: C) -> Progress<C> {
+        Progress {
+            checkpoints: self.checkpoints,
+            checkpoint_viewed_at: self.checkpoint_viewed_at,
+            cursor,
+            failed: self.failed,
+            _permit: self._permit,
+        }
+    }
+}
+
+impl<C> Progress<C> {
+    /// The checkpoint to fetch the next page at. Waits for a new checkpoint if
+    /// all items up to the current one have been fetched, or if the last page
+    /// failed. Returns `None` once the service shuts down.
+    async fn next_checkpoint(&mut self) -> Option<u64> {
+        match self.checkpoint_viewed_at {
+            Some(checkpoint) if !self.failed => return Some(checkpoint),
+            _ => {}
+        }
+        self.checkpoints.changed().await.ok()?;
+        let latest = (*self.checkpoints.borrow_and_update())?;
+        self.failed = false;
+        Some(*self.checkpoint_viewed_at.get_or_insert(latest))
+    }
+}
+
+/// Root subscription fields are not resolved through the `FeatureGate`
+/// extension, so the functional group is checked when subscribing instead.
+fn check_enabled(cfg: &ServiceConfig) -> Result<()> {
+    if cfg
+        .disabled_features
+        .contains(&FunctionalGroup::Subscriptions)
+    {
+        return Err(Error::Client(format!(
+            "Feature {} is disabled.",
+            FunctionalGroup::Subscriptions.name()
+        )))
+        .extend();
+    }
+    Ok(())
+}
+
+/// Fetch a page of the transaction blocks matching `filter` from the
+/// checkpoints after `after_checkpoint`, up to and including
+/// `checkpoint_viewed_at`. Also returns the cursor of the next page, if any.
+async fn transaction_blocks_page(
+    ctx: &Context<'_>,
+    filter: TransactionBlockFilter,
+    after_checkpoint: u64,
+    after: Option<transaction_block::Cursor>,
+    checkpoint_viewed_at: u64,
+    scan_limit: Option<u64>,
+) -> Result<(Vec<TransactionBlock>, Option<transaction_block::Cursor>)> {
+    let cfg: &ServiceConfig = ctx.data_unchecked();
+    let Some(filter) = filter.intersect(TransactionBlockFilter {
+        after_checkpoint: Some(UInt53::from(after_checkpoint)),
+        ..Default::default()
+    }) else {
+        return Ok((vec![], None));
+    };
+
+    let page = Page::from_params(
+        cfg,
+        Some(cfg.limits.max_page_size as u64),
+        after,
+        None,
+        None,
+    )?;
+    let conn = TransactionBlock::paginate(ctx, page, filter, checkpoint_viewed_at, scan_limit)
+        .await
+        .extend()?;
+
+    // Pages of scan limited queries may be empty and only carry an end cursor.
+    let end_cursor = conn
+        .end_cursor
+        .clone()
+        .or_else(|| conn.edges.last().map(|edge| edge.cursor.clone()));
+    let next = match end_cursor {
+        Some(cursor) if conn.has_next_page => Some(
+            transaction_block::Cursor::decode_cursor(&cursor)
+                .map_err(|e| Error::Internal(format!("Invalid cursor: {e}")))
+                .extend()?,
+        ),
+        _ => None,
+    };
+    let transactions = conn.edges.into_iter().map(|edge| edge.node).collect();
+    Ok((transactions, next))
+}
+
+/// Fetch a page of the events matching `filter` that come after the event at
+/// `after`, as of `checkpoint_viewed_at`. Also returns the key of the last
+/// event fetched, to resume from, and whether there are more events to fetch.
+async fn events_page(
+    ctx: &Context<'_>,
+    filter: EventFilter,
+    after: Option<EventKey>,
+    checkpoint_viewed_at: u64,
+) -> Result<(Vec<Event>, Option<EventKey>, bool)> {
+    let cfg: &ServiceConfig = ctx.data_unchecked();
+    let db: &Db = ctx.data_unchecked();
+
+    // Cursors pin the checkpoint they were viewed at, so move them forward to
+    // the latest checkpoint.
+    let cursor = after.map(|key| {
+        event::Cursor::new(EventKey {
+            checkpoint_viewed_at,
+            ..key
+        })
+    });
+    let page = Page::from_params(
+        cfg,
+        Some(cfg.limits.max_page_size as u64),
+        cursor,
+        None,
+        None,
+    )?;
+    let conn = Event::paginate(db, page, filter, checkpoint_viewed_at)
+        .await
+        .extend()?;
+
+    let last = conn
+        .edges
+        .last()
+        .map(|edge| decode_event_key(&edge.cursor))
+        .transpose()
+        .extend()?;
+    let events = conn.edges.into_iter().map(|edge| edge.node).collect();
+    Ok((events, last, conn.has_next_page))
+}
+
+fn decode_event_key(cursor: &str) -> Result<EventKey, Error> {
+    event::Cursor::decode_cursor(cursor)
+        .map(|cursor| (*cursor).clone())
+        .map_err(|e| Error::Internal(format!("Invalid cursor: {e}")))
+}
+
+/// Turns the result of fetching a page of items into a stream of items, or a
+/// single error.
+fn flatten<T>(result: Result<Vec<T>>) -> stream::Iter<std::vec::IntoIter<Result<T>>> {
+    stream::iter(match result {
+        Ok(items) => items.into_iter().map(Ok).collect(),
+        Err(e) => vec![Err(e)],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeSet, time::Duration};
+
+    use async_graphql::Schema;
+
+    use super::*;
+    use crate::{mutation::Mutation, types::query::Query};
+
+    const TRANSACTIONS: &str = "subscription { transactions { digest } }";
+    const EVENTS: &str = "subscription { events { sendingModule { name } } }";
+
+    fn schema(
+        cfg: ServiceConfig,
+        latest_checkpoint: Option<u64>,
+    ) -> (
+        Schema<Query, Mutation, Subscription>,
+        watch::Sender<Option<u64>>,
+    ) {
+        let (sender, receiver) = watch::channel(latest_checkpoint);
+        let schema = Schema::build(Query, Mutation, Subscription)
+            .data(SubscriptionPermits::new(&cfg.limits))
+            .data(cfg)
+            .data(receiver)
+            .finish();
+        (schema, sender)
+    }
+
+    /// Subscribes and returns the errors of the first response, or `None` if
+    /// the subscription is waiting for new checkpoints.
+    async fn subscribe(
+        schema: &Schema<Query, Mutation, Subscription>,
+        query: &str,
+    ) -> (impl Stream<Item = Response>, Option<Vec<String>>) {
+        let mut stream = schema.execute_stream(Request::new(query));
+        let errors = tokio::time::timeout(Duration::from_millis(100), stream.next())
+            .await
+            .ok()
+            .map(|response| {
+                response
+                    .unwrap()
+                    .errors
+                    .into_iter()
+                    .map(|e| e.message)
+                    .collect()
+            });
+        (stream, errors)
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions_disabled() {
+        let (schema, _sender) = schema(
+            ServiceConfig {
+                disabled_features: BTreeSet::from_iter([FunctionalGroup::Subscriptions]),
+                ..Default::default()
+            },
+            Some(0),
+        );
+
+        let expect = format!(
+            "Feature {} is disabled.",
+            FunctionalGroup::Subscriptions.name()
+        );
+        for query in [TRANSACTIONS, EVENTS] {
+            let (_, errors) = subscribe(&schema, query).await;
+            assert_eq!(errors, Some(vec![expect.clone()]));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions_before_watermark() {
+        let (schema, _sender) = schema(ServiceConfig::default(), None);
+
+        let expect =
+            Error::Internal("The latest checkpoint is not known yet, try again later".to_string())
+                .to_string();
+        for query in [TRANSACTIONS, EVENTS] {
+            let (_, errors) = subscribe(&schema, query).await;
+            assert_eq!(errors, Some(vec![expect.clone()]));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions_per_connection_limit() {
+        let mut cfg = ServiceConfig::default();
+        cfg.limits.max_subscriptions_per_connection = 1;
+        let (schema, _sender) = schema(cfg, Some(0));
+
+        // The first subscription waits for new checkpoints, holding the only permit.
+        let (first, errors) = subscribe(&schema, TRANSACTIONS).await;
+        assert_eq!(errors, None);
+
+        let (_, errors) = subscribe(&schema, TRANSACTIONS).await;
+        assert_eq!(
+            errors,
+            Some(vec![
+                "Too many subscriptions on this connection. Limit: 1".to_string()
+            ])
+        );
+
+        // Ending the first subscription releases its permit.
+        drop(first);
+        let (_, errors) = subscribe(&schema, TRANSACTIONS).await;
+        assert_eq!(errors, None);
+    }
+
+    #[test]
+    fn test_connection_limit() {
+        let limits = Limits {
+            max_subscription_connections: 2,
+            ..Default::default()
+        };
+        let permits = ConnectionPermits::new(&limits);
+
+        let first = permits.try_acquire().unwrap();
+        let _second = permits.try_acquire().unwrap();
+        assert!(permits.try_acquire().is_none());
+
+        drop(first);
+        assert!(permits.try_acquire().is_some());
+    }
+}
@@ -3,14 +3,29 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use async_graphql::*;
-use diesel::{CombineDsl, ExpressionMethods, QueryDsl, QueryResult};
-use iota_indexer::schema::{checkpoints, objects_snapshot};
+use diesel::{CombineDsl, ExpressionMethods, QueryDsl, QueryResult, dsl::max};
+use iota_indexer::schema::{checkpoints, objects_snapshot, watermarks};
 
 use crate::{
     data::{Conn, Db, DbConnection, QueryExecutor},
     error::Error,
-    types::checkpoint::{Checkpoint, CheckpointId},
+    types::{
+        checkpoint::{Checkpoint, CheckpointId},
+        uint53::UInt53,
+    },
 };
+
+/// Tables whose pruning affects the consistency of the whole RPC. Requests
+/// viewing checkpoints below the reader watermark of any of them are out of the
+/// available range.
+const CONSISTENCY_TABLES: [&str; 5] = [
+    "checkpoints",
+    "objects_history",
+    "transactions",
+    "events",
+    "tx_indices",
+];
+
 #[derive(Clone, Debug, PartialEq, Eq, Copy)]
 pub(crate) struct AvailableRange {
     pub first: u64,
@@ -32,6 +47,49 @@ impl AvailableRange {
             .await
             .extend()
     }
+
+    /// Ranges of checkpoints whose data is available in the tables pruned by
+    /// the indexer, according to their retention policies. Tables that are
+    /// not listed are not pruned.
+    async fn tables(&self, ctx: &Context<'_>) -> Result<Vec<TableAvailableRange>> {
+        use watermarks::dsl;
+
+        let last = self.last;
+        let watermarks: Vec<(String, i64)> = ctx
+            .data_unchecked::<Db>()
+            .execute(move |conn| {
+                conn.results(move || {
+                    dsl::watermarks
+                        .select((dsl::entity, dsl::reader_lo))
+                        .order(dsl::entity)
+                })
+            })
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to fetch watermarks: {e}")))
+            .extend()?;
+
+        Ok(watermarks
+            .into_iter()
+            .map(|(table, reader_lo)| TableAvailableRange {
+                table,
+                first: UInt53::from(reader_lo as u64),
+                last: UInt53::from(last),
+            })
+            .collect())
+    }
+}
+
+/// Range of checkpoints whose data is available in a table pruned by the
+/// indexer.
+#[derive(Clone, Debug, SimpleObject)]
+pub(crate) struct TableAvailableRange {
+    /// The pruned table, or group of tables, such as `events` or
+    /// `tx_indices`.
+    table: String,
+    /// The first checkpoint whose data has not been pruned from the table.
+    first: UInt53,
+    /// The last checkpoint whose data is available in the table.
+    last: UInt53,
 }
 
 impl AvailableRange {
@@ -62,6 +120,7 @@ impl AvailableRange {
     pub(crate) fn result(conn: &mut Conn, checkpoint_viewed_at: u64) -> QueryResult<Option<Self>> {
         use checkpoints::dsl as checkpoints;
         use objects_snapshot::dsl as snapshots;
+        use watermarks::dsl as watermarks;
 
         let checkpoint_range: Vec<i64> = conn.results(move || {
             let rhs = checkpoints::checkpoints
@@ -87,6 +146,15 @@ impl AvailableRange {
             }
         };
 
+        // The data of checkpoints below the reader watermarks of the indexer pruner has
+        // been pruned, or is about to be.
+        let pruned_below: Option<i64> = conn.result(move || {
+            watermarks::watermarks
+                .select(max(watermarks::reader_lo))
+                .filter(watermarks::entity.eq_any(CONSISTENCY_TABLES))
+        })?;
+        let first = first.max(pruned_below.unwrap_or_default() as u64);
+
         if checkpoint_viewed_at < first || last < checkpoint_viewed_at {
             return Ok(None);
         }
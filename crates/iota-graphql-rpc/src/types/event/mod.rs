@@ -39,7 +39,7 @@ use crate::{
 mod cursor;
 mod filter;
 mod lookups;
-pub(crate) use cursor::Cursor;
+pub(crate) use cursor::{Cursor, EventKey};
 pub(crate) use filter::EventFilter;
 
 /// A Iota node emits one of the following events:
@@ -50,6 +50,7 @@ pub(crate) mod system_state_summary;
 pub(crate) mod transaction_block;
 pub(crate) mod transaction_block_effects;
 pub(crate) mod transaction_block_kind;
+pub(crate) mod transaction_dependency_graph;
 pub(crate) mod transaction_metadata;
 pub(crate) mod type_filter;
 pub(crate) mod uint53;
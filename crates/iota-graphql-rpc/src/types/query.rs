@@ -22,6 +22,7 @@ use crate::{
     error::Error,
     mutation::Mutation,
     server::watermark_task::Watermark,
+    subscription::Subscription,
     types::{
         address::Address,
         available_range::AvailableRange,
@@ -52,7 +53,7 @@ use crate::{
 };
 
 pub(crate) struct Query;
-pub(crate) type IotaGraphQLSchema = async_graphql::Schema<Query, Mutation, EmptySubscription>;
+pub(crate) type IotaGraphQLSchema = async_graphql::Schema<Query, Mutation, Subscription>;
 
 #[Object]
 impl Query {
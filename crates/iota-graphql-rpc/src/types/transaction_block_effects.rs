@@ -8,6 +8,7 @@ use async_graphql::{
 };
 use fastcrypto::encoding::{Base64 as FBase64, Encoding};
 use iota_indexer::models::transactions::StoredTransaction;
+use iota_json_rpc_api::validate_transaction_dependency_depth;
 use iota_package_resolver::{CleverError, ErrorConstants};
 use iota_types::{
     effects::{TransactionEffects as NativeTransactionEffects, TransactionEffectsAPI},
@@ -25,6 +26,7 @@ use iota_types::{
 
 use crate::{
     consistency::ConsistentIndexCursor,
+    context_data::db_data_provider::PgManager,
     data::package_resolver::PackageResolver,
     error::Error,
     types::{
@@ -39,6 +41,7 @@ use crate::{
         gas::GasEffects,
         object_change::ObjectChange,
         transaction_block::{TransactionBlock, TransactionBlockInner},
+        transaction_dependency_graph::TransactionDependencyGraph,
         uint53::UInt53,
         unchanged_shared_object::UnchangedSharedObject,
     },
@@ -270,6 +273,39 @@ impl TransactionBlockEffects {
         Ok(connection)
     }
 
+    /// The causal ancestors and descendants of this transaction, built from the
+    /// object versions it used as inputs, following at most `depth` levels of
+    /// dependencies in each direction. Only available for indexed
+    /// transactions.
+    async fn dependency_graph(
+        &self,
+        ctx: &Context<'_>,
+        depth: Option<u64>,
+    ) -> Result<Option<TransactionDependencyGraph>> {
+        if !matches!(self.kind, TransactionBlockEffectsKind::Stored { .. }) {
+            return Ok(None);
+        }
+
+        let depth = validate_transaction_dependency_depth(depth.map(|depth| depth as usize))
+            .map_err(|e| Error::Client(e.to_string()))
+            .extend()?;
+
+        let graph = ctx
+            .data_unchecked::<PgManager>()
+            .fetch_transaction_dependency_graph(
+                *self.native().transaction_digest(),
+                depth,
+                self.checkpoint_viewed_at,
+            )
+            .await
+            .extend()?;
+
+        TransactionDependencyGraph::query(ctx, graph, self.checkpoint_viewed_at)
+            .await
+            .map(Some)
+            .extend()
+    }
+
     /// Effects to the gas object.
     async fn gas_effects(&self) -> Option<GasEffects> {
         Some(GasEffects::from(self.native(), self.checkpoint_viewed_at))
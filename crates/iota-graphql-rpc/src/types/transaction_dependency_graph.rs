@@ -0,0 +1,88 @@
+// Copyright (c) 2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use async_graphql::*;
+use iota_json_rpc_types::{
+    TransactionDependency as RpcTransactionDependency,
+    TransactionDependencyGraph as RpcTransactionDependencyGraph,
+};
+
+use crate::{
+    error::Error,
+    types::{digest::Digest, transaction_block::TransactionBlock},
+};
+
+/// The causal dependency graph of a transaction block. A transaction block
+/// depends on the transaction blocks that produced the object versions it used
+/// as inputs.
+#[derive(SimpleObject, Clone, Debug)]
+pub(crate) struct TransactionDependencyGraph {
+    /// The dependency edges leading from the transaction block to its
+    /// ancestors.
+    pub ancestors: Vec<TransactionDependency>,
+    /// The dependency edges leading from the descendants to the transaction
+    /// block.
+    pub descendants: Vec<TransactionDependency>,
+    /// Whether edges were left out because the maximum number of edges was
+    /// reached before the requested depth.
+    pub truncated: bool,
+}
+
+/// A dependency edge between two transaction blocks.
+#[derive(SimpleObject, Clone, Debug)]
+pub(crate) struct TransactionDependency {
+    /// The dependent transaction block.
+    pub transaction: TransactionBlock,
+    /// The transaction block that produced object versions used as inputs by
+    /// the dependent transaction block.
+    pub dependency: TransactionBlock,
+    /// The distance of the edge from the transaction block the graph is built
+    /// around, starting at 1.
+    pub depth: u32,
+}
+
+impl TransactionDependencyGraph {
+    /// Resolve the transaction blocks on both ends of every edge of `graph`,
+    /// as viewed at `checkpoint_viewed_at`. Edges with an end that is not
+    /// visible at that checkpoint are left out.
+    pub(crate) async fn query(
+        ctx: &Context<'_>,
+        graph: RpcTransactionDependencyGraph,
+        checkpoint_viewed_at: u64,
+    ) -> Result<Self, Error> {
+        let digests = graph
+            .ancestors
+            .iter()
+            .chain(graph.descendants.iter())
+            .flat_map(|edge| [edge.transaction.into(), edge.dependency.into()])
+            .collect::<BTreeSet<Digest>>();
+
+        let transactions =
+            TransactionBlock::multi_query(ctx, digests.into_iter().collect(), checkpoint_viewed_at)
+                .await?;
+
+        Ok(Self {
+            ancestors: resolve_edges(graph.ancestors, &transactions),
+            descendants: resolve_edges(graph.descendants, &transactions),
+            truncated: graph.truncated,
+        })
+    }
+}
+
+fn resolve_edges(
+    edges: Vec<RpcTransactionDependency>,
+    transactions: &BTreeMap<Digest, TransactionBlock>,
+) -> Vec<TransactionDependency> {
+    edges
+        .into_iter()
+        .filter_map(|edge| {
+            Some(TransactionDependency {
+                transaction: transactions.get(&edge.transaction.into())?.clone(),
+                dependency: transactions.get(&edge.dependency.into())?.clone(),
+                depth: edge.depth,
+            })
+        })
+        .collect()
+}
@@ -14,7 +14,9 @@ mod tests {
     };
     use iota_types::{
         IOTA_FRAMEWORK_ADDRESS, IOTA_FRAMEWORK_PACKAGE_ID, STARDUST_ADDRESS,
+        base_types::IotaAddress,
         digests::ChainIdentifier,
+        effects::TransactionEffectsAPI,
         gas_coin::GAS,
         transaction::{CallArg, ObjectArg, TransactionDataAPI},
     };
@@ -834,6 +836,114 @@ mod tests {
         cluster.cleanup_resources().await
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_transaction_dependency_graph() {
+        let rng = StdRng::from_seed([12; 32]);
+        let mut sim = Simulacrum::new_with_rng(rng);
+        let data_ingestion_path = tempdir().unwrap().into_path();
+        sim.set_data_ingestion_path(data_ingestion_path.clone());
+
+        // Transfers paid with the same gas coin, so that each one depends on the
+        // previous one.
+        let digests = (0..3)
+            .map(|_| {
+                let (transaction, _) = sim.transfer_txn(IotaAddress::random_for_testing_only());
+                let digest = transaction.digest().to_string();
+                let (_, err) = sim.execute_transaction(transaction).unwrap();
+                assert!(err.is_none());
+                digest
+            })
+            .collect::<Vec<_>>();
+        let checkpoint = sim.create_checkpoint().sequence_number;
+
+        let cluster = iota_graphql_rpc::test_infra::cluster::serve_executor(
+            ConnectionConfig::ci_integration_test_cfg(),
+            DEFAULT_INTERNAL_DATA_SOURCE_PORT,
+            Arc::new(sim),
+            None,
+            data_ingestion_path,
+        )
+        .await;
+        cluster
+            .wait_for_checkpoint_catchup(checkpoint, Duration::from_secs(10))
+            .await;
+
+        let dependency_graph = |digest: &str, depth: u64| {
+            let query = format!(
+                r#"
+                {{
+                    transactionBlock(digest: "{digest}") {{
+                        effects {{
+                            dependencyGraph(depth: {depth}) {{
+                                ancestors {{
+                                    transaction {{ digest }}
+                                    dependency {{ digest }}
+                                    depth
+                                }}
+                                descendants {{
+                                    transaction {{ digest }}
+                                    dependency {{ digest }}
+                                    depth
+                                }}
+                                truncated
+                            }}
+                        }}
+                    }}
+                }}
+                "#
+            );
+            cluster
+                .graphql_client
+                .execute_to_graphql(query, false, vec![], vec![])
+        };
+        let edge = |transaction: &str, dependency: &str, depth: u32| {
+            json!({
+                "transaction": { "digest": transaction },
+                "dependency": { "digest": dependency },
+                "depth": depth,
+            })
+        };
+
+        let res = dependency_graph(&digests[1], 1).await.unwrap();
+        assert!(res.errors().is_empty(), "{:?}", res.errors());
+        let binding = res.response_body().data.clone().into_json().unwrap();
+        assert_eq!(
+            binding["transactionBlock"]["effects"]["dependencyGraph"],
+            json!({
+                "ancestors": [edge(&digests[1], &digests[0], 1)],
+                "descendants": [edge(&digests[2], &digests[1], 1)],
+                "truncated": false,
+            })
+        );
+
+        // Edges further away than the requested depth are left out.
+        let res = dependency_graph(&digests[2], 2).await.unwrap();
+        assert!(res.errors().is_empty(), "{:?}", res.errors());
+        let binding = res.response_body().data.clone().into_json().unwrap();
+        assert_eq!(
+            binding["transactionBlock"]["effects"]["dependencyGraph"],
+            json!({
+                "ancestors": [
+                    edge(&digests[2], &digests[1], 1),
+                    edge(&digests[1], &digests[0], 2),
+                ],
+                "descendants": [],
+                "truncated": false,
+            })
+        );
+
+        for (depth, message) in [
+            (0, "Dependency depth cannot be smaller than 1"),
+            (11, "Dependency depth 11 exceeds max depth 10"),
+        ] {
+            let res = dependency_graph(&digests[1], depth).await.unwrap();
+            let errors = res.errors();
+            assert_eq!(errors.len(), 1, "{errors:?}");
+            assert!(errors[0].message.contains(message), "{errors:?}");
+        }
+    }
+
     use iota_graphql_rpc::server::builder::tests::*;
 
     #[tokio::test]
@@ -887,6 +997,46 @@ mod tests {
         test_query_complexity_metrics_impl().await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_subscription_new_transactions() {
+        let rng = StdRng::from_seed([12; 32]);
+        let data_ingestion_path = tempdir().unwrap().into_path();
+        let mut sim = Simulacrum::new_with_rng(rng);
+        sim.set_data_ingestion_path(data_ingestion_path.clone());
+
+        // One transfer per checkpoint.
+        let mut digest = None;
+        let mut last_checkpoint = 0;
+        for _ in 0..2 {
+            let (transaction, _) = sim.transfer_txn(IotaAddress::random_for_testing_only());
+            let (effects, err) = sim.execute_transaction(transaction).unwrap();
+            assert!(err.is_none());
+            digest = Some(effects.transaction_digest().to_string());
+            last_checkpoint = sim.create_checkpoint().sequence_number;
+        }
+
+        let connection_config = ConnectionConfig::ci_integration_test_cfg();
+        let cluster = iota_graphql_rpc::test_infra::cluster::serve_executor(
+            connection_config.clone(),
+            DEFAULT_INTERNAL_DATA_SOURCE_PORT,
+            Arc::new(sim),
+            None,
+            data_ingestion_path,
+        )
+        .await;
+        cluster
+            .wait_for_checkpoint_catchup(last_checkpoint, Duration::from_secs(10))
+            .await;
+
+        test_subscription_new_transactions_impl(
+            connection_config,
+            last_checkpoint,
+            &digest.unwrap(),
+        )
+        .await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_health_check() {
@@ -0,0 +1,13 @@
+-- Pruning progress of the tables with a retention policy. Data of checkpoints
+-- below `reader_lo` must not be read anymore, data of checkpoints below
+-- `pruner_hi` has been deleted.
+CREATE TABLE watermarks (
+    -- The pruned table, or group of tables, e.g. `events` or `tx_indices`.
+    entity                      VARCHAR(255) PRIMARY KEY,
+    -- Inclusive lower bound of the checkpoints whose data is available.
+    reader_lo                   BIGINT       NOT NULL,
+    -- Exclusive upper bound of the checkpoints whose data has been pruned.
+    pruner_hi                   BIGINT       NOT NULL,
+    -- Timestamp of the last update of `reader_lo`.
+    timestamp_ms                BIGINT       NOT NULL
+);
@@ -0,0 +1,10 @@
+-- A transaction depends on the transactions that produced the object versions
+-- it used as inputs, as recorded in its effects.
+CREATE TABLE tx_dependencies (
+    tx_sequence_number          BIGINT       NOT NULL,
+    tx_digest                   BYTEA        NOT NULL,
+    dependency_digest           BYTEA        NOT NULL,
+    PRIMARY KEY(tx_digest, dependency_digest)
+);
+CREATE INDEX tx_dependencies_dependency_digest ON tx_dependencies (dependency_digest, tx_digest);
+CREATE INDEX tx_dependencies_tx_sequence_number ON tx_dependencies (tx_sequence_number);
@@ -3,16 +3,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use diesel::r2d2::R2D2Connection;
-use iota_json_rpc::IotaRpcModule;
+use iota_json_rpc::{IotaRpcModule, error::IotaRpcInputError};
 use iota_json_rpc_api::{
-    ExtendedApiServer, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS, internal_error, validate_limit,
+    ExtendedApiServer, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS, QUERY_MAX_TRANSACTION_DEPENDENCY_EDGES,
+    internal_error, validate_limit, validate_transaction_dependency_depth,
 };
 use iota_json_rpc_types::{
     AddressMetrics, EpochInfo, EpochMetrics, EpochMetricsPage, EpochPage, MoveCallMetrics,
-    NetworkMetrics, Page,
+    NetworkMetrics, Page, TransactionDependencyGraph,
 };
 use iota_open_rpc::Module;
-use iota_types::iota_serde::BigInt;
+use iota_types::{digests::TransactionDigest, iota_serde::BigInt};
 use jsonrpsee::{RpcModule, core::RpcResult};
 
 use crate::indexer_reader::IndexerReader;
@@ -156,6 +157,27 @@ impl<T: R2D2Connection + 'static> ExtendedApiServer for ExtendedApi<T> {
             .await?;
         Ok(latest_checkpoint.network_total_transactions.into())
     }
+
+    async fn get_transaction_dependency_graph(
+        &self,
+        digest: TransactionDigest,
+        depth: Option<usize>,
+    ) -> RpcResult<TransactionDependencyGraph> {
+        let depth =
+            validate_transaction_dependency_depth(depth).map_err(IotaRpcInputError::from)?;
+        let graph = self
+            .inner
+            .spawn_blocking(move |this| {
+                this.get_transaction_dependency_graph(
+                    digest,
+                    depth,
+                    QUERY_MAX_TRANSACTION_DEPENDENCY_EDGES,
+                    None,
+                )
+            })
+            .await?;
+        Ok(graph)
+    }
 }
 
 impl<T: R2D2Connection> IotaRpcModule for ExtendedApi<T> {
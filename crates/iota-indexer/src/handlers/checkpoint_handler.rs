@@ -16,6 +16,7 @@ use iota_package_resolver::{PackageStore, PackageStoreWithLruCache, Resolver};
 use iota_rest_api::{CheckpointData, CheckpointTransaction};
 use iota_types::{
     base_types::ObjectID,
+    digests::TransactionDigest,
     dynamic_field::{DynamicFieldInfo, DynamicFieldName, DynamicFieldType},
     effects::TransactionEffectsAPI,
     event::SystemEpochInfoEventV1,
@@ -514,6 +515,14 @@ where
                 .map(|(p, m, f)| (*<&ObjectID>::clone(p), m.to_string(), f.to_string()))
                 .collect();
 
+            // Dependencies, skipping the marker of objects created at genesis
+            let dependencies = fx
+                .dependencies()
+                .iter()
+                .filter(|digest| **digest != TransactionDigest::genesis_marker())
+                .copied()
+                .collect::<Vec<_>>();
+
             db_tx_indices.push(TxIndex {
                 tx_sequence_number,
                 transaction_digest: tx_digest,
@@ -525,6 +534,7 @@ where
                 recipients,
                 move_calls,
                 tx_kind: transaction_kind,
+                dependencies,
             });
         }
         Ok((
@@ -16,6 +16,7 @@ pub mod checkpoint_handler;
 pub mod committer;
 pub mod objects_snapshot_processor;
 pub mod pruner;
+pub mod tx_dependencies_backfill;
 pub mod tx_processor;
 
 #[derive(Debug)]
@@ -2,25 +2,232 @@
 // Modifications Copyright (c) 2024 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use std::time::Duration;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use diesel::r2d2::R2D2Connection;
 use tokio_util::sync::CancellationToken;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 use super::checkpoint_handler::CheckpointHandler;
 use crate::{
     errors::IndexerError,
     metrics::IndexerMetrics,
+    models::watermarks::StoredWatermark,
     store::{IndexerStore, pg_partition_manager::PgPartitionManager},
     types::IndexerResult,
 };
 
+/// Default number of checkpoints pruned from a table in one go. The watermark
+/// of the table is advanced after each batch.
+pub const PRUNING_BATCH_SIZE: u64 = 1000;
+/// Interval between two pruning rounds.
+const PRUNING_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A table, or a group of tables pruned together, that can be given its own
+/// retention policy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PrunableTable {
+    Checkpoints,
+    Transactions,
+    /// The `tx_*` index tables.
+    TxIndices,
+    Events,
+    /// The `event_*` index tables.
+    EventIndices,
+    ObjectsHistory,
+}
+
+impl PrunableTable {
+    pub const ALL: [Self; 6] = [
+        Self::Checkpoints,
+        Self::Transactions,
+        Self::TxIndices,
+        Self::Events,
+        Self::EventIndices,
+        Self::ObjectsHistory,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Checkpoints => "checkpoints",
+            Self::Transactions => "transactions",
+            Self::TxIndices => "tx_indices",
+            Self::Events => "events",
+            Self::EventIndices => "event_indices",
+            Self::ObjectsHistory => "objects_history",
+        }
+    }
+
+    /// Whether the table is partitioned by epoch, in which case it is pruned
+    /// by dropping whole partitions.
+    pub fn is_epoch_partitioned(&self) -> bool {
+        matches!(
+            self,
+            Self::Transactions | Self::Events | Self::ObjectsHistory
+        )
+    }
+}
+
+impl Display for PrunableTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PrunableTable {
+    type Err = IndexerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|table| table.as_str() == s)
+            .ok_or_else(|| {
+                IndexerError::InvalidArgument(format!(
+                    "Unknown prunable table `{s}`, expected one of: {}",
+                    Self::ALL.map(|table| table.as_str()).join(", ")
+                ))
+            })
+    }
+}
+
+/// How long the data of a table is kept.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RetentionPolicy {
+    /// Keeps the data of the latest epochs, including the current one.
+    Epochs(u64),
+    /// Keeps the data of the latest checkpoints.
+    Checkpoints(u64),
+    /// Keeps the data of the checkpoints created within the duration before
+    /// the latest checkpoint.
+    Duration(Duration),
+}
+
+impl Display for RetentionPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Epochs(epochs) => write!(f, "{epochs}epochs"),
+            Self::Checkpoints(checkpoints) => write!(f, "{checkpoints}checkpoints"),
+            Self::Duration(duration) => write!(f, "{}s", duration.as_secs()),
+        }
+    }
+}
+
+impl FromStr for RetentionPolicy {
+    type Err = IndexerError;
+
+    /// Parses a policy such as `7epochs`, `100000checkpoints`, `30d`, `12h`,
+    /// `90m` or `3600s`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| {
+            IndexerError::InvalidArgument(format!("Invalid retention policy `{s}`: {reason}"))
+        };
+        let (value, unit) = s.split_at(
+            s.find(|c: char| !c.is_ascii_digit())
+                .ok_or_else(|| invalid("missing unit"))?,
+        );
+        let value = value
+            .parse::<u64>()
+            .map_err(|_| invalid("expected a number followed by a unit"))?;
+        if value == 0 {
+            return Err(invalid("the retention must be positive"));
+        }
+        let seconds = |factor: u64| {
+            value
+                .checked_mul(factor)
+                .map(|secs| Self::Duration(Duration::from_secs(secs)))
+                .ok_or_else(|| invalid("duration too large"))
+        };
+        match unit {
+            "epochs" => Ok(Self::Epochs(value)),
+            "checkpoints" => Ok(Self::Checkpoints(value)),
+            "d" => seconds(24 * 60 * 60),
+            "h" => seconds(60 * 60),
+            "m" => seconds(60),
+            "s" => seconds(1),
+            _ => Err(invalid(
+                "unknown unit, expected one of: epochs, checkpoints, d, h, m, s",
+            )),
+        }
+    }
+}
+
+/// Retention policies of the prunable tables.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RetentionConfig {
+    /// Policy of the tables without an override.
+    pub default: Option<RetentionPolicy>,
+    /// Per-table policies.
+    pub overrides: BTreeMap<PrunableTable, RetentionPolicy>,
+}
+
+impl RetentionConfig {
+    /// Reads the retention policies from the environment. `EPOCHS_TO_KEEP`
+    /// sets the default number of epochs to keep, `RETENTION_POLICIES`
+    /// overrides it per table, e.g. `events=30d,objects_history=7d`.
+    /// Returns `None` if no table is pruned.
+    pub fn from_env() -> Result<Option<Self>, IndexerError> {
+        let default = std::env::var("EPOCHS_TO_KEEP")
+            .ok()
+            .map(|epochs| format!("{epochs}epochs").parse())
+            .transpose()?;
+        let overrides = std::env::var("RETENTION_POLICIES")
+            .ok()
+            .map(|policies| Self::parse_overrides(&policies))
+            .transpose()?
+            .unwrap_or_default();
+        let config = Self { default, overrides };
+        Ok((config.default.is_some() || !config.overrides.is_empty()).then_some(config))
+    }
+
+    /// Parses a comma separated list of `table=policy` entries.
+    pub fn parse_overrides(
+        policies: &str,
+    ) -> Result<BTreeMap<PrunableTable, RetentionPolicy>, IndexerError> {
+        policies
+            .split(',')
+            .filter(|entry| !entry.trim().is_empty())
+            .map(|entry| {
+                let (table, policy) = entry.split_once('=').ok_or_else(|| {
+                    IndexerError::InvalidArgument(format!(
+                        "Invalid retention policy entry `{entry}`, expected `table=policy`"
+                    ))
+                })?;
+                Ok((table.trim().parse()?, policy.trim().parse()?))
+            })
+            .collect()
+    }
+
+    /// Returns the policy of the table, `None` if its data is kept forever.
+    pub fn policy(&self, table: PrunableTable) -> Option<RetentionPolicy> {
+        self.overrides.get(&table).copied().or(self.default)
+    }
+}
+
+impl Display for RetentionConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let policies = PrunableTable::ALL
+            .into_iter()
+            .map(|table| match self.policy(table) {
+                Some(policy) => format!("{table}={policy}"),
+                None => format!("{table}=forever"),
+            })
+            .collect::<Vec<_>>();
+        f.write_str(&policies.join(","))
+    }
+}
+
 pub struct Pruner<S, T: R2D2Connection + 'static> {
     pub store: S,
     pub partition_manager: PgPartitionManager<T>,
-    pub epochs_to_keep: u64,
+    pub retention: RetentionConfig,
     pub metrics: IndexerMetrics,
+    /// Number of checkpoints pruned from a table in one go.
+    pub batch_size: u64,
 }
 
 impl<S, T> Pruner<S, T>
@@ -30,7 +237,7 @@ where
 {
     pub fn new(
         store: S,
-        epochs_to_keep: u64,
+        retention: RetentionConfig,
         metrics: IndexerMetrics,
     ) -> Result<Self, IndexerError> {
         let blocking_cp = CheckpointHandler::<S, T>::pg_blocking_cp(store.clone()).unwrap();
@@ -38,67 +245,282 @@ where
         Ok(Self {
             store,
             partition_manager,
-            epochs_to_keep,
+            retention,
             metrics,
+            batch_size: PRUNING_BATCH_SIZE,
         })
     }
 
+    /// Sets the number of checkpoints pruned from a table in one go.
+    pub fn with_batch_size(mut self, batch_size: u64) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
     pub async fn start(&self, cancel: CancellationToken) -> IndexerResult<()> {
         loop {
             if cancel.is_cancelled() {
                 info!("Pruner task cancelled.");
                 return Ok(());
             }
+            if let Err(e) = self.prune(&cancel).await {
+                error!("Failed to prune tables: {}", e);
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(PRUNING_INTERVAL) => {}
+                _ = cancel.cancelled() => {}
+            }
+        }
+    }
 
-            let (mut min_epoch, mut max_epoch) = self.store.get_available_epoch_range().await?;
-            while min_epoch + self.epochs_to_keep > max_epoch {
-                if cancel.is_cancelled() {
-                    info!("Pruner task cancelled.");
-                    return Ok(());
-                }
-                tokio::time::sleep(Duration::from_secs(5)).await;
-                (min_epoch, max_epoch) = self.store.get_available_epoch_range().await?;
+    /// Prunes every table with a retention policy up to its cutoff, then the
+    /// bookkeeping tables up to the checkpoint all tables are pruned to.
+    pub async fn prune(&self, cancel: &CancellationToken) -> IndexerResult<()> {
+        let Some(latest_checkpoint) = self.store.get_latest_checkpoint_sequence_number().await?
+        else {
+            return Ok(());
+        };
+        let mut watermarks = self
+            .store
+            .get_watermarks()
+            .await?
+            .into_iter()
+            .map(|watermark| (watermark.entity.clone(), watermark))
+            .collect::<HashMap<_, _>>();
+        self.seed_watermarks(&mut watermarks).await?;
+
+        let mut bookkeeping_hi = Some(u64::MAX);
+        for table in PrunableTable::ALL {
+            if cancel.is_cancelled() {
+                return Ok(());
             }
+            let Some(policy) = self.retention.policy(table) else {
+                // The data of the table is kept forever, and so is the bookkeeping
+                // needed to prune it.
+                bookkeeping_hi = None;
+                continue;
+            };
+            let mut watermark = watermarks
+                .remove(table.as_str())
+                .expect("watermarks of tables with a policy are seeded");
 
-            let table_partitions = self.partition_manager.get_table_partitions()?;
-            let table_names = table_partitions.keys().cloned().collect::<Vec<_>>();
-            for (table_name, (min_partition, max_partition)) in table_partitions {
-                if max_epoch != max_partition {
-                    error!(
-                        "Epochs are out of sync for table {}: max_epoch={}, max_partition={}",
-                        table_name, max_epoch, max_partition
-                    );
+            let reader_lo = self.retention_cutoff(policy, latest_checkpoint).await?;
+            if reader_lo > watermark.reader_lo as u64 {
+                // Advance the reader watermark before deleting any data, so that readers
+                // stop requesting it first.
+                watermark.reader_lo = reader_lo as i64;
+                watermark.timestamp_ms = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis() as i64;
+                self.store.update_watermark(watermark.clone()).await?;
+            }
+
+            if table.is_epoch_partitioned() {
+                self.prune_partitions(table, &mut watermark, cancel).await?;
+            } else {
+                self.prune_checkpoints(table, &mut watermark, cancel)
+                    .await?;
+            }
+            bookkeeping_hi = bookkeeping_hi.map(|hi| hi.min(watermark.pruner_hi as u64));
+        }
+
+        if let Some(pruner_hi) = bookkeeping_hi {
+            self.store.prune_bookkeeping(pruner_hi).await?;
+        }
+        Ok(())
+    }
+
+    /// Stores a watermark for each table with a policy that has none yet,
+    /// before any table is pruned. A table without a watermark was never
+    /// pruned, so its data starts at the first checkpoint whose transaction
+    /// range is still kept: that range is only pruned once all tables are.
+    /// The first indexed checkpoint can't be used, as the checkpoints table
+    /// may have been pruned past the data of the other tables.
+    async fn seed_watermarks(
+        &self,
+        watermarks: &mut HashMap<String, StoredWatermark>,
+    ) -> IndexerResult<()> {
+        let mut first_checkpoint = None;
+        for table in PrunableTable::ALL {
+            if self.retention.policy(table).is_none() || watermarks.contains_key(table.as_str()) {
+                continue;
+            }
+            let first_checkpoint = match first_checkpoint {
+                Some(first_checkpoint) => first_checkpoint,
+                None => {
+                    let first = match self.store.get_first_prunable_checkpoint().await? {
+                        Some(first) => first,
+                        None => self.store.get_available_checkpoint_range().await?.0,
+                    };
+                    *first_checkpoint.insert(first)
                 }
-                // drop partitions if pruning is enabled afterwards, where all epochs before
-                // min_epoch would have been pruned already if the pruner was
-                // running.
-                for epoch in min_partition..min_epoch {
-                    self.partition_manager
-                        .drop_table_partition(table_name.clone(), epoch)?;
-                    info!(
-                        "Batch dropped table partition {} epoch {}",
-                        table_name, epoch
-                    );
+            };
+            let watermark = StoredWatermark {
+                entity: table.to_string(),
+                reader_lo: first_checkpoint as i64,
+                pruner_hi: first_checkpoint as i64,
+                timestamp_ms: 0,
+            };
+            self.store.update_watermark(watermark.clone()).await?;
+            watermarks.insert(table.to_string(), watermark);
+        }
+        Ok(())
+    }
+
+    /// Returns the first checkpoint whose data is kept under the policy.
+    async fn retention_cutoff(
+        &self,
+        policy: RetentionPolicy,
+        latest_checkpoint: u64,
+    ) -> IndexerResult<u64> {
+        match policy {
+            RetentionPolicy::Epochs(epochs) => {
+                let (min_epoch, max_epoch) = self.store.get_available_epoch_range().await?;
+                match (max_epoch + 1).checked_sub(epochs) {
+                    Some(epoch) if epoch > 0 && epoch >= min_epoch => {
+                        let (first_checkpoint, _) =
+                            self.store.get_checkpoint_range_for_epoch(epoch).await?;
+                        Ok(first_checkpoint)
+                    }
+                    _ => Ok(0),
                 }
             }
+            RetentionPolicy::Checkpoints(checkpoints) => {
+                Ok((latest_checkpoint + 1).saturating_sub(checkpoints))
+            }
+            RetentionPolicy::Duration(duration) => {
+                self.store
+                    .get_first_checkpoint_within(duration.as_millis() as u64)
+                    .await
+            }
+        }
+    }
 
-            for epoch in min_epoch..max_epoch.saturating_sub(self.epochs_to_keep - 1) {
-                if cancel.is_cancelled() {
-                    info!("Pruner task cancelled.");
-                    return Ok(());
-                }
-                info!("Pruning epoch {}", epoch);
-                for table_name in table_names.clone() {
-                    self.partition_manager
-                        .drop_table_partition(table_name.clone(), epoch)?;
-                    info!("Dropped table partition {} epoch {}", table_name, epoch);
+    /// Drops the epoch partitions of the table whose checkpoints are all below
+    /// the reader watermark.
+    async fn prune_partitions(
+        &self,
+        table: PrunableTable,
+        watermark: &mut StoredWatermark,
+        cancel: &CancellationToken,
+    ) -> IndexerResult<()> {
+        let table_name = table.as_str().to_string();
+        let Some((min_partition, max_partition)) = self
+            .partition_manager
+            .get_table_partitions()?
+            .get(&table_name)
+            .copied()
+        else {
+            warn!("No epoch partitions found for table {}", table_name);
+            return Ok(());
+        };
+        let (min_epoch, _) = self.store.get_available_epoch_range().await?;
+
+        for epoch in min_partition..=max_partition {
+            if cancel.is_cancelled() {
+                return Ok(());
+            }
+            // Epochs missing from the epochs table have been pruned from all tables
+            // before, e.g. if the partitions were kept by an earlier configuration.
+            if epoch >= min_epoch {
+                let (first_checkpoint, last_checkpoint) =
+                    self.store.get_checkpoint_range_for_epoch(epoch).await?;
+                if !last_checkpoint.is_some_and(|last| last < watermark.reader_lo as u64) {
+                    watermark.pruner_hi = watermark.pruner_hi.max(first_checkpoint as i64);
+                    break;
                 }
-                self.store.prune_epoch(epoch).await.unwrap_or_else(|e| {
-                    error!("Failed to prune epoch {}: {}", epoch, e);
-                });
+            }
+            self.partition_manager
+                .drop_table_partition(table_name.clone(), epoch)?;
+            info!("Dropped table partition {} epoch {}", table_name, epoch);
+            if table == PrunableTable::Transactions {
                 self.metrics.last_pruned_epoch.set(epoch as i64);
-                info!("Pruned epoch {}", epoch);
             }
         }
+        self.store.update_watermark(watermark.clone()).await
+    }
+
+    /// Deletes the data of the checkpoints below the reader watermark in
+    /// batches, advancing the pruner watermark after each batch.
+    async fn prune_checkpoints(
+        &self,
+        table: PrunableTable,
+        watermark: &mut StoredWatermark,
+        cancel: &CancellationToken,
+    ) -> IndexerResult<()> {
+        while watermark.pruner_hi < watermark.reader_lo && !cancel.is_cancelled() {
+            let start = watermark.pruner_hi as u64;
+            let end = (start + self.batch_size).min(watermark.reader_lo as u64);
+            self.store.prune_checkpoints(table, start..end).await?;
+            watermark.pruner_hi = end as i64;
+            self.store.update_watermark(watermark.clone()).await?;
+            info!(
+                "Pruned checkpoints {} to {} from table {}",
+                start,
+                end - 1,
+                table
+            );
+            if table == PrunableTable::Checkpoints {
+                self.metrics.last_pruned_checkpoint.set(end as i64 - 1);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_retention_policies() {
+        assert_eq!(
+            "7epochs".parse::<RetentionPolicy>().unwrap(),
+            RetentionPolicy::Epochs(7)
+        );
+        assert_eq!(
+            "1000checkpoints".parse::<RetentionPolicy>().unwrap(),
+            RetentionPolicy::Checkpoints(1000)
+        );
+        assert_eq!(
+            "30d".parse::<RetentionPolicy>().unwrap(),
+            RetentionPolicy::Duration(Duration::from_secs(30 * 24 * 60 * 60))
+        );
+        assert!("0epochs".parse::<RetentionPolicy>().is_err());
+        assert!("30".parse::<RetentionPolicy>().is_err());
+        assert!("30w".parse::<RetentionPolicy>().is_err());
+
+        let overrides =
+            RetentionConfig::parse_overrides("events=30d, objects_history=7d,").unwrap();
+        assert_eq!(
+            overrides,
+            BTreeMap::from([
+                (
+                    PrunableTable::Events,
+                    RetentionPolicy::Duration(Duration::from_secs(30 * 24 * 60 * 60))
+                ),
+                (
+                    PrunableTable::ObjectsHistory,
+                    RetentionPolicy::Duration(Duration::from_secs(7 * 24 * 60 * 60))
+                ),
+            ])
+        );
+        assert!(RetentionConfig::parse_overrides("objects=7d").is_err());
+        assert!(RetentionConfig::parse_overrides("events").is_err());
+
+        let config = RetentionConfig {
+            default: Some(RetentionPolicy::Epochs(2)),
+            overrides,
+        };
+        assert_eq!(
+            config.policy(PrunableTable::Checkpoints),
+            Some(RetentionPolicy::Epochs(2))
+        );
+        assert_eq!(
+            config.to_string(),
+            "checkpoints=2epochs,transactions=2epochs,tx_indices=2epochs,events=2592000s,\
+             event_indices=2epochs,objects_history=604800s"
+        );
     }
 }
@@ -0,0 +1,35 @@
+// Copyright (c) 2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+use crate::{store::IndexerStore, types::IndexerResult};
+
+/// Number of transactions whose dependencies are indexed in one go.
+const BACKFILL_BATCH_SIZE: usize = 1000;
+
+/// Indexes the dependencies of the transactions indexed before the
+/// `tx_dependencies` table was introduced, from the most recent to the oldest
+/// one. Dependencies are read from the effects of the transactions, so only
+/// transactions that are not pruned yet are backfilled.
+pub async fn backfill_tx_dependencies<S: IndexerStore>(
+    store: S,
+    cancel: CancellationToken,
+) -> IndexerResult<()> {
+    let mut tx_hi = store.get_tx_dependencies_backfill_hi().await?;
+    info!(tx_hi, "Starting tx_dependencies backfill");
+    while !cancel.is_cancelled() {
+        match store
+            .backfill_tx_dependencies(tx_hi, BACKFILL_BATCH_SIZE)
+            .await?
+        {
+            Some(lowest) => tx_hi = lowest,
+            None => {
+                info!("Finished tx_dependencies backfill");
+                break;
+            }
+        }
+    }
+    Ok(())
+}
@@ -15,7 +15,7 @@ use iota_types::messages_checkpoint::CheckpointSequenceNumber;
 use prometheus::Registry;
 use tokio::sync::oneshot;
 use tokio_util::sync::CancellationToken;
-use tracing::info;
+use tracing::{error, info};
 
 use crate::{
     IndexerConfig, build_json_rpc_server,
@@ -23,7 +23,8 @@ use crate::{
     handlers::{
         checkpoint_handler::new_handlers,
         objects_snapshot_processor::{SnapshotLagConfig, start_objects_snapshot_processor},
-        pruner::Pruner,
+        pruner::{Pruner, RetentionConfig},
+        tx_dependencies_backfill::backfill_tx_dependencies,
     },
     indexer_reader::IndexerReader,
     metrics::IndexerMetrics,
@@ -111,19 +112,25 @@ impl Indexer {
             )
             .await?;
 
-        let epochs_to_keep = std::env::var("EPOCHS_TO_KEEP")
-            .map(|s| s.parse::<u64>().ok())
-            .unwrap_or_else(|_e| None);
-        if let Some(epochs_to_keep) = epochs_to_keep {
+        if let Some(retention) = RetentionConfig::from_env()? {
             info!(
-                "Starting indexer pruner with epochs to keep: {}",
-                epochs_to_keep
+                "Starting indexer pruner with retention policies: {}",
+                retention
             );
-            assert!(epochs_to_keep > 0, "Epochs to keep must be positive");
-            let pruner: Pruner<S, T> = Pruner::new(store.clone(), epochs_to_keep, metrics.clone())?;
+            let pruner: Pruner<S, T> = Pruner::new(store.clone(), retention, metrics.clone())?;
             spawn_monitored_task!(pruner.start(CancellationToken::new()));
         }
 
+        if config.backfill_tx_dependencies {
+            let store = store.clone();
+            let cancel = cancel.clone();
+            spawn_monitored_task!(async move {
+                if let Err(e) = backfill_tx_dependencies(store, cancel).await {
+                    error!("Failed to backfill tx_dependencies with error: {e}");
+                }
+            });
+        }
+
         // If we already have chain identifier indexed (i.e. the first checkpoint has
         // been indexed), then we persist protocol configs for protocol versions
         // not yet in the db. Otherwise, we would do the persisting in
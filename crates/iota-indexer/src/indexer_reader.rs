@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     sync::{Arc, Mutex},
 };
 
@@ -20,7 +20,8 @@ use iota_json_rpc_types::{
     AddressMetrics, Balance, CheckpointId, Coin as IotaCoin, DisplayFieldsResponse, EpochInfo,
     EventFilter, IotaCoinMetadata, IotaEvent, IotaObjectDataFilter, IotaTransactionBlockEffects,
     IotaTransactionBlockEffectsAPI, IotaTransactionBlockResponse, MoveCallMetrics,
-    MoveFunctionName, NetworkMetrics, TransactionFilter,
+    MoveFunctionName, NetworkMetrics, TransactionDependency, TransactionDependencyGraph,
+    TransactionFilter,
 };
 use iota_package_resolver::{Package, PackageStore, PackageStoreWithLruCache, Resolver};
 use iota_types::{
@@ -59,11 +60,11 @@ use crate::{
             StoredTransaction, StoredTransactionEvents, stored_events_to_events,
             tx_events_to_iota_tx_events,
         },
-        tx_indices::TxSequenceNumber,
+        tx_indices::{StoredTxDependency, TxSequenceNumber},
     },
     schema::{
         address_metrics, checkpoints, display, epochs, events, move_call_metrics, objects,
-        objects_snapshot, packages, transactions,
+        objects_snapshot, packages, transactions, tx_dependencies,
     },
     store::{diesel_macro::*, package_resolver::IndexerStorePackageResolver},
     types::{IndexerResult, OwnerType},
@@ -1629,6 +1630,96 @@ impl<U: R2D2Connection> IndexerReader<U> {
             .collect())
     }
 
+    /// Returns the ancestors and descendants of a transaction up to `depth`
+    /// levels away. Each direction returns at most `max_edges` edges, if more
+    /// exist the graph is marked as truncated. If `tx_hi` is set, only the
+    /// transactions with a lower sequence number are part of the graph.
+    pub fn get_transaction_dependency_graph(
+        &self,
+        digest: TransactionDigest,
+        depth: usize,
+        max_edges: usize,
+        tx_hi: Option<u64>,
+    ) -> IndexerResult<TransactionDependencyGraph> {
+        let (ancestors, ancestors_truncated) =
+            self.walk_transaction_dependencies(digest, true, depth, max_edges, tx_hi)?;
+        let (descendants, descendants_truncated) =
+            self.walk_transaction_dependencies(digest, false, depth, max_edges, tx_hi)?;
+        Ok(TransactionDependencyGraph {
+            transaction: digest,
+            ancestors,
+            descendants,
+            truncated: ancestors_truncated || descendants_truncated,
+        })
+    }
+
+    /// Walks `tx_dependencies` breadth first from `digest`, towards the
+    /// dependencies of the transactions if `ancestors` is set and towards their
+    /// dependents otherwise.
+    fn walk_transaction_dependencies(
+        &self,
+        digest: TransactionDigest,
+        ancestors: bool,
+        depth: usize,
+        max_edges: usize,
+        tx_hi: Option<u64>,
+    ) -> IndexerResult<(Vec<TransactionDependency>, bool)> {
+        let mut visited = HashSet::from([digest]);
+        let mut frontier = vec![digest.inner().to_vec()];
+        let mut edges = vec![];
+        for level in 1..=depth {
+            if frontier.is_empty() {
+                break;
+            }
+            // Fetch one row more than what is left of the budget to detect
+            // truncation.
+            let remaining = max_edges - edges.len();
+            let stored_dependencies = run_query!(&self.pool, |conn| {
+                let query = tx_dependencies::table.into_boxed();
+                let query = if ancestors {
+                    query.filter(tx_dependencies::tx_digest.eq_any(frontier.clone()))
+                } else {
+                    query.filter(tx_dependencies::dependency_digest.eq_any(frontier.clone()))
+                };
+                // The dependent transaction of an edge is always the most recent one.
+                let query = if let Some(tx_hi) = tx_hi {
+                    query.filter(tx_dependencies::tx_sequence_number.lt(tx_hi as i64))
+                } else {
+                    query
+                };
+                query
+                    .order_by(tx_dependencies::tx_digest)
+                    .then_order_by(tx_dependencies::dependency_digest)
+                    .limit(remaining as i64 + 1)
+                    .load::<StoredTxDependency>(conn)
+            })?;
+
+            let mut next_frontier = vec![];
+            for stored in stored_dependencies {
+                if edges.len() == max_edges {
+                    return Ok((edges, true));
+                }
+                let transaction = stored_tx_dependency_digest(&stored.tx_digest)?;
+                let dependency = stored_tx_dependency_digest(&stored.dependency_digest)?;
+                let (next, next_bytes) = if ancestors {
+                    (dependency, stored.dependency_digest)
+                } else {
+                    (transaction, stored.tx_digest)
+                };
+                if visited.insert(next) {
+                    next_frontier.push(next_bytes);
+                }
+                edges.push(TransactionDependency {
+                    transaction,
+                    dependency,
+                    depth: level as u32,
+                });
+            }
+            frontier = next_frontier;
+        }
+        Ok((edges, false))
+    }
+
     pub(crate) async fn get_display_fields(
         &self,
         original_object: &iota_types::object::Object,
@@ -1819,3 +1910,12 @@ fn get_single_obj_id_from_package_publish<U: R2D2Connection>(
         Ok(None)
     }
 }
+
+fn stored_tx_dependency_digest(bytes: &[u8]) -> IndexerResult<TransactionDigest> {
+    TransactionDigest::try_from(bytes).map_err(|e| {
+        IndexerError::PersistentStorageDataCorruption(format!(
+            "Can't convert {:?} as tx_digest. Error: {e}",
+            bytes
+        ))
+    })
+}
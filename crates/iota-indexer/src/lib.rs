@@ -87,6 +87,8 @@ pub struct IndexerConfig {
     pub data_ingestion_path: Option<PathBuf>,
     #[clap(long)]
     pub analytical_worker: bool,
+    #[clap(long)]
+    pub backfill_tx_dependencies: bool,
 }
 
 impl IndexerConfig {
@@ -159,6 +161,7 @@ impl Default for IndexerConfig {
             rpc_server_worker: true,
             data_ingestion_path: None,
             analytical_worker: false,
+            backfill_tx_dependencies: false,
         }
     }
 }
@@ -18,3 +18,4 @@ pub mod packages;
 pub mod transactions;
 pub mod tx_count_metrics;
 pub mod tx_indices;
+pub mod watermarks;
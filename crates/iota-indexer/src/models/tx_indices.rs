@@ -6,8 +6,8 @@ use diesel::prelude::*;
 
 use crate::{
     schema::{
-        tx_calls_fun, tx_calls_mod, tx_calls_pkg, tx_changed_objects, tx_digests, tx_input_objects,
-        tx_kinds, tx_recipients, tx_senders,
+        tx_calls_fun, tx_calls_mod, tx_calls_pkg, tx_changed_objects, tx_dependencies, tx_digests,
+        tx_input_objects, tx_kinds, tx_recipients, tx_senders,
     },
     types::TxIndex,
 };
@@ -96,6 +96,14 @@ pub struct StoredTxKind {
     pub tx_sequence_number: i64,
 }
 
+#[derive(Queryable, Insertable, Selectable, Debug, Clone, Default)]
+#[diesel(table_name = tx_dependencies)]
+pub struct StoredTxDependency {
+    pub tx_sequence_number: i64,
+    pub tx_digest: Vec<u8>,
+    pub dependency_digest: Vec<u8>,
+}
+
 #[expect(clippy::type_complexity)]
 impl TxIndex {
     pub fn split(
@@ -110,6 +118,7 @@ impl TxIndex {
         Vec<StoredTxFun>,
         Vec<StoredTxDigest>,
         Vec<StoredTxKind>,
+        Vec<StoredTxDependency>,
     ) {
         let tx_sequence_number = self.tx_sequence_number as i64;
         let tx_sender = StoredTxSenders {
@@ -198,6 +207,16 @@ impl TxIndex {
             tx_sequence_number,
         };
 
+        let tx_dependencies = self
+            .dependencies
+            .iter()
+            .map(|d| StoredTxDependency {
+                tx_sequence_number,
+                tx_digest: self.transaction_digest.into_inner().to_vec(),
+                dependency_digest: d.into_inner().to_vec(),
+            })
+            .collect();
+
         (
             vec![tx_sender],
             tx_recipients,
@@ -208,6 +227,7 @@ impl TxIndex {
             tx_calls,
             vec![stored_tx_digest],
             vec![tx_kind],
+            tx_dependencies,
         )
     }
 }
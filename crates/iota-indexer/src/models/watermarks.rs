@@ -0,0 +1,19 @@
+// Copyright (c) 2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+
+use crate::schema::watermarks;
+
+/// Pruning progress of a table, or group of tables, with a retention policy.
+#[derive(Queryable, Insertable, Selectable, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = watermarks)]
+pub struct StoredWatermark {
+    pub entity: String,
+    /// Inclusive lower bound of the checkpoints whose data can be read.
+    pub reader_lo: i64,
+    /// Exclusive upper bound of the checkpoints whose data has been pruned.
+    pub pruner_hi: i64,
+    /// Timestamp of the last update of `reader_lo`.
+    pub timestamp_ms: i64,
+}
@@ -20,7 +20,8 @@ mod inner {
         events, feature_flags, move_call_metrics, move_calls, objects, objects_history,
         objects_snapshot, objects_version, packages, protocol_configs, pruner_cp_watermark,
         transactions, tx_calls_fun, tx_calls_mod, tx_calls_pkg, tx_changed_objects,
-        tx_count_metrics, tx_digests, tx_input_objects, tx_kinds, tx_recipients, tx_senders,
+        tx_count_metrics, tx_dependencies, tx_digests, tx_input_objects, tx_kinds, tx_recipients,
+        tx_senders, watermarks,
     };
 }
 
@@ -32,8 +33,8 @@ mod inner {
         event_senders, event_struct_instantiation, event_struct_module, event_struct_name,
         event_struct_package, events, feature_flags, objects, objects_history, objects_snapshot,
         objects_version, packages, protocol_configs, pruner_cp_watermark, transactions,
-        tx_calls_fun, tx_calls_mod, tx_calls_pkg, tx_changed_objects, tx_digests, tx_input_objects,
-        tx_kinds, tx_recipients, tx_senders,
+        tx_calls_fun, tx_calls_mod, tx_calls_pkg, tx_changed_objects, tx_dependencies, tx_digests,
+        tx_input_objects, tx_kinds, tx_recipients, tx_senders, watermarks,
     };
 }
 
@@ -47,8 +48,8 @@ pub use inner::{
     event_senders, event_struct_instantiation, event_struct_module, event_struct_name,
     event_struct_package, events, feature_flags, objects, objects_history, objects_snapshot,
     objects_version, packages, protocol_configs, pruner_cp_watermark, transactions, tx_calls_fun,
-    tx_calls_mod, tx_calls_pkg, tx_changed_objects, tx_digests, tx_input_objects, tx_kinds,
-    tx_recipients, tx_senders,
+    tx_calls_mod, tx_calls_pkg, tx_changed_objects, tx_dependencies, tx_digests, tx_input_objects,
+    tx_kinds, tx_recipients, tx_senders, watermarks,
 };
 
 #[cfg(feature = "postgres-feature")]
@@ -313,6 +313,14 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    tx_dependencies (tx_digest, dependency_digest) {
+        tx_sequence_number -> Bigint,
+        tx_digest -> Blob,
+        dependency_digest -> Blob,
+    }
+}
+
 diesel::table! {
     tx_digests (tx_digest) {
         tx_digest -> Blob,
@@ -354,6 +362,15 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    watermarks (entity) {
+        entity -> Varchar,
+        reader_lo -> Bigint,
+        pruner_hi -> Bigint,
+        timestamp_ms -> Bigint,
+    }
+}
+
 #[macro_export]
 macro_rules! for_all_tables {
     ($action:path) => {
@@ -381,11 +398,13 @@ macro_rules! for_all_tables {
             tx_calls_mod,
             tx_calls_pkg,
             tx_changed_objects,
+            tx_dependencies,
             tx_digests,
             tx_input_objects,
             tx_kinds,
             tx_recipients,
-            tx_senders
+            tx_senders,
+            watermarks
         );
     };
 }
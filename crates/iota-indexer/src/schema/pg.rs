@@ -2481,6 +2481,14 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    tx_dependencies (tx_digest, dependency_digest) {
+        tx_sequence_number -> Int8,
+        tx_digest -> Bytea,
+        dependency_digest -> Bytea,
+    }
+}
+
 diesel::table! {
     tx_digests (tx_digest) {
         tx_digest -> Bytea,
@@ -2518,6 +2526,15 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    watermarks (entity) {
+        entity -> Text,
+        reader_lo -> Int8,
+        pruner_hi -> Int8,
+        timestamp_ms -> Int8,
+    }
+}
+
 #[macro_export]
 macro_rules! for_all_tables {
     ($action:path) => {
@@ -2555,11 +2572,13 @@ macro_rules! for_all_tables {
             tx_calls_pkg,
             tx_changed_objects,
             tx_count_metrics,
+            tx_dependencies,
             tx_digests,
             tx_input_objects,
             tx_kinds,
             tx_recipients,
-            tx_senders
+            tx_senders,
+            watermarks
         );
     };
 }
@@ -2,16 +2,17 @@
 // Modifications Copyright (c) 2024 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{any::Any, collections::BTreeMap};
+use std::{any::Any, collections::BTreeMap, ops::Range};
 
 use async_trait::async_trait;
 
 use crate::{
     errors::IndexerError,
-    handlers::{EpochToCommit, TransactionObjectChangesToCommit},
+    handlers::{EpochToCommit, TransactionObjectChangesToCommit, pruner::PrunableTable},
     models::{
         display::StoredDisplay,
         objects::{StoredDeletedObject, StoredObject},
+        watermarks::StoredWatermark,
     },
     types::{
         EventIndex, IndexedCheckpoint, IndexedEvent, IndexedPackage, IndexedTransaction, TxIndex,
@@ -92,7 +93,48 @@ pub trait IndexerStore: Any + Clone + Sync + Send + 'static {
 
     async fn advance_epoch(&self, epoch: EpochToCommit) -> Result<(), IndexerError>;
 
-    async fn prune_epoch(&self, epoch: u64) -> Result<(), IndexerError>;
+    async fn get_checkpoint_range_for_epoch(
+        &self,
+        epoch: u64,
+    ) -> Result<(u64, Option<u64>), IndexerError>;
+
+    /// Returns the first checkpoint created less than `duration_ms` before the
+    /// latest checkpoint.
+    async fn get_first_checkpoint_within(&self, duration_ms: u64) -> Result<u64, IndexerError>;
+
+    /// Returns the first checkpoint whose transaction range is still kept for
+    /// pruning, or `None` if there is none. No table holds data of earlier
+    /// checkpoints.
+    async fn get_first_prunable_checkpoint(&self) -> Result<Option<u64>, IndexerError>;
+
+    async fn get_watermarks(&self) -> Result<Vec<StoredWatermark>, IndexerError>;
+
+    async fn update_watermark(&self, watermark: StoredWatermark) -> Result<(), IndexerError>;
+
+    /// Prunes the data of the given checkpoints from the table. Epoch
+    /// partitioned tables are pruned by dropping partitions instead.
+    async fn prune_checkpoints(
+        &self,
+        table: PrunableTable,
+        checkpoints: Range<u64>,
+    ) -> Result<(), IndexerError>;
+
+    /// Returns the exclusive upper bound of the transactions whose
+    /// dependencies may not be indexed yet, because they were indexed before
+    /// the `tx_dependencies` table was introduced.
+    async fn get_tx_dependencies_backfill_hi(&self) -> Result<u64, IndexerError>;
+
+    /// Indexes the dependencies of up to `limit` transactions below `tx_hi`,
+    /// and returns the lowest of these transactions, if there were any.
+    async fn backfill_tx_dependencies(
+        &self,
+        tx_hi: u64,
+        limit: usize,
+    ) -> Result<Option<u64>, IndexerError>;
+
+    /// Prunes the tables used to locate the data of a checkpoint or epoch,
+    /// once all tables are pruned up to `pruner_hi`.
+    async fn prune_bookkeeping(&self, pruner_hi: u64) -> Result<(), IndexerError>;
 
     async fn get_network_total_transactions_by_end_of_epoch(
         &self,
@@ -6,6 +6,7 @@ use core::result::Result::Ok;
 use std::{
     any::Any as StdAny,
     collections::{BTreeMap, HashMap, hash_map::Entry},
+    ops::Range,
     time::{Duration, Instant},
 };
 
@@ -21,7 +22,8 @@ use downcast::Any;
 use iota_protocol_config::ProtocolConfig;
 use iota_types::{
     base_types::ObjectID,
-    digests::{ChainIdentifier, CheckpointDigest},
+    digests::{ChainIdentifier, CheckpointDigest, TransactionDigest},
+    effects::{TransactionEffects, TransactionEffectsAPI},
 };
 use itertools::Itertools;
 use tap::TapFallible;
@@ -34,7 +36,7 @@ use super::{
 use crate::{
     db::ConnectionPool,
     errors::{Context, IndexerError},
-    handlers::{EpochToCommit, TransactionObjectChangesToCommit},
+    handlers::{EpochToCommit, TransactionObjectChangesToCommit, pruner::PrunableTable},
     insert_or_ignore_into,
     metrics::IndexerMetrics,
     models::{
@@ -49,6 +51,8 @@ use crate::{
         },
         packages::StoredPackage,
         transactions::StoredTransaction,
+        tx_indices::StoredTxDependency,
+        watermarks::StoredWatermark,
     },
     on_conflict_do_update, persist_chunk_into_table, read_only_blocking,
     schema::{
@@ -56,8 +60,8 @@ use crate::{
         event_senders, event_struct_instantiation, event_struct_module, event_struct_name,
         event_struct_package, events, feature_flags, objects, objects_history, objects_snapshot,
         objects_version, packages, protocol_configs, pruner_cp_watermark, transactions,
-        tx_calls_fun, tx_calls_mod, tx_calls_pkg, tx_changed_objects, tx_digests, tx_input_objects,
-        tx_kinds, tx_recipients, tx_senders,
+        tx_calls_fun, tx_calls_mod, tx_calls_pkg, tx_changed_objects, tx_dependencies, tx_digests,
+        tx_input_objects, tx_kinds, tx_recipients, tx_senders, watermarks,
     },
     transactional_blocking_with_retry,
     types::{
@@ -276,16 +280,6 @@ impl<T: R2D2Connection + 'static> PgIndexerStore<T> {
         .context("Failed reading min and max epoch numbers from PostgresDB")
     }
 
-    fn get_min_prunable_checkpoint(&self) -> Result<u64, IndexerError> {
-        read_only_blocking!(&self.blocking_cp, |conn| {
-            pruner_cp_watermark::dsl::pruner_cp_watermark
-                .select(min(pruner_cp_watermark::checkpoint_sequence_number))
-                .first::<Option<i64>>(conn)
-                .map(|v| v.unwrap_or_default() as u64)
-        })
-        .context("Failed reading min prunable checkpoint sequence number from PostgresDB")
-    }
-
     fn get_checkpoint_range_for_epoch(
         &self,
         epoch: u64,
@@ -317,6 +311,79 @@ impl<T: R2D2Connection + 'static> PgIndexerStore<T> {
         .context("Failed reading transaction range from PostgresDB")
     }
 
+    fn get_first_prunable_checkpoint(&self) -> Result<Option<u64>, IndexerError> {
+        read_only_blocking!(&self.blocking_cp, |conn| {
+            pruner_cp_watermark::dsl::pruner_cp_watermark
+                .select(min(pruner_cp_watermark::checkpoint_sequence_number))
+                .first::<Option<i64>>(conn)
+                .map(|checkpoint| checkpoint.map(|checkpoint| checkpoint as u64))
+        })
+        .context("Failed reading the first prunable checkpoint from PostgresDB")
+    }
+
+    fn get_first_checkpoint_within(&self, duration_ms: u64) -> Result<u64, IndexerError> {
+        read_only_blocking!(&self.blocking_cp, |conn| {
+            let Some(latest_timestamp_ms) = checkpoints::dsl::checkpoints
+                .select(max(checkpoints::timestamp_ms))
+                .first::<Option<i64>>(conn)?
+            else {
+                return Ok(0);
+            };
+            let cutoff_ms = latest_timestamp_ms.saturating_sub(duration_ms as i64);
+            let last_checkpoint_before = checkpoints::dsl::checkpoints
+                .select(max(checkpoints::sequence_number))
+                .filter(checkpoints::timestamp_ms.lt(cutoff_ms))
+                .first::<Option<i64>>(conn)?;
+            // The checkpoints table may have been pruned beyond the cutoff already, in
+            // which case the start of the epochs gives a cutoff with epoch granularity.
+            let first_checkpoint_of_epoch = epochs::dsl::epochs
+                .select(max(epochs::first_checkpoint_id))
+                .filter(epochs::epoch_start_timestamp.le(cutoff_ms))
+                .first::<Option<i64>>(conn)?;
+            Ok::<_, diesel::result::Error>(
+                last_checkpoint_before
+                    .map(|cp| cp + 1)
+                    .max(first_checkpoint_of_epoch)
+                    .unwrap_or_default() as u64,
+            )
+        })
+        .context("Failed reading the retention cutoff checkpoint from PostgresDB")
+    }
+
+    fn get_watermarks(&self) -> Result<Vec<StoredWatermark>, IndexerError> {
+        read_only_blocking!(&self.blocking_cp, |conn| {
+            watermarks::dsl::watermarks.load::<StoredWatermark>(conn)
+        })
+        .context("Failed reading watermarks from PostgresDB")
+    }
+
+    fn update_watermark(&self, watermark: StoredWatermark) -> Result<(), IndexerError> {
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                on_conflict_do_update!(
+                    watermarks::table,
+                    vec![watermark.clone()],
+                    watermarks::entity,
+                    (
+                        watermarks::reader_lo.eq(excluded(watermarks::reader_lo)),
+                        watermarks::pruner_hi.eq(excluded(watermarks::pruner_hi)),
+                        watermarks::timestamp_ms.eq(excluded(watermarks::timestamp_ms)),
+                    ),
+                    |excluded: StoredWatermark| (
+                        watermarks::reader_lo.eq(excluded.reader_lo),
+                        watermarks::pruner_hi.eq(excluded.pruner_hi),
+                        watermarks::timestamp_ms.eq(excluded.timestamp_ms),
+                    ),
+                    conn
+                );
+                Ok::<(), IndexerError>(())
+            },
+            PG_DB_COMMIT_SLEEP_DURATION
+        )
+        .context("Failed to update watermark")
+    }
+
     fn get_latest_object_snapshot_checkpoint_sequence_number(
         &self,
     ) -> Result<Option<u64>, IndexerError> {
@@ -1006,53 +1073,67 @@ impl<T: R2D2Connection + 'static> PgIndexerStore<T> {
             .checkpoint_db_commit_latency_tx_indices_chunks
             .start_timer();
         let len = indices.len();
-        let (senders, recipients, input_objects, changed_objects, pkgs, mods, funs, digests, kinds) =
-            indices.into_iter().map(|i| i.split()).fold(
+        let (
+            senders,
+            recipients,
+            input_objects,
+            changed_objects,
+            pkgs,
+            mods,
+            funs,
+            digests,
+            kinds,
+            dependencies,
+        ) = indices.into_iter().map(|i| i.split()).fold(
+            (
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+            ),
+            |(
+                mut tx_senders,
+                mut tx_recipients,
+                mut tx_input_objects,
+                mut tx_changed_objects,
+                mut tx_pkgs,
+                mut tx_mods,
+                mut tx_funs,
+                mut tx_digests,
+                mut tx_kinds,
+                mut tx_dependencies,
+            ),
+             index| {
+                tx_senders.extend(index.0);
+                tx_recipients.extend(index.1);
+                tx_input_objects.extend(index.2);
+                tx_changed_objects.extend(index.3);
+                tx_pkgs.extend(index.4);
+                tx_mods.extend(index.5);
+                tx_funs.extend(index.6);
+                tx_digests.extend(index.7);
+                tx_kinds.extend(index.8);
+                tx_dependencies.extend(index.9);
                 (
-                    Vec::new(),
-                    Vec::new(),
-                    Vec::new(),
-                    Vec::new(),
-                    Vec::new(),
-                    Vec::new(),
-                    Vec::new(),
-                    Vec::new(),
-                    Vec::new(),
-                ),
-                |(
-                    mut tx_senders,
-                    mut tx_recipients,
-                    mut tx_input_objects,
-                    mut tx_changed_objects,
-                    mut tx_pkgs,
-                    mut tx_mods,
-                    mut tx_funs,
-                    mut tx_digests,
-                    mut tx_kinds,
-                ),
-                 index| {
-                    tx_senders.extend(index.0);
-                    tx_recipients.extend(index.1);
-                    tx_input_objects.extend(index.2);
-                    tx_changed_objects.extend(index.3);
-                    tx_pkgs.extend(index.4);
-                    tx_mods.extend(index.5);
-                    tx_funs.extend(index.6);
-                    tx_digests.extend(index.7);
-                    tx_kinds.extend(index.8);
-                    (
-                        tx_senders,
-                        tx_recipients,
-                        tx_input_objects,
-                        tx_changed_objects,
-                        tx_pkgs,
-                        tx_mods,
-                        tx_funs,
-                        tx_digests,
-                        tx_kinds,
-                    )
-                },
-            );
+                    tx_senders,
+                    tx_recipients,
+                    tx_input_objects,
+                    tx_changed_objects,
+                    tx_pkgs,
+                    tx_mods,
+                    tx_funs,
+                    tx_digests,
+                    tx_kinds,
+                    tx_dependencies,
+                )
+            },
+        );
 
         let mut futures = vec![];
         futures.push(self.spawn_blocking_task(move |this| {
@@ -1252,6 +1333,31 @@ impl<T: R2D2Connection + 'static> PgIndexerStore<T> {
             })
         }));
 
+        futures.push(self.spawn_blocking_task(move |this| {
+            let now = Instant::now();
+            let rows_len = dependencies.len();
+            transactional_blocking_with_retry!(
+                &this.blocking_cp,
+                |conn| {
+                    for chunk in dependencies.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                        insert_or_ignore_into!(tx_dependencies::table, chunk, conn);
+                    }
+                    Ok::<(), IndexerError>(())
+                },
+                PG_DB_COMMIT_SLEEP_DURATION
+            )
+            .tap_ok(|_| {
+                let elapsed = now.elapsed().as_secs_f64();
+                info!(
+                    elapsed,
+                    "Persisted {} rows to tx_dependencies table", rows_len
+                );
+            })
+            .tap_err(|e| {
+                tracing::error!("Failed to persist tx_dependencies with error: {}", e);
+            })
+        }));
+
         futures::future::try_join_all(futures)
             .await
             .map_err(|e| {
@@ -1415,12 +1521,15 @@ impl<T: R2D2Connection + 'static> PgIndexerStore<T> {
         Ok(())
     }
 
-    fn prune_checkpoints_table(&self, cp: u64) -> Result<(), IndexerError> {
+    fn prune_checkpoints_table(&self, checkpoints: Range<u64>) -> Result<(), IndexerError> {
+        let (start, end) = (checkpoints.start as i64, checkpoints.end as i64);
         transactional_blocking_with_retry!(
             &self.blocking_cp,
             |conn| {
                 diesel::delete(
-                    checkpoints::table.filter(checkpoints::sequence_number.eq(cp as i64)),
+                    checkpoints::table
+                        .filter(checkpoints::sequence_number.ge(start))
+                        .filter(checkpoints::sequence_number.lt(end)),
                 )
                 .execute(conn)
                 .map_err(IndexerError::from)
@@ -1432,14 +1541,17 @@ impl<T: R2D2Connection + 'static> PgIndexerStore<T> {
         )
     }
 
-    fn prune_epochs_table(&self, epoch: u64) -> Result<(), IndexerError> {
+    /// Prunes the epochs whose checkpoints are all below `pruner_hi`.
+    fn prune_epochs_table(&self, pruner_hi: u64) -> Result<(), IndexerError> {
         transactional_blocking_with_retry!(
             &self.blocking_cp,
             |conn| {
-                diesel::delete(epochs::table.filter(epochs::epoch.eq(epoch as i64)))
-                    .execute(conn)
-                    .map_err(IndexerError::from)
-                    .context("Failed to prune epochs table")?;
+                diesel::delete(
+                    epochs::table.filter(epochs::last_checkpoint_id.lt(pruner_hi as i64)),
+                )
+                .execute(conn)
+                .map_err(IndexerError::from)
+                .context("Failed to prune epochs table")?;
                 Ok::<(), IndexerError>(())
             },
             PG_DB_COMMIT_SLEEP_DURATION
@@ -1567,19 +1679,27 @@ impl<T: R2D2Connection + 'static> PgIndexerStore<T> {
                     max_tx,
                     "Failed to prune tx_digests table"
                 ];
+                prune_tx_or_event_indice_table![
+                    tx_dependencies,
+                    conn,
+                    min_tx,
+                    max_tx,
+                    "Failed to prune tx_dependencies table"
+                ];
                 Ok::<(), IndexerError>(())
             },
             PG_DB_COMMIT_SLEEP_DURATION
         )
     }
 
-    fn prune_cp_tx_table(&self, cp: u64) -> Result<(), IndexerError> {
+    fn prune_cp_tx_table(&self, pruner_hi: u64) -> Result<(), IndexerError> {
         transactional_blocking_with_retry!(
             &self.blocking_cp,
             |conn| {
                 diesel::delete(
-                    pruner_cp_watermark::table
-                        .filter(pruner_cp_watermark::checkpoint_sequence_number.eq(cp as i64)),
+                    pruner_cp_watermark::table.filter(
+                        pruner_cp_watermark::checkpoint_sequence_number.lt(pruner_hi as i64),
+                    ),
                 )
                 .execute(conn)
                 .map_err(IndexerError::from)
@@ -1605,6 +1725,88 @@ impl<T: R2D2Connection + 'static> PgIndexerStore<T> {
         .map(|v| v as u64)
     }
 
+    fn get_tx_dependencies_backfill_hi(&self) -> Result<u64, IndexerError> {
+        read_only_blocking!(&self.blocking_cp, |conn| {
+            let lowest_indexed = tx_dependencies::table
+                .select(min(tx_dependencies::tx_sequence_number))
+                .first::<Option<i64>>(conn)?;
+            match lowest_indexed {
+                Some(tx_sequence_number) => Ok(tx_sequence_number as u64),
+                None => transactions::table
+                    .select(max(transactions::tx_sequence_number))
+                    .first::<Option<i64>>(conn)
+                    .map(|v| v.map_or(0, |v| v as u64 + 1)),
+            }
+        })
+        .context("Failed reading tx_dependencies backfill upper bound from PostgresDB")
+    }
+
+    fn backfill_tx_dependencies(
+        &self,
+        tx_hi: u64,
+        limit: usize,
+    ) -> Result<Option<u64>, IndexerError> {
+        let transactions: Vec<(i64, Vec<u8>, Vec<u8>)> =
+            read_only_blocking!(&self.blocking_cp, |conn| {
+                transactions::table
+                    .select((
+                        transactions::tx_sequence_number,
+                        transactions::transaction_digest,
+                        transactions::raw_effects,
+                    ))
+                    .filter(transactions::tx_sequence_number.lt(tx_hi as i64))
+                    .order(transactions::tx_sequence_number.desc())
+                    .limit(limit as i64)
+                    .load(conn)
+            })
+            .context("Failed reading transactions to backfill tx_dependencies from PostgresDB")?;
+        let Some(lowest) = transactions.last().map(|(seq, _, _)| *seq as u64) else {
+            return Ok(None);
+        };
+
+        let mut dependencies = vec![];
+        for (tx_sequence_number, tx_digest, raw_effects) in transactions {
+            let effects: TransactionEffects = bcs::from_bytes(&raw_effects).map_err(|e| {
+                IndexerError::PersistentStorageDataCorruption(format!(
+                    "Can't convert raw_effects of {} into TransactionEffects. Error: {e}",
+                    tx_sequence_number
+                ))
+            })?;
+            // Skip the marker of objects created at genesis, as the checkpoint handler
+            // does.
+            dependencies.extend(
+                effects
+                    .dependencies()
+                    .iter()
+                    .filter(|d| **d != TransactionDigest::genesis_marker())
+                    .map(|d| StoredTxDependency {
+                        tx_sequence_number,
+                        tx_digest: tx_digest.clone(),
+                        dependency_digest: d.into_inner().to_vec(),
+                    }),
+            );
+        }
+
+        let rows_len = dependencies.len();
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                for chunk in dependencies.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                    insert_or_ignore_into!(tx_dependencies::table, chunk, conn);
+                }
+                Ok::<(), IndexerError>(())
+            },
+            PG_DB_COMMIT_SLEEP_DURATION
+        )
+        .tap_ok(|_| {
+            info!(
+                "Backfilled {} rows to tx_dependencies table down to transaction {}",
+                rows_len, lowest
+            );
+        })?;
+        Ok(Some(lowest))
+    }
+
     async fn execute_in_blocking_worker<F, R>(&self, f: F) -> Result<R, IndexerError>
     where
         F: FnOnce(Self) -> Result<R, IndexerError> + Send + 'static,
@@ -2056,93 +2258,88 @@ impl<T: R2D2Connection> IndexerStore for PgIndexerStore<T> {
             .await
     }
 
-    async fn prune_epoch(&self, epoch: u64) -> Result<(), IndexerError> {
-        let (mut min_cp, max_cp) = match self.get_checkpoint_range_for_epoch(epoch)? {
-            (min_cp, Some(max_cp)) => Ok((min_cp, max_cp)),
-            _ => Err(IndexerError::PostgresRead(format!(
-                "Failed to get checkpoint range for epoch {}",
-                epoch
-            ))),
-        }?;
-
-        // NOTE: for disaster recovery, min_cp is the min cp of the current epoch, which
-        // is likely partially pruned already. min_prunable_cp is the min cp to
-        // be pruned. By std::cmp::max, we will resume the pruning process from
-        // the next checkpoint, instead of the first cp of the current epoch.
-        let min_prunable_cp = self.get_min_prunable_checkpoint()?;
-        min_cp = std::cmp::max(min_cp, min_prunable_cp);
-        for cp in min_cp..=max_cp {
-            // NOTE: the order of pruning tables is crucial:
-            // 1. prune checkpoints table, checkpoints table is the source table of
-            //    available range,
-            // we prune it first to make sure that we always have full data for checkpoints
-            // within the available range;
-            // 2. then prune tx_* tables;
-            // 3. then prune pruner_cp_watermark table, which is the checkpoint pruning
-            //    watermark table and also tx seq source
-            // of a checkpoint to prune tx_* tables;
-            // 4. lastly we prune epochs table when all checkpoints of the epoch have been
-            //    pruned.
-            info!(
-                "Pruning checkpoint {} of epoch {} (min_prunable_cp: {})",
-                cp, epoch, min_prunable_cp
-            );
-            self.execute_in_blocking_worker(move |this| this.prune_checkpoints_table(cp))
-                .await
-                .unwrap_or_else(|e| {
-                    tracing::error!("Failed to prune checkpoint {}: {}", cp, e);
-                });
-
-            let (min_tx, max_tx) = self.get_transaction_range_for_checkpoint(cp)?;
-            self.execute_in_blocking_worker(move |this| {
-                this.prune_tx_indices_table(min_tx, max_tx)
-            })
+    async fn get_checkpoint_range_for_epoch(
+        &self,
+        epoch: u64,
+    ) -> Result<(u64, Option<u64>), IndexerError> {
+        self.execute_in_blocking_worker(move |this| this.get_checkpoint_range_for_epoch(epoch))
             .await
-            .unwrap_or_else(|e| {
-                tracing::error!("Failed to prune transactions for cp {}: {}", cp, e);
-            });
-            info!(
-                "Pruned transactions for checkpoint {} from tx {} to tx {}",
-                cp, min_tx, max_tx
-            );
-            self.execute_in_blocking_worker(move |this| {
-                this.prune_event_indices_table(min_tx, max_tx)
-            })
+    }
+
+    async fn get_first_checkpoint_within(&self, duration_ms: u64) -> Result<u64, IndexerError> {
+        self.execute_in_blocking_worker(move |this| this.get_first_checkpoint_within(duration_ms))
             .await
-            .unwrap_or_else(|e| {
-                tracing::error!(
-                    "Failed to prune events of transactions for cp {}: {}",
-                    cp,
-                    e
-                );
-            });
-            info!(
-                "Pruned events of transactions for checkpoint {} from tx {} to tx {}",
-                cp, min_tx, max_tx
-            );
-            self.metrics.last_pruned_transaction.set(max_tx as i64);
-
-            self.execute_in_blocking_worker(move |this| this.prune_cp_tx_table(cp))
-                .await
-                .unwrap_or_else(|e| {
-                    tracing::error!(
-                        "Failed to prune pruner_cp_watermark table for cp {}: {}",
-                        cp,
-                        e
-                    );
-                });
-            info!("Pruned checkpoint {} of epoch {}", cp, epoch);
-            self.metrics.last_pruned_checkpoint.set(cp as i64);
+    }
+
+    async fn get_first_prunable_checkpoint(&self) -> Result<Option<u64>, IndexerError> {
+        self.execute_in_blocking_worker(|this| this.get_first_prunable_checkpoint())
+            .await
+    }
+
+    async fn get_watermarks(&self) -> Result<Vec<StoredWatermark>, IndexerError> {
+        self.execute_in_blocking_worker(|this| this.get_watermarks())
+            .await
+    }
+
+    async fn update_watermark(&self, watermark: StoredWatermark) -> Result<(), IndexerError> {
+        self.execute_in_blocking_worker(move |this| this.update_watermark(watermark))
+            .await
+    }
+
+    async fn prune_checkpoints(
+        &self,
+        table: PrunableTable,
+        checkpoints: Range<u64>,
+    ) -> Result<(), IndexerError> {
+        if checkpoints.is_empty() {
+            return Ok(());
         }
+        self.execute_in_blocking_worker(move |this| match table {
+            PrunableTable::Checkpoints => this.prune_checkpoints_table(checkpoints),
+            PrunableTable::TxIndices | PrunableTable::EventIndices => {
+                // The transaction ranges of the checkpoints are kept in the
+                // pruner_cp_watermark table until all tables are pruned.
+                let (min_tx, _) = this.get_transaction_range_for_checkpoint(checkpoints.start)?;
+                let (_, max_tx) = this.get_transaction_range_for_checkpoint(checkpoints.end - 1)?;
+                if table == PrunableTable::TxIndices {
+                    this.prune_tx_indices_table(min_tx, max_tx)?;
+                    this.metrics.last_pruned_transaction.set(max_tx as i64);
+                } else {
+                    this.prune_event_indices_table(min_tx, max_tx)?;
+                }
+                Ok(())
+            }
+            PrunableTable::Transactions | PrunableTable::Events | PrunableTable::ObjectsHistory => {
+                Err(IndexerError::NotSupported(format!(
+                    "Table {table} is pruned by dropping its epoch partitions"
+                )))
+            }
+        })
+        .await
+    }
 
-        // NOTE: prune epochs table last, otherwise get_checkpoint_range_for_epoch would
-        // fail.
-        self.execute_in_blocking_worker(move |this| this.prune_epochs_table(epoch))
+    async fn get_tx_dependencies_backfill_hi(&self) -> Result<u64, IndexerError> {
+        self.execute_in_blocking_worker(|this| this.get_tx_dependencies_backfill_hi())
             .await
-            .unwrap_or_else(|e| {
-                tracing::error!("Failed to prune epoch table for epoch {}: {}", epoch, e);
-            });
-        Ok(())
+    }
+
+    async fn backfill_tx_dependencies(
+        &self,
+        tx_hi: u64,
+        limit: usize,
+    ) -> Result<Option<u64>, IndexerError> {
+        self.execute_in_blocking_worker(move |this| this.backfill_tx_dependencies(tx_hi, limit))
+            .await
+    }
+
+    async fn prune_bookkeeping(&self, pruner_hi: u64) -> Result<(), IndexerError> {
+        // NOTE: prune epochs table last, it is the source of the checkpoint ranges of
+        // the epoch partitions.
+        self.execute_in_blocking_worker(move |this| {
+            this.prune_cp_tx_table(pruner_hi)?;
+            this.prune_epochs_table(pruner_hi)
+        })
+        .await
     }
 
     async fn get_network_total_transactions_by_end_of_epoch(
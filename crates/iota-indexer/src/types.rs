@@ -398,6 +398,9 @@ pub struct TxIndex {
     pub sender: IotaAddress,
     pub recipients: Vec<IotaAddress>,
     pub move_calls: Vec<(ObjectID, String, String)>,
+    /// Transactions that produced the object versions this transaction used as
+    /// inputs.
+    pub dependencies: Vec<TransactionDigest>,
 }
 
 // ObjectChange is not bcs deserializable, IndexedObjectChange is.
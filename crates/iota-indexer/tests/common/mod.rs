@@ -143,7 +143,7 @@ pub async fn start_test_cluster_with_read_write_indexer(
     (cluster, pg_store, rpc_client)
 }
 
-fn get_indexer_db_url(database_name: Option<&str>) -> String {
+pub fn get_indexer_db_url(database_name: Option<&str>) -> String {
     database_name.map_or_else(
         || format!("{POSTGRES_URL}/{DEFAULT_DB}"),
         |db_name| format!("{POSTGRES_URL}/{db_name}"),
@@ -0,0 +1,299 @@
+// Copyright (c) 2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+#[expect(dead_code)]
+#[cfg(feature = "pg_integration")]
+mod common;
+#[cfg(feature = "pg_integration")]
+mod pruner_tests {
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use diesel::{ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+    use iota_indexer::{
+        db::{PoolConnection, get_pool_connection},
+        handlers::pruner::{PrunableTable, Pruner, RetentionConfig, RetentionPolicy},
+        metrics::IndexerMetrics,
+        models::watermarks::StoredWatermark,
+        schema::{checkpoints, tx_senders},
+        store::{PgIndexerStore, indexer_store::IndexerStore},
+    };
+    use iota_types::base_types::IotaAddress;
+    use prometheus::Registry;
+    use simulacrum::Simulacrum;
+    use tempfile::tempdir;
+    use tokio_util::sync::CancellationToken;
+
+    use crate::common::{
+        indexer_wait_for_checkpoint, start_simulacrum_rest_api_with_write_indexer,
+    };
+
+    /// Number of epochs of the test network, the last one is still ongoing.
+    const EPOCHS: u64 = 3;
+    /// Number of checkpoints with a transfer created in each epoch.
+    const CHECKPOINTS_PER_EPOCH: u64 = 3;
+
+    /// Runs a network of a few epochs and indexes it, returning the store and
+    /// the latest checkpoint.
+    async fn index_network(database_name: &str) -> (PgIndexerStore<PgConnection>, u64) {
+        let mut sim = Simulacrum::new();
+        let data_ingestion_path = tempdir().unwrap().into_path();
+        sim.set_data_ingestion_path(data_ingestion_path.clone());
+
+        let mut latest_checkpoint = 0;
+        for epoch in 0..EPOCHS {
+            for _ in 0..CHECKPOINTS_PER_EPOCH {
+                let (transaction, _) = sim.transfer_txn(IotaAddress::random_for_testing_only());
+                let (_, err) = sim.execute_transaction(transaction).unwrap();
+                assert!(err.is_none());
+                latest_checkpoint = sim.create_checkpoint().sequence_number;
+            }
+            if epoch + 1 < EPOCHS {
+                sim.advance_epoch();
+                latest_checkpoint += 1;
+            }
+        }
+
+        let (_, pg_store, _) = start_simulacrum_rest_api_with_write_indexer(
+            Arc::new(sim),
+            data_ingestion_path,
+            None,
+            Some(database_name),
+        )
+        .await;
+        indexer_wait_for_checkpoint(&pg_store, latest_checkpoint).await;
+        (pg_store, latest_checkpoint)
+    }
+
+    fn pruner(
+        store: &PgIndexerStore<PgConnection>,
+        retention: RetentionConfig,
+    ) -> Pruner<PgIndexerStore<PgConnection>, PgConnection> {
+        Pruner::new(
+            store.clone(),
+            retention,
+            IndexerMetrics::new(&Registry::default()),
+        )
+        .unwrap()
+    }
+
+    async fn watermark(
+        store: &PgIndexerStore<PgConnection>,
+        table: PrunableTable,
+    ) -> Option<StoredWatermark> {
+        store
+            .get_watermarks()
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|watermark| watermark.entity == table.as_str())
+    }
+
+    #[tokio::test]
+    pub async fn test_prune_checkpoints_in_batches() {
+        let (store, latest_checkpoint) = index_network("indexer_pruner_batches_tests_db").await;
+        let retention = RetentionConfig {
+            default: None,
+            overrides: BTreeMap::from([(
+                PrunableTable::Checkpoints,
+                RetentionPolicy::Checkpoints(2),
+            )]),
+        };
+        // A batch size which does not divide the pruned range, so that the last batch
+        // is cut at the reader watermark.
+        let pruner = pruner(&store, retention).with_batch_size(3);
+        pruner.prune(&CancellationToken::new()).await.unwrap();
+
+        let reader_lo = latest_checkpoint - 1;
+        let watermark = watermark(&store, PrunableTable::Checkpoints).await.unwrap();
+        assert_eq!(watermark.reader_lo as u64, reader_lo);
+        assert_eq!(watermark.pruner_hi as u64, reader_lo);
+        assert!(watermark.timestamp_ms > 0);
+        assert_eq!(
+            store.get_available_checkpoint_range().await.unwrap(),
+            (reader_lo, latest_checkpoint)
+        );
+
+        // Tables without a policy are kept forever.
+        assert!(
+            watermark(&store, PrunableTable::Transactions)
+                .await
+                .is_none()
+        );
+        assert!(watermark(&store, PrunableTable::Events).await.is_none());
+    }
+
+    #[tokio::test]
+    pub async fn test_prune_deletes_rows() {
+        let (store, latest_checkpoint) = index_network("indexer_pruner_rows_tests_db").await;
+        let mut conn = get_pool_connection::<PgConnection>(&store.blocking_cp()).unwrap();
+        let reader_lo = latest_checkpoint - 1;
+        let first_kept_tx = checkpoints::table
+            .select(checkpoints::min_tx_sequence_number)
+            .filter(checkpoints::sequence_number.eq(reader_lo as i64))
+            .first::<Option<i64>>(&mut conn)
+            .unwrap()
+            .unwrap();
+        let count_senders = |conn: &mut PoolConnection<PgConnection>| {
+            tx_senders::table
+                .filter(tx_senders::tx_sequence_number.lt(first_kept_tx))
+                .count()
+                .get_result::<i64>(conn)
+                .unwrap()
+        };
+        assert!(count_senders(&mut conn) > 0);
+
+        // The checkpoints table is pruned before the tx indices, whose watermark
+        // must still start at their first row.
+        let retention = RetentionConfig {
+            default: None,
+            overrides: BTreeMap::from([
+                (PrunableTable::Checkpoints, RetentionPolicy::Checkpoints(2)),
+                (PrunableTable::TxIndices, RetentionPolicy::Checkpoints(2)),
+            ]),
+        };
+        pruner(&store, retention)
+            .prune(&CancellationToken::new())
+            .await
+            .unwrap();
+
+        let pruned_checkpoints = checkpoints::table
+            .filter(checkpoints::sequence_number.lt(reader_lo as i64))
+            .count()
+            .get_result::<i64>(&mut conn)
+            .unwrap();
+        assert_eq!(pruned_checkpoints, 0);
+        assert_eq!(count_senders(&mut conn), 0);
+        let watermark = watermark(&store, PrunableTable::TxIndices).await.unwrap();
+        assert_eq!(watermark.pruner_hi as u64, reader_lo);
+    }
+
+    #[tokio::test]
+    pub async fn test_watermarks_advance() {
+        let (store, latest_checkpoint) = index_network("indexer_pruner_watermarks_tests_db").await;
+        let retention = RetentionConfig {
+            default: None,
+            overrides: BTreeMap::from([(
+                PrunableTable::Checkpoints,
+                RetentionPolicy::Checkpoints(latest_checkpoint),
+            )]),
+        };
+        let pruner = pruner(&store, retention);
+        pruner.prune(&CancellationToken::new()).await.unwrap();
+        let first = watermark(&store, PrunableTable::Checkpoints).await.unwrap();
+        assert_eq!(first.reader_lo, 1);
+        assert_eq!(first.pruner_hi, 1);
+
+        // Pruning again without new checkpoints leaves the watermark untouched.
+        pruner.prune(&CancellationToken::new()).await.unwrap();
+        assert_eq!(
+            watermark(&store, PrunableTable::Checkpoints).await.unwrap(),
+            first
+        );
+
+        // A stricter policy advances the watermark, a looser one never moves it back.
+        let mut pruner = pruner;
+        pruner
+            .retention
+            .overrides
+            .insert(PrunableTable::Checkpoints, RetentionPolicy::Checkpoints(2));
+        pruner.prune(&CancellationToken::new()).await.unwrap();
+        pruner.retention.overrides.insert(
+            PrunableTable::Checkpoints,
+            RetentionPolicy::Checkpoints(latest_checkpoint),
+        );
+        pruner.prune(&CancellationToken::new()).await.unwrap();
+        let second = watermark(&store, PrunableTable::Checkpoints).await.unwrap();
+        assert_eq!(second.reader_lo as u64, latest_checkpoint - 1);
+        assert_eq!(second.pruner_hi as u64, latest_checkpoint - 1);
+        assert!(second.timestamp_ms >= first.timestamp_ms);
+    }
+
+    #[tokio::test]
+    pub async fn test_drop_epoch_partitions() {
+        let (store, _) = index_network("indexer_pruner_partitions_tests_db").await;
+        let retention = RetentionConfig {
+            default: None,
+            overrides: BTreeMap::from([(PrunableTable::Transactions, RetentionPolicy::Epochs(1))]),
+        };
+        let pruner = pruner(&store, retention);
+        assert_eq!(
+            pruner.partition_manager.get_table_partitions().unwrap()["transactions"],
+            (0, EPOCHS - 1)
+        );
+
+        pruner.prune(&CancellationToken::new()).await.unwrap();
+
+        let partitions = pruner.partition_manager.get_table_partitions().unwrap();
+        assert_eq!(partitions["transactions"], (EPOCHS - 1, EPOCHS - 1));
+        // Tables without a policy keep all their partitions.
+        assert_eq!(partitions["events"], (0, EPOCHS - 1));
+
+        let (first_checkpoint, _) = store
+            .get_checkpoint_range_for_epoch(EPOCHS - 1)
+            .await
+            .unwrap();
+        let watermark = watermark(&store, PrunableTable::Transactions)
+            .await
+            .unwrap();
+        assert_eq!(watermark.reader_lo as u64, first_checkpoint);
+        assert_eq!(watermark.pruner_hi as u64, first_checkpoint);
+    }
+
+    #[tokio::test]
+    pub async fn test_prune_bookkeeping() {
+        let (store, _) = index_network("indexer_pruner_bookkeeping_tests_db").await;
+        let mut retention = RetentionConfig {
+            default: None,
+            overrides: PrunableTable::ALL
+                .into_iter()
+                .filter(|table| *table != PrunableTable::ObjectsHistory)
+                .map(|table| (table, RetentionPolicy::Epochs(1)))
+                .collect(),
+        };
+        pruner(&store, retention.clone())
+            .prune(&CancellationToken::new())
+            .await
+            .unwrap();
+        // The objects history is kept forever, and so is the bookkeeping needed to
+        // prune it.
+        assert_eq!(
+            store.get_available_epoch_range().await.unwrap(),
+            (0, EPOCHS - 1)
+        );
+
+        retention
+            .overrides
+            .insert(PrunableTable::ObjectsHistory, RetentionPolicy::Epochs(2));
+        pruner(&store, retention)
+            .prune(&CancellationToken::new())
+            .await
+            .unwrap();
+        // The bookkeeping is pruned up to the table pruned the least.
+        let (first_checkpoint, _) = store
+            .get_checkpoint_range_for_epoch(EPOCHS - 2)
+            .await
+            .unwrap();
+        let watermarks = store.get_watermarks().await.unwrap();
+        assert_eq!(watermarks.len(), PrunableTable::ALL.len());
+        assert_eq!(
+            watermarks
+                .iter()
+                .map(|watermark| watermark.pruner_hi as u64)
+                .min(),
+            Some(first_checkpoint)
+        );
+        assert_eq!(
+            store.get_available_epoch_range().await.unwrap(),
+            (EPOCHS - 2, EPOCHS - 1)
+        );
+        // The epochs still referenced by a watermark remain, they give the checkpoint
+        // ranges of the epoch partitions.
+        assert!(
+            store
+                .get_checkpoint_range_for_epoch(EPOCHS - 2)
+                .await
+                .is_ok()
+        );
+        assert!(store.get_checkpoint_range_for_epoch(0).await.is_err());
+    }
+}
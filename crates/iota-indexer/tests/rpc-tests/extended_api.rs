@@ -8,12 +8,13 @@ use iota_json_rpc_api::{
     ExtendedApiClient, IndexerApiClient, ReadApiClient, TransactionBuilderClient, WriteApiClient,
 };
 use iota_json_rpc_types::{
-    IotaObjectDataOptions, IotaObjectResponseQuery, IotaTransactionBlockResponseOptions,
-    TransactionBlockBytes,
+    IotaObjectDataOptions, IotaObjectResponseQuery, IotaTransactionBlockEffectsAPI,
+    IotaTransactionBlockResponseOptions, IotaTransactionBlockResponseQuery, TransactionBlockBytes,
 };
 use iota_types::{
     IOTA_FRAMEWORK_ADDRESS,
     base_types::{IotaAddress, ObjectID},
+    digests::TransactionDigest,
     gas_coin::GAS,
     quorum_driver_types::ExecuteTransactionRequestType,
     storage::ReadStore,
@@ -21,7 +22,9 @@ use iota_types::{
 use simulacrum::Simulacrum;
 use test_cluster::TestCluster;
 
-use crate::common::{ApiTestSetup, SimulacrumTestSetup, indexer_wait_for_checkpoint};
+use crate::common::{
+    ApiTestSetup, SimulacrumTestSetup, indexer_wait_for_checkpoint, rpc_call_error_msg_matches,
+};
 
 static EXTENDED_API_SHARED_SIMULACRUM_INITIALIZED_ENV: OnceLock<SimulacrumTestSetup> =
     OnceLock::new();
@@ -413,6 +416,114 @@ fn get_total_transactions() {
     });
 }
 
+#[test]
+fn get_transaction_dependency_graph() {
+    let SimulacrumTestSetup {
+        runtime,
+        sim,
+        store,
+        client,
+    } = get_or_init_shared_extended_api_simulacrum_env();
+
+    runtime.block_on(async move {
+        let latest_checkpoint = sim.get_latest_checkpoint().unwrap();
+        indexer_wait_for_checkpoint(store, latest_checkpoint.sequence_number).await;
+
+        let transactions = client
+            .query_transaction_blocks(
+                IotaTransactionBlockResponseQuery::new(
+                    None,
+                    Some(IotaTransactionBlockResponseOptions::new().with_effects()),
+                ),
+                None,
+                None,
+                Some(true),
+            )
+            .await
+            .unwrap();
+        let transaction = transactions.data.first().unwrap();
+        let mut expected_dependencies = transaction
+            .effects
+            .as_ref()
+            .unwrap()
+            .dependencies()
+            .iter()
+            .copied()
+            .filter(|digest| *digest != TransactionDigest::genesis_marker())
+            .collect::<Vec<_>>();
+        expected_dependencies.sort();
+        assert!(!expected_dependencies.is_empty());
+
+        let graph = client
+            .get_transaction_dependency_graph(transaction.digest, Some(1))
+            .await
+            .unwrap();
+        assert_eq!(graph.transaction, transaction.digest);
+        assert!(!graph.truncated);
+        assert!(graph.descendants.is_empty());
+        let mut dependencies = graph
+            .ancestors
+            .iter()
+            .map(|edge| {
+                assert_eq!(edge.transaction, transaction.digest);
+                assert_eq!(edge.depth, 1);
+                edge.dependency
+            })
+            .collect::<Vec<_>>();
+        dependencies.sort();
+        assert_eq!(dependencies, expected_dependencies);
+
+        // The transaction shows up as a descendant of each of its dependencies.
+        for dependency in expected_dependencies {
+            let graph = client
+                .get_transaction_dependency_graph(dependency, None)
+                .await
+                .unwrap();
+            assert!(graph.descendants.iter().any(|edge| {
+                edge.transaction == transaction.digest && edge.dependency == dependency
+            }));
+        }
+
+        let deeper_graph = client
+            .get_transaction_dependency_graph(transaction.digest, Some(3))
+            .await
+            .unwrap();
+        assert!(deeper_graph.ancestors.len() >= graph.ancestors.len());
+        assert!(deeper_graph.ancestors.iter().all(|edge| edge.depth <= 3));
+    });
+}
+
+#[test]
+fn get_transaction_dependency_graph_invalid_depth() {
+    let SimulacrumTestSetup {
+        runtime,
+        sim,
+        store,
+        client,
+    } = get_or_init_shared_extended_api_simulacrum_env();
+
+    runtime.block_on(async move {
+        let latest_checkpoint = sim.get_latest_checkpoint().unwrap();
+        indexer_wait_for_checkpoint(store, latest_checkpoint.sequence_number).await;
+
+        let result = client
+            .get_transaction_dependency_graph(TransactionDigest::genesis_marker(), Some(0))
+            .await;
+        assert!(rpc_call_error_msg_matches(
+            result,
+            r#"{"code":-32602,"message":"Dependency depth cannot be smaller than 1"}"#,
+        ));
+
+        let result = client
+            .get_transaction_dependency_graph(TransactionDigest::genesis_marker(), Some(11))
+            .await;
+        assert!(rpc_call_error_msg_matches(
+            result,
+            r#"{"code":-32602,"message":"Dependency depth 11 exceeds max depth 10"}"#,
+        ));
+    });
+}
+
 async fn execute_move_fn(cluster: &TestCluster) -> Result<(), anyhow::Error> {
     let http_client = cluster.rpc_client();
     let address = cluster.get_address_0();
@@ -0,0 +1,179 @@
+// Copyright (c) 2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+#[expect(dead_code)]
+#[cfg(feature = "pg_integration")]
+mod common;
+#[cfg(feature = "pg_integration")]
+mod tx_dependencies_tests {
+    use std::sync::Arc;
+
+    use diesel::{ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+    use iota_indexer::{
+        db::{PoolConnection, get_pool_connection},
+        handlers::tx_dependencies_backfill::backfill_tx_dependencies,
+        indexer_reader::IndexerReader,
+        schema::tx_dependencies,
+        store::PgIndexerStore,
+    };
+    use iota_json_rpc_types::TransactionDependency;
+    use iota_types::{base_types::IotaAddress, digests::TransactionDigest};
+    use simulacrum::Simulacrum;
+    use tempfile::tempdir;
+    use tokio_util::sync::CancellationToken;
+
+    use crate::common::{
+        get_indexer_db_url, indexer_wait_for_checkpoint,
+        start_simulacrum_rest_api_with_write_indexer,
+    };
+
+    /// Number of transfers paid with the same gas coin, so that each one
+    /// depends on the previous one.
+    const TRANSFERS: usize = 5;
+
+    /// Indexes a chain of transfers, returning the store and the digests of
+    /// the transfers in execution order.
+    async fn index_transfers(
+        database_name: &str,
+    ) -> (PgIndexerStore<PgConnection>, Vec<TransactionDigest>) {
+        let mut sim = Simulacrum::new();
+        let data_ingestion_path = tempdir().unwrap().into_path();
+        sim.set_data_ingestion_path(data_ingestion_path.clone());
+
+        let mut digests = vec![];
+        for _ in 0..TRANSFERS {
+            let (transaction, _) = sim.transfer_txn(IotaAddress::random_for_testing_only());
+            digests.push(*transaction.digest());
+            let (_, err) = sim.execute_transaction(transaction).unwrap();
+            assert!(err.is_none());
+        }
+        let latest_checkpoint = sim.create_checkpoint().sequence_number;
+
+        let (_, pg_store, _) = start_simulacrum_rest_api_with_write_indexer(
+            Arc::new(sim),
+            data_ingestion_path,
+            None,
+            Some(database_name),
+        )
+        .await;
+        indexer_wait_for_checkpoint(&pg_store, latest_checkpoint).await;
+        (pg_store, digests)
+    }
+
+    fn stored_dependencies(
+        conn: &mut PoolConnection<PgConnection>,
+    ) -> Vec<(i64, Vec<u8>, Vec<u8>)> {
+        tx_dependencies::table
+            .select((
+                tx_dependencies::tx_sequence_number,
+                tx_dependencies::tx_digest,
+                tx_dependencies::dependency_digest,
+            ))
+            .order_by(tx_dependencies::tx_sequence_number)
+            .then_order_by(tx_dependencies::dependency_digest)
+            .load(conn)
+            .unwrap()
+    }
+
+    /// A dependency edge as `(transaction, dependency, depth)`.
+    type Edge = (TransactionDigest, TransactionDigest, u32);
+
+    fn edges(dependencies: &[TransactionDependency]) -> Vec<Edge> {
+        dependencies
+            .iter()
+            .map(|edge| (edge.transaction, edge.dependency, edge.depth))
+            .collect()
+    }
+
+    #[tokio::test]
+    pub async fn test_backfill_tx_dependencies() {
+        let database_name = "indexer_tx_dependencies_backfill_tests_db";
+        let (store, _) = index_transfers(database_name).await;
+        let mut conn = get_pool_connection::<PgConnection>(&store.blocking_cp()).unwrap();
+        let indexed = stored_dependencies(&mut conn);
+        assert!(indexed.len() >= TRANSFERS);
+
+        // Nothing indexed yet, as after the migration.
+        diesel::delete(tx_dependencies::table)
+            .execute(&mut conn)
+            .unwrap();
+        backfill_tx_dependencies(store.clone(), CancellationToken::new())
+            .await
+            .unwrap();
+        assert_eq!(stored_dependencies(&mut conn), indexed);
+
+        // An interrupted backfill resumes below the oldest indexed transaction.
+        let resume_at = indexed[indexed.len() / 2].0;
+        diesel::delete(
+            tx_dependencies::table.filter(tx_dependencies::tx_sequence_number.lt(resume_at)),
+        )
+        .execute(&mut conn)
+        .unwrap();
+        backfill_tx_dependencies(store.clone(), CancellationToken::new())
+            .await
+            .unwrap();
+        assert_eq!(stored_dependencies(&mut conn), indexed);
+
+        // A cancelled backfill doesn't index anything.
+        diesel::delete(tx_dependencies::table)
+            .execute(&mut conn)
+            .unwrap();
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        backfill_tx_dependencies(store, cancel).await.unwrap();
+        assert!(stored_dependencies(&mut conn).is_empty());
+    }
+
+    #[tokio::test]
+    pub async fn test_transaction_dependency_graph_truncation() {
+        let database_name = "indexer_tx_dependencies_graph_tests_db";
+        let (store, digests) = index_transfers(database_name).await;
+        let reader =
+            IndexerReader::<PgConnection>::new(get_indexer_db_url(Some(database_name))).unwrap();
+        let max_edges = 1000;
+
+        // Only the edges up to the requested depth are returned, which doesn't
+        // truncate the graph.
+        let graph = reader
+            .get_transaction_dependency_graph(digests[2], 1, max_edges, None)
+            .unwrap();
+        assert_eq!(edges(&graph.ancestors), vec![(digests[2], digests[1], 1)]);
+        assert_eq!(edges(&graph.descendants), vec![(digests[3], digests[2], 1)]);
+        assert!(!graph.truncated);
+
+        let graph = reader
+            .get_transaction_dependency_graph(digests[0], 10, max_edges, None)
+            .unwrap();
+        let descendants: Vec<Edge> = (1..TRANSFERS)
+            .map(|i| (digests[i], digests[i - 1], i as u32))
+            .collect();
+        assert_eq!(edges(&graph.descendants), descendants);
+        assert!(!graph.truncated);
+
+        // A graph with exactly as many edges as allowed is not truncated.
+        let graph = reader
+            .get_transaction_dependency_graph(digests[0], 10, TRANSFERS - 1, None)
+            .unwrap();
+        assert_eq!(edges(&graph.descendants), descendants);
+        assert!(!graph.truncated);
+
+        // Edges beyond the maximum number are left out, closest first.
+        let graph = reader
+            .get_transaction_dependency_graph(digests[0], 10, 2, None)
+            .unwrap();
+        assert_eq!(edges(&graph.descendants), descendants[..2]);
+        assert!(graph.truncated);
+
+        // Transactions from `tx_hi` on are not part of the graph.
+        let mut conn = get_pool_connection::<PgConnection>(&store.blocking_cp()).unwrap();
+        let (tx_hi, _, _) = stored_dependencies(&mut conn)
+            .into_iter()
+            .find(|(_, tx_digest, _)| tx_digest[..] == digests[2].inner()[..])
+            .unwrap();
+        let graph = reader
+            .get_transaction_dependency_graph(digests[0], 10, max_edges, Some(tx_hi as u64))
+            .unwrap();
+        assert_eq!(edges(&graph.descendants), descendants[..1]);
+        assert!(!graph.truncated);
+    }
+}
@@ -4,9 +4,10 @@
 
 use iota_json_rpc_types::{
     AddressMetrics, EpochInfo, EpochMetricsPage, EpochPage, MoveCallMetrics, NetworkMetrics,
+    TransactionDependencyGraph,
 };
 use iota_open_rpc_macros::open_rpc;
-use iota_types::iota_serde::BigInt;
+use iota_types::{digests::TransactionDigest, iota_serde::BigInt};
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 
 /// Methods served exclusively by the indexer, supporting queries using refined
@@ -66,4 +67,15 @@ pub trait ExtendedApi {
 
     #[method(name = "getTotalTransactions")]
     async fn get_total_transactions(&self) -> RpcResult<BigInt<u64>>;
+
+    /// Return the causal ancestors and descendants of a transaction, built from
+    /// the object versions it used as inputs, up to the given depth
+    #[method(name = "getTransactionDependencyGraph")]
+    async fn get_transaction_dependency_graph(
+        &self,
+        /// The digest of the transaction
+        digest: TransactionDigest,
+        /// Maximum number of dependency levels to follow in each direction
+        depth: Option<usize>,
+    ) -> RpcResult<TransactionDependencyGraph>;
 }
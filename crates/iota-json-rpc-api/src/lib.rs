@@ -51,6 +51,12 @@ pub static QUERY_MAX_RESULT_LIMIT: Lazy<usize> = Lazy::new(|| {
 // TODOD(chris): make this configurable
 pub const QUERY_MAX_RESULT_LIMIT_CHECKPOINTS: usize = 100;
 
+/// The maximum depth of a transaction dependency graph query.
+pub const QUERY_MAX_TRANSACTION_DEPENDENCY_DEPTH: usize = 10;
+/// The maximum number of edges returned in each direction by a transaction
+/// dependency graph query.
+pub const QUERY_MAX_TRANSACTION_DEPENDENCY_EDGES: usize = 1000;
+
 pub fn cap_page_limit(limit: Option<usize>) -> usize {
     let limit = limit.unwrap_or_default();
     if limit > *QUERY_MAX_RESULT_LIMIT || limit == 0 {
@@ -69,6 +75,16 @@ pub fn validate_limit(limit: Option<usize>, max: usize) -> Result<usize, anyhow:
     }
 }
 
+pub fn validate_transaction_dependency_depth(depth: Option<usize>) -> Result<usize, anyhow::Error> {
+    let max = QUERY_MAX_TRANSACTION_DEPENDENCY_DEPTH;
+    match depth {
+        Some(d) if d > max => Err(anyhow!("Dependency depth {d} exceeds max depth {max}")),
+        Some(0) => Err(anyhow!("Dependency depth cannot be smaller than 1")),
+        Some(d) => Ok(d),
+        None => Ok(max),
+    }
+}
+
 #[derive(Clone)]
 pub struct JsonRpcMetrics {
     pub get_objects_limit: Histogram,
@@ -8,6 +8,7 @@ use fastcrypto::traits::ToFromBytes;
 use iota_types::{
     base_types::{AuthorityName, EpochId, ObjectID},
     committee::Committee,
+    digests::TransactionDigest,
     iota_serde::BigInt,
     iota_system_state::iota_system_state_summary::IotaValidatorSummary,
     messages_checkpoint::CheckpointSequenceNumber,
@@ -212,3 +213,33 @@ pub struct AddressMetrics {
     /// The count of daily unique sender addresses.
     pub daily_active_addresses: u64,
 }
+
+/// The causal dependency graph of a transaction. A transaction depends on the
+/// transactions that produced the object versions it used as inputs.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionDependencyGraph {
+    /// The digest of the transaction the graph is built around.
+    pub transaction: TransactionDigest,
+    /// The dependency edges leading from the transaction to its ancestors.
+    pub ancestors: Vec<TransactionDependency>,
+    /// The dependency edges leading from the descendants to the transaction.
+    pub descendants: Vec<TransactionDependency>,
+    /// Whether edges were left out because the maximum number of edges was
+    /// reached before the requested depth.
+    pub truncated: bool,
+}
+
+/// A dependency edge between two transactions.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionDependency {
+    /// The digest of the dependent transaction.
+    pub transaction: TransactionDigest,
+    /// The digest of the transaction that produced object versions used as
+    /// inputs by the dependent transaction.
+    pub dependency: TransactionDigest,
+    /// The distance of the edge from the transaction the graph is built
+    /// around, starting at 1.
+    pub depth: u32,
+}
@@ -0,0 +1,229 @@
+// Copyright (c) 2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Passphrase based encryption of keystore files. A symmetric key is derived
+//! from the passphrase with scrypt and used to encrypt the keystore content
+//! with ChaCha20-Poly1305.
+
+use anyhow::{anyhow, ensure};
+use chacha20poly1305::{
+    ChaCha20Poly1305, Key, KeyInit, Nonce,
+    aead::{Aead, Payload},
+};
+use fastcrypto::encoding::{Base64, Encoding};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+/// Version of the encrypted keystore file format.
+pub const ENCRYPTED_KEYSTORE_VERSION: u8 = 1;
+
+const KEY_LENGTH: usize = 32;
+const SALT_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+/// Associated data binding the ciphertext to the file format version.
+const ASSOCIATED_DATA: &[u8] = b"iota-encrypted-keystore-v1";
+
+/// Parameters of the scrypt key derivation function.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KdfParams {
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+}
+
+impl Default for KdfParams {
+    /// Uses 2^17 iterations, which takes 128 MiB of memory and roughly a
+    /// second to derive a key on commodity hardware.
+    fn default() -> Self {
+        Self {
+            log_n: 17,
+            r: 8,
+            p: 1,
+        }
+    }
+}
+
+impl KdfParams {
+    /// Cheap parameters that must only be used in tests.
+    pub fn insecure_for_tests() -> Self {
+        Self {
+            log_n: 4,
+            r: 8,
+            p: 1,
+        }
+    }
+}
+
+/// A symmetric key derived from a passphrase, together with the salt and
+/// parameters used to derive it.
+#[derive(Clone)]
+pub struct KeystoreKey {
+    key: Zeroizing<[u8; KEY_LENGTH]>,
+    salt: [u8; SALT_LENGTH],
+    kdf: KdfParams,
+}
+
+impl KeystoreKey {
+    /// Derives a key from `passphrase` with a fresh random salt.
+    pub fn generate(passphrase: &str, kdf: KdfParams) -> Result<Self, anyhow::Error> {
+        let mut salt = [0u8; SALT_LENGTH];
+        rand::thread_rng().fill_bytes(&mut salt);
+        Self::derive(passphrase, salt, kdf)
+    }
+
+    fn derive(
+        passphrase: &str,
+        salt: [u8; SALT_LENGTH],
+        kdf: KdfParams,
+    ) -> Result<Self, anyhow::Error> {
+        let params = scrypt::Params::new(kdf.log_n, kdf.r, kdf.p, KEY_LENGTH)
+            .map_err(|e| anyhow!("Invalid key derivation parameters: {e}"))?;
+        let mut key = Zeroizing::new([0u8; KEY_LENGTH]);
+        scrypt::scrypt(passphrase.as_bytes(), &salt, &params, key.as_mut())
+            .map_err(|e| anyhow!("Cannot derive keystore key: {e}"))?;
+        Ok(Self { key, salt, kdf })
+    }
+
+    /// Re-derives the key that was used to encrypt `file` from `passphrase`.
+    pub fn derive_for(
+        passphrase: &str,
+        file: &EncryptedKeystoreFile,
+    ) -> Result<Self, anyhow::Error> {
+        Self::derive(passphrase, file.salt()?, file.kdf)
+    }
+
+    /// Returns true if this key was derived with the salt and parameters of
+    /// `file`.
+    pub fn matches(&self, file: &EncryptedKeystoreFile) -> bool {
+        file.salt().is_ok_and(|salt| salt == self.salt) && file.kdf == self.kdf
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<EncryptedKeystoreFile, anyhow::Error> {
+        let mut nonce = [0u8; NONCE_LENGTH];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher()
+            .encrypt(Nonce::from_slice(&nonce), Payload {
+                msg: plaintext,
+                aad: ASSOCIATED_DATA,
+            })
+            .map_err(|_| anyhow!("Cannot encrypt keystore"))?;
+        Ok(EncryptedKeystoreFile {
+            version: ENCRYPTED_KEYSTORE_VERSION,
+            kdf: self.kdf,
+            salt: Base64::encode(self.salt),
+            nonce: Base64::encode(nonce),
+            ciphertext: Base64::encode(ciphertext),
+        })
+    }
+
+    pub fn decrypt(
+        &self,
+        file: &EncryptedKeystoreFile,
+    ) -> Result<Zeroizing<Vec<u8>>, anyhow::Error> {
+        ensure!(
+            file.version == ENCRYPTED_KEYSTORE_VERSION,
+            "Unsupported encrypted keystore version {}",
+            file.version
+        );
+        let nonce = Base64::decode(&file.nonce).map_err(|e| anyhow!("Invalid nonce: {e}"))?;
+        ensure!(nonce.len() == NONCE_LENGTH, "Invalid nonce length");
+        let ciphertext =
+            Base64::decode(&file.ciphertext).map_err(|e| anyhow!("Invalid ciphertext: {e}"))?;
+        self.cipher()
+            .decrypt(Nonce::from_slice(&nonce), Payload {
+                msg: &ciphertext,
+                aad: ASSOCIATED_DATA,
+            })
+            .map(Zeroizing::new)
+            .map_err(|_| anyhow!("Cannot decrypt keystore, the passphrase is incorrect"))
+    }
+
+    /// Encodes the key as Base64 `salt || key`, so that it can be cached
+    /// for the duration of a session.
+    pub fn encode_base64(&self) -> String {
+        let mut bytes = Zeroizing::new(Vec::with_capacity(SALT_LENGTH + KEY_LENGTH));
+        bytes.extend_from_slice(&self.salt);
+        bytes.extend_from_slice(self.key.as_ref());
+        Base64::encode(bytes.as_slice())
+    }
+
+    pub fn decode_base64(value: &str, kdf: KdfParams) -> Result<Self, anyhow::Error> {
+        let bytes = Zeroizing::new(
+            Base64::decode(value).map_err(|e| anyhow!("Invalid keystore key: {e}"))?,
+        );
+        ensure!(
+            bytes.len() == SALT_LENGTH + KEY_LENGTH,
+            "Invalid keystore key length"
+        );
+        let (salt, key) = bytes.split_at(SALT_LENGTH);
+        Ok(Self {
+            key: Zeroizing::new(key.try_into()?),
+            salt: salt.try_into()?,
+            kdf,
+        })
+    }
+
+    fn cipher(&self) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new(Key::from_slice(self.key.as_ref()))
+    }
+}
+
+/// Content of an encrypted keystore file.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EncryptedKeystoreFile {
+    pub version: u8,
+    pub kdf: KdfParams,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl EncryptedKeystoreFile {
+    fn salt(&self) -> Result<[u8; SALT_LENGTH], anyhow::Error> {
+        Base64::decode(&self.salt)
+            .map_err(|e| anyhow!("Invalid salt: {e}"))?
+            .try_into()
+            .map_err(|_| anyhow!("Invalid salt length"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypt_decrypt_roundtrip() {
+        let key = KeystoreKey::generate("passphrase", KdfParams::insecure_for_tests()).unwrap();
+        let file = key.encrypt(b"secret keys").unwrap();
+        assert_eq!(key.decrypt(&file).unwrap().as_slice(), b"secret keys");
+
+        let rederived = KeystoreKey::derive_for("passphrase", &file).unwrap();
+        assert!(rederived.matches(&file));
+        assert_eq!(rederived.decrypt(&file).unwrap().as_slice(), b"secret keys");
+
+        let wrong = KeystoreKey::derive_for("wrong passphrase", &file).unwrap();
+        assert!(wrong.decrypt(&file).is_err());
+    }
+
+    #[test]
+    fn tampered_ciphertext_is_rejected() {
+        let key = KeystoreKey::generate("passphrase", KdfParams::insecure_for_tests()).unwrap();
+        let mut file = key.encrypt(b"secret keys").unwrap();
+        let mut ciphertext = Base64::decode(&file.ciphertext).unwrap();
+        ciphertext[0] ^= 1;
+        file.ciphertext = Base64::encode(ciphertext);
+        assert!(key.decrypt(&file).is_err());
+    }
+
+    #[test]
+    fn encoded_key_roundtrip() {
+        let kdf = KdfParams::insecure_for_tests();
+        let key = KeystoreKey::generate("passphrase", kdf).unwrap();
+        let file = key.encrypt(b"secret keys").unwrap();
+        let decoded = KeystoreKey::decode_base64(&key.encode_base64(), kdf).unwrap();
+        assert!(decoded.matches(&file));
+        assert_eq!(decoded.decrypt(&file).unwrap().as_slice(), b"secret keys");
+    }
+}
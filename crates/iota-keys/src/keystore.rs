@@ -7,8 +7,9 @@ use std::{
     fmt::{Display, Formatter, Write},
     fs,
     fs::File,
-    io::BufReader,
+    io::{BufReader, Write as _},
     path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use anyhow::{Context, anyhow, bail, ensure};
@@ -21,21 +22,52 @@ use iota_types::{
         get_key_pair_from_rng,
     },
 };
+use once_cell::sync::OnceCell;
 use rand::{SeedableRng, rngs::StdRng};
 use regex::Regex;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use shared_crypto::intent::{Intent, IntentMessage};
 
 use crate::{
+    encryption::{EncryptedKeystoreFile, KdfParams, KeystoreKey},
     key_derive::{derive_key_pair_from_path, generate_new_key},
     random_names::{random_name, random_names},
 };
 
 #[derive(Serialize, Deserialize)]
+#[serde(try_from = "KeystoreRepr")]
 #[enum_dispatch(AccountKeystore)]
 pub enum Keystore {
     File(FileBasedKeystore),
     InMem(InMemKeystore),
+    Encrypted(EncryptedFileBasedKeystore),
+}
+
+/// Serialized form of [`Keystore`]. A `File` keystore that has been encrypted
+/// in place is opened as an [`EncryptedFileBasedKeystore`], so that existing
+/// client configs keep working after encrypting the keystore.
+#[derive(Deserialize)]
+enum KeystoreRepr {
+    File(PathBuf),
+    InMem(InMemKeystore),
+    Encrypted(PathBuf),
+}
+
+impl TryFrom<KeystoreRepr> for Keystore {
+    type Error = anyhow::Error;
+
+    fn try_from(repr: KeystoreRepr) -> Result<Self, Self::Error> {
+        Ok(match repr {
+            KeystoreRepr::File(path) if is_encrypted_keystore(&path) => {
+                Keystore::Encrypted(EncryptedFileBasedKeystore::new(&path)?)
+            }
+            KeystoreRepr::File(path) => Keystore::File(FileBasedKeystore::new(&path)?),
+            KeystoreRepr::InMem(keystore) => Keystore::InMem(keystore),
+            KeystoreRepr::Encrypted(path) => {
+                Keystore::Encrypted(EncryptedFileBasedKeystore::new(&path)?)
+            }
+        })
+    }
 }
 #[enum_dispatch]
 pub trait AccountKeystore: Send + Sync {
@@ -170,6 +202,20 @@ impl Display for Keystore {
                 writeln!(writer, "Keystore Type: InMem")?;
                 write!(f, "{}", writer)
             }
+            Keystore::Encrypted(file) => {
+                writeln!(writer, "Keystore Type: Encrypted")?;
+                writeln!(writer, "Keystore Path : {:?}", file.path)?;
+                write!(
+                    writer,
+                    "Keystore Status : {}",
+                    if file.is_locked() {
+                        "Locked"
+                    } else {
+                        "Unlocked"
+                    }
+                )?;
+                write!(f, "{}", writer)
+            }
         }
     }
 }
@@ -336,6 +382,11 @@ impl FileBasedKeystore {
                 BufReader::new(File::open(path).with_context(|| {
                     format!("Cannot open the keystore file: {}", path.display())
                 })?);
+            ensure!(
+                !is_encrypted_keystore(path),
+                "The keystore file {} is encrypted",
+                path.display()
+            );
             let kp_strings: Vec<String> = serde_json::from_reader(reader).with_context(|| {
                 format!("Cannot deserialize the keystore file: {}", path.display(),)
             })?;
@@ -421,6 +472,10 @@ impl FileBasedKeystore {
         self.path = path.to_path_buf();
     }
 
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
     pub fn save_aliases(&self) -> Result<(), anyhow::Error> {
         let aliases_store = serde_json::to_string_pretty(
             &self.aliases.values().collect::<Vec<_>>(),
@@ -468,6 +523,556 @@ impl FileBasedKeystore {
     }
 }
 
+/// Provides the passphrase of an encrypted keystore, e.g. by prompting the
+/// user.
+pub type PassphraseProvider = Box<dyn Fn() -> Result<String, anyhow::Error> + Send + Sync>;
+
+/// A keystore whose private keys are encrypted at rest with a key derived
+/// from a passphrase. The aliases file, which only contains public keys, is
+/// kept in plaintext so that addresses can be listed while the keystore is
+/// locked.
+///
+/// The keystore is unlocked the first time a private key is needed, with the
+/// key cached by a session if there is one, or else with the passphrase of
+/// the [`PassphraseProvider`], if one is set. Without either, signing and
+/// adding keys fail while the keystore is locked.
+pub struct EncryptedFileBasedKeystore {
+    aliases: BTreeMap<IotaAddress, Alias>,
+    path: PathBuf,
+    file: Option<EncryptedKeystoreFile>,
+    unlocked: OnceCell<UnlockedKeys>,
+    passphrase_provider: Option<PassphraseProvider>,
+}
+
+/// The decrypted contents of an [`EncryptedFileBasedKeystore`].
+struct UnlockedKeys {
+    key: KeystoreKey,
+    keys: BTreeMap<IotaAddress, IotaKeyPair>,
+}
+
+/// Derived key cached on disk for the duration of a session.
+#[derive(Serialize, Deserialize)]
+struct KeystoreSession {
+    key: String,
+    kdf: KdfParams,
+    expires_at_secs: u64,
+}
+
+impl Serialize for EncryptedFileBasedKeystore {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.path.to_str().unwrap_or(""))
+    }
+}
+
+impl<'de> Deserialize<'de> for EncryptedFileBasedKeystore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+        EncryptedFileBasedKeystore::new(&PathBuf::from(String::deserialize(deserializer)?))
+            .map_err(D::Error::custom)
+    }
+}
+
+impl AccountKeystore for EncryptedFileBasedKeystore {
+    fn sign_hashed(
+        &self,
+        address: &IotaAddress,
+        msg: &[u8],
+    ) -> Result<Signature, signature::Error> {
+        Ok(Signature::new_hashed(
+            msg,
+            self.get_key(address)
+                .map_err(|e| signature::Error::from_source(e.to_string()))?,
+        ))
+    }
+    fn sign_secure<T>(
+        &self,
+        address: &IotaAddress,
+        msg: &T,
+        intent: Intent,
+    ) -> Result<Signature, signature::Error>
+    where
+        T: Serialize,
+    {
+        Ok(Signature::new_secure(
+            &IntentMessage::new(intent, msg),
+            self.get_key(address)
+                .map_err(|e| signature::Error::from_source(e.to_string()))?,
+        ))
+    }
+
+    fn add_key(
+        &mut self,
+        alias: Option<String>,
+        keypair: IotaKeyPair,
+    ) -> Result<(), anyhow::Error> {
+        let address: IotaAddress = (&keypair.public()).into();
+        let alias = self.create_alias(alias)?;
+        let public_key_base64 = keypair.public().encode_base64();
+        self.unlocked_mut()?.keys.insert(address, keypair);
+        self.aliases.insert(address, Alias {
+            alias,
+            public_key_base64,
+        });
+        self.save()?;
+        Ok(())
+    }
+
+    fn aliases(&self) -> Vec<&Alias> {
+        self.aliases.values().collect()
+    }
+
+    fn addresses_with_alias(&self) -> Vec<(&IotaAddress, &Alias)> {
+        self.aliases.iter().collect::<Vec<_>>()
+    }
+
+    fn aliases_mut(&mut self) -> Vec<&mut Alias> {
+        self.aliases.values_mut().collect()
+    }
+
+    /// Public keys are read from the aliases file, so they are available
+    /// even if the keystore is locked.
+    fn keys(&self) -> Vec<PublicKey> {
+        self.aliases
+            .values()
+            .filter_map(|alias| PublicKey::decode_base64(&alias.public_key_base64).ok())
+            .collect()
+    }
+
+    fn create_alias(&self, alias: Option<String>) -> Result<String, anyhow::Error> {
+        match alias {
+            Some(a) if self.alias_exists(&a) => {
+                bail!("Alias {a} already exists. Please choose another alias.")
+            }
+            Some(a) => validate_alias(&a),
+            None => Ok(random_name(
+                &self
+                    .alias_names()
+                    .into_iter()
+                    .map(|x| x.to_string())
+                    .collect::<HashSet<_>>(),
+            )),
+        }
+    }
+
+    fn get_address_by_alias(&self, alias: String) -> Result<&IotaAddress, anyhow::Error> {
+        self.addresses_with_alias()
+            .iter()
+            .find(|x| x.1.alias == alias)
+            .ok_or_else(|| anyhow!("Cannot resolve alias {alias} to an address"))
+            .map(|x| x.0)
+    }
+
+    fn get_alias_by_address(&self, address: &IotaAddress) -> Result<String, anyhow::Error> {
+        match self.aliases.get(address) {
+            Some(alias) => Ok(alias.alias.clone()),
+            None => bail!("Cannot find alias for address {address}"),
+        }
+    }
+
+    fn get_key(&self, address: &IotaAddress) -> Result<&IotaKeyPair, anyhow::Error> {
+        match self.ensure_unlocked()?.keys.get(address) {
+            Some(key) => Ok(key),
+            None => Err(anyhow!("Cannot find key for address: [{address}]")),
+        }
+    }
+
+    fn update_alias(
+        &mut self,
+        old_alias: &str,
+        new_alias: Option<&str>,
+    ) -> Result<String, anyhow::Error> {
+        let new_alias_name = self.update_alias_value(old_alias, new_alias)?;
+        write_aliases(&self.path, &self.aliases)?;
+        Ok(new_alias_name)
+    }
+}
+
+impl EncryptedFileBasedKeystore {
+    /// Opens the encrypted keystore at `path` in locked state. If the file
+    /// does not exist, the keystore is empty and must be initialized with
+    /// [`EncryptedFileBasedKeystore::set_passphrase`] before keys can be
+    /// added.
+    pub fn new(path: &PathBuf) -> Result<Self, anyhow::Error> {
+        let file = if path.exists() {
+            let reader =
+                BufReader::new(File::open(path).with_context(|| {
+                    format!("Cannot open the keystore file: {}", path.display())
+                })?);
+            Some(
+                serde_json::from_reader::<_, EncryptedKeystoreFile>(reader).with_context(|| {
+                    format!(
+                        "Cannot deserialize the encrypted keystore file: {}",
+                        path.display()
+                    )
+                })?,
+            )
+        } else {
+            None
+        };
+        let aliases = read_aliases(path)?.unwrap_or_default();
+        Ok(Self {
+            aliases,
+            path: path.to_path_buf(),
+            file,
+            unlocked: OnceCell::new(),
+            passphrase_provider: None,
+        })
+    }
+
+    /// Sets the provider of the passphrase used to unlock the keystore when a
+    /// private key is first needed and there is no session to unlock it with.
+    pub fn set_passphrase_provider(&mut self, provider: PassphraseProvider) {
+        self.passphrase_provider = Some(provider);
+    }
+
+    /// Encrypts the plaintext [`FileBasedKeystore`] at `path` in place with a
+    /// key derived from `passphrase`. The returned keystore is unlocked.
+    pub fn migrate(
+        path: &PathBuf,
+        passphrase: &str,
+        kdf: KdfParams,
+    ) -> Result<Self, anyhow::Error> {
+        ensure!(
+            !is_encrypted_keystore(path),
+            "Keystore {} is already encrypted",
+            path.display()
+        );
+        ensure!(!passphrase.is_empty(), "The passphrase must not be empty");
+        let plaintext = FileBasedKeystore::new(path)?;
+        let mut keystore = Self {
+            aliases: plaintext.aliases,
+            path: path.to_path_buf(),
+            file: None,
+            unlocked: OnceCell::with_value(UnlockedKeys {
+                key: KeystoreKey::generate(passphrase, kdf)?,
+                keys: plaintext.keys,
+            }),
+            passphrase_provider: None,
+        };
+        keystore.end_session()?;
+        keystore.save()?;
+        Ok(keystore)
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.unlocked.get().is_none()
+    }
+
+    /// Decrypts the keystore with a key derived from `passphrase`.
+    pub fn unlock(&mut self, passphrase: &str) -> Result<(), anyhow::Error> {
+        let key = KeystoreKey::derive_for(passphrase, self.existing_file()?)?;
+        self.unlock_with_key(key)
+    }
+
+    /// Decrypts the keystore with a previously derived key.
+    pub fn unlock_with_key(&mut self, key: KeystoreKey) -> Result<(), anyhow::Error> {
+        self.unlocked = OnceCell::with_value(self.decrypt(key)?);
+        Ok(())
+    }
+
+    /// Tries to unlock the keystore with the key cached by
+    /// [`EncryptedFileBasedKeystore::start_session`]. Returns false if there
+    /// is no unexpired session.
+    pub fn unlock_with_session(&mut self) -> Result<bool, anyhow::Error> {
+        match self.session_key()? {
+            Some(key) => {
+                self.unlock_with_key(key)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Reads the key cached by a session, discarding the session if it is no
+    /// longer valid.
+    fn session_key(&self) -> Result<Option<KeystoreKey>, anyhow::Error> {
+        let session_path = self.session_path();
+        if !session_path.exists() {
+            return Ok(None);
+        }
+        let session: KeystoreSession = serde_json::from_slice(&fs::read(&session_path)?)
+            .with_context(|| {
+                format!(
+                    "Cannot deserialize keystore session: {}",
+                    session_path.display()
+                )
+            })?;
+        let key = KeystoreKey::decode_base64(&session.key, session.kdf)?;
+        // sessions that expired, were started before the passphrase was
+        // changed or are readable by other users are discarded
+        let is_valid = session.expires_at_secs > unix_timestamp_secs()
+            && self.file.as_ref().is_some_and(|file| key.matches(file))
+            && is_private_file(&session_path)?;
+        if !is_valid {
+            fs::remove_file(&session_path)?;
+            return Ok(None);
+        }
+        Ok(Some(key))
+    }
+
+    /// Caches the derived key of the unlocked keystore next to the keystore
+    /// file, readable only by the current user, so that subsequent commands
+    /// can unlock it without the passphrase until `ttl` elapses.
+    pub fn start_session(&self, ttl: Duration) -> Result<(), anyhow::Error> {
+        let key = &self.ensure_unlocked()?.key;
+        let session = KeystoreSession {
+            key: key.encode_base64(),
+            kdf: self.file.as_ref().map(|f| f.kdf).unwrap_or_default(),
+            expires_at_secs: unix_timestamp_secs() + ttl.as_secs(),
+        };
+        write_private_file(&self.session_path(), &serde_json::to_vec(&session)?)
+    }
+
+    /// Removes a cached session key, if any.
+    pub fn end_session(&self) -> Result<(), anyhow::Error> {
+        let session_path = self.session_path();
+        if session_path.exists() {
+            fs::remove_file(session_path)?;
+        }
+        Ok(())
+    }
+
+    /// Re-encrypts the keystore with a key derived from `passphrase`, which
+    /// must be non-empty and differ from the current one. The keystore must be
+    /// unlocked, unless it does not exist yet. Any cached session is
+    /// invalidated.
+    pub fn set_passphrase(
+        &mut self,
+        passphrase: &str,
+        kdf: KdfParams,
+    ) -> Result<(), anyhow::Error> {
+        ensure!(!passphrase.is_empty(), "The passphrase must not be empty");
+        if let Some(file) = &self.file {
+            let decrypts = KeystoreKey::derive_for(passphrase, file)
+                .and_then(|key| key.decrypt(file))
+                .is_ok();
+            ensure!(
+                !decrypts,
+                "The new passphrase must differ from the current one"
+            );
+        }
+        let key = KeystoreKey::generate(passphrase, kdf)?;
+        let keys = if self.file.is_some() {
+            std::mem::take(&mut self.unlocked_mut()?.keys)
+        } else {
+            BTreeMap::new()
+        };
+        self.unlocked = OnceCell::with_value(UnlockedKeys { key, keys });
+        self.end_session()?;
+        self.save()
+    }
+
+    /// Drops the decrypted keys from memory and removes the cached session
+    /// key, if any.
+    pub fn lock(&mut self) -> Result<(), anyhow::Error> {
+        self.unlocked.take();
+        self.end_session()
+    }
+
+    pub fn set_path(&mut self, path: &Path) {
+        self.path = path.to_path_buf();
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn save(&mut self) -> Result<(), anyhow::Error> {
+        let unlocked = self.ensure_unlocked()?;
+        let kp_strings = serde_json::to_vec(
+            &unlocked
+                .keys
+                .values()
+                .map(|k| k.encode())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| anyhow!(e))?,
+        )?;
+        let file = unlocked.key.encrypt(&kp_strings)?;
+        let store = serde_json::to_string_pretty(&file).with_context(|| {
+            format!("Cannot serialize keystore to file: {}", self.path.display())
+        })?;
+        write_private_file(&self.path, store.as_bytes())?;
+        self.file = Some(file);
+        write_aliases(&self.path, &self.aliases)
+    }
+
+    pub fn key_pairs(&self) -> Result<Vec<&IotaKeyPair>, anyhow::Error> {
+        Ok(self.ensure_unlocked()?.keys.values().collect())
+    }
+
+    /// Returns the decrypted keys, unlocking the keystore first if needed.
+    fn ensure_unlocked(&self) -> Result<&UnlockedKeys, anyhow::Error> {
+        self.unlocked.get_or_try_init(|| {
+            let key = match (self.session_key()?, &self.passphrase_provider) {
+                (Some(key), _) => key,
+                (None, Some(provider)) => {
+                    KeystoreKey::derive_for(&provider()?, self.existing_file()?)?
+                }
+                (None, None) => bail!(
+                    "Keystore {} is locked. Unlock it with `iota keytool unlock`",
+                    self.path.display()
+                ),
+            };
+            self.decrypt(key)
+        })
+    }
+
+    fn unlocked_mut(&mut self) -> Result<&mut UnlockedKeys, anyhow::Error> {
+        self.ensure_unlocked()?;
+        Ok(self
+            .unlocked
+            .get_mut()
+            .expect("keystore was unlocked above"))
+    }
+
+    fn decrypt(&self, key: KeystoreKey) -> Result<UnlockedKeys, anyhow::Error> {
+        let file = self.existing_file()?;
+        ensure!(key.matches(file), "Key was not derived for this keystore");
+        let plaintext = key.decrypt(file)?;
+        let kp_strings: Vec<String> = serde_json::from_slice(&plaintext).with_context(|| {
+            format!(
+                "Cannot deserialize the decrypted keystore: {}",
+                self.path.display()
+            )
+        })?;
+        let keys = kp_strings
+            .iter()
+            .map(|kpstr| {
+                let key = IotaKeyPair::decode(kpstr);
+                key.map(|k| (IotaAddress::from(&k.public()), k))
+            })
+            .collect::<Result<BTreeMap<_, _>, _>>()
+            .map_err(|e| anyhow!("Invalid keystore file: {}. {}", self.path.display(), e))?;
+        Ok(UnlockedKeys { key, keys })
+    }
+
+    fn existing_file(&self) -> Result<&EncryptedKeystoreFile, anyhow::Error> {
+        self.file
+            .as_ref()
+            .ok_or_else(|| anyhow!("Keystore {} does not exist", self.path.display()))
+    }
+
+    fn session_path(&self) -> PathBuf {
+        let mut session_path = self.path.clone();
+        session_path.set_extension("session");
+        session_path
+    }
+}
+
+/// Returns true if the keystore file at `path` exists and is encrypted.
+pub fn is_encrypted_keystore(path: &Path) -> bool {
+    fs::read(path)
+        .ok()
+        .and_then(|content| serde_json::from_slice::<EncryptedKeystoreFile>(&content).ok())
+        .is_some()
+}
+
+fn read_aliases(
+    keystore_path: &Path,
+) -> Result<Option<BTreeMap<IotaAddress, Alias>>, anyhow::Error> {
+    let mut aliases_path = keystore_path.to_path_buf();
+    aliases_path.set_extension("aliases");
+    if !aliases_path.exists() {
+        return Ok(None);
+    }
+    let reader = BufReader::new(File::open(&aliases_path).with_context(|| {
+        format!(
+            "Cannot open aliases file in keystore: {}",
+            aliases_path.display()
+        )
+    })?);
+    let aliases: Vec<Alias> = serde_json::from_reader(reader).with_context(|| {
+        format!(
+            "Cannot deserialize aliases file in keystore: {}",
+            aliases_path.display(),
+        )
+    })?;
+    aliases
+        .into_iter()
+        .map(|alias| {
+            let key = PublicKey::decode_base64(&alias.public_key_base64);
+            key.map(|k| (Into::<IotaAddress>::into(&k), alias))
+        })
+        .collect::<Result<BTreeMap<_, _>, _>>()
+        .map(Some)
+        .map_err(|e| {
+            anyhow!(
+                "Invalid aliases file in keystore: {}. {}",
+                aliases_path.display(),
+                e
+            )
+        })
+}
+
+fn write_aliases(
+    keystore_path: &Path,
+    aliases: &BTreeMap<IotaAddress, Alias>,
+) -> Result<(), anyhow::Error> {
+    let aliases_store = serde_json::to_string_pretty(&aliases.values().collect::<Vec<_>>())
+        .with_context(|| {
+            format!(
+                "Cannot serialize aliases to file in keystore: {}",
+                keystore_path.display()
+            )
+        })?;
+    let mut aliases_path = keystore_path.to_path_buf();
+    aliases_path.set_extension("aliases");
+    write_private_file(&aliases_path, aliases_store.as_bytes())
+}
+
+/// Writes `content` to `path` through a temporary file, so that the file is
+/// replaced atomically. The file is created readable only by the current
+/// user, so the content is never exposed to others, not even briefly.
+fn write_private_file(path: &Path, content: &[u8]) -> Result<(), anyhow::Error> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+    // A file left behind by an interrupted write may have other permissions.
+    if tmp_path.exists() {
+        fs::remove_file(&tmp_path)?;
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&tmp_path)?;
+    file.write_all(content)?;
+    file.sync_all()?;
+    fs::rename(tmp_path, path)?;
+    Ok(())
+}
+
+/// Returns false if the file at `path` is accessible by users other than its
+/// owner.
+fn is_private_file(path: &Path) -> Result<bool, anyhow::Error> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        Ok(fs::metadata(path)?.permissions().mode() & 0o077 == 0)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        Ok(true)
+    }
+}
+
+fn unix_timestamp_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
 #[derive(Default, Serialize, Deserialize)]
 pub struct InMemKeystore {
     aliases: BTreeMap<IotaAddress, Alias>,
@@ -2,6 +2,7 @@
 // Modifications Copyright (c) 2024 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod encryption;
 pub mod key_derive;
 pub mod keypair_file;
 pub mod keystore;
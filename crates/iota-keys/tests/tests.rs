@@ -2,17 +2,32 @@
 // Modifications Copyright (c) 2024 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{fs, str::FromStr};
+use std::{
+    fs,
+    str::FromStr,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
 
 use fastcrypto::hash::HashFunction;
 use iota_keys::{
+    encryption::KdfParams,
     key_derive::generate_new_key,
-    keystore::{AccountKeystore, FileBasedKeystore, InMemKeystore, Keystore},
+    keystore::{
+        AccountKeystore, EncryptedFileBasedKeystore, FileBasedKeystore, InMemKeystore, Keystore,
+        is_encrypted_keystore,
+    },
 };
 use iota_types::{
     base_types::{IOTA_ADDRESS_LENGTH, IotaAddress},
-    crypto::{DefaultHash, Ed25519IotaSignature, IotaSignatureInner, SignatureScheme},
+    crypto::{
+        DefaultHash, Ed25519IotaSignature, EncodeDecodeBase64, IotaSignatureInner, SignatureScheme,
+    },
 };
+use shared_crypto::intent::Intent;
 use tempfile::TempDir;
 
 #[test]
@@ -267,3 +282,218 @@ fn get_alias_by_address_test() {
     let address = generate_new_key(SignatureScheme::ED25519, None, None).unwrap();
     assert!(keystore.get_alias_by_address(&address.0).is_err())
 }
+
+#[test]
+fn encrypted_keystore_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("iota.keystore");
+    let mut keystore = EncryptedFileBasedKeystore::new(&keystore_path).unwrap();
+    keystore
+        .set_passphrase("passphrase", KdfParams::insecure_for_tests())
+        .unwrap();
+    let mut keystore = Keystore::from(keystore);
+    let (address, _, _) = keystore
+        .generate_and_add_new_key(SignatureScheme::ED25519, None, None, None)
+        .unwrap();
+    assert!(is_encrypted_keystore(&keystore_path));
+    // private keys are not stored in plaintext
+    let content = fs::read_to_string(&keystore_path).unwrap();
+    let exported = keystore.get_key(&address).unwrap().encode_base64();
+    assert!(!content.contains(&exported));
+
+    // a reopened keystore is locked, but addresses can still be listed
+    let mut reopened = EncryptedFileBasedKeystore::new(&keystore_path).unwrap();
+    assert!(reopened.is_locked());
+    assert_eq!(reopened.addresses(), vec![address]);
+    assert!(reopened.get_key(&address).is_err());
+    assert!(
+        reopened
+            .sign_secure(&address, &"message", Intent::iota_transaction())
+            .is_err()
+    );
+
+    assert!(reopened.unlock("wrong passphrase").is_err());
+    assert!(reopened.is_locked());
+    reopened.unlock("passphrase").unwrap();
+    assert_eq!(
+        reopened.get_key(&address).unwrap().encode_base64(),
+        exported
+    );
+    reopened
+        .sign_secure(&address, &"message", Intent::iota_transaction())
+        .unwrap();
+}
+
+#[test]
+fn migrate_to_encrypted_keystore_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("iota.keystore");
+    let mut keystore = Keystore::from(FileBasedKeystore::new(&keystore_path).unwrap());
+    let (address, _, _) = keystore
+        .generate_and_add_new_key(
+            SignatureScheme::ED25519,
+            Some("my_alias".to_string()),
+            None,
+            None,
+        )
+        .unwrap();
+    let exported = keystore.get_key(&address).unwrap().encode_base64();
+
+    EncryptedFileBasedKeystore::migrate(
+        &keystore_path,
+        "passphrase",
+        KdfParams::insecure_for_tests(),
+    )
+    .unwrap();
+    assert!(is_encrypted_keystore(&keystore_path));
+    assert!(FileBasedKeystore::new(&keystore_path).is_err());
+    assert!(
+        EncryptedFileBasedKeystore::migrate(
+            &keystore_path,
+            "passphrase",
+            KdfParams::insecure_for_tests()
+        )
+        .is_err()
+    );
+
+    let mut migrated = EncryptedFileBasedKeystore::new(&keystore_path).unwrap();
+    assert_eq!(migrated.get_alias_by_address(&address).unwrap(), "my_alias");
+    migrated.unlock("passphrase").unwrap();
+    assert_eq!(
+        migrated.get_key(&address).unwrap().encode_base64(),
+        exported
+    );
+}
+
+#[test]
+fn encrypted_keystore_session_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("iota.keystore");
+    let mut keystore = EncryptedFileBasedKeystore::new(&keystore_path).unwrap();
+    keystore
+        .set_passphrase("passphrase", KdfParams::insecure_for_tests())
+        .unwrap();
+    keystore
+        .generate_and_add_new_key(SignatureScheme::ED25519, None, None, None)
+        .unwrap();
+
+    let mut reopened = EncryptedFileBasedKeystore::new(&keystore_path).unwrap();
+    assert!(!reopened.unlock_with_session().unwrap());
+
+    keystore.start_session(Duration::from_secs(60)).unwrap();
+    let mut reopened = EncryptedFileBasedKeystore::new(&keystore_path).unwrap();
+    assert!(reopened.unlock_with_session().unwrap());
+    assert!(!reopened.is_locked());
+
+    // changing the passphrase invalidates the session
+    keystore
+        .set_passphrase("new passphrase", KdfParams::insecure_for_tests())
+        .unwrap();
+    let mut reopened = EncryptedFileBasedKeystore::new(&keystore_path).unwrap();
+    assert!(!reopened.unlock_with_session().unwrap());
+    reopened.unlock("new passphrase").unwrap();
+
+    // locking removes the session
+    reopened.start_session(Duration::from_secs(60)).unwrap();
+    reopened.lock().unwrap();
+    assert!(reopened.is_locked());
+    assert!(!keystore_path.with_extension("session").exists());
+
+    // expired sessions are ignored and removed
+    reopened.unlock("new passphrase").unwrap();
+    reopened.start_session(Duration::ZERO).unwrap();
+    let mut reopened = EncryptedFileBasedKeystore::new(&keystore_path).unwrap();
+    assert!(!reopened.unlock_with_session().unwrap());
+    assert!(!keystore_path.with_extension("session").exists());
+}
+
+#[test]
+fn encrypted_keystore_rejects_empty_or_unchanged_passphrase_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("iota.keystore");
+    let mut keystore = EncryptedFileBasedKeystore::new(&keystore_path).unwrap();
+    assert!(
+        keystore
+            .set_passphrase("", KdfParams::insecure_for_tests())
+            .is_err()
+    );
+    keystore
+        .set_passphrase("passphrase", KdfParams::insecure_for_tests())
+        .unwrap();
+
+    assert!(
+        keystore
+            .set_passphrase("passphrase", KdfParams::insecure_for_tests())
+            .is_err()
+    );
+    assert!(
+        keystore
+            .set_passphrase("", KdfParams::insecure_for_tests())
+            .is_err()
+    );
+    let mut reopened = EncryptedFileBasedKeystore::new(&keystore_path).unwrap();
+    reopened.unlock("passphrase").unwrap();
+}
+
+#[test]
+fn encrypted_keystore_lazy_unlock_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("iota.keystore");
+    let mut keystore = EncryptedFileBasedKeystore::new(&keystore_path).unwrap();
+    keystore
+        .set_passphrase("passphrase", KdfParams::insecure_for_tests())
+        .unwrap();
+    let (address, _, _) = keystore
+        .generate_and_add_new_key(SignatureScheme::ED25519, None, None, None)
+        .unwrap();
+
+    let prompts = Arc::new(AtomicUsize::new(0));
+    let mut reopened = EncryptedFileBasedKeystore::new(&keystore_path).unwrap();
+    reopened.set_passphrase_provider(Box::new({
+        let prompts = prompts.clone();
+        move || {
+            prompts.fetch_add(1, Ordering::SeqCst);
+            Ok("passphrase".to_string())
+        }
+    }));
+
+    // reading public data does not unlock the keystore
+    assert_eq!(reopened.addresses(), vec![address]);
+    assert!(reopened.is_locked());
+    assert_eq!(prompts.load(Ordering::SeqCst), 0);
+
+    // the passphrase is asked for once, when a private key is first needed
+    reopened.get_key(&address).unwrap();
+    reopened.get_key(&address).unwrap();
+    assert!(!reopened.is_locked());
+    assert_eq!(prompts.load(Ordering::SeqCst), 1);
+
+    // a session takes precedence over the passphrase provider
+    reopened.start_session(Duration::from_secs(60)).unwrap();
+    let mut reopened = EncryptedFileBasedKeystore::new(&keystore_path).unwrap();
+    reopened.set_passphrase_provider(Box::new(|| Err(anyhow::anyhow!("no prompt"))));
+    reopened.get_key(&address).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn encrypted_keystore_files_are_private_test() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("iota.keystore");
+    let mut keystore = EncryptedFileBasedKeystore::new(&keystore_path).unwrap();
+    keystore
+        .set_passphrase("passphrase", KdfParams::insecure_for_tests())
+        .unwrap();
+    keystore.start_session(Duration::from_secs(60)).unwrap();
+
+    for path in [
+        keystore_path.clone(),
+        keystore_path.with_extension("aliases"),
+        keystore_path.with_extension("session"),
+    ] {
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600, "{}", path.display());
+    }
+}
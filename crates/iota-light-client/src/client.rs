@@ -0,0 +1,237 @@
+// Copyright (c) 2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, ensure};
+use iota_config::genesis::Genesis;
+use iota_rest_api::{CheckpointData, CheckpointTransaction, Client};
+use iota_types::{
+    base_types::ObjectID,
+    committee::{Committee, EpochId},
+    digests::TransactionDigest,
+    effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents},
+    messages_checkpoint::{CertifiedCheckpointSummary, CheckpointSequenceNumber, EndOfEpochData},
+    object::Object,
+};
+
+use crate::{
+    construct::construct_proof,
+    proof::{Proof, ProofTarget, TransactionProof, verify_proof},
+};
+
+/// A light client that follows the chain from a trusted committee by verifying
+/// end-of-epoch checkpoints, and uses the committees it learns this way to
+/// verify transactions and objects served by a full node.
+///
+/// Unlike the file based flow in [`crate::utils`], all state is kept in memory
+/// so the client can be embedded in other services. Verified end-of-epoch
+/// checkpoints can be persisted by the caller and fed back with
+/// [`LightClient::verify_end_of_epoch_checkpoint`] on restart.
+pub struct LightClient {
+    client: Client,
+    /// Trusted committees by epoch, starting with the root of trust.
+    committees: BTreeMap<EpochId, Committee>,
+    /// Verified end-of-epoch checkpoints by the epoch they close.
+    end_of_epoch_checkpoints: BTreeMap<EpochId, CertifiedCheckpointSummary>,
+}
+
+impl LightClient {
+    /// Creates a light client that trusts `committee` and fetches data from
+    /// the REST API of the full node at `rest_url`.
+    pub fn new(rest_url: impl AsRef<str>, committee: Committee) -> Self {
+        Self {
+            client: Client::new(rest_url),
+            committees: BTreeMap::from([(committee.epoch, committee)]),
+            end_of_epoch_checkpoints: BTreeMap::new(),
+        }
+    }
+
+    /// Creates a light client that trusts the genesis committee.
+    pub fn from_genesis(rest_url: impl AsRef<str>, genesis: &Genesis) -> anyhow::Result<Self> {
+        Ok(Self::new(rest_url, genesis.committee()?))
+    }
+
+    /// Returns the committee of the latest epoch the client has verified.
+    pub fn committee(&self) -> &Committee {
+        self.committees
+            .last_key_value()
+            .map(|(_, committee)| committee)
+            .expect("light client always has a trusted committee")
+    }
+
+    /// Returns the latest epoch the client has verified the committee of.
+    pub fn epoch(&self) -> EpochId {
+        self.committee().epoch
+    }
+
+    /// Returns the verified committee of `epoch`, if known.
+    pub fn committee_for_epoch(&self, epoch: EpochId) -> Option<&Committee> {
+        self.committees.get(&epoch)
+    }
+
+    /// Returns the verified end-of-epoch checkpoints in epoch order.
+    pub fn end_of_epoch_checkpoints(&self) -> impl Iterator<Item = &CertifiedCheckpointSummary> {
+        self.end_of_epoch_checkpoints.values()
+    }
+
+    /// Verifies the end-of-epoch checkpoint of the latest known epoch against
+    /// its committee and rolls the trusted committee forward to the next
+    /// epoch. Returns the new committee.
+    pub fn verify_end_of_epoch_checkpoint(
+        &mut self,
+        summary: CertifiedCheckpointSummary,
+    ) -> anyhow::Result<&Committee> {
+        let epoch = self.epoch();
+        ensure!(
+            summary.epoch() == epoch,
+            "Expected end-of-epoch checkpoint of epoch {epoch}, got epoch {}",
+            summary.epoch()
+        );
+        summary.verify_with_contents(self.committee(), None)?;
+        let next_committee = next_epoch_committee(&summary)?;
+        self.end_of_epoch_checkpoints.insert(epoch, summary);
+        self.committees
+            .insert(next_committee.epoch, next_committee.clone());
+        Ok(self.committee())
+    }
+
+    /// Downloads and verifies all end-of-epoch checkpoints between the latest
+    /// known epoch and the latest checkpoint of the full node. Returns the
+    /// epoch the client is synced to.
+    pub async fn sync(&mut self) -> anyhow::Result<EpochId> {
+        let latest = self.client.get_latest_checkpoint().await?;
+        while self.epoch() < latest.epoch() {
+            let start = self
+                .end_of_epoch_checkpoints
+                .values()
+                .last()
+                .map_or(0, |summary| summary.sequence_number + 1);
+            let summary = self
+                .find_end_of_epoch_checkpoint(self.epoch(), start, latest.sequence_number)
+                .await?;
+            self.verify_end_of_epoch_checkpoint(summary)?;
+        }
+        Ok(self.epoch())
+    }
+
+    /// Verifies a proof against the committee of the epoch of its checkpoint.
+    pub fn verify_proof(&self, proof: &Proof) -> anyhow::Result<()> {
+        let epoch = proof.checkpoint_summary.epoch();
+        let committee = self
+            .committee_for_epoch(epoch)
+            .ok_or_else(|| anyhow!("No verified committee for epoch {epoch}, sync first"))?;
+        verify_proof(committee, proof)
+    }
+
+    /// Downloads the checkpoint that includes the transaction and returns its
+    /// effects and events once their inclusion is verified.
+    pub async fn get_verified_transaction(
+        &self,
+        digest: TransactionDigest,
+    ) -> anyhow::Result<(TransactionEffects, Option<TransactionEvents>)> {
+        let proof = self.get_transaction_proof(digest).await?;
+        self.verify_proof(&proof)?;
+        let contents_proof = proof
+            .contents_proof
+            .expect("transaction proof has contents");
+        Ok((contents_proof.effects, contents_proof.events))
+    }
+
+    /// Constructs a proof that the transaction is included in a checkpoint.
+    /// The proof is not verified.
+    pub async fn get_transaction_proof(&self, digest: TransactionDigest) -> anyhow::Result<Proof> {
+        let data = self.get_transaction_checkpoint(digest).await?;
+        let CheckpointTransaction {
+            transaction,
+            effects,
+            events,
+            ..
+        } = data
+            .transactions
+            .iter()
+            .find(|tx| tx.effects.transaction_digest() == &digest)
+            .ok_or_else(|| anyhow!("Transaction {digest} not found in checkpoint data"))?
+            .clone();
+        Ok(Proof {
+            targets: ProofTarget::new(),
+            checkpoint_summary: data.checkpoint_summary,
+            contents_proof: Some(TransactionProof {
+                checkpoint_contents: data.checkpoint_contents,
+                transaction,
+                effects,
+                events,
+            }),
+        })
+    }
+
+    /// Downloads the latest version of an object and returns it once the
+    /// transaction that wrote it is verified to have produced it.
+    pub async fn get_verified_object(&self, object_id: ObjectID) -> anyhow::Result<Object> {
+        let object = self.client.get_object(object_id).await?;
+        let data = self
+            .get_transaction_checkpoint(object.previous_transaction)
+            .await?;
+        let proof = construct_proof(
+            ProofTarget::new().add_object(object.compute_object_reference(), object.clone()),
+            &data,
+        )?;
+        self.verify_proof(&proof)?;
+        Ok(object)
+    }
+
+    async fn get_transaction_checkpoint(
+        &self,
+        digest: TransactionDigest,
+    ) -> anyhow::Result<CheckpointData> {
+        let seq = self
+            .client
+            .get_transaction_checkpoint(&digest)
+            .await?
+            .ok_or_else(|| anyhow!("Transaction {digest} is not checkpointed yet"))?;
+        Ok(self.client.get_full_checkpoint(seq).await?)
+    }
+
+    /// Binary searches the last checkpoint of `epoch` between the checkpoints
+    /// `start` and `end`.
+    async fn find_end_of_epoch_checkpoint(
+        &self,
+        epoch: EpochId,
+        mut start: CheckpointSequenceNumber,
+        mut end: CheckpointSequenceNumber,
+    ) -> anyhow::Result<CertifiedCheckpointSummary> {
+        while start < end {
+            let mid = (start + end) / 2;
+            let summary = self.client.get_checkpoint_summary(mid).await?;
+            if summary.epoch() == epoch && summary.end_of_epoch_data.is_some() {
+                return Ok(summary);
+            }
+            if summary.epoch() <= epoch {
+                start = mid + 1;
+            } else {
+                end = mid;
+            }
+        }
+        Err(anyhow!(
+            "End-of-epoch checkpoint of epoch {epoch} not found"
+        ))
+    }
+}
+
+/// Extracts the committee of the next epoch from an end-of-epoch checkpoint.
+pub fn next_epoch_committee(summary: &CertifiedCheckpointSummary) -> anyhow::Result<Committee> {
+    let Some(EndOfEpochData {
+        next_epoch_committee,
+        ..
+    }) = &summary.end_of_epoch_data
+    else {
+        return Err(anyhow!(
+            "Checkpoint {} is not an end-of-epoch checkpoint",
+            summary.sequence_number
+        ));
+    };
+    Ok(Committee::new(
+        summary.epoch().checked_add(1).unwrap(),
+        next_epoch_committee.iter().cloned().collect(),
+    ))
+}
@@ -2,10 +2,13 @@
 // Modifications Copyright (c) 2024 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod client;
 pub mod construct;
 pub mod proof;
 pub mod utils;
 
+#[doc(inline)]
+pub use client::*;
 #[doc(inline)]
 pub use construct::*;
 #[doc(inline)]
@@ -10,6 +10,7 @@ use std::{
 
 use anyhow::anyhow;
 use iota_light_client::{
+    client::LightClient,
     construct::construct_proof,
     proof::{Proof, ProofTarget, verify_proof},
     utils::{CheckpointsList, read_checkpoint_list},
@@ -301,3 +302,53 @@ async fn test_event_target_fail_bad_event() {
 
     assert!(verify_proof(&committee, &event_proof).is_err());
 }
+
+#[tokio::test]
+async fn test_light_client_rolls_committee_forward() {
+    let (committee, full_checkpoint) = read_test_data().await;
+    let epoch = committee.epoch;
+
+    let mut light_client = LightClient::new("http://localhost:9000", committee);
+    let next_committee = light_client
+        .verify_end_of_epoch_checkpoint(full_checkpoint.checkpoint_summary.clone())
+        .unwrap()
+        .clone();
+    assert_eq!(next_committee.epoch, epoch + 1);
+    assert_eq!(light_client.epoch(), epoch + 1);
+    assert_eq!(light_client.end_of_epoch_checkpoints().count(), 1);
+
+    // Proofs of checkpoints in the previous epoch are verified against its
+    // committee
+    let sample_object: Object = full_checkpoint.transactions[0].output_objects[0].clone();
+    let target =
+        ProofTarget::new().add_object(sample_object.compute_object_reference(), sample_object);
+    let object_proof = construct_proof(target, &full_checkpoint).unwrap();
+    light_client.verify_proof(&object_proof).unwrap();
+
+    // The same checkpoint can not be used to roll forward again
+    assert!(
+        light_client
+            .verify_end_of_epoch_checkpoint(full_checkpoint.checkpoint_summary)
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_light_client_rejects_unknown_epoch() {
+    let (committee, full_checkpoint) = read_test_data().await;
+
+    // A client trusting the next committee can not verify the checkpoint
+    let light_client = LightClient::new(
+        "http://localhost:9000",
+        Committee::new(
+            committee.epoch + 1,
+            committee.voting_rights.iter().cloned().collect(),
+        ),
+    );
+    let proof = Proof {
+        checkpoint_summary: full_checkpoint.checkpoint_summary,
+        contents_proof: None,
+        targets: ProofTarget::new(),
+    };
+    assert!(light_client.verify_proof(&proof).is_err());
+}
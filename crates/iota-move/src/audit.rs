@@ -0,0 +1,841 @@
+// Copyright (c) 2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::{BTreeMap, BTreeSet, btree_map::Entry},
+    fmt, fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context, bail};
+use async_trait::async_trait;
+use clap::Parser;
+use colored::Colorize;
+use iota_move_build::{BuildConfig, CompiledPackage, set_iota_flavor};
+use iota_package_resolver::{
+    Package, PackageStore, PackageStoreWithLruCache, Result as ResolverResult,
+    error::Error as ResolverError,
+};
+use iota_sdk::{
+    IotaClient,
+    rpc_types::{
+        IotaObjectDataOptions, IotaRawData, IotaTransactionBlockResponseOptions, ObjectChange,
+    },
+};
+use iota_source_validation::{
+    BytecodeSourceVerifier, ValidationMode, error::Error as SourceVerificationError,
+};
+use iota_types::{
+    base_types::{MoveObjectType, ObjectID, SequenceNumber},
+    error::IotaObjectResponseError,
+    is_system_package,
+    move_package::{UpgradeCap, UpgradePolicy},
+    object::Object,
+};
+use move_cli::base;
+use move_core_types::account_address::AccountAddress;
+use move_package::BuildConfig as MoveBuildConfig;
+use serde::Serialize;
+
+use crate::manage_package::resolve_lock_file_path;
+
+/// Audit the on-chain dependencies of a package: report dependencies that
+/// have been upgraded since, their upgrade policies, dependencies that are
+/// denied, and whether their sources match their on-chain bytecode. Exits
+/// with a non-zero code if a denied dependency is found or a source doesn't
+/// match.
+#[derive(Parser)]
+#[group(id = "iota-move-audit")]
+pub struct Audit {
+    /// The ID of the published package to audit. Defaults to the
+    /// `published-at` address of the package, or to auditing its
+    /// dependencies only if it is not published.
+    #[clap(long = "package-id", value_parser = ObjectID::from_hex_literal)]
+    pub package_id: Option<ObjectID>,
+    /// A file of package IDs that must not be depended on, one per line.
+    /// Blank lines and lines starting with `#` are ignored.
+    #[clap(long = "deny-list")]
+    pub deny_list: Option<PathBuf>,
+    /// Also exit with a non-zero code if a dependency has a newer version.
+    #[clap(long = "fail-on-outdated")]
+    pub fail_on_outdated: bool,
+    /// Print the report as JSON.
+    #[clap(long)]
+    pub json: bool,
+}
+
+impl Audit {
+    /// Audits the package at `path` against the network `client` is connected
+    /// to and prints the report. Returns whether the audit passed.
+    pub async fn execute(
+        &self,
+        path: Option<&Path>,
+        mut build_config: MoveBuildConfig,
+        client: IotaClient,
+    ) -> anyhow::Result<bool> {
+        if let Some(err_msg) = set_iota_flavor(&mut build_config) {
+            bail!(err_msg);
+        }
+        let deny_list = match &self.deny_list {
+            Some(path) => read_deny_list(path)?,
+            None => BTreeSet::new(),
+        };
+
+        let rerooted_path = base::reroot_path(path)?;
+        let build_config = resolve_lock_file_path(build_config, Some(&rerooted_path))?;
+        let chain_id = client.read_api().get_chain_identifier().await.ok();
+        let compiled_package = BuildConfig {
+            config: build_config,
+            run_bytecode_verifier: true,
+            print_diags_to_stderr: true,
+            chain_id,
+        }
+        .build(&rerooted_path)?;
+
+        let (root, mode) = match (self.package_id, &compiled_package.published_at) {
+            (Some(id), _) => (Some(id), ValidationMode::root_and_deps_at(id.into())),
+            (None, Ok(id)) => (Some(*id), ValidationMode::root_and_deps()),
+            (None, Err(_)) => (None, ValidationMode::deps()),
+        };
+
+        let mut report = Auditor::new(client)
+            .audit(&compiled_package, root, mode, &deny_list)
+            .await?;
+        report.passed = report.passes(self.fail_on_outdated);
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            print!("{report}");
+        }
+        Ok(report.passed)
+    }
+}
+
+/// Reads a deny list of package IDs, one per line.
+fn read_deny_list(path: &Path) -> anyhow::Result<BTreeSet<ObjectID>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Cannot read deny list {}", path.display()))?;
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            ObjectID::from_hex_literal(line)
+                .with_context(|| format!("Invalid package ID in deny list: {line}"))
+        })
+        .collect()
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AuditReport {
+    /// The audited package, if it is published.
+    package: Option<PackageReport>,
+    /// The packages the audited package is linked against, transitively.
+    dependencies: Vec<PackageReport>,
+    /// Source verification errors that can't be attributed to a package.
+    source_verification_errors: Vec<String>,
+    /// Whether the audit passed, i.e. whether the command exits with zero.
+    passed: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PackageReport {
+    /// The ID of the first version of the package.
+    original_id: ObjectID,
+    /// The ID of the version that is linked against.
+    linked_id: ObjectID,
+    linked_version: u64,
+    /// The ID of the latest version of the package, if it is known.
+    latest_id: Option<ObjectID>,
+    latest_version: Option<u64>,
+    upgrade_policy: UpgradePolicyStatus,
+    denied: bool,
+    source_verification: SourceVerification,
+}
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum UpgradePolicyStatus {
+    /// System packages are upgraded by the protocol.
+    System,
+    Compatible,
+    Additive,
+    DepOnly,
+    /// The upgrade cap of the package no longer exists, because the package
+    /// was made immutable or because the cap was wrapped into another object.
+    Unavailable,
+    /// The upgrade cap of the package could not be found.
+    Unknown,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum SourceVerification {
+    Verified,
+    Failed { errors: Vec<String> },
+}
+
+impl AuditReport {
+    fn packages(&self) -> impl Iterator<Item = &PackageReport> {
+        self.package.iter().chain(&self.dependencies)
+    }
+
+    fn passes(&self, fail_on_outdated: bool) -> bool {
+        self.source_verification_errors.is_empty()
+            && self.packages().all(|package| {
+                !package.denied
+                    && matches!(package.source_verification, SourceVerification::Verified)
+                    && !(fail_on_outdated && package.is_outdated())
+            })
+    }
+}
+
+impl PackageReport {
+    fn is_outdated(&self) -> bool {
+        self.latest_id.is_some_and(|id| id != self.linked_id)
+    }
+}
+
+impl fmt::Display for AuditReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(package) = &self.package {
+            writeln!(f, "{}", "Package".bold())?;
+            write!(f, "{package}")?;
+        }
+        writeln!(f, "{}", "Dependencies".bold())?;
+        if self.dependencies.is_empty() {
+            writeln!(f, "  none")?;
+        }
+        for package in &self.dependencies {
+            write!(f, "{package}")?;
+        }
+        if !self.source_verification_errors.is_empty() {
+            writeln!(f, "{}", "Source verification errors".bold())?;
+            for error in &self.source_verification_errors {
+                writeln!(f, "  - {error}")?;
+            }
+        }
+        if self.passed {
+            writeln!(f, "{}", "Audit passed".green().bold())
+        } else {
+            writeln!(f, "{}", "Audit failed".red().bold())
+        }
+    }
+}
+
+impl fmt::Display for PackageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "  {} v{}", self.original_id, self.linked_version)?;
+        if self.linked_id != self.original_id {
+            write!(f, " at {}", self.linked_id)?;
+        }
+        match (self.latest_id, self.latest_version) {
+            (Some(id), Some(version)) if self.is_outdated() => write!(
+                f,
+                ", {}",
+                format!("v{version} available at {id}").as_str().yellow()
+            )?,
+            (Some(_), _) => write!(f, ", up to date")?,
+            _ => write!(f, ", latest version unknown")?,
+        }
+        write!(f, ", upgrade policy: {}", self.upgrade_policy)?;
+        match &self.source_verification {
+            SourceVerification::Verified => write!(f, ", {}", "source verified".green())?,
+            SourceVerification::Failed { .. } => write!(f, ", {}", "source mismatch".red())?,
+        }
+        if self.denied {
+            write!(f, ", {}", "DENIED".red().bold())?;
+        }
+        writeln!(f)?;
+        if let SourceVerification::Failed { errors } = &self.source_verification {
+            for error in errors {
+                writeln!(f, "    - {error}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for UpgradePolicyStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::System => "system",
+            Self::Compatible => "compatible",
+            Self::Additive => "additive",
+            Self::DepOnly => "dependency only",
+            Self::Unavailable => "immutable or cap wrapped",
+            Self::Unknown => "unknown",
+        })
+    }
+}
+
+/// Fetches packages for the package resolver over JSON-RPC.
+struct RpcPackageStore {
+    client: IotaClient,
+}
+
+#[async_trait]
+impl PackageStore for RpcPackageStore {
+    async fn fetch(&self, id: AccountAddress) -> ResolverResult<Arc<Package>> {
+        let object =
+            fetch_object(&self.client, id.into())
+                .await
+                .map_err(|e| ResolverError::Store {
+                    store: "RPC",
+                    source: Arc::from(Box::<dyn std::error::Error + Send + Sync>::from(e)),
+                })?;
+        Ok(Arc::new(Package::read_from_object(&object)?))
+    }
+}
+
+async fn fetch_object(client: &IotaClient, id: ObjectID) -> anyhow::Result<Object> {
+    client
+        .read_api()
+        .get_object_with_options(id, IotaObjectDataOptions::bcs_lossless())
+        .await?
+        .into_object()?
+        .try_into()
+}
+
+struct Auditor {
+    client: IotaClient,
+    store: PackageStoreWithLruCache<RpcPackageStore>,
+}
+
+impl Auditor {
+    fn new(client: IotaClient) -> Self {
+        let store = PackageStoreWithLruCache::new(RpcPackageStore {
+            client: client.clone(),
+        });
+        Self { client, store }
+    }
+
+    async fn audit(
+        &self,
+        compiled_package: &CompiledPackage,
+        root: Option<ObjectID>,
+        mode: ValidationMode,
+        deny_list: &BTreeSet<ObjectID>,
+    ) -> anyhow::Result<AuditReport> {
+        let root = match root {
+            Some(id) => Some(self.store.fetch(id.into()).await?),
+            None => None,
+        };
+        // The linkage table of a published package is what it runs against.
+        // An unpublished package would be linked against its dependencies and
+        // theirs.
+        let direct_dependencies: Vec<AccountAddress> = match &root {
+            Some(root) => root.linkage().values().copied().collect(),
+            None => compiled_package
+                .dependency_ids
+                .published
+                .values()
+                .map(|id| (*id).into())
+                .collect(),
+        };
+        let dependencies = resolve_linkage(&self.store, &direct_dependencies).await?;
+
+        let mut verification_errors = match BytecodeSourceVerifier::new(self.client.read_api())
+            .verify(compiled_package, mode)
+            .await
+        {
+            Ok(()) => vec![],
+            Err(e) => e
+                .errors()
+                .iter()
+                .map(|error| (error_address(error), error.to_string()))
+                .collect(),
+        };
+
+        let mut report = AuditReport {
+            package: None,
+            dependencies: vec![],
+            source_verification_errors: vec![],
+            passed: false,
+        };
+        if let Some(root) = &root {
+            report.package = Some(
+                self.package_report(root, deny_list, &mut verification_errors)
+                    .await?,
+            );
+        }
+        for package in dependencies.values() {
+            report.dependencies.push(
+                self.package_report(package, deny_list, &mut verification_errors)
+                    .await?,
+            );
+        }
+        report.source_verification_errors = verification_errors
+            .into_iter()
+            .map(|(_, error)| error)
+            .collect();
+        Ok(report)
+    }
+
+    /// Reports on `package`, taking the source verification errors about it
+    /// out of `verification_errors`.
+    async fn package_report(
+        &self,
+        package: &Package,
+        deny_list: &BTreeSet<ObjectID>,
+        verification_errors: &mut Vec<(Option<AccountAddress>, String)>,
+    ) -> anyhow::Result<PackageReport> {
+        let original_id = ObjectID::from(package.runtime_id());
+        let linked_id = ObjectID::from(package.storage_id());
+        let (upgrade_policy, latest) = if is_system_package(original_id) {
+            // System packages are upgraded in place, so the one fetched is
+            // always the latest.
+            (
+                UpgradePolicyStatus::System,
+                Some((linked_id, package.version())),
+            )
+        } else {
+            match self.upgrades(original_id).await? {
+                // Without its cap the package can't be upgraded anymore, so
+                // the linked version is taken to be the latest.
+                (UpgradePolicyStatus::Unavailable, None) => (
+                    UpgradePolicyStatus::Unavailable,
+                    Some((linked_id, package.version())),
+                ),
+                upgrades => upgrades,
+            }
+        };
+        let errors = take_package_errors(verification_errors, package);
+
+        Ok(PackageReport {
+            original_id,
+            linked_id,
+            linked_version: package.version().value(),
+            latest_id: latest.map(|(id, _)| id),
+            latest_version: latest.map(|(_, version)| version.value()),
+            upgrade_policy,
+            denied: deny_list.contains(&original_id) || deny_list.contains(&linked_id),
+            source_verification: if errors.is_empty() {
+                SourceVerification::Verified
+            } else {
+                SourceVerification::Failed { errors }
+            },
+        })
+    }
+
+    /// Looks up the upgrade policy and the latest version of the package
+    /// first published at `original_id` through its upgrade cap, which was
+    /// created alongside it.
+    async fn upgrades(
+        &self,
+        original_id: ObjectID,
+    ) -> anyhow::Result<(UpgradePolicyStatus, Option<(ObjectID, SequenceNumber)>)> {
+        let read_api = self.client.read_api();
+        let original = read_api
+            .get_object_with_options(
+                original_id,
+                IotaObjectDataOptions::new().with_previous_transaction(),
+            )
+            .await?
+            .into_object()?;
+        let Some(digest) = original.previous_transaction else {
+            bail!("Missing publish transaction of package {original_id}");
+        };
+        let publish = read_api
+            .get_transaction_with_options(
+                digest,
+                IotaTransactionBlockResponseOptions::new().with_object_changes(),
+            )
+            .await?;
+
+        let mut caps = vec![];
+        for change in publish.object_changes.unwrap_or_default() {
+            let ObjectChange::Created {
+                object_type,
+                object_id,
+                ..
+            } = change
+            else {
+                continue;
+            };
+            if !MoveObjectType::from(object_type).is_upgrade_cap() {
+                continue;
+            }
+            let cap = match read_api
+                .get_object_with_options(object_id, IotaObjectDataOptions::new().with_bcs())
+                .await?
+                .into_object()
+            {
+                Ok(cap) => cap,
+                Err(IotaObjectResponseError::Deleted { .. }) => {
+                    caps.push(None);
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+            let Some(IotaRawData::MoveObject(cap)) = cap.bcs else {
+                bail!("Upgrade cap {object_id} is not a Move object");
+            };
+            caps.push(Some(bcs::from_bytes(&cap.bcs_bytes)?));
+        }
+        match_upgrade_cap(&self.store, original_id, &caps).await
+    }
+}
+
+/// Resolves the packages `packages` are linked against, and the packages
+/// themselves, keyed by runtime ID. Linkage tables are transitive, so this
+/// covers the whole dependency graph. When different versions of a package
+/// are linked against, the latest one is kept, as publishing would.
+async fn resolve_linkage(
+    store: &impl PackageStore,
+    packages: &[AccountAddress],
+) -> anyhow::Result<BTreeMap<AccountAddress, Arc<Package>>> {
+    let mut linked = BTreeMap::new();
+    for id in packages {
+        let package = store.fetch(*id).await?;
+        for dependency in package.linkage().values() {
+            keep_latest(&mut linked, store.fetch(*dependency).await?);
+        }
+        keep_latest(&mut linked, package);
+    }
+    Ok(linked)
+}
+
+/// Finds the cap of the package first published at `original_id` among the
+/// caps created by its publish transaction, where `None` is a cap that no
+/// longer exists, and returns its upgrade policy and the latest version of the
+/// package. Several packages may have been published together, each with its
+/// own cap, so the caps are matched by the package they upgrade.
+async fn match_upgrade_cap(
+    store: &impl PackageStore,
+    original_id: ObjectID,
+    caps: &[Option<UpgradeCap>],
+) -> anyhow::Result<(UpgradePolicyStatus, Option<(ObjectID, SequenceNumber)>)> {
+    for cap in caps.iter().flatten() {
+        let latest = store.fetch(cap.package.bytes.into()).await?;
+        if ObjectID::from(latest.runtime_id()) != original_id {
+            continue;
+        }
+        let policy = match UpgradePolicy::try_from(cap.policy) {
+            Ok(UpgradePolicy::Compatible) => UpgradePolicyStatus::Compatible,
+            Ok(UpgradePolicy::Additive) => UpgradePolicyStatus::Additive,
+            Ok(UpgradePolicy::DepOnly) => UpgradePolicyStatus::DepOnly,
+            Err(()) => UpgradePolicyStatus::Unknown,
+        };
+        return Ok((policy, Some((latest.storage_id().into(), latest.version()))));
+    }
+
+    if caps.iter().any(Option::is_none) {
+        Ok((UpgradePolicyStatus::Unavailable, None))
+    } else {
+        Ok((UpgradePolicyStatus::Unknown, None))
+    }
+}
+
+fn keep_latest(linked: &mut BTreeMap<AccountAddress, Arc<Package>>, package: Arc<Package>) {
+    match linked.entry(package.runtime_id()) {
+        Entry::Vacant(entry) => {
+            entry.insert(package);
+        }
+        Entry::Occupied(mut entry) => {
+            if entry.get().version() < package.version() {
+                entry.insert(package);
+            }
+        }
+    }
+}
+
+/// Takes the source verification errors about `package` out of
+/// `verification_errors`.
+fn take_package_errors(
+    verification_errors: &mut Vec<(Option<AccountAddress>, String)>,
+    package: &Package,
+) -> Vec<String> {
+    let (errors, rest): (Vec<_>, Vec<_>) = std::mem::take(verification_errors)
+        .into_iter()
+        .partition(|(address, _)| {
+            address.is_some_and(|a| a == package.runtime_id() || a == package.storage_id())
+        });
+    *verification_errors = rest;
+    errors.into_iter().map(|(_, error)| error).collect()
+}
+
+/// The address of the package a source verification error is about, if any.
+fn error_address(error: &SourceVerificationError) -> Option<AccountAddress> {
+    match error {
+        SourceVerificationError::EmptyOnChainPackage(address)
+        | SourceVerificationError::LocalDependencyNotFound { address, .. }
+        | SourceVerificationError::MissingDependencyInLinkageTable(address)
+        | SourceVerificationError::MissingDependencyInSourcePackage(address)
+        | SourceVerificationError::ModuleBytecodeMismatch { address, .. }
+        | SourceVerificationError::OnChainDependencyDeserializationError { address, .. } => {
+            Some(*address)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iota_types::move_package::{MovePackage, UpgradeInfo};
+    use move_binary_format::file_format::empty_module;
+
+    use super::*;
+
+    /// Packages keyed by the ID they are stored at.
+    struct TestStore(BTreeMap<AccountAddress, Arc<Package>>);
+
+    #[async_trait]
+    impl PackageStore for TestStore {
+        async fn fetch(&self, id: AccountAddress) -> ResolverResult<Arc<Package>> {
+            self.0
+                .get(&id)
+                .cloned()
+                .ok_or(ResolverError::PackageNotFound(id))
+        }
+    }
+
+    fn store(packages: &[&Arc<Package>]) -> TestStore {
+        TestStore(
+            packages
+                .iter()
+                .map(|package| (package.storage_id(), Arc::clone(package)))
+                .collect(),
+        )
+    }
+
+    fn address(id: u8) -> AccountAddress {
+        ObjectID::from_single_byte(id).into()
+    }
+
+    /// A package with a single empty module, first published at `runtime`
+    /// and stored at `storage`, linked against the `(runtime, storage)`
+    /// packages in `linkage`.
+    fn move_package(runtime: u8, storage: u8, version: u64, linkage: &[(u8, u8)]) -> Arc<Package> {
+        let mut module = empty_module();
+        module.address_identifiers[0] = address(runtime);
+        let mut bytes = vec![];
+        module
+            .serialize_with_version(module.version, &mut bytes)
+            .unwrap();
+        let linkage = linkage
+            .iter()
+            .map(|(runtime, storage)| {
+                (
+                    ObjectID::from_single_byte(*runtime),
+                    UpgradeInfo {
+                        upgraded_id: ObjectID::from_single_byte(*storage),
+                        upgraded_version: SequenceNumber::from_u64(1),
+                    },
+                )
+            })
+            .collect();
+        let package = MovePackage::new(
+            ObjectID::from_single_byte(storage),
+            SequenceNumber::from_u64(version),
+            BTreeMap::from([("m".to_string(), bytes)]),
+            u64::MAX,
+            vec![],
+            linkage,
+        )
+        .unwrap();
+        Arc::new(Package::read_from_package(&package).unwrap())
+    }
+
+    fn upgrade_cap(package: u8, policy: UpgradePolicy) -> UpgradeCap {
+        let mut cap = UpgradeCap::new(ObjectID::random(), ObjectID::from_single_byte(package));
+        cap.policy = policy as u8;
+        cap
+    }
+
+    fn package(id: u8, latest: Option<u8>) -> PackageReport {
+        PackageReport {
+            original_id: ObjectID::from_single_byte(id),
+            linked_id: ObjectID::from_single_byte(id),
+            linked_version: 1,
+            latest_id: latest.map(ObjectID::from_single_byte),
+            latest_version: latest.map(|_| 2),
+            upgrade_policy: UpgradePolicyStatus::Compatible,
+            denied: false,
+            source_verification: SourceVerification::Verified,
+        }
+    }
+
+    fn report(dependencies: Vec<PackageReport>) -> AuditReport {
+        AuditReport {
+            package: None,
+            dependencies,
+            source_verification_errors: vec![],
+            passed: false,
+        }
+    }
+
+    #[test]
+    fn test_read_deny_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("deny-list");
+        fs::write(&path, "# denied packages\n\n  0x2a  \n0x0b\n").unwrap();
+        assert_eq!(
+            read_deny_list(&path).unwrap(),
+            BTreeSet::from([ObjectID::from_single_byte(0x2a), ObjectID::from_single_byte(0x0b)])
+        );
+
+        fs::write(&path, "0x2a\nnot-an-id\n").unwrap();
+        assert!(read_deny_list(&path).is_err());
+    }
+
+    #[test]
+    fn test_audit_verdict() {
+        // Outdated dependencies only fail the audit when asked to.
+        let outdated = report(vec![package(1, Some(1)), package(2, Some(3))]);
+        assert!(outdated.passes(false));
+        assert!(!outdated.passes(true));
+
+        // A dependency whose latest version is unknown isn't outdated.
+        assert!(report(vec![package(1, None)]).passes(true));
+
+        let mut denied = package(1, Some(1));
+        denied.denied = true;
+        assert!(!report(vec![denied]).passes(false));
+
+        let mut mismatch = package(1, Some(1));
+        mismatch.source_verification = SourceVerification::Failed {
+            errors: vec!["module mismatch".to_string()],
+        };
+        assert!(!report(vec![mismatch]).passes(false));
+
+        let mut unattributed = report(vec![package(1, Some(1))]);
+        unattributed
+            .source_verification_errors
+            .push("missing dependency".to_string());
+        assert!(!unattributed.passes(false));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_linkage() {
+        let a_v1 = move_package(0x1, 0x1, 1, &[]);
+        let a_v2 = move_package(0x1, 0x11, 2, &[]);
+        let b = move_package(0x2, 0x2, 1, &[(0x1, 0x1)]);
+        let c = move_package(0x3, 0x3, 1, &[(0x1, 0x11), (0x2, 0x2)]);
+        let store = store(&[&a_v1, &a_v2, &b, &c]);
+
+        // Dependencies of dependencies are linked as well.
+        let linked = resolve_linkage(&store, &[address(0x2)]).await.unwrap();
+        let linked: Vec<_> = linked
+            .iter()
+            .map(|(runtime_id, package)| (*runtime_id, package.storage_id()))
+            .collect();
+        assert_eq!(
+            linked,
+            vec![(address(0x1), address(0x1)), (address(0x2), address(0x2))]
+        );
+
+        // The latest of the versions linked against is kept, whichever
+        // package links against it.
+        for packages in [[address(0x2), address(0x3)], [address(0x3), address(0x2)]] {
+            let linked = resolve_linkage(&store, &packages).await.unwrap();
+            let linked: Vec<_> = linked
+                .iter()
+                .map(|(runtime_id, package)| (*runtime_id, package.storage_id()))
+                .collect();
+            assert_eq!(
+                linked,
+                vec![
+                    (address(0x1), address(0x11)),
+                    (address(0x2), address(0x2)),
+                    (address(0x3), address(0x3)),
+                ]
+            );
+        }
+
+        assert!(resolve_linkage(&store, &[address(0x4)]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_match_upgrade_cap() {
+        // Packages 0x1 and 0x2 were published together, and 0x1 was upgraded
+        // since.
+        let a_v2 = move_package(0x1, 0x11, 2, &[]);
+        let b = move_package(0x2, 0x2, 1, &[]);
+        let store = store(&[&a_v2, &b]);
+        let caps = [
+            Some(upgrade_cap(0x2, UpgradePolicy::Compatible)),
+            Some(upgrade_cap(0x11, UpgradePolicy::Additive)),
+        ];
+
+        let (policy, latest) = match_upgrade_cap(&store, ObjectID::from_single_byte(0x1), &caps)
+            .await
+            .unwrap();
+        assert!(matches!(policy, UpgradePolicyStatus::Additive));
+        assert_eq!(
+            latest,
+            Some((
+                ObjectID::from_single_byte(0x11),
+                SequenceNumber::from_u64(2)
+            ))
+        );
+
+        let (policy, latest) = match_upgrade_cap(&store, ObjectID::from_single_byte(0x2), &caps)
+            .await
+            .unwrap();
+        assert!(matches!(policy, UpgradePolicyStatus::Compatible));
+        assert_eq!(
+            latest,
+            Some((ObjectID::from_single_byte(0x2), SequenceNumber::from_u64(1)))
+        );
+
+        // The cap of 0x1 was deleted, the one left belongs to 0x2.
+        let caps = [None, Some(upgrade_cap(0x2, UpgradePolicy::DepOnly))];
+        let (policy, latest) = match_upgrade_cap(&store, ObjectID::from_single_byte(0x1), &caps)
+            .await
+            .unwrap();
+        assert!(matches!(policy, UpgradePolicyStatus::Unavailable));
+        assert_eq!(latest, None);
+
+        let caps = [Some(upgrade_cap(0x2, UpgradePolicy::DepOnly))];
+        let (policy, latest) = match_upgrade_cap(&store, ObjectID::from_single_byte(0x1), &caps)
+            .await
+            .unwrap();
+        assert!(matches!(policy, UpgradePolicyStatus::Unknown));
+        assert_eq!(latest, None);
+    }
+
+    #[test]
+    fn test_error_attribution() {
+        let a_v2 = move_package(0x1, 0x11, 2, &[]);
+        let b = move_package(0x2, 0x2, 1, &[]);
+        let mut errors: Vec<_> = [
+            SourceVerificationError::ModuleBytecodeMismatch {
+                address: address(0x11),
+                package: "a".into(),
+                module: "m".into(),
+            },
+            SourceVerificationError::MissingDependencyInSourcePackage(address(0x1)),
+            SourceVerificationError::LocalDependencyNotFound {
+                address: address(0x2),
+                module: "m".into(),
+            },
+            SourceVerificationError::ZeroOnChainAddressSpecifiedFailure,
+        ]
+        .iter()
+        .map(|error| (error_address(error), error.to_string()))
+        .collect();
+
+        // Errors are attributed by runtime and by storage ID.
+        let a_errors = take_package_errors(&mut errors, &a_v2);
+        assert_eq!(a_errors.len(), 2);
+        assert!(a_errors[0].contains("did not match"), "{}", a_errors[0]);
+        assert!(
+            a_errors[1].contains("not a source dependency"),
+            "{}",
+            a_errors[1]
+        );
+
+        let b_errors = take_package_errors(&mut errors, &b);
+        assert_eq!(b_errors.len(), 1);
+        assert!(b_errors[0].contains("was not found"), "{}", b_errors[0]);
+
+        // What can't be attributed is left over.
+        assert!(take_package_errors(&mut errors, &b).is_empty());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, None);
+    }
+}
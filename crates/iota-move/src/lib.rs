@@ -10,6 +10,7 @@ use iota_move_build::set_iota_flavor;
 use move_cli::base::test::UnitTestResult;
 use move_package::BuildConfig;
 
+pub mod audit;
 #[cfg(feature = "build")]
 pub mod build;
 #[cfg(feature = "coverage")]
@@ -24,6 +25,7 @@ pub mod unit_test;
 
 #[derive(Parser)]
 pub enum Command {
+    Audit(audit::Audit),
     #[cfg(feature = "build")]
     Build(build::Build),
     #[cfg(feature = "coverage")]
@@ -53,6 +55,11 @@ pub fn execute_move_command(
         anyhow::bail!(err_msg);
     }
     match command {
+        // Auditing resolves dependencies on-chain, which needs a connection to
+        // the network that only the `iota` binary sets up.
+        Command::Audit(_) => {
+            anyhow::bail!("Auditing a package is only supported by `iota move audit`")
+        }
         #[cfg(feature = "build")]
         Command::Build(c) => c.execute(package_path, build_config),
         #[cfg(feature = "coverage")]
@@ -16,8 +16,10 @@ use axum::{
 };
 use base64::Engine;
 use humantime::parse_duration;
+use iota_config::transaction_deny_config::DenyListEntry;
+use iota_core::authority::AuthorityState;
 use iota_types::{
-    base_types::AuthorityName,
+    base_types::{AuthorityName, IotaAddress, ObjectID},
     crypto::{RandomnessPartialSignature, RandomnessRound, RandomnessSignature},
     error::IotaError,
 };
@@ -73,6 +75,19 @@ use crate::IotaNode;
 // Inject a full signature from another node, bypassing validity checks.
 //
 //  $ curl 'http://127.0.0.1:1337/randomness-inject-full-sig?round=123&sigs=base64encodedsig'
+//
+// View the transaction deny list entries currently in effect:
+//
+//   $ curl 'http://127.0.0.1:1337/deny-list'
+//
+// Deny transactions using a package (kind can be object, package or address).
+// The change is persisted and survives restarts of the node:
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/deny-list/add?kind=package&id=0x1234'
+//
+// Remove a package from the deny list, including one from the node config:
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/deny-list/remove?kind=package&id=0x1234'
 
 const LOGGING_ROUTE: &str = "/logging";
 const TRACING_ROUTE: &str = "/enable-tracing";
@@ -85,6 +100,9 @@ const NODE_CONFIG: &str = "/node-config";
 const RANDOMNESS_PARTIAL_SIGS_ROUTE: &str = "/randomness-partial-sigs";
 const RANDOMNESS_INJECT_PARTIAL_SIGS_ROUTE: &str = "/randomness-inject-partial-sigs";
 const RANDOMNESS_INJECT_FULL_SIG_ROUTE: &str = "/randomness-inject-full-sig";
+const DENY_LIST_ROUTE: &str = "/deny-list";
+const DENY_LIST_ADD_ROUTE: &str = "/deny-list/add";
+const DENY_LIST_REMOVE_ROUTE: &str = "/deny-list/remove";
 
 struct AppState {
     node: Arc<IotaNode>,
@@ -93,6 +111,7 @@ struct AppState {
 
 pub async fn run_admin_server(node: Arc<IotaNode>, port: u16, tracing_handle: TracingHandle) {
     let filter = tracing_handle.get_log().unwrap();
+    let authority_state = node.state();
 
     let app_state = AppState {
         node,
@@ -124,7 +143,8 @@ pub async fn run_admin_server(node: Arc<IotaNode>, port: u16, tracing_handle: Tr
             RANDOMNESS_INJECT_FULL_SIG_ROUTE,
             post(randomness_inject_full_sig),
         )
-        .with_state(Arc::new(app_state));
+        .with_state(Arc::new(app_state))
+        .merge(deny_list_router(authority_state));
 
     let socket_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
     info!(
@@ -442,3 +462,172 @@ async fn randomness_inject_full_sig(
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
     }
 }
+
+/// Routes to view and update the transaction deny lists of `state`.
+fn deny_list_router(state: Arc<AuthorityState>) -> Router {
+    Router::new()
+        .route(DENY_LIST_ROUTE, get(deny_list))
+        .route(DENY_LIST_ADD_ROUTE, post(deny_list_add))
+        .route(DENY_LIST_REMOVE_ROUTE, post(deny_list_remove))
+        .with_state(state)
+}
+
+async fn deny_list(State(state): State<Arc<AuthorityState>>) -> (StatusCode, String) {
+    let transaction_deny_config = state.transaction_deny_config();
+    let entries = transaction_deny_config
+        .deny_list_entries()
+        .into_iter()
+        .map(|entry| match entry {
+            DenyListEntry::Object(id) => format!("object {id}\n"),
+            DenyListEntry::Package(id) => format!("package {id}\n"),
+            DenyListEntry::Address(address) => format!("address {address}\n"),
+        })
+        .collect();
+    (StatusCode::OK, entries)
+}
+
+#[derive(Deserialize)]
+struct DenyListEntryArgs {
+    kind: String,
+    id: String,
+}
+
+impl TryFrom<DenyListEntryArgs> for DenyListEntry {
+    type Error = String;
+
+    fn try_from(args: DenyListEntryArgs) -> Result<Self, Self::Error> {
+        let parse_id =
+            |id: &str| ObjectID::from_str(id).map_err(|err| format!("invalid id {id}: {err}"));
+        match args.kind.as_str() {
+            "object" => Ok(DenyListEntry::Object(parse_id(&args.id)?)),
+            "package" => Ok(DenyListEntry::Package(parse_id(&args.id)?)),
+            "address" => IotaAddress::from_str(&args.id)
+                .map(DenyListEntry::Address)
+                .map_err(|err| format!("invalid address {}: {err}", args.id)),
+            kind => Err(format!(
+                "invalid kind {kind}, expected object, package or address"
+            )),
+        }
+    }
+}
+
+async fn deny_list_add(
+    State(state): State<Arc<AuthorityState>>,
+    args: Query<DenyListEntryArgs>,
+) -> (StatusCode, String) {
+    update_deny_list(state, args.0, true)
+}
+
+async fn deny_list_remove(
+    State(state): State<Arc<AuthorityState>>,
+    args: Query<DenyListEntryArgs>,
+) -> (StatusCode, String) {
+    update_deny_list(state, args.0, false)
+}
+
+fn update_deny_list(
+    state: Arc<AuthorityState>,
+    args: DenyListEntryArgs,
+    denied: bool,
+) -> (StatusCode, String) {
+    let entry = match DenyListEntry::try_from(args) {
+        Ok(entry) => entry,
+        Err(err) => return (StatusCode::BAD_REQUEST, format!("{err}\n")),
+    };
+    match state.update_transaction_deny_list(entry, denied) {
+        Ok(true) if denied => (StatusCode::OK, format!("{entry:?} denied\n")),
+        Ok(true) => (StatusCode::OK, format!("{entry:?} allowed\n")),
+        Ok(false) if denied => (StatusCode::OK, format!("{entry:?} already denied\n")),
+        Ok(false) => (StatusCode::OK, format!("{entry:?} not denied\n")),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{Body, to_bytes},
+        http::{Method, Request},
+    };
+    use iota_core::authority::test_authority_builder::TestAuthorityBuilder;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    async fn request(router: &Router, method: Method, uri: &str) -> (StatusCode, String) {
+        let response = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_deny_list_routes() {
+        let state = TestAuthorityBuilder::new().build().await;
+        let router = deny_list_router(state.clone());
+        let package = ObjectID::random();
+
+        let (status, body) = request(&router, Method::GET, DENY_LIST_ROUTE).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.is_empty());
+
+        let add = format!("{DENY_LIST_ADD_ROUTE}?kind=package&id={package}");
+        let (status, body) = request(&router, Method::POST, &add).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.ends_with(" denied\n"));
+        let (status, body) = request(&router, Method::POST, &add).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.ends_with(" already denied\n"));
+
+        let (status, body) = request(&router, Method::GET, DENY_LIST_ROUTE).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, format!("package {package}\n"));
+        // The update is persisted for restarts.
+        let overrides =
+            std::fs::read_to_string(state.config.transaction_deny_overrides_path()).unwrap();
+        assert!(overrides.contains(&package.to_string()));
+
+        let remove = format!("{DENY_LIST_REMOVE_ROUTE}?kind=package&id={package}");
+        let (status, body) = request(&router, Method::POST, &remove).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.ends_with(" allowed\n"));
+        let (status, body) = request(&router, Method::POST, &remove).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.ends_with(" not denied\n"));
+        let (_, body) = request(&router, Method::GET, DENY_LIST_ROUTE).await;
+        assert!(body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_deny_list_routes_reject_invalid_entries() {
+        let state = TestAuthorityBuilder::new().build().await;
+        let router = deny_list_router(state.clone());
+
+        for uri in [
+            format!("{DENY_LIST_ADD_ROUTE}?kind=coin&id=0x1"),
+            format!("{DENY_LIST_ADD_ROUTE}?kind=package&id=not-an-id"),
+            format!("{DENY_LIST_REMOVE_ROUTE}?kind=address&id=0xzz"),
+        ] {
+            let (status, _) = request(&router, Method::POST, &uri).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
+        }
+        let (status, _) = request(&router, Method::POST, DENY_LIST_ADD_ROUTE).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(
+            state
+                .transaction_deny_config()
+                .deny_list_entries()
+                .is_empty()
+        );
+    }
+}
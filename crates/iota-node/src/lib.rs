@@ -34,6 +34,7 @@ use iota_config::{
 use iota_core::{
     authority::{
         AuthorityState, AuthorityStore, CHAIN_IDENTIFIER, RandomnessRoundReceiver,
+        StableSyncAuthoritySigner,
         authority_per_epoch_store::AuthorityPerEpochStore,
         authority_store_tables::AuthorityPerpetualTables,
         epoch_start_configuration::{EpochFlag, EpochStartConfigTrait, EpochStartConfiguration},
@@ -90,6 +91,7 @@ use iota_network::{
 };
 use iota_network_stack::server::ServerBuilder;
 use iota_protocol_config::{Chain, ProtocolConfig};
+use iota_remote_signer::{LocalSocketSigner, RemoteAuthoritySigner, RemoteSigner};
 use iota_rest_api::RestMetrics;
 use iota_snapshot::uploader::StateSnapshotUploader;
 use iota_storage::{
@@ -101,7 +103,7 @@ use iota_storage::{
 use iota_types::{
     base_types::{AuthorityName, ConciseableName, EpochId},
     committee::Committee,
-    crypto::{KeypairTraits, RandomnessRound},
+    crypto::{KeypairTraits, RandomnessRound, randomness_key_scalar},
     digests::ChainIdentifier,
     error::{IotaError, IotaResult},
     execution_config_utils::to_binary_config,
@@ -109,6 +111,7 @@ use iota_types::{
         IotaSystemState, IotaSystemStateTrait,
         epoch_start_iota_system_state::{EpochStartSystemState, EpochStartSystemStateTrait},
     },
+    messages_checkpoint::CheckpointSequenceNumber,
     messages_consensus::{AuthorityCapabilitiesV1, ConsensusTransaction, check_total_jwk_size},
     quorum_driver_types::QuorumDriverEffectsQueueResult,
     supported_protocol_versions::SupportedProtocolVersions,
@@ -227,6 +230,10 @@ pub struct IotaNode {
     /// peers.
     trusted_peer_change_tx: watch::Sender<TrustedPeerChangeEvent>,
 
+    /// Watch channel notified by the checkpoint executor of every executed
+    /// checkpoint, used by REST checkpoint subscriptions.
+    executed_checkpoint_sender: watch::Sender<CheckpointSequenceNumber>,
+
     _db_checkpoint_handle: Option<tokio::sync::broadcast::Sender<()>>,
 
     #[cfg(msim)]
@@ -451,7 +458,26 @@ impl IotaNode {
             None
         };
 
-        let secret = Arc::pin(config.authority_key_pair().copy());
+        let secret: StableSyncAuthoritySigner = match &config.remote_signer {
+            Some(remote_signer_config) => {
+                config.check_remote_signer()?;
+                let signer = LocalSocketSigner::connect(&remote_signer_config.socket_path).await?;
+                let authority = signer.public_keys().await?.authority;
+                if authority != config.authority_public_key() {
+                    return Err(anyhow!(
+                        "Remote signer signs for {} instead of {}",
+                        authority.concise(),
+                        config.authority_public_key().concise()
+                    ));
+                }
+                info!(
+                    "Signing with the remote signer at {:?}",
+                    remote_signer_config.socket_path
+                );
+                Arc::pin(RemoteAuthoritySigner::new(Arc::new(signer)))
+            }
+            None => Arc::pin(config.authority_key_pair().copy()),
+        };
         let genesis_committee = genesis.committee()?;
         let committee_store = Arc::new(CommitteeStore::new(
             config.db_path().join("epochs"),
@@ -754,10 +780,17 @@ impl IotaNode {
             None
         };
 
+        let (executed_checkpoint_sender, executed_checkpoint_receiver) = watch::channel(
+            checkpoint_store
+                .get_highest_executed_checkpoint_seq_number()?
+                .unwrap_or_default(),
+        );
+
         let http_server = build_http_server(
             state.clone(),
             state_sync_store,
             &transaction_orchestrator.clone(),
+            executed_checkpoint_receiver,
             &config,
             &prometheus_registry,
             custom_rpc_runtime,
@@ -838,6 +871,7 @@ impl IotaNode {
             end_of_epoch_channel,
             connection_monitor_status,
             trusted_peer_change_tx,
+            executed_checkpoint_sender,
 
             _db_checkpoint_handle: db_checkpoint_handle,
 
@@ -954,6 +988,9 @@ impl IotaNode {
                 &config.snapshot_path(),
                 remote_store_config.clone(),
                 60,
+                config
+                    .state_snapshot_write_config
+                    .full_snapshot_interval_epochs,
                 prometheus_registry,
                 checkpoint_store,
             )?;
@@ -1192,8 +1229,14 @@ impl IotaNode {
             &registry_service.default_registry(),
             client.clone(),
         ));
-        let consensus_manager =
-            ConsensusManager::new(&config, consensus_config, registry_service, client);
+        let consensus_manager = ConsensusManager::new(
+            &config,
+            consensus_config,
+            &epoch_store.epoch_start_state().get_consensus_committee(),
+            registry_service,
+            client,
+        )
+        .await?;
 
         // This only gets started up once, not on every epoch. (Make call to remove
         // every epoch.)
@@ -1292,11 +1335,20 @@ impl IotaNode {
 
         consensus_adapter.swap_low_scoring_authorities(low_scoring_authorities.clone());
 
+        let randomness_key = match &config.remote_signer {
+            Some(remote_signer_config) => {
+                LocalSocketSigner::connect(&remote_signer_config.socket_path)
+                    .await?
+                    .randomness_key()
+                    .await?
+            }
+            None => randomness_key_scalar(config.authority_key_pair()),
+        };
         let randomness_manager = RandomnessManager::try_new(
             Arc::downgrade(&epoch_store),
             Box::new(consensus_adapter.clone()),
             randomness_handle,
-            config.authority_key_pair(),
+            randomness_key,
         )
         .await;
         if let Some(randomness_manager) = randomness_manager {
@@ -1524,7 +1576,8 @@ impl IotaNode {
                 accumulator.clone(),
                 self.config.checkpoint_executor_config.clone(),
                 checkpoint_executor_metrics.clone(),
-            );
+            )
+            .with_executed_checkpoint_sender(self.executed_checkpoint_sender.clone());
 
             let run_with_range = self.config.run_with_range;
 
@@ -1986,6 +2039,7 @@ pub async fn build_http_server(
     state: Arc<AuthorityState>,
     store: RocksDbStore,
     transaction_orchestrator: &Option<Arc<TransactionOrchestrator<NetworkAuthorityClient>>>,
+    executed_checkpoint_receiver: watch::Receiver<CheckpointSequenceNumber>,
     config: &NodeConfig,
     prometheus_registry: &Registry,
     _custom_runtime: Option<Handle>,
@@ -2059,6 +2113,7 @@ pub async fn build_http_server(
         );
 
         rest_service.with_metrics(RestMetrics::new(prometheus_registry));
+        rest_service.with_executed_checkpoints(executed_checkpoint_receiver);
 
         if let Some(transaction_orchestrator) = transaction_orchestrator {
             rest_service.with_executor(transaction_orchestrator.clone())
@@ -773,6 +773,27 @@ impl Package {
         &self.modules
     }
 
+    /// The ID this package was loaded from on-chain.
+    pub fn storage_id(&self) -> AccountAddress {
+        self.storage_id
+    }
+
+    /// The ID this package is referred to by at runtime, which is the ID of
+    /// its first version.
+    pub fn runtime_id(&self) -> AccountAddress {
+        self.runtime_id
+    }
+
+    /// The package's transitive dependencies, mapping their runtime IDs to
+    /// the storage IDs of the versions this package is linked against.
+    pub fn linkage(&self) -> &BTreeMap<AccountAddress, AccountAddress> {
+        &self.linkage
+    }
+
+    pub fn version(&self) -> SequenceNumber {
+        self.version
+    }
+
     fn data_def(&self, module_name: &str, datatype_name: &str) -> Result<DataDef> {
         let module = self.module(module_name)?;
         let Some(data_def) = module.data_def(datatype_name)? else {
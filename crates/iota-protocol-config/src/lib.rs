@@ -16,13 +16,15 @@ use tracing::{info, warn};
 
 /// The minimum and maximum protocol versions supported by this build.
 const MIN_PROTOCOL_VERSION: u64 = 1;
-pub const MAX_PROTOCOL_VERSION: u64 = 2;
+pub const MAX_PROTOCOL_VERSION: u64 = 3;
 
 // Record history of protocol version allocations here:
 //
 // Version 1: Original version.
 // Version 2: Don't redistribute slashed staking rewards, fix computation of
 // SystemEpochInfoEventV1.
+// Version 3: Enable the 512-bit hash functions, passkey signatures in multisig
+// and Schnorr signature verification over secp256k1 on devnet.
 #[derive(Copy, Clone, Debug, Hash, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ProtocolVersion(u64);
 
@@ -182,6 +184,26 @@ struct FeatureFlags {
     // This flag is used to provide the correct MoveVM configuration for clients.
     #[serde(skip_serializing_if = "is_true")]
     rethrow_serialization_type_layout_errors: bool,
+
+    // If true, transactions touching congested shared objects are priced with a
+    // congestion surcharge instead of being deferred. Requires
+    // `congestion_surcharge_bps` to be set, transactions are deferred otherwise.
+    #[serde(skip_serializing_if = "is_false")]
+    congestion_control_surcharge_pricing: bool,
+
+    // Enable the 512-bit hash functions blake2b512, sha512 and keccak512 in
+    // `iota::hash`.
+    #[serde(skip_serializing_if = "is_false")]
+    enable_hash_512: bool,
+
+    // If true, passkey signatures are accepted as members of a multisig.
+    #[serde(skip_serializing_if = "is_false")]
+    accept_passkey_in_multisig: bool,
+
+    // Enable BIP-340 Schnorr signature verification over secp256k1 in
+    // `iota::ecdsa_k1`.
+    #[serde(skip_serializing_if = "is_false")]
+    enable_secp256k1_schnorr: bool,
 }
 
 fn is_true(b: &bool) -> bool {
@@ -734,6 +756,9 @@ pub struct ProtocolConfig {
     ecdsa_k1_secp256k1_verify_sha256_msg_cost_per_byte: Option<u64>,
     ecdsa_k1_secp256k1_verify_sha256_msg_cost_per_block: Option<u64>,
 
+    // ecdsa_k1::secp256k1_schnorr_verify
+    ecdsa_k1_secp256k1_schnorr_verify_cost_base: Option<u64>,
+
     // ecdsa_r1::ecrecover
     ecdsa_r1_ecrecover_keccak256_cost_base: Option<u64>,
     ecdsa_r1_ecrecover_keccak256_msg_cost_per_byte: Option<u64>,
@@ -781,6 +806,21 @@ pub struct ProtocolConfig {
     hash_keccak256_data_cost_per_byte: Option<u64>,
     hash_keccak256_data_cost_per_block: Option<u64>,
 
+    // hash::blake2b512
+    hash_blake2b512_cost_base: Option<u64>,
+    hash_blake2b512_data_cost_per_byte: Option<u64>,
+    hash_blake2b512_data_cost_per_block: Option<u64>,
+
+    // hash::sha512
+    hash_sha512_cost_base: Option<u64>,
+    hash_sha512_data_cost_per_byte: Option<u64>,
+    hash_sha512_data_cost_per_block: Option<u64>,
+
+    // hash::keccak512
+    hash_keccak512_cost_base: Option<u64>,
+    hash_keccak512_data_cost_per_byte: Option<u64>,
+    hash_keccak512_data_cost_per_block: Option<u64>,
+
     // poseidon::poseidon_bn254
     poseidon_bn254_cost_base: Option<u64>,
     poseidon_bn254_cost_per_block: Option<u64>,
@@ -934,6 +974,14 @@ pub struct ProtocolConfig {
     /// Transactions in a commit will be deferred once their touch shared
     /// objects hit this limit.    
     max_accumulated_txn_cost_per_object_in_mysticeti_commit: Option<u64>,
+
+    /// The congestion surcharge, in basis points of the reference gas price,
+    /// required to access a shared object for each multiple of
+    /// `max_accumulated_txn_cost_per_object_in_mysticeti_commit` its
+    /// accumulated cost in a commit, plus its decayed excess cost in recent
+    /// commits, exceeds the limit by. Only used if congestion surcharge pricing
+    /// is enabled.
+    congestion_surcharge_bps: Option<u64>,
 }
 
 // feature flags
@@ -1017,6 +1065,10 @@ impl ProtocolConfig {
         self.feature_flags.per_object_congestion_control_mode
     }
 
+    pub fn congestion_control_surcharge_pricing(&self) -> bool {
+        self.feature_flags.congestion_control_surcharge_pricing
+    }
+
     pub fn consensus_choice(&self) -> ConsensusChoice {
         self.feature_flags.consensus_choice
     }
@@ -1033,6 +1085,18 @@ impl ProtocolConfig {
         self.feature_flags.passkey_auth
     }
 
+    pub fn enable_hash_512(&self) -> bool {
+        self.feature_flags.enable_hash_512
+    }
+
+    pub fn accept_passkey_in_multisig(&self) -> bool {
+        self.feature_flags.accept_passkey_in_multisig
+    }
+
+    pub fn enable_secp256k1_schnorr(&self) -> bool {
+        self.feature_flags.enable_secp256k1_schnorr
+    }
+
     pub fn max_transaction_size_bytes(&self) -> u64 {
         // Provide a default value if protocol config version is too low.
         self.consensus_max_transaction_size_bytes
@@ -1395,6 +1459,9 @@ impl ProtocolConfig {
             ecdsa_k1_secp256k1_verify_sha256_msg_cost_per_byte: Some(2),
             ecdsa_k1_secp256k1_verify_sha256_msg_cost_per_block: Some(2),
 
+            // ecdsa_k1::secp256k1_schnorr_verify
+            ecdsa_k1_secp256k1_schnorr_verify_cost_base: None,
+
             // ecdsa_r1::ecrecover
             ecdsa_r1_ecrecover_keccak256_cost_base: Some(52),
             ecdsa_r1_ecrecover_keccak256_msg_cost_per_byte: Some(2),
@@ -1440,6 +1507,18 @@ impl ProtocolConfig {
             hash_keccak256_cost_base: Some(52),
             hash_keccak256_data_cost_per_byte: Some(2),
             hash_keccak256_data_cost_per_block: Some(2),
+            // hash::blake2b512
+            hash_blake2b512_cost_base: None,
+            hash_blake2b512_data_cost_per_byte: None,
+            hash_blake2b512_data_cost_per_block: None,
+            // hash::sha512
+            hash_sha512_cost_base: None,
+            hash_sha512_data_cost_per_byte: None,
+            hash_sha512_data_cost_per_block: None,
+            // hash::keccak512
+            hash_keccak512_cost_base: None,
+            hash_keccak512_data_cost_per_byte: None,
+            hash_keccak512_data_cost_per_block: None,
 
             poseidon_bn254_cost_base: None,
             poseidon_bn254_cost_per_block: None,
@@ -1581,6 +1660,8 @@ impl ProtocolConfig {
             bridge_should_try_to_finalize_committee: None,
 
             max_accumulated_txn_cost_per_object_in_mysticeti_commit: Some(10),
+
+            congestion_surcharge_bps: None,
             // When adding a new constant, set it to None in the earliest version, like this:
             // new_constant: None,
         };
@@ -1639,6 +1720,26 @@ impl ProtocolConfig {
                 1 => unreachable!(),
                 // version 2 is a new framework version but with no config changes
                 2 => {}
+                3 => {
+                    // Devnet
+                    if chain != Chain::Mainnet && chain != Chain::Testnet {
+                        cfg.feature_flags.enable_hash_512 = true;
+                        cfg.hash_blake2b512_cost_base = Some(52);
+                        cfg.hash_blake2b512_data_cost_per_byte = Some(2);
+                        cfg.hash_blake2b512_data_cost_per_block = Some(2);
+                        cfg.hash_sha512_cost_base = Some(52);
+                        cfg.hash_sha512_data_cost_per_byte = Some(2);
+                        cfg.hash_sha512_data_cost_per_block = Some(2);
+                        cfg.hash_keccak512_cost_base = Some(52);
+                        cfg.hash_keccak512_data_cost_per_byte = Some(2);
+                        cfg.hash_keccak512_data_cost_per_block = Some(2);
+
+                        cfg.feature_flags.accept_passkey_in_multisig = true;
+
+                        cfg.feature_flags.enable_secp256k1_schnorr = true;
+                        cfg.ecdsa_k1_secp256k1_schnorr_verify_cost_base = Some(52);
+                    }
+                }
                 // Use this template when making changes:
                 //
                 //     // modify an existing constant.
@@ -1741,6 +1842,10 @@ impl ProtocolConfig {
         self.feature_flags.per_object_congestion_control_mode = val;
     }
 
+    pub fn set_congestion_control_surcharge_pricing_for_testing(&mut self, val: bool) {
+        self.feature_flags.congestion_control_surcharge_pricing = val;
+    }
+
     pub fn set_consensus_choice_for_testing(&mut self, val: ConsensusChoice) {
         self.feature_flags.consensus_choice = val;
     }
@@ -1759,6 +1864,18 @@ impl ProtocolConfig {
     pub fn set_passkey_auth_for_testing(&mut self, val: bool) {
         self.feature_flags.passkey_auth = val
     }
+
+    pub fn set_enable_hash_512_for_testing(&mut self, val: bool) {
+        self.feature_flags.enable_hash_512 = val
+    }
+
+    pub fn set_accept_passkey_in_multisig_for_testing(&mut self, val: bool) {
+        self.feature_flags.accept_passkey_in_multisig = val
+    }
+
+    pub fn set_enable_secp256k1_schnorr_for_testing(&mut self, val: bool) {
+        self.feature_flags.enable_secp256k1_schnorr = val
+    }
 }
 
 type OverrideFn = dyn Fn(ProtocolVersion, ProtocolConfig) -> ProtocolConfig + Send + Sync;
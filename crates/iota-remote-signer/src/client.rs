@@ -0,0 +1,141 @@
+// Copyright (c) 2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{io, path::PathBuf, time::Duration};
+
+use async_trait::async_trait;
+use fastcrypto::{ed25519::Ed25519Signature, groups::bls12381, serde_helpers::ToFromByteArray};
+use iota_types::crypto::AuthoritySignature;
+use parking_lot::Mutex;
+use tokio::{net::UnixStream, time::timeout};
+use tracing::warn;
+
+use crate::{
+    RemoteSigner, RemoteSignerError, SignerPublicKeys,
+    protocol::{SignRequest, SignResponse, read_message, write_message},
+};
+
+/// Number of times a request is sent before giving up on the signer process.
+/// Resending a request is safe, as the signer signs the same message again.
+const MAX_ATTEMPTS: usize = 3;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Number of idle connections kept open for later requests.
+const MAX_IDLE_CONNECTIONS: usize = 8;
+
+/// A [`RemoteSigner`] that talks to a signer process listening on a Unix
+/// socket, see [`crate::serve`].
+///
+/// Each request takes an idle connection, or opens a new one if there is
+/// none, so that concurrent requests don't wait for each other. A connection
+/// is only reused after a complete round trip, and dropped if it fails.
+pub struct LocalSocketSigner {
+    path: PathBuf,
+    idle: Mutex<Vec<UnixStream>>,
+}
+
+impl LocalSocketSigner {
+    /// Connects to the signer process, failing if it isn't listening.
+    pub async fn connect(path: impl Into<PathBuf>) -> Result<Self, RemoteSignerError> {
+        let path = path.into();
+        let stream = UnixStream::connect(&path).await?;
+        Ok(Self {
+            path,
+            idle: Mutex::new(vec![stream]),
+        })
+    }
+
+    async fn round_trip(&self, request: &SignRequest) -> Result<SignResponse, RemoteSignerError> {
+        let idle = self.idle.lock().pop();
+        let mut stream = match idle {
+            Some(stream) => stream,
+            None => UnixStream::connect(&self.path).await?,
+        };
+        let response = timeout(REQUEST_TIMEOUT, async {
+            write_message(&mut stream, request).await?;
+            read_message::<SignResponse>(&mut stream).await
+        })
+        .await
+        .map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::TimedOut,
+                "request to the remote signer timed out",
+            )
+        })??;
+        let mut idle = self.idle.lock();
+        if idle.len() < MAX_IDLE_CONNECTIONS {
+            idle.push(stream);
+        }
+        Ok(response)
+    }
+
+    async fn request(&self, request: &SignRequest) -> Result<SignResponse, RemoteSignerError> {
+        let mut attempt = 1;
+        loop {
+            match self.round_trip(request).await {
+                Ok(SignResponse::Refused(reason)) => {
+                    return Err(RemoteSignerError::Refused(reason));
+                }
+                Ok(SignResponse::ConsensusBlockAlreadySigned { block, signature }) => {
+                    return Err(RemoteSignerError::AlreadySigned { block, signature });
+                }
+                Ok(response) => return Ok(response),
+                Err(RemoteSignerError::Io(e)) if attempt < MAX_ATTEMPTS => {
+                    warn!(
+                        "Request to remote signer at {:?} failed, reconnecting (attempt {attempt}): {e}",
+                        self.path
+                    );
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+fn unexpected(response: SignResponse) -> RemoteSignerError {
+    RemoteSignerError::InvalidMessage(format!("unexpected response: {response:?}"))
+}
+
+#[async_trait]
+impl RemoteSigner for LocalSocketSigner {
+    async fn public_keys(&self) -> Result<SignerPublicKeys, RemoteSignerError> {
+        match self.request(&SignRequest::PublicKeys).await? {
+            SignResponse::PublicKeys(keys) => Ok(keys),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    async fn sign_authority_message(
+        &self,
+        message: &[u8],
+    ) -> Result<AuthoritySignature, RemoteSignerError> {
+        match self
+            .request(&SignRequest::AuthorityMessage(message.to_vec()))
+            .await?
+        {
+            SignResponse::AuthoritySignature(signature) => Ok(signature),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    async fn sign_consensus_block(
+        &self,
+        block: &[u8],
+    ) -> Result<Ed25519Signature, RemoteSignerError> {
+        match self
+            .request(&SignRequest::ConsensusBlock(block.to_vec()))
+            .await?
+        {
+            SignResponse::ConsensusBlockSignature(signature) => Ok(signature),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    async fn randomness_key(&self) -> Result<bls12381::Scalar, RemoteSignerError> {
+        match self.request(&SignRequest::RandomnessKey).await? {
+            SignResponse::RandomnessKey(bytes) => bls12381::Scalar::from_byte_array(&bytes)
+                .map_err(|e| RemoteSignerError::InvalidMessage(e.to_string())),
+            other => Err(unexpected(other)),
+        }
+    }
+}
@@ -0,0 +1,303 @@
+// Copyright (c) 2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use iota_types::committee::EpochId;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::RemoteSignerError;
+
+/// Number of signed checkpoints that are remembered. The node re-signs the
+/// checkpoints that are not certified yet when it restarts.
+const CHECKPOINT_WINDOW: usize = 1_000;
+/// Number of signed consensus blocks that are remembered.
+const CONSENSUS_BLOCK_WINDOW: usize = 100;
+
+/// The digests of the messages of a kind that were signed last, by epoch and
+/// height, i.e. checkpoint sequence number or consensus round.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct SignedWindow(BTreeMap<(EpochId, u64), [u8; 32]>);
+
+impl SignedWindow {
+    /// Records that the message with the given digest is signed at `height`,
+    /// failing if a different one was signed there. A height older than the
+    /// remembered ones is refused, as it can't be told whether it was signed.
+    fn insert(
+        &mut self,
+        kind: &str,
+        capacity: usize,
+        height: (EpochId, u64),
+        digest: [u8; 32],
+    ) -> Result<(), RemoteSignerError> {
+        let (epoch, sequence) = height;
+        if let Some(signed) = self.0.get(&height) {
+            if *signed == digest {
+                return Ok(());
+            }
+            return Err(RemoteSignerError::Refused(format!(
+                "a different {kind} was already signed at epoch {epoch} height {sequence}"
+            )));
+        }
+        if self.0.len() >= capacity {
+            if let Some((&(oldest_epoch, oldest_sequence), _)) = self.0.first_key_value() {
+                if height < (oldest_epoch, oldest_sequence) {
+                    return Err(RemoteSignerError::Refused(format!(
+                        "{kind} at epoch {epoch} height {sequence} is older than the oldest remembered one at epoch {oldest_epoch} height {oldest_sequence}"
+                    )));
+                }
+            }
+        }
+        self.0.insert(height, digest);
+        while self.0.len() > capacity {
+            self.0.pop_first();
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct GuardState {
+    checkpoints: SignedWindow,
+    consensus_blocks: SignedWindow,
+    /// The last consensus block that was signed, BCS encoded, with its epoch
+    /// and round.
+    last_consensus_block: Option<(EpochId, u64, Vec<u8>)>,
+}
+
+/// Protects a validator against double signing.
+///
+/// The guard remembers the checkpoints and consensus blocks that were signed
+/// last, and refuses to sign a different one at a height that was already
+/// signed. Signing the very same one again is allowed, e.g. when the node
+/// retries a request or re-signs pending checkpoints after a restart. Its
+/// state is persisted to disk before a signature is handed out, so that the
+/// protection holds across restarts of the signer process.
+pub struct SignGuard {
+    path: PathBuf,
+    state: Mutex<GuardState>,
+}
+
+impl SignGuard {
+    /// Opens the guard state stored at `path`, starting from scratch if the
+    /// file doesn't exist yet.
+    pub fn open(path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let path = path.into();
+        let state = if path.exists() {
+            bcs::from_bytes(&fs::read(&path)?)?
+        } else {
+            GuardState::default()
+        };
+        Ok(Self {
+            path,
+            state: Mutex::new(state),
+        })
+    }
+
+    /// Records that the checkpoint with the given sequence number and digest
+    /// is about to be signed, failing if that would be a double sign.
+    pub fn check_checkpoint(
+        &self,
+        epoch: EpochId,
+        sequence_number: u64,
+        digest: [u8; 32],
+    ) -> Result<(), RemoteSignerError> {
+        self.update(|state| {
+            state.checkpoints.insert(
+                "checkpoint",
+                CHECKPOINT_WINDOW,
+                (epoch, sequence_number),
+                digest,
+            )
+        })
+    }
+
+    /// Records that the consensus block of the given round and digest is
+    /// about to be signed, failing if that would be a double sign. The block
+    /// itself is kept, see [`SignGuard::signed_consensus_block`].
+    pub fn check_consensus_block(
+        &self,
+        epoch: EpochId,
+        round: u32,
+        digest: [u8; 32],
+        block: &[u8],
+    ) -> Result<(), RemoteSignerError> {
+        self.update(|state| {
+            state.consensus_blocks.insert(
+                "consensus block",
+                CONSENSUS_BLOCK_WINDOW,
+                (epoch, round.into()),
+                digest,
+            )?;
+            let height = (epoch, u64::from(round));
+            if state
+                .last_consensus_block
+                .as_ref()
+                .is_none_or(|(last_epoch, last_round, _)| (*last_epoch, *last_round) < height)
+            {
+                state.last_consensus_block = Some((epoch, round.into(), block.to_vec()));
+            }
+            Ok(())
+        })
+    }
+
+    /// Returns the consensus block signed at the given round, if it is the
+    /// last one that was signed. A node that crashed right after getting the
+    /// block signed proposes it again, rather than a different one.
+    pub fn signed_consensus_block(&self, epoch: EpochId, round: u32) -> Option<Vec<u8>> {
+        match &self.state.lock().last_consensus_block {
+            Some((last_epoch, last_round, block))
+                if (*last_epoch, *last_round) == (epoch, round.into()) =>
+            {
+                Some(block.clone())
+            }
+            _ => None,
+        }
+    }
+
+    fn update(
+        &self,
+        f: impl FnOnce(&mut GuardState) -> Result<(), RemoteSignerError>,
+    ) -> Result<(), RemoteSignerError> {
+        let mut state = self.state.lock();
+        let mut updated = state.clone();
+        f(&mut updated)?;
+        if updated != *state {
+            persist(&self.path, &updated)?;
+            *state = updated;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the state to a temporary file first, so that a crash never leaves
+/// a truncated state behind.
+fn persist(path: &Path, state: &GuardState) -> Result<(), RemoteSignerError> {
+    let bytes = bcs::to_bytes(state).expect("guard state serialization should not fail");
+    let tmp_path = path.with_extension("tmp");
+    let mut file = fs::File::create(&tmp_path)?;
+    file.write_all(&bytes)?;
+    file.sync_all()?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_double_sign() {
+        let dir = tempfile::tempdir().unwrap();
+        let guard = SignGuard::open(dir.path().join("guard")).unwrap();
+
+        guard.check_checkpoint(0, 1, [1; 32]).unwrap();
+        // Signing the same checkpoint again is fine.
+        guard.check_checkpoint(0, 1, [1; 32]).unwrap();
+        // A different checkpoint at the same sequence number is not.
+        assert!(matches!(
+            guard.check_checkpoint(0, 1, [2; 32]),
+            Err(RemoteSignerError::Refused(_))
+        ));
+        guard.check_checkpoint(0, 2, [2; 32]).unwrap();
+        // Going back to an older checkpoint is fine as long as it is the same.
+        guard.check_checkpoint(0, 1, [1; 32]).unwrap();
+        assert!(matches!(
+            guard.check_checkpoint(0, 1, [3; 32]),
+            Err(RemoteSignerError::Refused(_))
+        ));
+        guard.check_checkpoint(1, 3, [3; 32]).unwrap();
+    }
+
+    #[test]
+    fn test_checkpoint_resign_after_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("guard");
+
+        // The node signs checkpoints 10 and 11, then crashes before they are
+        // certified.
+        let guard = SignGuard::open(&path).unwrap();
+        guard.check_checkpoint(1, 10, [1; 32]).unwrap();
+        guard.check_checkpoint(1, 11, [2; 32]).unwrap();
+        drop(guard);
+
+        // After the restart it signs them again, starting from checkpoint 10.
+        let guard = SignGuard::open(&path).unwrap();
+        guard.check_checkpoint(1, 10, [1; 32]).unwrap();
+        assert!(matches!(
+            guard.check_checkpoint(1, 10, [3; 32]),
+            Err(RemoteSignerError::Refused(_))
+        ));
+        guard.check_checkpoint(1, 11, [2; 32]).unwrap();
+        guard.check_checkpoint(1, 12, [3; 32]).unwrap();
+    }
+
+    #[test]
+    fn test_checkpoint_older_than_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let guard = SignGuard::open(dir.path().join("guard")).unwrap();
+
+        let mut window = SignedWindow::default();
+        for sequence_number in 1..=CHECKPOINT_WINDOW as u64 + 1 {
+            window
+                .insert(
+                    "checkpoint",
+                    CHECKPOINT_WINDOW,
+                    (0, sequence_number),
+                    [1; 32],
+                )
+                .unwrap();
+        }
+        guard.state.lock().checkpoints = window;
+
+        // The first checkpoint is forgotten, so it can't be signed anymore.
+        assert!(matches!(
+            guard.check_checkpoint(0, 1, [1; 32]),
+            Err(RemoteSignerError::Refused(_))
+        ));
+        guard.check_checkpoint(0, 2, [1; 32]).unwrap();
+    }
+
+    #[test]
+    fn test_consensus_block_double_sign_across_restarts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("guard");
+
+        let guard = SignGuard::open(&path).unwrap();
+        guard
+            .check_consensus_block(1, 10, [1; 32], b"block 10")
+            .unwrap();
+        // Checkpoints and consensus blocks are tracked independently.
+        guard.check_checkpoint(1, 5, [2; 32]).unwrap();
+        drop(guard);
+
+        let guard = SignGuard::open(&path).unwrap();
+        assert!(matches!(
+            guard.check_consensus_block(1, 10, [3; 32], b"another block 10"),
+            Err(RemoteSignerError::Refused(_))
+        ));
+        assert!(matches!(
+            guard.check_checkpoint(1, 5, [3; 32]),
+            Err(RemoteSignerError::Refused(_))
+        ));
+        // The block signed before the restart is handed back.
+        assert_eq!(
+            guard.signed_consensus_block(1, 10),
+            Some(b"block 10".to_vec())
+        );
+        guard
+            .check_consensus_block(1, 10, [1; 32], b"block 10")
+            .unwrap();
+        // Rounds start over in a new epoch.
+        guard
+            .check_consensus_block(2, 1, [4; 32], b"block 1")
+            .unwrap();
+        assert_eq!(guard.signed_consensus_block(1, 10), None);
+    }
+}
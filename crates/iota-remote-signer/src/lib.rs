@@ -0,0 +1,170 @@
+// Copyright (c) 2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Signing of validator messages by a process other than the node.
+//!
+//! A validator signs transactions, effects and checkpoints with its authority
+//! key, and consensus blocks with its protocol key. [`RemoteSigner`] abstracts
+//! over where those keys live, so that operators can keep them in a separate
+//! signer process:
+//!
+//! - [`LocalSocketSigner`] is the node side, talking to the signer process over
+//!   a Unix socket. [`RemoteAuthoritySigner`] and [`RemoteBlockSigner`] plug
+//!   any [`RemoteSigner`] into the authority and into consensus in place of the
+//!   local key pairs.
+//! - [`SignerService`] is the signer process side. It holds the keys and
+//!   refuses to sign two different checkpoints at the same sequence number or
+//!   two different consensus blocks at the same round, see [`SignGuard`].
+
+mod client;
+mod guard;
+mod protocol;
+mod server;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+pub use client::LocalSocketSigner;
+use consensus_config::{BlockSigner, BlockSigningError};
+use fastcrypto::{
+    ed25519::{Ed25519PublicKey, Ed25519Signature},
+    groups::bls12381,
+};
+pub use guard::SignGuard;
+use iota_types::{
+    crypto::{AuthorityPublicKeyBytes, AuthoritySignature, AuthoritySigner},
+    error::{IotaError, IotaResult},
+};
+pub use protocol::{SignRequest, SignResponse};
+use serde::{Deserialize, Serialize};
+pub use server::{SignerService, serve};
+use tokio::runtime::Handle;
+#[cfg(not(msim))]
+use tokio::runtime::RuntimeFlavor;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RemoteSignerError {
+    #[error("failed to communicate with the remote signer: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid message: {0}")]
+    InvalidMessage(String),
+    #[error("remote signer refused to sign: {0}")]
+    Refused(String),
+    #[error("a different consensus block was already signed for the same round")]
+    AlreadySigned {
+        block: Vec<u8>,
+        signature: Ed25519Signature,
+    },
+    #[error("signing consensus blocks remotely requires the multi-threaded Tokio runtime")]
+    UnsupportedRuntime,
+}
+
+/// The public keys of the validator a signer signs for.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignerPublicKeys {
+    pub authority: AuthorityPublicKeyBytes,
+    pub protocol: Ed25519PublicKey,
+}
+
+/// Signs validator messages on behalf of the node.
+///
+/// Implementations may refuse to sign a message, in particular one that would
+/// make the validator equivocate.
+#[async_trait]
+pub trait RemoteSigner: Send + Sync {
+    async fn public_keys(&self) -> Result<SignerPublicKeys, RemoteSignerError>;
+
+    /// Signs with the authority key. `message` is the BCS encoded
+    /// `IntentMessage` followed by the epoch, as built by
+    /// `AuthoritySignature::new_secure`.
+    async fn sign_authority_message(
+        &self,
+        message: &[u8],
+    ) -> Result<AuthoritySignature, RemoteSignerError>;
+
+    /// Signs the digest of a BCS encoded consensus block with the protocol
+    /// key, as consensus does when proposing it. If a different block was
+    /// already signed for the same round, fails with
+    /// [`RemoteSignerError::AlreadySigned`].
+    async fn sign_consensus_block(
+        &self,
+        block: &[u8],
+    ) -> Result<Ed25519Signature, RemoteSignerError>;
+
+    /// Returns the scalar of the authority key, which the random beacon DKG
+    /// needs to decrypt the shares sent to the validator.
+    async fn randomness_key(&self) -> Result<bls12381::Scalar, RemoteSignerError>;
+}
+
+/// Adapts a [`RemoteSigner`] to the authority signer of the node.
+///
+/// If the remote signer can't be reached or refuses to sign, the request being
+/// served fails with [`IotaError::AuthoritySigning`], which clients retry, and
+/// the checkpoint builder retries signing the checkpoint.
+pub struct RemoteAuthoritySigner {
+    signer: Arc<dyn RemoteSigner>,
+}
+
+impl RemoteAuthoritySigner {
+    pub fn new(signer: Arc<dyn RemoteSigner>) -> Self {
+        Self { signer }
+    }
+}
+
+#[async_trait]
+impl AuthoritySigner for RemoteAuthoritySigner {
+    async fn try_sign(&self, msg: &[u8]) -> IotaResult<AuthoritySignature> {
+        self.signer
+            .sign_authority_message(msg)
+            .await
+            .map_err(|e| IotaError::AuthoritySigning {
+                error: e.to_string(),
+            })
+    }
+}
+
+/// Adapts a [`RemoteSigner`] to the block signer of consensus.
+///
+/// Consensus signs blocks synchronously from within the runtime, so the
+/// worker thread is handed over to the other tasks while waiting for the
+/// signature. This requires the multi-threaded runtime.
+pub struct RemoteBlockSigner {
+    signer: Arc<dyn RemoteSigner>,
+}
+
+impl RemoteBlockSigner {
+    /// Fails with [`RemoteSignerError::UnsupportedRuntime`] unless called
+    /// from within the multi-threaded runtime, so that a node that can't sign
+    /// blocks fails at startup rather than when proposing.
+    pub fn new(signer: Arc<dyn RemoteSigner>) -> Result<Self, RemoteSignerError> {
+        if !in_multi_threaded_runtime() {
+            return Err(RemoteSignerError::UnsupportedRuntime);
+        }
+        Ok(Self { signer })
+    }
+}
+
+#[cfg(not(msim))]
+fn in_multi_threaded_runtime() -> bool {
+    Handle::try_current().is_ok_and(|handle| handle.runtime_flavor() == RuntimeFlavor::MultiThread)
+}
+
+// The simulator runs all tasks on a single thread.
+#[cfg(msim)]
+fn in_multi_threaded_runtime() -> bool {
+    false
+}
+
+impl BlockSigner for RemoteBlockSigner {
+    fn sign_block(&self, block: &[u8]) -> Result<Ed25519Signature, BlockSigningError> {
+        tokio::task::block_in_place(|| {
+            Handle::current().block_on(self.signer.sign_consensus_block(block))
+        })
+        .map_err(|e| match e {
+            RemoteSignerError::AlreadySigned { block, signature } => {
+                BlockSigningError::AlreadySigned { block, signature }
+            }
+            e => BlockSigningError::Failed(e.to_string()),
+        })
+    }
+}
@@ -0,0 +1,57 @@
+// Copyright (c) 2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{fs, os::unix::fs::PermissionsExt, path::PathBuf, sync::Arc};
+
+use anyhow::Result;
+use clap::Parser;
+use iota_keys::keypair_file::{read_authority_keypair_from_file, read_network_keypair_from_file};
+use iota_remote_signer::{SignGuard, SignerService, serve};
+use tokio::net::UnixListener;
+use tracing::info;
+
+/// Holds the keys of a validator and signs on behalf of its node, which
+/// connects over a Unix socket.
+#[derive(Parser)]
+#[command(name = "iota-remote-signer", version)]
+struct Args {
+    /// Path of the authority key pair file.
+    #[arg(long)]
+    authority_key_path: PathBuf,
+    /// Path of the protocol key pair file.
+    #[arg(long)]
+    protocol_key_path: PathBuf,
+    /// Path of the Unix socket to listen on.
+    #[arg(long)]
+    socket_path: PathBuf,
+    /// Path of the file in which the last signed checkpoint and consensus
+    /// block are recorded to prevent double signing.
+    #[arg(long)]
+    guard_path: PathBuf,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let _guard = telemetry_subscribers::TelemetryConfig::new()
+        .with_env()
+        .init();
+
+    let args = Args::parse();
+    let service = SignerService::new(
+        read_authority_keypair_from_file(&args.authority_key_path)?,
+        read_network_keypair_from_file(&args.protocol_key_path)?,
+        SignGuard::open(&args.guard_path)?,
+    );
+
+    // A socket left behind by a previous run would make binding fail.
+    if args.socket_path.exists() {
+        fs::remove_file(&args.socket_path)?;
+    }
+    let listener = UnixListener::bind(&args.socket_path)?;
+    // Only the user running the signer (and the node) may connect, whatever the
+    // umask of the process.
+    fs::set_permissions(&args.socket_path, fs::Permissions::from_mode(0o600))?;
+    info!("Remote signer listening on {:?}", args.socket_path);
+    serve(listener, Arc::new(service)).await?;
+    Ok(())
+}
@@ -0,0 +1,74 @@
+// Copyright (c) 2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Wire format between the node and the signer process. Every message is a
+//! BCS encoded value prefixed by its length as a little endian `u32`.
+
+use fastcrypto::ed25519::Ed25519Signature;
+use iota_types::crypto::AuthoritySignature;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::{RemoteSignerError, SignerPublicKeys};
+
+/// Upper bound on the size of a message, large enough for any consensus block.
+const MAX_MESSAGE_SIZE: usize = 64 << 20;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignRequest {
+    PublicKeys,
+    AuthorityMessage(Vec<u8>),
+    ConsensusBlock(Vec<u8>),
+    RandomnessKey,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum SignResponse {
+    PublicKeys(SignerPublicKeys),
+    AuthoritySignature(AuthoritySignature),
+    ConsensusBlockSignature(Ed25519Signature),
+    /// A different consensus block was already signed for the same round.
+    /// Carries that block, BCS encoded, and its signature.
+    ConsensusBlockAlreadySigned {
+        block: Vec<u8>,
+        signature: Ed25519Signature,
+    },
+    RandomnessKey([u8; 32]),
+    Refused(String),
+}
+
+pub(crate) async fn write_message<T: Serialize + Sync>(
+    writer: &mut (impl AsyncWrite + Unpin),
+    message: &T,
+) -> Result<(), RemoteSignerError> {
+    let bytes =
+        bcs::to_bytes(message).map_err(|e| RemoteSignerError::InvalidMessage(e.to_string()))?;
+    if bytes.len() > MAX_MESSAGE_SIZE {
+        return Err(RemoteSignerError::InvalidMessage(format!(
+            "message of {} bytes exceeds the limit of {MAX_MESSAGE_SIZE} bytes",
+            bytes.len()
+        )));
+    }
+    writer
+        .write_all(&(bytes.len() as u32).to_le_bytes())
+        .await?;
+    writer.write_all(&bytes).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+pub(crate) async fn read_message<T: DeserializeOwned>(
+    reader: &mut (impl AsyncRead + Unpin),
+) -> Result<T, RemoteSignerError> {
+    let mut len = [0u8; 4];
+    reader.read_exact(&mut len).await?;
+    let len = u32::from_le_bytes(len) as usize;
+    if len > MAX_MESSAGE_SIZE {
+        return Err(RemoteSignerError::InvalidMessage(format!(
+            "message of {len} bytes exceeds the limit of {MAX_MESSAGE_SIZE} bytes"
+        )));
+    }
+    let mut bytes = vec![0u8; len];
+    reader.read_exact(&mut bytes).await?;
+    bcs::from_bytes(&bytes).map_err(|e| RemoteSignerError::InvalidMessage(e.to_string()))
+}
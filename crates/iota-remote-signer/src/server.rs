@@ -0,0 +1,456 @@
+// Copyright (c) 2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{io::ErrorKind, sync::Arc};
+
+use fastcrypto::{
+    ed25519::{Ed25519KeyPair, Ed25519Signature},
+    groups::bls12381,
+    hash::{Blake2b256, HashFunction},
+    serde_helpers::ToFromByteArray,
+};
+use iota_types::{
+    committee::EpochId,
+    crypto::{AuthorityKeyPair, AuthoritySignature, KeypairTraits, Signer, randomness_key_scalar},
+    message_envelope::Message,
+    messages_checkpoint::CheckpointSummary,
+};
+use shared_crypto::intent::{INTENT_PREFIX_LENGTH, Intent, IntentMessage, IntentScope};
+use tokio::net::{UnixListener, UnixStream};
+use tracing::{debug, warn};
+
+use crate::{
+    RemoteSignerError, SignGuard, SignerPublicKeys,
+    protocol::{SignRequest, SignResponse, read_message, write_message},
+};
+
+/// Holds the keys of a validator in the signer process and signs on its
+/// behalf, guarding against double signing with a [`SignGuard`].
+///
+/// Only the messages the node actually signs are accepted: transactions,
+/// effects and checkpoints with the authority key, and consensus blocks with
+/// the protocol key. The only key material handed out is the scalar of the
+/// authority key, which the random beacon DKG of the node decrypts with.
+pub struct SignerService {
+    authority_key: AuthorityKeyPair,
+    protocol_key: Ed25519KeyPair,
+    guard: SignGuard,
+}
+
+impl SignerService {
+    pub fn new(
+        authority_key: AuthorityKeyPair,
+        protocol_key: Ed25519KeyPair,
+        guard: SignGuard,
+    ) -> Self {
+        Self {
+            authority_key,
+            protocol_key,
+            guard,
+        }
+    }
+
+    pub fn handle(&self, request: SignRequest) -> SignResponse {
+        let response = match request {
+            SignRequest::PublicKeys => self.public_keys().map(SignResponse::PublicKeys),
+            SignRequest::AuthorityMessage(message) => self
+                .sign_authority_message(&message)
+                .map(SignResponse::AuthoritySignature),
+            SignRequest::ConsensusBlock(block) => self
+                .sign_consensus_block(&block)
+                .map(SignResponse::ConsensusBlockSignature),
+            SignRequest::RandomnessKey => Ok(SignResponse::RandomnessKey(
+                self.randomness_key().to_byte_array(),
+            )),
+        };
+        response.unwrap_or_else(|e| match e {
+            RemoteSignerError::AlreadySigned { block, signature } => {
+                warn!("Handing back the consensus block already signed for the round");
+                SignResponse::ConsensusBlockAlreadySigned { block, signature }
+            }
+            e => {
+                warn!("Refusing to sign: {e}");
+                SignResponse::Refused(e.to_string())
+            }
+        })
+    }
+
+    pub fn public_keys(&self) -> Result<SignerPublicKeys, RemoteSignerError> {
+        Ok(SignerPublicKeys {
+            authority: self.authority_key.public().into(),
+            protocol: self.protocol_key.public().clone(),
+        })
+    }
+
+    pub fn randomness_key(&self) -> bls12381::Scalar {
+        randomness_key_scalar(&self.authority_key)
+    }
+
+    pub fn sign_authority_message(
+        &self,
+        message: &[u8],
+    ) -> Result<AuthoritySignature, RemoteSignerError> {
+        let intent = message
+            .get(..INTENT_PREFIX_LENGTH)
+            .and_then(|bytes| Intent::from_bytes(bytes).ok())
+            .ok_or_else(|| RemoteSignerError::InvalidMessage("missing intent".to_string()))?;
+        match intent.scope {
+            IntentScope::SenderSignedTransaction | IntentScope::TransactionEffects => {}
+            IntentScope::CheckpointSummary => {
+                let summary = parse_checkpoint_summary(message)?;
+                self.guard.check_checkpoint(
+                    summary.epoch,
+                    summary.sequence_number,
+                    summary.digest().into_inner(),
+                )?;
+            }
+            scope => {
+                return Err(RemoteSignerError::Refused(format!(
+                    "{scope:?} messages are not signed with the authority key"
+                )));
+            }
+        }
+        Ok(self.authority_key.sign(message))
+    }
+
+    /// Signs a consensus block. If a different block was already signed for
+    /// the same round, and it is the last one signed, that block is signed
+    /// again and handed back instead, so that a node which crashed before
+    /// storing it can propose it again.
+    pub fn sign_consensus_block(
+        &self,
+        block: &[u8],
+    ) -> Result<Ed25519Signature, RemoteSignerError> {
+        let (epoch, round) = parse_block_header(block)?;
+        let digest = consensus_block_digest(block);
+        if let Err(e) = self
+            .guard
+            .check_consensus_block(epoch, round, digest, block)
+        {
+            return match self.guard.signed_consensus_block(epoch, round) {
+                Some(signed_block) => {
+                    let signature =
+                        self.sign_consensus_block_digest(consensus_block_digest(&signed_block));
+                    Err(RemoteSignerError::AlreadySigned {
+                        block: signed_block,
+                        signature,
+                    })
+                }
+                None => Err(e),
+            };
+        }
+        Ok(self.sign_consensus_block_digest(digest))
+    }
+
+    fn sign_consensus_block_digest(&self, digest: [u8; 32]) -> Ed25519Signature {
+        let message = bcs::to_bytes(&IntentMessage::new(
+            Intent::consensus_app(IntentScope::ConsensusBlock),
+            digest,
+        ))
+        .expect("message serialization should not fail");
+        self.protocol_key.sign(&message)
+    }
+}
+
+/// Consensus signs the digest of the block rather than the block itself.
+fn consensus_block_digest(block: &[u8]) -> [u8; 32] {
+    Blake2b256::digest(block).into()
+}
+
+/// Splits a message built by `AuthoritySignature::new_secure`, i.e. the
+/// intent, the summary and the epoch, and checks that the epochs match.
+fn parse_checkpoint_summary(message: &[u8]) -> Result<CheckpointSummary, RemoteSignerError> {
+    let invalid = RemoteSignerError::InvalidMessage;
+    let payload = &message[INTENT_PREFIX_LENGTH..];
+    let epoch_offset = payload
+        .len()
+        .checked_sub(size_of::<EpochId>())
+        .ok_or_else(|| invalid("missing epoch".to_string()))?;
+    let (summary, epoch) = payload.split_at(epoch_offset);
+    let summary: CheckpointSummary =
+        bcs::from_bytes(summary).map_err(|e| invalid(format!("invalid checkpoint: {e}")))?;
+    let epoch: EpochId =
+        bcs::from_bytes(epoch).map_err(|e| invalid(format!("invalid epoch: {e}")))?;
+    if summary.epoch != epoch {
+        return Err(invalid(format!(
+            "checkpoint of epoch {} signed for epoch {epoch}",
+            summary.epoch
+        )));
+    }
+    Ok(summary)
+}
+
+/// Reads the epoch and round of a BCS encoded consensus block. Both are the
+/// first fields of a `BlockV1`, right after the variant index of the block.
+fn parse_block_header(block: &[u8]) -> Result<(EpochId, u32), RemoteSignerError> {
+    const BLOCK_V1: u8 = 0;
+    const EPOCH_OFFSET: usize = 1;
+    const ROUND_OFFSET: usize = EPOCH_OFFSET + size_of::<EpochId>();
+
+    if block.first() != Some(&BLOCK_V1) {
+        return Err(RemoteSignerError::InvalidMessage(
+            "unsupported consensus block version".to_string(),
+        ));
+    }
+    let header = block
+        .get(EPOCH_OFFSET..ROUND_OFFSET + size_of::<u32>())
+        .ok_or_else(|| {
+            RemoteSignerError::InvalidMessage("truncated consensus block".to_string())
+        })?;
+    let (epoch, round) = header.split_at(size_of::<EpochId>());
+    Ok((
+        EpochId::from_le_bytes(epoch.try_into().expect("length was checked")),
+        u32::from_le_bytes(round.try_into().expect("length was checked")),
+    ))
+}
+
+/// Serves the signing requests of the nodes connecting to `listener`, each
+/// connection in its own task. Only returns if the listener fails.
+pub async fn serve(listener: UnixListener, service: Arc<SignerService>) -> std::io::Result<()> {
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let service = service.clone();
+        tokio::spawn(async move {
+            debug!("Node connected");
+            if let Err(e) = handle_connection(stream, service).await {
+                debug!("Connection closed: {e}");
+            }
+        });
+    }
+}
+
+async fn handle_connection(
+    mut stream: UnixStream,
+    service: Arc<SignerService>,
+) -> Result<(), RemoteSignerError> {
+    loop {
+        let request = match read_message(&mut stream).await {
+            Ok(request) => request,
+            Err(RemoteSignerError::Io(e)) if e.kind() == ErrorKind::UnexpectedEof => {
+                debug!("Node disconnected");
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+        // Signing may write the guard state to disk.
+        let service = service.clone();
+        let response = tokio::task::spawn_blocking(move || service.handle(request))
+            .await
+            .map_err(std::io::Error::other)?;
+        write_message(&mut stream, &response).await?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use consensus_config::{BlockSigner, BlockSigningError};
+    use fastcrypto::{ed25519::Ed25519PublicKey, groups::GroupElement, traits::VerifyingKey};
+    use iota_types::{
+        crypto::{AuthorityPublicKeyBytes, IotaAuthoritySignature, get_key_pair},
+        digests::CheckpointContentsDigest,
+        error::IotaError,
+        gas::GasCostSummary,
+        messages_checkpoint::SignedCheckpointSummary,
+    };
+
+    use super::*;
+    use crate::{LocalSocketSigner, RemoteAuthoritySigner, RemoteBlockSigner, RemoteSigner};
+
+    fn checkpoint(sequence_number: u64, timestamp_ms: u64) -> CheckpointSummary {
+        CheckpointSummary {
+            epoch: 1,
+            sequence_number,
+            network_total_transactions: 0,
+            content_digest: CheckpointContentsDigest::new([0; 32]),
+            previous_digest: None,
+            epoch_rolling_gas_cost_summary: GasCostSummary::default(),
+            timestamp_ms,
+            checkpoint_commitments: vec![],
+            end_of_epoch_data: None,
+            version_specific_data: vec![],
+        }
+    }
+
+    fn signed_message(summary: &CheckpointSummary) -> Vec<u8> {
+        let mut message = bcs::to_bytes(&IntentMessage::new(
+            Intent::iota_app(IntentScope::CheckpointSummary),
+            summary,
+        ))
+        .unwrap();
+        message.extend(bcs::to_bytes(&summary.epoch).unwrap());
+        message
+    }
+
+    fn consensus_block(epoch: EpochId, round: u32, timestamp_ms: u64) -> Vec<u8> {
+        let mut block = vec![0];
+        block.extend(bcs::to_bytes(&(epoch, round, 0u32, timestamp_ms)).unwrap());
+        block
+    }
+
+    /// Starts a signer process side serving on a socket in `dir`, and
+    /// connects to it.
+    async fn start_signer(
+        dir: &std::path::Path,
+    ) -> (
+        Arc<LocalSocketSigner>,
+        AuthorityPublicKeyBytes,
+        Ed25519PublicKey,
+    ) {
+        let socket_path = dir.join("signer.sock");
+        let (_, authority_key): (_, AuthorityKeyPair) = get_key_pair();
+        let (_, protocol_key): (_, Ed25519KeyPair) = get_key_pair();
+        let authority: AuthorityPublicKeyBytes = authority_key.public().into();
+        let protocol = protocol_key.public().clone();
+        let service = SignerService::new(
+            authority_key,
+            protocol_key,
+            SignGuard::open(dir.join("guard")).unwrap(),
+        );
+        let listener = UnixListener::bind(&socket_path).unwrap();
+        tokio::spawn(serve(listener, Arc::new(service)));
+
+        let signer = Arc::new(LocalSocketSigner::connect(&socket_path).await.unwrap());
+        (signer, authority, protocol)
+    }
+
+    #[tokio::test]
+    async fn test_sign_over_local_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let (signer, authority, protocol) = start_signer(dir.path()).await;
+        let keys = signer.public_keys().await.unwrap();
+        assert_eq!(keys.authority, authority);
+        assert_eq!(keys.protocol, protocol);
+
+        // The randomness key is the scalar of the authority key.
+        let randomness_key = signer.randomness_key().await.unwrap();
+        assert_eq!(
+            (bls12381::G2Element::generator() * randomness_key).to_byte_array(),
+            authority.0
+        );
+
+        // Checkpoints signed through the authority signer verify as usual.
+        let authority_signer = RemoteAuthoritySigner::new(signer.clone());
+        let summary = checkpoint(1, 0);
+        let signed_summary =
+            SignedCheckpointSummary::try_new(1, summary.clone(), &authority_signer, authority)
+                .await
+                .unwrap();
+        let intent_message =
+            IntentMessage::new(Intent::iota_app(IntentScope::CheckpointSummary), &summary);
+        signed_summary
+            .auth_sig()
+            .signature
+            .verify_secure(&intent_message, summary.epoch, authority)
+            .unwrap();
+        // A conflicting checkpoint at the same sequence number is refused.
+        assert!(matches!(
+            signer
+                .sign_authority_message(&signed_message(&checkpoint(1, 1)))
+                .await,
+            Err(RemoteSignerError::Refused(_))
+        ));
+        // The node fails signing with a retryable error rather than panicking.
+        let err =
+            SignedCheckpointSummary::try_new(1, checkpoint(1, 2), &authority_signer, authority)
+                .await
+                .unwrap_err();
+        assert!(matches!(err, IotaError::AuthoritySigning { .. }));
+        assert!(err.is_retryable().0);
+        signer
+            .sign_authority_message(&signed_message(&checkpoint(2, 1)))
+            .await
+            .unwrap();
+
+        // So are consensus blocks.
+        let block = consensus_block(1, 5, 0);
+        let signature = signer.sign_consensus_block(&block).await.unwrap();
+        let digest: [u8; 32] = Blake2b256::digest(&block).into();
+        let message = bcs::to_bytes(&IntentMessage::new(
+            Intent::consensus_app(IntentScope::ConsensusBlock),
+            digest,
+        ))
+        .unwrap();
+        protocol.verify(&message, &signature).unwrap();
+        // Instead of signing a conflicting block for the same round, the signer
+        // hands back the block it signed.
+        let Err(RemoteSignerError::AlreadySigned {
+            block: signed_block,
+            signature,
+        }) = signer.sign_consensus_block(&consensus_block(1, 5, 1)).await
+        else {
+            panic!("the conflicting block should not have been signed");
+        };
+        assert_eq!(signed_block, block);
+        protocol.verify(&message, &signature).unwrap();
+        signer
+            .sign_consensus_block(&consensus_block(1, 6, 1))
+            .await
+            .unwrap();
+        // An older round is only handed back while it is the last one signed.
+        assert!(matches!(
+            signer.sign_consensus_block(&consensus_block(1, 5, 2)).await,
+            Err(RemoteSignerError::Refused(_))
+        ));
+
+        // Messages the node never signs with the authority key are refused.
+        let personal_message = bcs::to_bytes(&IntentMessage::new(
+            Intent::iota_app(IntentScope::PersonalMessage),
+            vec![0u8; 8],
+        ))
+        .unwrap();
+        assert!(matches!(
+            signer.sign_authority_message(&personal_message).await,
+            Err(RemoteSignerError::Refused(_))
+        ));
+    }
+
+    #[cfg(not(msim))]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_concurrent_requests() {
+        let dir = tempfile::tempdir().unwrap();
+        let (signer, _, protocol) = start_signer(dir.path()).await;
+
+        // Requests don't wait for each other, each gets its own connection.
+        let requests = (1..=10).map(|sequence_number| {
+            let signer = signer.clone();
+            tokio::spawn(async move {
+                signer
+                    .sign_authority_message(&signed_message(&checkpoint(sequence_number, 0)))
+                    .await
+            })
+        });
+        for request in requests.collect::<Vec<_>>() {
+            request.await.unwrap().unwrap();
+        }
+
+        // Consensus signs blocks synchronously, from within the runtime.
+        let block_signer = RemoteBlockSigner::new(signer.clone()).unwrap();
+        let block = consensus_block(1, 1, 0);
+        block_signer.sign_block(&block).unwrap();
+        let Err(BlockSigningError::AlreadySigned {
+            block: signed_block,
+            signature,
+        }) = block_signer.sign_block(&consensus_block(1, 1, 1))
+        else {
+            panic!("the conflicting block should not have been signed");
+        };
+        assert_eq!(signed_block, block);
+        let message = bcs::to_bytes(&IntentMessage::new(
+            Intent::consensus_app(IntentScope::ConsensusBlock),
+            consensus_block_digest(&block),
+        ))
+        .unwrap();
+        protocol.verify(&message, &signature).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_block_signer_requires_multi_threaded_runtime() {
+        let dir = tempfile::tempdir().unwrap();
+        let (signer, _, _) = start_signer(dir.path()).await;
+
+        assert!(matches!(
+            RemoteBlockSigner::new(signer),
+            Err(RemoteSignerError::UnsupportedRuntime)
+        ));
+    }
+}
@@ -2,29 +2,58 @@
 // Modifications Copyright (c) 2024 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{collections::BTreeMap, num::NonZeroUsize, str::FromStr};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs::File,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
 
+use anyhow::{anyhow, bail};
 use async_trait::async_trait;
+use bytes::Bytes;
 use futures::future::join_all;
-use iota_core::authority::NodeStateDump;
+use iota_archival::{
+    CHECKPOINT_FILE_MAGIC, FileMetadata as ArchiveFileMetadata, FileType as ArchiveFileType,
+    SUMMARY_FILE_MAGIC, read_manifest_from_bytes,
+};
+use iota_core::authority::{NodeStateDump, authority_store_tables::LiveObject};
 use iota_json_rpc_api::QUERY_MAX_RESULT_LIMIT;
 use iota_json_rpc_types::{
     EventFilter, IotaEvent, IotaGetPastObjectRequest, IotaObjectData, IotaObjectDataOptions,
-    IotaObjectResponse, IotaPastObjectResponse, IotaTransactionBlockResponse,
-    IotaTransactionBlockResponseOptions,
+    IotaObjectResponse, IotaPastObjectResponse, IotaTransactionBlockEffects,
+    IotaTransactionBlockResponse, IotaTransactionBlockResponseOptions,
 };
 use iota_sdk::IotaClient;
+use iota_snapshot::{
+    FileMetadata as SnapshotFileMetadata, FileType as SnapshotFileType,
+    reader::{LiveObjectIter, ObjectRefIter, StateSnapshotReaderV1},
+};
+use iota_storage::{blob::Blob, make_iterator};
 use iota_types::{
-    base_types::{ObjectID, SequenceNumber, VersionNumber},
-    digests::TransactionDigest,
-    object::Object,
+    IOTA_SYSTEM_PACKAGE_ID,
+    base_types::{IotaAddress, ObjectID, SequenceNumber, VersionNumber},
+    digests::{ChainIdentifier, TransactionDigest},
+    effects::TransactionEffectsAPI,
+    event::{Event, EventID, SystemEpochInfoEventV1},
+    full_checkpoint_content::{CheckpointData, CheckpointTransaction},
+    iota_system_state::{IotaSystemStateTrait, get_iota_system_state},
+    messages_checkpoint::{
+        CertifiedCheckpointSummary, CheckpointSequenceNumber, FullCheckpointContents,
+    },
+    object::{Object, Owner},
+    storage::{ObjectStore, error::Error as StorageError},
     transaction::{
-        EndOfEpochTransactionKind, SenderSignedData, TransactionDataAPI, TransactionKind,
+        ChangeEpoch, EndOfEpochTransactionKind, SenderSignedData, Transaction, TransactionDataAPI,
+        TransactionKind,
     },
 };
 use lru::LruCache;
-use move_core_types::parser::parse_struct_tag;
-use parking_lot::RwLock;
+use move_core_types::{identifier::Identifier, parser::parse_struct_tag};
+use object_store::path::Path as ObjectStorePath;
+use parking_lot::{Mutex, RwLock};
 use rand::Rng;
 
 use crate::types::{EPOCH_CHANGE_STRUCT_TAG, ReplayEngineError};
@@ -95,13 +124,16 @@ pub(crate) trait DataFetcher {
 pub enum Fetchers {
     Remote(RemoteFetcher),
     NodeStateDump(NodeStateDumpFetcher),
+    LocalArchive(LocalArchiveFetcher),
 }
 
 impl Fetchers {
     pub fn as_remote(&self) -> &RemoteFetcher {
         match self {
             Fetchers::Remote(q) => q,
-            Fetchers::NodeStateDump(_) => panic!("not a remote fetcher"),
+            Fetchers::NodeStateDump(_) | Fetchers::LocalArchive(_) => {
+                panic!("not a remote fetcher")
+            }
         }
     }
 
@@ -113,13 +145,17 @@ impl Fetchers {
                 q.clear_cache_for_new_task();
                 q
             }
-            Fetchers::NodeStateDump(_) => panic!("not a remote fetcher"),
+            Fetchers::NodeStateDump(_) | Fetchers::LocalArchive(_) => {
+                panic!("not a remote fetcher")
+            }
         }
     }
 
     pub fn as_node_state_dump(&self) -> &NodeStateDumpFetcher {
         match self {
-            Fetchers::Remote(_) => panic!("not a node state dump fetcher"),
+            Fetchers::Remote(_) | Fetchers::LocalArchive(_) => {
+                panic!("not a node state dump fetcher")
+            }
             Fetchers::NodeStateDump(q) => q,
         }
     }
@@ -135,6 +171,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.multi_get_versioned(objects).await,
             Fetchers::NodeStateDump(q) => q.multi_get_versioned(objects).await,
+            Fetchers::LocalArchive(q) => q.multi_get_versioned(objects).await,
         }
     }
 
@@ -145,6 +182,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.multi_get_latest(objects).await,
             Fetchers::NodeStateDump(q) => q.multi_get_latest(objects).await,
+            Fetchers::LocalArchive(q) => q.multi_get_latest(objects).await,
         }
     }
 
@@ -155,6 +193,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_checkpoint_txs(id).await,
             Fetchers::NodeStateDump(q) => q.get_checkpoint_txs(id).await,
+            Fetchers::LocalArchive(q) => q.get_checkpoint_txs(id).await,
         }
     }
 
@@ -165,6 +204,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_transaction(tx_digest).await,
             Fetchers::NodeStateDump(q) => q.get_transaction(tx_digest).await,
+            Fetchers::LocalArchive(q) => q.get_transaction(tx_digest).await,
         }
     }
 
@@ -175,6 +215,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_loaded_child_objects(tx_digest).await,
             Fetchers::NodeStateDump(q) => q.get_loaded_child_objects(tx_digest).await,
+            Fetchers::LocalArchive(q) => q.get_loaded_child_objects(tx_digest).await,
         }
     }
 
@@ -182,6 +223,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_latest_checkpoint_sequence_number().await,
             Fetchers::NodeStateDump(q) => q.get_latest_checkpoint_sequence_number().await,
+            Fetchers::LocalArchive(q) => q.get_latest_checkpoint_sequence_number().await,
         }
     }
 
@@ -199,6 +241,10 @@ impl DataFetcher for Fetchers {
                 q.fetch_random_transaction(checkpoint_id_start, checkpoint_id_end)
                     .await
             }
+            Fetchers::LocalArchive(q) => {
+                q.fetch_random_transaction(checkpoint_id_start, checkpoint_id_end)
+                    .await
+            }
         }
     }
 
@@ -209,6 +255,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_epoch_start_timestamp_and_rgp(epoch_id).await,
             Fetchers::NodeStateDump(q) => q.get_epoch_start_timestamp_and_rgp(epoch_id).await,
+            Fetchers::LocalArchive(q) => q.get_epoch_start_timestamp_and_rgp(epoch_id).await,
         }
     }
 
@@ -219,12 +266,14 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_epoch_change_events(reverse).await,
             Fetchers::NodeStateDump(q) => q.get_epoch_change_events(reverse).await,
+            Fetchers::LocalArchive(q) => q.get_epoch_change_events(reverse).await,
         }
     }
     async fn get_chain_id(&self) -> Result<String, ReplayEngineError> {
         match self {
             Fetchers::Remote(q) => q.get_chain_id().await,
             Fetchers::NodeStateDump(q) => q.get_chain_id().await,
+            Fetchers::LocalArchive(q) => q.get_chain_id().await,
         }
     }
     async fn get_child_object(
@@ -235,6 +284,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_child_object(object_id, version_upper_bound).await,
             Fetchers::NodeStateDump(q) => q.get_child_object(object_id, version_upper_bound).await,
+            Fetchers::LocalArchive(q) => q.get_child_object(object_id, version_upper_bound).await,
         }
     }
 }
@@ -815,3 +865,740 @@ impl DataFetcher for NodeStateDumpFetcher {
         unimplemented!("get child object is not implemented for state dump");
     }
 }
+
+/// Number of decoded checkpoint files kept in memory by the local archive
+/// fetcher.
+const LOCAL_ARCHIVE_FILE_CACHE_CAPACITY: Option<NonZeroUsize> = NonZeroUsize::new(16);
+/// Number of objects read from a formal snapshot kept in memory.
+const SNAPSHOT_OBJECT_CACHE_CAPACITY: Option<NonZeroUsize> = NonZeroUsize::new(1_000);
+const ARCHIVE_MANIFEST_FILENAME: &str = "MANIFEST";
+
+/// Serves transactions from the checkpoints stored in a local directory, so
+/// that they can be replayed without access to a full node.
+///
+/// Two layouts of the directory are supported:
+/// - `{sequence_number}.chk` files holding one `CheckpointData` each, as
+///   written by the blob worker of the ingestion framework. These contain the
+///   objects read and written by every transaction.
+/// - the archive format of `iota-archival`, a `MANIFEST` with pairs of summary
+///   and contents files per checkpoint range. These only contain transactions
+///   and effects, so objects are only available from the snapshot base.
+///
+/// Objects last written before the archive window are read from a formal
+/// snapshot of the epoch preceding it, if one is provided.
+///
+/// Only a lightweight index of the checkpoints is kept in memory, the files are
+/// decoded on demand and the most recently used ones are cached.
+#[derive(Clone)]
+pub struct LocalArchiveFetcher {
+    files: Arc<CheckpointFiles>,
+    index: Arc<ArchiveIndex>,
+    cache: Arc<Mutex<LruCache<CheckpointSequenceNumber, Arc<Vec<CheckpointData>>>>>,
+    snapshot: Option<Arc<SnapshotObjects>>,
+}
+
+impl LocalArchiveFetcher {
+    pub fn new(path: &Path, snapshot: Option<&Path>) -> Result<Self, ReplayEngineError> {
+        let files = CheckpointFiles::open(path)?;
+        let index = ArchiveIndex::build(&files)?;
+        let snapshot = snapshot.map(SnapshotObjects::open).transpose()?;
+        Ok(Self {
+            files: Arc::new(files),
+            index: Arc::new(index),
+            cache: Arc::new(Mutex::new(LruCache::new(
+                LOCAL_ARCHIVE_FILE_CACHE_CAPACITY.expect("Cache size must be non zero"),
+            ))),
+            snapshot: snapshot.map(Arc::new),
+        })
+    }
+
+    /// Reads the checkpoints of the file starting at `file`, going through the
+    /// cache.
+    fn read_file(
+        &self,
+        file: CheckpointSequenceNumber,
+    ) -> Result<Arc<Vec<CheckpointData>>, ReplayEngineError> {
+        if let Some(checkpoints) = self.cache.lock().get(&file) {
+            return Ok(checkpoints.clone());
+        }
+        let checkpoints = Arc::new(self.files.read(file)?);
+        self.cache.lock().put(file, checkpoints.clone());
+        Ok(checkpoints)
+    }
+
+    fn with_checkpoint<T>(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+        f: impl FnOnce(&CheckpointData) -> T,
+    ) -> Result<T, ReplayEngineError> {
+        let not_found = ReplayEngineError::CheckpointNotFound { sequence_number };
+        let entry = self
+            .index
+            .checkpoints
+            .get(&sequence_number)
+            .ok_or_else(|| not_found.clone())?;
+        let checkpoints = self.read_file(entry.file)?;
+        checkpoints
+            .iter()
+            .find(|checkpoint| checkpoint.checkpoint_summary.sequence_number == sequence_number)
+            .map(f)
+            .ok_or(not_found)
+    }
+
+    fn transaction(
+        &self,
+        tx_digest: &TransactionDigest,
+    ) -> Result<(CheckpointSequenceNumber, CheckpointTransaction), ReplayEngineError> {
+        let not_found = ReplayEngineError::TransactionNotFound { digest: *tx_digest };
+        let sequence_number = *self
+            .index
+            .transactions
+            .get(tx_digest)
+            .ok_or_else(|| not_found.clone())?;
+        self.with_checkpoint(sequence_number, |checkpoint| {
+            checkpoint
+                .transactions
+                .iter()
+                .find(|transaction| transaction.transaction.digest() == tx_digest)
+                .cloned()
+        })?
+        .map(|transaction| (sequence_number, transaction))
+        .ok_or(not_found)
+    }
+
+    fn object_in_checkpoint(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> Result<Option<Object>, ReplayEngineError> {
+        self.with_checkpoint(sequence_number, |checkpoint| {
+            checkpoint
+                .transactions
+                .iter()
+                .flat_map(|transaction| {
+                    transaction
+                        .input_objects
+                        .iter()
+                        .chain(&transaction.output_objects)
+                })
+                .find(|object| object.id() == *id && object.version() == version)
+                .cloned()
+        })
+    }
+
+    fn snapshot_object(&self, id: &ObjectID) -> Result<Option<Object>, ReplayEngineError> {
+        match &self.snapshot {
+            Some(snapshot) => Ok(snapshot.get(id)?),
+            None => Ok(None),
+        }
+    }
+
+    fn object(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> Result<Option<Object>, ReplayEngineError> {
+        if let Some(sequence_number) = self.index.objects.get(&(*id, version)) {
+            return self.object_in_checkpoint(*sequence_number, id, version);
+        }
+        Ok(self
+            .snapshot_object(id)?
+            .filter(|object| object.version() == version))
+    }
+
+    fn latest_object_before(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> Result<Option<Object>, ReplayEngineError> {
+        if let Some(((_, found), sequence_number)) = self
+            .index
+            .objects
+            .range((*id, SequenceNumber::MIN)..=(*id, version))
+            .next_back()
+        {
+            return self.object_in_checkpoint(*sequence_number, id, *found);
+        }
+        Ok(self
+            .snapshot_object(id)?
+            .filter(|object| object.version() <= version))
+    }
+
+    /// Returns the reference gas price of `epoch` from the system state of the
+    /// snapshot base, as the archive format of `iota-archival` does not hold
+    /// the epoch change events carrying it.
+    fn snapshot_reference_gas_price(&self, epoch: u64) -> Result<u64, ReplayEngineError> {
+        let snapshot = self
+            .snapshot
+            .as_ref()
+            .ok_or_else(|| ReplayEngineError::GeneralError {
+                err: format!(
+                    "The reference gas price of epoch {epoch} is only available with a snapshot base"
+                ),
+            })?;
+        let system_state = get_iota_system_state(snapshot.as_ref())?;
+        if system_state.epoch() != epoch {
+            return Err(ReplayEngineError::GeneralError {
+                err: format!(
+                    "The snapshot base only holds the reference gas price of epoch {}, not of epoch {epoch}",
+                    system_state.epoch()
+                ),
+            });
+        }
+        Ok(system_state.reference_gas_price())
+    }
+}
+
+/// Layout of the checkpoint files of a local archive.
+enum CheckpointFiles {
+    /// `{sequence_number}.chk` files written by the blob worker.
+    Blob(PathBuf),
+    /// Files of the `iota-archival` format.
+    Archival {
+        root: PathBuf,
+        /// Summary and contents file of each checkpoint range, keyed by its
+        /// first checkpoint.
+        files: BTreeMap<CheckpointSequenceNumber, (ArchiveFileMetadata, ArchiveFileMetadata)>,
+    },
+}
+
+impl CheckpointFiles {
+    fn open(path: &Path) -> anyhow::Result<Self> {
+        let manifest_path = path.join(ARCHIVE_MANIFEST_FILENAME);
+        if !manifest_path.exists() {
+            return Ok(Self::Blob(path.to_path_buf()));
+        }
+        let manifest = read_manifest_from_bytes(std::fs::read(manifest_path)?)?;
+        let mut summaries = BTreeMap::new();
+        let mut contents = BTreeMap::new();
+        for file in manifest.files() {
+            let start = file.checkpoint_seq_range.start;
+            match file.file_type {
+                ArchiveFileType::CheckpointSummary => summaries.insert(start, file),
+                ArchiveFileType::CheckpointContent => contents.insert(start, file),
+            };
+        }
+        let files = summaries
+            .into_iter()
+            .map(|(start, summary)| {
+                let content = contents
+                    .remove(&start)
+                    .ok_or_else(|| anyhow!("Missing contents file of checkpoint {start}"))?;
+                Ok((start, (summary, content)))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self::Archival {
+            root: path.to_path_buf(),
+            files,
+        })
+    }
+
+    /// Returns the first checkpoint of every file, in order.
+    fn keys(&self) -> anyhow::Result<Vec<CheckpointSequenceNumber>> {
+        match self {
+            Self::Blob(dir) => {
+                let mut keys = vec![];
+                for entry in std::fs::read_dir(dir)? {
+                    let path = entry?.path();
+                    if path.extension().and_then(|ext| ext.to_str()) != Some("chk") {
+                        continue;
+                    }
+                    if let Some(sequence_number) = path
+                        .file_stem()
+                        .and_then(|stem| stem.to_str())
+                        .and_then(|stem| stem.parse().ok())
+                    {
+                        keys.push(sequence_number);
+                    }
+                }
+                keys.sort();
+                Ok(keys)
+            }
+            Self::Archival { files, .. } => Ok(files.keys().copied().collect()),
+        }
+    }
+
+    /// Decodes the checkpoints of the file starting at `key`.
+    fn read(&self, key: CheckpointSequenceNumber) -> anyhow::Result<Vec<CheckpointData>> {
+        match self {
+            Self::Blob(dir) => {
+                let bytes = std::fs::read(dir.join(format!("{key}.chk")))?;
+                Ok(vec![Blob::from_bytes::<CheckpointData>(&bytes)?])
+            }
+            Self::Archival { root, files } => {
+                let (summary, contents) = files
+                    .get(&key)
+                    .ok_or_else(|| anyhow!("No archive file starts at checkpoint {key}"))?;
+                let summaries = make_iterator::<CertifiedCheckpointSummary, _>(
+                    SUMMARY_FILE_MAGIC,
+                    File::open(root.join(summary.file_path().to_string()))?,
+                )?;
+                let contents = make_iterator::<FullCheckpointContents, _>(
+                    CHECKPOINT_FILE_MAGIC,
+                    File::open(root.join(contents.file_path().to_string()))?,
+                )?;
+                Ok(summaries
+                    .zip(contents)
+                    .map(|(summary, contents)| CheckpointData {
+                        checkpoint_summary: summary,
+                        checkpoint_contents: contents.checkpoint_contents(),
+                        transactions: contents
+                            .iter()
+                            .map(|data| CheckpointTransaction {
+                                transaction: data.transaction.clone(),
+                                effects: data.effects.clone(),
+                                events: None,
+                                input_objects: vec![],
+                                output_objects: vec![],
+                            })
+                            .collect(),
+                    })
+                    .collect())
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct ArchiveIndex {
+    checkpoints: BTreeMap<CheckpointSequenceNumber, CheckpointEntry>,
+    /// Checkpoint of each transaction
+    transactions: HashMap<TransactionDigest, CheckpointSequenceNumber>,
+    /// Checkpoint holding each object version read or written by a transaction
+    objects: BTreeMap<(ObjectID, SequenceNumber), CheckpointSequenceNumber>,
+    /// System epoch info events ordered by checkpoint
+    epoch_change_events: Vec<IotaEvent>,
+    chain_id: Option<String>,
+}
+
+struct CheckpointEntry {
+    /// First checkpoint of the file holding this checkpoint
+    file: CheckpointSequenceNumber,
+    timestamp_ms: u64,
+    transactions: Vec<TransactionDigest>,
+}
+
+impl ArchiveIndex {
+    fn build(files: &CheckpointFiles) -> Result<Self, ReplayEngineError> {
+        let mut index = Self::default();
+        for file in files.keys()? {
+            for checkpoint in files.read(file)? {
+                index.insert_checkpoint(file, &checkpoint)?;
+            }
+        }
+        Ok(index)
+    }
+
+    fn insert_checkpoint(
+        &mut self,
+        file: CheckpointSequenceNumber,
+        checkpoint: &CheckpointData,
+    ) -> Result<(), ReplayEngineError> {
+        let summary = &checkpoint.checkpoint_summary;
+        let sequence_number = summary.sequence_number;
+        if sequence_number == 0 {
+            self.chain_id = Some(ChainIdentifier::from(*summary.digest()).to_string());
+        }
+        self.checkpoints.insert(sequence_number, CheckpointEntry {
+            file,
+            timestamp_ms: summary.timestamp_ms,
+            transactions: checkpoint
+                .checkpoint_contents
+                .iter()
+                .map(|digests| digests.transaction)
+                .collect(),
+        });
+        for transaction in &checkpoint.transactions {
+            for object in transaction
+                .input_objects
+                .iter()
+                .chain(&transaction.output_objects)
+            {
+                self.objects
+                    .insert((object.id(), object.version()), sequence_number);
+            }
+            let tx_digest = *transaction.transaction.digest();
+            match &transaction.events {
+                Some(events) => {
+                    for (event_seq, event) in events.data.iter().enumerate() {
+                        if event.is_system_epoch_info_event() {
+                            self.epoch_change_events.push(epoch_change_event(
+                                event,
+                                tx_digest,
+                                event_seq as u64,
+                                summary.timestamp_ms,
+                            )?);
+                        }
+                    }
+                }
+                // The archive format of iota-archival holds no events, so the
+                // epoch changes are recovered from the change epoch
+                // transactions.
+                None => {
+                    if let Some(change) = change_epoch(&transaction.transaction) {
+                        self.epoch_change_events.push(change_epoch_event(
+                            change,
+                            tx_digest,
+                            summary.timestamp_ms,
+                        )?);
+                    }
+                }
+            }
+            self.transactions.insert(tx_digest, sequence_number);
+        }
+        Ok(())
+    }
+}
+
+/// Objects of the live object set of a local formal snapshot.
+///
+/// The objects are written to the snapshot ordered by ID, so only the first ID
+/// of each partition is kept in memory and an object is read from the
+/// partition that can hold its ID.
+struct SnapshotObjects {
+    root: PathBuf,
+    /// Reference and object file of each partition, keyed by its first object
+    partitions: BTreeMap<ObjectID, (SnapshotFileMetadata, SnapshotFileMetadata)>,
+    cache: Mutex<LruCache<ObjectID, Option<Object>>>,
+}
+
+impl SnapshotObjects {
+    fn open(path: &Path) -> anyhow::Result<Self> {
+        let manifest = StateSnapshotReaderV1::read_manifest(path.join("MANIFEST"))?;
+        if manifest.base_epoch().is_some() {
+            bail!(
+                "{} holds a delta snapshot, the snapshot base must be a full snapshot",
+                path.display()
+            );
+        }
+        let mut references = BTreeMap::new();
+        let mut objects = BTreeMap::new();
+        for file in manifest.file_metadata() {
+            let key = (file.bucket_num, file.part_num);
+            match file.file_type {
+                SnapshotFileType::Reference => references.insert(key, file.clone()),
+                SnapshotFileType::Object => objects.insert(key, file.clone()),
+                SnapshotFileType::Deletion => None,
+            };
+        }
+        let mut partitions = BTreeMap::new();
+        for (key, reference_file) in references {
+            let object_file = objects
+                .remove(&key)
+                .ok_or_else(|| anyhow!("Missing object file of partition {key:?}"))?;
+            // Empty partitions hold no objects to look up
+            if let Some((first_id, ..)) = ObjectRefIter::new(
+                &reference_file,
+                path.to_path_buf(),
+                ObjectStorePath::default(),
+            )?
+            .next()
+            {
+                partitions.insert(first_id, (reference_file, object_file));
+            }
+        }
+        Ok(Self {
+            root: path.to_path_buf(),
+            partitions,
+            cache: Mutex::new(LruCache::new(
+                SNAPSHOT_OBJECT_CACHE_CAPACITY.expect("Cache size must be non zero"),
+            )),
+        })
+    }
+
+    fn get(&self, id: &ObjectID) -> anyhow::Result<Option<Object>> {
+        if let Some(object) = self.cache.lock().get(id) {
+            return Ok(object.clone());
+        }
+        let object = self.read(id)?;
+        self.cache.lock().put(*id, object.clone());
+        Ok(object)
+    }
+
+    fn read(&self, id: &ObjectID) -> anyhow::Result<Option<Object>> {
+        let Some((_, (reference_file, object_file))) = self.partitions.range(..=*id).next_back()
+        else {
+            return Ok(None);
+        };
+        let Some(position) = ObjectRefIter::new(
+            reference_file,
+            self.root.clone(),
+            ObjectStorePath::default(),
+        )?
+        .position(|(object_id, ..)| object_id == *id) else {
+            return Ok(None);
+        };
+        let bytes =
+            std::fs::read(object_file.local_file_path(&self.root, &ObjectStorePath::default())?)?;
+        match LiveObjectIter::new(object_file, Bytes::from(bytes))?.nth(position) {
+            Some(LiveObject::Normal(object)) => Ok(Some(object)),
+            Some(LiveObject::Wrapped(_)) => Ok(None),
+            None => Err(anyhow!(
+                "Object file of partition ({}, {}) has less objects than its reference file",
+                object_file.bucket_num,
+                object_file.part_num
+            )),
+        }
+    }
+}
+
+impl ObjectStore for SnapshotObjects {
+    fn get_object(
+        &self,
+        object_id: &ObjectID,
+    ) -> iota_types::storage::error::Result<Option<Object>> {
+        self.get(object_id).map_err(StorageError::custom)
+    }
+
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: VersionNumber,
+    ) -> iota_types::storage::error::Result<Option<Object>> {
+        Ok(self
+            .get_object(object_id)?
+            .filter(|object| object.version() == version))
+    }
+}
+
+/// Returns the change epoch command of an end of epoch transaction.
+fn change_epoch(transaction: &Transaction) -> Option<&ChangeEpoch> {
+    let TransactionKind::EndOfEpochTransaction(kinds) = transaction.transaction_data().kind()
+    else {
+        return None;
+    };
+    kinds.iter().find_map(|kind| match kind {
+        EndOfEpochTransactionKind::ChangeEpoch(change) => Some(change),
+        _ => None,
+    })
+}
+
+/// Converts a system epoch info event to the fields of its JSON-RPC
+/// representation read by replay.
+fn epoch_change_event(
+    event: &Event,
+    tx_digest: TransactionDigest,
+    event_seq: u64,
+    timestamp_ms: u64,
+) -> Result<IotaEvent, ReplayEngineError> {
+    let info: SystemEpochInfoEventV1 =
+        bcs::from_bytes(&event.contents).map_err(anyhow::Error::from)?;
+    Ok(IotaEvent {
+        id: EventID {
+            tx_digest,
+            event_seq,
+        },
+        package_id: event.package_id,
+        transaction_module: event.transaction_module.clone(),
+        sender: event.sender,
+        type_: event.type_.clone(),
+        parsed_json: serde_json::json!({
+            "epoch": info.epoch.to_string(),
+            "protocol_version": info.protocol_version.to_string(),
+            "reference_gas_price": info.reference_gas_price.to_string(),
+        }),
+        bcs: event.contents.clone(),
+        timestamp_ms: Some(timestamp_ms),
+    })
+}
+
+/// Builds the epoch change event of a change epoch command, which lacks the
+/// reference gas price of the new epoch.
+fn change_epoch_event(
+    change: &ChangeEpoch,
+    tx_digest: TransactionDigest,
+    timestamp_ms: u64,
+) -> Result<IotaEvent, ReplayEngineError> {
+    Ok(IotaEvent {
+        id: EventID {
+            tx_digest,
+            event_seq: 0,
+        },
+        package_id: IOTA_SYSTEM_PACKAGE_ID,
+        transaction_module: Identifier::new("iota_system").map_err(anyhow::Error::from)?,
+        sender: IotaAddress::ZERO,
+        type_: parse_struct_tag(EPOCH_CHANGE_STRUCT_TAG)?,
+        parsed_json: serde_json::json!({
+            "epoch": change.epoch.to_string(),
+            "protocol_version": change.protocol_version.as_u64().to_string(),
+        }),
+        bcs: vec![],
+        timestamp_ms: Some(timestamp_ms),
+    })
+}
+
+#[async_trait]
+impl DataFetcher for LocalArchiveFetcher {
+    async fn multi_get_versioned(
+        &self,
+        objects: &[(ObjectID, SequenceNumber)],
+    ) -> Result<Vec<Object>, ReplayEngineError> {
+        objects
+            .iter()
+            .map(|(id, version)| {
+                self.object(id, *version)?
+                    .ok_or(ReplayEngineError::ObjectVersionNotFound {
+                        id: *id,
+                        version: *version,
+                    })
+            })
+            .collect()
+    }
+
+    async fn multi_get_latest(
+        &self,
+        objects: &[ObjectID],
+    ) -> Result<Vec<Object>, ReplayEngineError> {
+        objects
+            .iter()
+            .map(|id| {
+                self.latest_object_before(id, SequenceNumber::MAX)?
+                    .ok_or(ReplayEngineError::ObjectNotExist { id: *id })
+            })
+            .collect()
+    }
+
+    async fn get_checkpoint_txs(
+        &self,
+        id: u64,
+    ) -> Result<Vec<TransactionDigest>, ReplayEngineError> {
+        self.index
+            .checkpoints
+            .get(&id)
+            .map(|entry| entry.transactions.clone())
+            .ok_or(ReplayEngineError::CheckpointNotFound {
+                sequence_number: id,
+            })
+    }
+
+    async fn get_transaction(
+        &self,
+        tx_digest: &TransactionDigest,
+    ) -> Result<IotaTransactionBlockResponse, ReplayEngineError> {
+        let (checkpoint, transaction) = self.transaction(tx_digest)?;
+        let mut response = IotaTransactionBlockResponse::new(*tx_digest);
+        response.raw_transaction =
+            bcs::to_bytes(transaction.transaction.data()).map_err(anyhow::Error::from)?;
+        response.raw_effects = bcs::to_bytes(&transaction.effects).map_err(anyhow::Error::from)?;
+        response.effects = Some(IotaTransactionBlockEffects::try_from(transaction.effects)?);
+        response.checkpoint = Some(checkpoint);
+        response.timestamp_ms = self
+            .index
+            .checkpoints
+            .get(&checkpoint)
+            .map(|entry| entry.timestamp_ms);
+        Ok(response)
+    }
+
+    async fn get_loaded_child_objects(
+        &self,
+        tx_digest: &TransactionDigest,
+    ) -> Result<Vec<(ObjectID, SequenceNumber)>, ReplayEngineError> {
+        // Child objects that were only read are not part of the effects, they
+        // are fetched through `get_child_object` during execution instead.
+        let (_, transaction) = self.transaction(tx_digest)?;
+        let mut children = transaction
+            .effects
+            .old_object_metadata()
+            .into_iter()
+            .filter(|(_, owner)| matches!(owner, Owner::ObjectOwner(_)))
+            .map(|((id, version, _), _)| (id, version))
+            .collect::<BTreeSet<_>>();
+        children.extend(
+            transaction
+                .input_objects
+                .iter()
+                .filter(|object| matches!(object.owner, Owner::ObjectOwner(_)))
+                .map(|object| (object.id(), object.version())),
+        );
+        Ok(children.into_iter().collect())
+    }
+
+    async fn get_latest_checkpoint_sequence_number(&self) -> Result<u64, ReplayEngineError> {
+        self.index
+            .checkpoints
+            .last_key_value()
+            .map(|(sequence_number, _)| *sequence_number)
+            .ok_or(ReplayEngineError::CheckpointNotFound { sequence_number: 0 })
+    }
+
+    async fn fetch_random_transaction(
+        &self,
+        checkpoint_id_start_inclusive: Option<u64>,
+        checkpoint_id_end_inclusive: Option<u64>,
+    ) -> Result<TransactionDigest, ReplayEngineError> {
+        let transactions = self
+            .index
+            .checkpoints
+            .range(
+                checkpoint_id_start_inclusive.unwrap_or(1)
+                    ..=checkpoint_id_end_inclusive.unwrap_or(u64::MAX),
+            )
+            .flat_map(|(_, entry)| &entry.transactions)
+            .collect::<Vec<_>>();
+        if transactions.is_empty() {
+            return Err(ReplayEngineError::GeneralError {
+                err: "No transactions in the checkpoint range".to_string(),
+            });
+        }
+        Ok(*transactions[rand::thread_rng().gen_range(0..transactions.len())])
+    }
+
+    async fn get_epoch_start_timestamp_and_rgp(
+        &self,
+        epoch_id: u64,
+    ) -> Result<(u64, u64), ReplayEngineError> {
+        let event = self
+            .index
+            .epoch_change_events
+            .iter()
+            .find(|ev| match extract_epoch_and_version((*ev).clone()) {
+                Ok((epoch, _)) => epoch == epoch_id,
+                Err(_) => false,
+            })
+            .ok_or(ReplayEngineError::EventNotFound { epoch: epoch_id })?;
+        let reference_gas_price = match event.parsed_json.get("reference_gas_price") {
+            Some(rgp) => rgp
+                .as_str()
+                .and_then(|rgp| rgp.parse().ok())
+                .ok_or_else(|| ReplayEngineError::UnexpectedEventFormat {
+                    event: Box::new(event.clone()),
+                })?,
+            None => self.snapshot_reference_gas_price(epoch_id)?,
+        };
+        let (_, transaction) = self.transaction(&event.id.tx_digest)?;
+        change_epoch(&transaction.transaction)
+            .map(|change| (change.epoch_start_timestamp_ms, reference_gas_price))
+            .ok_or(ReplayEngineError::InvalidEpochChangeTx { epoch: epoch_id })
+    }
+
+    async fn get_epoch_change_events(
+        &self,
+        reverse: bool,
+    ) -> Result<Vec<IotaEvent>, ReplayEngineError> {
+        let mut events = self.index.epoch_change_events.clone();
+        if reverse {
+            events.reverse();
+        }
+        Ok(events)
+    }
+
+    async fn get_chain_id(&self) -> Result<String, ReplayEngineError> {
+        self.index
+            .chain_id
+            .clone()
+            .ok_or_else(|| ReplayEngineError::UnableToGetChainId {
+                err: "Genesis checkpoint not found in the archive".to_string(),
+            })
+    }
+
+    async fn get_child_object(
+        &self,
+        object_id: &ObjectID,
+        version_upper_bound: VersionNumber,
+    ) -> Result<Object, ReplayEngineError> {
+        self.latest_object_before(object_id, version_upper_bound)?
+            .ok_or(ReplayEngineError::ObjectNotExist { id: *object_id })
+    }
+}
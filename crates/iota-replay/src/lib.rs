@@ -15,8 +15,10 @@ use iota_types::{
     base_types::{ObjectID, SequenceNumber},
     digests::{TransactionDigest, get_mainnet_chain_identifier, get_testnet_chain_identifier},
     message_envelope::Message,
+    transaction::TransactionData,
 };
 use move_vm_config::runtime::get_default_output_filepath;
+use move_vm_profiler::GasProfileSummary;
 use tracing::{error, info, warn};
 use transaction_provider::{FuzzStartPoint, TransactionSource};
 
@@ -143,6 +145,24 @@ pub enum ReplayToolCommand {
         show_effects: bool,
     },
 
+    /// Replay a transaction from the checkpoints in a local directory, as
+    /// written by the blob worker of the ingestion framework or in the
+    /// archive format of iota-archival
+    /// This is a completely local execution
+    #[command(name = "ra")]
+    ReplayArchive {
+        #[arg(long, short)]
+        path: PathBuf,
+        /// Directory of a local formal snapshot of the epoch preceding the
+        /// archive, used for the objects not written within the archive
+        #[arg(long)]
+        snapshot: Option<PathBuf>,
+        #[arg(long, short)]
+        tx_digest: String,
+        #[arg(long, short)]
+        show_effects: bool,
+    },
+
     /// Replay multiple transactions from JSON files that contain the sandbox
     /// persisted state.
     #[command(name = "brd")]
@@ -294,6 +314,27 @@ pub async fn execute_replay_command(
             info!("Execution finished successfully. Local and on-chain effects match.");
             Some((1u64, 1u64))
         }
+        ReplayToolCommand::ReplayArchive {
+            path,
+            snapshot,
+            tx_digest,
+            show_effects,
+        } => {
+            let tx_digest = TransactionDigest::from_str(&tx_digest)?;
+            let mut lx = LocalExec::new_for_local_archive(&path, snapshot.as_deref())
+                .await?
+                .init_for_execution()
+                .await?;
+            let sandbox_state = lx.execution_engine_execute(&tx_digest, safety).await?;
+            if show_effects {
+                println!("{:#?}", sandbox_state.local_exec_effects);
+            }
+
+            sandbox_state.check_effects()?;
+
+            info!("Execution finished successfully. Local and on-chain effects match.");
+            Some((1u64, 1u64))
+        }
         ReplayToolCommand::ReplayBatch {
             path,
             terminate_early,
@@ -575,6 +616,50 @@ pub async fn execute_replay_command(
     })
 }
 
+/// A transaction to run with the gas profiler.
+pub enum ProfiledTransaction {
+    /// A transaction executed on chain, replayed with the state it was
+    /// executed with.
+    Executed(TransactionDigest),
+    /// Transaction data which was not executed, dry run against the latest
+    /// state of the network.
+    DryRun(TransactionData),
+}
+
+/// Runs a transaction with the gas profiler enabled. The profile is written to
+/// `profile_output` (or the default output filepath) and its breakdown per PTB
+/// command and per Move function is returned. Returns `None` if the
+/// `gas-profiler` feature is not enabled.
+pub async fn profile_transaction(
+    rpc_url: String,
+    transaction: ProfiledTransaction,
+    profile_output: Option<PathBuf>,
+) -> anyhow::Result<Option<GasProfileSummary>> {
+    let output_path = profile_output.or(Some(get_default_output_filepath()));
+    let safety = ExpensiveSafetyCheckConfig::default();
+    let sandbox_state = match transaction {
+        ProfiledTransaction::Executed(tx_digest) => {
+            info!("Executing tx: {}", tx_digest);
+            LocalExec::replay_with_network_config(
+                rpc_url,
+                tx_digest,
+                safety,
+                false,
+                None,
+                None,
+                output_path,
+                None,
+            )
+            .await?
+        }
+        ProfiledTransaction::DryRun(tx_data) => {
+            info!("Dry running tx: {}", tx_data.digest());
+            LocalExec::dry_run_with_network_config(rpc_url, tx_data, safety, output_path).await?
+        }
+    };
+    Ok(sandbox_state.gas_profile)
+}
+
 pub(crate) fn chain_from_chain_id(chain: &str) -> Chain {
     let mainnet_chain_id = format!("{}", get_mainnet_chain_identifier());
     // TODO: Since testnet periodically resets, we need to ensure that the chain id
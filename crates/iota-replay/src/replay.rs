@@ -4,7 +4,7 @@
 
 use std::{
     collections::{BTreeMap, HashSet},
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
 };
 
@@ -25,7 +25,7 @@ use iota_types::{
     digests::{ObjectDigest, TransactionDigest},
     error::{ExecutionError, IotaError, IotaResult},
     executable_transaction::VerifiedExecutableTransaction,
-    gas::IotaGasStatus,
+    gas::{IotaGasStatus, IotaGasStatusAPI},
     in_memory_storage::InMemoryStorage,
     inner_temporary_store::InnerTemporaryStore,
     message_envelope::Message,
@@ -37,7 +37,7 @@ use iota_types::{
     },
     transaction::{
         CheckedInputObjects, InputObjectKind, InputObjects, ObjectReadResult, ObjectReadResultKind,
-        SenderSignedData, Transaction, TransactionDataAPI, TransactionKind,
+        SenderSignedData, Transaction, TransactionData, TransactionDataAPI, TransactionKind,
         TransactionKind::ProgrammableTransaction, VerifiedTransaction,
     },
 };
@@ -48,6 +48,7 @@ use move_core_types::{
     language_storage::{ModuleId, StructTag},
     resolver::{ModuleResolver, ResourceResolver},
 };
+use move_vm_profiler::{GasProfileSummary, GasProfiler};
 use prometheus::Registry;
 use serde::{Deserialize, Serialize};
 use similar::{ChangeTag, TextDiff};
@@ -56,7 +57,8 @@ use tracing::{error, info, trace, warn};
 use crate::{
     chain_from_chain_id,
     data_fetcher::{
-        DataFetcher, Fetchers, NodeStateDumpFetcher, RemoteFetcher, extract_epoch_and_version,
+        DataFetcher, Fetchers, LocalArchiveFetcher, NodeStateDumpFetcher, RemoteFetcher,
+        extract_epoch_and_version,
     },
     displays::{
         Pretty,
@@ -82,6 +84,10 @@ pub struct ExecutionSandboxState {
     /// Status from executing this locally in `execute_transaction_to_effects`
     #[serde(skip)]
     pub local_exec_status: Option<Result<(), ExecutionError>>,
+    /// Gas usage per PTB command and Move function, if the gas profiler was
+    /// enabled for this execution
+    #[serde(skip)]
+    pub gas_profile: Option<GasProfileSummary>,
 }
 
 impl ExecutionSandboxState {
@@ -348,6 +354,30 @@ impl LocalExec {
             .await
     }
 
+    /// Executes transaction data which was not executed on chain, against the
+    /// latest state of the network. The versions of the objects it uses are
+    /// taken from a dry run on the RPC node.
+    pub async fn dry_run_with_network_config(
+        rpc_url: String,
+        tx_data: TransactionData,
+        expensive_safety_check_config: ExpensiveSafetyCheckConfig,
+        enable_profiler: Option<PathBuf>,
+    ) -> Result<ExecutionSandboxState, ReplayEngineError> {
+        info!("Using RPC URL: {}", rpc_url);
+        let mut exec = LocalExec::new_from_fn_url(&rpc_url)
+            .await?
+            .init_for_execution()
+            .await?;
+        exec.enable_profiler = enable_profiler;
+        let tx_info = exec.resolve_dry_run_tx_components(tx_data).await?;
+        exec.execution_engine_execute_with_tx_info_impl(
+            &tx_info,
+            None,
+            expensive_safety_check_config,
+        )
+        .await
+    }
+
     /// This captures the state of the network at a given point in time and
     /// populates prptocol version tables including which system packages to
     /// fetch If this function is called across epoch boundaries, the info
@@ -440,6 +470,40 @@ impl LocalExec {
         })
     }
 
+    /// Replays transactions from the checkpoints in a local directory, either
+    /// written by the blob worker of the ingestion framework or in the archive
+    /// format of `iota-archival`. Objects last written before the checkpoints
+    /// of the directory are read from the formal snapshot at `snapshot`, if
+    /// given. See [`LocalArchiveFetcher`].
+    pub async fn new_for_local_archive(
+        path: &Path,
+        snapshot: Option<&Path>,
+    ) -> Result<Self, ReplayEngineError> {
+        // Use a throwaway metrics registry for local execution.
+        let registry = prometheus::Registry::new();
+        let metrics = Arc::new(LimitsMetrics::new(&registry));
+
+        let fetcher = LocalArchiveFetcher::new(path, snapshot)?;
+
+        Ok(Self {
+            client: None,
+            protocol_version_epoch_table: BTreeMap::new(),
+            protocol_version_system_package_table: BTreeMap::new(),
+            current_protocol_version: 0,
+            exec_store_events: Arc::new(Mutex::new(Vec::new())),
+            metrics,
+            storage: Storage::default(),
+            fetcher: Fetchers::LocalArchive(fetcher),
+            // TODO: make these configurable
+            num_retries_for_timeout: RPC_TIMEOUT_ERR_NUM_RETRIES,
+            sleep_period_for_timeout: RPC_TIMEOUT_ERR_SLEEP_RETRY_PERIOD,
+            executor_version: None,
+            protocol_version: None,
+            enable_profiler: None,
+            config_and_versions: None,
+        })
+    }
+
     pub async fn multi_download_and_store(
         &mut self,
         objs: &[(ObjectID, SequenceNumber)],
@@ -807,6 +871,10 @@ impl LocalExec {
             local_exec_temporary_store: Some(inner_store),
             local_exec_effects: effects,
             local_exec_status: Some(result),
+            gas_profile: gas_status
+                .move_gas_status()
+                .profiler()
+                .map(GasProfiler::summary),
         })
     }
 
@@ -868,7 +936,7 @@ impl LocalExec {
         tx_digest: &TransactionDigest,
         expensive_safety_check_config: ExpensiveSafetyCheckConfig,
     ) -> Result<ExecutionSandboxState, ReplayEngineError> {
-        if self.is_remote_replay() {
+        if !self.is_state_dump_replay() {
             assert!(
                 !self.protocol_version_system_package_table.is_empty()
                     || !self.protocol_version_epoch_table.is_empty(),
@@ -876,10 +944,10 @@ impl LocalExec {
             );
         }
 
-        let tx_info = if self.is_remote_replay() {
-            self.resolve_tx_components(tx_digest).await?
-        } else {
+        let tx_info = if self.is_state_dump_replay() {
             self.resolve_tx_components_from_dump(tx_digest).await?
+        } else {
+            self.resolve_tx_components(tx_digest).await?
         };
         self.execution_engine_execute_with_tx_info_impl(
             &tx_info,
@@ -953,6 +1021,7 @@ impl LocalExec {
             local_exec_temporary_store: None, // We dont capture it for cert exec run
             local_exec_effects: effects,
             local_exec_status: Some(exec_res),
+            gas_profile: None,
         })
     }
 
@@ -990,7 +1059,7 @@ impl LocalExec {
         &mut self,
         expensive_safety_check_config: ExpensiveSafetyCheckConfig,
     ) -> Result<(ExecutionSandboxState, NodeStateDump), ReplayEngineError> {
-        assert!(!self.is_remote_replay());
+        assert!(self.is_state_dump_replay());
 
         let d = match self.fetcher.clone() {
             Fetchers::NodeStateDump(d) => d,
@@ -1091,13 +1160,17 @@ impl LocalExec {
         matches!(self.fetcher, Fetchers::Remote(_))
     }
 
+    pub fn is_state_dump_replay(&self) -> bool {
+        matches!(self.fetcher, Fetchers::NodeStateDump(_))
+    }
+
     /// Must be called after `populate_protocol_version_tables`
     pub fn system_package_versions_for_protocol_version(
         &self,
         protocol_version: u64,
     ) -> Result<Vec<(ObjectID, SequenceNumber)>, ReplayEngineError> {
         match &self.fetcher {
-            Fetchers::Remote(_) => Ok(self
+            Fetchers::Remote(_) | Fetchers::LocalArchive(_) => Ok(self
                 .protocol_version_system_package_table
                 .get(&protocol_version)
                 .ok_or(ReplayEngineError::FrameworkObjectVersionTableNotPopulated {
@@ -1474,23 +1547,54 @@ impl LocalExec {
         &self,
         tx_digest: &TransactionDigest,
     ) -> Result<OnChainTransactionInfo, ReplayEngineError> {
-        assert!(self.is_remote_replay());
+        assert!(!self.is_state_dump_replay());
         // Fetch full transaction content
         let tx_info = self.fetcher.get_transaction(tx_digest).await?;
-        let sender = match tx_info.clone().transaction.unwrap().data {
-            iota_json_rpc_types::IotaTransactionBlockData::V1(tx) => tx.sender,
-        };
-        let IotaTransactionBlockEffects::V1(effects) = tx_info.clone().effects.unwrap();
-
-        let config_objects = self.add_config_objects_if_needed(effects.status());
+        let effects = tx_info.clone().effects.unwrap();
 
         let raw_tx_bytes = tx_info.clone().raw_transaction;
         let orig_tx: SenderSignedData = bcs::from_bytes(&raw_tx_bytes).unwrap();
+        self.tx_components_from_effects(tx_digest, orig_tx, effects)
+            .await
+    }
+
+    /// Resolves the components of transaction data which was not executed on
+    /// chain, using the effects of a dry run.
+    async fn resolve_dry_run_tx_components(
+        &self,
+        tx_data: TransactionData,
+    ) -> Result<OnChainTransactionInfo, ReplayEngineError> {
+        assert!(!self.is_state_dump_replay());
+        let client = self.client.as_ref().expect("Remote client not initialized");
+        let response = client
+            .read_api()
+            .dry_run_transaction_block(tx_data.clone())
+            .await?;
+        let tx_digest = tx_data.digest();
+        self.tx_components_from_effects(
+            &tx_digest,
+            SenderSignedData::new(tx_data, vec![]),
+            response.effects,
+        )
+        .await
+    }
+
+    async fn tx_components_from_effects(
+        &self,
+        tx_digest: &TransactionDigest,
+        orig_tx: SenderSignedData,
+        effects: IotaTransactionBlockEffects,
+    ) -> Result<OnChainTransactionInfo, ReplayEngineError> {
+        let IotaTransactionBlockEffects::V1(effects) = effects;
+
+        let config_objects = self.add_config_objects_if_needed(effects.status());
+
         let input_objs = orig_tx
             .transaction_data()
             .input_objects()
             .map_err(|e| ReplayEngineError::UserInputError { err: e })?;
         let tx_kind_orig = orig_tx.transaction_data().kind();
+        let sender = orig_tx.transaction_data().sender();
 
         // Download the objects at the version right before the execution of this TX
         let modified_at_versions: Vec<(ObjectID, SequenceNumber)> = effects.modified_at_versions();
@@ -1508,14 +1612,8 @@ impl LocalExec {
                 }
             })
             .collect();
-        let gas_data = match tx_info.clone().transaction.unwrap().data {
-            iota_json_rpc_types::IotaTransactionBlockData::V1(tx) => tx.gas_data,
-        };
-        let gas_object_refs: Vec<_> = gas_data
-            .payment
-            .iter()
-            .map(|obj_ref| obj_ref.to_object_ref())
-            .collect();
+        let gas_data = orig_tx.transaction_data().gas_data();
+        let gas_object_refs = gas_data.payment.clone();
         let receiving_objs = orig_tx
             .transaction_data()
             .receiving_objects()
@@ -1561,7 +1659,7 @@ impl LocalExec {
         &self,
         tx_digest: &TransactionDigest,
     ) -> Result<OnChainTransactionInfo, ReplayEngineError> {
-        assert!(!self.is_remote_replay());
+        assert!(self.is_state_dump_replay());
 
         let dp = self.fetcher.as_node_state_dump();
 
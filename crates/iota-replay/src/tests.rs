@@ -2,15 +2,51 @@
 // Modifications Copyright (c) 2024 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use iota_config::node::ExpensiveSafetyCheckConfig;
+use std::{
+    collections::BTreeMap,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use bytes::Bytes;
+use fastcrypto::hash::MultisetHash;
+use iota_archival::{
+    CHECKPOINT_FILE_MAGIC, FileType, Manifest, SUMMARY_FILE_MAGIC,
+    create_file_metadata_from_bytes, finalize_manifest,
+};
+use iota_config::{
+    node::ExpensiveSafetyCheckConfig,
+    object_storage_config::{ObjectStoreConfig, ObjectStoreType},
+};
+use iota_core::{
+    authority::authority_store_tables::AuthorityPerpetualTables,
+    state_accumulator::StateAccumulator,
+};
 use iota_json_rpc_api::QUERY_MAX_RESULT_LIMIT;
 use iota_json_rpc_types::IotaTransactionBlockResponseOptions;
 use iota_sdk::{IotaClient, IotaClientBuilder};
-use iota_types::{base_types::IotaAddress, digests::TransactionDigest};
+use iota_snapshot::writer::StateSnapshotWriterV1;
+use iota_storage::{
+    FileCompression, StorageFormat,
+    blob::{Blob, BlobEncoding},
+};
+use iota_types::{
+    accumulator::Accumulator,
+    base_types::{ExecutionData, IotaAddress, ObjectID},
+    digests::TransactionDigest,
+    effects::TransactionEffectsAPI,
+    full_checkpoint_content::CheckpointData,
+    messages_checkpoint::{ECMHLiveObjectSetDigest, FullCheckpointContents},
+    object::Object,
+};
+use serde::Serialize;
+use simulacrum::Simulacrum;
 
 use crate::{
     LocalExec,
     config::ReplayableNetworkConfigSet,
+    data_fetcher::{DataFetcher, LocalArchiveFetcher},
     types::{MAX_CONCURRENT_REQUESTS, RPC_TIMEOUT_ERR_SLEEP_RETRY_PERIOD, ReplayEngineError},
 };
 
@@ -175,3 +211,346 @@ async fn execute_replay(url: &str, tx: &TransactionDigest) -> Result<(), ReplayE
 
     Ok(())
 }
+
+#[tokio::test]
+async fn local_archive_fetcher_serves_ingested_checkpoints() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut sim = Simulacrum::new();
+    sim.set_data_ingestion_path(dir.path().to_path_buf());
+    let (transaction, _) = sim.transfer_txn(IotaAddress::random_for_testing_only());
+    let tx_digest = *transaction.digest();
+    let (effects, error) = sim.execute_transaction(transaction).unwrap();
+    assert!(error.is_none());
+    let checkpoint = sim.create_checkpoint();
+
+    let fetcher = LocalArchiveFetcher::new(dir.path(), None).unwrap();
+    assert_eq!(
+        fetcher
+            .get_latest_checkpoint_sequence_number()
+            .await
+            .unwrap(),
+        checkpoint.sequence_number
+    );
+    assert_eq!(
+        fetcher
+            .get_checkpoint_txs(checkpoint.sequence_number)
+            .await
+            .unwrap(),
+        vec![tx_digest]
+    );
+    assert!(fetcher.get_chain_id().await.is_ok());
+
+    let response = fetcher.get_transaction(&tx_digest).await.unwrap();
+    assert_eq!(response.checkpoint, Some(checkpoint.sequence_number));
+    assert_eq!(
+        bcs::from_bytes::<iota_types::effects::TransactionEffects>(&response.raw_effects).unwrap(),
+        effects
+    );
+
+    // Both the inputs and the outputs of the transaction are available
+    let objects = fetcher
+        .multi_get_versioned(&effects.modified_at_versions())
+        .await
+        .unwrap();
+    assert_eq!(objects.len(), effects.modified_at_versions().len());
+    let gas_object = effects.gas_object().0;
+    assert_eq!(
+        fetcher.multi_get_latest(&[gas_object.0]).await.unwrap()[0].version(),
+        gas_object.1
+    );
+    // A transfer loads no child objects
+    assert!(
+        fetcher
+            .get_loaded_child_objects(&tx_digest)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert!(matches!(
+        fetcher.get_transaction(&TransactionDigest::random()).await,
+        Err(ReplayEngineError::TransactionNotFound { .. })
+    ));
+
+    // Objects of checkpoints missing from the archive are not available
+    std::fs::remove_file(
+        dir.path()
+            .join(format!("{}.chk", checkpoint.sequence_number)),
+    )
+    .unwrap();
+    let fetcher = LocalArchiveFetcher::new(dir.path(), None).unwrap();
+    assert!(matches!(
+        fetcher
+            .multi_get_versioned(&[(gas_object.0, gas_object.1)])
+            .await,
+        Err(ReplayEngineError::ObjectVersionNotFound { .. })
+    ));
+}
+
+/// Runs a transfer in the second epoch of a new chain whose checkpoints are
+/// written to `dir` by the blob worker, returning the digest of the transfer
+/// and the checkpoints.
+fn ingest_transfer_after_epoch_change(dir: &Path) -> (TransactionDigest, Vec<CheckpointData>) {
+    let mut sim = Simulacrum::new();
+    sim.set_data_ingestion_path(dir.to_path_buf());
+    sim.advance_epoch();
+    let (transaction, _) = sim.transfer_txn(IotaAddress::random_for_testing_only());
+    let tx_digest = *transaction.digest();
+    let (_, error) = sim.execute_transaction(transaction).unwrap();
+    assert!(error.is_none());
+    let checkpoint = sim.create_checkpoint();
+
+    let checkpoints = (0..=checkpoint.sequence_number)
+        .map(|sequence_number| {
+            let bytes = std::fs::read(dir.join(format!("{sequence_number}.chk"))).unwrap();
+            Blob::from_bytes::<CheckpointData>(&bytes).unwrap()
+        })
+        .collect();
+    (tx_digest, checkpoints)
+}
+
+/// Encodes `values` as a file of the `iota-archival` format.
+fn archive_file<T: Serialize>(magic: u32, values: impl IntoIterator<Item = T>) -> Bytes {
+    let mut bytes = magic.to_be_bytes().to_vec();
+    bytes.push(StorageFormat::Blob.into());
+    bytes.push(FileCompression::None.into());
+    for value in values {
+        Blob::encode(&value, BlobEncoding::Bcs)
+            .unwrap()
+            .write(&mut bytes)
+            .unwrap();
+    }
+    Bytes::from(bytes)
+}
+
+/// Writes `checkpoints` to `dir` in the archive format of `iota-archival`, as a
+/// single summary and contents file behind a `MANIFEST`.
+fn write_archival_archive(checkpoints: &[CheckpointData], dir: &Path) {
+    let first = checkpoints
+        .first()
+        .unwrap()
+        .checkpoint_summary
+        .sequence_number;
+    let last = checkpoints
+        .last()
+        .unwrap()
+        .checkpoint_summary
+        .sequence_number;
+    let epoch = checkpoints.last().unwrap().checkpoint_summary.epoch;
+    let summaries = archive_file(
+        SUMMARY_FILE_MAGIC,
+        checkpoints
+            .iter()
+            .map(|checkpoint| checkpoint.checkpoint_summary.clone()),
+    );
+    let contents = archive_file(
+        CHECKPOINT_FILE_MAGIC,
+        checkpoints.iter().map(|checkpoint| {
+            FullCheckpointContents::from_contents_and_execution_data(
+                checkpoint.checkpoint_contents.clone(),
+                checkpoint.transactions.iter().map(|transaction| {
+                    ExecutionData::new(transaction.transaction.clone(), transaction.effects.clone())
+                }),
+            )
+        }),
+    );
+
+    let mut manifest = Manifest::new(epoch, last + 1);
+    let write_file = |bytes: Bytes, file_type| {
+        let metadata =
+            create_file_metadata_from_bytes(bytes.clone(), file_type, epoch, first..last + 1)
+                .unwrap();
+        let path = dir.join(metadata.file_path().to_string());
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, bytes).unwrap();
+        metadata
+    };
+    let contents = write_file(contents, FileType::CheckpointContent);
+    let summaries = write_file(summaries, FileType::CheckpointSummary);
+    manifest.update(epoch, last + 1, contents, summaries);
+    std::fs::write(dir.join("MANIFEST"), finalize_manifest(manifest).unwrap()).unwrap();
+}
+
+/// Writes a formal snapshot of the objects live after `checkpoints` to
+/// `dir/epoch_{epoch}` and returns its path.
+async fn write_snapshot(checkpoints: &[CheckpointData], epoch: u64, dir: &Path) -> PathBuf {
+    let mut live_objects = BTreeMap::<ObjectID, Object>::new();
+    for transaction in checkpoints
+        .iter()
+        .flat_map(|checkpoint| &checkpoint.transactions)
+    {
+        for object in &transaction.output_objects {
+            live_objects.insert(object.id(), object.clone());
+        }
+        let effects = &transaction.effects;
+        for (id, ..) in effects
+            .deleted()
+            .into_iter()
+            .chain(effects.wrapped())
+            .chain(effects.unwrapped_then_deleted())
+        {
+            live_objects.remove(&id);
+        }
+    }
+
+    let perpetual_db = Arc::new(AuthorityPerpetualTables::open(
+        &tempfile::tempdir().unwrap().into_path(),
+        None,
+    ));
+    for object in live_objects.into_values() {
+        perpetual_db.insert_object_test_only(object).unwrap();
+    }
+    let mut accumulator = Accumulator::default();
+    for live_object in perpetual_db.iter_live_object_set() {
+        StateAccumulator::accumulate_live_object(&mut accumulator, &live_object);
+    }
+
+    let store_config = |directory: PathBuf| ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(directory),
+        ..Default::default()
+    };
+    StateSnapshotWriterV1::new(
+        &store_config(tempfile::tempdir().unwrap().into_path()),
+        &store_config(dir.to_path_buf()),
+        FileCompression::Zstd,
+        NonZeroUsize::new(1).unwrap(),
+    )
+    .await
+    .unwrap()
+    .write(
+        epoch,
+        perpetual_db,
+        ECMHLiveObjectSetDigest::from(accumulator.digest()),
+    )
+    .await
+    .unwrap();
+    dir.join(format!("epoch_{epoch}"))
+}
+
+#[tokio::test]
+async fn local_archive_fetcher_reads_archival_layout() {
+    let blob_dir = tempfile::tempdir().unwrap();
+    let (tx_digest, checkpoints) = ingest_transfer_after_epoch_change(blob_dir.path());
+    let archive_dir = tempfile::tempdir().unwrap();
+    write_archival_archive(&checkpoints, archive_dir.path());
+
+    let fetcher = LocalArchiveFetcher::new(archive_dir.path(), None).unwrap();
+    let last = checkpoints.last().unwrap();
+    assert_eq!(
+        fetcher
+            .get_latest_checkpoint_sequence_number()
+            .await
+            .unwrap(),
+        last.checkpoint_summary.sequence_number
+    );
+    assert_eq!(
+        fetcher
+            .get_checkpoint_txs(last.checkpoint_summary.sequence_number)
+            .await
+            .unwrap(),
+        vec![tx_digest]
+    );
+    assert!(fetcher.get_chain_id().await.is_ok());
+    let transaction = &last.transactions[0];
+    let response = fetcher.get_transaction(&tx_digest).await.unwrap();
+    assert_eq!(
+        bcs::from_bytes::<iota_types::effects::TransactionEffects>(&response.raw_effects).unwrap(),
+        transaction.effects
+    );
+
+    // The epoch change is recovered from the change epoch transaction, but the
+    // archive holds neither objects nor the reference gas price
+    assert_eq!(
+        fetcher.get_epoch_change_events(false).await.unwrap().len(),
+        1
+    );
+    assert!(fetcher.get_epoch_start_timestamp_and_rgp(1).await.is_err());
+    let gas_object = transaction.effects.gas_object().0;
+    assert!(matches!(
+        fetcher
+            .multi_get_versioned(&[(gas_object.0, gas_object.1)])
+            .await,
+        Err(ReplayEngineError::ObjectVersionNotFound { .. })
+    ));
+}
+
+#[tokio::test]
+async fn local_archive_fetcher_reads_snapshot_base() {
+    let blob_dir = tempfile::tempdir().unwrap();
+    let (tx_digest, checkpoints) = ingest_transfer_after_epoch_change(blob_dir.path());
+    let (transfer, epoch_0) = checkpoints.split_last().unwrap();
+    let archive_dir = tempfile::tempdir().unwrap();
+    write_archival_archive(&checkpoints, archive_dir.path());
+    let snapshot_dir = tempfile::tempdir().unwrap();
+    let snapshot = write_snapshot(epoch_0, 0, snapshot_dir.path()).await;
+
+    let fetcher = LocalArchiveFetcher::new(archive_dir.path(), Some(&snapshot)).unwrap();
+    let transaction = &transfer.transactions[0];
+    assert_eq!(*transaction.transaction.digest(), tx_digest);
+    // The inputs of the transfer were last written before it, so they are read
+    // from the snapshot base
+    let inputs = transaction.effects.modified_at_versions();
+    let objects = fetcher.multi_get_versioned(&inputs).await.unwrap();
+    assert_eq!(objects.len(), inputs.len());
+    assert!(
+        objects
+            .iter()
+            .all(|object| transaction.input_objects.contains(object))
+    );
+    assert_eq!(
+        fetcher.multi_get_latest(&[inputs[0].0]).await.unwrap()[0].version(),
+        inputs[0].1
+    );
+    // Only the versions held by the snapshot are available
+    let gas_object = transaction.effects.gas_object().0;
+    assert!(
+        fetcher
+            .multi_get_versioned(&[(gas_object.0, gas_object.1)])
+            .await
+            .is_err()
+    );
+    // The reference gas price of the epoch following the snapshot comes from its
+    // system state
+    let (_, reference_gas_price) = fetcher.get_epoch_start_timestamp_and_rgp(1).await.unwrap();
+    assert!(reference_gas_price > 0);
+
+    // A snapshot base needs the MANIFEST of a formal snapshot
+    assert!(LocalArchiveFetcher::new(archive_dir.path(), Some(blob_dir.path())).is_err());
+}
+
+#[tokio::test]
+async fn replay_from_local_archive() {
+    let blob_dir = tempfile::tempdir().unwrap();
+    let (tx_digest, checkpoints) = ingest_transfer_after_epoch_change(blob_dir.path());
+    let (_, epoch_0) = checkpoints.split_last().unwrap();
+
+    // Checkpoints written by the blob worker hold all objects the transfer needs
+    LocalExec::new_for_local_archive(blob_dir.path(), None)
+        .await
+        .unwrap()
+        .init_for_execution()
+        .await
+        .unwrap()
+        .execution_engine_execute(&tx_digest, ExpensiveSafetyCheckConfig::default())
+        .await
+        .unwrap()
+        .check_effects()
+        .unwrap();
+
+    // An iota-archival archive needs the snapshot base for the objects
+    let archive_dir = tempfile::tempdir().unwrap();
+    write_archival_archive(&checkpoints, archive_dir.path());
+    let snapshot_dir = tempfile::tempdir().unwrap();
+    let snapshot = write_snapshot(epoch_0, 0, snapshot_dir.path()).await;
+    LocalExec::new_for_local_archive(archive_dir.path(), Some(&snapshot))
+        .await
+        .unwrap()
+        .init_for_execution()
+        .await
+        .unwrap()
+        .execution_engine_execute(&tx_digest, ExpensiveSafetyCheckConfig::default())
+        .await
+        .unwrap()
+        .check_effects()
+        .unwrap();
+}
@@ -203,6 +203,12 @@ pub enum ReplayEngineError {
 
     #[error("Unable to get chain id: {}", err)]
     UnableToGetChainId { err: String },
+
+    #[error("Transaction {:#?} not found", digest)]
+    TransactionNotFound { digest: TransactionDigest },
+
+    #[error("Checkpoint {sequence_number} not found")]
+    CheckpointNotFound { sequence_number: u64 },
 }
 
 impl From<IotaObjectResponseError> for ReplayEngineError {
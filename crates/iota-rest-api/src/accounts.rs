@@ -4,7 +4,7 @@
 
 use axum::extract::{Path, Query, State};
 use iota_sdk2::types::{Address, ObjectId, StructTag, Version};
-use iota_types::iota_sdk2_conversions::struct_tag_core_to_sdk;
+use iota_types::iota_sdk2_conversions::{struct_tag_core_to_sdk, struct_tag_sdk_to_core};
 use openapiv3::v3_1::Operation;
 use tap::Pipe;
 
@@ -110,3 +110,96 @@ pub struct AccountOwnedObjectInfo {
     #[serde(rename = "type")]
     pub type_: StructTag,
 }
+
+pub struct ListAccountBalances;
+
+impl ApiEndpoint<RestService> for ListAccountBalances {
+    fn method(&self) -> axum::http::Method {
+        axum::http::Method::GET
+    }
+
+    fn path(&self) -> &'static str {
+        "/accounts/{account}/balances"
+    }
+
+    fn operation(&self, generator: &mut schemars::gen::SchemaGenerator) -> Operation {
+        OperationBuilder::new()
+            .tag("Account")
+            .operation_id("ListAccountBalances")
+            .path_parameter::<Address>("account", generator)
+            .query_parameters::<ListAccountBalancesQueryParameters>(generator)
+            .response(
+                200,
+                ResponseBuilder::new()
+                    .json_content::<Vec<AccountBalanceInfo>>(generator)
+                    .header::<String>(crate::types::X_IOTA_CURSOR, generator)
+                    .build(),
+            )
+            .build()
+    }
+
+    fn handler(&self) -> crate::openapi::RouteHandler<RestService> {
+        RouteHandler::new(self.method(), list_account_balances)
+    }
+}
+
+async fn list_account_balances(
+    Path(address): Path<Address>,
+    Query(parameters): Query<ListAccountBalancesQueryParameters>,
+    State(state): State<StateReader>,
+) -> Result<Page<AccountBalanceInfo, StructTag>> {
+    let limit = parameters.limit();
+    let start = parameters.start.map(struct_tag_sdk_to_core);
+
+    let mut balances = state
+        .inner()
+        .account_balances_iter(address.into(), start)?
+        .map(|info| AccountBalanceInfo {
+            owner: info.owner.into(),
+            coin_type: struct_tag_core_to_sdk(info.coin_type),
+            balance: info.balance,
+            coin_object_count: info.coin_object_count,
+        })
+        .take(limit + 1)
+        .collect::<Vec<_>>();
+
+    let cursor = if balances.len() > limit {
+        // SAFETY: We've already verified that balances is greater than limit, which
+        // is gaurenteed to be >= 1.
+        balances.pop().unwrap().coin_type.pipe(Some)
+    } else {
+        None
+    };
+
+    balances
+        .pipe(ResponseContent::Json)
+        .pipe(|entries| Page { entries, cursor })
+        .pipe(Ok)
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub struct ListAccountBalancesQueryParameters {
+    pub limit: Option<u32>,
+    pub start: Option<StructTag>,
+}
+
+impl ListAccountBalancesQueryParameters {
+    pub fn limit(&self) -> usize {
+        self.limit
+            .map(|l| (l as usize).clamp(1, crate::MAX_PAGE_SIZE))
+            .unwrap_or(crate::DEFAULT_PAGE_SIZE)
+    }
+}
+
+#[serde_with::serde_as]
+#[derive(Debug, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub struct AccountBalanceInfo {
+    pub owner: Address,
+    pub coin_type: StructTag,
+    #[serde_as(as = "iota_types::iota_serde::BigInt<u64>")]
+    #[schemars(with = "crate::_schemars::U64")]
+    pub balance: u64,
+    #[serde_as(as = "iota_types::iota_serde::BigInt<u64>")]
+    #[schemars(with = "crate::_schemars::U64")]
+    pub coin_object_count: u64,
+}
@@ -2,12 +2,20 @@
 // Modifications Copyright (c) 2024 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use axum::extract::{Path, Query, State};
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures::Stream;
 use iota_sdk2::types::{
     CheckpointData, CheckpointDigest, CheckpointSequenceNumber, SignedCheckpointSummary,
 };
 use iota_types::storage::ReadStore;
 use tap::Pipe;
+use tokio::sync::watch;
 
 use crate::{
     Direction, Page, RestService, Result,
@@ -323,3 +331,146 @@ impl ListCheckpointsQueryParameters {
         self.direction.unwrap_or(Direction::Descending)
     }
 }
+
+/// Interval at which checkpoint subscriptions check for newly executed
+/// checkpoints if the service isn't notified of executed checkpoints.
+const CHECKPOINT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+pub struct SubscribeCheckpoints;
+
+impl ApiEndpoint<RestService> for SubscribeCheckpoints {
+    fn method(&self) -> axum::http::Method {
+        axum::http::Method::GET
+    }
+
+    fn path(&self) -> &'static str {
+        "/checkpoints/subscribe"
+    }
+
+    fn operation(
+        &self,
+        generator: &mut schemars::gen::SchemaGenerator,
+    ) -> openapiv3::v3_1::Operation {
+        OperationBuilder::new()
+            .tag("Checkpoint")
+            .operation_id("SubscribeCheckpoints")
+            .query_parameters::<SubscribeCheckpointsQueryParameters>(generator)
+            .response(200, ResponseBuilder::new().event_stream_content().build())
+            .response(410, ResponseBuilder::new().build())
+            .build()
+    }
+
+    fn handler(&self) -> RouteHandler<RestService> {
+        RouteHandler::new(self.method(), subscribe_checkpoints)
+    }
+}
+
+/// Streams every full checkpoint as a server-sent event once it is executed.
+/// The id of each event is the sequence number of the checkpoint and its data
+/// the JSON encoded `CheckpointData`. A reconnecting client resumes after the
+/// checkpoint in its `Last-Event-ID` header.
+async fn subscribe_checkpoints(
+    Query(parameters): Query<SubscribeCheckpointsQueryParameters>,
+    headers: HeaderMap,
+    State(state): State<StateReader>,
+    State(executed_checkpoints): State<Option<watch::Receiver<CheckpointSequenceNumber>>>,
+) -> Result<Sse<impl Stream<Item = Result<Event, anyhow::Error>>>> {
+    let last_event_id = headers
+        .get(LAST_EVENT_ID)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<CheckpointSequenceNumber>().ok());
+    let start = match last_event_id {
+        Some(last_event_id) => last_event_id.saturating_add(1),
+        None => parameters.start(state.inner().get_latest_checkpoint_sequence_number()?),
+    };
+
+    // Since we need object contents we need to check for the lowest available
+    // checkpoint with objects that hasn't been pruned
+    let oldest_checkpoint = state.inner().get_lowest_available_checkpoint_objects()?;
+    if start < oldest_checkpoint {
+        return Err(crate::RestError::new(
+            axum::http::StatusCode::GONE,
+            "Old checkpoints have been pruned",
+        ));
+    }
+
+    let stream = futures::stream::try_unfold(
+        (start, executed_checkpoints),
+        move |(sequence_number, mut executed_checkpoints)| {
+            let state = state.clone();
+            async move {
+                if !wait_for_checkpoint(&state, &mut executed_checkpoints, sequence_number).await? {
+                    return Ok(None);
+                }
+                let checkpoint_data = get_checkpoint_data(&state, sequence_number)?;
+                let event = Event::default()
+                    .id(sequence_number.to_string())
+                    .json_data(CheckpointData::from(checkpoint_data))?;
+                Ok(Some((event, (sequence_number + 1, executed_checkpoints))))
+            }
+        },
+    );
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// Waits until the checkpoint `sequence_number` has been executed. Returns
+/// `false` if the node stopped executing checkpoints before that.
+async fn wait_for_checkpoint(
+    state: &StateReader,
+    executed_checkpoints: &mut Option<watch::Receiver<CheckpointSequenceNumber>>,
+    sequence_number: CheckpointSequenceNumber,
+) -> anyhow::Result<bool> {
+    loop {
+        // Mark the current value as seen before reading the store so that a
+        // checkpoint executed in between still wakes us up.
+        if let Some(executed_checkpoints) = executed_checkpoints {
+            executed_checkpoints.borrow_and_update();
+        }
+        if state.inner().get_latest_checkpoint_sequence_number()? >= sequence_number {
+            return Ok(true);
+        }
+        match executed_checkpoints {
+            Some(executed_checkpoints) => {
+                if executed_checkpoints.changed().await.is_err() {
+                    return Ok(false);
+                }
+            }
+            None => tokio::time::sleep(CHECKPOINT_POLL_INTERVAL).await,
+        }
+    }
+}
+
+fn get_checkpoint_data(
+    state: &StateReader,
+    sequence_number: CheckpointSequenceNumber,
+) -> anyhow::Result<iota_types::full_checkpoint_content::CheckpointData> {
+    let checkpoint_id = CheckpointId::SequenceNumber(sequence_number);
+    let verified_summary = state
+        .inner()
+        .get_checkpoint_by_sequence_number(sequence_number)?
+        .ok_or(CheckpointNotFoundError(checkpoint_id))?;
+    let checkpoint_contents = state
+        .inner()
+        .get_checkpoint_contents_by_digest(&verified_summary.content_digest)?
+        .ok_or(CheckpointNotFoundError(checkpoint_id))?;
+    Ok(state
+        .inner()
+        .get_checkpoint_data(verified_summary, checkpoint_contents)?)
+}
+
+const LAST_EVENT_ID: &str = "last-event-id";
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub struct SubscribeCheckpointsQueryParameters {
+    /// The checkpoint to start streaming from.
+    ///
+    /// Defaults to the latest checkpoint if not provided.
+    pub start: Option<CheckpointSequenceNumber>,
+}
+
+impl SubscribeCheckpointsQueryParameters {
+    pub fn start(&self, default: CheckpointSequenceNumber) -> CheckpointSequenceNumber {
+        self.start.unwrap_or(default)
+    }
+}
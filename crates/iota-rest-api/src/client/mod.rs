@@ -8,6 +8,7 @@ use iota_types::{
     TypeTag,
     base_types::{IotaAddress, ObjectID, SequenceNumber},
     crypto::AuthorityStrongQuorumSignInfo,
+    digests::TransactionDigest,
     effects::{TransactionEffects, TransactionEvents},
     full_checkpoint_content::CheckpointData,
     messages_checkpoint::{CertifiedCheckpointSummary, CheckpointSequenceNumber},
@@ -91,6 +92,18 @@ impl Client {
             .map(Into::into)
     }
 
+    /// Returns the sequence number of the checkpoint that includes the
+    /// transaction, or `None` if it is not yet checkpointed.
+    pub async fn get_transaction_checkpoint(
+        &self,
+        transaction: &TransactionDigest,
+    ) -> Result<Option<CheckpointSequenceNumber>> {
+        self.inner
+            .get_transaction(&(*transaction).into())
+            .await
+            .map(|response| response.into_inner().checkpoint)
+    }
+
     pub async fn execute_transaction(
         &self,
         parameters: &ExecuteTransactionQueryParameters,
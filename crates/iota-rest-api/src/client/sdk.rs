@@ -2,6 +2,7 @@
 // Modifications Copyright (c) 2024 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+use futures::Stream;
 use iota_sdk2::types::{
     Address, CheckpointData, CheckpointDigest, CheckpointSequenceNumber, EpochId, Object, ObjectId,
     SignedCheckpointSummary, SignedTransaction, StructTag, TransactionDigest, ValidatorCommittee,
@@ -12,8 +13,11 @@ use tap::Pipe;
 
 use crate::{
     ExecuteTransactionQueryParameters,
-    accounts::{AccountOwnedObjectInfo, ListAccountOwnedObjectsQueryParameters},
-    checkpoints::ListCheckpointsQueryParameters,
+    accounts::{
+        AccountBalanceInfo, AccountOwnedObjectInfo, ListAccountBalancesQueryParameters,
+        ListAccountOwnedObjectsQueryParameters,
+    },
+    checkpoints::{ListCheckpointsQueryParameters, SubscribeCheckpointsQueryParameters},
     coins::CoinInfo,
     health::Threshold,
     info::NodeInfo,
@@ -124,6 +128,24 @@ impl Client {
         self.json(response).await
     }
 
+    pub async fn list_account_balances(
+        &self,
+        account: Address,
+        parameters: &ListAccountBalancesQueryParameters,
+    ) -> Result<Response<Vec<AccountBalanceInfo>>> {
+        let url = self.url().join(&format!("accounts/{account}/balances"))?;
+
+        let response = self
+            .inner
+            .get(url)
+            .query(parameters)
+            .header(reqwest::header::ACCEPT, crate::APPLICATION_JSON)
+            .send()
+            .await?;
+
+        self.json(response).await
+    }
+
     pub async fn get_object(&self, object_id: ObjectId) -> Result<Response<Object>> {
         let url = self.url().join(&format!("objects/{object_id}"))?;
 
@@ -331,6 +353,48 @@ impl Client {
         self.bcs(response).await
     }
 
+    /// Subscribes to full checkpoints as they are executed, starting from
+    /// `start` or the latest checkpoint. The stream ends when the connection is
+    /// closed, after which it can be resumed from the checkpoint following the
+    /// last one received.
+    pub async fn subscribe_checkpoints(
+        &self,
+        start: Option<CheckpointSequenceNumber>,
+    ) -> Result<Response<impl Stream<Item = Result<CheckpointData>>>> {
+        let url = self.url().join("checkpoints/subscribe")?;
+
+        let response = self
+            .inner
+            .get(url)
+            .query(&SubscribeCheckpointsQueryParameters { start })
+            .header(reqwest::header::ACCEPT, crate::TEXT_EVENT_STREAM)
+            .send()
+            .await?;
+
+        let (response, parts) = self.check_response(response).await?;
+        let stream = futures::stream::try_unfold(
+            (response, Vec::new()),
+            |(mut response, mut buffer)| async move {
+                loop {
+                    if let Some(data) = take_event_data(&mut buffer) {
+                        // Skips keep-alive comments
+                        if data.is_empty() {
+                            continue;
+                        }
+                        let checkpoint = serde_json::from_str(&data).map_err(Error::from_error)?;
+                        return Ok(Some((checkpoint, (response, buffer))));
+                    }
+                    match response.chunk().await? {
+                        Some(chunk) => buffer.extend_from_slice(&chunk),
+                        None => return Ok(None),
+                    }
+                }
+            },
+        );
+
+        Ok(Response::new(stream, parts))
+    }
+
     pub async fn get_transaction(
         &self,
         transaction: &TransactionDigest,
@@ -434,6 +498,39 @@ impl Client {
     }
 }
 
+/// Removes the first complete server-sent event from `buffer` and returns the
+/// contents of its `data` fields, which are empty for events without data like
+/// keep-alive comments. Lines may be terminated by `\r\n`, `\n` or `\r`.
+fn take_event_data(buffer: &mut Vec<u8>) -> Option<String> {
+    let mut data_lines = Vec::new();
+    let mut line_start = 0;
+    let mut position = 0;
+    while position < buffer.len() {
+        let next_line_start = match buffer[position] {
+            b'\n' => position + 1,
+            b'\r' if buffer.get(position + 1) == Some(&b'\n') => position + 2,
+            // A trailing carriage return might still be followed by a line feed
+            b'\r' if position + 1 == buffer.len() => return None,
+            b'\r' => position + 1,
+            _ => {
+                position += 1;
+                continue;
+            }
+        };
+        let line = String::from_utf8_lossy(&buffer[line_start..position]);
+        if line.is_empty() {
+            buffer.drain(..next_line_start);
+            return Some(data_lines.join("\n"));
+        }
+        if let Some(data) = line.strip_prefix("data:") {
+            data_lines.push(data.strip_prefix(' ').unwrap_or(data).to_owned());
+        }
+        line_start = next_line_start;
+        position = next_line_start;
+    }
+    None
+}
+
 #[derive(Debug)]
 pub struct ResponseParts {
     pub status: StatusCode,
@@ -652,3 +749,28 @@ impl From<url::ParseError> for Error {
         Self::from_error(error)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_event_data() {
+        let mut buffer = b"id: 1\ndata: {\"a\":\ndata: 1}\n\n: keep-alive\n\nid: 2\ndata".to_vec();
+        assert_eq!(take_event_data(&mut buffer).unwrap(), "{\"a\":\n1}");
+        assert_eq!(take_event_data(&mut buffer).unwrap(), "");
+        assert_eq!(take_event_data(&mut buffer), None);
+        assert_eq!(buffer, b"id: 2\ndata");
+    }
+
+    #[test]
+    fn test_take_event_data_crlf() {
+        let mut buffer = b"id: 1\r\ndata: first\r\n\r\nid: 2\rdata:second\r\r".to_vec();
+        assert_eq!(take_event_data(&mut buffer).unwrap(), "first");
+        // The final carriage return might be the start of a CRLF
+        assert_eq!(take_event_data(&mut buffer), None);
+        buffer.extend_from_slice(b"\n");
+        assert_eq!(take_event_data(&mut buffer).unwrap(), "second");
+        assert!(buffer.is_empty());
+    }
+}
@@ -6,10 +6,14 @@ use std::sync::Arc;
 
 use axum::{Router, response::Redirect, routing::get};
 use iota_network_stack::callback::CallbackLayer;
-use iota_types::{storage::RestStateReader, transaction_executor::TransactionExecutor};
+use iota_types::{
+    messages_checkpoint::CheckpointSequenceNumber, storage::RestStateReader,
+    transaction_executor::TransactionExecutor,
+};
 use openapi::ApiEndpoint;
 use reader::StateReader;
 use tap::Pipe;
+use tokio::sync::watch;
 
 pub mod accept;
 mod accounts;
@@ -30,6 +34,7 @@ mod system;
 pub mod transactions;
 pub mod types;
 
+pub use accounts::ListAccountBalancesQueryParameters;
 pub use client::Client;
 pub use error::{RestError, Result};
 pub use iota_types::full_checkpoint_content::{CheckpointData, CheckpointTransaction};
@@ -39,6 +44,7 @@ pub use transactions::ExecuteTransactionQueryParameters;
 pub const TEXT_PLAIN_UTF_8: &str = "text/plain; charset=utf-8";
 pub const APPLICATION_BCS: &str = "application/bcs";
 pub const APPLICATION_JSON: &str = "application/json";
+pub const TEXT_EVENT_STREAM: &str = "text/event-stream";
 
 #[derive(Debug, Copy, Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "lowercase")]
@@ -70,12 +76,14 @@ const ENDPOINTS: &[&dyn ApiEndpoint<RestService>] = &[
     &info::GetNodeInfo,
     &health::HealthCheck,
     &accounts::ListAccountObjects,
+    &accounts::ListAccountBalances,
     &objects::GetObject,
     &objects::GetObjectWithVersion,
     &objects::ListDynamicFields,
     &checkpoints::ListCheckpoints,
     &checkpoints::GetCheckpoint,
     &checkpoints::GetCheckpointFull,
+    &checkpoints::SubscribeCheckpoints,
     &transactions::GetTransaction,
     &transactions::ListTransactions,
     &committee::GetCommittee,
@@ -95,6 +103,7 @@ pub struct RestService {
     chain_id: iota_types::digests::ChainIdentifier,
     software_version: &'static str,
     metrics: Option<Arc<RestMetrics>>,
+    executed_checkpoints: Option<watch::Receiver<CheckpointSequenceNumber>>,
 }
 
 impl axum::extract::FromRef<RestService> for StateReader {
@@ -109,6 +118,12 @@ impl axum::extract::FromRef<RestService> for Option<Arc<dyn TransactionExecutor>
     }
 }
 
+impl axum::extract::FromRef<RestService> for Option<watch::Receiver<CheckpointSequenceNumber>> {
+    fn from_ref(input: &RestService) -> Self {
+        input.executed_checkpoints.clone()
+    }
+}
+
 impl RestService {
     pub fn new(reader: Arc<dyn RestStateReader>, software_version: &'static str) -> Self {
         let chain_id = reader.get_chain_identifier().unwrap();
@@ -118,6 +133,7 @@ impl RestService {
             chain_id,
             software_version,
             metrics: None,
+            executed_checkpoints: None,
         }
     }
 
@@ -133,6 +149,16 @@ impl RestService {
         self.metrics = Some(Arc::new(metrics));
     }
 
+    /// Sets the channel notified of every executed checkpoint. Checkpoint
+    /// subscriptions wait on it for new checkpoints, falling back to polling
+    /// the store if it isn't set.
+    pub fn with_executed_checkpoints(
+        &mut self,
+        executed_checkpoints: watch::Receiver<CheckpointSequenceNumber>,
+    ) {
+        self.executed_checkpoints = Some(executed_checkpoints);
+    }
+
     pub fn chain_id(&self) -> iota_types::digests::ChainIdentifier {
         self.chain_id
     }
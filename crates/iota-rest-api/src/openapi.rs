@@ -603,6 +603,10 @@ impl ResponseBuilder {
     pub fn text_content(&mut self) -> &mut Self {
         self.content(mime::TEXT_PLAIN_UTF_8.as_ref(), MediaType::default())
     }
+
+    pub fn event_stream_content(&mut self) -> &mut Self {
+        self.content(crate::TEXT_EVENT_STREAM, MediaType::default())
+    }
 }
 
 #[derive(Default)]
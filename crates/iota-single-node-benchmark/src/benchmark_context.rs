@@ -239,12 +239,14 @@ impl BenchmarkContext {
                     let committee = validator.get_committee();
                     let validator_state = validator.get_validator();
                     let sig = if skip_signing {
-                        SignedTransaction::sign(
+                        SignedTransaction::try_sign(
                             0,
                             &tx,
                             &*validator_state.secret,
                             validator_state.name,
                         )
+                        .await
+                        .unwrap()
                     } else {
                         let verified_tx = VerifiedTransaction::new_unchecked(tx.clone());
                         validator_state
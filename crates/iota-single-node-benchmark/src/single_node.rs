@@ -20,11 +20,12 @@ use iota_core::{
     mock_consensus::{ConsensusMode, MockConsensusClient},
     state_accumulator::StateAccumulator,
 };
+use iota_swarm_config::network_config_builder::ConfigBuilder;
 use iota_test_transaction_builder::{PublishData, TestTransactionBuilder};
 use iota_types::{
     base_types::{AuthorityName, IotaAddress, ObjectRef, TransactionDigest},
     committee::Committee,
-    crypto::{AccountKeyPair, AuthoritySignature, Signer},
+    crypto::{AccountKeyPair, AuthorityKeyPair, AuthoritySignature, Signer},
     effects::{TransactionEffects, TransactionEffectsAPI},
     executable_transaction::VerifiedExecutableTransaction,
     messages_checkpoint::{VerifiedCheckpoint, VerifiedCheckpointContents},
@@ -44,11 +45,18 @@ use crate::{command::Component, mock_storage::InMemoryObjectStore};
 pub struct SingleValidator {
     validator_service: Arc<ValidatorService>,
     epoch_store: Arc<AuthorityPerEpochStore>,
+    keypair: Arc<AuthorityKeyPair>,
 }
 
 impl SingleValidator {
     pub(crate) async fn new(genesis_objects: &[Object], component: Component) -> Self {
+        // The validator's key is kept here to sign checkpoints synchronously.
+        let network_config = ConfigBuilder::new_with_temp_dir().build();
+        let keypair = network_config.validator_configs()[0]
+            .authority_key_pair()
+            .copy();
         let validator = TestAuthorityBuilder::new()
+            .with_network_config(&network_config, 0)
             .disable_indexer()
             .with_starting_objects(genesis_objects)
             // This is needed to properly run checkpoint executor.
@@ -84,6 +92,7 @@ impl SingleValidator {
         Self {
             validator_service,
             epoch_store,
+            keypair: Arc::new(keypair),
         }
     }
 
@@ -329,7 +338,7 @@ impl SingleValidator {
 impl ValidatorKeypairProvider for SingleValidator {
     fn get_validator_key(&self, name: &AuthorityName) -> &dyn Signer<AuthoritySignature> {
         assert_eq!(name, &self.get_validator().name);
-        &*self.get_validator().secret
+        self.keypair.as_ref()
     }
 
     fn get_committee(&self) -> &Committee {
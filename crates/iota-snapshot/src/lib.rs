@@ -9,7 +9,7 @@ mod tests;
 
 pub mod reader;
 pub mod uploader;
-mod writer;
+pub mod writer;
 
 use std::{
     path::PathBuf,
@@ -82,6 +82,21 @@ use tokio::time::Instant;
 ///     - epoch_1/
 ///       - 1_1.obj
 ///       - ...
+///     - epoch_2/
+///       - 1_1.obj
+///       - 1_1.ref
+///       - 1_1.del
+///       - MANIFEST
+///
+/// A delta snapshot, like the one of epoch 2 above, stores the live object set
+/// of an epoch relative to the one of a base epoch. Its object and reference
+/// files hold the objects that were created or modified since the base epoch,
+/// and its deletion files (*.del) hold the references of base objects that are
+/// no longer live, including the previous versions of modified objects.
+/// Deletion files use the REFERENCE file format. The MANIFEST of a delta
+/// snapshot records the base epoch, so that a chain of deltas can be resolved
+/// down to a full snapshot. The uploader always writes deltas relative to a
+/// full snapshot, so that a restore downloads at most one delta.
 ///
 /// Object File Disk Format
 /// ┌──────────────────────────────┐
@@ -153,6 +168,7 @@ const FILE_METADATA_BYTES: usize =
 pub enum FileType {
     Object = 0,
     Reference,
+    Deletion,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
@@ -174,6 +190,9 @@ impl FileMetadata {
             FileType::Reference => {
                 dir_path.child(&*format!("{}_{}.ref", self.bucket_num, self.part_num))
             }
+            FileType::Deletion => {
+                dir_path.child(&*format!("{}_{}.del", self.bucket_num, self.part_num))
+            }
         }
     }
     pub fn local_file_path(&self, root_path: &std::path::Path, dir_path: &Path) -> Result<PathBuf> {
@@ -189,32 +208,61 @@ pub struct ManifestV1 {
     pub epoch: u64,
 }
 
+/// Manifest of a delta snapshot, holding the changes of the live object set
+/// between `base_epoch` and `epoch`.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct DeltaManifestV1 {
+    pub snapshot_version: u8,
+    pub address_length: u64,
+    pub file_metadata: Vec<FileMetadata>,
+    pub epoch: u64,
+    pub base_epoch: u64,
+}
+
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub enum Manifest {
     V1(ManifestV1),
+    DeltaV1(DeltaManifestV1),
 }
 
 impl Manifest {
     pub fn snapshot_version(&self) -> u8 {
         match self {
             Self::V1(manifest) => manifest.snapshot_version,
+            Self::DeltaV1(manifest) => manifest.snapshot_version,
         }
     }
     pub fn address_length(&self) -> u64 {
         match self {
             Self::V1(manifest) => manifest.address_length,
+            Self::DeltaV1(manifest) => manifest.address_length,
         }
     }
     pub fn file_metadata(&self) -> &Vec<FileMetadata> {
         match self {
             Self::V1(manifest) => &manifest.file_metadata,
+            Self::DeltaV1(manifest) => &manifest.file_metadata,
         }
     }
     pub fn epoch(&self) -> u64 {
         match self {
             Self::V1(manifest) => manifest.epoch,
+            Self::DeltaV1(manifest) => manifest.epoch,
         }
     }
+    /// Returns the epoch a delta snapshot is based on, or `None` for a full
+    /// snapshot.
+    pub fn base_epoch(&self) -> Option<u64> {
+        match self {
+            Self::V1(_) => None,
+            Self::DeltaV1(manifest) => Some(manifest.base_epoch),
+        }
+    }
+}
+
+/// Directory of the full or delta snapshot of `epoch`.
+pub fn epoch_dir(epoch: u64) -> Path {
+    Path::from(format!("epoch_{}", epoch))
 }
 
 /// Creates a FileMetadata of the provided file path, which is overwritten with
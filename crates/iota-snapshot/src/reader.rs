@@ -15,7 +15,7 @@ use std::{
     },
 };
 
-use anyhow::{Context, Result, anyhow};
+use anyhow::{Context, Result, anyhow, ensure};
 use byteorder::{BigEndian, ReadBytesExt};
 use bytes::{Buf, Bytes};
 use fastcrypto::hash::{HashFunction, MultisetHash, Sha3_256};
@@ -41,10 +41,11 @@ use iota_storage::{
 use iota_types::{
     accumulator::Accumulator,
     base_types::{ObjectDigest, ObjectID, ObjectRef, SequenceNumber},
+    messages_checkpoint::ECMHLiveObjectSetDigest,
 };
 use object_store::path::Path;
 use tokio::{
-    sync::Mutex,
+    sync::{Mutex, mpsc},
     task::JoinHandle,
     time::{Duration, Instant},
 };
@@ -53,25 +54,42 @@ use tracing::{error, info};
 use crate::{
     FileMetadata, FileType, MAGIC_BYTES, MANIFEST_FILE_MAGIC, Manifest, OBJECT_FILE_MAGIC,
     OBJECT_ID_BYTES, OBJECT_REF_BYTES, REFERENCE_FILE_MAGIC, SEQUENCE_NUM_BYTES, SHA3_BYTES,
+    epoch_dir,
 };
 
 pub type SnapshotChecksums = (DigestByBucketAndPartition, Accumulator);
 pub type DigestByBucketAndPartition = BTreeMap<u32, BTreeMap<u32, [u8; 32]>>;
+pub type FilesByBucketAndPartition = BTreeMap<u32, BTreeMap<u32, FileMetadata>>;
 pub struct StateSnapshotReaderV1 {
     epoch: u64,
     local_staging_dir_root: PathBuf,
     remote_object_store: Arc<dyn ObjectStoreGetExt>,
     local_object_store: Arc<dyn ObjectStorePutExt>,
-    ref_files: BTreeMap<u32, BTreeMap<u32, FileMetadata>>,
-    object_files: BTreeMap<u32, BTreeMap<u32, FileMetadata>>,
+    ref_files: FilesByBucketAndPartition,
+    object_files: FilesByBucketAndPartition,
+    /// Delta snapshots applied on top of the full snapshot, ordered by epoch.
+    deltas: Vec<DeltaSnapshot>,
     indirect_objects_threshold: usize,
     multi_progress_bar: MultiProgress,
     concurrency: usize,
 }
 
+/// The files of a delta snapshot, holding the changes of the live object set
+/// between `base_epoch` and `epoch`.
+struct DeltaSnapshot {
+    epoch: u64,
+    base_epoch: u64,
+    ref_files: FilesByBucketAndPartition,
+    object_files: FilesByBucketAndPartition,
+    deletion_files: FilesByBucketAndPartition,
+}
+
 impl StateSnapshotReaderV1 {
     /// Downloads the MANIFEST, FileMetadata of objects and references from the
-    /// remote store, then creates a StateSnapshotReaderV1 instance.
+    /// remote store, then creates a StateSnapshotReaderV1 instance. If the
+    /// snapshot of `epoch` is a delta snapshot, the chain of deltas leading
+    /// to it is resolved down to a full snapshot, and the deltas are applied
+    /// on top of it by [`StateSnapshotReaderV1::read`].
     pub async fn new(
         epoch: u64,
         remote_store_config: &ObjectStoreConfig,
@@ -80,7 +98,6 @@ impl StateSnapshotReaderV1 {
         download_concurrency: NonZeroUsize,
         multi_progress_bar: MultiProgress,
     ) -> Result<Self> {
-        let epoch_dir = format!("epoch_{}", epoch);
         let remote_object_store = if remote_store_config.no_sign_request {
             remote_store_config.make_http()?
         } else {
@@ -93,76 +110,67 @@ impl StateSnapshotReaderV1 {
             .as_ref()
             .context("No directory specified")?
             .clone();
-        let local_epoch_dir_path = local_staging_dir_root.join(&epoch_dir);
-        if local_epoch_dir_path.exists() {
-            fs::remove_dir_all(&local_epoch_dir_path)?;
-        }
-        fs::create_dir_all(&local_epoch_dir_path)?;
-        // Downloads MANIFEST from remote store
-        let manifest_file_path = Path::from(epoch_dir.clone()).child("MANIFEST");
-        copy_file(
-            &manifest_file_path,
-            &manifest_file_path,
+        let mut manifest = Self::download_manifest(
+            &epoch_dir(epoch),
+            &local_staging_dir_root,
             &remote_object_store,
             &local_object_store,
         )
         .await?;
-        let manifest = Self::read_manifest(path_to_filesystem(
-            local_staging_dir_root.clone(),
-            &manifest_file_path,
-        )?)?;
-        // Verifies MANIFEST
-        let snapshot_version = manifest.snapshot_version();
-        if snapshot_version != 1u8 {
-            return Err(anyhow!("Unexpected snapshot version: {}", snapshot_version));
-        }
-        if manifest.address_length() as usize > ObjectID::LENGTH {
-            return Err(anyhow!(
-                "Max possible address length is: {}",
-                ObjectID::LENGTH
-            ));
-        }
         if manifest.epoch() != epoch {
             return Err(anyhow!("Download manifest is not for epoch: {}", epoch,));
         }
+        // Resolves the chain of delta snapshots down to the full snapshot and
+        // downloads their reference and deletion files
+        let mut deltas = vec![];
+        while let Some(base_epoch) = manifest.base_epoch() {
+            let delta_epoch = manifest.epoch();
+            ensure!(
+                base_epoch < delta_epoch,
+                "Delta snapshot of epoch {delta_epoch} has invalid base epoch {base_epoch}"
+            );
+            let delta = DeltaSnapshot {
+                epoch: delta_epoch,
+                base_epoch,
+                ref_files: files_by_bucket_and_partition(&manifest, FileType::Reference),
+                object_files: files_by_bucket_and_partition(&manifest, FileType::Object),
+                deletion_files: files_by_bucket_and_partition(&manifest, FileType::Deletion),
+            };
+            let dir = epoch_dir(delta_epoch);
+            let mut files = file_paths(&delta.ref_files, &dir);
+            files.extend(file_paths(&delta.deletion_files, &dir));
+            copy_files(
+                &files,
+                &files,
+                &remote_object_store,
+                &local_object_store,
+                download_concurrency,
+                None,
+            )
+            .await?;
+            info!("Resolved delta snapshot of epoch {delta_epoch} based on epoch {base_epoch}");
+            deltas.push(delta);
+            manifest = Self::download_manifest(
+                &epoch_dir(base_epoch),
+                &local_staging_dir_root,
+                &remote_object_store,
+                &local_object_store,
+            )
+            .await?;
+            ensure!(
+                manifest.epoch() == base_epoch,
+                "Download manifest is not for epoch: {base_epoch}"
+            );
+        }
+        deltas.reverse();
+        let epoch = manifest.epoch();
+        let epoch_dir_path = epoch_dir(epoch);
         // Stores the objects and references FileMetadata in MANIFEST to the local
         // directory
-        let mut object_files = BTreeMap::new();
-        let mut ref_files = BTreeMap::new();
-        for file_metadata in manifest.file_metadata() {
-            match file_metadata.file_type {
-                FileType::Object => {
-                    // Gets the object FileMetadata bucket with the bucket number, or inserts a new
-                    // one if it doesn't exist.
-                    let entry = object_files
-                        .entry(file_metadata.bucket_num)
-                        .or_insert_with(BTreeMap::new);
-                    // Inserts the object FileMetadata with the partition number to the bucket.
-                    entry.insert(file_metadata.part_num, file_metadata.clone());
-                }
-                FileType::Reference => {
-                    // Gets the reference FileMetadata bucket with the bucket number, or inserts a
-                    // new one if it doesn't exist.
-                    let entry = ref_files
-                        .entry(file_metadata.bucket_num)
-                        .or_insert_with(BTreeMap::new);
-                    // Inserts the reference FileMetadata with the partition number to the bucket.
-                    entry.insert(file_metadata.part_num, file_metadata.clone());
-                }
-            }
-        }
-        let epoch_dir_path = Path::from(epoch_dir);
+        let object_files = files_by_bucket_and_partition(&manifest, FileType::Object);
+        let ref_files = files_by_bucket_and_partition(&manifest, FileType::Reference);
         // Collects the path of all reference files
-        let files: Vec<Path> = ref_files
-            .values()
-            .flat_map(|entry| {
-                let files: Vec<_> = entry
-                    .values()
-                    .map(|file_metadata| file_metadata.file_path(&epoch_dir_path))
-                    .collect();
-                files
-            })
-            .collect();
+        let files = file_paths(&ref_files, &epoch_dir_path);
 
         let progress_bar = multi_progress_bar.add(
             ProgressBar::new(files.len() as u64).with_style(
@@ -191,12 +199,19 @@ impl StateSnapshotReaderV1 {
             local_object_store,
             ref_files,
             object_files,
+            deltas,
             indirect_objects_threshold,
             multi_progress_bar,
             concurrency: download_concurrency.get(),
         })
     }
 
+    /// Returns the epoch of the live object set restored by this reader, which
+    /// is the epoch of the last delta snapshot if there is any.
+    pub fn target_epoch(&self) -> u64 {
+        self.deltas.last().map_or(self.epoch, |delta| delta.epoch)
+    }
+
     pub async fn read(
         &mut self,
         perpetual_db: &AuthorityPerpetualTables,
@@ -231,51 +246,177 @@ impl StateSnapshotReaderV1 {
             ),
         );
 
-        // Iterates over all FileMetadata in the ref files by partition and build up the
-        // sha3 digests mapping: (bucket, (partition, sha3_digest))
-        for (bucket, part_files) in self.ref_files.clone().iter() {
-            for (part, _part_file) in part_files.iter() {
-                let mut sha3_digests = sha3_digests.lock().await;
-                let ref_iter = self.ref_iter(*bucket, *part)?;
-                let mut hasher = Sha3_256::default();
-                let mut empty = true;
-                // TODO: This can be removed, the same operation is done in ref_iter() in line
-                // 238
-                self.object_files
+        *sha3_digests.lock().await = self.partition_sha3_digests(
+            &self.ref_files,
+            &self.object_files,
+            &self.epoch_dir(),
+            Some(&checksum_progress_bar),
+        )?;
+        checksum_progress_bar.finish_with_message("Checksumming complete");
+
+        let delta_sender = sender.clone();
+        let accum_handle =
+            sender.map(|sender| self.spawn_accumulation_tasks(sender, num_part_files));
+
+        // Downloads all object files from remote in parallel and inserts the objects
+        // into the AuthorityPerpetualTables, then applies the delta snapshots on top
+        Abortable::new(
+            async {
+                self.sync_live_objects(
+                    perpetual_db,
+                    self.epoch_dir(),
+                    &self.object_files,
+                    sha3_digests,
+                )
+                .await?;
+                self.apply_deltas(perpetual_db, delta_sender).await
+            },
+            abort_registration,
+        )
+        .await??;
+
+        if let Some(handle) = accum_handle {
+            handle.await?;
+        }
+        Ok(())
+    }
+
+    /// Restores the live object set like [`StateSnapshotReaderV1::read`] and
+    /// verifies that its accumulator matches the root state hash of the
+    /// target epoch. Returns the accumulator of the restored live object set.
+    pub async fn read_and_verify(
+        &mut self,
+        perpetual_db: &AuthorityPerpetualTables,
+        abort_registration: AbortRegistration,
+        root_state_hash: ECMHLiveObjectSetDigest,
+    ) -> Result<Accumulator> {
+        let (sender, mut receiver) = mpsc::channel(self.concurrency);
+        let accumulate = async move {
+            let mut root_accumulator = Accumulator::default();
+            while let Some((partial_acc, _num_objects)) = receiver.recv().await {
+                root_accumulator.union(&partial_acc);
+            }
+            root_accumulator
+        };
+        let (result, root_accumulator) = tokio::join!(
+            self.read(perpetual_db, abort_registration, Some(sender)),
+            accumulate
+        );
+        result?;
+        let local_digest = ECMHLiveObjectSetDigest::from(root_accumulator.digest());
+        ensure!(
+            local_digest == root_state_hash,
+            "Root state digest {} of epoch {} does not match local root state hash {} \
+            computed from snapshot data",
+            root_state_hash.digest,
+            self.target_epoch(),
+            local_digest.digest,
+        );
+        Ok(root_accumulator)
+    }
+
+    /// Computes the sha3 digest of the object references of every reference
+    /// file by partition: (bucket, (partition, sha3_digest)). When downloading
+    /// objects, the digest of each *.obj file is compared against it.
+    fn partition_sha3_digests(
+        &self,
+        ref_files: &FilesByBucketAndPartition,
+        object_files: &FilesByBucketAndPartition,
+        epoch_dir: &Path,
+        progress_bar: Option<&ProgressBar>,
+    ) -> Result<DigestByBucketAndPartition> {
+        let mut sha3_digests = DigestByBucketAndPartition::new();
+        for (bucket, part_files) in ref_files.iter() {
+            for (part, file_metadata) in part_files.iter() {
+                object_files
                     .get(bucket)
                     .context(format!("No bucket exists for: {bucket}"))?
                     .get(part)
                     .context(format!("No part exists for bucket: {bucket}, part: {part}"))?;
+                let ref_iter = ObjectRefIter::new(
+                    file_metadata,
+                    self.local_staging_dir_root.clone(),
+                    epoch_dir.clone(),
+                )?;
+                let mut hasher = Sha3_256::default();
+                let mut empty = true;
                 // Inserts the sha3 digest of each object into the hasher
                 for object_ref in ref_iter {
                     hasher.update(object_ref.2.inner());
                     empty = false;
                 }
-                // Computes the sha3 digest of the partition and insert sit into the
+                // Computes the sha3 digest of the partition and inserts it into the
                 // sha3_digests map
                 if !empty {
                     sha3_digests
                         .entry(*bucket)
-                        .or_insert(BTreeMap::new())
+                        .or_default()
                         .entry(*part)
                         .or_insert(hasher.finalize().digest);
                 }
-                checksum_progress_bar.inc(1);
-                checksum_progress_bar.set_message(format!("Bucket: {}, Part: {}", bucket, part));
+                if let Some(progress_bar) = progress_bar {
+                    progress_bar.inc(1);
+                    progress_bar.set_message(format!("Bucket: {}, Part: {}", bucket, part));
+                }
             }
         }
-        checksum_progress_bar.finish_with_message("Checksumming complete");
-
-        let accum_handle =
-            sender.map(|sender| self.spawn_accumulation_tasks(sender, num_part_files));
+        Ok(sha3_digests)
+    }
 
-        // Downloads all object files from remote in parallel and inserts the objects
-        // into the AuthorityPerpetualTables
-        self.sync_live_objects(perpetual_db, abort_registration, sha3_digests)
+    /// Applies the delta snapshots in epoch order on top of the restored live
+    /// object set. Objects listed in deletion files are removed before the
+    /// created and modified objects are inserted. If a sender is provided, a
+    /// partial accumulator reflecting the changes of each delta is sent to it.
+    async fn apply_deltas(
+        &self,
+        perpetual_db: &AuthorityPerpetualTables,
+        sender: Option<mpsc::Sender<(Accumulator, u64)>>,
+    ) -> Result<()> {
+        for delta in &self.deltas {
+            info!(
+                "Applying delta snapshot of epoch {} on top of epoch {}",
+                delta.epoch, delta.base_epoch
+            );
+            let dir = epoch_dir(delta.epoch);
+            let mut partial_acc = Accumulator::default();
+            for file_metadata in delta
+                .deletion_files
+                .values()
+                .flat_map(|parts| parts.values())
+            {
+                let deleted = ObjectRefIter::new(
+                    file_metadata,
+                    self.local_staging_dir_root.clone(),
+                    dir.clone(),
+                )?
+                .collect::<Vec<_>>();
+                partial_acc.remove_all(deleted.iter().map(|object_ref| object_ref.2));
+                AuthorityStore::bulk_remove_live_objects(perpetual_db, deleted.into_iter())?;
+            }
+            let mut num_objects = 0;
+            for file_metadata in delta.ref_files.values().flat_map(|parts| parts.values()) {
+                let digests = ObjectRefIter::new(
+                    file_metadata,
+                    self.local_staging_dir_root.clone(),
+                    dir.clone(),
+                )?
+                .map(|object_ref| object_ref.2)
+                .collect::<Vec<_>>();
+                num_objects += digests.len() as u64;
+                partial_acc.insert_all(digests);
+            }
+            let sha3_digests =
+                self.partition_sha3_digests(&delta.ref_files, &delta.object_files, &dir, None)?;
+            self.sync_live_objects(
+                perpetual_db,
+                dir,
+                &delta.object_files,
+                Arc::new(Mutex::new(sha3_digests)),
+            )
             .await?;
-
-        if let Some(handle) = accum_handle {
-            handle.await?;
+            if let Some(sender) = &sender {
+                sender.send((partial_acc, num_objects)).await?;
+            }
         }
         Ok(())
     }
@@ -381,22 +522,21 @@ impl StateSnapshotReaderV1 {
         })
     }
 
-    /// Downloads all object files from remote in parallel and inserts the
-    /// objects into the AuthorityPerpetualTables.
+    /// Downloads all object files in `epoch_dir` from remote in parallel and
+    /// inserts the objects into the AuthorityPerpetualTables.
     async fn sync_live_objects(
         &self,
         perpetual_db: &AuthorityPerpetualTables,
-        abort_registration: AbortRegistration,
+        epoch_dir: Path,
+        object_files: &FilesByBucketAndPartition,
         sha3_digests: Arc<Mutex<DigestByBucketAndPartition>>,
     ) -> Result<(), anyhow::Error> {
-        let epoch_dir = self.epoch_dir();
         let concurrency = self.concurrency;
         let threshold = self.indirect_objects_threshold;
         let remote_object_store = self.remote_object_store.clone();
         // collects a vector of all object FileMetadata in the form of:
         // (bucket, (partition, File_metadata))
-        let input_files: Vec<_> = self
-            .object_files
+        let input_files: Vec<_> = object_files
             .iter()
             .flat_map(|(bucket, parts)| {
                 parts
@@ -419,98 +559,92 @@ impl StateSnapshotReaderV1 {
         let instant = Instant::now();
         let downloaded_bytes = AtomicUsize::new(0);
 
-        let ret = Abortable::new(
-            async move {
-                // Downloads all object files from remote store to local store in parallel
-                // and inserts the objects into the AuthorityPerpetualTables
-                futures::stream::iter(input_files.iter())
-                    .map(|(bucket, (part_num, file_metadata))| {
-                        let epoch_dir = epoch_dir.clone();
-                        let file_path = file_metadata.file_path(&epoch_dir);
-                        let remote_object_store = remote_object_store.clone();
-                        let sha3_digests_cloned = sha3_digests.clone();
-                        async move {
-                            // Downloads object file with retries
-                            let max_timeout = Duration::from_secs(30);
-                            let mut timeout = Duration::from_secs(2);
-                            timeout += timeout / 2;
-                            timeout = std::cmp::min(max_timeout, timeout);
-                            let mut attempts = 0usize;
-                            let bytes = loop {
-                                match remote_object_store.get_bytes(&file_path).await {
-                                    Ok(bytes) => {
-                                        break bytes;
-                                    }
-                                    Err(err) => {
-                                        error!(
-                                            "Obj {} .get failed (attempt {}): {}",
-                                            file_metadata.file_path(&epoch_dir),
-                                            attempts,
-                                            err,
-                                        );
-                                        if timeout > max_timeout {
-                                            panic!(
-                                                "Failed to get obj file {} after {} attempts",
-                                                file_metadata.file_path(&epoch_dir),
-                                                attempts,
-                                            );
-                                        } else {
-                                            attempts += 1;
-                                            tokio::time::sleep(timeout).await;
-                                            timeout += timeout / 2;
-                                            continue;
-                                        }
-                                    }
+        // Downloads all object files from remote store to local store in parallel
+        // and inserts the objects into the AuthorityPerpetualTables
+        let ret = futures::stream::iter(input_files.iter())
+            .map(|(bucket, (part_num, file_metadata))| {
+                let epoch_dir = epoch_dir.clone();
+                let file_path = file_metadata.file_path(&epoch_dir);
+                let remote_object_store = remote_object_store.clone();
+                let sha3_digests_cloned = sha3_digests.clone();
+                async move {
+                    // Downloads object file with retries
+                    let max_timeout = Duration::from_secs(30);
+                    let mut timeout = Duration::from_secs(2);
+                    timeout += timeout / 2;
+                    timeout = std::cmp::min(max_timeout, timeout);
+                    let mut attempts = 0usize;
+                    let bytes = loop {
+                        match remote_object_store.get_bytes(&file_path).await {
+                            Ok(bytes) => {
+                                break bytes;
+                            }
+                            Err(err) => {
+                                error!(
+                                    "Obj {} .get failed (attempt {}): {}",
+                                    file_metadata.file_path(&epoch_dir),
+                                    attempts,
+                                    err,
+                                );
+                                if timeout > max_timeout {
+                                    panic!(
+                                        "Failed to get obj file {} after {} attempts",
+                                        file_metadata.file_path(&epoch_dir),
+                                        attempts,
+                                    );
+                                } else {
+                                    attempts += 1;
+                                    tokio::time::sleep(timeout).await;
+                                    timeout += timeout / 2;
+                                    continue;
                                 }
-                            };
-
-                            // Gets the sha3 digest of the partition
-                            let sha3_digest = sha3_digests_cloned.lock().await;
-                            let bucket_map = sha3_digest
-                                .get(bucket)
-                                .expect("Bucket not in digest map")
-                                .clone();
-                            let sha3_digest = *bucket_map
-                                .get(part_num)
-                                .expect("sha3 digest not in bucket map");
-                            Ok::<(Bytes, FileMetadata, [u8; 32]), anyhow::Error>((
-                                bytes,
-                                (*file_metadata).clone(),
-                                sha3_digest,
-                            ))
+                            }
                         }
-                    })
-                    .boxed()
-                    .buffer_unordered(concurrency)
-                    .try_for_each(|(bytes, file_metadata, sha3_digest)| {
-                        let bytes_len = bytes.len();
-                        // Inserts live objects into the AuthorityStore
-                        let result: Result<(), anyhow::Error> =
-                            LiveObjectIter::new(&file_metadata, bytes).map(|obj_iter| {
-                                AuthorityStore::bulk_insert_live_objects(
-                                    perpetual_db,
-                                    obj_iter,
-                                    threshold,
-                                    &sha3_digest,
-                                )
-                                .expect("Failed to insert live objects");
-                            });
-                        downloaded_bytes.fetch_add(bytes_len, Ordering::Relaxed);
-                        // Updates the progress bar
-                        obj_progress_bar_clone.inc(1);
-                        obj_progress_bar_clone.set_message(format!(
-                            "Download speed: {} MiB/s",
-                            downloaded_bytes.load(Ordering::Relaxed) as f64
-                                / (1024 * 1024) as f64
-                                / instant.elapsed().as_secs_f64(),
-                        ));
-                        futures::future::ready(result)
-                    })
-                    .await
-            },
-            abort_registration,
-        )
-        .await?;
+                    };
+
+                    // Gets the sha3 digest of the partition
+                    let sha3_digest = sha3_digests_cloned.lock().await;
+                    let bucket_map = sha3_digest
+                        .get(bucket)
+                        .expect("Bucket not in digest map")
+                        .clone();
+                    let sha3_digest = *bucket_map
+                        .get(part_num)
+                        .expect("sha3 digest not in bucket map");
+                    Ok::<(Bytes, FileMetadata, [u8; 32]), anyhow::Error>((
+                        bytes,
+                        (*file_metadata).clone(),
+                        sha3_digest,
+                    ))
+                }
+            })
+            .boxed()
+            .buffer_unordered(concurrency)
+            .try_for_each(|(bytes, file_metadata, sha3_digest)| {
+                let bytes_len = bytes.len();
+                // Inserts live objects into the AuthorityStore
+                let result: Result<(), anyhow::Error> = LiveObjectIter::new(&file_metadata, bytes)
+                    .map(|obj_iter| {
+                        AuthorityStore::bulk_insert_live_objects(
+                            perpetual_db,
+                            obj_iter,
+                            threshold,
+                            &sha3_digest,
+                        )
+                        .expect("Failed to insert live objects");
+                    });
+                downloaded_bytes.fetch_add(bytes_len, Ordering::Relaxed);
+                // Updates the progress bar
+                obj_progress_bar_clone.inc(1);
+                obj_progress_bar_clone.set_message(format!(
+                    "Download speed: {} MiB/s",
+                    downloaded_bytes.load(Ordering::Relaxed) as f64
+                        / (1024 * 1024) as f64
+                        / instant.elapsed().as_secs_f64(),
+                ));
+                futures::future::ready(result)
+            })
+            .await;
         obj_progress_bar.finish_with_message("Objects download complete");
         ret
     }
@@ -539,12 +673,51 @@ impl StateSnapshotReaderV1 {
     }
 
     fn epoch_dir(&self) -> Path {
-        Path::from(format!("epoch_{}", self.epoch))
+        epoch_dir(self.epoch)
+    }
+
+    /// Recreates `dir` in the local staging directory and downloads the
+    /// MANIFEST in it from the remote store, then reads and verifies it.
+    pub(crate) async fn download_manifest<S: ObjectStoreGetExt, D: ObjectStorePutExt>(
+        dir: &Path,
+        local_staging_dir_root: &std::path::Path,
+        remote_object_store: &S,
+        local_object_store: &D,
+    ) -> Result<Manifest> {
+        let local_dir_path = local_staging_dir_root.join(dir.as_ref());
+        if local_dir_path.exists() {
+            fs::remove_dir_all(&local_dir_path)?;
+        }
+        fs::create_dir_all(&local_dir_path)?;
+        let manifest_file_path = dir.child("MANIFEST");
+        copy_file(
+            &manifest_file_path,
+            &manifest_file_path,
+            remote_object_store,
+            local_object_store,
+        )
+        .await?;
+        let manifest = Self::read_manifest(path_to_filesystem(
+            local_staging_dir_root.to_path_buf(),
+            &manifest_file_path,
+        )?)?;
+        // Verifies MANIFEST
+        let snapshot_version = manifest.snapshot_version();
+        if snapshot_version != 1u8 {
+            return Err(anyhow!("Unexpected snapshot version: {}", snapshot_version));
+        }
+        if manifest.address_length() as usize > ObjectID::LENGTH {
+            return Err(anyhow!(
+                "Max possible address length is: {}",
+                ObjectID::LENGTH
+            ));
+        }
+        Ok(manifest)
     }
 
     /// Reads the MANIFEST file, verifies it with the checksum, and returns the
     /// Manifest.
-    fn read_manifest(path: PathBuf) -> anyhow::Result<Manifest> {
+    pub fn read_manifest(path: PathBuf) -> anyhow::Result<Manifest> {
         let manifest_file = File::open(path)?;
         let manifest_file_size = manifest_file.metadata()?.len() as usize;
         let mut manifest_reader = BufReader::new(manifest_file);
@@ -581,6 +754,33 @@ impl StateSnapshotReaderV1 {
     }
 }
 
+/// Groups the FileMetadata of the given type in the MANIFEST by bucket and
+/// partition number.
+pub(crate) fn files_by_bucket_and_partition(
+    manifest: &Manifest,
+    file_type: FileType,
+) -> FilesByBucketAndPartition {
+    let mut files = FilesByBucketAndPartition::new();
+    for file_metadata in manifest.file_metadata() {
+        if file_metadata.file_type == file_type {
+            files
+                .entry(file_metadata.bucket_num)
+                .or_default()
+                .insert(file_metadata.part_num, file_metadata.clone());
+        }
+    }
+    files
+}
+
+/// Collects the paths of all files in `dir`.
+pub(crate) fn file_paths(files: &FilesByBucketAndPartition, dir: &Path) -> Vec<Path> {
+    files
+        .values()
+        .flat_map(|parts| parts.values())
+        .map(|file_metadata| file_metadata.file_path(dir))
+        .collect()
+}
+
 /// An iterator over all object refs in a .ref file.
 pub struct ObjectRefIter {
     reader: Box<dyn Read>,
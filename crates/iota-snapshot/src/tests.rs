@@ -13,7 +13,9 @@ use iota_core::{
     state_accumulator::StateAccumulator,
 };
 use iota_types::{
-    accumulator::Accumulator, base_types::ObjectID, messages_checkpoint::ECMHLiveObjectSetDigest,
+    accumulator::Accumulator,
+    base_types::{IotaAddress, ObjectID, SequenceNumber},
+    messages_checkpoint::ECMHLiveObjectSetDigest,
     object::Object,
 };
 use tempfile::tempdir;
@@ -38,6 +40,20 @@ pub fn insert_keys(
     Ok(())
 }
 
+fn object_ids(range: std::ops::Range<u64>) -> Vec<ObjectID> {
+    ObjectID::in_range(ObjectID::ZERO, range.end).unwrap()[range.start as usize..].to_vec()
+}
+
+fn insert_objects(
+    db: &AuthorityPerpetualTables,
+    objects: impl IntoIterator<Item = Object>,
+) -> Result<(), anyhow::Error> {
+    for object in objects {
+        db.insert_object_test_only(object)?;
+    }
+    Ok(())
+}
+
 fn compare_live_objects(
     db1: &AuthorityPerpetualTables,
     db2: &AuthorityPerpetualTables,
@@ -62,6 +78,34 @@ fn accumulate_live_object_set(perpetual_db: &AuthorityPerpetualTables) -> Accumu
     acc
 }
 
+#[test]
+fn test_live_object_set_at_snapshot() -> Result<(), anyhow::Error> {
+    let perpetual_db = AuthorityPerpetualTables::open(&temp_dir(), None);
+    insert_keys(&perpetual_db, 100)?;
+    let expected = accumulate_live_object_set(&perpetual_db);
+
+    let snapshot = perpetual_db.snapshot();
+    let live_object_set = perpetual_db.live_object_set_at(&snapshot)?;
+    // Objects written after the snapshot was taken are not part of its live object
+    // set.
+    insert_objects(
+        &perpetual_db,
+        object_ids(100..150)
+            .into_iter()
+            .map(Object::immutable_with_id_for_testing),
+    )?;
+    let mut acc = Accumulator::default();
+    for live_object in live_object_set.iter_live_object_set() {
+        StateAccumulator::accumulate_live_object(&mut acc, &live_object);
+    }
+    assert_eq!(acc.digest(), expected.digest());
+    assert_ne!(
+        accumulate_live_object_set(&perpetual_db).digest(),
+        expected.digest()
+    );
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_snapshot_basic() -> Result<(), anyhow::Error> {
     let db_path = temp_dir();
@@ -169,3 +213,116 @@ async fn test_snapshot_empty_db() -> Result<(), anyhow::Error> {
     compare_live_objects(&perpetual_db, &restored_perpetual_db)?;
     Ok(())
 }
+
+#[tokio::test]
+async fn test_snapshot_with_deltas() -> Result<(), anyhow::Error> {
+    let remote_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(temp_dir().join("remote_dir")),
+        ..Default::default()
+    };
+    let writer = || async {
+        let local_store_config = ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::File),
+            directory: Some(temp_dir().join("local_dir")),
+            ..Default::default()
+        };
+        StateSnapshotWriterV1::new(
+            &local_store_config,
+            &remote_store_config,
+            FileCompression::Zstd,
+            NonZeroUsize::new(1).unwrap(),
+        )
+        .await
+    };
+    let restore = |epoch: u64, root_state_hash: ECMHLiveObjectSetDigest| {
+        let remote_store_config = remote_store_config.clone();
+        async move {
+            let local_store_restore_config = ObjectStoreConfig {
+                object_store: Some(ObjectStoreType::File),
+                directory: Some(temp_dir().join("local_dir_restore")),
+                ..Default::default()
+            };
+            let mut snapshot_reader = StateSnapshotReaderV1::new(
+                epoch,
+                &remote_store_config,
+                &local_store_restore_config,
+                usize::MAX,
+                NonZeroUsize::new(1).unwrap(),
+                MultiProgress::new(),
+            )
+            .await?;
+            assert_eq!(snapshot_reader.target_epoch(), epoch);
+            let restored_perpetual_db = AuthorityPerpetualTables::open(&temp_dir(), None);
+            let (_abort_handle, abort_registration) = AbortHandle::new_pair();
+            snapshot_reader
+                .read_and_verify(&restored_perpetual_db, abort_registration, root_state_hash)
+                .await?;
+            Ok::<_, anyhow::Error>(restored_perpetual_db)
+        }
+    };
+    let owner = IotaAddress::random_for_testing_only();
+    let modified = |range: std::ops::Range<u64>, version: u64| {
+        object_ids(range).into_iter().map(move |id| {
+            Object::with_id_owner_version_for_testing(id, SequenceNumber::from_u64(version), owner)
+        })
+    };
+
+    // Epoch 0: full snapshot of 1000 objects
+    let db_0 = Arc::new(AuthorityPerpetualTables::open(&temp_dir(), None));
+    insert_keys(&db_0, 1000)?;
+    let root_0 = ECMHLiveObjectSetDigest::from(accumulate_live_object_set(&db_0).digest());
+    writer()
+        .await?
+        .write_internal(0, db_0.clone(), root_0.clone())
+        .await?;
+
+    // Epoch 1: objects 900..1000 are deleted, 100..200 are modified and
+    // 1000..1100 are created
+    let db_1 = Arc::new(AuthorityPerpetualTables::open(&temp_dir(), None));
+    insert_keys(&db_1, 900)?;
+    insert_objects(&db_1, modified(100..200, 2))?;
+    insert_objects(
+        &db_1,
+        object_ids(1000..1100)
+            .into_iter()
+            .map(Object::immutable_with_id_for_testing),
+    )?;
+    let root_1 = ECMHLiveObjectSetDigest::from(accumulate_live_object_set(&db_1).digest());
+    writer()
+        .await?
+        .write_delta(1, 0, db_1.clone(), root_1.clone())
+        .await?;
+
+    // Epoch 2: objects 150..250 are modified again, relative to epoch 0
+    let db_2 = Arc::new(AuthorityPerpetualTables::open(&temp_dir(), None));
+    insert_keys(&db_2, 900)?;
+    insert_objects(&db_2, modified(100..150, 2))?;
+    insert_objects(&db_2, modified(150..250, 3))?;
+    insert_objects(
+        &db_2,
+        object_ids(1000..1100)
+            .into_iter()
+            .map(Object::immutable_with_id_for_testing),
+    )?;
+    let root_2 = ECMHLiveObjectSetDigest::from(accumulate_live_object_set(&db_2).digest());
+    writer()
+        .await?
+        .write_delta(2, 0, db_2.clone(), root_2.clone())
+        .await?;
+
+    // Deltas can only be written relative to a full snapshot
+    assert!(
+        writer()
+            .await?
+            .write_delta(3, 1, db_2.clone(), root_2.clone())
+            .await
+            .is_err()
+    );
+
+    // Restoring an epoch with a delta snapshot resolves its full base snapshot
+    compare_live_objects(&db_1, &restore(1, root_1).await?)?;
+    compare_live_objects(&db_2, &restore(2, root_2).await?)?;
+    compare_live_objects(&db_0, &restore(0, root_0).await?)?;
+    Ok(())
+}
@@ -2,7 +2,12 @@
 // Modifications Copyright (c) 2024 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{num::NonZeroUsize, path::PathBuf, sync::Arc, time::Duration};
+use std::{
+    num::{NonZeroU64, NonZeroUsize},
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
 
 use anyhow::Result;
 use bytes::Bytes;
@@ -15,7 +20,7 @@ use iota_core::{
 use iota_storage::{
     FileCompression,
     object_store::util::{
-        find_all_dirs_with_epoch_prefix, find_missing_epochs_dirs, path_to_filesystem, put,
+        exists, find_all_dirs_with_epoch_prefix, find_missing_epochs_dirs, path_to_filesystem, put,
         run_manifest_update_loop,
     },
 };
@@ -25,9 +30,9 @@ use prometheus::{
     IntCounter, IntGauge, Registry, register_int_counter_with_registry,
     register_int_gauge_with_registry,
 };
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
-use crate::writer::StateSnapshotWriterV1;
+use crate::{epoch_dir, writer::StateSnapshotWriterV1};
 
 pub struct StateSnapshotUploaderMetrics {
     pub first_missing_state_snapshot_epoch: IntGauge,
@@ -73,6 +78,9 @@ pub struct StateSnapshotUploader {
     /// Time interval to check for presence of new db checkpoint (default: 60
     /// secs)
     interval: Duration,
+    /// Number of epochs between full state snapshots, with delta snapshots
+    /// written in between. Every snapshot is a full snapshot if not set.
+    full_snapshot_interval_epochs: Option<NonZeroU64>,
     metrics: Arc<StateSnapshotUploaderMetrics>,
 }
 
@@ -82,6 +90,7 @@ impl StateSnapshotUploader {
         staging_path: &std::path::Path,
         snapshot_store_config: ObjectStoreConfig,
         interval_s: u64,
+        full_snapshot_interval_epochs: Option<NonZeroU64>,
         registry: &Registry,
         checkpoint_store: Arc<CheckpointStore>,
     ) -> Result<Arc<Self>> {
@@ -103,6 +112,7 @@ impl StateSnapshotUploader {
             staging_store: staging_store_config.make()?,
             snapshot_store: snapshot_store_config.make()?,
             interval: Duration::from_secs(interval_s),
+            full_snapshot_interval_epochs,
             metrics: StateSnapshotUploaderMetrics::new(registry),
         }))
     }
@@ -131,14 +141,6 @@ impl StateSnapshotUploader {
             // or if the local has more advanced epochs than the remote
             if missing_epochs.contains(epoch) || *epoch >= last_missing_epoch {
                 info!("Starting state snapshot creation for epoch: {}", *epoch);
-                let state_snapshot_writer = StateSnapshotWriterV1::new_from_store(
-                    &self.staging_path,
-                    &self.staging_store,
-                    &self.snapshot_store,
-                    FileCompression::Zstd,
-                    NonZeroUsize::new(20).unwrap(),
-                )
-                .await?;
                 let db = Arc::new(AuthorityPerpetualTables::open(
                     &path_to_filesystem(self.db_checkpoint_path.clone(), &db_path.child("store"))?,
                     None,
@@ -152,9 +154,37 @@ impl StateSnapshotUploader {
                     .last()
                     .expect("Expected at least one commitment")
                     .clone();
-                state_snapshot_writer
-                    .write(*epoch, db, state_hash_commitment)
-                    .await?;
+                match self.delta_base_epoch(*epoch).await {
+                    Some(base_epoch) => {
+                        info!("Writing delta state snapshot based on epoch: {base_epoch}");
+                        let result = self
+                            .writer()
+                            .await?
+                            .write_delta(
+                                *epoch,
+                                base_epoch,
+                                db.clone(),
+                                state_hash_commitment.clone(),
+                            )
+                            .await;
+                        if let Err(err) = result {
+                            warn!(
+                                "Failed to write delta state snapshot for epoch {epoch}, \
+                                writing a full snapshot instead: {err:?}"
+                            );
+                            self.writer()
+                                .await?
+                                .write(*epoch, db, state_hash_commitment)
+                                .await?;
+                        }
+                    }
+                    None => {
+                        self.writer()
+                            .await?
+                            .write(*epoch, db, state_hash_commitment)
+                            .await?
+                    }
+                }
                 info!("State snapshot creation successful for epoch: {}", *epoch);
                 // Drops marker in the output directory that upload completed successfully
                 let bytes = Bytes::from_static(b"success");
@@ -185,6 +215,34 @@ impl StateSnapshotUploader {
         Ok(())
     }
 
+    async fn writer(&self) -> Result<StateSnapshotWriterV1> {
+        StateSnapshotWriterV1::new_from_store(
+            &self.staging_path,
+            &self.staging_store,
+            &self.snapshot_store,
+            FileCompression::Zstd,
+            NonZeroUsize::new(20).unwrap(),
+        )
+        .await
+    }
+
+    /// Returns the epoch of the full snapshot that the snapshot of `epoch`
+    /// is written relative to, or `None` if it is written as a full snapshot.
+    /// Full snapshots are written every `full_snapshot_interval_epochs`
+    /// epochs, and whenever the snapshot of the base epoch is missing from
+    /// the remote store.
+    async fn delta_base_epoch(&self, epoch: u64) -> Option<u64> {
+        let interval = self.full_snapshot_interval_epochs?.get();
+        let base_epoch = epoch - epoch % interval;
+        if base_epoch == epoch {
+            return None;
+        }
+        let success_marker = epoch_dir(base_epoch).child(SUCCESS_MARKER);
+        exists(&self.snapshot_store, &success_marker)
+            .await
+            .then_some(base_epoch)
+    }
+
     /// Main loop that checks for missing remote state snapshots and uploads
     /// them from the local store.
     async fn run_upload_loop(
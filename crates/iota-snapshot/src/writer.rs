@@ -14,7 +14,7 @@ use std::{
     sync::Arc,
 };
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, ensure};
 use byteorder::{BigEndian, ByteOrder};
 use fastcrypto::hash::MultisetHash;
 use futures::StreamExt;
@@ -26,7 +26,7 @@ use iota_core::{
 };
 use iota_storage::{
     blob::{BLOB_ENCODING_BYTES, Blob, BlobEncoding},
-    object_store::util::{copy_file, delete_recursively, path_to_filesystem},
+    object_store::util::{copy_file, copy_files, delete_recursively, path_to_filesystem},
 };
 use iota_types::{
     accumulator::Accumulator,
@@ -45,9 +45,14 @@ use tokio_stream::wrappers::ReceiverStream;
 use tracing::debug;
 
 use crate::{
-    FILE_MAX_BYTES, FileCompression, FileMetadata, FileType, MAGIC_BYTES, MANIFEST_FILE_MAGIC,
-    Manifest, ManifestV1, OBJECT_FILE_MAGIC, OBJECT_REF_BYTES, REFERENCE_FILE_MAGIC,
-    SEQUENCE_NUM_BYTES, compute_sha3_checksum, create_file_metadata,
+    DeltaManifestV1, FILE_MAX_BYTES, FileCompression, FileMetadata, FileType, MAGIC_BYTES,
+    MANIFEST_FILE_MAGIC, Manifest, ManifestV1, OBJECT_FILE_MAGIC, OBJECT_REF_BYTES,
+    REFERENCE_FILE_MAGIC, SEQUENCE_NUM_BYTES, compute_sha3_checksum, create_file_metadata,
+    epoch_dir,
+    reader::{
+        FilesByBucketAndPartition, ObjectRefIter, StateSnapshotReaderV1, file_paths,
+        files_by_bucket_and_partition,
+    },
 };
 
 /// LiveObjectSetWriterV1 writes live object set. It creates multiple *.obj
@@ -236,19 +241,154 @@ impl LiveObjectSetWriterV1 {
 
     /// Writes an object reference to the reference file.
     fn write_object_ref(&mut self, object_ref: &ObjectRef) -> Result<()> {
-        let mut buf = [0u8; OBJECT_REF_BYTES];
-        buf[0..ObjectID::LENGTH].copy_from_slice(object_ref.0.as_ref());
-        BigEndian::write_u64(
-            &mut buf[ObjectID::LENGTH..OBJECT_REF_BYTES],
-            object_ref.1.value(),
-        );
-        buf[ObjectID::LENGTH + SEQUENCE_NUM_BYTES..OBJECT_REF_BYTES]
-            .copy_from_slice(object_ref.2.as_ref());
-        self.ref_wbuf.write_all(&buf)?;
+        self.ref_wbuf.write_all(&encode_object_ref(object_ref))?;
+        Ok(())
+    }
+}
+
+/// DeletedObjectSetWriterV1 writes the references of objects that were removed
+/// from the live object set since the base epoch of a delta snapshot. It
+/// creates multiple *.del files in the REFERENCE file format.
+struct DeletedObjectSetWriterV1 {
+    dir_path: PathBuf,
+    bucket_num: u32,
+    current_part_num: u32,
+    wbuf: BufWriter<File>,
+    file_size: usize,
+    files: Vec<FileMetadata>,
+    sender: Sender<FileMetadata>,
+    file_compression: FileCompression,
+}
+
+impl DeletedObjectSetWriterV1 {
+    fn new(
+        dir_path: PathBuf,
+        bucket_num: u32,
+        file_compression: FileCompression,
+        sender: Sender<FileMetadata>,
+    ) -> Result<Self> {
+        let part_num = 1;
+        let (file_size, f) = Self::deletion_file(&dir_path, bucket_num, part_num)?;
+        Ok(DeletedObjectSetWriterV1 {
+            dir_path,
+            bucket_num,
+            current_part_num: part_num,
+            wbuf: BufWriter::new(f),
+            file_size,
+            files: vec![],
+            sender,
+            file_compression,
+        })
+    }
+
+    /// Writes the reference of a deleted object. Creates a new partition if
+    /// the current one exceeds the maximum size.
+    fn write(&mut self, object_ref: &ObjectRef) -> Result<()> {
+        if self.file_size + OBJECT_REF_BYTES > FILE_MAX_BYTES {
+            self.finalize()?;
+            self.current_part_num += 1;
+            let (file_size, f) =
+                Self::deletion_file(&self.dir_path, self.bucket_num, self.current_part_num)?;
+            self.file_size = file_size;
+            self.wbuf = BufWriter::new(f);
+        }
+        self.wbuf.write_all(&encode_object_ref(object_ref))?;
+        self.file_size += OBJECT_REF_BYTES;
+        Ok(())
+    }
+
+    /// Finalizes the current deletion file and returns the FileMetadata of all
+    /// written files.
+    fn done(mut self) -> Result<Vec<FileMetadata>> {
+        self.finalize()?;
+        Ok(self.files)
+    }
+
+    /// Creates a new deletion file for the provided bucket number and part
+    /// number, and returns the file and the number of bytes written to it.
+    fn deletion_file(
+        dir_path: &std::path::Path,
+        bucket_num: u32,
+        part_num: u32,
+    ) -> Result<(usize, File)> {
+        let path = dir_path.join(format!("{bucket_num}_{part_num}.del"));
+        let tmp_path = dir_path.join(format!("{bucket_num}_{part_num}.del.tmp"));
+        let mut f = File::create(tmp_path.clone())?;
+        let mut metab = [0u8; MAGIC_BYTES];
+        BigEndian::write_u32(&mut metab, REFERENCE_FILE_MAGIC);
+        let n = f.write(&metab)?;
+        drop(f);
+        fs::rename(tmp_path, path.clone())?;
+        let mut f = OpenOptions::new().append(true).open(path)?;
+        f.seek(SeekFrom::Start(n as u64))?;
+        Ok((n, f))
+    }
+
+    /// Flushes the current deletion file to disk and sends its FileMetadata to
+    /// the channel.
+    fn finalize(&mut self) -> Result<()> {
+        self.wbuf.flush()?;
+        self.wbuf.get_ref().sync_data()?;
+        let file_path = self
+            .dir_path
+            .join(format!("{}_{}.del", self.bucket_num, self.current_part_num));
+        let file_metadata = create_file_metadata(
+            &file_path,
+            self.file_compression,
+            FileType::Deletion,
+            self.bucket_num,
+            self.current_part_num,
+        )?;
+        self.files.push(file_metadata.clone());
+        self.sender.blocking_send(file_metadata)?;
         Ok(())
     }
 }
 
+/// Iterates over the object references in the given reference files of a
+/// snapshot in object ID order. The references of each bucket are sorted
+/// across its partitions, so the buckets only need to be merged.
+fn sorted_object_refs(
+    local_staging_dir: &std::path::Path,
+    dir: &Path,
+    ref_files: &FilesByBucketAndPartition,
+) -> Result<impl Iterator<Item = ObjectRef>> {
+    let mut buckets = ref_files
+        .values()
+        .map(|part_files| {
+            let parts = part_files
+                .values()
+                .map(|file_metadata| {
+                    ObjectRefIter::new(file_metadata, local_staging_dir.to_path_buf(), dir.clone())
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(parts.into_iter().flatten().peekable())
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(std::iter::from_fn(move || {
+        let next_bucket = buckets
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, bucket)| bucket.peek().map(|object_ref| (i, object_ref.0)))
+            .min_by_key(|(_, object_id)| *object_id)?
+            .0;
+        buckets[next_bucket].next()
+    }))
+}
+
+/// Encodes an object reference in the REFERENCE file format.
+fn encode_object_ref(object_ref: &ObjectRef) -> [u8; OBJECT_REF_BYTES] {
+    let mut buf = [0u8; OBJECT_REF_BYTES];
+    buf[0..ObjectID::LENGTH].copy_from_slice(object_ref.0.as_ref());
+    BigEndian::write_u64(
+        &mut buf[ObjectID::LENGTH..OBJECT_REF_BYTES],
+        object_ref.1.value(),
+    );
+    buf[ObjectID::LENGTH + SEQUENCE_NUM_BYTES..OBJECT_REF_BYTES]
+        .copy_from_slice(object_ref.2.as_ref());
+    buf
+}
+
 /// StateSnapshotWriterV1 writes snapshot files to a local staging dir and
 /// simultaneously uploads them to a remote object store
 pub struct StateSnapshotWriterV1 {
@@ -314,30 +454,113 @@ impl StateSnapshotWriterV1 {
     /// Writes the state snapshot for the provided epoch to the local staging
     /// directory and uploads it to the remote store.
     pub(crate) async fn write_internal(
-        mut self,
+        self,
         epoch: u64,
         perpetual_db: Arc<AuthorityPerpetualTables>,
         root_state_hash: ECMHLiveObjectSetDigest,
     ) -> Result<()> {
-        self.setup_epoch_dir(epoch).await?;
+        self.write_to_dir(epoch, epoch_dir(epoch), move |this, sender| {
+            this.write_live_object_set(
+                epoch,
+                perpetual_db,
+                sender,
+                Self::bucket_func,
+                root_state_hash,
+            )
+        })
+        .await
+    }
 
-        let manifest_file_path = self.epoch_dir(epoch).child("MANIFEST");
+    /// Writes a delta state snapshot for the provided epoch, holding the
+    /// changes of the live object set since `base_epoch`, to the local
+    /// staging directory and uploads it to the remote store. The snapshot of
+    /// `base_epoch` must be a full snapshot in the remote store, whose
+    /// reference files are downloaded to compute the changes.
+    pub async fn write_delta(
+        self,
+        epoch: u64,
+        base_epoch: u64,
+        perpetual_db: Arc<AuthorityPerpetualTables>,
+        root_state_hash: ECMHLiveObjectSetDigest,
+    ) -> Result<()> {
+        ensure!(
+            base_epoch < epoch,
+            "Base epoch {base_epoch} of a delta snapshot must precede epoch {epoch}"
+        );
+        let base_ref_files = self.download_base_snapshot(base_epoch).await?;
+        let base_dir_path =
+            path_to_filesystem(self.local_staging_dir.clone(), &epoch_dir(base_epoch))?;
         let local_staging_dir = self.local_staging_dir.clone();
-        let local_object_store = self.local_staging_store.clone();
-        let remote_object_store = self.remote_object_store.clone();
-
-        let (sender, receiver) = mpsc::channel::<FileMetadata>(1000);
-        // Starts the upload loop, which listens on the receiver for FileMetadata
-        let upload_handle = self.start_upload(epoch, receiver)?;
-        let write_handler = tokio::task::spawn_blocking(move || {
-            self.write_live_object_set(
+        self.write_to_dir(epoch, epoch_dir(epoch), move |this, sender| {
+            let base_refs =
+                sorted_object_refs(&local_staging_dir, &epoch_dir(base_epoch), &base_ref_files)?;
+            this.write_delta_object_set(
                 epoch,
+                base_epoch,
+                base_refs,
                 perpetual_db,
                 sender,
                 Self::bucket_func,
                 root_state_hash,
             )
-        });
+        })
+        .await?;
+        fs::remove_dir_all(base_dir_path)?;
+        Ok(())
+    }
+
+    /// Downloads the MANIFEST and the reference files of the full snapshot of
+    /// `base_epoch` from the remote store to the local staging directory, and
+    /// returns the reference files by bucket and partition.
+    async fn download_base_snapshot(&self, base_epoch: u64) -> Result<FilesByBucketAndPartition> {
+        let dir = epoch_dir(base_epoch);
+        let manifest = StateSnapshotReaderV1::download_manifest(
+            &dir,
+            &self.local_staging_dir,
+            &self.remote_object_store,
+            &self.local_staging_store,
+        )
+        .await?;
+        ensure!(
+            manifest.epoch() == base_epoch,
+            "Download manifest is not for epoch: {base_epoch}"
+        );
+        ensure!(
+            manifest.base_epoch().is_none(),
+            "Base epoch {base_epoch} of a delta snapshot must be a full snapshot"
+        );
+        let ref_files = files_by_bucket_and_partition(&manifest, FileType::Reference);
+        let files = file_paths(&ref_files, &dir);
+        copy_files(
+            &files,
+            &files,
+            &self.remote_object_store,
+            &self.local_staging_store,
+            NonZeroUsize::new(self.concurrency).unwrap(),
+            None,
+        )
+        .await?;
+        Ok(ref_files)
+    }
+
+    /// Sets up `dir`, runs `write` on a blocking thread to write the snapshot
+    /// files of an epoch to it while uploading them to the remote store, and
+    /// finally uploads the MANIFEST.
+    async fn write_to_dir<W>(mut self, epoch: u64, dir: Path, write: W) -> Result<()>
+    where
+        W: FnOnce(&mut Self, Sender<FileMetadata>) -> Result<()> + Send + 'static,
+    {
+        self.setup_dir(&dir).await?;
+
+        let manifest_file_path = dir.child("MANIFEST");
+        let local_staging_dir = self.local_staging_dir.clone();
+        let local_object_store = self.local_staging_store.clone();
+        let remote_object_store = self.remote_object_store.clone();
+
+        let (sender, receiver) = mpsc::channel::<FileMetadata>(1000);
+        // Starts the upload loop, which listens on the receiver for FileMetadata
+        let upload_handle = self.start_upload(dir, receiver)?;
+        let write_handler = tokio::task::spawn_blocking(move || write(&mut self, sender));
         // Awaits the object and reference files to be written to the local staging
         // directory and informs the upload loop
         write_handler.await?.context(format!(
@@ -366,13 +589,12 @@ impl StateSnapshotWriterV1 {
     /// to the remote store in parallel.
     fn start_upload(
         &self,
-        epoch: u64,
+        epoch_dir: Path,
         receiver: Receiver<FileMetadata>,
     ) -> Result<JoinHandle<Result<Vec<()>, anyhow::Error>>> {
         let remote_object_store = self.remote_object_store.clone();
         let local_staging_store = self.local_staging_store.clone();
         let local_dir_path = self.local_staging_dir.clone();
-        let epoch_dir = self.epoch_dir(epoch);
         let upload_concurrency = self.concurrency;
         let join_handle = tokio::spawn(async move {
             // Uploads the files to the remote store in parallel for each received
@@ -421,9 +643,13 @@ impl StateSnapshotWriterV1 {
     {
         let mut object_writers: HashMap<u32, LiveObjectSetWriterV1> = HashMap::new();
         let local_staging_dir_path =
-            path_to_filesystem(self.local_staging_dir.clone(), &self.epoch_dir(epoch))?;
+            path_to_filesystem(self.local_staging_dir.clone(), &epoch_dir(epoch))?;
         let mut acc = Accumulator::default();
-        for object in perpetual_db.iter_live_object_set() {
+        // The live object set is read from a snapshot, so that it matches the root
+        // state hash even if objects are written while the files are being written.
+        let snapshot = perpetual_db.snapshot();
+        let live_object_set = perpetual_db.live_object_set_at(&snapshot)?;
+        for object in live_object_set.iter_live_object_set() {
             StateAccumulator::accumulate_live_object(&mut acc, &object);
             let bucket_num = bucket_func(&object);
             // Creates a new LiveObjectSetWriterV1 for the bucket if it does not exist
@@ -452,21 +678,107 @@ impl StateSnapshotWriterV1 {
             files.extend(writer.done()?);
         }
         // Write the manifest file for the epoch(bucket)
-        self.write_manifest(epoch, files)?;
+        let manifest = Manifest::V1(ManifestV1 {
+            snapshot_version: 1,
+            address_length: ObjectID::LENGTH as u64,
+            file_metadata: files,
+            epoch,
+        });
+        self.write_manifest(&epoch_dir(epoch), manifest)?;
         Ok(())
     }
 
-    /// Writes the manifest file for the provided FileMetadata of an epoch and
-    /// its sha3 checksum.
-    fn write_manifest(&mut self, epoch: u64, file_metadata: Vec<FileMetadata>) -> Result<()> {
-        let (f, manifest_file_path) = self.manifest_file(epoch)?;
-        let mut wbuf = BufWriter::new(f);
-        let manifest: Manifest = Manifest::V1(ManifestV1 {
+    /// Writes the changes of the live object set between the base snapshot
+    /// and `perpetual_db` in the form of object and reference files for
+    /// created or modified objects, deletion files for objects that are no
+    /// longer live, and a MANIFEST referencing the base epoch. Both live
+    /// object sets are iterated in object ID order, so they can be compared in
+    /// a single pass.
+    fn write_delta_object_set<F>(
+        &mut self,
+        epoch: u64,
+        base_epoch: u64,
+        base_refs: impl Iterator<Item = ObjectRef>,
+        perpetual_db: Arc<AuthorityPerpetualTables>,
+        sender: Sender<FileMetadata>,
+        bucket_func: F,
+        root_state_hash: ECMHLiveObjectSetDigest,
+    ) -> Result<()>
+    where
+        F: Fn(&LiveObject) -> u32,
+    {
+        let mut object_writers: HashMap<u32, LiveObjectSetWriterV1> = HashMap::new();
+        let local_staging_dir_path =
+            path_to_filesystem(self.local_staging_dir.clone(), &epoch_dir(epoch))?;
+        // Deleted objects are only known by reference, so they all go to a single
+        // bucket
+        let mut deletion_writer = DeletedObjectSetWriterV1::new(
+            local_staging_dir_path.clone(),
+            1,
+            self.file_compression,
+            sender.clone(),
+        )?;
+        let mut acc = Accumulator::default();
+        let mut base_refs = base_refs.peekable();
+        let snapshot = perpetual_db.snapshot();
+        let live_object_set = perpetual_db.live_object_set_at(&snapshot)?;
+        for object in live_object_set.iter_live_object_set() {
+            StateAccumulator::accumulate_live_object(&mut acc, &object);
+            let object_ref = object.object_reference();
+            // Base objects with a smaller ID than the current one were deleted
+            while let Some(deleted) = base_refs.next_if(|base_ref| base_ref.0 < object_ref.0) {
+                deletion_writer.write(&deleted)?;
+            }
+            if let Some(base_ref) = base_refs.next_if(|base_ref| base_ref.0 == object_ref.0) {
+                if base_ref == object_ref {
+                    continue;
+                }
+                // The previous version of a modified object is removed from the base set
+                deletion_writer.write(&base_ref)?;
+            }
+            let bucket_num = bucket_func(&object);
+            if let Vacant(entry) = object_writers.entry(bucket_num) {
+                entry.insert(LiveObjectSetWriterV1::new(
+                    local_staging_dir_path.clone(),
+                    bucket_num,
+                    self.file_compression,
+                    sender.clone(),
+                )?);
+            }
+            object_writers
+                .get_mut(&bucket_num)
+                .context("Unexpected missing bucket writer")?
+                .write(&object)?;
+        }
+        for deleted in base_refs {
+            deletion_writer.write(&deleted)?;
+        }
+        assert_eq!(
+            ECMHLiveObjectSetDigest::from(acc.digest()),
+            root_state_hash,
+            "Root state hash mismatch!"
+        );
+        let mut files = vec![];
+        for (_, writer) in object_writers.into_iter() {
+            files.extend(writer.done()?);
+        }
+        files.extend(deletion_writer.done()?);
+        let manifest = Manifest::DeltaV1(DeltaManifestV1 {
             snapshot_version: 1,
             address_length: ObjectID::LENGTH as u64,
-            file_metadata,
+            file_metadata: files,
             epoch,
+            base_epoch,
         });
+        self.write_manifest(&epoch_dir(epoch), manifest)?;
+        Ok(())
+    }
+
+    /// Writes the manifest file in the provided directory and its sha3
+    /// checksum.
+    fn write_manifest(&mut self, dir: &Path, manifest: Manifest) -> Result<()> {
+        let (f, manifest_file_path) = self.manifest_file(dir)?;
+        let mut wbuf = BufWriter::new(f);
         let serialized_manifest = bcs::to_bytes(&manifest)?;
         wbuf.write_all(&serialized_manifest)?;
         wbuf.flush()?;
@@ -482,17 +794,13 @@ impl StateSnapshotWriterV1 {
         Ok(())
     }
 
-    /// Creates a new manifest file for the provided epoch and returns the file
-    /// and the path to the file.
-    fn manifest_file(&mut self, epoch: u64) -> Result<(File, PathBuf)> {
-        let manifest_file_path = path_to_filesystem(
-            self.local_staging_dir.clone(),
-            &self.epoch_dir(epoch).child("MANIFEST"),
-        )?;
-        let manifest_file_tmp_path = path_to_filesystem(
-            self.local_staging_dir.clone(),
-            &self.epoch_dir(epoch).child("MANIFEST.tmp"),
-        )?;
+    /// Creates a new manifest file in the provided directory and returns the
+    /// file and the path to the file.
+    fn manifest_file(&mut self, dir: &Path) -> Result<(File, PathBuf)> {
+        let manifest_file_path =
+            path_to_filesystem(self.local_staging_dir.clone(), &dir.child("MANIFEST"))?;
+        let manifest_file_tmp_path =
+            path_to_filesystem(self.local_staging_dir.clone(), &dir.child("MANIFEST.tmp"))?;
         let mut f = File::create(manifest_file_tmp_path.clone())?;
         let mut metab = vec![0u8; MAGIC_BYTES];
         BigEndian::write_u32(&mut metab, MANIFEST_FILE_MAGIC);
@@ -513,23 +821,18 @@ impl StateSnapshotWriterV1 {
         1u32
     }
 
-    fn epoch_dir(&self, epoch: u64) -> Path {
-        Path::from(format!("epoch_{}", epoch))
-    }
-
-    /// Creates a new epoch directory and a new staging directory for the epoch
-    /// in the local store. Deletes the old ones if they exist.
-    async fn setup_epoch_dir(&self, epoch: u64) -> Result<()> {
-        let epoch_dir = self.epoch_dir(epoch);
+    /// Creates a new snapshot directory and a new staging directory for it in
+    /// the local store. Deletes the old ones if they exist.
+    async fn setup_dir(&self, dir: &Path) -> Result<()> {
         // Deletes remote epoch dir if it exists
         delete_recursively(
-            &epoch_dir,
+            dir,
             &self.remote_object_store,
             NonZeroUsize::new(self.concurrency).unwrap(),
         )
         .await?;
         // Deletes local staging epoch dir if it exists
-        let local_epoch_dir_path = self.local_staging_dir.join(format!("epoch_{}", epoch));
+        let local_epoch_dir_path = path_to_filesystem(self.local_staging_dir.clone(), dir)?;
         if local_epoch_dir_path.exists() {
             fs::remove_dir_all(&local_epoch_dir_path)?;
         }
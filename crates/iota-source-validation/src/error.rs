@@ -14,6 +14,12 @@ use move_symbol_pool::Symbol;
 #[derive(Debug, thiserror::Error)]
 pub struct AggregateError(pub(crate) Vec<Error>);
 
+impl AggregateError {
+    pub fn errors(&self) -> &[Error] {
+        &self.0
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("Cannot check local module for {package}: {message}")]
@@ -220,6 +220,7 @@ impl ValidatorConfigBuilder {
             firewall_config: self.firewall_config,
             execution_cache: ExecutionCacheConfig::default(),
             enable_validator_tx_finalizer: true,
+            remote_signer: None,
         }
     }
 
@@ -509,6 +510,7 @@ impl FullnodeConfigBuilder {
             execution_cache: ExecutionCacheConfig::default(),
             // This is a validator specific feature.
             enable_validator_tx_finalizer: false,
+            remote_signer: None,
         }
     }
 
@@ -201,6 +201,8 @@ where
     K: Serialize + serde::de::DeserializeOwned + Clone + std::fmt::Debug,
     V: serde::Serialize + DeserializeOwned + Clone,
 {
+    let snapshot = db_map.snapshot();
+    let db_map = snapshot.map(db_map)?;
     let mut entries = Vec::new();
     match termination {
         SearchRange::ExclusiveLastKey(exclusive_last_key) => {
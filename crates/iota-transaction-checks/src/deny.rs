@@ -2,15 +2,19 @@
 // Modifications Copyright (c) 2024 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::HashSet;
+
 use fastcrypto_zkp::bn254::zk_login::OIDCProvider;
 use iota_config::transaction_deny_config::TransactionDenyConfig;
 use iota_types::{
-    base_types::ObjectRef,
+    base_types::{ObjectID, ObjectRef},
     error::{IotaError, IotaResult, UserInputError},
     signature::GenericSignature,
     storage::BackingPackageStore,
     transaction::{Command, InputObjectKind, TransactionData, TransactionDataAPI},
 };
+use tracing::warn;
+
 macro_rules! deny_if_true {
     ($cond:expr, $msg:expr) => {
         if ($cond) {
@@ -23,6 +27,32 @@ macro_rules! deny_if_true {
     };
 }
 
+/// Resolves the original package IDs of the packages in the package deny list
+/// of `filter_config`, which deny their entire upgrade families when
+/// `package_deny_upgrade_family` is set. Packages that cannot be loaded deny
+/// the family they are the original package of.
+///
+/// This must be called whenever the package deny list changes, so that
+/// checking transactions does not need to load the denied packages.
+pub fn resolve_package_deny_families(
+    filter_config: &mut TransactionDenyConfig,
+    package_store: &dyn BackingPackageStore,
+) {
+    let families = filter_config
+        .get_package_deny_set()
+        .iter()
+        .map(|id| match package_store.get_package_object(id) {
+            Ok(Some(package)) => package.move_package().original_package_id(),
+            Ok(None) => *id,
+            Err(err) => {
+                warn!("Failed to load denied package {id}: {err}");
+                *id
+            }
+        })
+        .collect::<HashSet<_>>();
+    filter_config.set_package_deny_families(families);
+}
+
 /// Check that the provided transaction is allowed to be signed according to the
 /// deny config.
 pub fn check_transaction_for_signing(
@@ -158,7 +188,11 @@ fn check_package_dependencies(
     if deny_map.is_empty() {
         return Ok(());
     }
+    let deny_upgrade_family = filter_config.package_deny_upgrade_family();
     let mut dependencies = vec![];
+    // Original package IDs of the dependencies, only collected when the upgrade
+    // families of the denied packages are denied.
+    let mut original_ids = vec![];
     for command in tx_data.kind().iter_commands() {
         match command {
             Command::Publish(_, deps) => {
@@ -166,12 +200,19 @@ fn check_package_dependencies(
                 // by the user. But that's OK because this publish transaction will fail
                 // to execute in the end. Similar reasoning for Upgrade.
                 dependencies.extend(deps.iter().copied());
+                if deny_upgrade_family {
+                    collect_original_ids(package_store, deps, &mut original_ids)?;
+                }
             }
             Command::Upgrade(_, deps, package_id, _) => {
                 dependencies.extend(deps.iter().copied());
                 // It's crucial that we don't allow upgrading a package in the deny list,
                 // otherwise one can bypass the deny list by upgrading a package.
                 dependencies.push(*package_id);
+                if deny_upgrade_family {
+                    collect_original_ids(package_store, deps, &mut original_ids)?;
+                    collect_original_ids(package_store, &[*package_id], &mut original_ids)?;
+                }
             }
             Command::MoveCall(call) => {
                 let package = package_store.get_package_object(&call.package)?.ok_or(
@@ -187,14 +228,20 @@ fn check_package_dependencies(
                 // deny list. This means that we only make sure that the denied package is not
                 // currently used as a dependency. This allows us to deny an older version of
                 // package but permits the use of a newer version.
+                let move_package = package.move_package();
                 dependencies.extend(
-                    package
-                        .move_package()
+                    move_package
                         .linkage_table()
                         .values()
                         .map(|upgrade_info| upgrade_info.upgraded_id),
                 );
-                dependencies.push(package.move_package().id());
+                dependencies.push(move_package.id());
+                if deny_upgrade_family {
+                    original_ids.extend(move_package.linkage_table().iter().map(
+                        |(original_id, upgrade_info)| (upgrade_info.upgraded_id, *original_id),
+                    ));
+                    original_ids.push((move_package.id(), move_package.original_package_id()));
+                }
             }
             Command::TransferObjects(..)
             | &Command::SplitCoins(..)
@@ -202,11 +249,38 @@ fn check_package_dependencies(
             | &Command::MakeMoveVec(..) => {}
         }
     }
-    for dep in dependencies {
+    for dep in &dependencies {
         deny_if_true!(
-            deny_map.contains(&dep),
+            deny_map.contains(dep),
             format!("Access to package {:?} is temporarily disabled", dep)
         );
     }
+    // Packages of the same upgrade family share their original package ID.
+    let denied_families = filter_config.package_deny_family_set();
+    for (dep, original) in &original_ids {
+        deny_if_true!(
+            denied_families.contains(original),
+            format!(
+                "Access to package {:?} is temporarily disabled, its upgrade family {:?} is denied",
+                dep, original
+            )
+        );
+    }
+    Ok(())
+}
+
+/// Collects the original package IDs of the packages `ids`. Packages that
+/// cannot be found are skipped, a transaction depending on them will fail
+/// anyway.
+fn collect_original_ids(
+    package_store: &dyn BackingPackageStore,
+    ids: &[ObjectID],
+    original_ids: &mut Vec<(ObjectID, ObjectID)>,
+) -> IotaResult {
+    for id in ids {
+        if let Some(package) = package_store.get_package_object(id)? {
+            original_ids.push((*id, package.move_package().original_package_id()));
+        }
+    }
     Ok(())
 }
@@ -746,6 +746,37 @@ impl RestStateReader for PersistedStoreInnerReadOnlyWrapper {
     ) -> iota_types::storage::error::Result<Option<iota_types::storage::CoinInfo>> {
         todo!()
     }
+
+    fn account_balances_iter(
+        &self,
+        owner: IotaAddress,
+        cursor: Option<StructTag>,
+    ) -> iota_types::storage::error::Result<
+        Box<dyn Iterator<Item = iota_types::storage::AccountBalanceInfo> + '_>,
+    > {
+        self.sync();
+
+        let objects = self
+            .inner
+            .live_objects
+            .unbounded_iter()
+            .filter_map(|(id, version)| {
+                self.inner
+                    .objects
+                    .get(&id)
+                    .expect("Fatal: DB read failed")
+                    .and_then(|versions| versions.get(&version).cloned())
+            })
+            .filter(
+                |object| matches!(object.owner, Owner::AddressOwner(address) if address == owner),
+            );
+        let balances = iota_types::storage::AccountBalanceInfo::from_owned_objects(
+            owner,
+            objects,
+            cursor.as_ref(),
+        );
+        Ok(Box::new(balances.into_iter()))
+    }
 }
 
 impl PersistedStoreInnerReadOnlyWrapper {
@@ -1079,6 +1079,15 @@ impl SequenceNumber {
     pub const CONGESTED: SequenceNumber = SequenceNumber(SequenceNumber::MAX.value() + 2);
     pub const RANDOMNESS_UNAVAILABLE: SequenceNumber =
         SequenceNumber(SequenceNumber::MAX.value() + 3);
+    /// The largest congestion surcharge that can be encoded in the version of
+    /// a cancelled shared object. It is far above any payable gas price.
+    pub const MAX_CONGESTION_SURCHARGE: u64 = u32::MAX as u64;
+    // Versions from this one up to `MAX_CONGESTION_SURCHARGE` above it are
+    // assigned to the congested shared objects of transactions cancelled because
+    // their gas price doesn't cover the congestion surcharge, which is the offset
+    // of the version from this one. Use `SharedObjectCancellation` to encode and
+    // decode them.
+    const CONGESTED_WITH_SURCHARGE_BASE: u64 = SequenceNumber::MAX.value() + 4;
 
     pub const fn new() -> Self {
         SequenceNumber(0)
@@ -1127,10 +1136,24 @@ impl SequenceNumber {
         SequenceNumber(max_input.0 + 1)
     }
 
+    /// Returns the cancellation encoded in this version, if it is the version
+    /// assigned to a shared object of a cancelled transaction.
+    pub fn cancellation(&self) -> Option<SharedObjectCancellation> {
+        match *self {
+            SequenceNumber::CANCELLED_READ => Some(SharedObjectCancellation::Read),
+            SequenceNumber::CONGESTED => Some(SharedObjectCancellation::Congested),
+            SequenceNumber::RANDOMNESS_UNAVAILABLE => {
+                Some(SharedObjectCancellation::RandomnessUnavailable)
+            }
+            SequenceNumber(version) => version
+                .checked_sub(Self::CONGESTED_WITH_SURCHARGE_BASE)
+                .filter(|surcharge| *surcharge <= Self::MAX_CONGESTION_SURCHARGE)
+                .map(SharedObjectCancellation::CongestedWithSurcharge),
+        }
+    }
+
     pub fn is_cancelled(&self) -> bool {
-        self == &SequenceNumber::CANCELLED_READ
-            || self == &SequenceNumber::CONGESTED
-            || self == &SequenceNumber::RANDOMNESS_UNAVAILABLE
+        self.cancellation().is_some()
     }
 
     pub fn is_valid(&self) -> bool {
@@ -1138,6 +1161,51 @@ impl SequenceNumber {
     }
 }
 
+/// The reason a shared object of a cancelled transaction is cancelled, which is
+/// encoded in the version assigned to the object.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SharedObjectCancellation {
+    /// The object is not responsible for the cancellation.
+    Read,
+    /// The object is congested.
+    Congested,
+    /// The object is the randomness state, and randomness is unavailable.
+    RandomnessUnavailable,
+    /// The object is congested, and the gas price of the transaction doesn't
+    /// cover the congestion surcharge, on top of the reference gas price,
+    /// required to access it.
+    CongestedWithSurcharge(u64),
+}
+
+impl SharedObjectCancellation {
+    /// Returns the congestion surcharge required to access the object, if any.
+    pub fn congestion_surcharge(&self) -> Option<u64> {
+        match self {
+            Self::CongestedWithSurcharge(surcharge) => Some(*surcharge),
+            _ => None,
+        }
+    }
+}
+
+impl From<SharedObjectCancellation> for SequenceNumber {
+    fn from(cancellation: SharedObjectCancellation) -> Self {
+        match cancellation {
+            SharedObjectCancellation::Read => SequenceNumber::CANCELLED_READ,
+            SharedObjectCancellation::Congested => SequenceNumber::CONGESTED,
+            SharedObjectCancellation::RandomnessUnavailable => {
+                SequenceNumber::RANDOMNESS_UNAVAILABLE
+            }
+            SharedObjectCancellation::CongestedWithSurcharge(surcharge) => {
+                assert!(
+                    surcharge <= SequenceNumber::MAX_CONGESTION_SURCHARGE,
+                    "congestion surcharge {surcharge} can't be encoded in a version"
+                );
+                SequenceNumber(SequenceNumber::CONGESTED_WITH_SURCHARGE_BASE + surcharge)
+            }
+        }
+    }
+}
+
 impl From<SequenceNumber> for u64 {
     fn from(val: SequenceNumber) -> Self {
         val.0
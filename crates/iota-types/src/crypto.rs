@@ -10,6 +10,7 @@ use std::{
 };
 
 use anyhow::{Error, anyhow};
+use async_trait::async_trait;
 use derive_more::{AsMut, AsRef, From};
 pub use enum_dispatch::enum_dispatch;
 use eyre::eyre;
@@ -28,6 +29,7 @@ use fastcrypto::{
     },
     encoding::{Base64, Bech32, Encoding, Hex},
     error::{FastCryptoError, FastCryptoResult},
+    groups::bls12381,
     hash::{Blake2b256, HashFunction},
     secp256k1::{
         Secp256k1KeyPair, Secp256k1PublicKey, Secp256k1PublicKeyAsBytes, Secp256k1Signature,
@@ -37,6 +39,7 @@ use fastcrypto::{
         Secp256r1KeyPair, Secp256r1PublicKey, Secp256r1PublicKeyAsBytes, Secp256r1Signature,
         Secp256r1SignatureAsBytes,
     },
+    serde_helpers::ToFromByteArray,
 };
 use fastcrypto_zkp::{bn254::zk_login::ZkLoginInputs, zk_login_utils::Bn254FrElement};
 use rand::{
@@ -553,6 +556,24 @@ impl Default for AuthorityPublicKeyBytes {
 // Add helper calls for Authority Signature
 //
 
+/// Signs with the authority key of a validator.
+///
+/// Unlike with [`Signer`], signing may fail, e.g. when the key is held by a
+/// remote signer that can't be reached. The request being served then fails
+/// rather than the node. Signing is asynchronous, so that waiting for a
+/// remote signer doesn't block the runtime.
+#[async_trait]
+pub trait AuthoritySigner: Send + Sync {
+    async fn try_sign(&self, msg: &[u8]) -> IotaResult<AuthoritySignature>;
+}
+
+#[async_trait]
+impl AuthoritySigner for AuthorityKeyPair {
+    async fn try_sign(&self, msg: &[u8]) -> IotaResult<AuthoritySignature> {
+        Ok(self.sign(msg))
+    }
+}
+
 pub trait IotaAuthoritySignature {
     fn verify_secure<T>(
         &self,
@@ -1165,6 +1186,27 @@ impl AuthoritySignInfo {
             ),
         }
     }
+
+    /// Same as [`AuthoritySignInfo::new`], failing if `secret` fails to sign.
+    pub async fn try_new<T>(
+        epoch: EpochId,
+        value: &T,
+        intent: Intent,
+        name: AuthorityName,
+        secret: &dyn AuthoritySigner,
+    ) -> IotaResult<Self>
+    where
+        T: Serialize,
+    {
+        let mut message = bcs::to_bytes(&IntentMessage::new(intent, value))
+            .expect("Message serialization should not fail");
+        epoch.write(&mut message);
+        Ok(Self {
+            epoch,
+            authority: name,
+            signature: secret.try_sign(&message).await?,
+        })
+    }
 }
 
 impl Hash for AuthoritySignInfo {
@@ -1733,10 +1775,14 @@ pub enum CompressedSignature {
     Secp256k1(Secp256k1SignatureAsBytes),
     Secp256r1(Secp256r1SignatureAsBytes),
     ZkLogin(ZkLoginAuthenticatorAsBytes),
+    Passkey(PasskeyAuthenticatorAsBytes),
 }
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub struct ZkLoginAuthenticatorAsBytes(#[schemars(with = "Base64")] pub Vec<u8>);
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct PasskeyAuthenticatorAsBytes(#[schemars(with = "Base64")] pub Vec<u8>);
+
 impl AsRef<[u8]> for CompressedSignature {
     fn as_ref(&self) -> &[u8] {
         match self {
@@ -1744,6 +1790,7 @@ impl AsRef<[u8]> for CompressedSignature {
             CompressedSignature::Secp256k1(sig) => &sig.0,
             CompressedSignature::Secp256r1(sig) => &sig.0,
             CompressedSignature::ZkLogin(sig) => &sig.0,
+            CompressedSignature::Passkey(sig) => &sig.0,
         }
     }
 }
@@ -1776,6 +1823,20 @@ pub type RandomnessPartialSignature = fastcrypto_tbls::tbls::PartialSignature<Ra
 pub type RandomnessPrivateKey =
     fastcrypto_tbls::ecies::PrivateKey<fastcrypto::groups::bls12381::G2Element>;
 
+/// Returns the scalar of the authority key, from which the random beacon
+/// derives the validator's DKG private key.
+pub fn randomness_key_scalar(authority_key_pair: &AuthorityKeyPair) -> bls12381::Scalar {
+    bls12381::Scalar::from_byte_array(
+        authority_key_pair
+            .copy()
+            .private()
+            .as_bytes()
+            .try_into()
+            .expect("key length should match"),
+    )
+    .expect("should work to convert BLS key to Scalar")
+}
+
 /// Round number of generated randomness.
 #[derive(Clone, Copy, Hash, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct RandomnessRound(pub u64);
@@ -672,6 +672,9 @@ pub enum IotaError {
 
     #[error("The request did not contain a certificate")]
     NoCertificateProvided,
+
+    #[error("Failed to sign with the authority key: {error}")]
+    AuthoritySigning { error: String },
 }
 
 #[repr(u64)]
@@ -835,6 +838,10 @@ impl IotaError {
             IotaError::TooManyTransactionsPendingConsensus => true,
             IotaError::ValidatorOverloadedRetryAfter { .. } => true,
 
+            // The authority key may be held by a remote signer that is temporarily
+            // unavailable
+            IotaError::AuthoritySigning { .. } => true,
+
             // Non retryable error
             IotaError::Execution(..) => false,
             IotaError::ByzantineAuthoritySuspicion { .. } => false,
@@ -208,6 +208,15 @@ pub enum ExecutionFailureStatus {
 
     #[error("Certificate is cancelled because randomness could not be generated this epoch")]
     ExecutionCancelledDueToRandomnessUnavailable,
+
+    #[error(
+        "Certificate is cancelled due to congestion on shared objects: {congested_objects}, \
+        a gas price of at least the reference gas price plus {surcharge} is required to access them"
+    )]
+    ExecutionCancelledDueToSharedObjectCongestionWithSurcharge {
+        congested_objects: CongestedObjects,
+        surcharge: u64,
+    },
     // NOTE: if you want to add a new enum,
     // please add it at the end for Rust SDK backward compatibility.
 }
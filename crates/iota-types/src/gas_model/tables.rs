@@ -343,6 +343,10 @@ impl GasStatus {
     pub fn instructions_executed(&self) -> u64 {
         self.instructions_executed
     }
+
+    pub fn profiler(&self) -> Option<&GasProfiler> {
+        self.profiler.as_ref()
+    }
 }
 
 /// Returns a tuple of (<pops>, <pushes>, <stack_size_decrease>,
@@ -744,7 +744,10 @@ impl From<TransactionEffects> for crate::effects::TransactionEffects {
         match value {
             TransactionEffects::V1(transaction_effects_v1) => {
                 crate::effects::effects_v1::TransactionEffectsV1 {
-                    status: transaction_effects_v1.status.into(),
+                    status: restore_congestion_surcharge(
+                        transaction_effects_v1.status.into(),
+                        &transaction_effects_v1.unchanged_shared_objects,
+                    ),
                     executed_epoch: transaction_effects_v1.epoch,
                     gas_used: crate::gas::GasCostSummary::new(
                         transaction_effects_v1.gas_used.computation_cost,
@@ -837,6 +840,48 @@ impl From<TransactionEffects> for crate::effects::TransactionEffects {
     }
 }
 
+// The SDK converts a cancellation due to a congestion surcharge into a plain
+// congestion cancellation, so the surcharge is restored from the version of the
+// congested objects.
+fn restore_congestion_surcharge(
+    status: crate::execution_status::ExecutionStatus,
+    unchanged_shared_objects: &[UnchangedSharedObject],
+) -> crate::execution_status::ExecutionStatus {
+    use crate::{
+        base_types::SequenceNumber,
+        execution_status::{ExecutionFailureStatus, ExecutionStatus},
+    };
+
+    let ExecutionStatus::Failure {
+        error:
+            ExecutionFailureStatus::ExecutionCancelledDueToSharedObjectCongestion { congested_objects },
+        command,
+    } = status
+    else {
+        return status;
+    };
+    let surcharge = unchanged_shared_objects
+        .iter()
+        .find_map(|obj| match obj.kind {
+            UnchangedSharedKind::Cancelled { version } => SequenceNumber::from(version)
+                .cancellation()
+                .and_then(|cancellation| cancellation.congestion_surcharge()),
+            _ => None,
+        });
+    let error = match surcharge {
+        Some(surcharge) => {
+            ExecutionFailureStatus::ExecutionCancelledDueToSharedObjectCongestionWithSurcharge {
+                congested_objects,
+                surcharge,
+            }
+        }
+        None => ExecutionFailureStatus::ExecutionCancelledDueToSharedObjectCongestion {
+            congested_objects,
+        },
+    };
+    ExecutionStatus::Failure { error, command }
+}
+
 macro_rules! impl_convert_digest {
     ($name:ident) => {
         impl From<crate::digests::$name> for $name {
@@ -1093,6 +1138,15 @@ impl From<crate::execution_status::ExecutionFailureStatus> for ExecutionError {
             ExecutionFailureStatus::ExecutionCancelledDueToRandomnessUnavailable => {
                 Self::ExecutionCancelledDueToRandomnessUnavailable
             }
+            // The SDK has no notion of the congestion surcharge yet. It is still
+            // encoded in the versions of the congested objects in the effects, which
+            // is how it is restored when converting the effects back.
+            ExecutionFailureStatus::ExecutionCancelledDueToSharedObjectCongestionWithSurcharge {
+                congested_objects,
+                ..
+            } => Self::ExecutionCancelledDueToSharedObjectCongestion {
+                congested_objects: congested_objects.0.into_iter().map(Into::into).collect(),
+            },
         }
     }
 }
@@ -17,7 +17,7 @@ use crate::{
     committee::{Committee, EpochId},
     crypto::{
         AuthorityKeyPair, AuthorityQuorumSignInfo, AuthoritySignInfo, AuthoritySignInfoTrait,
-        AuthoritySignature, AuthorityStrongQuorumSignInfo, EmptySignInfo, Signer,
+        AuthoritySignature, AuthoritySigner, AuthorityStrongQuorumSignInfo, EmptySignInfo, Signer,
     },
     error::IotaResult,
     executable_transaction::CertificateProof,
@@ -140,6 +140,27 @@ where
         AuthoritySignInfo::new(epoch, &data, Intent::iota_app(T::SCOPE), authority, secret)
     }
 
+    /// Same as [`Envelope::new`], failing if `secret` fails to sign.
+    pub async fn try_new(
+        epoch: EpochId,
+        data: T,
+        secret: &dyn AuthoritySigner,
+        authority: AuthorityName,
+    ) -> IotaResult<Self> {
+        let auth_signature = Self::try_sign(epoch, &data, secret, authority).await?;
+        Ok(Self::new_from_data_and_sig(data, auth_signature))
+    }
+
+    /// Same as [`Envelope::sign`], failing if `secret` fails to sign.
+    pub async fn try_sign(
+        epoch: EpochId,
+        data: &T,
+        secret: &dyn AuthoritySigner,
+        authority: AuthorityName,
+    ) -> IotaResult<AuthoritySignInfo> {
+        AuthoritySignInfo::try_new(epoch, data, Intent::iota_app(T::SCOPE), authority, secret).await
+    }
+
     pub fn epoch(&self) -> EpochId {
         self.auth_signature.epoch
     }
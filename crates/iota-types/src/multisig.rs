@@ -29,6 +29,7 @@ use crate::{
     crypto::{CompressedSignature, DefaultHash, PublicKey, SignatureScheme},
     digests::ZKLoginInputsDigest,
     error::IotaError,
+    passkey_authenticator::PasskeyAuthenticator,
     signature::{AuthenticatorTrait, GenericSignature, VerifyParams},
     signature_verification::VerifiedDigestCache,
     zk_login_authenticator::ZkLoginAuthenticator,
@@ -196,6 +197,24 @@ impl AuthenticatorTrait for MultiSig {
                         )
                         .map_err(|e| FastCryptoError::GeneralError(e.to_string()))
                 }
+                CompressedSignature::Passkey(p) => {
+                    let authenticator = PasskeyAuthenticator::from_bytes(&p.0).map_err(|_| {
+                        IotaError::InvalidSignature {
+                            error: "Invalid passkey authenticator bytes".to_string(),
+                        }
+                    })?;
+                    // The passkey authenticator checks that the address derived from its own
+                    // public key matches the one of the member, which binds the signature to
+                    // the member public key.
+                    authenticator
+                        .verify_claims(
+                            value,
+                            IotaAddress::from(subsig_pubkey),
+                            verify_params,
+                            zklogin_inputs_cache.clone(),
+                        )
+                        .map_err(|e| FastCryptoError::GeneralError(e.to_string()))
+                }
             };
             if res.is_ok() {
                 weight_sum += *weight as u16;
@@ -338,6 +357,13 @@ impl MultiSig {
             .collect()
     }
 
+    /// Returns whether any of the signatures is a passkey signature.
+    pub fn has_passkey_sigs(&self) -> bool {
+        self.sigs
+            .iter()
+            .any(|s| matches!(s, CompressedSignature::Passkey(_)))
+    }
+
     pub fn get_indices(&self) -> Result<Vec<u8>, IotaError> {
         as_indices(self.bitmap)
     }
@@ -25,8 +25,8 @@ use crate::{
     base_types::IotaAddress,
     committee::EpochId,
     crypto::{
-        CompressedSignature, IotaSignature, PublicKey, Signature, SignatureScheme,
-        ZkLoginAuthenticatorAsBytes,
+        CompressedSignature, IotaSignature, PasskeyAuthenticatorAsBytes, PublicKey, Signature,
+        SignatureScheme, ZkLoginAuthenticatorAsBytes,
     },
     digests::ZKLoginInputsDigest,
     error::{IotaError, IotaResult},
@@ -164,6 +164,9 @@ impl GenericSignature {
             GenericSignature::ZkLoginAuthenticator(s) => Ok(CompressedSignature::ZkLogin(
                 ZkLoginAuthenticatorAsBytes(s.as_ref().to_vec()),
             )),
+            GenericSignature::PasskeyAuthenticator(s) => Ok(CompressedSignature::Passkey(
+                PasskeyAuthenticatorAsBytes(s.as_ref().to_vec()),
+            )),
             _ => Err(IotaError::UnsupportedFeature {
                 error: "Unsupported signature scheme".to_string(),
             }),
@@ -202,6 +205,7 @@ impl GenericSignature {
                 }
             }
             GenericSignature::ZkLoginAuthenticator(s) => s.get_pk(),
+            GenericSignature::PasskeyAuthenticator(s) => s.get_pk(),
             _ => Err(IotaError::UnsupportedFeature {
                 error: "Unsupported signature scheme".to_string(),
             }),
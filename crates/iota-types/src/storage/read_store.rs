@@ -2,7 +2,7 @@
 // Modifications Copyright (c) 2024 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 use move_core_types::language_storage::{StructTag, TypeTag};
 use serde::{Deserialize, Serialize};
@@ -21,6 +21,7 @@ use crate::{
     messages_checkpoint::{
         CheckpointContents, CheckpointSequenceNumber, FullCheckpointContents, VerifiedCheckpoint,
     },
+    object::Object,
     transaction::VerifiedTransaction,
 };
 
@@ -678,6 +679,14 @@ pub trait RestStateReader: ObjectStore + ReadStore + Send + Sync {
     ) -> Result<Box<dyn Iterator<Item = (DynamicFieldKey, DynamicFieldIndexInfo)> + '_>>;
 
     fn get_coin_info(&self, coin_type: &StructTag) -> Result<Option<CoinInfo>>;
+
+    /// Iterate over the balances of `owner`, ordered by coin type and starting
+    /// at the coin type `cursor` if provided.
+    fn account_balances_iter(
+        &self,
+        owner: IotaAddress,
+        cursor: Option<StructTag>,
+    ) -> Result<Box<dyn Iterator<Item = AccountBalanceInfo> + '_>>;
 }
 
 pub struct AccountOwnedObjectInfo {
@@ -687,6 +696,48 @@ pub struct AccountOwnedObjectInfo {
     pub type_: MoveObjectType,
 }
 
+pub struct AccountBalanceInfo {
+    pub owner: IotaAddress,
+    pub coin_type: StructTag,
+    /// Sum of the values of all `Coin<T>` objects owned by the account.
+    pub balance: u64,
+    pub coin_object_count: u64,
+}
+
+impl AccountBalanceInfo {
+    /// Sums up the balances of `owner` from the objects it owns, ordered by
+    /// coin type and starting at the coin type `cursor` if provided. Meant for
+    /// stores without a balance index.
+    pub fn from_owned_objects(
+        owner: IotaAddress,
+        objects: impl IntoIterator<Item = Object>,
+        cursor: Option<&StructTag>,
+    ) -> Vec<Self> {
+        let mut balances = BTreeMap::<StructTag, (u64, u64)>::new();
+        for object in objects {
+            let (Some(TypeTag::Struct(coin_type)), Some(coin)) =
+                (object.coin_type_maybe(), object.as_coin_maybe())
+            else {
+                continue;
+            };
+            let (balance, coin_object_count) = balances.entry(*coin_type).or_default();
+            *balance = balance.saturating_add(coin.value());
+            *coin_object_count += 1;
+        }
+
+        balances
+            .into_iter()
+            .filter(|(coin_type, _)| cursor.is_none_or(|cursor| coin_type >= cursor))
+            .map(|(coin_type, (balance, coin_object_count))| Self {
+                owner,
+                coin_type,
+                balance,
+                coin_object_count,
+            })
+            .collect()
+    }
+}
+
 #[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub struct DynamicFieldKey {
     pub parent: ObjectID,
@@ -206,6 +206,112 @@ fn default_sketch_tolerance() -> f64 {
     DEFAULT_SKETCH_TOLERANCE
 }
 
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TokenBucketConfig {
+    /// Maximum number of tallies a direct client can burst before
+    /// being blocked.
+    #[serde(default = "default_client_burst")]
+    pub client_burst: u64,
+    /// Number of tallies per second refilled into a direct client's bucket.
+    #[serde(default = "default_client_refill_per_sec")]
+    pub client_refill_per_sec: f64,
+    /// Maximum number of tallies a proxied client can burst before
+    /// being blocked.
+    #[serde(default = "default_proxied_client_burst")]
+    pub proxied_client_burst: u64,
+    /// Number of tallies per second refilled into a proxied client's bucket.
+    #[serde(default = "default_proxied_client_refill_per_sec")]
+    pub proxied_client_refill_per_sec: f64,
+}
+
+impl Default for TokenBucketConfig {
+    fn default() -> Self {
+        Self {
+            client_burst: default_client_burst(),
+            client_refill_per_sec: default_client_refill_per_sec(),
+            proxied_client_burst: default_proxied_client_burst(),
+            proxied_client_refill_per_sec: default_proxied_client_refill_per_sec(),
+        }
+    }
+}
+
+fn default_client_burst() -> u64 {
+    // as with the frequency threshold, a direct client may be
+    // a fullnode proxying many well behaved clients, so the
+    // default is intentionally permissive
+    1_000_000
+}
+
+fn default_client_refill_per_sec() -> f64 {
+    1_000_000.0
+}
+
+fn default_proxied_client_burst() -> u64 {
+    100
+}
+
+fn default_proxied_client_refill_per_sec() -> f64 {
+    10.0
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ReputationConfig {
+    /// Score assigned to clients that have not been seen before, and the
+    /// upper bound that scores recover to.
+    #[serde(default = "default_max_score")]
+    pub max_score: f64,
+    /// Score deducted from a direct client for each tally.
+    #[serde(default = "default_client_penalty")]
+    pub client_penalty: f64,
+    /// Score deducted from a proxied client for each tally.
+    #[serde(default = "default_proxied_client_penalty")]
+    pub proxied_client_penalty: f64,
+    /// Score recovered per second, up to `max_score`.
+    #[serde(default = "default_recovery_per_sec")]
+    pub recovery_per_sec: f64,
+    /// Clients are blocked once their score drops to or below this value.
+    #[serde(default = "default_block_threshold")]
+    pub block_threshold: f64,
+}
+
+impl Default for ReputationConfig {
+    fn default() -> Self {
+        Self {
+            max_score: default_max_score(),
+            client_penalty: default_client_penalty(),
+            proxied_client_penalty: default_proxied_client_penalty(),
+            recovery_per_sec: default_recovery_per_sec(),
+            block_threshold: default_block_threshold(),
+        }
+    }
+}
+
+fn default_max_score() -> f64 {
+    100.0
+}
+
+fn default_client_penalty() -> f64 {
+    // direct clients may be fullnodes relaying errors on behalf of
+    // many clients, so they are penalized less per tally
+    0.1
+}
+
+fn default_proxied_client_penalty() -> f64 {
+    10.0
+}
+
+fn default_recovery_per_sec() -> f64 {
+    1.0
+}
+
+fn default_block_threshold() -> f64 {
+    0.0
+}
+
 // Serializable representation of policy types, used in config
 // in order to easily change in tests or to killswitch
 #[derive(Clone, Serialize, Deserialize, Debug, Default)]
@@ -219,6 +325,17 @@ pub enum PolicyType {
     /// `window_size_secs` with granularity of `update_interval_secs`
     FreqThreshold(FreqThresholdConfig),
 
+    /// Blocks a client once it exhausts its token bucket. Each tally consumes
+    /// one token, buckets hold at most the configured burst and are refilled
+    /// at a constant rate per second.
+    TokenBucket(TokenBucketConfig),
+
+    /// Blocks a client once its reputation score drops to or below
+    /// `block_threshold`. Each tally lowers the score by a penalty, and
+    /// scores recover linearly over time up to `max_score`. Typically used as
+    /// an error policy.
+    Reputation(ReputationConfig),
+
     // Below this point are test policies, and thus should not be used in production
     /// Simple policy that adds connection_ip to blocklist when the same
     /// connection_ip is encountered in tally N times. If used in an error
@@ -37,7 +37,7 @@ use crate::{
     authenticator_state::ActiveJwk,
     committee::{Committee, EpochId, ProtocolVersion},
     crypto::{
-        AuthoritySignInfo, AuthoritySignInfoTrait, AuthoritySignature,
+        AuthoritySignInfo, AuthoritySignInfoTrait, AuthoritySignature, AuthoritySigner,
         AuthorityStrongQuorumSignInfo, DefaultHash, Ed25519IotaSignature, EmptySignInfo,
         IotaSignatureInner, RandomnessRound, Signature, Signer, ToFromBytes, default_hash,
     },
@@ -2194,7 +2194,17 @@ impl SenderSignedData {
                         });
                     }
                 }
-                GenericSignature::Signature(_) | GenericSignature::MultiSig(_) => (),
+                GenericSignature::MultiSig(multisig) => {
+                    if multisig.has_passkey_sigs() && !config.accept_passkey_in_multisig() {
+                        return Err(IotaError::UserInput {
+                            error: UserInputError::Unsupported(
+                                "passkey is not enabled inside multisig on this network"
+                                    .to_string(),
+                            ),
+                        });
+                    }
+                }
+                GenericSignature::Signature(_) => (),
             }
         }
 
@@ -2456,6 +2466,24 @@ impl VerifiedSignedTransaction {
             authority,
         ))
     }
+
+    /// Same as [`VerifiedSignedTransaction::new`], failing if `secret` fails
+    /// to sign.
+    pub async fn try_new(
+        epoch: EpochId,
+        transaction: VerifiedTransaction,
+        authority: AuthorityName,
+        secret: &dyn AuthoritySigner,
+    ) -> IotaResult<Self> {
+        let signed_transaction = SignedTransaction::try_new(
+            epoch,
+            transaction.into_inner().into_data(),
+            secret,
+            authority,
+        )
+        .await?;
+        Ok(Self::new_from_verified(signed_transaction))
+    }
 }
 
 /// A transaction that is signed by a sender but not yet by an authority.
@@ -2874,8 +2902,9 @@ impl InputObjects {
         for obj in &self.objects {
             if let ObjectReadResultKind::CancelledTransactionSharedObject(version) = obj.object {
                 contains_cancelled = true;
-                if version == SequenceNumber::CONGESTED
-                    || version == SequenceNumber::RANDOMNESS_UNAVAILABLE
+                if version
+                    .cancellation()
+                    .is_some_and(|cancellation| cancellation != SharedObjectCancellation::Read)
                 {
                     // Verify we don't have multiple cancellation reasons.
                     assert!(cancel_reason.is_none() || cancel_reason == Some(version));
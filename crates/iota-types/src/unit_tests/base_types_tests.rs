@@ -493,3 +493,47 @@ fn next_lexicographical_digest() {
         Some(Digest::from(output))
     );
 }
+
+#[test]
+fn test_shared_object_cancellation_roundtrip() {
+    for cancellation in [
+        SharedObjectCancellation::Read,
+        SharedObjectCancellation::Congested,
+        SharedObjectCancellation::RandomnessUnavailable,
+        SharedObjectCancellation::CongestedWithSurcharge(0),
+        SharedObjectCancellation::CongestedWithSurcharge(1000),
+        SharedObjectCancellation::CongestedWithSurcharge(SequenceNumber::MAX_CONGESTION_SURCHARGE),
+    ] {
+        let version = SequenceNumber::from(cancellation);
+        assert!(!version.is_valid());
+        assert!(version.is_cancelled());
+        assert_eq!(version.cancellation(), Some(cancellation));
+    }
+
+    // Regular versions and versions past the surcharge range aren't
+    // cancellations.
+    for version in [
+        SequenceNumber::MIN,
+        SequenceNumber::from_u64(42),
+        SequenceNumber::MAX,
+        SequenceNumber::from_u64(
+            SequenceNumber::from(SharedObjectCancellation::CongestedWithSurcharge(
+                SequenceNumber::MAX_CONGESTION_SURCHARGE,
+            ))
+            .value()
+                + 1,
+        ),
+        SequenceNumber::from_u64(u64::MAX),
+    ] {
+        assert!(!version.is_cancelled());
+        assert_eq!(version.cancellation(), None);
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_shared_object_cancellation_surcharge_too_large() {
+    let _ = SequenceNumber::from(SharedObjectCancellation::CongestedWithSurcharge(
+        SequenceNumber::MAX_CONGESTION_SURCHARGE + 1,
+    ));
+}
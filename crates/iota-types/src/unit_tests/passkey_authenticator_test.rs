@@ -10,6 +10,7 @@ use fastcrypto::{
     rsa::{Base64UrlUnpadded, Encoding as _},
     traits::ToFromBytes,
 };
+use iota_protocol_config::ProtocolConfig;
 use p256::pkcs8::DecodePublicKey;
 use passkey_authenticator::{Authenticator, UserCheck, UserValidationMethod};
 use passkey_client::Client;
@@ -30,13 +31,14 @@ use url::Url;
 use super::to_signing_message;
 use crate::{
     base_types::{IotaAddress, ObjectID, dbg_addr},
-    crypto::{DefaultHash, PublicKey, Signature, SignatureScheme},
-    error::IotaError,
+    crypto::{DefaultHash, IotaKeyPair, PublicKey, Signature, SignatureScheme, get_key_pair},
+    error::{IotaError, UserInputError},
+    multisig::{MultiSig, MultiSigPublicKey},
     object::Object,
     passkey_authenticator::{PasskeyAuthenticator, RawPasskeyAuthenticator},
     signature::GenericSignature,
     signature_verification::VerifiedDigestCache,
-    transaction::{TEST_ONLY_GAS_UNIT_FOR_TRANSFER, TransactionData},
+    transaction::{TEST_ONLY_GAS_UNIT_FOR_TRANSFER, Transaction, TransactionData},
 };
 
 /// Helper struct to initialize passkey client.
@@ -82,6 +84,17 @@ pub struct PasskeyResponse<T> {
 async fn create_credential_and_sign_test_tx(
     origin: &Url,
     request: CredentialCreationOptions,
+) -> PasskeyResponse<TransactionData> {
+    create_credential_and_sign_test_tx_for_sender(origin, request, |pk| IotaAddress::from(pk)).await
+}
+
+/// Create a new passkey credential and request a signature from passkey for a
+/// test transaction whose sender is derived from the passkey public key with
+/// `sender_for_pk`.
+async fn create_credential_and_sign_test_tx_for_sender(
+    origin: &Url,
+    request: CredentialCreationOptions,
+    sender_for_pk: impl FnOnce(&PublicKey) -> IotaAddress,
 ) -> PasskeyResponse<TransactionData> {
     // Set up authenticator and client.
     let my_aaguid = Aaguid::new_empty();
@@ -110,8 +123,8 @@ async fn create_credential_and_sign_test_tx(
     pk_bytes.extend_from_slice(x.unwrap());
     let pk = PublicKey::try_from_bytes(SignatureScheme::PasskeyAuthenticator, &pk_bytes).unwrap();
 
-    // Derives its iota address and make a test transaction with it as sender.
-    let sender = IotaAddress::from(&pk);
+    // Derives the sender address and make a test transaction with it as sender.
+    let sender = sender_for_pk(&pk);
     let recipient = dbg_addr(2);
     let object_id = ObjectID::ZERO;
     let object = Object::immutable_with_id_for_testing(object_id);
@@ -254,6 +267,101 @@ async fn test_passkey_authenticator() {
     assert!(res.is_ok());
 }
 
+#[tokio::test]
+async fn test_passkey_in_multisig() {
+    let origin = Url::parse("https://www.iota.io").unwrap();
+    let request = make_credential_creation_option(&origin);
+    let ed25519_kp = IotaKeyPair::Ed25519(get_key_pair().1);
+    let mut multisig_pk = None;
+    let response = create_credential_and_sign_test_tx_for_sender(&origin, request, |pk| {
+        let pk =
+            MultiSigPublicKey::new(vec![pk.clone(), ed25519_kp.public()], vec![1, 1], 2).unwrap();
+        let address = IotaAddress::from(&pk);
+        multisig_pk = Some(pk);
+        address
+    })
+    .await;
+    let multisig_pk = multisig_pk.unwrap();
+
+    let passkey_sig = GenericSignature::PasskeyAuthenticator(
+        PasskeyAuthenticator::new_for_testing(
+            response.authenticator_data,
+            response.client_data_json,
+            Signature::from_bytes(&response.user_sig_bytes).unwrap(),
+        )
+        .unwrap(),
+    );
+    let ed25519_sig: GenericSignature =
+        Signature::new_secure(&response.intent_msg, &ed25519_kp).into();
+
+    // Both members sign, the threshold is reached.
+    let multisig =
+        MultiSig::combine(vec![passkey_sig.clone(), ed25519_sig], multisig_pk.clone()).unwrap();
+    assert!(multisig.has_passkey_sigs());
+    let res = GenericSignature::MultiSig(multisig).verify_authenticator(
+        &response.intent_msg,
+        response.sender,
+        0,
+        &Default::default(),
+        Arc::new(VerifiedDigestCache::new_empty()),
+    );
+    assert!(res.is_ok());
+
+    // Only the passkey signs, the threshold is not reached.
+    let multisig = MultiSig::combine(vec![passkey_sig], multisig_pk).unwrap();
+    let res = GenericSignature::MultiSig(multisig).verify_authenticator(
+        &response.intent_msg,
+        response.sender,
+        0,
+        &Default::default(),
+        Arc::new(VerifiedDigestCache::new_empty()),
+    );
+    assert!(res.is_err());
+}
+
+#[tokio::test]
+async fn test_passkey_in_multisig_rejected_when_disabled() {
+    let origin = Url::parse("https://www.iota.io").unwrap();
+    let request = make_credential_creation_option(&origin);
+    let ed25519_kp = IotaKeyPair::Ed25519(get_key_pair().1);
+    let mut multisig_pk = None;
+    let response = create_credential_and_sign_test_tx_for_sender(&origin, request, |pk| {
+        let pk =
+            MultiSigPublicKey::new(vec![pk.clone(), ed25519_kp.public()], vec![1, 1], 2).unwrap();
+        let address = IotaAddress::from(&pk);
+        multisig_pk = Some(pk);
+        address
+    })
+    .await;
+
+    let passkey_sig = GenericSignature::PasskeyAuthenticator(
+        PasskeyAuthenticator::new_for_testing(
+            response.authenticator_data,
+            response.client_data_json,
+            Signature::from_bytes(&response.user_sig_bytes).unwrap(),
+        )
+        .unwrap(),
+    );
+    let ed25519_sig: GenericSignature =
+        Signature::new_secure(&response.intent_msg, &ed25519_kp).into();
+    let multisig = MultiSig::combine(vec![passkey_sig, ed25519_sig], multisig_pk.unwrap()).unwrap();
+    let tx = Transaction::from_generic_sig_data(response.intent_msg.value, vec![
+        GenericSignature::MultiSig(multisig),
+    ]);
+
+    let mut config = ProtocolConfig::get_for_max_version_UNSAFE();
+    config.set_passkey_auth_for_testing(true);
+
+    config.set_accept_passkey_in_multisig_for_testing(false);
+    let err = tx.data().validity_check(&config, 0).unwrap_err();
+    assert!(matches!(err, IotaError::UserInput {
+        error: UserInputError::Unsupported(_)
+    }));
+
+    config.set_accept_passkey_in_multisig_for_testing(true);
+    assert!(tx.data().validity_check(&config, 0).is_ok());
+}
+
 #[tokio::test]
 async fn test_passkey_fails_invalid_json() {
     let origin = Url::parse("https://www.iota.io").unwrap();
@@ -36,7 +36,7 @@ use iota_move_build::{
 };
 use iota_package_management::{LockCommand, PublishedAtError};
 use iota_protocol_config::{Chain, ProtocolConfig, ProtocolVersion};
-use iota_replay::ReplayToolCommand;
+use iota_replay::{ProfiledTransaction, ReplayToolCommand};
 use iota_sdk::{
     IOTA_COIN_TYPE, IOTA_DEVNET_GAS_URL, IOTA_DEVNET_URL, IOTA_LOCAL_NETWORK_GAS_URL,
     IOTA_LOCAL_NETWORK_URL, IOTA_LOCAL_NETWORK_URL_0, IOTA_TESTNET_GAS_URL, IOTA_TESTNET_URL,
@@ -48,7 +48,7 @@ use iota_sdk::{
 use iota_source_validation::{BytecodeSourceVerifier, ValidationMode};
 use iota_types::{
     base_types::{IotaAddress, ObjectID, SequenceNumber},
-    crypto::{EmptySignInfo, SignatureScheme},
+    crypto::{EmptySignInfo, PublicKey, SignatureScheme},
     digests::TransactionDigest,
     dynamic_field::DynamicFieldInfo,
     error::IotaError,
@@ -57,6 +57,7 @@ use iota_types::{
     message_envelope::Envelope,
     metrics::BytecodeVerifierMetrics,
     move_package::UpgradeCap,
+    multisig::{MultiSigPublicKey, ThresholdUnit, WeightUnit},
     object::Owner,
     parse_iota_type_tag,
     quorum_driver_types::ExecuteTransactionRequestType,
@@ -70,6 +71,7 @@ use move_binary_format::CompiledModule;
 use move_bytecode_verifier_meter::Scope;
 use move_core_types::{account_address::AccountAddress, language_storage::TypeTag};
 use move_package::BuildConfig as MoveBuildConfig;
+use move_vm_profiler::GasProfileSummary;
 use prometheus::Registry;
 use reqwest::StatusCode;
 use serde::Serialize;
@@ -93,6 +95,7 @@ use crate::{
     client_ptb::ptb::PTB,
     displays::Pretty,
     key_identity::{KeyIdentity, get_identity_address},
+    signing_bundle::{SigningBundle, SigningBundleSummary},
     verifier_meter::{AccumulatingMeter, Accumulator},
 };
 
@@ -208,6 +211,47 @@ pub enum IotaClientCommands {
         #[clap(long)]
         signed_tx_bytes: String,
     },
+    /// Execute a transaction from signing bundles signed with `iota keytool
+    /// sign-bundle`. The signatures of all bundles are merged, and combined
+    /// into a MultiSig if the bundles carry a MultiSig public key.
+    ExecuteSigningBundle {
+        /// Paths of the signing bundles, all of the same transaction.
+        #[clap(long, num_args(1..))]
+        bundles: Vec<PathBuf>,
+        /// Additional Base64 encoded signatures `flag || sig || pk`, e.g.
+        /// scanned from the QR codes shown by `iota keytool sign-bundle --qr`.
+        #[clap(long, num_args(1..))]
+        signatures: Vec<GenericSignature>,
+        /// Instead of executing the transaction, serialize the bcs bytes of
+        /// the signed transaction data (SenderSignedData) using base64
+        /// encoding.
+        #[clap(long)]
+        serialize_signed_transaction: bool,
+    },
+    /// Create a signing bundle for a transaction, to be signed offline with
+    /// `iota keytool sign-bundle`. The bundle contains the transaction and
+    /// the objects it uses, so that a summary can be shown without network
+    /// access.
+    ExportSigningBundle {
+        /// BCS serialized transaction data bytes without its type tag, as
+        /// base64 encoded string. This is the output of iota client command
+        /// using --serialize-unsigned-transaction.
+        #[clap(long)]
+        tx_bytes: String,
+        /// Path to write the signing bundle to.
+        #[clap(long)]
+        output: PathBuf,
+        /// If the sender is a MultiSig address, the public keys `flag || pk`
+        /// of its members in Base64.
+        #[clap(long, num_args(1..), requires = "threshold")]
+        multisig_pks: Vec<PublicKey>,
+        /// The weights of the MultiSig members.
+        #[clap(long, num_args(1..))]
+        weights: Vec<WeightUnit>,
+        /// The threshold of the MultiSig address.
+        #[clap(long)]
+        threshold: Option<ThresholdUnit>,
+    },
 
     /// Request gas coin from faucet. By default, it will use the active address
     /// and the active network.
@@ -551,19 +595,28 @@ pub enum IotaClientCommands {
     /// Profile the gas usage of a transaction. Unless an output filepath is not
     /// specified, outputs a file
     /// `gas_profile_{tx_digest}_{unix_timestamp}.json` which can be opened in a
-    /// flamegraph tool such as speedscope.
+    /// flamegraph tool such as speedscope. A summary of the gas used per PTB
+    /// command and per Move function is printed.
     #[clap(name = "profile-transaction")]
     ProfileTransaction {
         /// The digest of the transaction to replay
-        #[arg(long, short)]
-        tx_digest: String,
+        #[arg(long, short, required_unless_present = "tx_bytes")]
+        tx_digest: Option<String>,
+
+        /// Instead of replaying an executed transaction, dry run the BCS
+        /// serialized transaction data bytes, as base64 encoded string. This
+        /// is the output of iota client commands using
+        /// --serialize-unsigned-transaction.
+        #[arg(long, conflicts_with = "tx_digest")]
+        tx_bytes: Option<String>,
 
         /// If specified, overrides the filepath of the output profile, for
         /// example -- /temp/my_profile_name.json will write output to
         /// `/temp/my_profile_name_{tx_digest}_{unix_timestamp}.json` If
         /// an output filepath is not specified, it will output a file
         /// `gas_profile_{tx_digest}_{unix_timestamp}.json` to the working
-        /// directory
+        /// directory. A filepath with the `.folded` extension writes the
+        /// profile in the folded stack format instead.
         #[arg(long, short)]
         profile_output: Option<PathBuf>,
     },
@@ -773,6 +826,7 @@ impl IotaClientCommands {
         let ret = match self {
             IotaClientCommands::ProfileTransaction {
                 tx_digest,
+                tx_bytes,
                 profile_output,
             } => {
                 move_vm_profiler::gas_profiler_feature_disabled! {
@@ -782,19 +836,23 @@ impl IotaClientCommands {
                     );
                 };
 
-                let cmd = ReplayToolCommand::ProfileTransaction {
-                    tx_digest,
-                    executor_version: None,
-                    protocol_version: None,
-                    profile_output,
-                    config_objects: None,
+                let transaction = match (tx_digest, tx_bytes) {
+                    (Some(tx_digest), _) => {
+                        ProfiledTransaction::Executed(TransactionDigest::from_str(&tx_digest)?)
+                    }
+                    (None, Some(tx_bytes)) => ProfiledTransaction::DryRun(bcs::from_bytes(
+                        &Base64::try_from(tx_bytes)
+                            .map_err(|_| anyhow!("Invalid Base64 encoding"))?
+                            .to_vec()
+                            .map_err(|_| anyhow!("Invalid Base64 encoding"))?
+                    ).map_err(|_| anyhow!("Failed to parse tx bytes, check if it matches the output of iota client commands with --serialize-unsigned-transaction"))?),
+                    (None, None) => bail!("Either --tx-digest or --tx-bytes must be provided"),
                 };
                 let rpc = context.config().get_active_env()?.rpc().clone();
-                let _command_result =
-                    iota_replay::execute_replay_command(Some(rpc), false, false, None, None, cmd)
-                        .await?;
-                // this will be displayed via trace info, so no output is needed here
-                IotaClientCommandResult::NoOutput
+                match iota_replay::profile_transaction(rpc, transaction, profile_output).await? {
+                    Some(summary) => IotaClientCommandResult::GasProfile(summary),
+                    None => IotaClientCommandResult::NoOutput,
+                }
             }
             IotaClientCommands::ReplayTransaction {
                 tx_digest,
@@ -1682,6 +1740,60 @@ impl IotaClientCommands {
                 let response = context.execute_transaction_may_fail(transaction).await?;
                 IotaClientCommandResult::TransactionBlock(response)
             }
+            IotaClientCommands::ExecuteSigningBundle {
+                bundles,
+                signatures,
+                serialize_signed_transaction,
+            } => {
+                let mut paths = bundles.iter();
+                let first = paths
+                    .next()
+                    .ok_or_else(|| anyhow!("At least one signing bundle is required"))?;
+                let mut bundle = SigningBundle::read(first)?;
+                for path in paths {
+                    bundle.merge(SigningBundle::read(path)?)?;
+                }
+                for signature in signatures {
+                    bundle.add_signature(signature)?;
+                }
+                let data =
+                    SenderSignedData::new(bundle.tx_data()?, bundle.transaction_signatures()?);
+                if serialize_signed_transaction {
+                    IotaClientCommandResult::SerializedSignedTransaction(data)
+                } else {
+                    let response = context
+                        .execute_transaction_may_fail(Transaction::new(data))
+                        .await?;
+                    IotaClientCommandResult::TransactionBlock(response)
+                }
+            }
+            IotaClientCommands::ExportSigningBundle {
+                tx_bytes,
+                output,
+                multisig_pks,
+                weights,
+                threshold,
+            } => {
+                let tx_data: TransactionData = bcs::from_bytes(
+                    &Base64::try_from(tx_bytes)
+                        .map_err(|_| anyhow!("Invalid Base64 encoding"))?
+                        .to_vec()
+                        .map_err(|_| anyhow!("Invalid Base64 encoding"))?
+                ).map_err(|_| anyhow!("Failed to parse tx bytes, check if it matches the output of iota client commands with --serialize-unsigned-transaction"))?;
+                let multisig_pk = match threshold {
+                    Some(threshold) => {
+                        Some(MultiSigPublicKey::new(multisig_pks, weights, threshold)?)
+                    }
+                    None => None,
+                };
+                let client = context.get_client().await?;
+                let bundle = SigningBundle::fetch(&client, &tx_data, multisig_pk).await?;
+                bundle.write(&output)?;
+                IotaClientCommandResult::SigningBundle {
+                    path: output,
+                    summary: bundle.summary()?,
+                }
+            }
             IotaClientCommands::NewEnv {
                 alias,
                 rpc,
@@ -2209,6 +2321,13 @@ impl Display for IotaClientCommandResult {
                     fastcrypto::encoding::Base64::encode(bcs::to_bytes(sender_signed_tx).unwrap())
                 )?;
             }
+            IotaClientCommandResult::SigningBundle { path, summary } => {
+                writeln!(writer, "{summary}")?;
+                writeln!(writer, "Signing bundle written to {}", path.display())?;
+            }
+            IotaClientCommandResult::GasProfile(summary) => {
+                writeln!(writer, "{}", Pretty(summary))?;
+            }
             IotaClientCommandResult::SyncClientState => {
                 writeln!(writer, "Client state sync complete.")?;
             }
@@ -2444,6 +2563,7 @@ impl IotaClientCommandResult {
             | IotaClientCommandResult::DynamicFieldQuery(_)
             | IotaClientCommandResult::Envs(_, _)
             | IotaClientCommandResult::Gas(_)
+            | IotaClientCommandResult::GasProfile(_)
             | IotaClientCommandResult::NewAddress(_)
             | IotaClientCommandResult::NewEnv(_)
             | IotaClientCommandResult::NoOutput
@@ -2452,6 +2572,7 @@ impl IotaClientCommandResult {
             | IotaClientCommandResult::RawObject(_)
             | IotaClientCommandResult::SerializedSignedTransaction(_)
             | IotaClientCommandResult::SerializedUnsignedTransaction(_)
+            | IotaClientCommandResult::SigningBundle { .. }
             | IotaClientCommandResult::Switch(_)
             | IotaClientCommandResult::SyncClientState
             | IotaClientCommandResult::VerifyBytecodeMeter { .. }
@@ -2593,6 +2714,7 @@ pub enum IotaClientCommandResult {
     DryRun(DryRunTransactionBlockResponse),
     Envs(Vec<IotaEnv>, Option<String>),
     Gas(Vec<GasCoin>),
+    GasProfile(GasProfileSummary),
     NewAddress(NewAddressOutput),
     NewEnv(IotaEnv),
     NoOutput,
@@ -2601,6 +2723,10 @@ pub enum IotaClientCommandResult {
     RawObject(IotaObjectResponse),
     SerializedSignedTransaction(SenderSignedData),
     SerializedUnsignedTransaction(TransactionData),
+    SigningBundle {
+        path: PathBuf,
+        summary: SigningBundleSummary,
+    },
     Switch(SwitchResponse),
     SyncClientState,
     TransactionBlock(IotaTransactionBlockResponse),
@@ -0,0 +1,57 @@
+// Copyright (c) 2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::{Display, Formatter};
+
+use move_vm_profiler::{FrameGasUsage, GasProfileSummary};
+use tabled::{
+    builder::Builder as TableBuilder,
+    settings::{Style as TableStyle, style::HorizontalLine},
+};
+
+use crate::displays::Pretty;
+
+impl Display for Pretty<'_, GasProfileSummary> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let Pretty(summary) = self;
+        writeln!(
+            f,
+            "Gas Profile Summary for {}:\n   Total Gas: {}",
+            summary.name, summary.total_gas
+        )?;
+        writeln!(f, "PTB Commands:")?;
+        usage_table(f, "Command", &summary.commands, summary.total_gas)?;
+        writeln!(f, "Move Functions:")?;
+        usage_table(f, "Function", &summary.functions, summary.total_gas)
+    }
+}
+
+fn usage_table(
+    f: &mut Formatter<'_>,
+    header: &str,
+    usages: &[FrameGasUsage],
+    total_gas: u64,
+) -> std::fmt::Result {
+    let mut builder = TableBuilder::default();
+    builder.push_record(vec![header, "Calls", "Total Gas", "Self Gas", "% of Total"]);
+    for usage in usages {
+        let share = if total_gas == 0 {
+            0.0
+        } else {
+            usage.total_gas as f64 * 100.0 / total_gas as f64
+        };
+        builder.push_record(vec![
+            usage.name.clone(),
+            usage.calls.to_string(),
+            usage.total_gas.to_string(),
+            usage.self_gas.to_string(),
+            format!("{share:.2}"),
+        ]);
+    }
+    let mut table = builder.build();
+    table.with(TableStyle::rounded().horizontals([HorizontalLine::new(
+        1,
+        TableStyle::modern().get_horizontal(),
+    )]));
+    writeln!(f, "{table}")
+}
@@ -4,6 +4,7 @@
 
 mod dry_run_tx_block;
 mod gas_cost_summary;
+mod gas_profile_summary;
 mod ptb_preview;
 mod status;
 mod summary;
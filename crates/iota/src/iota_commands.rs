@@ -33,7 +33,10 @@ use iota_graphql_rpc::{
 use iota_indexer::test_utils::{ReaderWriterConfig, start_test_indexer};
 use iota_keys::{
     keypair_file::read_key,
-    keystore::{AccountKeystore, FileBasedKeystore, Keystore},
+    keystore::{
+        AccountKeystore, EncryptedFileBasedKeystore, FileBasedKeystore, Keystore,
+        is_encrypted_keystore,
+    },
 };
 use iota_move::{self, execute_move_command};
 use iota_move_build::IotaPackageHooks;
@@ -63,7 +66,7 @@ use crate::{
     console::start_console,
     fire_drill::{FireDrill, run_fire_drill},
     genesis_ceremony::{Ceremony, run},
-    keytool::KeyToolCommand,
+    keytool::{KeyToolCommand, enable_lazy_unlock},
     validator_commands::IotaValidatorCommand,
 };
 
@@ -334,7 +337,7 @@ pub enum IotaCommand {
         package_path: Option<PathBuf>,
         /// Sets the file storing the state of our user accounts (an empty one
         /// will be created if missing) Only used when the
-        /// `--dump-bytecode-as-base64` is set.
+        /// `--dump-bytecode-as-base64` is set, and by `audit`.
         #[clap(long = "client.config")]
         config: Option<PathBuf>,
         /// Package build options
@@ -433,7 +436,11 @@ impl IotaCommand {
             } => {
                 let keystore_path =
                     keystore_path.unwrap_or(iota_config_dir()?.join(IOTA_KEYSTORE_FILENAME));
-                let mut keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+                let mut keystore = if is_encrypted_keystore(&keystore_path) {
+                    Keystore::from(EncryptedFileBasedKeystore::new(&keystore_path)?)
+                } else {
+                    Keystore::from(FileBasedKeystore::new(&keystore_path)?)
+                };
                 cmd.execute(&mut keystore).await?.print(!json);
                 Ok(())
             }
@@ -452,6 +459,7 @@ impl IotaCommand {
                 let config_path = config.unwrap_or(iota_config_dir()?.join(IOTA_CLIENT_CONFIG));
                 prompt_if_no_config(&config_path, accept_defaults).await?;
                 let mut context = WalletContext::new(&config_path, None, None)?;
+                enable_lazy_unlock(context.config_mut().keystore_mut());
                 if let Some(cmd) = cmd {
                     cmd.execute(&mut context).await?.print(!json);
                 } else {
@@ -471,6 +479,7 @@ impl IotaCommand {
                 let config_path = config.unwrap_or(iota_config_dir()?.join(IOTA_CLIENT_CONFIG));
                 prompt_if_no_config(&config_path, accept_defaults).await?;
                 let mut context = WalletContext::new(&config_path, None, None)?;
+                enable_lazy_unlock(context.config_mut().keystore_mut());
                 if let Some(cmd) = cmd {
                     cmd.execute(&mut context).await?.print(!json);
                 } else {
@@ -502,6 +511,23 @@ impl IotaCommand {
                         let chain_id = client.read_api().get_chain_identifier().await.ok();
                         build.chain_id = chain_id.clone();
                     }
+                    iota_move::Command::Audit(audit) => {
+                        // The dependencies of the package are audited against the chain the
+                        // client is connected to.
+                        let config =
+                            client_config.unwrap_or(iota_config_dir()?.join(IOTA_CLIENT_CONFIG));
+                        prompt_if_no_config(&config, false).await?;
+                        let context = WalletContext::new(&config, None, None)?;
+                        let client = context.get_client().await?;
+                        let passed = audit
+                            .execute(package_path.as_deref(), build_config, client)
+                            .await?;
+                        // Return a non-zero exit code if the audit found problems
+                        if !passed {
+                            std::process::exit(1)
+                        }
+                        return Ok(());
+                    }
                     _ => (),
                 };
                 execute_move_command(package_path.as_deref(), build_config, cmd)
@@ -6,9 +6,10 @@ use std::{
     fmt::{Debug, Display, Formatter},
     path::PathBuf,
     sync::Arc,
+    time::Duration,
 };
 
-use anyhow::anyhow;
+use anyhow::{anyhow, ensure};
 use aws_config::BehaviorVersion;
 use aws_sdk_kms::{
     Client as KmsClient,
@@ -24,13 +25,15 @@ use fastcrypto::{
     secp256k1::recoverable::Secp256k1Sig,
     traits::{KeyPair, ToFromBytes},
 };
+use inquire::{Confirm, Password};
 use iota_keys::{
+    encryption::KdfParams,
     key_derive::generate_new_key,
     keypair_file::{
         read_authority_keypair_from_file, read_keypair_from_file, write_authority_keypair_to_file,
         write_keypair_to_file,
     },
-    keystore::{AccountKeystore, Keystore},
+    keystore::{AccountKeystore, EncryptedFileBasedKeystore, Keystore},
 };
 use iota_types::{
     base_types::IotaAddress,
@@ -54,11 +57,22 @@ use tabled::{
 };
 use tracing::info;
 
-use crate::key_identity::{KeyIdentity, get_identity_address_from_keystore};
+use crate::{
+    key_identity::{KeyIdentity, get_identity_address_from_keystore},
+    signing_bundle::{SigningBundle, SigningBundleSummary, render_qr_code},
+};
 #[cfg(test)]
 #[path = "unit_tests/keytool_tests.rs"]
 mod keytool_tests;
 
+/// Environment variable that can hold the passphrase of an encrypted keystore,
+/// to unlock it without prompting.
+pub const IOTA_KEYSTORE_PASSPHRASE_ENV: &str = "IOTA_KEYSTORE_PASSPHRASE";
+
+/// Environment variable that can hold the passphrase to encrypt a keystore
+/// with, or to change its passphrase to, without prompting.
+pub const IOTA_KEYSTORE_NEW_PASSPHRASE_ENV: &str = "IOTA_KEYSTORE_NEW_PASSPHRASE";
+
 #[derive(Subcommand)]
 #[clap(rename_all = "kebab-case")]
 pub enum KeyToolCommand {
@@ -71,6 +85,11 @@ pub enum KeyToolCommand {
         /// digits, dots, hyphens (-), or underscores (_).
         new_alias: Option<String>,
     },
+    /// Change the passphrase of an encrypted keystore. The new passphrase is
+    /// prompted for or read from the `IOTA_KEYSTORE_NEW_PASSPHRASE`
+    /// environment variable, and must differ from the current one. Any cached
+    /// session is invalidated.
+    ChangePassphrase,
     /// Convert private key in Hex or Base64 to new format (Bech32
     /// encoded 33 byte flag || private key starting with "iotaprivkey").
     /// Hex private key format import and export are both deprecated in
@@ -98,6 +117,11 @@ pub enum KeyToolCommand {
         #[clap(long, default_value = "0")]
         cur_epoch: u64,
     },
+    /// Encrypt the plaintext keystore in place with a passphrase. Private keys
+    /// of an encrypted keystore are only accessible after unlocking it with
+    /// the passphrase, which is prompted for or read from the
+    /// `IOTA_KEYSTORE_NEW_PASSPHRASE` environment variable.
+    Encrypt,
     /// Generate a new keypair with key scheme flag {ed25519 | secp256k1 |
     /// secp256r1} with optional derivation path, default to
     /// m/44'/4218'/0'/0'/0' for ed25519 or m/54'/4218'/0'/0/0 for secp256k1
@@ -149,8 +173,13 @@ pub enum KeyToolCommand {
         #[clap(long, short = 's')]
         sort_by_alias: bool,
     },
+    /// End the session started by `iota keytool unlock`, so that the
+    /// passphrase of the encrypted keystore is required again.
+    Lock,
     /// To MultiSig Iota Address. Pass in a list of all public keys `flag || pk`
-    /// in Base64. See `keytool list` for example public keys.
+    /// in Base64. See `keytool list` for example public keys. A passkey member
+    /// is passed with the passkey flag (0x06) followed by its compressed
+    /// secp256r1 public key.
     MultiSigAddress {
         #[clap(long)]
         threshold: ThresholdUnit,
@@ -168,7 +197,8 @@ pub enum KeyToolCommand {
     ///
     /// The order of `sigs` must be the same as the order of `pks`.
     /// e.g. for [pk1, pk2, pk3, pk4, pk5], [sig1, sig2, sig5] is valid, but
-    /// [sig2, sig1, sig5] is invalid.
+    /// [sig2, sig1, sig5] is invalid. Passkey members sign with a serialized
+    /// passkey authenticator (flag 0x06).
     MultiSigCombinePartialSig {
         #[clap(long, num_args(1..))]
         sigs: Vec<GenericSignature>,
@@ -197,6 +227,25 @@ pub enum KeyToolCommand {
         #[clap(long)]
         intent: Option<Intent>,
     },
+    /// Sign a transaction from a signing bundle created with `iota client
+    /// export-signing-bundle`. A summary of the transaction and of the objects
+    /// it uses is shown before signing, without any network access, so that
+    /// this can run on an air-gapped machine. The signature is added to the
+    /// bundle file, and optionally shown as a QR code.
+    SignBundle {
+        /// The address (or its alias) to sign with.
+        #[clap(long)]
+        address: KeyIdentity,
+        /// Path of the signing bundle.
+        #[clap(long)]
+        bundle: PathBuf,
+        /// Sign without asking for confirmation.
+        #[clap(long)]
+        yes: bool,
+        /// Show the signature as a QR code.
+        #[clap(long)]
+        qr: bool,
+    },
     /// Creates a signature by leveraging AWS KMS. Pass in a key-id to leverage
     /// Amazon KMS to sign a message and the base64 pubkey.
     /// Generate PubKey from pem using iotaledger/base64pemkey
@@ -213,6 +262,14 @@ pub enum KeyToolCommand {
         #[clap(long)]
         base64pk: String,
     },
+    /// Unlock an encrypted keystore and cache the derived key next to the
+    /// keystore, so that subsequent commands do not prompt for the passphrase
+    /// until the session expires or `iota keytool lock` is called.
+    Unlock {
+        /// Duration of the session in seconds.
+        #[clap(long, default_value = "300")]
+        session_ttl_secs: u64,
+    },
     // Commented for now: https://github.com/iotaledger/iota/issues/1777
     // /// Given the max_epoch, generate an OAuth url, ask user to paste the
     // /// redirect with id_token, call salt server, then call the prover server,
@@ -375,12 +432,50 @@ pub struct ConvertOutput {
     scheme: String,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeystoreStatus {
+    encrypted: bool,
+    locked: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session_ttl_secs: Option<u64>,
+}
+
+impl From<&Keystore> for KeystoreStatus {
+    fn from(keystore: &Keystore) -> Self {
+        match keystore {
+            Keystore::Encrypted(keystore) => Self {
+                encrypted: true,
+                locked: keystore.is_locked(),
+                session_ttl_secs: None,
+            },
+            _ => Self {
+                encrypted: false,
+                locked: false,
+                session_ttl_secs: None,
+            },
+        }
+    }
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SerializedSig {
     serialized_sig_base64: String,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignBundleOutput {
+    iota_address: IotaAddress,
+    bundle: PathBuf,
+    summary: SigningBundleSummary,
+    // Base64 encoded `flag || signature || pubkey`, also added to the bundle.
+    iota_signature: String,
+    #[serde(skip)]
+    qr_code: Option<String>,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SignData {
@@ -434,11 +529,13 @@ pub enum CommandOutput {
     Generate(Key),
     Import(Key),
     Export(ExportedKey),
+    KeystoreStatus(KeystoreStatus),
     List(Vec<Key>),
     MultiSigAddress(MultiSigAddress),
     MultiSigCombinePartialSig(MultiSigCombinePartialSig),
     Show(Key),
     Sign(SignData),
+    SignBundle(SignBundleOutput),
     SignKMS(SerializedSig),
     // Commented for now: https://github.com/iotaledger/iota/issues/1777
     // ZkLoginSignAndExecuteTx(ZkLoginSignAndExecuteTx),
@@ -448,6 +545,7 @@ pub enum CommandOutput {
 
 impl KeyToolCommand {
     pub async fn execute(self, keystore: &mut Keystore) -> Result<CommandOutput, anyhow::Error> {
+        enable_lazy_unlock(keystore);
         let cmd_result = Ok(match self {
             KeyToolCommand::Alias {
                 old_alias,
@@ -459,6 +557,14 @@ impl KeyToolCommand {
                     new_alias,
                 })
             }
+            KeyToolCommand::ChangePassphrase => {
+                let Keystore::Encrypted(encrypted) = keystore else {
+                    return Err(anyhow!("The keystore is not encrypted"));
+                };
+                let passphrase = prompt_new_passphrase()?;
+                encrypted.set_passphrase(&passphrase, KdfParams::default())?;
+                CommandOutput::KeystoreStatus(KeystoreStatus::from(&*keystore))
+            }
             KeyToolCommand::Convert { value } => {
                 let result = convert_private_key_to_bech32(value)?;
                 CommandOutput::Convert(result)
@@ -552,6 +658,19 @@ impl KeyToolCommand {
                     }
                 }
             }
+            KeyToolCommand::Encrypt => {
+                let Keystore::File(file) = keystore else {
+                    return Err(anyhow!("Only file based keystores can be encrypted"));
+                };
+                let passphrase = prompt_new_passphrase()?;
+                let encrypted = EncryptedFileBasedKeystore::migrate(
+                    &file.path().to_path_buf(),
+                    &passphrase,
+                    KdfParams::default(),
+                )?;
+                *keystore = Keystore::Encrypted(encrypted);
+                CommandOutput::KeystoreStatus(KeystoreStatus::from(&*keystore))
+            }
             KeyToolCommand::Generate {
                 key_scheme,
                 derivation_path,
@@ -647,6 +766,21 @@ impl KeyToolCommand {
                 }
                 CommandOutput::List(keys)
             }
+            KeyToolCommand::Lock => {
+                if let Keystore::Encrypted(encrypted) = keystore {
+                    encrypted.lock()?;
+                }
+                CommandOutput::KeystoreStatus(KeystoreStatus::from(&*keystore))
+            }
+            KeyToolCommand::Unlock { session_ttl_secs } => {
+                let Keystore::Encrypted(encrypted) = keystore else {
+                    return Err(anyhow!("The keystore is not encrypted"));
+                };
+                encrypted.start_session(Duration::from_secs(session_ttl_secs))?;
+                let mut status = KeystoreStatus::from(&*keystore);
+                status.session_ttl_secs = Some(session_ttl_secs);
+                CommandOutput::KeystoreStatus(status)
+            }
             KeyToolCommand::MultiSigAddress {
                 threshold,
                 pks,
@@ -740,6 +874,45 @@ impl KeyToolCommand {
                     iota_signature: iota_signature.encode_base64(),
                 })
             }
+            KeyToolCommand::SignBundle {
+                address,
+                bundle: bundle_path,
+                yes,
+                qr,
+            } => {
+                let address = get_identity_address_from_keystore(address, keystore)?;
+                let mut bundle = SigningBundle::read(&bundle_path)?;
+                let summary = bundle.summary()?;
+                let public_key = keystore.get_key(&address)?.public();
+                ensure!(
+                    address == summary.sender
+                        || address == summary.gas_owner
+                        || bundle
+                            .multisig_pk
+                            .as_ref()
+                            .is_some_and(|pk| pk.get_index(&public_key).is_some()),
+                    "Address {address} is not a signer of the bundled transaction"
+                );
+                if !yes {
+                    println!("{summary}");
+                    let confirmed = Confirm::new(&format!("Sign this transaction with {address}?"))
+                        .with_default(false)
+                        .prompt()?;
+                    ensure!(confirmed, "Signing aborted");
+                }
+                let signature: GenericSignature = keystore
+                    .sign_secure(&address, &bundle.tx_data()?, Intent::iota_transaction())?
+                    .into();
+                bundle.add_signature(signature.clone())?;
+                bundle.write(&bundle_path)?;
+                CommandOutput::SignBundle(SignBundleOutput {
+                    iota_address: address,
+                    bundle: bundle_path,
+                    summary: bundle.summary()?,
+                    iota_signature: signature.encode_base64(),
+                    qr_code: qr.then(|| render_qr_code(&signature)).transpose()?,
+                })
+            }
             KeyToolCommand::SignKMS {
                 data,
                 keyid,
@@ -1214,6 +1387,15 @@ impl Display for CommandOutput {
                 table.with(Modify::new(Rows::new(0..)).with(Width::wrap(160).keep_words()));
                 write!(formatter, "{}", table)
             }
+            CommandOutput::SignBundle(output) => {
+                writeln!(formatter, "{}", output.summary)?;
+                writeln!(formatter, "Bundle: {}", output.bundle.display())?;
+                write!(formatter, "Signature: {}", output.iota_signature)?;
+                if let Some(qr_code) = &output.qr_code {
+                    write!(formatter, "\n{qr_code}")?;
+                }
+                Ok(())
+            }
             _ => {
                 let json_obj = json![self];
                 let mut table = json_to_table(&json_obj);
@@ -1254,6 +1436,41 @@ impl Debug for CommandOutput {
     }
 }
 
+/// Lets an encrypted keystore unlock itself the first time a private key is
+/// needed, using a cached session if there is one. Otherwise the passphrase
+/// is read from the `IOTA_KEYSTORE_PASSPHRASE` environment variable or
+/// prompted for. Commands that only read public data never unlock the
+/// keystore. Other keystores are left untouched.
+pub fn enable_lazy_unlock(keystore: &mut Keystore) {
+    if let Keystore::Encrypted(keystore) = keystore {
+        keystore.set_passphrase_provider(Box::new(|| {
+            Ok(match std::env::var(IOTA_KEYSTORE_PASSPHRASE_ENV) {
+                Ok(passphrase) => passphrase,
+                Err(_) => Password::new("Keystore passphrase:")
+                    .without_confirmation()
+                    .prompt()?,
+            })
+        }));
+    }
+}
+
+/// Reads the new passphrase of a keystore from the
+/// `IOTA_KEYSTORE_NEW_PASSPHRASE` environment variable or prompts for it. The
+/// current passphrase in `IOTA_KEYSTORE_PASSPHRASE` is never used, so that a
+/// passphrase cannot be "changed" to itself by accident.
+fn prompt_new_passphrase() -> Result<String, anyhow::Error> {
+    let passphrase = match std::env::var(IOTA_KEYSTORE_NEW_PASSPHRASE_ENV) {
+        Ok(passphrase) => passphrase,
+        Err(_) => Password::new("New keystore passphrase:")
+            .with_custom_confirmation_message("Confirm keystore passphrase:")
+            .prompt()?,
+    };
+    if passphrase.is_empty() {
+        return Err(anyhow!("The keystore passphrase must not be empty"));
+    }
+    Ok(passphrase)
+}
+
 /// Converts legacy formatted private key to 33 bytes bech32 encoded private key
 /// or vice versa. It can handle:
 /// 1) Hex encoded 32 byte private key (assumes scheme is Ed25519), this is the
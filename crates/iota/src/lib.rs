@@ -16,6 +16,7 @@ pub mod iota_commands;
 pub mod key_identity;
 pub mod keytool;
 pub mod shell;
+pub mod signing_bundle;
 pub mod validator_commands;
 mod verifier_meter;
 // Commented: https://github.com/iotaledger/iota/issues/1777
@@ -0,0 +1,425 @@
+// Copyright (c) 2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Signing bundles carry a transaction to an air-gapped machine and the
+//! signatures back. A bundle contains the unsigned transaction together with
+//! the objects it reads, so that `iota keytool sign-bundle` can show what is
+//! being signed without any network access.
+
+use std::{collections::BTreeMap, fmt::Display, fs, path::Path};
+
+use anyhow::{Context, anyhow, bail, ensure};
+use fastcrypto::encoding::{Base64, Encoding, Hex};
+use iota_json_rpc_types::IotaObjectDataOptions;
+use iota_sdk::IotaClient;
+use iota_types::{
+    base_types::{IotaAddress, ObjectID, SequenceNumber},
+    crypto::EncodeDecodeBase64,
+    digests::TransactionDigest,
+    multisig::{MultiSig, MultiSigPublicKey},
+    object::Object,
+    signature::GenericSignature,
+    transaction::{
+        Argument, CallArg, Command, InputObjectKind, ProgrammableTransaction, TransactionData,
+        TransactionDataAPI, TransactionKind,
+    },
+};
+use qrcode::{QrCode, render::unicode::Dense1x2};
+use serde::{Deserialize, Serialize};
+use tempfile::NamedTempFile;
+
+/// An unsigned transaction, the objects needed to display it offline and the
+/// signatures collected so far.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SigningBundle {
+    /// BCS serialized `TransactionData`.
+    pub tx_bytes: Base64,
+    /// BCS serialized input objects of the transaction, Move packages
+    /// excluded.
+    pub objects: Vec<Base64>,
+    /// The public key of the sender if the sender is a MultiSig address. The
+    /// collected signatures are then combined into a single MultiSig.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multisig_pk: Option<MultiSigPublicKey>,
+    /// Signatures `flag || sig || pk` collected so far.
+    #[serde(default)]
+    pub signatures: Vec<GenericSignature>,
+}
+
+/// Human-readable description of a bundled transaction.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SigningBundleSummary {
+    pub digest: TransactionDigest,
+    pub sender: IotaAddress,
+    pub gas_owner: IotaAddress,
+    pub gas_budget: u64,
+    pub gas_price: u64,
+    pub commands: Vec<String>,
+    pub objects: Vec<BundleObjectSummary>,
+    pub signed_by: Vec<IotaAddress>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleObjectSummary {
+    pub object_id: ObjectID,
+    pub version: SequenceNumber,
+    pub object_type: String,
+    pub owner: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<u64>,
+    /// Whether the object content is committed to by the transaction. Shared
+    /// objects are only referenced by ID, so their content may have changed
+    /// by the time the transaction executes.
+    pub verified: bool,
+}
+
+impl SigningBundle {
+    pub fn new(
+        tx_data: &TransactionData,
+        objects: &[Object],
+        multisig_pk: Option<MultiSigPublicKey>,
+    ) -> Result<Self, anyhow::Error> {
+        if let Some(multisig_pk) = &multisig_pk {
+            ensure!(
+                IotaAddress::from(multisig_pk) == tx_data.sender(),
+                "The MultiSig public key does not match the transaction sender {}",
+                tx_data.sender()
+            );
+        }
+        Ok(Self {
+            tx_bytes: Base64::from_bytes(&bcs::to_bytes(tx_data)?),
+            objects: objects
+                .iter()
+                .map(|object| Ok(Base64::from_bytes(&bcs::to_bytes(object)?)))
+                .collect::<Result<_, bcs::Error>>()?,
+            multisig_pk,
+            signatures: vec![],
+        })
+    }
+
+    /// Creates a bundle for the transaction, fetching its input objects from
+    /// the network.
+    pub async fn fetch(
+        client: &IotaClient,
+        tx_data: &TransactionData,
+        multisig_pk: Option<MultiSigPublicKey>,
+    ) -> Result<Self, anyhow::Error> {
+        let mut object_ids = tx_data
+            .input_objects()?
+            .into_iter()
+            .filter_map(|kind| match kind {
+                InputObjectKind::MovePackage(_) => None,
+                kind => Some(kind.object_id()),
+            })
+            .chain(tx_data.receiving_objects().into_iter().map(|(id, _, _)| id))
+            .collect::<Vec<_>>();
+        object_ids.sort();
+        object_ids.dedup();
+
+        let mut objects = Vec::with_capacity(object_ids.len());
+        for response in client
+            .read_api()
+            .multi_get_object_with_options(object_ids, IotaObjectDataOptions::bcs_lossless())
+            .await?
+        {
+            let data = response
+                .into_object()
+                .map_err(|e| anyhow!("Failed to fetch input object: {e}"))?;
+            objects.push(data.try_into()?);
+        }
+        Self::new(tx_data, &objects, multisig_pk)
+    }
+
+    pub fn read(path: &Path) -> Result<Self, anyhow::Error> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Cannot read signing bundle {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Invalid signing bundle {}", path.display()))
+    }
+
+    /// Writes the bundle to a temporary file next to `path` and renames it, so
+    /// that an interrupted write never leaves a truncated bundle behind.
+    pub fn write(&self, path: &Path) -> Result<(), anyhow::Error> {
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let mut file = NamedTempFile::new_in(dir)
+            .with_context(|| format!("Cannot write signing bundle {}", path.display()))?;
+        serde_json::to_writer_pretty(&mut file, self)?;
+        file.as_file().sync_all()?;
+        file.persist(path)
+            .with_context(|| format!("Cannot write signing bundle {}", path.display()))?;
+        Ok(())
+    }
+
+    pub fn tx_data(&self) -> Result<TransactionData, anyhow::Error> {
+        bcs::from_bytes(&self.tx_bytes.to_vec().map_err(|e| anyhow!(e))?)
+            .map_err(|e| anyhow!("Cannot deserialize bundled transaction: {e}"))
+    }
+
+    /// Decodes the bundled objects. Fails if an object does not match the
+    /// reference the transaction commits to, which means the bundle was
+    /// tampered with.
+    fn objects(&self, tx_data: &TransactionData) -> Result<Vec<(Object, bool)>, anyhow::Error> {
+        let committed = tx_data
+            .input_objects()?
+            .into_iter()
+            .filter_map(|kind| match kind {
+                InputObjectKind::ImmOrOwnedMoveObject(obj_ref) => Some(obj_ref),
+                _ => None,
+            })
+            .chain(tx_data.receiving_objects())
+            .map(|obj_ref| (obj_ref.0, obj_ref))
+            .collect::<BTreeMap<_, _>>();
+
+        self.objects
+            .iter()
+            .map(|bytes| {
+                let object: Object = bcs::from_bytes(&bytes.to_vec().map_err(|e| anyhow!(e))?)?;
+                let verified = match committed.get(&object.id()) {
+                    Some(obj_ref) => {
+                        ensure!(
+                            object.compute_object_reference() == *obj_ref,
+                            "Bundled object {} does not match the transaction input {:?}",
+                            object.id(),
+                            obj_ref
+                        );
+                        true
+                    }
+                    None => false,
+                };
+                Ok((object, verified))
+            })
+            .collect()
+    }
+
+    pub fn summary(&self) -> Result<SigningBundleSummary, anyhow::Error> {
+        let tx_data = self.tx_data()?;
+        let commands = match tx_data.kind() {
+            TransactionKind::ProgrammableTransaction(pt) => describe_inputs(pt)
+                .into_iter()
+                .chain(pt.commands.iter().map(ToString::to_string))
+                .collect(),
+            kind => vec![kind.to_string()],
+        };
+        let objects = self
+            .objects(&tx_data)?
+            .into_iter()
+            .map(|(object, verified)| BundleObjectSummary {
+                object_id: object.id(),
+                version: object.version(),
+                object_type: object
+                    .struct_tag()
+                    .map_or_else(|| "package".to_string(), |tag| tag.to_string()),
+                owner: object.owner().to_string(),
+                balance: object
+                    .is_coin()
+                    .then(|| object.as_coin_maybe().map(|coin| coin.value()))
+                    .flatten(),
+                verified,
+            })
+            .collect();
+        Ok(SigningBundleSummary {
+            digest: tx_data.digest(),
+            sender: tx_data.sender(),
+            gas_owner: tx_data.gas_owner(),
+            gas_budget: tx_data.gas_budget(),
+            gas_price: tx_data.gas_price(),
+            commands,
+            objects,
+            signed_by: self
+                .signatures
+                .iter()
+                .filter_map(|sig| sig.to_public_key().ok())
+                .map(|pk| IotaAddress::from(&pk))
+                .collect(),
+        })
+    }
+
+    /// Adds a signature, replacing a previous one of the same signer.
+    pub fn add_signature(&mut self, signature: GenericSignature) -> Result<(), anyhow::Error> {
+        let pk = signature.to_public_key()?;
+        self.signatures
+            .retain(|sig| sig.to_public_key().ok().as_ref() != Some(&pk));
+        self.signatures.push(signature);
+        Ok(())
+    }
+
+    /// Merges the signatures of other bundles of the same transaction into
+    /// this one.
+    pub fn merge(&mut self, other: SigningBundle) -> Result<(), anyhow::Error> {
+        ensure!(
+            self.tx_bytes == other.tx_bytes,
+            "Cannot merge signing bundles of different transactions"
+        );
+        ensure!(
+            self.multisig_pk == other.multisig_pk,
+            "Cannot merge signing bundles with different MultiSig public keys"
+        );
+        for signature in other.signatures {
+            self.add_signature(signature)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the transaction signatures. Signatures of MultiSig members are
+    /// combined into a single MultiSig, the remaining ones (e.g. of a
+    /// sponsor) are kept as they are.
+    pub fn transaction_signatures(&self) -> Result<Vec<GenericSignature>, anyhow::Error> {
+        ensure!(
+            !self.signatures.is_empty(),
+            "The signing bundle has no signatures"
+        );
+        let Some(multisig_pk) = &self.multisig_pk else {
+            return Ok(self.signatures.clone());
+        };
+        let mut members = Vec::new();
+        let mut others = Vec::new();
+        for signature in &self.signatures {
+            match multisig_pk.get_index(&signature.to_public_key()?) {
+                Some(index) => members.push((index, signature.clone())),
+                None => others.push(signature.clone()),
+            }
+        }
+        if members.is_empty() {
+            bail!("The signing bundle has no signatures of MultiSig members");
+        }
+        // MultiSig::combine expects the signatures in the order of the public keys.
+        members.sort_by_key(|(index, _)| *index);
+        let multisig = MultiSig::combine(
+            members.into_iter().map(|(_, sig)| sig).collect(),
+            multisig_pk.clone(),
+        )?;
+        Ok(std::iter::once(GenericSignature::MultiSig(multisig))
+            .chain(others)
+            .collect())
+    }
+}
+
+impl Display for SigningBundleSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Transaction Digest: {}", self.digest)?;
+        writeln!(f, "Sender: {}", self.sender)?;
+        writeln!(f, "Gas Owner: {}", self.gas_owner)?;
+        writeln!(f, "Gas Budget: {} NANOS", self.gas_budget)?;
+        writeln!(f, "Gas Price: {} NANOS", self.gas_price)?;
+        writeln!(f, "Commands:")?;
+        for command in &self.commands {
+            writeln!(f, "  {command}")?;
+        }
+        writeln!(f, "Objects:")?;
+        for object in &self.objects {
+            write!(
+                f,
+                "  {} (version {}) {}, owner: {}",
+                object.object_id, object.version, object.object_type, object.owner
+            )?;
+            if let Some(balance) = object.balance {
+                write!(f, ", balance: {balance}")?;
+            }
+            if !object.verified {
+                write!(f, " [unverified]")?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(f, "Signed By:")?;
+        for address in &self.signed_by {
+            writeln!(f, "  {address}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The type of a pure input, as implied by the commands using it.
+#[derive(Clone, Copy)]
+enum PureType {
+    U64,
+    Address,
+}
+
+/// Describes the inputs of a programmable transaction. Pure inputs are
+/// decoded with the type implied by the commands using them; the type of
+/// Move call arguments is not known offline, so those are shown as bytes
+/// together with their most likely interpretation.
+fn describe_inputs(pt: &ProgrammableTransaction) -> Vec<String> {
+    let mut types = BTreeMap::new();
+    for command in &pt.commands {
+        match command {
+            Command::SplitCoins(_, amounts) => {
+                for amount in amounts {
+                    if let Argument::Input(i) = amount {
+                        types.insert(*i as usize, PureType::U64);
+                    }
+                }
+            }
+            Command::TransferObjects(_, Argument::Input(i)) => {
+                types.insert(*i as usize, PureType::Address);
+            }
+            _ => {}
+        }
+    }
+
+    pt.inputs
+        .iter()
+        .enumerate()
+        .map(|(i, input)| match input {
+            CallArg::Pure(bytes) => {
+                format!("Input({i}): Pure({})", describe_pure(bytes, types.get(&i)))
+            }
+            CallArg::Object(arg) => format!("Input({i}): {arg:?}"),
+        })
+        .collect()
+}
+
+fn describe_pure(bytes: &[u8], ty: Option<&PureType>) -> String {
+    match ty {
+        Some(PureType::U64) => {
+            if let Ok(value) = bcs::from_bytes::<u64>(bytes) {
+                return format!("u64 {value}");
+            }
+        }
+        Some(PureType::Address) => {
+            if let Ok(address) = bcs::from_bytes::<IotaAddress>(bytes) {
+                return format!("address {address}");
+            }
+        }
+        None => {}
+    }
+
+    let hex = format!("0x{}", Hex::encode(bytes));
+    let guess = match bytes.len() {
+        1 => bcs::from_bytes::<u8>(bytes).ok().map(|v| format!("u8 {v}")),
+        8 => bcs::from_bytes::<u64>(bytes)
+            .ok()
+            .map(|v| format!("u64 {v}")),
+        16 => bcs::from_bytes::<u128>(bytes)
+            .ok()
+            .map(|v| format!("u128 {v}")),
+        32 => bcs::from_bytes::<IotaAddress>(bytes)
+            .ok()
+            .map(|v| format!("address {v}")),
+        _ => bcs::from_bytes::<String>(bytes)
+            .ok()
+            .filter(|v| !v.is_empty() && !v.chars().any(char::is_control))
+            .map(|v| format!("string {v:?}")),
+    };
+    match guess {
+        Some(guess) => format!("{hex}, as {guess}"),
+        None => hex,
+    }
+}
+
+/// Renders a signature as a QR code for the terminal, so that it can be
+/// transferred from an air-gapped machine without a file.
+pub fn render_qr_code(signature: &GenericSignature) -> Result<String, anyhow::Error> {
+    let code = QrCode::new(signature.encode_base64())?;
+    Ok(code
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .build())
+}
@@ -8,17 +8,21 @@ use anyhow::Ok;
 use fastcrypto::{
     ed25519::Ed25519KeyPair,
     encoding::{Base64, Encoding, Hex},
-    traits::ToFromBytes,
+    secp256r1::Secp256r1KeyPair,
+    traits::{KeyPair, ToFromBytes},
 };
 use iota_keys::keystore::{AccountKeystore, FileBasedKeystore, InMemKeystore, Keystore};
 use iota_types::{
     base_types::{IotaAddress, ObjectDigest, ObjectID, SequenceNumber},
     crypto::{
         AuthorityKeyPair, Ed25519IotaSignature, EncodeDecodeBase64, IotaKeyPair,
-        IotaSignatureInner, Secp256k1IotaSignature, Secp256r1IotaSignature, Signature,
+        IotaSignatureInner, PublicKey, Secp256k1IotaSignature, Secp256r1IotaSignature, Signature,
         SignatureScheme, get_key_pair, get_key_pair_from_rng,
     },
-    transaction::{TEST_ONLY_GAS_UNIT_FOR_TRANSFER, TransactionData},
+    multisig::MultiSigPublicKey,
+    object::Object,
+    signature::{GenericSignature, VerifyParams},
+    transaction::{TEST_ONLY_GAS_UNIT_FOR_TRANSFER, Transaction, TransactionData},
 };
 use rand::{SeedableRng, rngs::StdRng};
 use shared_crypto::intent::{Intent, IntentScope};
@@ -29,6 +33,7 @@ use super::{KeyToolCommand, write_keypair_to_file};
 use crate::{
     key_identity::KeyIdentity,
     keytool::{CommandOutput, read_authority_keypair_from_file, read_keypair_from_file},
+    signing_bundle::SigningBundle,
 };
 
 const TEST_MNEMONIC: &str = "result crisp session latin must fruit genuine question prevent start coconut brave speak student dismiss";
@@ -620,3 +625,179 @@ async fn test_sign_command() -> Result<(), anyhow::Error> {
     .await?;
     Ok(())
 }
+
+#[test]
+async fn test_multisig_address_with_passkey() -> Result<(), anyhow::Error> {
+    let mut keystore = Keystore::from(InMemKeystore::new_insecure_for_tests(0));
+    let ed25519_pk = IotaKeyPair::Ed25519(get_key_pair().1).public();
+    let r1_kp: Secp256r1KeyPair = get_key_pair().1;
+    // A passkey member is passed as `0x06 || compressed secp256r1 pk` in Base64.
+    let mut passkey_pk_bytes = vec![SignatureScheme::PasskeyAuthenticator.flag()];
+    passkey_pk_bytes.extend_from_slice(r1_kp.public().as_bytes());
+    let passkey_pk = PublicKey::from_str(&Base64::encode(&passkey_pk_bytes)).unwrap();
+    assert_eq!(passkey_pk.scheme(), SignatureScheme::PasskeyAuthenticator);
+
+    let output = KeyToolCommand::MultiSigAddress {
+        threshold: 2,
+        pks: vec![passkey_pk.clone(), ed25519_pk.clone()],
+        weights: vec![1, 1],
+    }
+    .execute(&mut keystore)
+    .await?;
+    let expected = IotaAddress::from(&MultiSigPublicKey::new(
+        vec![passkey_pk.clone(), ed25519_pk],
+        vec![1, 1],
+        2,
+    )?);
+    match output {
+        CommandOutput::MultiSigAddress(output) => {
+            assert_eq!(output.multisig_address, expected.to_string());
+            assert_eq!(output.multisig[0].address, IotaAddress::from(&passkey_pk));
+        }
+        _ => panic!("unexpected output"),
+    }
+    Ok(())
+}
+
+#[test]
+async fn test_sign_bundle_command() -> Result<(), anyhow::Error> {
+    let mut keystore = Keystore::from(InMemKeystore::new_insecure_for_tests(2));
+    let addresses = keystore.addresses();
+    let (sender, other) = (addresses[0], addresses[1]);
+
+    let gas_object = Object::with_id_owner_gas_for_testing(ObjectID::random(), sender, 1_000_000);
+    let gas = gas_object.compute_object_reference();
+    let gas_price = 1;
+    let tx_data = TransactionData::new_pay_iota(
+        sender,
+        vec![gas],
+        vec![IotaAddress::random_for_testing_only()],
+        vec![10000],
+        gas,
+        gas_price * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        gas_price,
+    )
+    .unwrap();
+
+    let temp_dir = TempDir::new().unwrap();
+    let bundle_path = temp_dir.path().join("bundle.json");
+    SigningBundle::new(&tx_data, &[gas_object.clone()], None)?.write(&bundle_path)?;
+
+    let output = KeyToolCommand::SignBundle {
+        address: KeyIdentity::Address(sender),
+        bundle: bundle_path.clone(),
+        yes: true,
+        qr: true,
+    }
+    .execute(&mut keystore)
+    .await?;
+    assert!(matches!(output, CommandOutput::SignBundle(_)));
+
+    // The signature was added to the bundle file.
+    let bundle = SigningBundle::read(&bundle_path)?;
+    let summary = bundle.summary()?;
+    assert_eq!(summary.signed_by, vec![sender]);
+    assert_eq!(summary.objects.len(), 1);
+    assert!(summary.objects[0].verified);
+    assert_eq!(summary.objects[0].balance, Some(1_000_000));
+    assert_eq!(bundle.transaction_signatures()?.len(), 1);
+
+    // A bundled object that does not match the transaction input is rejected.
+    let tampered_object = Object::with_id_owner_gas_for_testing(gas.0, sender, 2_000_000);
+    let tampered = SigningBundle::new(&tx_data, &[tampered_object], None)?;
+    assert!(tampered.summary().is_err());
+
+    // Only signers of the transaction can sign the bundle.
+    assert!(
+        KeyToolCommand::SignBundle {
+            address: KeyIdentity::Address(other),
+            bundle: bundle_path,
+            yes: true,
+            qr: false,
+        }
+        .execute(&mut keystore)
+        .await
+        .is_err()
+    );
+    Ok(())
+}
+
+#[test]
+async fn test_sign_bundle_multisig() -> Result<(), anyhow::Error> {
+    let mut keystore = Keystore::from(InMemKeystore::new_insecure_for_tests(3));
+    let addresses = keystore.addresses();
+    let pks = addresses
+        .iter()
+        .map(|address| keystore.get_key(address).map(|kp| kp.public()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let multisig_pk = MultiSigPublicKey::new(pks, vec![1, 1, 1], 2)?;
+    let sender = IotaAddress::from(&multisig_pk);
+
+    let gas_object = Object::with_id_owner_gas_for_testing(ObjectID::random(), sender, 1_000_000);
+    let gas = gas_object.compute_object_reference();
+    let recipient = IotaAddress::random_for_testing_only();
+    let gas_price = 1;
+    let tx_data = TransactionData::new_pay_iota(
+        sender,
+        vec![gas],
+        vec![recipient],
+        vec![10000],
+        gas,
+        gas_price * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        gas_price,
+    )
+    .unwrap();
+
+    let temp_dir = TempDir::new().unwrap();
+    let bundle = SigningBundle::new(&tx_data, &[gas_object], Some(multisig_pk))?;
+
+    // The pure inputs are decoded with the types the commands use them with.
+    let summary = bundle.summary()?;
+    assert!(
+        summary
+            .commands
+            .iter()
+            .any(|c| c.contains("Pure(u64 10000)"))
+    );
+    assert!(
+        summary
+            .commands
+            .iter()
+            .any(|c| c.contains(&format!("Pure(address {recipient})")))
+    );
+
+    // Each member signs its own copy of the bundle, in reverse order of the
+    // MultiSig public keys.
+    let mut paths = vec![];
+    for address in [addresses[2], addresses[0]] {
+        let path = temp_dir.path().join(format!("bundle-{address}.json"));
+        bundle.write(&path)?;
+        KeyToolCommand::SignBundle {
+            address: KeyIdentity::Address(address),
+            bundle: path.clone(),
+            yes: true,
+            qr: false,
+        }
+        .execute(&mut keystore)
+        .await?;
+        paths.push(path);
+    }
+
+    let mut merged = SigningBundle::read(&paths[0])?;
+    merged.merge(SigningBundle::read(&paths[1])?)?;
+    let signatures = merged.transaction_signatures()?;
+    assert_eq!(signatures.len(), 1);
+    assert!(matches!(signatures[0], GenericSignature::MultiSig(_)));
+    Transaction::from_generic_sig_data(tx_data.clone(), signatures)
+        .verify_signature_for_testing(0, &VerifyParams::default())?;
+
+    // A single member does not reach the threshold.
+    let single = SigningBundle::read(&paths[0])?;
+    let signatures = single.transaction_signatures()?;
+    assert!(
+        Transaction::from_generic_sig_data(tx_data, signatures)
+            .verify_signature_for_testing(0, &VerifyParams::default())
+            .is_err()
+    );
+    Ok(())
+}
@@ -15,12 +15,13 @@ use std::{
     fs::read_dir,
     io::Read,
     net::SocketAddr,
-    path::PathBuf,
+    path::{Path, PathBuf},
     str, thread,
     time::Duration,
 };
 
 use expect_test::expect;
+use fastcrypto::encoding::{Base64, Encoding};
 #[cfg(feature = "indexer")]
 use iota::iota_commands::IndexerFeatureArgs;
 use iota::{
@@ -31,6 +32,7 @@ use iota::{
     client_ptb::ptb::PTB,
     iota_commands::{IotaCommand, parse_host_port},
     key_identity::{KeyIdentity, get_identity_address},
+    keytool::KeyToolCommand,
 };
 use iota_config::{
     IOTA_CLIENT_CONFIG, IOTA_FULLNODE_CONFIG, IOTA_GENESIS_FILENAME,
@@ -39,7 +41,7 @@ use iota_config::{
 use iota_json::IotaJsonValue;
 use iota_json_rpc_types::{
     IotaExecutionStatus, IotaObjectData, IotaObjectDataFilter, IotaObjectDataOptions,
-    IotaObjectResponse, IotaObjectResponseQuery, IotaTransactionBlockDataAPI,
+    IotaObjectResponse, IotaObjectResponseQuery, IotaRawData, IotaTransactionBlockDataAPI,
     IotaTransactionBlockEffects, IotaTransactionBlockEffectsAPI, OwnedObjectRef,
     get_new_package_obj_from_response,
 };
@@ -60,6 +62,7 @@ use iota_types::{
     },
     error::IotaObjectResponseError,
     gas_coin::GasCoin,
+    move_package::UpgradeCap,
     object::Owner,
     transaction::{
         TEST_ONLY_GAS_UNIT_FOR_GENERIC, TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS,
@@ -3043,6 +3046,85 @@ async fn test_serialize_tx() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_signing_bundle() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let address = test_cluster.get_address_0();
+    let address1 = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+    let client = context.get_client().await?;
+    let coins = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data;
+    let tx_data = client
+        .transaction_builder()
+        .transfer_iota(
+            address,
+            coins[0].coin_object_id,
+            rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+            address1,
+            Some(1),
+        )
+        .await?;
+
+    let temp_dir = tempfile::tempdir()?;
+    let bundle_path = temp_dir.path().join("bundle.json");
+    let result = IotaClientCommands::ExportSigningBundle {
+        tx_bytes: Base64::encode(bcs::to_bytes(&tx_data)?),
+        output: bundle_path.clone(),
+        multisig_pks: vec![],
+        weights: vec![],
+        threshold: None,
+    }
+    .execute(context)
+    .await?;
+    let IotaClientCommandResult::SigningBundle { summary, .. } = result else {
+        panic!("Command failed")
+    };
+    assert_eq!(summary.digest, tx_data.digest());
+    assert_eq!(summary.sender, address);
+    assert!(!summary.objects.is_empty());
+    assert!(summary.objects.iter().all(|object| object.verified));
+
+    // The bundle cannot be executed before it is signed.
+    assert!(
+        IotaClientCommands::ExecuteSigningBundle {
+            bundles: vec![bundle_path.clone()],
+            signatures: vec![],
+            serialize_signed_transaction: false,
+        }
+        .execute(context)
+        .await
+        .is_err()
+    );
+
+    KeyToolCommand::SignBundle {
+        address: KeyIdentity::Address(address),
+        bundle: bundle_path.clone(),
+        yes: true,
+        qr: false,
+    }
+    .execute(context.config_mut().keystore_mut())
+    .await?;
+
+    let result = IotaClientCommands::ExecuteSigningBundle {
+        bundles: vec![bundle_path],
+        signatures: vec![],
+        serialize_signed_transaction: false,
+    }
+    .execute(context)
+    .await?;
+    let IotaClientCommandResult::TransactionBlock(response) = result else {
+        panic!("Command failed")
+    };
+    assert_eq!(response.digest, tx_data.digest());
+    assert!(response.effects.unwrap().status().is_ok());
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_stake_with_none_amount() -> Result<(), anyhow::Error> {
     let mut test_cluster = TestClusterBuilder::new().build().await;
@@ -4078,6 +4160,187 @@ async fn test_move_build_bytecode_with_address_resolution() -> Result<(), anyhow
     Ok(())
 }
 
+/// Runs the `iota` binary like [`test_with_iota_binary`], returning its output
+/// whether it succeeds or not.
+async fn iota_binary_output(args: &[&str]) -> std::process::Output {
+    let mut cmd = assert_cmd::Command::cargo_bin("iota").unwrap();
+    let args = args.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    // test cluster will not response if this call is in the same thread
+    let out = thread::spawn(move || cmd.args(args).output().unwrap());
+    while !out.is_finished() {
+        sleep(Duration::from_millis(100)).await;
+    }
+    out.join().unwrap()
+}
+
+/// Reads the published package IDs from the `Move.lock` of the package at
+/// `path`, returning the original and the latest ID.
+fn published_ids(path: &Path) -> (ObjectID, ObjectID) {
+    let mut lock_file = std::fs::File::open(path.join("Move.lock")).unwrap();
+    let envs = ManagedPackage::read(&mut lock_file).unwrap();
+    let localnet = envs.get("localnet").unwrap();
+    (
+        ObjectID::from_hex_literal(&localnet.original_published_id).unwrap(),
+        ObjectID::from_hex_literal(&localnet.latest_published_id).unwrap(),
+    )
+}
+
+/// Audits the package at `package_path` with `iota move audit --json`,
+/// returning the exit code and the report.
+async fn move_audit(
+    config_path: &Path,
+    package_path: &Path,
+    extra_args: &[&str],
+) -> (Option<i32>, serde_json::Value) {
+    let mut args = vec![
+        "move",
+        "--client.config",
+        config_path.to_str().unwrap(),
+        "audit",
+        "--json",
+        "--path",
+        package_path.to_str().unwrap(),
+    ];
+    args.extend(extra_args);
+    let output = iota_binary_output(&args).await;
+    let report = serde_json::from_slice(&output.stdout).unwrap();
+    (output.status.code(), report)
+}
+
+#[tokio::test]
+async fn test_move_audit() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let config_path = test_cluster.swarm.dir().join(IOTA_CLIENT_CONFIG);
+    let address = test_cluster.wallet.active_address()?;
+    let client = test_cluster.wallet.get_client().await?;
+
+    // Package setup: a simple package depends on another and copied to tmpdir
+    let tmp_dir = tempfile::tempdir().unwrap();
+    for package in ["simple", "depends_on_simple"] {
+        fs_extra::dir::copy(
+            PathBuf::from(TEST_DATA_DIR).join(package),
+            &tmp_dir,
+            &fs_extra::dir::CopyOptions::default(),
+        )?;
+    }
+    let simple_tmp_dir = tmp_dir.path().join("simple");
+    let depends_on_simple_tmp_dir = tmp_dir.path().join("depends_on_simple");
+
+    // Publish the dependency, then the package that depends on it.
+    for package_path in [&simple_tmp_dir, &depends_on_simple_tmp_dir] {
+        test_with_iota_binary(&[
+            "client",
+            "--client.config",
+            config_path.to_str().unwrap(),
+            "publish",
+            package_path.to_str().unwrap(),
+        ])
+        .await?;
+    }
+    let (simple_id, _) = published_ids(&simple_tmp_dir);
+    let (depends_on_simple_id, _) = published_ids(&depends_on_simple_tmp_dir);
+
+    // Upgrade the dependency from a copy, so that the package keeps being built
+    // against the version it is linked against.
+    let cap_id = client
+        .read_api()
+        .get_owned_objects(
+            address,
+            Some(IotaObjectResponseQuery::new(
+                Some(IotaObjectDataFilter::StructType(UpgradeCap::type_())),
+                Some(IotaObjectDataOptions::new().with_bcs()),
+            )),
+            None,
+            None,
+        )
+        .await?
+        .data
+        .into_iter()
+        .find_map(|response| {
+            let object = response.object().ok()?;
+            let IotaRawData::MoveObject(cap) = object.bcs.as_ref()? else {
+                return None;
+            };
+            let cap: UpgradeCap = bcs::from_bytes(&cap.bcs_bytes).ok()?;
+            (cap.package.bytes == simple_id).then_some(object.object_id)
+        })
+        .expect("upgrade cap of the dependency");
+    let upgrade_dir = tmp_dir.path().join("upgrade");
+    std::fs::create_dir(&upgrade_dir)?;
+    fs_extra::dir::copy(
+        &simple_tmp_dir,
+        &upgrade_dir,
+        &fs_extra::dir::CopyOptions::default(),
+    )?;
+    test_with_iota_binary(&[
+        "client",
+        "--client.config",
+        config_path.to_str().unwrap(),
+        "upgrade",
+        "--upgrade-capability",
+        &cap_id.to_string(),
+        upgrade_dir.join("simple").to_str().unwrap(),
+    ])
+    .await?;
+    let (_, simple_upgraded_id) = published_ids(&upgrade_dir.join("simple"));
+    assert_ne!(simple_id, simple_upgraded_id);
+
+    let deny_list = tmp_dir.path().join("deny-list");
+    std::fs::write(&deny_list, format!("# denied packages\n{simple_id}\n"))?;
+
+    // The outdated dependency is reported, but doesn't fail the audit.
+    let (code, report) = move_audit(&config_path, &depends_on_simple_tmp_dir, &[]).await;
+    assert_eq!(code, Some(0), "{report:#}");
+    assert_eq!(report["passed"], json!(true));
+    let package = &report["package"];
+    assert_eq!(
+        package["originalId"],
+        json!(depends_on_simple_id.to_string())
+    );
+    assert_eq!(package["upgradePolicy"], json!("compatible"));
+    assert_eq!(package["sourceVerification"]["status"], json!("verified"));
+    let simple = report["dependencies"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|dependency| dependency["originalId"] == json!(simple_id.to_string()))
+        .expect("the dependency is reported");
+    assert_eq!(simple["linkedId"], json!(simple_id.to_string()));
+    assert_eq!(simple["linkedVersion"], json!(1));
+    assert_eq!(simple["latestId"], json!(simple_upgraded_id.to_string()));
+    assert_eq!(simple["latestVersion"], json!(2));
+    assert_eq!(simple["upgradePolicy"], json!("compatible"));
+    assert_eq!(simple["denied"], json!(false));
+    assert_eq!(simple["sourceVerification"]["status"], json!("verified"));
+    assert_eq!(report["sourceVerificationErrors"], json!([]));
+
+    let (code, report) = move_audit(
+        &config_path,
+        &depends_on_simple_tmp_dir,
+        &["--fail-on-outdated"],
+    )
+    .await;
+    assert_eq!(code, Some(1), "{report:#}");
+    assert_eq!(report["passed"], json!(false));
+
+    let (code, report) = move_audit(
+        &config_path,
+        &depends_on_simple_tmp_dir,
+        &["--deny-list", deny_list.to_str().unwrap()],
+    )
+    .await;
+    assert_eq!(code, Some(1), "{report:#}");
+    assert_eq!(report["passed"], json!(false));
+    let simple = report["dependencies"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|dependency| dependency["originalId"] == json!(simple_id.to_string()))
+        .unwrap();
+    assert_eq!(simple["denied"], json!(true));
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_parse_host_port() {
     let input = "127.0.0.0";
@@ -13,6 +13,7 @@
 //! [`Simulacrum`]: crate::Simulacrum
 
 mod epoch_state;
+pub mod scenario;
 pub mod store;
 
 use std::{num::NonZeroUsize, path::PathBuf, sync::Arc};
@@ -607,6 +608,21 @@ impl<T: Send + Sync, V: store::SimulatorStore + Send + Sync> RestStateReader for
     ) -> iota_types::storage::error::Result<Option<iota_types::storage::CoinInfo>> {
         todo!()
     }
+
+    fn account_balances_iter(
+        &self,
+        owner: IotaAddress,
+        cursor: Option<StructTag>,
+    ) -> iota_types::storage::error::Result<
+        Box<dyn Iterator<Item = iota_types::storage::AccountBalanceInfo> + '_>,
+    > {
+        let balances = iota_types::storage::AccountBalanceInfo::from_owned_objects(
+            owner,
+            self.store().owned_objects(owner),
+            cursor.as_ref(),
+        );
+        Ok(Box::new(balances.into_iter()))
+    }
 }
 
 impl Simulacrum {
@@ -0,0 +1,1065 @@
+// Copyright (c) 2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Declarative scenarios executed by a [`Simulacrum`].
+//!
+//! A scenario is a YAML or TOML document describing a set of named accounts
+//! and a sequence of steps: package publishes, programmable transactions, clock
+//! and epoch advances and checkpoints. Since the chain is created from the seed
+//! of the scenario, running it twice produces exactly the same effects and
+//! events, which are rendered into a [`ScenarioReport`] that can be checked
+//! against a baseline.
+//!
+//! ```yaml
+//! seed: 1
+//! accounts:
+//!   alice: {}
+//!   bob:
+//!     balance: 5000000000
+//! steps:
+//!   - publish:
+//!       sender: alice
+//!       path: counter
+//!       name: counter
+//!   - ptb:
+//!       sender: alice
+//!       commands:
+//!         - move-call:
+//!             package: counter
+//!             module: counter
+//!             function: create
+//!     bind:
+//!       my_counter: counter::Counter
+//!     expect:
+//!       status: success
+//!       created: 1
+//!   - ptb:
+//!       sender: bob
+//!       commands:
+//!         - move-call:
+//!             package: counter
+//!             module: counter
+//!             function: increment
+//!             arguments:
+//!               - object: my_counter
+//!     expect:
+//!       events: [counter::Incremented]
+//!   - advance-clock:
+//!       ms: 1000
+//!   - create-checkpoint: {}
+//!   - advance-epoch: {}
+//! ```
+//!
+//! The same scenario in TOML starts with:
+//!
+//! ```toml
+//! seed = 1
+//!
+//! [accounts]
+//! alice = {}
+//! bob = { balance = 5000000000 }
+//!
+//! [[steps]]
+//! publish = { sender = "alice", path = "counter", name = "counter" }
+//! ```
+//!
+//! Scenario files are read as TOML if their extension is `.toml`, and as YAML
+//! otherwise. The scenarios in `tests/scenarios` are run against their
+//! committed baselines by `cargo test -p simulacrum --test scenarios`.
+//!
+//! Paths of packages are relative to the directory of the scenario file.
+//! Objects created by a step are bound to a name with `bind`, by the
+//! `module::Name` of their type, and packages by the `name` of their publish
+//! step, so that later steps can refer to them by name.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use anyhow::{Context, Result, anyhow, bail, ensure};
+use fastcrypto::encoding::{Encoding, Hex};
+use iota_move_build::BuildConfig;
+use iota_types::{
+    base_types::{IotaAddress, ObjectID},
+    crypto::{AccountKeyPair, get_key_pair_from_rng},
+    effects::{TransactionEffects, TransactionEffectsAPI},
+    execution_status::ExecutionStatus,
+    gas_coin::NANOS_PER_IOTA,
+    iota_system_state::epoch_start_iota_system_state::EpochStartSystemStateTrait,
+    object::{Object, Owner},
+    parse_iota_type_tag,
+    programmable_transaction_builder::ProgrammableTransactionBuilder,
+    transaction::{
+        Argument, Command, GasData, ObjectArg, Transaction, TransactionData, TransactionKind,
+    },
+};
+use move_command_line_common::testing::read_env_update_baseline;
+use move_core_types::{
+    identifier::Identifier,
+    language_storage::{StructTag, TypeTag},
+};
+use rand::{SeedableRng, rngs::StdRng};
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
+
+use crate::{Simulacrum, store::SimulatorStore};
+
+/// Extension of the baseline file of a scenario file.
+pub const BASELINE_EXTENSION: &str = "snap";
+
+const DEFAULT_BALANCE: u64 = 100 * NANOS_PER_IOTA;
+const DEFAULT_GAS_BUDGET: u64 = 5 * NANOS_PER_IOTA;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Scenario {
+    /// Seed of the rng the chain and the keys of the accounts are created
+    /// with.
+    #[serde(default)]
+    pub seed: u64,
+    #[serde(default)]
+    pub accounts: BTreeMap<String, AccountSpec>,
+    pub steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct AccountSpec {
+    /// Amount of nanos the account is funded with.
+    #[serde(default = "default_balance")]
+    pub balance: u64,
+}
+
+fn default_balance() -> u64 {
+    DEFAULT_BALANCE
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Step {
+    /// Name of the step in the report, defaults to the kind of the action.
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub action: Action,
+    /// Binds the objects created by the step to names, by the `module::Name`
+    /// of their type.
+    #[serde(default)]
+    pub bind: BTreeMap<String, String>,
+    #[serde(default)]
+    pub expect: Option<Expectation>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Action {
+    /// Builds the Move package at `path` and publishes it as `name`. The
+    /// upgrade cap is transferred to the sender.
+    Publish {
+        sender: String,
+        path: PathBuf,
+        name: String,
+        #[serde(default)]
+        gas_budget: Option<u64>,
+    },
+    /// Executes a programmable transaction.
+    Ptb {
+        sender: String,
+        commands: Vec<PtbCommand>,
+        #[serde(default)]
+        gas_budget: Option<u64>,
+    },
+    /// Transfers `amount` nanos, or the whole gas coin, to the recipient.
+    TransferIota {
+        sender: String,
+        recipient: String,
+        #[serde(default)]
+        amount: Option<u64>,
+    },
+    AdvanceClock {
+        ms: u64,
+    },
+    AdvanceEpoch {},
+    CreateCheckpoint {},
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PtbCommand {
+    MoveCall {
+        /// Name of a published package or a package ID.
+        package: String,
+        module: String,
+        function: String,
+        /// Type arguments, in which `name::` prefixes of published packages
+        /// are replaced by their IDs.
+        #[serde(default)]
+        type_arguments: Vec<String>,
+        #[serde(default)]
+        arguments: Vec<PtbArgument>,
+    },
+    TransferObjects {
+        objects: Vec<PtbArgument>,
+        recipient: PtbArgument,
+    },
+    SplitCoins {
+        coin: PtbArgument,
+        amounts: Vec<PtbArgument>,
+    },
+    MergeCoins {
+        coin: PtbArgument,
+        coins: Vec<PtbArgument>,
+    },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PtbArgument {
+    Gas,
+    Result(u16),
+    NestedResult(u16, u16),
+    /// A bound object.
+    Object(String),
+    /// An account name or an address.
+    Address(String),
+    Bool(bool),
+    U8(u8),
+    U64(u64),
+    String(String),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Expectation {
+    #[serde(default)]
+    pub status: Option<ExpectedStatus>,
+    /// Number of created objects.
+    #[serde(default)]
+    pub created: Option<usize>,
+    /// `module::Name` of the emitted events, in order.
+    #[serde(default)]
+    pub events: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExpectedStatus {
+    Success,
+    Failure,
+}
+
+/// Deterministic outputs of a scenario.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ScenarioReport {
+    pub steps: Vec<StepReport>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StepReport {
+    pub step: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<TransactionReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epoch: Option<u64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unmet_expectations: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TransactionReport {
+    pub status: String,
+    pub computation_cost: u64,
+    pub storage_cost: u64,
+    pub storage_rebate: u64,
+    pub created: Vec<ObjectReport>,
+    pub mutated: usize,
+    pub deleted: usize,
+    pub events: Vec<EventReport>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct ObjectReport {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub id: ObjectID,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EventReport {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub contents: String,
+}
+
+impl Scenario {
+    pub fn from_yaml(yaml: &str) -> Result<Self> {
+        Ok(serde_yaml::from_str(yaml)?)
+    }
+
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        Ok(toml::from_str(toml)?)
+    }
+
+    /// Reads a TOML scenario if the extension of `path` is `.toml`, a YAML
+    /// one otherwise.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Unable to read scenario {}", path.display()))?;
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "toml")
+        {
+            Self::from_toml(&contents)
+        } else {
+            Self::from_yaml(&contents)
+        }
+        .with_context(|| format!("Invalid scenario {}", path.display()))
+    }
+
+    /// Executes the scenario on a new chain. Package paths are resolved
+    /// relative to `base_dir`.
+    pub fn run(&self, base_dir: &Path) -> Result<ScenarioReport> {
+        ScenarioRunner::new(self, base_dir)?.run(&self.steps)
+    }
+}
+
+impl ScenarioReport {
+    pub fn to_yaml(&self) -> Result<String> {
+        Ok(serde_yaml::to_string(self)?)
+    }
+
+    /// Fails with all unmet expectations of the steps.
+    pub fn check_expectations(&self) -> Result<()> {
+        let mut message = String::new();
+        for step in &self.steps {
+            for unmet in &step.unmet_expectations {
+                writeln!(message, "{}: {unmet}", step.step)?;
+            }
+        }
+        ensure!(message.is_empty(), "Unmet expectations:\n{message}");
+        Ok(())
+    }
+
+    /// Returns a unified diff from `baseline` to the report, or `None` if they
+    /// are equal.
+    pub fn diff(&self, baseline: &str) -> Result<Option<String>> {
+        let report = self.to_yaml()?;
+        if report == baseline {
+            return Ok(None);
+        }
+        Ok(Some(
+            TextDiff::from_lines(baseline, &report)
+                .unified_diff()
+                .header("baseline", "report")
+                .to_string(),
+        ))
+    }
+}
+
+/// Runs the scenario file, checks the expectations of its steps and compares
+/// the report with the baseline next to it. The baseline is written instead
+/// if `UPDATE_BASELINE` is set.
+pub fn run_scenario_file(path: &Path) -> Result<ScenarioReport> {
+    let scenario = Scenario::from_file(path)?;
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let report = scenario.run(base_dir)?;
+    report.check_expectations()?;
+
+    let baseline_path = path.with_extension(BASELINE_EXTENSION);
+    if read_env_update_baseline() {
+        fs::write(&baseline_path, report.to_yaml()?)?;
+        return Ok(report);
+    }
+    let baseline = fs::read_to_string(&baseline_path).with_context(|| {
+        format!(
+            "Unable to read baseline {}, run with UPDATE_BASELINE=1 to create it",
+            baseline_path.display()
+        )
+    })?;
+    if let Some(diff) = report.diff(&baseline)? {
+        bail!(
+            "Outputs of {} changed, run with UPDATE_BASELINE=1 to accept them:\n{diff}",
+            path.display()
+        );
+    }
+    Ok(report)
+}
+
+struct ScenarioRunner {
+    sim: Simulacrum<StdRng>,
+    base_dir: PathBuf,
+    accounts: BTreeMap<String, (IotaAddress, AccountKeyPair)>,
+    packages: BTreeMap<String, ObjectID>,
+    objects: BTreeMap<String, ObjectID>,
+}
+
+impl ScenarioRunner {
+    fn new(scenario: &Scenario, base_dir: &Path) -> Result<Self> {
+        let mut sim = Simulacrum::new_with_rng(StdRng::seed_from_u64(scenario.seed));
+        let mut accounts = BTreeMap::new();
+        for (name, spec) in &scenario.accounts {
+            let (address, key) = get_key_pair_from_rng::<AccountKeyPair, _>(sim.rng());
+            let effects = sim.request_gas(address, spec.balance)?;
+            ensure!(
+                effects.status().is_ok(),
+                "Unable to fund account {name}: {:?}",
+                effects.status()
+            );
+            accounts.insert(name.clone(), (address, key));
+        }
+        if !accounts.is_empty() {
+            sim.create_checkpoint();
+        }
+        Ok(Self {
+            sim,
+            base_dir: base_dir.to_path_buf(),
+            accounts,
+            packages: BTreeMap::new(),
+            objects: BTreeMap::new(),
+        })
+    }
+
+    fn run(mut self, steps: &[Step]) -> Result<ScenarioReport> {
+        let mut report = ScenarioReport::default();
+        for (idx, step) in steps.iter().enumerate() {
+            let name = step
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("{idx}: {}", step.action.kind()));
+            let step_report = self
+                .run_step(name, step)
+                .with_context(|| format!("Step {idx} failed"))?;
+            report.steps.push(step_report);
+        }
+        Ok(report)
+    }
+
+    fn run_step(&mut self, name: String, step: &Step) -> Result<StepReport> {
+        let mut report = StepReport {
+            step: name,
+            transaction: None,
+            checkpoint: None,
+            epoch: None,
+            unmet_expectations: vec![],
+        };
+        let effects = match &step.action {
+            Action::Publish {
+                sender,
+                path,
+                name,
+                gas_budget,
+            } => {
+                let package = BuildConfig::new_for_testing().build(&self.base_dir.join(path))?;
+                let mut builder = ProgrammableTransactionBuilder::new();
+                let cap = builder.publish_upgradeable(
+                    package.get_package_bytes(false),
+                    package.get_dependency_storage_package_ids(),
+                );
+                let (address, _) = self.account(sender)?;
+                builder.transfer_arg(*address, cap);
+                let effects = self.execute(sender, builder, &BTreeSet::new(), *gas_budget)?;
+                if let Some(package_id) = effects
+                    .created()
+                    .into_iter()
+                    .map(|(object_ref, _)| object_ref.0)
+                    .find(|id| {
+                        self.get_object(id)
+                            .is_some_and(|object| object.is_package())
+                    })
+                {
+                    self.packages.insert(name.clone(), package_id);
+                }
+                Some(effects)
+            }
+            Action::Ptb {
+                sender,
+                commands,
+                gas_budget,
+            } => {
+                let mut builder = ProgrammableTransactionBuilder::new();
+                let mut inputs = BTreeSet::new();
+                for command in commands {
+                    self.add_command(&mut builder, &mut inputs, command)?;
+                }
+                Some(self.execute(sender, builder, &inputs, *gas_budget)?)
+            }
+            Action::TransferIota {
+                sender,
+                recipient,
+                amount,
+            } => {
+                let mut builder = ProgrammableTransactionBuilder::new();
+                builder.transfer_iota(self.address(recipient)?, *amount);
+                Some(self.execute(sender, builder, &BTreeSet::new(), None)?)
+            }
+            Action::AdvanceClock { ms } => {
+                self.sim.advance_clock(Duration::from_millis(*ms));
+                None
+            }
+            Action::AdvanceEpoch {} => {
+                self.sim.advance_epoch();
+                report.epoch = Some(self.sim.epoch_start_state().epoch());
+                None
+            }
+            Action::CreateCheckpoint {} => {
+                report.checkpoint = Some(self.sim.create_checkpoint().sequence_number);
+                None
+            }
+        };
+
+        if let Some(effects) = effects {
+            self.bind_objects(&effects, &step.bind)?;
+            let transaction = self.transaction_report(&effects);
+            if let Some(expectation) = &step.expect {
+                report.unmet_expectations = expectation.check(&transaction, &effects);
+            }
+            report.transaction = Some(transaction);
+        } else {
+            ensure!(
+                step.bind.is_empty() && step.expect.is_none(),
+                "Only transactions can bind objects and have expectations"
+            );
+        }
+        Ok(report)
+    }
+
+    /// Signs and executes the programmable transaction of `builder` with a
+    /// gas coin of the sender that is not among `inputs`.
+    fn execute(
+        &mut self,
+        sender: &str,
+        builder: ProgrammableTransactionBuilder,
+        inputs: &BTreeSet<ObjectID>,
+        gas_budget: Option<u64>,
+    ) -> Result<TransactionEffects> {
+        let (address, _) = self.account(sender)?;
+        let address = *address;
+        let gas = self
+            .sim
+            .store()
+            .owned_objects(address)
+            .filter(|object| object.is_gas_coin() && !inputs.contains(&object.id()))
+            .max_by_key(|object| (object.get_coin_value_unsafe(), object.id()))
+            .ok_or_else(|| anyhow!("Account {sender} has no gas coin"))?;
+        let gas_data = GasData {
+            payment: vec![gas.compute_object_reference()],
+            owner: address,
+            price: self.sim.reference_gas_price(),
+            budget: gas_budget.unwrap_or(DEFAULT_GAS_BUDGET),
+        };
+        let kind = TransactionKind::ProgrammableTransaction(builder.finish());
+        let tx_data = TransactionData::new_with_gas_data(kind, address, gas_data);
+        let (_, key) = self.account(sender)?;
+        let tx = Transaction::from_data_and_signer(tx_data, vec![key]);
+        Ok(self.sim.execute_transaction(tx)?.0)
+    }
+
+    fn add_command(
+        &self,
+        builder: &mut ProgrammableTransactionBuilder,
+        inputs: &mut BTreeSet<ObjectID>,
+        command: &PtbCommand,
+    ) -> Result<()> {
+        let mut arguments = |builder: &mut ProgrammableTransactionBuilder,
+                             args: &[PtbArgument]|
+         -> Result<Vec<Argument>> {
+            args.iter()
+                .map(|arg| self.argument(builder, inputs, arg))
+                .collect()
+        };
+        let command = match command {
+            PtbCommand::MoveCall {
+                package,
+                module,
+                function,
+                type_arguments,
+                arguments: args,
+            } => {
+                let type_arguments = type_arguments
+                    .iter()
+                    .map(|type_arg| self.type_tag(type_arg))
+                    .collect::<Result<_>>()?;
+                builder.programmable_move_call(
+                    self.package(package)?,
+                    Identifier::new(module.as_str())?,
+                    Identifier::new(function.as_str())?,
+                    type_arguments,
+                    arguments(builder, args)?,
+                );
+                return Ok(());
+            }
+            PtbCommand::TransferObjects { objects, recipient } => Command::TransferObjects(
+                arguments(builder, objects)?,
+                arguments(builder, std::slice::from_ref(recipient))?.remove(0),
+            ),
+            PtbCommand::SplitCoins { coin, amounts } => Command::SplitCoins(
+                arguments(builder, std::slice::from_ref(coin))?.remove(0),
+                arguments(builder, amounts)?,
+            ),
+            PtbCommand::MergeCoins { coin, coins } => Command::MergeCoins(
+                arguments(builder, std::slice::from_ref(coin))?.remove(0),
+                arguments(builder, coins)?,
+            ),
+        };
+        builder.command(command);
+        Ok(())
+    }
+
+    fn argument(
+        &self,
+        builder: &mut ProgrammableTransactionBuilder,
+        inputs: &mut BTreeSet<ObjectID>,
+        arg: &PtbArgument,
+    ) -> Result<Argument> {
+        Ok(match arg {
+            PtbArgument::Gas => Argument::GasCoin,
+            PtbArgument::Result(idx) => Argument::Result(*idx),
+            PtbArgument::NestedResult(idx, result_idx) => Argument::NestedResult(*idx, *result_idx),
+            PtbArgument::Object(name) => {
+                let id = self
+                    .objects
+                    .get(name)
+                    .ok_or_else(|| anyhow!("Unknown object {name}"))?;
+                let object = self
+                    .get_object(id)
+                    .ok_or_else(|| anyhow!("Object {name} ({id}) does not exist anymore"))?;
+                inputs.insert(*id);
+                builder.obj(match object.owner {
+                    Owner::Shared {
+                        initial_shared_version,
+                    } => ObjectArg::SharedObject {
+                        id: *id,
+                        initial_shared_version,
+                        mutable: true,
+                    },
+                    Owner::AddressOwner(_) | Owner::ObjectOwner(_) | Owner::Immutable => {
+                        ObjectArg::ImmOrOwnedObject(object.compute_object_reference())
+                    }
+                })?
+            }
+            PtbArgument::Address(name) => builder.pure(self.address(name)?)?,
+            PtbArgument::Bool(value) => builder.pure(value)?,
+            PtbArgument::U8(value) => builder.pure(value)?,
+            PtbArgument::U64(value) => builder.pure(value)?,
+            PtbArgument::String(value) => builder.pure(value)?,
+        })
+    }
+
+    /// Binds the objects created by a transaction to names by the
+    /// `module::Name` of their type.
+    fn bind_objects(
+        &mut self,
+        effects: &TransactionEffects,
+        bind: &BTreeMap<String, String>,
+    ) -> Result<()> {
+        let created = effects
+            .created()
+            .into_iter()
+            .filter_map(|(object_ref, _)| self.get_object(&object_ref.0))
+            .collect::<Vec<_>>();
+        for (name, type_name) in bind {
+            let mut matching = created.iter().filter(|object| {
+                object
+                    .struct_tag()
+                    .is_some_and(|tag| &format!("{}::{}", tag.module, tag.name) == type_name)
+            });
+            let (Some(object), None) = (matching.next(), matching.next()) else {
+                bail!("Expected exactly one created object of type {type_name} to bind to {name}");
+            };
+            self.objects.insert(name.clone(), object.id());
+        }
+        Ok(())
+    }
+
+    fn transaction_report(&self, effects: &TransactionEffects) -> TransactionReport {
+        let status = match effects.status() {
+            ExecutionStatus::Success => "success".to_string(),
+            ExecutionStatus::Failure { error, command } => match command {
+                Some(command) => format!("failure: {error:?} in command {command}"),
+                None => format!("failure: {error:?}"),
+            },
+        };
+        let gas_cost_summary = effects.gas_cost_summary();
+        let mut created = effects
+            .created()
+            .into_iter()
+            .filter_map(|(object_ref, _)| self.get_object(&object_ref.0))
+            .map(|object| ObjectReport {
+                type_: match object.struct_tag() {
+                    Some(tag) => self.display_struct_tag(&tag),
+                    None => "package".to_string(),
+                },
+                id: object.id(),
+            })
+            .collect::<Vec<_>>();
+        created.sort();
+        let events = self
+            .sim
+            .store()
+            .get_transaction_events_by_tx_digest(effects.transaction_digest())
+            .map(|events| events.data)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|event| EventReport {
+                type_: self.display_struct_tag(&event.type_),
+                contents: Hex::encode(event.contents),
+            })
+            .collect();
+        TransactionReport {
+            status,
+            computation_cost: gas_cost_summary.computation_cost,
+            storage_cost: gas_cost_summary.storage_cost,
+            storage_rebate: gas_cost_summary.storage_rebate,
+            created,
+            mutated: effects.mutated().len(),
+            deleted: effects.deleted().len(),
+            events,
+        }
+    }
+
+    /// Displays a type with the names of the published packages in place of
+    /// their IDs.
+    fn display_struct_tag(&self, tag: &StructTag) -> String {
+        let mut display = tag.to_canonical_string(true);
+        for (name, id) in &self.packages {
+            display = display.replace(&id.to_string(), name);
+        }
+        display
+    }
+
+    fn type_tag(&self, type_arg: &str) -> Result<TypeTag> {
+        let resolved = match type_arg.split_once("::") {
+            Some((package, rest)) if self.packages.contains_key(package) => {
+                format!("{}::{rest}", self.packages[package])
+            }
+            _ => type_arg.to_string(),
+        };
+        parse_iota_type_tag(&resolved)
+    }
+
+    fn package(&self, name: &str) -> Result<ObjectID> {
+        match self.packages.get(name) {
+            Some(id) => Ok(*id),
+            None => ObjectID::from_str(name).map_err(|_| anyhow!("Unknown package {name}")),
+        }
+    }
+
+    fn account(&self, name: &str) -> Result<&(IotaAddress, AccountKeyPair)> {
+        self.accounts
+            .get(name)
+            .ok_or_else(|| anyhow!("Unknown account {name}"))
+    }
+
+    fn address(&self, name: &str) -> Result<IotaAddress> {
+        match self.accounts.get(name) {
+            Some((address, _)) => Ok(*address),
+            None => IotaAddress::from_str(name).map_err(|_| anyhow!("Unknown account {name}")),
+        }
+    }
+
+    fn get_object(&self, id: &ObjectID) -> Option<Object> {
+        SimulatorStore::get_object(self.sim.store(), id)
+    }
+}
+
+impl Action {
+    fn kind(&self) -> &'static str {
+        match self {
+            Action::Publish { .. } => "publish",
+            Action::Ptb { .. } => "ptb",
+            Action::TransferIota { .. } => "transfer-iota",
+            Action::AdvanceClock { .. } => "advance-clock",
+            Action::AdvanceEpoch {} => "advance-epoch",
+            Action::CreateCheckpoint {} => "create-checkpoint",
+        }
+    }
+}
+
+impl Expectation {
+    fn check(&self, report: &TransactionReport, effects: &TransactionEffects) -> Vec<String> {
+        let mut unmet = vec![];
+        if let Some(status) = self.status {
+            let actual = if effects.status().is_ok() {
+                ExpectedStatus::Success
+            } else {
+                ExpectedStatus::Failure
+            };
+            if actual != status {
+                unmet.push(format!("expected {status:?}, got {}", report.status));
+            }
+        }
+        if let Some(created) = self.created {
+            if report.created.len() != created {
+                unmet.push(format!(
+                    "expected {created} created objects, got {}",
+                    report.created.len()
+                ));
+            }
+        }
+        if let Some(events) = &self.events {
+            let actual = report
+                .events
+                .iter()
+                .map(|event| {
+                    // `address::module::Name<...>` to `module::Name`
+                    let path = event.type_.split('<').next().unwrap_or_default();
+                    path.split_once("::")
+                        .map_or(path, |(_, rest)| rest)
+                        .to_string()
+                })
+                .collect::<Vec<_>>();
+            if &actual != events {
+                unmet.push(format!("expected events {events:?}, got {actual:?}"));
+            }
+        }
+        unmet
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCENARIO: &str = r#"
+seed: 7
+accounts:
+  alice: {}
+  bob:
+    balance: 10000000000
+steps:
+  - transfer-iota:
+      sender: alice
+      recipient: bob
+      amount: 1000
+    expect:
+      status: success
+      created: 1
+  - ptb:
+      sender: bob
+      commands:
+        - split-coins:
+            coin: gas
+            amounts:
+              - u64: 10
+              - u64: 20
+        - transfer-objects:
+            objects:
+              - nested-result: [0, 0]
+              - nested-result: [0, 1]
+            recipient:
+              address: alice
+    bind:
+      small_coin: coin::Coin
+  - advance-clock:
+      ms: 1000
+  - create-checkpoint: {}
+  - advance-epoch: {}
+"#;
+
+    fn without_binding() -> String {
+        SCENARIO.replace("    bind:\n      small_coin: coin::Coin\n", "")
+    }
+
+    #[test]
+    fn scenario_is_deterministic() {
+        let scenario = Scenario::from_yaml(&without_binding()).unwrap();
+        let report = scenario.run(Path::new(".")).unwrap();
+        report.check_expectations().unwrap();
+        assert_eq!(report.steps.len(), 5);
+        assert_eq!(
+            report.steps[1].transaction.as_ref().unwrap().created.len(),
+            2
+        );
+        assert_eq!(report.steps[3].checkpoint, Some(2));
+        assert_eq!(report.steps[4].epoch, Some(1));
+
+        let baseline = report.to_yaml().unwrap();
+        let rerun = scenario.run(Path::new(".")).unwrap();
+        assert_eq!(rerun.diff(&baseline).unwrap(), None);
+
+        let changed =
+            Scenario::from_yaml(&without_binding().replace("amount: 1000", "amount: 1001"))
+                .unwrap();
+        let diff = changed
+            .run(Path::new("."))
+            .unwrap()
+            .diff(&baseline)
+            .unwrap()
+            .expect("outputs changed");
+        assert!(diff.contains("--- baseline"));
+    }
+
+    #[test]
+    fn toml_scenario_matches_yaml() {
+        let toml = r#"
+seed = 7
+
+[accounts]
+alice = {}
+bob = { balance = 10000000000 }
+
+[[steps]]
+transfer-iota = { sender = "alice", recipient = "bob", amount = 1000 }
+expect = { status = "success", created = 1 }
+
+[[steps]]
+[steps.ptb]
+sender = "bob"
+commands = [
+    { split-coins = { coin = "gas", amounts = [{ u64 = 10 }, { u64 = 20 }] } },
+    { transfer-objects = { objects = [{ nested-result = [0, 0] }, { nested-result = [0, 1] }], recipient = { address = "alice" } } },
+]
+
+[[steps]]
+advance-clock = { ms = 1000 }
+
+[[steps]]
+create-checkpoint = {}
+
+[[steps]]
+advance-epoch = {}
+"#;
+        let report = Scenario::from_toml(toml)
+            .unwrap()
+            .run(Path::new("."))
+            .unwrap();
+        let baseline = Scenario::from_yaml(&without_binding())
+            .unwrap()
+            .run(Path::new("."))
+            .unwrap();
+        assert_eq!(report, baseline);
+    }
+
+    #[test]
+    fn ambiguous_binding_is_rejected() {
+        // Both split coins are of the same type, so the binding is ambiguous
+        let err = Scenario::from_yaml(SCENARIO)
+            .unwrap()
+            .run(Path::new("."))
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("small_coin"));
+    }
+
+    #[test]
+    fn unmet_expectations_are_reported() {
+        let scenario =
+            Scenario::from_yaml(&without_binding().replace("created: 1", "created: 2")).unwrap();
+        let err = scenario
+            .run(Path::new("."))
+            .unwrap()
+            .check_expectations()
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("expected 2 created objects, got 1")
+        );
+    }
+
+    const COUNTER_SCENARIO: &str = r#"
+seed: 3
+accounts:
+  alice: {}
+  bob: {}
+steps:
+  - publish:
+      sender: alice
+      path: counter
+      name: counter
+    expect:
+      status: success
+      created: 2
+  - ptb:
+      sender: alice
+      commands:
+        - move-call:
+            package: counter
+            module: counter
+            function: create
+    bind:
+      my_counter: counter::Counter
+    expect:
+      created: 1
+  - ptb:
+      sender: bob
+      commands:
+        - move-call:
+            package: counter
+            module: counter
+            function: increment
+            arguments:
+              - object: my_counter
+        - move-call:
+            package: counter
+            module: counter
+            function: increment
+            arguments:
+              - object: my_counter
+    expect:
+      status: success
+      events: [counter::Incremented, counter::Incremented]
+  - ptb:
+      sender: bob
+      commands:
+        - move-call:
+            package: counter
+            module: counter
+            function: set
+            arguments:
+              - object: my_counter
+              - u64: 100
+    expect:
+      status: failure
+      events: []
+"#;
+
+    fn run_counter_scenario(yaml: &str) -> ScenarioReport {
+        let base_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data");
+        Scenario::from_yaml(yaml).unwrap().run(&base_dir).unwrap()
+    }
+
+    #[test]
+    fn packages_are_published_and_called() {
+        let report = run_counter_scenario(COUNTER_SCENARIO);
+        report.check_expectations().unwrap();
+
+        // The package and the objects of its types are displayed by name
+        let publish = report.steps[0].transaction.as_ref().unwrap();
+        assert!(publish.created.iter().any(|object| object.type_ == "package"));
+        let create = report.steps[1].transaction.as_ref().unwrap();
+        assert_eq!(create.created[0].type_, "counter::counter::Counter");
+        let increment = report.steps[2].transaction.as_ref().unwrap();
+        assert_eq!(increment.events.len(), 2);
+        assert_eq!(increment.events[0].type_, "counter::counter::Incremented");
+        assert_ne!(increment.events[0].contents, increment.events[1].contents);
+
+        let rerun = run_counter_scenario(COUNTER_SCENARIO);
+        assert_eq!(rerun, report);
+    }
+
+    #[test]
+    fn unmet_event_expectations_are_reported() {
+        let report = run_counter_scenario(&COUNTER_SCENARIO.replace(
+            "events: [counter::Incremented, counter::Incremented]",
+            "events: [counter::Incremented]",
+        ));
+        assert_eq!(report.steps[2].unmet_expectations.len(), 1);
+        let err = report.check_expectations().unwrap_err().to_string();
+        assert!(err.contains(
+            r#"expected events ["counter::Incremented"], got ["counter::Incremented", "counter::Incremented"]"#
+        ));
+    }
+
+    #[test]
+    fn unmet_status_expectations_are_reported() {
+        let report = run_counter_scenario(&COUNTER_SCENARIO.replace("u64: 100", "u64: 99"));
+        let err = report.check_expectations().unwrap_err().to_string();
+        assert!(err.contains("expected Failure, got success"));
+    }
+}
@@ -0,0 +1,31 @@
+// Copyright (c) 2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+module counter::counter {
+    use iota::event;
+
+    const ETooLarge: u64 = 0;
+
+    public struct Counter has key {
+        id: UID,
+        value: u64,
+    }
+
+    public struct Incremented has copy, drop {
+        value: u64,
+    }
+
+    public fun create(ctx: &mut TxContext) {
+        transfer::share_object(Counter { id: object::new(ctx), value: 0 })
+    }
+
+    public fun increment(counter: &mut Counter) {
+        counter.value = counter.value + 1;
+        event::emit(Incremented { value: counter.value })
+    }
+
+    public fun set(counter: &mut Counter, value: u64) {
+        assert!(value < 100, ETooLarge);
+        counter.value = value;
+    }
+}
@@ -0,0 +1,17 @@
+// Copyright (c) 2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::Path;
+
+use simulacrum::scenario::run_scenario_file;
+
+pub const TEST_DIR: &str = "tests/scenarios";
+
+datatest_stable::harness!(run_scenario, TEST_DIR, r".*\.(yaml|toml)$");
+
+/// Runs the scenario and compares its report with the committed baseline,
+/// which is rewritten instead if `UPDATE_BASELINE` is set.
+fn run_scenario(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    run_scenario_file(path)?;
+    Ok(())
+}
@@ -504,11 +504,14 @@ pub fn derive_dbmap_utils_general(input: TokenStream) -> TokenStream {
                     #(
                         stringify!(#field_names) => {
                             typed_store::traits::Map::try_catch_up_with_primary(&self.#field_names)?;
-                            typed_store::traits::Map::unbounded_iter(&self.#field_names)
+                            let snapshot = self.#field_names.snapshot();
+                            let table = snapshot.map(&self.#field_names)?;
+                            let page = typed_store::traits::Map::unbounded_iter(&table)
                                 .skip((page_number * (page_size) as usize))
                                 .take(page_size as usize)
                                 .map(|(k, v)| (format!("{:?}", k), format!("{:?}", v)))
-                                .collect::<std::collections::BTreeMap<_, _>>()
+                                .collect::<std::collections::BTreeMap<_, _>>();
+                            page
                         }
                     )*
 
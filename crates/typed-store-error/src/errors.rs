@@ -20,4 +20,8 @@ pub enum TypedStoreError {
     MetricsReporting,
     #[error("Transaction should be retried")]
     RetryableTransaction,
+    #[error("a snapshot can't be read from another database")]
+    CrossDBSnapshot,
+    #[error("a snapshot is read-only")]
+    ReadOnlySnapshot,
 }
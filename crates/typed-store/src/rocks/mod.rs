@@ -6,6 +6,7 @@ pub mod errors;
 pub(crate) mod iter;
 pub(crate) mod keys;
 pub(crate) mod safe_iter;
+pub mod snapshot;
 pub mod util;
 pub(crate) mod values;
 
@@ -38,7 +39,7 @@ use tap::TapFallible;
 use tokio::sync::oneshot;
 use tracing::{debug, error, info, instrument, warn};
 
-use self::{iter::Iter, keys::Keys, values::Values};
+use self::{iter::Iter, keys::Keys, snapshot::Snapshot, values::Values};
 use crate::{
     TypedStoreError,
     metrics::{DBMetrics, RocksDBPerfContext, SamplingInterval},
@@ -1182,7 +1183,16 @@ impl<K, V> DBMap<K, V> {
         self.rocksdb.checkpoint(path)
     }
 
-    pub fn snapshot(&self) -> Result<RocksDBSnapshot<'_>, TypedStoreError> {
+    /// Takes a consistent, read-only snapshot across all column families of
+    /// the database backing this map. Any map opened on the same database can
+    /// then be read at the snapshot with [`Snapshot::map`].
+    pub fn snapshot(&self) -> Snapshot<'_> {
+        Snapshot::new(&self.rocksdb)
+    }
+
+    /// Takes an untyped RocksDB snapshot of the database backing this map.
+    /// Prefer [`DBMap::snapshot`], which can be read with the `Map` trait.
+    pub fn rocksdb_snapshot(&self) -> Result<RocksDBSnapshot<'_>, TypedStoreError> {
         Ok(self.rocksdb.snapshot())
     }
 
@@ -2249,7 +2259,7 @@ where
             .into_iter()
             .map(|k| (&cf, be_fix_int_ser(k.borrow()).unwrap()));
         let chunked_keys = keys_bytes.into_iter().chunks(chunk_size);
-        let snapshot = self.snapshot()?;
+        let snapshot = self.rocksdb_snapshot()?;
         let mut results = vec![];
         for chunk in chunked_keys.into_iter() {
             let chunk_result = snapshot.multi_get_cf(chunk);
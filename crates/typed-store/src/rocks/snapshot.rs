@@ -0,0 +1,355 @@
+// Copyright (c) 2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{borrow::Borrow, ops::RangeBounds, sync::Arc};
+
+use rocksdb::ReadOptions;
+use serde::{Serialize, de::DeserializeOwned};
+
+use super::{DBMap, RocksDB, RocksDBRawIter, RocksDBSnapshot, be_fix_int_ser};
+use crate::{
+    TypedStoreError,
+    rocks::{
+        errors::{typed_store_err_from_bcs_err, typed_store_err_from_rocks_err},
+        iter::Iter,
+        keys::Keys,
+        safe_iter::SafeIter,
+        values::Values,
+    },
+    traits::Map,
+};
+
+/// A consistent, read-only point-in-time view of a RocksDB database.
+///
+/// The snapshot covers all column families of the database, so every `DBMap`
+/// opened on it, e.g. all the tables of a `DBMapUtils` struct, can be read at
+/// the same point in time through [`Snapshot::map`], regardless of the writes
+/// that happen after the snapshot was taken.
+///
+/// RocksDB keeps the data visible to the snapshot from being compacted away
+/// until it is dropped, so snapshots should not be held longer than needed.
+///
+/// ```
+/// use typed_store::{Map, rocks::*};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let rocks = open_cf(
+///         tempfile::tempdir().unwrap(),
+///         None,
+///         MetricConf::default(),
+///         &["First_CF", "Second_CF"],
+///     )
+///     .unwrap();
+///     let db_cf_1 = DBMap::<u32, u32>::reopen(
+///         &rocks,
+///         Some("First_CF"),
+///         &ReadWriteOptions::default(),
+///         false,
+///     )
+///     .unwrap();
+///     let db_cf_2 = DBMap::<u32, u32>::reopen(
+///         &rocks,
+///         Some("Second_CF"),
+///         &ReadWriteOptions::default(),
+///         false,
+///     )
+///     .unwrap();
+///     db_cf_1.insert(&1, &1).unwrap();
+///
+///     let snapshot = db_cf_1.snapshot();
+///     db_cf_1.insert(&2, &2).unwrap();
+///     db_cf_2.insert(&1, &1).unwrap();
+///
+///     // Writes made after the snapshot was taken are not visible through it.
+///     let snapshot_cf_1 = snapshot.map(&db_cf_1).unwrap();
+///     let snapshot_cf_2 = snapshot.map(&db_cf_2).unwrap();
+///     assert_eq!(snapshot_cf_1.safe_iter().count(), 1);
+///     assert!(snapshot_cf_2.is_empty());
+/// }
+/// ```
+pub struct Snapshot<'a> {
+    rocksdb: &'a Arc<RocksDB>,
+    snapshot: RocksDBSnapshot<'a>,
+}
+
+impl<'a> Snapshot<'a> {
+    pub fn new(rocksdb: &'a Arc<RocksDB>) -> Self {
+        Self {
+            rocksdb,
+            snapshot: rocksdb.snapshot(),
+        }
+    }
+
+    /// Returns a typed view of `map` at this snapshot. The map must have been
+    /// opened on the database the snapshot was taken from.
+    pub fn map<'s, K, V>(
+        &'s self,
+        map: &'s DBMap<K, V>,
+    ) -> Result<SnapshotMap<'s, K, V>, TypedStoreError> {
+        if !Arc::ptr_eq(self.rocksdb, &map.rocksdb) {
+            return Err(TypedStoreError::CrossDBSnapshot);
+        }
+        Ok(SnapshotMap {
+            map,
+            snapshot: self,
+        })
+    }
+
+    // Pins the given read options to this snapshot.
+    fn pin(&self, mut readopts: ReadOptions) -> ReadOptions {
+        match &self.snapshot {
+            RocksDBSnapshot::DBWithThreadMode(s) => readopts.set_snapshot(s),
+            RocksDBSnapshot::OptimisticTransactionDB(s) => readopts.set_snapshot(s),
+        }
+        readopts
+    }
+}
+
+/// A read-only view of a `DBMap` at a [`Snapshot`].
+///
+/// All reads observe the state of the database at the time the snapshot was
+/// taken. Writes are rejected with [`TypedStoreError::ReadOnlySnapshot`].
+pub struct SnapshotMap<'a, K, V> {
+    map: &'a DBMap<K, V>,
+    snapshot: &'a Snapshot<'a>,
+}
+
+impl<K, V> SnapshotMap<'_, K, V> {
+    fn readopts(&self) -> ReadOptions {
+        self.snapshot.pin(self.map.opts.readopts())
+    }
+
+    fn raw_iter(&self, readopts: ReadOptions) -> RocksDBRawIter<'_> {
+        self.map
+            .rocksdb
+            .raw_iterator_cf(&self.map.cf(), self.snapshot.pin(readopts))
+    }
+
+    fn get_pinned(&self, key: &K) -> Result<Option<Vec<u8>>, TypedStoreError>
+    where
+        K: Serialize,
+    {
+        let key_buf = be_fix_int_ser(key)?;
+        let res = self
+            .map
+            .rocksdb
+            .get_pinned_cf_opt(&self.map.cf(), &key_buf, &self.readopts())
+            .map_err(typed_store_err_from_rocks_err)?;
+        Ok(res.map(|data| data.to_vec()))
+    }
+
+    fn iter(&self, readopts: ReadOptions) -> Iter<'_, K, V>
+    where
+        K: DeserializeOwned,
+        V: DeserializeOwned,
+    {
+        let (_timer, bytes_scanned, keys_scanned, _perf_ctx) = self.map.create_iter_context();
+        Iter::new(
+            self.map.cf.clone(),
+            self.raw_iter(readopts),
+            _timer,
+            _perf_ctx,
+            bytes_scanned,
+            keys_scanned,
+            Some(self.map.db_metrics.clone()),
+        )
+    }
+
+    fn safe_iter_with_readopts(&self, readopts: ReadOptions) -> SafeIter<'_, K, V>
+    where
+        K: DeserializeOwned,
+        V: DeserializeOwned,
+    {
+        let (_timer, bytes_scanned, keys_scanned, _perf_ctx) = self.map.create_iter_context();
+        SafeIter::new(
+            self.map.cf.clone(),
+            self.raw_iter(readopts),
+            _timer,
+            _perf_ctx,
+            bytes_scanned,
+            keys_scanned,
+            Some(self.map.db_metrics.clone()),
+        )
+    }
+}
+
+impl<'a, K, V> Map<'a, K, V> for SnapshotMap<'_, K, V>
+where
+    K: Serialize + DeserializeOwned,
+    V: Serialize + DeserializeOwned,
+{
+    type Error = TypedStoreError;
+    type Iterator = Iter<'a, K, V>;
+    type SafeIterator = SafeIter<'a, K, V>;
+    type Keys = Keys<'a, K>;
+    type Values = Values<'a, V>;
+
+    fn contains_key(&self, key: &K) -> Result<bool, TypedStoreError> {
+        Ok(self.get_pinned(key)?.is_some())
+    }
+
+    fn get(&self, key: &K) -> Result<Option<V>, TypedStoreError> {
+        self.get_pinned(key)?
+            .map(|data| bcs::from_bytes(&data).map_err(typed_store_err_from_bcs_err))
+            .transpose()
+    }
+
+    fn get_raw_bytes(&self, key: &K) -> Result<Option<Vec<u8>>, TypedStoreError> {
+        self.get_pinned(key)
+    }
+
+    fn insert(&self, _key: &K, _value: &V) -> Result<(), TypedStoreError> {
+        Err(TypedStoreError::ReadOnlySnapshot)
+    }
+
+    fn remove(&self, _key: &K) -> Result<(), TypedStoreError> {
+        Err(TypedStoreError::ReadOnlySnapshot)
+    }
+
+    fn unsafe_clear(&self) -> Result<(), TypedStoreError> {
+        Err(TypedStoreError::ReadOnlySnapshot)
+    }
+
+    fn schedule_delete_all(&self) -> Result<(), TypedStoreError> {
+        Err(TypedStoreError::ReadOnlySnapshot)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.safe_iter().next().is_none()
+    }
+
+    fn unbounded_iter(&'a self) -> Self::Iterator {
+        self.iter(self.map.opts.readopts())
+    }
+
+    fn iter_with_bounds(
+        &'a self,
+        lower_bound: Option<K>,
+        upper_bound: Option<K>,
+    ) -> Self::Iterator {
+        self.iter(
+            self.map
+                .create_read_options_with_bounds(lower_bound, upper_bound),
+        )
+    }
+
+    fn range_iter(&'a self, range: impl RangeBounds<K>) -> Self::Iterator {
+        self.iter(self.map.create_read_options_with_range(range))
+    }
+
+    fn safe_iter(&'a self) -> Self::SafeIterator {
+        self.safe_iter_with_readopts(self.map.opts.readopts())
+    }
+
+    fn safe_iter_with_bounds(
+        &'a self,
+        lower_bound: Option<K>,
+        upper_bound: Option<K>,
+    ) -> Self::SafeIterator {
+        self.safe_iter_with_readopts(
+            self.map
+                .create_read_options_with_bounds(lower_bound, upper_bound),
+        )
+    }
+
+    fn safe_range_iter(&'a self, range: impl RangeBounds<K>) -> Self::SafeIterator {
+        self.safe_iter_with_readopts(self.map.create_read_options_with_range(range))
+    }
+
+    fn keys(&'a self) -> Self::Keys {
+        let mut db_iter = self.raw_iter(self.map.opts.readopts());
+        db_iter.seek_to_first();
+
+        Keys::new(db_iter)
+    }
+
+    fn values(&'a self) -> Self::Values {
+        let mut db_iter = self.raw_iter(self.map.opts.readopts());
+        db_iter.seek_to_first();
+
+        Values::new(db_iter)
+    }
+
+    fn multi_get_raw_bytes<J>(
+        &self,
+        keys: impl IntoIterator<Item = J>,
+    ) -> Result<Vec<Option<Vec<u8>>>, TypedStoreError>
+    where
+        J: Borrow<K>,
+    {
+        let keys_bytes = keys
+            .into_iter()
+            .map(|k| be_fix_int_ser(k.borrow()))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.map
+            .rocksdb
+            .batched_multi_get_cf_opt(
+                &self.map.cf(),
+                keys_bytes,
+                // sorted_keys=
+                false,
+                &self.readopts(),
+            )
+            .into_iter()
+            .map(|r| {
+                r.map(|value| value.map(|v| v.to_vec()))
+                    .map_err(typed_store_err_from_rocks_err)
+            })
+            .collect()
+    }
+
+    fn multi_get<J>(
+        &self,
+        keys: impl IntoIterator<Item = J>,
+    ) -> Result<Vec<Option<V>>, TypedStoreError>
+    where
+        J: Borrow<K>,
+    {
+        self.multi_get_raw_bytes(keys)?
+            .into_iter()
+            .map(|value| {
+                value
+                    .map(|data| bcs::from_bytes(&data).map_err(typed_store_err_from_bcs_err))
+                    .transpose()
+            })
+            .collect()
+    }
+
+    fn chunked_multi_get<J>(
+        &self,
+        keys: impl IntoIterator<Item = J>,
+        _chunk_size: usize,
+    ) -> Result<Vec<Option<V>>, TypedStoreError>
+    where
+        J: Borrow<K>,
+    {
+        // All reads already go through the same snapshot, so there is no need to
+        // split the keys into chunks.
+        self.multi_get(keys)
+    }
+
+    fn multi_insert<J, U>(
+        &self,
+        _key_val_pairs: impl IntoIterator<Item = (J, U)>,
+    ) -> Result<(), Self::Error>
+    where
+        J: Borrow<K>,
+        U: Borrow<V>,
+    {
+        Err(TypedStoreError::ReadOnlySnapshot)
+    }
+
+    fn multi_remove<J>(&self, _keys: impl IntoIterator<Item = J>) -> Result<(), Self::Error>
+    where
+        J: Borrow<K>,
+    {
+        Err(TypedStoreError::ReadOnlySnapshot)
+    }
+
+    /// A snapshot is a fixed view of the database, so there is nothing to catch
+    /// up with.
+    fn try_catch_up_with_primary(&self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
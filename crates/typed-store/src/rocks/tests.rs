@@ -1116,6 +1116,104 @@ async fn test_multi_remove(#[values(true, false)] is_transactional: bool) {
     }
 }
 
+#[rstest]
+#[tokio::test]
+async fn test_snapshot(#[values(true, false)] is_transactional: bool) {
+    let rocks = open_rocksdb(temp_dir(), &["First_CF", "Second_CF"], is_transactional);
+    let db_cf_1: DBMap<i32, String> = DBMap::reopen(
+        &rocks,
+        Some("First_CF"),
+        &ReadWriteOptions::default(),
+        false,
+    )
+    .expect("Failed to open storage");
+    let db_cf_2: DBMap<i32, String> = DBMap::reopen(
+        &rocks,
+        Some("Second_CF"),
+        &ReadWriteOptions::default(),
+        false,
+    )
+    .expect("Failed to open storage");
+
+    let keys_vals = (0..100).map(|i| (i, i.to_string()));
+    db_cf_1
+        .multi_insert(keys_vals.clone())
+        .expect("Failed to multi-insert");
+    db_cf_2
+        .multi_insert(keys_vals.clone())
+        .expect("Failed to multi-insert");
+
+    let snapshot = db_cf_1.snapshot();
+
+    // Mutate both column families after taking the snapshot
+    db_cf_1
+        .multi_insert((100..200).map(|i| (i, i.to_string())))
+        .expect("Failed to multi-insert");
+    db_cf_1.multi_remove(0..50).expect("Failed to multi-remove");
+    db_cf_2
+        .multi_remove(0..100)
+        .expect("Failed to multi-remove");
+    db_cf_2
+        .insert(&0, &"new".to_string())
+        .expect("Failed to insert");
+
+    // The snapshot still sees the state at the time it was taken
+    let snapshot_cf_1 = snapshot.map(&db_cf_1).expect("Failed to read snapshot");
+    let snapshot_cf_2 = snapshot.map(&db_cf_2).expect("Failed to read snapshot");
+    for snapshot_cf in [&snapshot_cf_1, &snapshot_cf_2] {
+        assert_eq!(
+            snapshot_cf
+                .safe_iter()
+                .collect::<Result<Vec<_>, _>>()
+                .expect("Failed to iterate"),
+            keys_vals.clone().collect::<Vec<_>>()
+        );
+        assert_eq!(snapshot_cf.get(&0).unwrap(), Some("0".to_string()));
+        assert!(snapshot_cf.contains_key(&99).unwrap());
+        assert!(!snapshot_cf.contains_key(&100).unwrap());
+        assert_eq!(snapshot_cf.multi_get([0, 99, 150]).unwrap(), vec![
+            Some("0".to_string()),
+            Some("99".to_string()),
+            None
+        ]);
+        assert_eq!(
+            snapshot_cf
+                .safe_iter_with_bounds(Some(10), Some(20))
+                .count(),
+            10
+        );
+        assert_eq!(snapshot_cf.range_iter(90..).count(), 10);
+        assert_eq!(snapshot_cf.keys().count(), 100);
+        assert_eq!(snapshot_cf.values().count(), 100);
+        assert!(!snapshot_cf.is_empty());
+    }
+
+    // The live maps see the new state
+    assert_eq!(db_cf_1.safe_iter().count(), 150);
+    assert_eq!(db_cf_2.safe_iter().count(), 1);
+
+    // Snapshots are read-only
+    assert_eq!(
+        snapshot_cf_1.insert(&0, &"0".to_string()),
+        Err(TypedStoreError::ReadOnlySnapshot)
+    );
+    assert_eq!(
+        snapshot_cf_1.remove(&0),
+        Err(TypedStoreError::ReadOnlySnapshot)
+    );
+    assert_eq!(
+        snapshot_cf_1.multi_insert([(0, "0".to_string())]),
+        Err(TypedStoreError::ReadOnlySnapshot)
+    );
+
+    // Maps of other databases can't be read at the snapshot
+    let other_db: DBMap<i32, String> = open_map(temp_dir(), Some("First_CF"), is_transactional);
+    assert!(matches!(
+        snapshot.map(&other_db),
+        Err(TypedStoreError::CrossDBSnapshot)
+    ));
+}
+
 #[tokio::test]
 async fn test_transactional() {
     let key = "key";
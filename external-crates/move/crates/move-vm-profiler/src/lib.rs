@@ -64,7 +64,36 @@ pub struct GasProfiler {
     finished: bool,
 }
 
-#[cfg(feature = "gas-profiler")]
+/// Prefix of the names of the frames opened for the commands of a
+/// programmable transaction, to tell them apart from Move function frames.
+pub const PTB_COMMAND_FRAME_PREFIX: &str = "PTB command ";
+
+/// Gas used by a frame, summed over all its invocations.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrameGasUsage {
+    pub name: String,
+    pub calls: u64,
+    /// Gas used by the frame including the frames it opened. Recursive
+    /// invocations are only counted once.
+    pub total_gas: u64,
+    /// Gas used by the frame itself.
+    pub self_gas: u64,
+}
+
+/// Gas usage of a profiled transaction broken down per PTB command and per
+/// Move function.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasProfileSummary {
+    pub name: String,
+    pub total_gas: u64,
+    /// PTB commands in execution order.
+    pub commands: Vec<FrameGasUsage>,
+    /// Move functions, most expensive (by self gas) first.
+    pub functions: Vec<FrameGasUsage>,
+}
+
 impl GasProfiler {
     // Used by profiler viz tool
     const OPEN_FRAME_IDENT: &'static str = "O";
@@ -72,6 +101,100 @@ impl GasProfiler {
 
     const TOP_LEVEL_FRAME_NAME: &'static str = "root";
 
+    /// Replays the recorded events and calls `on_close` for every frame with
+    /// the stack of frame indices leading to it (the frame itself included),
+    /// the total gas and the self gas of the frame. Frames which were not
+    /// closed yet are closed at the last recorded gas value.
+    fn walk_frames(&self, mut on_close: impl FnMut(&[u64], u64, u64)) {
+        let Some(profile) = self.profiles.first() else {
+            return;
+        };
+        // (frame index, gas used when opened, gas used by nested frames)
+        let mut stack: Vec<(u64, u64, u64)> = vec![];
+        let mut close = |stack: &mut Vec<(u64, u64, u64)>, at: u64| {
+            let path = stack.iter().map(|(frame, ..)| *frame).collect::<Vec<_>>();
+            let Some((_, opened_at, nested_gas)) = stack.pop() else {
+                return;
+            };
+            let total_gas = at.saturating_sub(opened_at);
+            on_close(&path, total_gas, total_gas.saturating_sub(nested_gas));
+            if let Some(parent) = stack.last_mut() {
+                parent.2 += total_gas;
+            }
+        };
+        for event in &profile.events {
+            if event.ty == Self::OPEN_FRAME_IDENT {
+                stack.push((event.frame, event.at, 0));
+            } else if event.ty == Self::CLOSE_FRAME_IDENT {
+                close(&mut stack, event.at);
+            }
+        }
+        while !stack.is_empty() {
+            close(&mut stack, profile.end_value);
+        }
+    }
+
+    /// Returns the self gas of every stack of frames, in the folded stack
+    /// format used by flamegraph tools: `root;frame;nested_frame`.
+    pub fn folded_stacks(&self) -> BTreeMap<String, u64> {
+        let mut stacks = BTreeMap::new();
+        self.walk_frames(|path, _, self_gas| {
+            if self_gas == 0 {
+                return;
+            }
+            let stack = path
+                .iter()
+                .map(|frame| self.shared.frames[*frame as usize].name.as_str())
+                .collect::<Vec<_>>()
+                .join(";");
+            *stacks.entry(stack).or_default() += self_gas;
+        });
+        stacks
+    }
+
+    /// Summarizes the gas usage per PTB command and per Move function.
+    pub fn summary(&self) -> GasProfileSummary {
+        let mut commands: Vec<FrameGasUsage> = vec![];
+        let mut functions: BTreeMap<&str, FrameGasUsage> = BTreeMap::new();
+        self.walk_frames(|path, total_gas, self_gas| {
+            let Some((frame, callers)) = path.split_last() else {
+                return;
+            };
+            let FrameName { name, file } = &self.shared.frames[*frame as usize];
+            if file.starts_with(PTB_COMMAND_FRAME_PREFIX) {
+                commands.push(FrameGasUsage {
+                    name: name.clone(),
+                    calls: 1,
+                    total_gas,
+                    self_gas,
+                });
+            } else if file != Self::TOP_LEVEL_FRAME_NAME {
+                let usage = functions
+                    .entry(file.as_str())
+                    .or_insert_with(|| FrameGasUsage {
+                        name: file.clone(),
+                        ..Default::default()
+                    });
+                usage.calls += 1;
+                usage.self_gas += self_gas;
+                if !callers.contains(frame) {
+                    usage.total_gas += total_gas;
+                }
+            }
+        });
+        let mut functions = functions.into_values().collect::<Vec<_>>();
+        functions.sort_by(|a, b| b.self_gas.cmp(&a.self_gas));
+        GasProfileSummary {
+            name: self.name.clone(),
+            total_gas: self.profiles.first().map_or(0, |p| p.end_value),
+            commands,
+            functions,
+        }
+    }
+}
+
+#[cfg(feature = "gas-profiler")]
+impl GasProfiler {
     #[cfg(feature = "gas-profiler")]
     pub fn init(config: &Option<VMProfilerConfig>, name: String, start_gas: u64) -> Self {
         let mut prof = GasProfiler {
@@ -198,6 +321,9 @@ impl GasProfiler {
         }
 
         let mut p = config.full_path.clone();
+        // A `.folded` output path selects the folded stack format instead of
+        // the speedscope one.
+        let folded = p.extension() == Some(OsStr::new("folded"));
         let mut filename = OsString::new();
         filename.push(p.file_name().unwrap_or_else(|| OsStr::new("gas_profile")));
         filename.push("_");
@@ -216,8 +342,15 @@ impl GasProfiler {
 
         let mut file = File::create(&p).expect("Unable to create file");
 
-        let json = serde_json::to_string_pretty(&self).expect("Unable to serialize profile");
-        file.write_all(json.as_bytes())
+        let content = if folded {
+            self.folded_stacks()
+                .into_iter()
+                .map(|(stack, gas)| format!("{stack} {gas}\n"))
+                .collect()
+        } else {
+            serde_json::to_string_pretty(&self).expect("Unable to serialize profile")
+        };
+        file.write_all(content.as_bytes())
             .expect("Unable to write to file");
         info!("Gas profile written to file: {}", p.display());
     }
@@ -383,3 +516,101 @@ macro_rules! gas_profiler_feature_disabled {
 macro_rules! gas_profiler_feature_disabled {
     ( $( $tt:tt )* ) => {};
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profiler(frames: &[(&str, &str)], events: &[(&str, u64, u64)], end_value: u64) -> GasProfiler {
+        GasProfiler {
+            exporter: String::new(),
+            name: "tx".to_string(),
+            active_profile_index: 0,
+            schema: String::new(),
+            shared: Shared {
+                frames: frames
+                    .iter()
+                    .map(|(name, file)| FrameName {
+                        name: name.to_string(),
+                        file: file.to_string(),
+                    })
+                    .collect(),
+                frame_table: BTreeMap::new(),
+            },
+            profiles: vec![Profile {
+                ty: "evented".to_string(),
+                name: "tx".to_string(),
+                unit: "none".to_string(),
+                start_value: 0,
+                end_value,
+                events: events
+                    .iter()
+                    .map(|(ty, frame, at)| Event {
+                        ty: ty.to_string(),
+                        frame: *frame,
+                        at: *at,
+                    })
+                    .collect(),
+            }],
+            start_gas: 1000,
+            config: None,
+            finished: false,
+        }
+    }
+
+    #[test]
+    fn summary_per_command_and_function() {
+        let profiler = profiler(
+            &[
+                ("root", "root"),
+                ("PTB command 0: SplitCoins", "PTB command 0: SplitCoins"),
+                ("PTB command 1: MoveCall 0x2::m::f", "PTB command 1: MoveCall 0x2::m::f"),
+                ("f", "0x2::m::f"),
+                ("g", "0x2::m::g"),
+            ],
+            &[
+                ("O", 0, 0),
+                ("O", 1, 0),
+                ("C", 1, 10),
+                ("O", 2, 10),
+                ("O", 3, 12),
+                ("O", 4, 20),
+                ("C", 4, 50),
+                ("O", 3, 50),
+                ("C", 3, 60),
+                ("C", 3, 70),
+                ("C", 2, 75),
+                // The root frame is left open.
+            ],
+            80,
+        );
+
+        let summary = profiler.summary();
+        assert_eq!(summary.total_gas, 80);
+        let commands = summary
+            .commands
+            .iter()
+            .map(|c| (c.name.as_str(), c.total_gas, c.self_gas))
+            .collect::<Vec<_>>();
+        assert_eq!(commands, vec![
+            ("PTB command 0: SplitCoins", 10, 10),
+            ("PTB command 1: MoveCall 0x2::m::f", 65, 7),
+        ]);
+        let functions = summary
+            .functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.calls, f.total_gas, f.self_gas))
+            .collect::<Vec<_>>();
+        // The recursive call of `f` is only counted once in its total gas.
+        assert_eq!(functions, vec![
+            ("0x2::m::g", 1, 30, 30),
+            ("0x2::m::f", 2, 58, 28),
+        ]);
+
+        let stacks = profiler.folded_stacks();
+        assert_eq!(stacks["root"], 5);
+        assert_eq!(stacks["root;PTB command 1: MoveCall 0x2::m::f;f;g"], 30);
+        assert_eq!(stacks["root;PTB command 1: MoveCall 0x2::m::f;f;f"], 10);
+        assert_eq!(stacks.values().sum::<u64>(), 80);
+    }
+}
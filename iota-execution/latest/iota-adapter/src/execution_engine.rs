@@ -27,7 +27,8 @@ mod checked {
             BALANCE_MODULE_NAME,
         },
         base_types::{
-            IotaAddress, ObjectID, ObjectRef, SequenceNumber, TransactionDigest, TxContext,
+            IotaAddress, ObjectID, ObjectRef, SequenceNumber, SharedObjectCancellation,
+            TransactionDigest, TxContext,
         },
         bridge::{
             BRIDGE_COMMITTEE_MINIMAL_VOTING_POWER, BRIDGE_CREATE_FUNCTION_NAME,
@@ -334,17 +335,26 @@ mod checked {
                     None,
                 ))
             } else if let Some((cancelled_objects, reason)) = cancelled_objects {
-                match reason {
-                    SequenceNumber::CONGESTED => Err(ExecutionError::new(
+                match reason.cancellation() {
+                    Some(SharedObjectCancellation::Congested) => Err(ExecutionError::new(
                         ExecutionErrorKind::ExecutionCancelledDueToSharedObjectCongestion {
                             congested_objects: CongestedObjects(cancelled_objects),
                         },
                         None,
                     )),
-                    SequenceNumber::RANDOMNESS_UNAVAILABLE => Err(ExecutionError::new(
+                    Some(SharedObjectCancellation::RandomnessUnavailable) => Err(ExecutionError::new(
                         ExecutionErrorKind::ExecutionCancelledDueToRandomnessUnavailable,
                         None,
                     )),
+                    Some(SharedObjectCancellation::CongestedWithSurcharge(surcharge)) => {
+                        Err(ExecutionError::new(
+                            ExecutionErrorKind::ExecutionCancelledDueToSharedObjectCongestionWithSurcharge {
+                                congested_objects: CongestedObjects(cancelled_objects),
+                                surcharge,
+                            },
+                            None,
+                        ))
+                    }
                     _ => panic!("invalid cancellation reason SequenceNumber: {reason}"),
                 }
             } else {
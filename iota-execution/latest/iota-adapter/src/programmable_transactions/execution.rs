@@ -100,7 +100,14 @@ mod checked {
         // execute commands
         let mut mode_results = Mode::empty_results();
         for (idx, command) in commands.into_iter().enumerate() {
-            if let Err(err) = execute_command::<Mode>(&mut context, &mut mode_results, command) {
+            #[cfg(feature = "gas-profiler")]
+            let frame_name = command_frame_name(idx, &command);
+            #[cfg(feature = "gas-profiler")]
+            profile_command_frame(&mut context, &frame_name, true);
+            let result = execute_command::<Mode>(&mut context, &mut mode_results, command);
+            #[cfg(feature = "gas-profiler")]
+            profile_command_frame(&mut context, &frame_name, false);
+            if let Err(err) = result {
                 let object_runtime: &ObjectRuntime = context.object_runtime();
                 // We still need to record the loaded child objects for replay
                 let loaded_runtime_objects = object_runtime.loaded_runtime_objects();
@@ -131,6 +138,61 @@ mod checked {
         Ok(mode_results)
     }
 
+    /// Name of the gas profiler frame of a command, e.g.
+    /// `PTB command 0: MoveCall 0x2::coin::split`.
+    #[cfg(feature = "gas-profiler")]
+    fn command_frame_name(idx: usize, command: &Command) -> String {
+        let kind = match command {
+            Command::MoveCall(call) => {
+                format!(
+                    "MoveCall 0x{}::{}::{}",
+                    AccountAddress::from(call.package).short_str_lossless(),
+                    call.module,
+                    call.function
+                )
+            }
+            Command::TransferObjects(..) => "TransferObjects".to_string(),
+            Command::SplitCoins(..) => "SplitCoins".to_string(),
+            Command::MergeCoins(..) => "MergeCoins".to_string(),
+            Command::MakeMoveVec(..) => "MakeMoveVec".to_string(),
+            Command::Publish(..) => "Publish".to_string(),
+            Command::Upgrade(..) => "Upgrade".to_string(),
+        };
+        format!(
+            "{}{idx}: {kind}",
+            move_vm_profiler::PTB_COMMAND_FRAME_PREFIX
+        )
+    }
+
+    /// Opens or closes the gas profiler frame of a command. The frame name is
+    /// passed as is, so that it is not shortened like Move function names.
+    #[cfg(feature = "gas-profiler")]
+    fn profile_command_frame(
+        context: &mut ExecutionContext<'_, '_, '_>,
+        frame_name: &str,
+        open: bool,
+    ) {
+        use move_vm_types::gas::GasMeter;
+
+        let gas_status = context.gas_charger.move_gas_status_mut();
+        let remaining_gas: u64 = gas_status.remaining_gas().into();
+        if let Some(profiler) = gas_status.get_profiler_mut() {
+            if open {
+                profiler.open_frame(
+                    frame_name.to_string(),
+                    frame_name.to_string(),
+                    remaining_gas,
+                );
+            } else {
+                profiler.close_frame(
+                    frame_name.to_string(),
+                    frame_name.to_string(),
+                    remaining_gas,
+                );
+            }
+        }
+    }
+
     /// Execute a single command
     #[instrument(level = "trace", skip_all)]
     fn execute_command<Mode: ExecutionMode>(
@@ -2,7 +2,7 @@
 // Modifications Copyright (c) 2024 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::VecDeque;
+use std::{collections::VecDeque, sync::LazyLock};
 
 use fastcrypto::{
     error::FastCryptoError,
@@ -24,9 +24,15 @@ use move_vm_types::{
     values::{self, Value, VectorRef},
 };
 use rand::{SeedableRng, rngs::StdRng};
+use secp256k1::{Message, Secp256k1, VerifyOnly, XOnlyPublicKey, schnorr};
 use smallvec::smallvec;
 
-use crate::NativesCostTable;
+use super::hash::cost_or_invariant_violation;
+use crate::{NativesCostTable, object_runtime::ObjectRuntime};
+
+/// Verification context shared by all calls, as creating one is expensive.
+static SECP256K1_VERIFIER: LazyLock<Secp256k1<VerifyOnly>> =
+    LazyLock::new(Secp256k1::verification_only);
 
 pub const FAIL_TO_RECOVER_PUBKEY: u64 = 0;
 pub const INVALID_SIGNATURE: u64 = 1;
@@ -34,6 +40,7 @@ pub const INVALID_PUBKEY: u64 = 2;
 pub const INVALID_PRIVKEY: u64 = 3;
 pub const INVALID_HASH_FUNCTION: u64 = 4;
 pub const INVALID_SEED: u64 = 5;
+pub const NOT_SUPPORTED_ERROR: u64 = 6;
 
 pub const KECCAK256: u8 = 0;
 pub const SHA256: u8 = 1;
@@ -319,6 +326,87 @@ pub fn secp256k1_verify(
     Ok(NativeResult::ok(cost, smallvec![Value::bool(result)]))
 }
 
+#[derive(Clone)]
+pub struct EcdsaK1Secp256k1SchnorrVerifyCostParams {
+    /// Base cost for invoking the `secp256k1_schnorr_verify` function
+    pub ecdsa_k1_secp256k1_schnorr_verify_cost_base: Option<InternalGas>,
+}
+/// ****************************************************************************
+/// ********************* native fun secp256k1_schnorr_verify
+/// Implementation of the Move native function
+/// `secp256k1_schnorr_verify(signature: &vector<u8>, public_key: &vector<u8>,
+/// msg: &vector<u8>): bool` Verifies a BIP-340 Schnorr signature over a 32-byte
+/// message. Only available when the `enable_secp256k1_schnorr` feature flag is
+/// set.
+///   gas cost: ecdsa_k1_secp256k1_schnorr_verify_cost_base | covers various
+/// fixed costs in the oper
+/// Note: `signature`, `public_key` and `msg` are fixed size, so their costs are
+/// included in the base cost.
+/// ****************************************************************************
+/// *******************
+pub fn secp256k1_schnorr_verify(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 3);
+
+    let cost = context.gas_used();
+    if !context
+        .extensions()
+        .get::<ObjectRuntime>()
+        .protocol_config
+        .enable_secp256k1_schnorr()
+    {
+        return Ok(NativeResult::err(cost, NOT_SUPPORTED_ERROR));
+    }
+
+    // Load the cost parameters from the protocol config
+    let ecdsa_k1_secp256k1_schnorr_verify_cost_params = context
+        .extensions()
+        .get::<NativesCostTable>()
+        .ecdsa_k1_secp256k1_schnorr_verify_cost_params
+        .clone();
+
+    // Charge the base cost for this oper
+    native_charge_gas_early_exit!(
+        context,
+        cost_or_invariant_violation(
+            ecdsa_k1_secp256k1_schnorr_verify_cost_params
+                .ecdsa_k1_secp256k1_schnorr_verify_cost_base,
+            "secp256k1_schnorr_verify",
+        )?
+    );
+
+    let msg = pop_arg!(args, VectorRef);
+    let public_key_bytes = pop_arg!(args, VectorRef);
+    let signature_bytes = pop_arg!(args, VectorRef);
+
+    let msg_ref = msg.as_bytes_ref();
+    let public_key_bytes_ref = public_key_bytes.as_bytes_ref();
+    let signature_bytes_ref = signature_bytes.as_bytes_ref();
+
+    let cost = context.gas_used();
+
+    let Ok(sig) = schnorr::Signature::from_slice(&signature_bytes_ref) else {
+        return Ok(NativeResult::ok(cost, smallvec![Value::bool(false)]));
+    };
+
+    let Ok(pk) = XOnlyPublicKey::from_slice(&public_key_bytes_ref) else {
+        return Ok(NativeResult::ok(cost, smallvec![Value::bool(false)]));
+    };
+
+    // Only 32-byte messages, such as Taproot signature hashes, are supported.
+    let Ok(msg) = Message::from_slice(&msg_ref) else {
+        return Ok(NativeResult::ok(cost, smallvec![Value::bool(false)]));
+    };
+
+    let result = SECP256K1_VERIFIER.verify_schnorr(&sig, &msg, &pk).is_ok();
+
+    Ok(NativeResult::ok(cost, smallvec![Value::bool(result)]))
+}
+
 /// ****************************************************************************
 /// ********************* native fun secp256k1_sign (TEST ONLY)
 /// Implementation of the Move native function `secp256k1_sign(private_key:
@@ -4,22 +4,48 @@
 
 use std::{collections::VecDeque, ops::Mul};
 
-use fastcrypto::hash::{Blake2b256, HashFunction, Keccak256};
+use fastcrypto::hash::{Blake2b256, HashFunction, HashFunctionWrapper, Keccak256, Sha512};
 use move_binary_format::errors::PartialVMResult;
-use move_core_types::gas_algebra::InternalGas;
+use move_core_types::{gas_algebra::InternalGas, vm_status::StatusCode};
 use move_vm_runtime::{native_charge_gas_early_exit, native_functions::NativeContext};
 use move_vm_types::{
     loaded_data::runtime_types::Type,
-    natives::function::NativeResult,
+    natives::function::{NativeResult, PartialVMError},
     pop_arg,
     values::{Value, VectorRef},
 };
 use smallvec::smallvec;
 
-use crate::NativesCostTable;
+use crate::{NativesCostTable, object_runtime::ObjectRuntime};
 
 const BLAKE_2B256_BLOCK_SIZE: u16 = 128;
 const KECCAK_256_BLOCK_SIZE: u16 = 136;
+const BLAKE_2B512_BLOCK_SIZE: u16 = 128;
+const SHA_512_BLOCK_SIZE: u16 = 128;
+const KECCAK_512_BLOCK_SIZE: u16 = 72;
+
+pub const NOT_SUPPORTED_ERROR: u64 = 0;
+
+type Blake2b512 = HashFunctionWrapper<blake2::Blake2b512, 64>;
+type Keccak512 = HashFunctionWrapper<sha3::Keccak512, 64>;
+
+fn is_hash_512_supported(context: &NativeContext) -> bool {
+    context
+        .extensions()
+        .get::<ObjectRuntime>()
+        .protocol_config
+        .enable_hash_512()
+}
+
+pub(crate) fn cost_or_invariant_violation(
+    cost: Option<InternalGas>,
+    name: &str,
+) -> PartialVMResult<InternalGas> {
+    cost.ok_or_else(|| {
+        PartialVMError::new(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR)
+            .with_message(format!("Gas cost for {name} not available"))
+    })
+}
 
 fn hash<H: HashFunction<DIGEST_SIZE>, const DIGEST_SIZE: usize>(
     context: &mut NativeContext,
@@ -145,3 +171,146 @@ pub fn blake2b256(
         BLAKE_2B256_BLOCK_SIZE,
     )
 }
+
+#[derive(Clone)]
+pub struct Hash512CostParams {
+    /// Base cost for invoking the hash function
+    pub cost_base: Option<InternalGas>,
+    /// Cost per byte of `data`
+    pub data_cost_per_byte: Option<InternalGas>,
+    /// Cost per block of `data`
+    pub data_cost_per_block: Option<InternalGas>,
+}
+
+/// Shared implementation of the 512-bit hash functions, which are only
+/// available when the `enable_hash_512` feature flag is set.
+fn hash_512<H: HashFunction<64>>(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    args: VecDeque<Value>,
+    cost_params: Hash512CostParams,
+    block_size: u16,
+    name: &str,
+) -> PartialVMResult<NativeResult> {
+    let cost = context.gas_used();
+    if !is_hash_512_supported(context) {
+        return Ok(NativeResult::err(cost, NOT_SUPPORTED_ERROR));
+    }
+
+    // Charge the base cost for this operation
+    native_charge_gas_early_exit!(
+        context,
+        cost_or_invariant_violation(cost_params.cost_base, name)?
+    );
+
+    hash::<H, 64>(
+        context,
+        ty_args,
+        args,
+        cost_or_invariant_violation(cost_params.data_cost_per_byte, name)?,
+        cost_or_invariant_violation(cost_params.data_cost_per_block, name)?,
+        block_size,
+    )
+}
+
+/// ****************************************************************************
+/// ********************* native fun blake2b512
+/// Implementation of the Move native function `hash::blake2b512(data:
+/// &vector<u8>): vector<u8>`   gas cost: hash_blake2b512_cost_base
+/// | base cost for function call and fixed opers
+///              + hash_blake2b512_data_cost_per_byte * msg.len()       | cost
+///                depends on length of message
+///              + hash_blake2b512_data_cost_per_block * num_blocks     | cost
+///                depends on number of blocks in message, where a block is 128
+///                bytes
+/// ****************************************************************************
+/// *******************
+pub fn blake2b512(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    // Load the cost parameters from the protocol config
+    let cost_params = context
+        .extensions()
+        .get::<NativesCostTable>()
+        .hash_blake2b512_cost_params
+        .clone();
+
+    hash_512::<Blake2b512>(
+        context,
+        ty_args,
+        args,
+        cost_params,
+        BLAKE_2B512_BLOCK_SIZE,
+        "blake2b512",
+    )
+}
+
+/// ****************************************************************************
+/// ********************* native fun sha512
+/// Implementation of the Move native function `hash::sha512(data:
+/// &vector<u8>): vector<u8>`   gas cost: hash_sha512_cost_base
+/// | base cost for function call and fixed opers
+///              + hash_sha512_data_cost_per_byte * msg.len()       | cost
+///                depends on length of message
+///              + hash_sha512_data_cost_per_block * num_blocks     | cost
+///                depends on number of blocks in message, where a block is 128
+///                bytes
+/// ****************************************************************************
+/// *******************
+pub fn sha512(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    // Load the cost parameters from the protocol config
+    let cost_params = context
+        .extensions()
+        .get::<NativesCostTable>()
+        .hash_sha512_cost_params
+        .clone();
+
+    hash_512::<Sha512>(
+        context,
+        ty_args,
+        args,
+        cost_params,
+        SHA_512_BLOCK_SIZE,
+        "sha512",
+    )
+}
+
+/// ****************************************************************************
+/// ********************* native fun keccak512
+/// Implementation of the Move native function `hash::keccak512(data:
+/// &vector<u8>): vector<u8>`   gas cost: hash_keccak512_cost_base
+/// | base cost for function call and fixed opers
+///              + hash_keccak512_data_cost_per_byte * msg.len()       | cost
+///                depends on length of message
+///              + hash_keccak512_data_cost_per_block * num_blocks     | cost
+///                depends on number of blocks in message, where a block is 72
+///                bytes
+/// ****************************************************************************
+/// *******************
+pub fn keccak512(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    // Load the cost parameters from the protocol config
+    let cost_params = context
+        .extensions()
+        .get::<NativesCostTable>()
+        .hash_keccak512_cost_params
+        .clone();
+
+    hash_512::<Keccak512>(
+        context,
+        ty_args,
+        args,
+        cost_params,
+        KECCAK_512_BLOCK_SIZE,
+        "keccak512",
+    )
+}
@@ -35,7 +35,7 @@ use self::{
         ecdsa_k1,
         ecdsa_k1::{
             EcdsaK1DecompressPubkeyCostParams, EcdsaK1EcrecoverCostParams,
-            EcdsaK1Secp256k1VerifyCostParams,
+            EcdsaK1Secp256k1SchnorrVerifyCostParams, EcdsaK1Secp256k1VerifyCostParams,
         },
         ecdsa_r1,
         ecdsa_r1::{EcdsaR1EcrecoverCostParams, EcdsaR1Secp256R1VerifyCostParams},
@@ -48,7 +48,7 @@ use self::{
             Groth16PrepareVerifyingKeyCostParams, Groth16VerifyGroth16ProofInternalCostParams,
         },
         hash,
-        hash::{HashBlake2b256CostParams, HashKeccak256CostParams},
+        hash::{Hash512CostParams, HashBlake2b256CostParams, HashKeccak256CostParams},
         hmac,
         hmac::HmacHmacSha3256CostParams,
         poseidon,
@@ -141,6 +141,7 @@ pub struct NativesCostTable {
     pub ecdsa_k1_ecrecover_cost_params: EcdsaK1EcrecoverCostParams,
     pub ecdsa_k1_decompress_pubkey_cost_params: EcdsaK1DecompressPubkeyCostParams,
     pub ecdsa_k1_secp256k1_verify_cost_params: EcdsaK1Secp256k1VerifyCostParams,
+    pub ecdsa_k1_secp256k1_schnorr_verify_cost_params: EcdsaK1Secp256k1SchnorrVerifyCostParams,
 
     // ecdsar1
     pub ecdsa_r1_ecrecover_cost_params: EcdsaR1EcrecoverCostParams,
@@ -160,6 +161,9 @@ pub struct NativesCostTable {
     // hash
     pub hash_blake2b256_cost_params: HashBlake2b256CostParams,
     pub hash_keccak256_cost_params: HashKeccak256CostParams,
+    pub hash_blake2b512_cost_params: Hash512CostParams,
+    pub hash_sha512_cost_params: Hash512CostParams,
+    pub hash_keccak512_cost_params: Hash512CostParams,
 
     // poseidon
     pub poseidon_bn254_cost_params: PoseidonBN254CostParams,
@@ -331,6 +335,39 @@ impl NativesCostTable {
                     .hash_keccak256_data_cost_per_block()
                     .into(),
             },
+            hash_blake2b512_cost_params: Hash512CostParams {
+                cost_base: protocol_config
+                    .hash_blake2b512_cost_base_as_option()
+                    .map(Into::into),
+                data_cost_per_byte: protocol_config
+                    .hash_blake2b512_data_cost_per_byte_as_option()
+                    .map(Into::into),
+                data_cost_per_block: protocol_config
+                    .hash_blake2b512_data_cost_per_block_as_option()
+                    .map(Into::into),
+            },
+            hash_sha512_cost_params: Hash512CostParams {
+                cost_base: protocol_config
+                    .hash_sha512_cost_base_as_option()
+                    .map(Into::into),
+                data_cost_per_byte: protocol_config
+                    .hash_sha512_data_cost_per_byte_as_option()
+                    .map(Into::into),
+                data_cost_per_block: protocol_config
+                    .hash_sha512_data_cost_per_block_as_option()
+                    .map(Into::into),
+            },
+            hash_keccak512_cost_params: Hash512CostParams {
+                cost_base: protocol_config
+                    .hash_keccak512_cost_base_as_option()
+                    .map(Into::into),
+                data_cost_per_byte: protocol_config
+                    .hash_keccak512_data_cost_per_byte_as_option()
+                    .map(Into::into),
+                data_cost_per_block: protocol_config
+                    .hash_keccak512_data_cost_per_block_as_option()
+                    .map(Into::into),
+            },
             transfer_transfer_internal_cost_params: TransferInternalCostParams {
                 transfer_transfer_internal_cost_base: protocol_config
                     .transfer_transfer_internal_cost_base()
@@ -437,6 +474,12 @@ impl NativesCostTable {
                     .ecdsa_k1_secp256k1_verify_sha256_msg_cost_per_block()
                     .into(),
             },
+            ecdsa_k1_secp256k1_schnorr_verify_cost_params:
+                EcdsaK1Secp256k1SchnorrVerifyCostParams {
+                    ecdsa_k1_secp256k1_schnorr_verify_cost_base: protocol_config
+                        .ecdsa_k1_secp256k1_schnorr_verify_cost_base_as_option()
+                        .map(Into::into),
+                },
             ecdsa_r1_ecrecover_cost_params: EcdsaR1EcrecoverCostParams {
                 ecdsa_r1_ecrecover_keccak256_cost_base: protocol_config
                     .ecdsa_r1_ecrecover_keccak256_cost_base()
@@ -827,6 +870,11 @@ pub fn all_natives(silent: bool, protocol_config: &ProtocolConfig) -> NativeFunc
             "secp256k1_verify",
             make_native!(ecdsa_k1::secp256k1_verify),
         ),
+        (
+            "ecdsa_k1",
+            "secp256k1_schnorr_verify",
+            make_native!(ecdsa_k1::secp256k1_schnorr_verify),
+        ),
         ("ecvrf", "ecvrf_verify", make_native!(ecvrf::ecvrf_verify)),
         (
             "ecdsa_r1",
@@ -862,6 +910,9 @@ pub fn all_natives(silent: bool, protocol_config: &ProtocolConfig) -> NativeFunc
         ),
         ("hmac", "hmac_sha3_256", make_native!(hmac::hmac_sha3_256)),
         ("hash", "keccak256", make_native!(hash::keccak256)),
+        ("hash", "blake2b512", make_native!(hash::blake2b512)),
+        ("hash", "sha512", make_native!(hash::sha512)),
+        ("hash", "keccak512", make_native!(hash::keccak512)),
         (
             "group_ops",
             "internal_validate",